@@ -8,7 +8,7 @@ use std::time::Duration;
 
 // Import the library components
 use cosmic_desktop_widget::{
-    widget::ClockWidget,
+    widget::{ClockWidget, Widget},
     update::UpdateScheduler,
     theme::Theme,
 };
@@ -44,6 +44,23 @@ fn bench_clock_time_string(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark fetching widget content, as the event loop does every frame
+fn bench_clock_content(c: &mut Criterion) {
+    let clock = ClockWidget::new("24h", true, true);
+
+    let mut group = c.benchmark_group("clock_content");
+
+    group.bench_function("clone", |b| {
+        b.iter(|| black_box(clock.content()))
+    });
+
+    group.bench_function("borrow", |b| {
+        b.iter(|| black_box(clock.content_ref()))
+    });
+
+    group.finish();
+}
+
 /// Benchmark update scheduler
 fn bench_update_scheduler(c: &mut Criterion) {
     let mut scheduler = UpdateScheduler::new(
@@ -104,6 +121,7 @@ criterion_group!(
     benches,
     bench_clock_update,
     bench_clock_time_string,
+    bench_clock_content,
     bench_update_scheduler,
     bench_time_until_next_update,
     bench_theme_operations,
@@ -0,0 +1,67 @@
+## Shared by widgets
+
+error = Fehler: { $message }
+now = Jetzt!
+widget-crashed = Nach einem internen Fehler angehalten
+error-check-logs = Details im Protokoll
+duration-days = { $count } T
+duration-hours = { $count } Std
+duration-minutes = { $count } Min
+duration-seconds = { $count } s
+
+## Weather
+
+weather-humidity = { $humidity } % Luftfeuchtigkeit
+weather-loading = Wetter: Wird geladen...
+weather-no-api-key = Wetter: Kein API-Schlüssel
+
+## System monitor
+
+sysmon-cpu = CPU: { $usage } %
+sysmon-temperature = Temp.: { $temperature }
+sysmon-memory = RAM: { $used }/{ $total } ({ $percent } %)
+sysmon-disk = Festplatte: { $used }/{ $total } ({ $percent } %)
+
+## Battery
+
+battery-error = Akku: { $message }
+battery-time-left = { $hours } Std { $minutes } Min
+battery-time-left-minutes = { $minutes } Min
+battery-none = Kein Akku
+
+## Other widgets
+
+calendar-no-events = Keine anstehenden Termine
+media-nothing-playing = Keine Medienwiedergabe
+stocks-no-data = Keine Kursdaten
+
+## Configuration GUI
+
+tab-general = Allgemein
+tab-general-description = Größe, Position und Anordnung
+tab-appearance = Darstellung
+tab-appearance-description = Themenauswahl und Transparenz
+tab-theme = Thema
+tab-theme-description = Eigene Farben und Stile
+tab-sounds = Klänge
+tab-sounds-description = Benachrichtigungs- und Alarmklänge
+tab-widgets = Widgets
+tab-widgets-description = Widgets aktivieren, deaktivieren und anordnen
+
+position-top-left = ↖ Oben links
+position-top-center = ↑ Oben
+position-top-right = ↗ Oben rechts
+position-center-left = ← Links
+position-center = ● Mitte
+position-center-right = → Rechts
+position-bottom-left = ↙ Unten links
+position-bottom-center = ↓ Unten
+position-bottom-right = ↘ Unten rechts
+
+color-background = Hintergrund
+color-text-primary = Haupttext
+color-text-secondary = Nebentext
+color-accent = Akzent
+color-border = Rahmen
+color-gradient-start = Startfarbe
+color-gradient-end = Endfarbe
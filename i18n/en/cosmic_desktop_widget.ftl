@@ -0,0 +1,69 @@
+# English strings, also used for anything missing from another language
+
+## Shared by widgets
+
+error = Error: { $message }
+now = Now!
+widget-crashed = Stopped after an internal error
+error-check-logs = Check the logs for details
+duration-days = { $count }d
+duration-hours = { $count }h
+duration-minutes = { $count }m
+duration-seconds = { $count }s
+
+## Weather
+
+weather-humidity = { $humidity }% humidity
+weather-loading = Weather: Loading...
+weather-no-api-key = Weather: No API key
+
+## System monitor
+
+sysmon-cpu = CPU: { $usage }%
+sysmon-temperature = Temp: { $temperature }
+sysmon-memory = RAM: { $used }/{ $total } ({ $percent }%)
+sysmon-disk = Disk: { $used }/{ $total } ({ $percent }%)
+
+## Battery
+
+battery-error = Battery: { $message }
+battery-time-left = { $hours }h { $minutes }m
+battery-time-left-minutes = { $minutes }m
+battery-none = No battery
+
+## Other widgets
+
+calendar-no-events = No upcoming events
+media-nothing-playing = No media playing
+stocks-no-data = No stock data
+
+## Configuration GUI
+
+tab-general = General
+tab-general-description = Panel size, position, and layout settings
+tab-appearance = Appearance
+tab-appearance-description = Theme selection and transparency options
+tab-theme = Theme
+tab-theme-description = Custom color and style configuration
+tab-sounds = Sounds
+tab-sounds-description = Notification and alarm sounds
+tab-widgets = Widgets
+tab-widgets-description = Enable, disable, and reorder widgets
+
+position-top-left = ↖ Top Left
+position-top-center = ↑ Top
+position-top-right = ↗ Top Right
+position-center-left = ← Left
+position-center = ● Center
+position-center-right = → Right
+position-bottom-left = ↙ Bottom Left
+position-bottom-center = ↓ Bottom
+position-bottom-right = ↘ Bottom Right
+
+color-background = Background
+color-text-primary = Primary Text
+color-text-secondary = Secondary Text
+color-accent = Accent
+color-border = Border
+color-gradient-start = Start Color
+color-gradient-end = End Color
@@ -0,0 +1,67 @@
+## Shared by widgets
+
+error = Feil: { $message }
+now = Nå!
+widget-crashed = Stoppet etter en intern feil
+error-check-logs = Se loggen for detaljer
+duration-days = { $count }d
+duration-hours = { $count }t
+duration-minutes = { $count }m
+duration-seconds = { $count }s
+
+## Weather
+
+weather-humidity = { $humidity } % luftfuktighet
+weather-loading = Vær: Laster...
+weather-no-api-key = Vær: Ingen API-nøkkel
+
+## System monitor
+
+sysmon-cpu = CPU: { $usage } %
+sysmon-temperature = Temp: { $temperature }
+sysmon-memory = RAM: { $used }/{ $total } ({ $percent } %)
+sysmon-disk = Disk: { $used }/{ $total } ({ $percent } %)
+
+## Battery
+
+battery-error = Batteri: { $message }
+battery-time-left = { $hours }t { $minutes }m
+battery-time-left-minutes = { $minutes }m
+battery-none = Ingen batteri
+
+## Other widgets
+
+calendar-no-events = Ingen kommende hendelser
+media-nothing-playing = Ingen medier spilles av
+stocks-no-data = Ingen aksjedata
+
+## Configuration GUI
+
+tab-general = Generelt
+tab-general-description = Størrelse, plassering og oppsett
+tab-appearance = Utseende
+tab-appearance-description = Temavalg og gjennomsiktighet
+tab-theme = Tema
+tab-theme-description = Egne farger og stiler
+tab-sounds = Lyder
+tab-sounds-description = Varslings- og alarmlyder
+tab-widgets = Widgeter
+tab-widgets-description = Slå på, slå av og sorter widgeter
+
+position-top-left = ↖ Øverst til venstre
+position-top-center = ↑ Øverst
+position-top-right = ↗ Øverst til høyre
+position-center-left = ← Venstre
+position-center = ● Midten
+position-center-right = → Høyre
+position-bottom-left = ↙ Nederst til venstre
+position-bottom-center = ↓ Nederst
+position-bottom-right = ↘ Nederst til høyre
+
+color-background = Bakgrunn
+color-text-primary = Hovedtekst
+color-text-secondary = Sekundærtekst
+color-accent = Aksent
+color-border = Kant
+color-gradient-start = Startfarge
+color-gradient-end = Sluttfarge
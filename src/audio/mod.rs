@@ -1,21 +1,132 @@
 //! Audio playback system for alarms and notifications
 //!
 //! This module provides audio playback capabilities for the widget system,
-//! supporting both built-in sounds and custom audio files.
+//! supporting built-in sounds, the freedesktop sound theme and custom audio
+//! files.
 
 #[cfg(feature = "audio")]
 mod player;
 
 #[cfg(feature = "audio")]
-pub use player::{AudioPlayer, SoundEffect};
+pub use player::AudioPlayer;
+
+#[cfg(feature = "audio")]
+mod queue;
 
 #[cfg(not(feature = "audio"))]
 mod stub;
 
 #[cfg(not(feature = "audio"))]
-pub use stub::{AudioPlayer, SoundEffect};
+pub use stub::AudioPlayer;
+
+pub mod sound_theme;
+
+pub use sound_theme::SoundTheme;
 
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Names of the built-in sounds
+pub const BUILTIN_SOUNDS: &[&str] = &["alarm", "chime", "notification", "beep"];
+
+/// Audio file extensions that can be decoded
+pub const SOUND_FILE_EXTENSIONS: &[&str] = &["wav", "ogg", "mp3", "flac"];
+
+/// How urgent a sound is
+///
+/// A sound waits while one of equal or higher priority plays, and lowers the
+/// volume of lower-priority sounds while it plays itself.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SoundPriority {
+    /// Ambient sounds such as the hourly chime
+    Low,
+    /// Notifications
+    #[default]
+    Normal,
+    /// Alarms and timers
+    High,
+}
+
+/// Sound effect specification
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SoundEffect {
+    /// Named sound: looked up in the sound theme, with bundled tones for
+    /// alarm, chime, notification and beep
+    Builtin(String),
+    /// Custom sound from file path
+    Custom(PathBuf),
+}
+
+impl Default for SoundEffect {
+    fn default() -> Self {
+        SoundEffect::Builtin("notification".to_string())
+    }
+}
+
+impl SoundEffect {
+    /// Parse a sound effect from configuration string
+    ///
+    /// Anything that looks like a path (contains a separator or ends in a
+    /// supported audio extension) is a custom file; `~/` is expanded.
+    pub fn from_config(value: &str) -> Self {
+        let value = value.trim();
+        if value.contains('/') || value.contains('\\') || has_sound_extension(Path::new(value)) {
+            let path = match value.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir()
+                    .map(|home| home.join(rest))
+                    .unwrap_or_else(|| PathBuf::from(value)),
+                None => PathBuf::from(value),
+            };
+            SoundEffect::Custom(path)
+        } else {
+            SoundEffect::Builtin(value.to_string())
+        }
+    }
+
+    /// Check that the sound exists and can be played
+    ///
+    /// Names must be a bundled sound or found in `theme`; files must exist
+    /// and have a supported extension. Decoding errors only show up at
+    /// playback.
+    pub fn validate(&self, theme: Option<&SoundTheme>) -> Result<()> {
+        match self {
+            SoundEffect::Builtin(name) => {
+                let themed = || theme.is_some_and(|t| t.lookup(name).is_some());
+                if !BUILTIN_SOUNDS.contains(&name.as_str()) && !themed() {
+                    bail!(
+                        "Unknown sound '{}' (expected one of {}, a sound theme name or a file path)",
+                        name,
+                        BUILTIN_SOUNDS.join(", ")
+                    );
+                }
+            }
+            SoundEffect::Custom(path) => {
+                if !has_sound_extension(path) {
+                    bail!(
+                        "Unsupported sound file '{}' (expected {})",
+                        path.display(),
+                        SOUND_FILE_EXTENSIONS.join(", ")
+                    );
+                }
+                if !path.is_file() {
+                    bail!("Sound file not found: {}", path.display());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn has_sound_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            SOUND_FILE_EXTENSIONS
+                .iter()
+                .any(|supported| ext.eq_ignore_ascii_case(supported))
+        })
+}
 
 /// Sound configuration for widgets
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,7 +135,8 @@ pub struct SoundConfig {
     #[serde(default = "default_sound_enabled")]
     pub enabled: bool,
 
-    /// Sound effect to play
+    /// Sound effect to play: a built-in or sound theme name, or a path to a
+    /// wav/ogg/mp3/flac file
     #[serde(default)]
     pub effect: String,
 
@@ -60,9 +172,25 @@ impl Default for SoundConfig {
     }
 }
 
+impl SoundConfig {
+    /// The effect this config plays
+    pub fn sound_effect(&self) -> SoundEffect {
+        SoundEffect::from_config(&self.effect)
+    }
+
+    /// Same settings with a different effect
+    pub fn with_effect(&self, effect: &str) -> Self {
+        Self {
+            effect: effect.to_string(),
+            ..self.clone()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::TempDir;
 
     #[test]
     fn test_sound_config_default() {
@@ -71,4 +199,67 @@ mod tests {
         assert_eq!(config.volume, 0.8);
         assert_eq!(config.repeat, 1);
     }
+
+    #[test]
+    fn test_sound_effect_from_config() {
+        let builtin = SoundEffect::from_config("alarm");
+        assert!(matches!(builtin, SoundEffect::Builtin(s) if s == "alarm"));
+
+        let custom = SoundEffect::from_config("/path/to/sound.ogg");
+        assert!(matches!(custom, SoundEffect::Custom(_)));
+
+        let custom_wav = SoundEffect::from_config("sound.wav");
+        assert!(matches!(custom_wav, SoundEffect::Custom(_)));
+
+        let custom_mp3 = SoundEffect::from_config("Ding.MP3");
+        assert!(matches!(custom_mp3, SoundEffect::Custom(_)));
+
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(
+                SoundEffect::from_config("~/sounds/bell.ogg"),
+                SoundEffect::Custom(home.join("sounds/bell.ogg"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_sound_effect_default() {
+        let effect = SoundEffect::default();
+        assert!(matches!(effect, SoundEffect::Builtin(s) if s == "notification"));
+    }
+
+    #[test]
+    fn test_sound_effect_validate() {
+        assert!(SoundEffect::from_config("chime").validate(None).is_ok());
+        assert!(SoundEffect::from_config("gong").validate(None).is_err());
+
+        let dir = TempDir::new().unwrap();
+        let wav = dir.path().join("bell.wav");
+        std::fs::write(&wav, b"RIFF").unwrap();
+        assert!(SoundEffect::Custom(wav.clone()).validate(None).is_ok());
+
+        // Missing files and unsupported formats are rejected
+        assert!(SoundEffect::Custom(dir.path().join("missing.ogg"))
+            .validate(None)
+            .is_err());
+        let midi = dir.path().join("tune.mid");
+        std::fs::write(&midi, b"MThd").unwrap();
+        assert!(SoundEffect::Custom(midi).validate(None).is_err());
+    }
+
+    #[test]
+    fn test_sound_effect_validate_with_theme() {
+        let dir = TempDir::new().unwrap();
+        let stereo = dir.path().join("freedesktop/stereo");
+        std::fs::create_dir_all(&stereo).unwrap();
+        std::fs::write(stereo.join("dialog-warning.oga"), b"").unwrap();
+        let theme = SoundTheme::with_base_dirs("freedesktop", vec![dir.path().to_path_buf()]);
+
+        let warning = SoundEffect::from_config("dialog-warning");
+        assert!(warning.validate(None).is_err());
+        assert!(warning.validate(Some(&theme)).is_ok());
+        assert!(SoundEffect::from_config("gong")
+            .validate(Some(&theme))
+            .is_err());
+    }
 }
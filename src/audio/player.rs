@@ -1,12 +1,22 @@
 //! Audio player implementation using rodio
-
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
-use std::io::Cursor;
-use std::path::PathBuf;
+//!
+//! The output stream follows the default audio device: if it changes (dock,
+//! Bluetooth headset) or the stream dies, it is reopened on the next play.
+
+use rodio::cpal::traits::HostTrait;
+use rodio::source::Zero;
+use rodio::{cpal, Decoder, DeviceTrait, OutputStream, OutputStreamHandle, Sink, Source};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 use thiserror::Error;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, info, warn};
+
+use super::queue::{duck_factor, PlaybackQueue, QueuedSound};
+use super::sound_theme::{theme_sound_name, SoundTheme};
+use super::{SoundConfig, SoundEffect, SoundPriority, BUILTIN_SOUNDS};
 
 /// Errors that can occur during audio playback
 #[derive(Debug, Error)]
@@ -27,52 +37,94 @@ pub enum AudioError {
     NotAvailable,
 }
 
-/// Sound effect specification
-#[derive(Debug, Clone)]
-pub enum SoundEffect {
-    /// Built-in sound by name (alarm, chime, notification)
-    Builtin(String),
-    /// Custom sound from file path
-    Custom(PathBuf),
+/// Silence between repetitions of a sound
+const REPEAT_GAP: Duration = Duration::from_millis(500);
+
+/// Sounds longer than this (~30s of 44.1kHz stereo) are decoded on every
+/// play instead of being kept in memory
+const MAX_CACHED_SAMPLES: usize = 44_100 * 2 * 30;
+
+/// Decoded samples, shared between plays
+#[derive(Clone)]
+struct DecodedSound {
+    samples: Arc<[f32]>,
+    channels: u16,
+    sample_rate: u32,
+    /// File modification time when decoded, to notice replaced files
+    modified: Option<SystemTime>,
 }
 
-impl Default for SoundEffect {
-    fn default() -> Self {
-        SoundEffect::Builtin("notification".to_string())
-    }
+/// An open output stream and the device it plays to
+struct Output {
+    _stream: OutputStream,
+    handle: OutputStreamHandle,
+    device: Option<String>,
 }
 
-impl SoundEffect {
-    /// Parse a sound effect from configuration string
-    pub fn from_config(value: &str) -> Self {
-        // Check if it's a path
-        if value.contains('/') || value.contains('\\') || value.ends_with(".ogg") || value.ends_with(".wav") || value.ends_with(".mp3") {
-            SoundEffect::Custom(PathBuf::from(value))
-        } else {
-            SoundEffect::Builtin(value.to_string())
-        }
+impl Output {
+    /// Open the current default output device
+    fn open() -> Result<Self, AudioError> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or(AudioError::NotAvailable)?;
+        let name = device.name().ok();
+
+        let (stream, handle) = OutputStream::try_from_device(&device)
+            .or_else(|_| OutputStream::try_default())
+            .map_err(|e| AudioError::StreamError(e.to_string()))?;
+
+        info!(
+            device = name.as_deref().unwrap_or("unknown"),
+            "Audio output opened"
+        );
+        Ok(Self {
+            _stream: stream,
+            handle,
+            device: name,
+        })
     }
 }
 
+/// A sound that has started playing
+struct Playing {
+    sink: Sink,
+    priority: SoundPriority,
+    /// The sound's own volume, before master volume and ducking
+    volume: f32,
+}
+
 /// Audio player for playing sounds
+///
+/// Sounds go through a priority queue: call [`AudioPlayer::update`]
+/// regularly so queued sounds start once the ones ahead of them finish.
 pub struct AudioPlayer {
-    _stream: OutputStream,
-    stream_handle: OutputStreamHandle,
+    /// Output stream, `None` after the device went away until it's reopened
+    output: RefCell<Option<Output>>,
     volume: f32,
+    /// Theme named sounds are looked up in before the bundled tones
+    sound_theme: Option<SoundTheme>,
+    /// Decoded sounds, so repeated alarms don't re-read and re-decode files
+    cache: RefCell<HashMap<SoundEffect, DecodedSound>>,
+    /// Sounds waiting for higher-priority ones to finish
+    queue: RefCell<PlaybackQueue>,
+    /// Sounds currently playing
+    playing: RefCell<Vec<Playing>>,
 }
 
 impl AudioPlayer {
     /// Create a new audio player
     pub fn new() -> Result<Self, AudioError> {
-        let (stream, stream_handle) = OutputStream::try_default()
-            .map_err(|e| AudioError::StreamError(e.to_string()))?;
+        let output = Output::open()?;
 
         info!("Audio player initialized");
 
         Ok(Self {
-            _stream: stream,
-            stream_handle,
+            output: RefCell::new(Some(output)),
             volume: 0.8,
+            sound_theme: None,
+            cache: RefCell::new(HashMap::new()),
+            queue: RefCell::new(PlaybackQueue::default()),
+            playing: RefCell::new(Vec::new()),
         })
     }
 
@@ -81,115 +133,320 @@ impl AudioPlayer {
         self.volume = volume.clamp(0.0, 1.0);
     }
 
+    /// Set the freedesktop sound theme used for named sounds
+    ///
+    /// With no theme only the bundled tones are available.
+    pub fn set_sound_theme(&mut self, theme: Option<SoundTheme>) {
+        if self.sound_theme != theme {
+            self.sound_theme = theme;
+            self.clear_cache();
+        }
+    }
+
     /// Play a sound effect
     pub fn play(&self, sound: &SoundEffect) -> Result<(), AudioError> {
-        match sound {
-            SoundEffect::Builtin(name) => self.play_builtin(name),
-            SoundEffect::Custom(path) => self.play_file(path),
-        }
+        self.enqueue(sound, 1, 1.0, SoundPriority::Normal)
     }
 
     /// Play a sound effect multiple times
+    ///
+    /// Repetitions are queued with a short gap, so this returns immediately.
     pub fn play_repeated(&self, sound: &SoundEffect, count: u32) -> Result<(), AudioError> {
-        for i in 0..count {
-            self.play(sound)?;
-            if i < count - 1 {
-                // Small delay between repetitions
-                std::thread::sleep(Duration::from_millis(500));
-            }
+        self.enqueue(sound, count, 1.0, SoundPriority::Normal)
+    }
+
+    /// Play a configured sound with its own volume and repeat count
+    ///
+    /// Does nothing if the sound is disabled.
+    pub fn play_config(
+        &self,
+        config: &SoundConfig,
+        priority: SoundPriority,
+    ) -> Result<(), AudioError> {
+        if !config.enabled {
+            return Ok(());
         }
-        Ok(())
+        self.enqueue(
+            &config.sound_effect(),
+            config.repeat.max(1),
+            config.volume,
+            priority,
+        )
     }
 
-    /// Play a built-in sound by name
-    fn play_builtin(&self, name: &str) -> Result<(), AudioError> {
-        debug!(sound = %name, "Playing builtin sound");
-
-        // Generate simple tones for built-in sounds
-        // In a real implementation, you'd embed actual sound files
-        let (frequency, duration_ms) = match name {
-            "alarm" => (880.0, 500),       // A5, 500ms
-            "chime" => (523.25, 200),      // C5, 200ms
-            "notification" => (659.25, 150), // E5, 150ms
-            "beep" => (440.0, 100),        // A4, 100ms
-            _ => {
-                warn!(sound = %name, "Unknown builtin sound, using default beep");
-                (440.0, 100)
+    /// Start queued sounds whose turn has come and adjust ducking
+    pub fn update(&self) {
+        let mut playing = self.playing.borrow_mut();
+        playing.retain(|p| !p.sink.empty());
+
+        let now = Instant::now();
+        loop {
+            let priorities: Vec<SoundPriority> = playing.iter().map(|p| p.priority).collect();
+            let Some(next) = self.queue.borrow_mut().pop_ready(&priorities, now) else {
+                break;
+            };
+            match self.start(&next) {
+                Ok(sink) => playing.push(Playing {
+                    sink,
+                    priority: next.priority,
+                    volume: next.volume,
+                }),
+                Err(e) => warn!(error = %e, sound = ?next.sound, "Failed to play sound"),
             }
-        };
+        }
 
-        // Generate a simple sine wave tone
-        let sample_rate = 44100u32;
-        let duration = Duration::from_millis(duration_ms);
-        let samples: Vec<f32> = (0..((sample_rate as u64 * duration.as_millis() as u64) / 1000) as usize)
-            .map(|i| {
-                let t = i as f32 / sample_rate as f32;
-                // Simple sine wave with envelope
-                let envelope = if t < 0.01 {
-                    t / 0.01
-                } else if t > duration.as_secs_f32() - 0.05 {
-                    (duration.as_secs_f32() - t) / 0.05
-                } else {
-                    1.0
-                };
-                (t * frequency * 2.0 * std::f32::consts::PI).sin() * envelope * self.volume
-            })
-            .collect();
-
-        let source = SamplesSource::new(samples, sample_rate);
-
-        let sink = Sink::try_new(&self.stream_handle)
-            .map_err(|e| AudioError::StreamError(e.to_string()))?;
+        // Duck sounds while something more important plays
+        let priorities: Vec<SoundPriority> = playing.iter().map(|p| p.priority).collect();
+        for p in playing.iter() {
+            p.sink
+                .set_volume(self.volume * p.volume * duck_factor(p.priority, &priorities));
+        }
+    }
+
+    /// Whether anything is playing or waiting to play
+    pub fn is_busy(&self) -> bool {
+        !self.queue.borrow().is_empty() || self.playing.borrow().iter().any(|p| !p.sink.empty())
+    }
+
+    /// Stop all sounds and drop queued ones
+    pub fn stop(&self) {
+        self.queue.borrow_mut().clear();
+        for p in self.playing.borrow_mut().drain(..) {
+            p.sink.stop();
+        }
+    }
 
-        sink.append(source);
-        sink.detach();
+    /// Decode a sound ahead of time so the first play starts instantly
+    pub fn preload(&self, sound: &SoundEffect) -> Result<(), AudioError> {
+        self.decoded(sound).map(|_| ())
+    }
 
+    /// Forget all decoded sounds
+    pub fn clear_cache(&self) {
+        self.cache.borrow_mut().clear();
+    }
+
+    /// Preview a sound effect (play once at current volume)
+    pub fn preview(&self, sound: &SoundEffect) -> Result<(), AudioError> {
+        self.play(sound)
+    }
+
+    /// Queue a sound, decoding it first so bad files are reported to the caller
+    fn enqueue(
+        &self,
+        sound: &SoundEffect,
+        count: u32,
+        volume: f32,
+        priority: SoundPriority,
+    ) -> Result<(), AudioError> {
+        self.decoded(sound)?;
+
+        let queued = QueuedSound {
+            sound: sound.clone(),
+            count,
+            volume: volume.clamp(0.0, 1.0),
+            priority,
+        };
+        if !self.queue.borrow_mut().push(queued, Instant::now()) {
+            debug!(sound = ?sound, "Sound coalesced with a recent one");
+        }
+        self.update();
         Ok(())
     }
 
-    /// Play a sound from a file
-    fn play_file(&self, path: &PathBuf) -> Result<(), AudioError> {
-        debug!(path = %path.display(), "Playing sound file");
+    /// Start playing a queued sound
+    fn start(&self, queued: &QueuedSound) -> Result<Sink, AudioError> {
+        let decoded = self.decoded(&queued.sound)?;
 
-        if !path.exists() {
-            return Err(AudioError::FileNotFound(path.clone()));
+        let sink = self.sink()?;
+        sink.set_volume(self.volume * queued.volume);
+
+        for i in 0..queued.count {
+            if i > 0 {
+                sink.append(
+                    Zero::<f32>::new(decoded.channels, decoded.sample_rate)
+                        .take_duration(REPEAT_GAP),
+                );
+            }
+            sink.append(SamplesSource::new(&decoded));
         }
 
-        let file = std::fs::File::open(path)
-            .map_err(|e| AudioError::DecodeError(e.to_string()))?;
+        Ok(sink)
+    }
 
-        let source = Decoder::new(std::io::BufReader::new(file))
-            .map_err(|e| AudioError::DecodeError(e.to_string()))?;
+    /// A sink on the default device, reopening the stream if the device
+    /// changed or the old stream no longer accepts sounds
+    fn sink(&self) -> Result<Sink, AudioError> {
+        let mut output = self.output.borrow_mut();
+
+        let current = default_device_name();
+        if let Some(open) = output.as_ref() {
+            if device_changed(&open.device, &current) {
+                info!(
+                    from = open.device.as_deref().unwrap_or("unknown"),
+                    to = current.as_deref().unwrap_or("unknown"),
+                    "Default audio device changed"
+                );
+                // Close the old stream first; some backends allow only one
+                *output = None;
+            }
+        }
 
-        let sink = Sink::try_new(&self.stream_handle)
-            .map_err(|e| AudioError::StreamError(e.to_string()))?;
+        if let Some(open) = output.as_ref() {
+            match Sink::try_new(&open.handle) {
+                Ok(sink) => return Ok(sink),
+                Err(e) => {
+                    warn!(error = %e, "Audio output lost, reopening");
+                    *output = None;
+                }
+            }
+        }
 
-        sink.set_volume(self.volume);
-        sink.append(source);
-        sink.detach();
+        let reopened = Output::open()?;
+        let sink =
+            Sink::try_new(&reopened.handle).map_err(|e| AudioError::StreamError(e.to_string()))?;
+        *output = Some(reopened);
+        Ok(sink)
+    }
 
-        Ok(())
+    /// Decoded samples for a sound, from the cache when still valid
+    fn decoded(&self, sound: &SoundEffect) -> Result<DecodedSound, AudioError> {
+        let file = match sound {
+            SoundEffect::Builtin(name) => {
+                let themed = self
+                    .sound_theme
+                    .as_ref()
+                    .and_then(|theme| theme.lookup(theme_sound_name(name)));
+                if themed.is_none() && !BUILTIN_SOUNDS.contains(&name.as_str()) {
+                    return Err(AudioError::UnknownBuiltin(name.clone()));
+                }
+                themed
+            }
+            SoundEffect::Custom(path) => {
+                if !path.exists() {
+                    return Err(AudioError::FileNotFound(path.clone()));
+                }
+                Some(path.clone())
+            }
+        };
+        let modified = file
+            .as_deref()
+            .and_then(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok());
+
+        if let Some(cached) = self.cache.borrow().get(sound) {
+            if cached.modified == modified {
+                return Ok(cached.clone());
+            }
+        }
+
+        let decoded = match (&file, sound) {
+            (Some(path), _) => decode_file(path, modified)?,
+            (None, SoundEffect::Builtin(name)) => generate_tone(name),
+            (None, SoundEffect::Custom(path)) => {
+                return Err(AudioError::FileNotFound(path.clone()))
+            }
+        };
+        if decoded.samples.len() <= MAX_CACHED_SAMPLES {
+            self.cache
+                .borrow_mut()
+                .insert(sound.clone(), decoded.clone());
+        }
+        Ok(decoded)
     }
+}
 
-    /// Preview a sound effect (play once at current volume)
-    pub fn preview(&self, sound: &SoundEffect) -> Result<(), AudioError> {
-        self.play(sound)
+fn default_device_name() -> Option<String> {
+    cpal::default_host()
+        .default_output_device()
+        .and_then(|device| device.name().ok())
+}
+
+/// Whether the default device differs from the one the stream was opened on
+///
+/// An unknown current device isn't a change: the name lookup can fail
+/// transiently, and the stream is reopened anyway if it stops working.
+fn device_changed(open: &Option<String>, current: &Option<String>) -> bool {
+    current.is_some() && current != open
+}
+
+/// Generate the tone for a built-in sound
+fn generate_tone(name: &str) -> DecodedSound {
+    debug!(sound = %name, "Generating builtin sound");
+
+    // Generate simple tones for built-in sounds
+    let (frequency, duration_ms) = match name {
+        "alarm" => (880.0, 500),         // A5, 500ms
+        "chime" => (523.25, 200),        // C5, 200ms
+        "notification" => (659.25, 150), // E5, 150ms
+        "beep" => (440.0, 100),          // A4, 100ms
+        _ => {
+            warn!(sound = %name, "Unknown builtin sound, using default beep");
+            (440.0, 100)
+        }
+    };
+
+    // Generate a simple sine wave tone
+    let sample_rate = 44100u32;
+    let duration = Duration::from_millis(duration_ms);
+    let count = (sample_rate as u64 * duration_ms / 1000) as usize;
+    let samples: Arc<[f32]> = (0..count)
+        .map(|i| {
+            let t = i as f32 / sample_rate as f32;
+            // Simple sine wave with envelope
+            let envelope = if t < 0.01 {
+                t / 0.01
+            } else if t > duration.as_secs_f32() - 0.05 {
+                (duration.as_secs_f32() - t) / 0.05
+            } else {
+                1.0
+            };
+            (t * frequency * 2.0 * std::f32::consts::PI).sin() * envelope
+        })
+        .collect();
+
+    DecodedSound {
+        samples,
+        channels: 1,
+        sample_rate,
+        modified: None,
     }
 }
 
-/// Simple samples-based audio source for generated tones
+/// Decode a wav/ogg/mp3/flac file into samples
+fn decode_file(path: &Path, modified: Option<SystemTime>) -> Result<DecodedSound, AudioError> {
+    debug!(path = %path.display(), "Decoding sound file");
+
+    let file = std::fs::File::open(path).map_err(|e| AudioError::DecodeError(e.to_string()))?;
+    let decoder = Decoder::new(std::io::BufReader::new(file))
+        .map_err(|e| AudioError::DecodeError(format!("{}: {}", path.display(), e)))?;
+
+    let channels = decoder.channels();
+    let sample_rate = decoder.sample_rate();
+    let samples: Arc<[f32]> = decoder.convert_samples::<f32>().collect();
+
+    Ok(DecodedSound {
+        samples,
+        channels,
+        sample_rate,
+        modified,
+    })
+}
+
+/// Simple samples-based audio source for decoded sounds
 struct SamplesSource {
-    samples: Vec<f32>,
+    samples: Arc<[f32]>,
     position: usize,
+    channels: u16,
     sample_rate: u32,
 }
 
 impl SamplesSource {
-    fn new(samples: Vec<f32>, sample_rate: u32) -> Self {
+    fn new(sound: &DecodedSound) -> Self {
         Self {
-            samples,
+            samples: Arc::clone(&sound.samples),
             position: 0,
-            sample_rate,
+            channels: sound.channels,
+            sample_rate: sound.sample_rate,
         }
     }
 }
@@ -198,23 +455,19 @@ impl Iterator for SamplesSource {
     type Item = f32;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.position < self.samples.len() {
-            let sample = self.samples[self.position];
-            self.position += 1;
-            Some(sample)
-        } else {
-            None
-        }
+        let sample = self.samples.get(self.position).copied();
+        self.position += 1;
+        sample
     }
 }
 
 impl Source for SamplesSource {
     fn current_frame_len(&self) -> Option<usize> {
-        Some(self.samples.len() - self.position)
+        Some(self.samples.len().saturating_sub(self.position))
     }
 
     fn channels(&self) -> u16 {
-        1 // Mono
+        self.channels
     }
 
     fn sample_rate(&self) -> u32 {
@@ -222,10 +475,8 @@ impl Source for SamplesSource {
     }
 
     fn total_duration(&self) -> Option<Duration> {
-        let samples_remaining = self.samples.len() - self.position;
-        Some(Duration::from_secs_f32(
-            samples_remaining as f32 / self.sample_rate as f32,
-        ))
+        let frames = self.samples.len() as f32 / self.channels.max(1) as f32;
+        Some(Duration::from_secs_f32(frames / self.sample_rate as f32))
     }
 }
 
@@ -234,20 +485,44 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_sound_effect_from_config() {
-        let builtin = SoundEffect::from_config("alarm");
-        assert!(matches!(builtin, SoundEffect::Builtin(s) if s == "alarm"));
+    fn test_generated_tone() {
+        let alarm = generate_tone("alarm");
+        assert_eq!(alarm.channels, 1);
+        assert_eq!(alarm.samples.len(), 44100 / 2);
+        assert!(alarm.samples.iter().all(|s| s.abs() <= 1.0));
+
+        // Unknown names fall back to the short beep
+        assert_eq!(generate_tone("gong").samples.len(), 4410);
+    }
 
-        let custom = SoundEffect::from_config("/path/to/sound.ogg");
-        assert!(matches!(custom, SoundEffect::Custom(_)));
+    #[test]
+    fn test_device_changed() {
+        let speakers = Some("Built-in Audio".to_string());
+        let headset = Some("Bluetooth Headset".to_string());
+
+        assert!(!device_changed(&speakers, &speakers));
+        assert!(device_changed(&speakers, &headset));
+        assert!(device_changed(&None, &headset));
+        assert!(!device_changed(&speakers, &None));
+    }
 
-        let custom_wav = SoundEffect::from_config("sound.wav");
-        assert!(matches!(custom_wav, SoundEffect::Custom(_)));
+    #[test]
+    fn test_samples_source_shares_samples() {
+        let tone = generate_tone("beep");
+        let first: Vec<f32> = SamplesSource::new(&tone).collect();
+        let second: Vec<f32> = SamplesSource::new(&tone).collect();
+        assert_eq!(first.len(), tone.samples.len());
+        assert_eq!(first, second);
     }
 
     #[test]
-    fn test_sound_effect_default() {
-        let effect = SoundEffect::default();
-        assert!(matches!(effect, SoundEffect::Builtin(s) if s == "notification"));
+    fn test_decode_file_rejects_garbage() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("broken.ogg");
+        std::fs::write(&path, b"not audio").unwrap();
+        assert!(matches!(
+            decode_file(&path, None),
+            Err(AudioError::DecodeError(_))
+        ));
     }
 }
@@ -0,0 +1,152 @@
+//! Playback ordering for the audio player
+//!
+//! Sounds wait for anything of equal or higher priority to finish, so two
+//! notifications never talk over each other, while a higher-priority sound
+//! starts straight away and ducks whatever is already playing. The same
+//! sound requested again within [`COALESCE_WINDOW`] is dropped.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::{SoundEffect, SoundPriority};
+
+/// Repeats of a sound within this window collapse into one
+pub const COALESCE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Volume factor for sounds playing under a higher-priority one
+pub const DUCK_VOLUME: f32 = 0.25;
+
+/// A sound waiting to be played
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedSound {
+    pub sound: SoundEffect,
+    pub count: u32,
+    pub volume: f32,
+    pub priority: SoundPriority,
+}
+
+/// Pending sounds, highest priority first
+#[derive(Debug, Default)]
+pub struct PlaybackQueue {
+    pending: VecDeque<QueuedSound>,
+    /// When each sound last started, for coalescing
+    last_started: HashMap<SoundEffect, Instant>,
+}
+
+impl PlaybackQueue {
+    /// Queue a sound, returning false if it was coalesced with an earlier one
+    pub fn push(&mut self, sound: QueuedSound, now: Instant) -> bool {
+        let recent = self
+            .last_started
+            .get(&sound.sound)
+            .is_some_and(|started| now.duration_since(*started) < COALESCE_WINDOW);
+        if recent || self.pending.iter().any(|p| p.sound == sound.sound) {
+            return false;
+        }
+
+        // FIFO within a priority
+        let at = self
+            .pending
+            .iter()
+            .position(|p| p.priority < sound.priority)
+            .unwrap_or(self.pending.len());
+        self.pending.insert(at, sound);
+        true
+    }
+
+    /// The next sound that may start while sounds of `playing` priorities
+    /// are active
+    pub fn pop_ready(&mut self, playing: &[SoundPriority], now: Instant) -> Option<QueuedSound> {
+        let next = self.pending.front()?;
+        if playing.iter().any(|p| *p >= next.priority) {
+            return None;
+        }
+
+        let sound = self.pending.pop_front()?;
+        self.last_started
+            .retain(|_, started| now.duration_since(*started) < COALESCE_WINDOW);
+        self.last_started.insert(sound.sound.clone(), now);
+        Some(sound)
+    }
+
+    /// Drop everything that hasn't started yet
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Whether no sounds are waiting
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Volume factor for a sound of `priority` while `playing` are active
+pub fn duck_factor(priority: SoundPriority, playing: &[SoundPriority]) -> f32 {
+    if playing.iter().any(|p| *p > priority) {
+        DUCK_VOLUME
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(name: &str, priority: SoundPriority) -> QueuedSound {
+        QueuedSound {
+            sound: SoundEffect::Builtin(name.to_string()),
+            count: 1,
+            volume: 1.0,
+            priority,
+        }
+    }
+
+    #[test]
+    fn test_priority_order() {
+        let now = Instant::now();
+        let mut queue = PlaybackQueue::default();
+        assert!(queue.push(queued("chime", SoundPriority::Low), now));
+        assert!(queue.push(queued("notification", SoundPriority::Normal), now));
+        assert!(queue.push(queued("alarm", SoundPriority::High), now));
+
+        let next = queue.pop_ready(&[], now).unwrap();
+        assert_eq!(next.priority, SoundPriority::High);
+
+        // The rest wait for the alarm
+        assert!(queue.pop_ready(&[SoundPriority::High], now).is_none());
+        assert_eq!(
+            queue.pop_ready(&[], now).unwrap().priority,
+            SoundPriority::Normal
+        );
+        assert!(!queue.is_empty());
+    }
+
+    #[test]
+    fn test_higher_priority_interrupts() {
+        let now = Instant::now();
+        let mut queue = PlaybackQueue::default();
+        queue.push(queued("alarm", SoundPriority::High), now);
+
+        // Starts over a notification, which gets ducked
+        assert!(queue.pop_ready(&[SoundPriority::Normal], now).is_some());
+        let playing = [SoundPriority::Normal, SoundPriority::High];
+        assert_eq!(duck_factor(SoundPriority::Normal, &playing), DUCK_VOLUME);
+        assert_eq!(duck_factor(SoundPriority::High, &playing), 1.0);
+    }
+
+    #[test]
+    fn test_repeats_coalesce() {
+        let now = Instant::now();
+        let mut queue = PlaybackQueue::default();
+        assert!(queue.push(queued("notification", SoundPriority::Normal), now));
+        assert!(!queue.push(queued("notification", SoundPriority::Normal), now));
+
+        queue.pop_ready(&[], now).unwrap();
+        let soon = now + Duration::from_millis(300);
+        assert!(!queue.push(queued("notification", SoundPriority::Normal), soon));
+
+        let later = now + COALESCE_WINDOW;
+        assert!(queue.push(queued("notification", SoundPriority::Normal), later));
+    }
+}
@@ -0,0 +1,296 @@
+//! Freedesktop sound theme lookup
+//!
+//! Resolves sound names like `alarm-clock-elapsed` to files following the
+//! XDG Sound Theme specification: themes live in `sounds/<theme>/` under each
+//! XDG data directory, list their output profile subdirectories (usually
+//! `stereo`) and parent themes in `index.theme`, and always fall back to the
+//! `freedesktop` theme.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Theme every sound theme implicitly inherits from
+pub const FALLBACK_THEME: &str = "freedesktop";
+
+/// Extensions tried for each sound, in order; `.disabled` turns a sound off
+const THEME_SOUND_EXTENSIONS: &[&str] = &["disabled", "oga", "ogg", "wav"];
+
+/// Theme sound used for each bundled effect name
+pub fn theme_sound_name(name: &str) -> &str {
+    match name {
+        "alarm" => "alarm-clock-elapsed",
+        "chime" => "complete",
+        "notification" => "message-new-instant",
+        "beep" => "bell",
+        other => other,
+    }
+}
+
+/// A named sound theme and the directories it is searched in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoundTheme {
+    name: String,
+    base_dirs: Vec<PathBuf>,
+}
+
+/// One theme directory and its output profile subdirectories
+struct ThemeDir {
+    path: PathBuf,
+    subdirs: Vec<String>,
+}
+
+impl SoundTheme {
+    /// Look up `name` in the standard XDG sound directories
+    pub fn new(name: &str) -> Self {
+        Self::with_base_dirs(name, base_dirs())
+    }
+
+    /// Look up `name` in the given `sounds` directories instead
+    pub fn with_base_dirs(name: &str, base_dirs: Vec<PathBuf>) -> Self {
+        Self {
+            name: name.to_string(),
+            base_dirs,
+        }
+    }
+
+    /// Theme name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the theme is installed in any of the base directories
+    pub fn exists(&self) -> bool {
+        self.base_dirs
+            .iter()
+            .any(|dir| dir.join(&self.name).is_dir())
+    }
+
+    /// Find the file for a sound, or `None` if no theme provides it
+    ///
+    /// Like the spec's lookup, the full name is searched through the whole
+    /// inheritance chain before falling back to shorter names
+    /// (`alarm-clock-elapsed` → `alarm-clock` → `alarm`). A `.disabled` file
+    /// means the theme deliberately silences the sound.
+    pub fn lookup(&self, sound: &str) -> Option<PathBuf> {
+        let chain = self.chain();
+        for candidate in name_fallbacks(sound) {
+            for theme in &chain {
+                for subdir in &theme.subdirs {
+                    for ext in THEME_SOUND_EXTENSIONS {
+                        let path = theme
+                            .path
+                            .join(subdir)
+                            .join(format!("{}.{}", candidate, ext));
+                        if !path.is_file() {
+                            continue;
+                        }
+                        if *ext == "disabled" {
+                            tracing::debug!(sound, theme = %self.name, "Sound disabled by theme");
+                            return None;
+                        }
+                        return Some(path);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Theme directories in lookup order: the theme, its parents, then
+    /// `freedesktop`
+    fn chain(&self) -> Vec<ThemeDir> {
+        let mut chain = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![self.name.clone()];
+
+        while let Some(name) = pending.pop() {
+            if !visited.insert(name.clone()) {
+                continue;
+            }
+
+            let mut parents = Vec::new();
+            for base in &self.base_dirs {
+                let path = base.join(&name);
+                if !path.is_dir() {
+                    continue;
+                }
+                let index = read_index(&path.join("index.theme"));
+                // The first index.theme found defines the inheritance
+                if parents.is_empty() {
+                    parents = index.inherits;
+                }
+                chain.push(ThemeDir {
+                    path,
+                    subdirs: index.directories,
+                });
+            }
+
+            // Parents are searched in listed order
+            pending.extend(parents.into_iter().rev());
+            if pending.is_empty() && !visited.contains(FALLBACK_THEME) {
+                pending.push(FALLBACK_THEME.to_string());
+            }
+        }
+        chain
+    }
+}
+
+/// The `[Sound Theme]` keys this lookup cares about
+struct ThemeIndex {
+    inherits: Vec<String>,
+    directories: Vec<String>,
+}
+
+fn read_index(path: &Path) -> ThemeIndex {
+    let mut index = ThemeIndex {
+        inherits: Vec::new(),
+        directories: Vec::new(),
+    };
+
+    if let Ok(contents) = std::fs::read_to_string(path) {
+        let mut in_section = false;
+        for line in contents.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_section = line == "[Sound Theme]";
+                continue;
+            }
+            if !in_section {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                let list = value
+                    .split(',')
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect();
+                match key.trim() {
+                    "Inherits" => index.inherits = list,
+                    "Directories" => index.directories = list,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // Themes without an index (or a Directories key) use the stereo profile
+    if index.directories.is_empty() {
+        index.directories.push("stereo".to_string());
+    }
+    index
+}
+
+/// `a-b-c`, `a-b`, `a`
+fn name_fallbacks(name: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(name), |name| {
+        name.rsplit_once('-').map(|(head, _)| head)
+    })
+    .filter(|name| !name.is_empty())
+}
+
+/// `sounds` directories from `$XDG_DATA_HOME` and `$XDG_DATA_DIRS`
+fn base_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = dirs::data_dir()
+        .map(|d| d.join("sounds"))
+        .into_iter()
+        .collect();
+
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(
+        data_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| PathBuf::from(dir).join("sounds")),
+    );
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn touch(path: PathBuf) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"").unwrap();
+    }
+
+    #[test]
+    fn test_name_fallbacks() {
+        let names: Vec<&str> = name_fallbacks("alarm-clock-elapsed").collect();
+        assert_eq!(names, ["alarm-clock-elapsed", "alarm-clock", "alarm"]);
+        assert_eq!(name_fallbacks("bell").count(), 1);
+    }
+
+    #[test]
+    fn test_lookup_with_inheritance() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().to_path_buf();
+
+        std::fs::create_dir_all(base.join("ocean")).unwrap();
+        std::fs::write(
+            base.join("ocean/index.theme"),
+            "[Sound Theme]\nName=Ocean\nInherits=parent\nDirectories=stereo\n",
+        )
+        .unwrap();
+        touch(base.join("ocean/stereo/bell.oga"));
+        touch(base.join("ocean/stereo/complete.disabled"));
+        touch(base.join("parent/stereo/message-new-instant.ogg"));
+        touch(base.join("freedesktop/stereo/alarm-clock.wav"));
+        touch(base.join("freedesktop/stereo/complete.oga"));
+
+        let theme = SoundTheme::with_base_dirs("ocean", vec![base.clone()]);
+        assert!(theme.exists());
+
+        // From the theme itself, a parent, and the freedesktop fallback
+        assert_eq!(
+            theme.lookup("bell"),
+            Some(base.join("ocean/stereo/bell.oga"))
+        );
+        assert_eq!(
+            theme.lookup("message-new-instant"),
+            Some(base.join("parent/stereo/message-new-instant.ogg"))
+        );
+        assert_eq!(
+            theme.lookup(theme_sound_name("alarm")),
+            Some(base.join("freedesktop/stereo/alarm-clock.wav"))
+        );
+
+        // Disabled in the theme even though freedesktop has it
+        assert_eq!(theme.lookup("complete"), None);
+        assert_eq!(theme.lookup("dialog-warning"), None);
+    }
+
+    #[test]
+    fn test_missing_theme_uses_freedesktop() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().to_path_buf();
+        touch(base.join("freedesktop/stereo/bell.oga"));
+
+        let theme = SoundTheme::with_base_dirs("nonexistent", vec![base.clone()]);
+        assert!(!theme.exists());
+        assert_eq!(
+            theme.lookup("bell"),
+            Some(base.join("freedesktop/stereo/bell.oga"))
+        );
+    }
+
+    #[test]
+    fn test_inheritance_cycle_terminates() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().to_path_buf();
+        for (name, parent) in [("a", "b"), ("b", "a")] {
+            std::fs::create_dir_all(base.join(name)).unwrap();
+            std::fs::write(
+                base.join(name).join("index.theme"),
+                format!("[Sound Theme]\nInherits={}\n", parent),
+            )
+            .unwrap();
+        }
+
+        let theme = SoundTheme::with_base_dirs("a", vec![base]);
+        assert_eq!(theme.lookup("bell"), None);
+    }
+}
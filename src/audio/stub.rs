@@ -1,9 +1,10 @@
 //! Stub implementation when audio feature is disabled
 
-use std::path::PathBuf;
 use thiserror::Error;
 use tracing::debug;
 
+use super::{SoundConfig, SoundEffect, SoundPriority, SoundTheme};
+
 /// Errors that can occur during audio playback
 #[derive(Debug, Error)]
 pub enum AudioError {
@@ -11,32 +12,6 @@ pub enum AudioError {
     NotEnabled,
 }
 
-/// Sound effect specification
-#[derive(Debug, Clone)]
-pub enum SoundEffect {
-    /// Built-in sound by name
-    Builtin(String),
-    /// Custom sound from file path
-    Custom(PathBuf),
-}
-
-impl Default for SoundEffect {
-    fn default() -> Self {
-        SoundEffect::Builtin("notification".to_string())
-    }
-}
-
-impl SoundEffect {
-    /// Parse a sound effect from configuration string
-    pub fn from_config(value: &str) -> Self {
-        if value.contains('/') || value.ends_with(".ogg") || value.ends_with(".wav") {
-            SoundEffect::Custom(PathBuf::from(value))
-        } else {
-            SoundEffect::Builtin(value.to_string())
-        }
-    }
-}
-
 /// Stub audio player (does nothing when audio feature is disabled)
 pub struct AudioPlayer;
 
@@ -50,6 +25,9 @@ impl AudioPlayer {
     /// Set volume (no-op)
     pub fn set_volume(&mut self, _volume: f32) {}
 
+    /// Set sound theme (no-op)
+    pub fn set_sound_theme(&mut self, _theme: Option<SoundTheme>) {}
+
     /// Play sound (no-op)
     pub fn play(&self, _sound: &SoundEffect) -> Result<(), AudioError> {
         debug!("Audio playback skipped (feature not enabled)");
@@ -61,6 +39,35 @@ impl AudioPlayer {
         Ok(())
     }
 
+    /// Play configured sound (no-op)
+    pub fn play_config(
+        &self,
+        _config: &SoundConfig,
+        _priority: SoundPriority,
+    ) -> Result<(), AudioError> {
+        debug!("Audio playback skipped (feature not enabled)");
+        Ok(())
+    }
+
+    /// Advance the playback queue (no-op)
+    pub fn update(&self) {}
+
+    /// Whether anything is playing (never)
+    pub fn is_busy(&self) -> bool {
+        false
+    }
+
+    /// Stop all sounds (no-op)
+    pub fn stop(&self) {}
+
+    /// Preload sound (no-op)
+    pub fn preload(&self, _sound: &SoundEffect) -> Result<(), AudioError> {
+        Ok(())
+    }
+
+    /// Clear the decoded sound cache (no-op)
+    pub fn clear_cache(&self) {}
+
     /// Preview sound (no-op)
     pub fn preview(&self, _sound: &SoundEffect) -> Result<(), AudioError> {
         Ok(())
@@ -0,0 +1,256 @@
+//! Starting the widget on login
+//!
+//! `cosmic-desktop-widget install-autostart` writes either a systemd user
+//! unit or an XDG autostart entry that runs the binary it was started from,
+//! and `--uninstall` removes them again. The systemd unit is tied to
+//! `graphical-session.target`, so it starts once the compositor has exported
+//! `WAYLAND_DISPLAY`, stops with the session and restarts after a crash.
+//!
+//! The user manager starts services with a minimal environment, so the unit
+//! carries over the variables in [`PASSED_ENV`]: widgets that run commands
+//! need the login `PATH`. An autostart entry is started by the session
+//! itself and inherits everything.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+/// Name of the systemd user unit
+pub const SERVICE_NAME: &str = "cosmic-desktop-widget.service";
+
+/// Name of the XDG autostart entry
+pub const DESKTOP_FILE: &str = "cosmic-desktop-widget.desktop";
+
+/// Variables copied into the systemd unit when set at install time
+pub const PASSED_ENV: &[&str] = &["PATH", "RUST_LOG"];
+
+/// Characters backslash-escaped between double quotes in a unit file
+const SYSTEMD_ESCAPED: &[char] = &['"', '\\'];
+
+/// Characters backslash-escaped between double quotes in an Exec key
+const DESKTOP_ESCAPED: &[char] = &['"', '`', '$', '\\'];
+
+/// How the widget gets started on login
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// A systemd user service
+    Systemd,
+    /// An entry in `~/.config/autostart`, for sessions without systemd
+    Xdg,
+}
+
+impl Method {
+    /// All methods, for uninstalling
+    pub const ALL: [Method; 2] = [Method::Systemd, Method::Xdg];
+
+    /// systemd when a user manager is running, XDG autostart otherwise
+    pub fn detect() -> Self {
+        let user_manager = dirs::runtime_dir().is_some_and(|dir| dir.join("systemd").is_dir());
+        if user_manager {
+            Method::Systemd
+        } else {
+            Method::Xdg
+        }
+    }
+
+    /// The file this method installs, under the config directory `config_dir`
+    pub fn path_in(self, config_dir: &Path) -> PathBuf {
+        match self {
+            Method::Systemd => config_dir.join("systemd/user").join(SERVICE_NAME),
+            Method::Xdg => config_dir.join("autostart").join(DESKTOP_FILE),
+        }
+    }
+
+    /// The file this method installs for the current user
+    pub fn path(self) -> Result<PathBuf> {
+        let config_dir = dirs::config_dir().context("Failed to get config directory")?;
+        Ok(self.path_in(&config_dir))
+    }
+
+    /// The file's contents for running `binary`
+    pub fn contents(self, binary: &Path, env: &[(String, String)]) -> String {
+        match self {
+            Method::Systemd => systemd_unit(binary, env),
+            Method::Xdg => desktop_entry(binary),
+        }
+    }
+}
+
+/// A systemd user unit running `binary` with the variables in `env`
+pub fn systemd_unit(binary: &Path, env: &[(String, String)]) -> String {
+    let mut unit = String::from(
+        "[Unit]\n\
+         Description=COSMIC Desktop Widget\n\
+         PartOf=graphical-session.target\n\
+         After=graphical-session.target\n\
+         \n\
+         [Service]\n",
+    );
+    // `%` starts a specifier and `$` a variable anywhere in a unit
+    let escape = |value: String| value.replace('%', "%%").replace('$', "$$");
+    let exec = quote(&binary.to_string_lossy(), SYSTEMD_ESCAPED);
+    unit.push_str(&format!("ExecStart={}\n", escape(exec)));
+    for (name, value) in env {
+        let assignment = escape_quoted(&format!("{}={}", name, value), SYSTEMD_ESCAPED);
+        unit.push_str(&format!("Environment=\"{}\"\n", escape(assignment)));
+    }
+    unit.push_str(
+        "Restart=on-failure\n\
+         RestartSec=5\n\
+         \n\
+         [Install]\n\
+         WantedBy=graphical-session.target\n",
+    );
+    unit
+}
+
+/// An XDG autostart entry running `binary`
+pub fn desktop_entry(binary: &Path) -> String {
+    // Exec is itself a string value, so its backslashes are escaped again
+    let exec = quote(&binary.to_string_lossy(), DESKTOP_ESCAPED)
+        .replace('\\', "\\\\")
+        .replace('%', "%%");
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=COSMIC Desktop Widget\n\
+         Comment=Widgets on the desktop background\n\
+         Exec={}\n\
+         Icon=preferences-desktop\n\
+         Terminal=false\n\
+         NoDisplay=true\n\
+         X-GNOME-Autostart-enabled=true\n",
+        exec
+    )
+}
+
+/// `value` as one command line argument, in double quotes if needed
+fn quote(value: &str, escaped: &[char]) -> String {
+    let plain = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "/._+-:".contains(c));
+    if plain && !value.is_empty() {
+        value.to_string()
+    } else {
+        format!("\"{}\"", escape_quoted(value, escaped))
+    }
+}
+
+/// Backslash-escape the `escaped` characters in `value`
+fn escape_quoted(value: &str, escaped: &[char]) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if escaped.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// The [`PASSED_ENV`] variables that are set
+fn passed_env() -> Vec<(String, String)> {
+    PASSED_ENV
+        .iter()
+        .filter_map(|&name| {
+            let value = std::env::var(name).ok().filter(|value| !value.is_empty())?;
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
+/// Install `method` for the running binary, replacing an earlier install
+///
+/// The systemd unit is enabled but not started. Returns the file written.
+pub fn install(method: Method) -> Result<PathBuf> {
+    let binary = std::env::current_exe().context("Failed to find the running binary")?;
+    let path = method.path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(&path, method.contents(&binary, &passed_env()))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    if method == Method::Systemd {
+        systemctl(&["daemon-reload"])?;
+        systemctl(&["enable", SERVICE_NAME])?;
+    }
+    Ok(path)
+}
+
+/// Remove `method`'s install, stopping the service. Returns the file
+/// removed, or None if it wasn't installed.
+pub fn uninstall(method: Method) -> Result<Option<PathBuf>> {
+    let path = method.path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    if method == Method::Systemd {
+        systemctl(&["disable", "--now", SERVICE_NAME])?;
+    }
+    std::fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    if method == Method::Systemd {
+        systemctl(&["daemon-reload"])?;
+    }
+    Ok(Some(path))
+}
+
+/// Run `systemctl --user` with `args`
+fn systemctl(args: &[&str]) -> Result<()> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .status()
+        .context("Failed to run systemctl")?;
+    if !status.success() {
+        anyhow::bail!("systemctl --user {} failed ({})", args.join(" "), status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_systemd_unit() {
+        let env = vec![
+            ("PATH".to_string(), "/usr/bin:/home/me/bin".to_string()),
+            ("RUST_LOG".to_string(), "debug".to_string()),
+        ];
+        let unit = systemd_unit(Path::new("/usr/local/bin/cosmic-desktop-widget"), &env);
+        assert!(unit.contains("ExecStart=/usr/local/bin/cosmic-desktop-widget\n"));
+        assert!(unit.contains("Environment=\"PATH=/usr/bin:/home/me/bin\"\n"));
+        assert!(unit.contains("Environment=\"RUST_LOG=debug\"\n"));
+        assert!(unit.contains("WantedBy=graphical-session.target"));
+
+        // Paths with spaces are quoted, specifiers and variables escaped
+        let unit = systemd_unit(Path::new("/opt/my apps/100%/$widget"), &[]);
+        assert!(unit.contains("ExecStart=\"/opt/my apps/100%%/$$widget\"\n"));
+    }
+
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry(Path::new("/usr/bin/cosmic-desktop-widget"));
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("Exec=/usr/bin/cosmic-desktop-widget\n"));
+
+        let entry = desktop_entry(Path::new("/home/me/my apps/$widget"));
+        assert!(entry.contains("Exec=\"/home/me/my apps/\\\\$widget\"\n"));
+    }
+
+    #[test]
+    fn test_paths() {
+        let config = Path::new("/home/me/.config");
+        assert_eq!(
+            Method::Systemd.path_in(config),
+            config.join("systemd/user/cosmic-desktop-widget.service")
+        );
+        assert_eq!(
+            Method::Xdg.path_in(config),
+            config.join("autostart/cosmic-desktop-widget.desktop")
+        );
+    }
+}
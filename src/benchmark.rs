@@ -0,0 +1,277 @@
+//! Offscreen render benchmark
+//!
+//! `cosmic-desktop-widget --benchmark [ITERATIONS]` renders every enabled
+//! widget into a plain buffer, at the size it gets on the desktop, and
+//! reports how long each render took. No compositor is involved, so runs on
+//! the same machine and config can be compared across builds.
+//!
+//! Each widget fetches its data once first, so network widgets are measured
+//! with real content rather than their loading text.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::render::Renderer;
+use crate::widget::{Freshness, Widget, WidgetContent, WidgetRegistry};
+
+/// Renders per widget when no count is given
+pub const DEFAULT_ITERATIONS: usize = 200;
+
+/// Longest a widget's first fetch may take before it is measured without
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Renders before timing starts, to fill the glyph cache
+const WARMUP_ITERATIONS: usize = 5;
+
+/// Summary of a set of render times
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    /// Number of renders
+    pub samples: usize,
+    /// Mean render time
+    pub mean: Duration,
+    /// Median render time
+    pub median: Duration,
+    /// 95th percentile
+    pub p95: Duration,
+    /// Slowest render
+    pub max: Duration,
+}
+
+impl Timing {
+    /// Summarize `samples`, or None if there are none
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let percentile = |p: f64| sorted[((sorted.len() - 1) as f64 * p).round() as usize];
+        Some(Self {
+            samples: sorted.len(),
+            mean: sorted.iter().sum::<Duration>() / sorted.len() as u32,
+            median: percentile(0.5),
+            p95: percentile(0.95),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// Render times of one widget instance
+#[derive(Debug, Clone)]
+pub struct WidgetResult {
+    /// Instance id
+    pub id: String,
+    /// Kind of content it drew, see [`content_kind`]
+    pub kind: &'static str,
+    /// Surface size in pixels
+    pub size: (u32, u32),
+    /// Render times
+    pub timing: Timing,
+}
+
+/// Results of a benchmark run
+#[derive(Debug, Clone, Default)]
+pub struct BenchmarkReport {
+    /// Timed renders per widget
+    pub iterations: usize,
+    /// One entry per enabled widget, in config order
+    pub widgets: Vec<WidgetResult>,
+    /// Render times of all widgets drawing each kind of content
+    pub kinds: BTreeMap<&'static str, Timing>,
+}
+
+/// Name of the [`WidgetContent`] variant, for grouping results
+pub fn content_kind(content: &WidgetContent) -> &'static str {
+    match content {
+        WidgetContent::Text { .. } => "text",
+        WidgetContent::MultiLine { .. } => "multi-line",
+        WidgetContent::IconText { .. } => "icon-text",
+        WidgetContent::IconLines { .. } => "icon-lines",
+        WidgetContent::Progress { .. } => "progress",
+        WidgetContent::StyledText { .. } => "styled-text",
+        WidgetContent::MultiProgress { .. } => "multi-progress",
+        WidgetContent::Grid { .. } => "grid",
+        WidgetContent::Chart { .. } => "chart",
+        WidgetContent::Media { .. } => "media",
+        WidgetContent::IconRow { .. } => "icon-row",
+        WidgetContent::Error { .. } => "error",
+        WidgetContent::Empty => "empty",
+    }
+}
+
+/// Render each of `config`'s enabled widgets `iterations` times
+pub fn run(config: &Config, iterations: usize) -> Result<BenchmarkReport> {
+    let iterations = iterations.max(1);
+    let registry = WidgetRegistry::with_user_widgets();
+
+    let theme = config.get_theme();
+    let mut renderer = Renderer::with_theme(theme.clone());
+    renderer.set_text_scale(config.accessibility.text_scale());
+    renderer.set_glyph_cache_capacity(config.performance.glyph_cache_size);
+
+    let mut report = BenchmarkReport {
+        iterations,
+        ..Default::default()
+    };
+    let mut by_kind: BTreeMap<&'static str, Vec<Duration>> = BTreeMap::new();
+
+    for instance in config.enabled_widgets() {
+        let id = instance.instance_id();
+        let mut widget = registry
+            .create(&instance.widget_type, &instance.config)
+            .with_context(|| format!("Failed to create widget '{}'", id))?;
+        widget.on_start();
+        prepare_widget(widget.as_mut(), &id);
+
+        let width = instance.effective_width(config.panel.width);
+        let height = instance.effective_height(config.panel.height);
+        let opacity = instance.effective_opacity(config.panel.background_opacity.unwrap_or(0.9));
+        let accent = instance
+            .accent
+            .is_some()
+            .then(|| instance.effective_accent(&theme));
+        let mut canvas = vec![0u8; width as usize * height as usize * 4];
+
+        let mut samples = Vec::with_capacity(iterations);
+        for i in 0..WARMUP_ITERATIONS + iterations {
+            let started = Instant::now();
+            renderer.render_single_widget(
+                &mut canvas,
+                width,
+                height,
+                widget.as_ref(),
+                opacity,
+                accent,
+                instance.frameless,
+                Freshness::Fresh,
+            );
+            if i >= WARMUP_ITERATIONS {
+                samples.push(started.elapsed());
+            }
+        }
+        widget.on_stop();
+
+        let kind = content_kind(&widget.content());
+        by_kind.entry(kind).or_default().extend(&samples);
+        if let Some(timing) = Timing::from_samples(&samples) {
+            report.widgets.push(WidgetResult {
+                id,
+                kind,
+                size: (width, height),
+                timing,
+            });
+        }
+    }
+
+    report.kinds = by_kind
+        .iter()
+        .filter_map(|(kind, samples)| Some((*kind, Timing::from_samples(samples)?)))
+        .collect();
+    Ok(report)
+}
+
+/// Bring `widget` (instance `id`) up to date outside the event loop: one
+/// update, and one fetch of its data source
+///
+/// A failed fetch is logged and handed to the widget like any other.
+pub fn prepare_widget(widget: &mut dyn Widget, id: &str) {
+    widget.update();
+    let Some(source) = widget.data_source() else {
+        return;
+    };
+    let result = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Failed to build fetch runtime")
+        .and_then(|runtime| {
+            runtime
+                .block_on(async { tokio::time::timeout(FETCH_TIMEOUT, source.fetch()).await })
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("Timed out")))
+        });
+    if let Err(e) = &result {
+        tracing::warn!(widget = %id, error = %e, "Rendering without fetched data");
+    }
+    widget.apply_fetch(result);
+    widget.update();
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Duration| format!("{:.3}", d.as_secs_f64() * 1000.0);
+        writeln!(f, "{} renders per widget, times in ms\n", self.iterations)?;
+        writeln!(
+            f,
+            "{:<20} {:<15} {:>9} {:>8} {:>8} {:>8} {:>8}",
+            "widget", "content", "size", "mean", "median", "p95", "max"
+        )?;
+        for widget in &self.widgets {
+            let t = &widget.timing;
+            writeln!(
+                f,
+                "{:<20} {:<15} {:>9} {:>8} {:>8} {:>8} {:>8}",
+                widget.id,
+                widget.kind,
+                format!("{}x{}", widget.size.0, widget.size.1),
+                ms(t.mean),
+                ms(t.median),
+                ms(t.p95),
+                ms(t.max)
+            )?;
+        }
+
+        writeln!(
+            f,
+            "\n{:<15} {:>8} {:>8} {:>8} {:>8} {:>8}",
+            "content", "renders", "mean", "median", "p95", "max"
+        )?;
+        for (kind, t) in &self.kinds {
+            writeln!(
+                f,
+                "{:<15} {:>8} {:>8} {:>8} {:>8} {:>8}",
+                kind,
+                t.samples,
+                ms(t.mean),
+                ms(t.median),
+                ms(t.p95),
+                ms(t.max)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_millis(v)).collect()
+    }
+
+    #[test]
+    fn test_timing() {
+        assert!(Timing::from_samples(&[]).is_none());
+
+        let timing = Timing::from_samples(&ms(&[5, 1, 3, 2, 4])).unwrap();
+        assert_eq!(timing.samples, 5);
+        assert_eq!(timing.mean, Duration::from_millis(3));
+        assert_eq!(timing.median, Duration::from_millis(3));
+        assert_eq!(timing.p95, Duration::from_millis(5));
+        assert_eq!(timing.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_run_renders_local_widgets() {
+        let mut config = Config::default();
+        config.widgets.retain(|w| w.widget_type == "clock");
+        let report = run(&config, 3).unwrap();
+        assert_eq!(report.widgets.len(), config.enabled_widgets().count());
+        assert!(report.widgets.iter().all(|w| w.timing.samples == 3));
+        assert!(report.to_string().contains("renders per widget"));
+    }
+}
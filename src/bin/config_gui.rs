@@ -12,23 +12,43 @@
 use cosmic::{
     app::{Core, Task},
     cosmic_config, cosmic_theme,
+    dialog::file_chooser::{self, FileFilter},
     iced::{
         alignment::{Horizontal, Vertical},
-        Alignment, Length,
+        Alignment, Length, Padding, Point,
     },
     widget::{
-        self, button, column, container, dropdown, horizontal_space, icon, row,
+        self, button,
+        color_picker::{self, ColorPickerModel, ColorPickerUpdate},
+        column, container, dropdown, horizontal_space, icon, row,
         segmented_button, settings, slider, text, text_input, toggler, vertical_space,
     },
     Application, Apply, Element,
     theme,
 };
-use cosmic_desktop_widget::{Config, GradientConfig, Position, SoundsConfig, ThemeColors, ThemeConfig, ThemeStyle};
+use cosmic_desktop_widget::config::DiffLine;
+use cosmic_desktop_widget::ipc::{self, IpcRequest, IpcResponse};
+use cosmic_desktop_widget::{fl, Config, GradientConfig, Position, SoundsConfig, ThemeColors, ThemeConfig, ThemeStyle, WidgetRegistry};
+use cosmic_desktop_widget::theme::{files::BUILTIN_THEMES, Color as WidgetColor, Theme};
+use cosmic_desktop_widget::audio::BUILTIN_SOUNDS;
+use cosmic_desktop_widget::render::Renderer;
+use cosmic_desktop_widget::wayland::{outputs, OutputGeometry};
+use cosmic_desktop_widget::widget::{ConfigField, FieldKind, Freshness, WidgetMetadata};
 
 const APP_ID: &str = "com.github.olafkfreund.cosmic-desktop-widget-config";
 
+/// Size of the example thumbnails in the widget gallery
+const THUMBNAIL_WIDTH: u32 = 200;
+const THUMBNAIL_HEIGHT: u32 = 72;
+
+/// Size of the monitor layout, which the outputs are scaled to fit
+const LAYOUT_WIDTH: f32 = 480.0;
+const LAYOUT_HEIGHT: f32 = 220.0;
+const LAYOUT_PADDING: f32 = 8.0;
+
 fn main() -> cosmic::iced::Result {
     tracing_subscriber::fmt::init();
+    cosmic_desktop_widget::i18n::init_from_env();
 
     let settings = cosmic::app::Settings::default();
 
@@ -46,23 +66,23 @@ enum Tab {
 }
 
 impl Tab {
-    fn title(&self) -> &'static str {
+    fn title(&self) -> String {
         match self {
-            Tab::General => "General",
-            Tab::Appearance => "Appearance",
-            Tab::Theme => "Theme",
-            Tab::Sounds => "Sounds",
-            Tab::Widgets => "Widgets",
+            Tab::General => fl!("tab-general"),
+            Tab::Appearance => fl!("tab-appearance"),
+            Tab::Theme => fl!("tab-theme"),
+            Tab::Sounds => fl!("tab-sounds"),
+            Tab::Widgets => fl!("tab-widgets"),
         }
     }
 
-    fn description(&self) -> &'static str {
+    fn description(&self) -> String {
         match self {
-            Tab::General => "Panel size, position, and layout settings",
-            Tab::Appearance => "Theme selection and transparency options",
-            Tab::Theme => "Custom color and style configuration",
-            Tab::Sounds => "Notification and alarm sounds",
-            Tab::Widgets => "Enable, disable, and reorder widgets",
+            Tab::General => fl!("tab-general-description"),
+            Tab::Appearance => fl!("tab-appearance-description"),
+            Tab::Theme => fl!("tab-theme-description"),
+            Tab::Sounds => fl!("tab-sounds-description"),
+            Tab::Widgets => fl!("tab-widgets-description"),
         }
     }
 
@@ -77,6 +97,53 @@ impl Tab {
     }
 }
 
+/// A color edited in the Theme tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ThemeColorField {
+    Background,
+    TextPrimary,
+    TextSecondary,
+    Accent,
+    Border,
+    GradientStart,
+    GradientEnd,
+}
+
+impl ThemeColorField {
+    /// Colors of the "Custom Colors" section
+    const COLORS: [Self; 5] = [
+        Self::Background,
+        Self::TextPrimary,
+        Self::TextSecondary,
+        Self::Accent,
+        Self::Border,
+    ];
+
+    fn label(self) -> String {
+        match self {
+            Self::Background => fl!("color-background"),
+            Self::TextPrimary => fl!("color-text-primary"),
+            Self::TextSecondary => fl!("color-text-secondary"),
+            Self::Accent => fl!("color-accent"),
+            Self::Border => fl!("color-border"),
+            Self::GradientStart => fl!("color-gradient-start"),
+            Self::GradientEnd => fl!("color-gradient-end"),
+        }
+    }
+
+    /// Default color, also shown in the empty hex input
+    fn placeholder(self) -> &'static str {
+        match self {
+            Self::Background | Self::GradientStart => "#1e1e2e",
+            Self::TextPrimary => "#cdd6f4",
+            Self::TextSecondary => "#a6adc8",
+            Self::Accent => "#89b4fa",
+            Self::Border => "#45475a",
+            Self::GradientEnd => "#313244",
+        }
+    }
+}
+
 /// Messages for the configuration application
 #[derive(Debug, Clone)]
 enum Message {
@@ -97,28 +164,31 @@ enum Message {
     // Appearance settings
     ThemeSelected(String),
     OpacityChanged(f32),
+    ThemeFileInputChanged(String),
+    ThemeImport,
+    ThemeExport,
 
     // Theme editor settings
-    ThemeBackgroundChanged(String),
-    ThemeTextPrimaryChanged(String),
-    ThemeTextSecondaryChanged(String),
-    ThemeAccentChanged(String),
-    ThemeBorderChanged(String),
+    ThemeColorInput(ThemeColorField, String),
+    ThemeColorAlphaChanged(ThemeColorField, f32),
+    ThemeColorPickerToggled(ThemeColorField),
+    ThemeColorPicker(ColorPickerUpdate),
     ThemeCornerRadiusChanged(f32),
     ThemeBorderWidthChanged(f32),
     ThemeBlurToggled(bool),
     GradientEnabledToggled(bool),
-    GradientStartChanged(String),
-    GradientEndChanged(String),
     GradientAngleChanged(f32),
 
     // Sound settings
     SoundsEnabledToggled(bool),
     SoundsMasterVolumeChanged(f32),
+    SoundThemeChanged(String),
     AlarmSoundSelected(String),
+    AlarmSoundFileChanged(String),
     AlarmVolumeChanged(f32),
     AlarmRepeatChanged(String),
     NotificationSoundSelected(String),
+    NotificationSoundFileChanged(String),
     NotificationVolumeChanged(f32),
     PreviewSound(String),
 
@@ -139,11 +209,35 @@ enum Message {
     WidgetMarginBottomChanged(usize, String),
     WidgetMarginLeftChanged(usize, String),
     WidgetOpacityChanged(usize, f32),
+    WidgetAccentChanged(usize, String),
+    WidgetSoundChanged(usize, String),
+    WidgetFramelessToggled(usize, bool),
+    WidgetFieldChanged(usize, &'static str, Option<toml::Value>),
+    WidgetFieldInput(usize, &'static str, String),
+    WidgetOutputChanged(usize, usize),
+
+    // Monitor layout
+    LayoutRefresh,
+    LayoutCursorMoved(Point),
+    LayoutPressed,
+    LayoutReleased,
+    LayoutExited,
+
+    // Configuration import/export
+    ConfigExport,
+    ConfigImport,
+    ConfigImportLoaded(Result<Box<Config>, String>),
+    ConfigImportConfirmed,
+    ConfigImportDiscarded,
+    ConfigFileStatus(Option<String>),
 
     // Actions
+    Apply,
     Save,
     Cancel,
     ConfigSaved(Result<(), String>),
+    ConfigApplied(Result<(), String>),
+    ConfigReverted(Result<(), String>),
 }
 
 /// Configuration application state
@@ -174,6 +268,9 @@ struct ConfigApp {
     theme_border_input: String,
     gradient_start_input: String,
     gradient_end_input: String,
+    color_picker: ColorPickerModel,
+    /// Color the picker is open for
+    picking_color: Option<ThemeColorField>,
 
     // Sound settings state
     alarm_repeat_input: String,
@@ -181,17 +278,51 @@ struct ConfigApp {
     // Available themes
     available_themes: Vec<String>,
 
+    // Theme file import/export
+    theme_file_input: String,
+    theme_file_status: Option<String>,
+
     // Available sounds
     available_sounds: Vec<String>,
 
     // Available widget types (from registry)
     available_widget_types: Vec<String>,
 
+    // Registry used for widget capabilities and config schemas
+    widget_registry: WidgetRegistry,
+
+    // Example renderings of widget types for the gallery
+    widget_thumbnails: std::collections::HashMap<String, widget::image::Handle>,
+
+    // A clock rendered in each theme, for picking themes in the Appearance tab
+    theme_previews: std::collections::HashMap<String, widget::image::Handle>,
+
+    // Theme the "custom" preview card was last rendered with
+    custom_preview_theme: Option<Theme>,
+
     // Widget configuration state
     expanded_widget: Option<usize>,
     widget_width_inputs: Vec<String>,
     widget_height_inputs: Vec<String>,
     widget_margin_inputs: Vec<(String, String, String, String)>, // top, right, bottom, left
+    widget_field_inputs: std::collections::HashMap<(usize, &'static str), String>, // raw text of schema fields
+
+    // Monitor layout
+    outputs: Vec<OutputGeometry>,
+    outputs_error: Option<String>,
+    /// "Any output" followed by the output names, for the output dropdowns
+    output_choices: Vec<String>,
+    layout_cursor: Point,
+    /// Widget being dragged in the monitor layout, with where it was grabbed
+    layout_drag: Option<(usize, f32, f32)>,
+
+    // Configuration import/export
+    config_file_status: Option<String>,
+    /// Imported config waiting for confirmation, with its diff to the saved one
+    pending_import: Option<(Config, Vec<DiffLine>)>,
+
+    /// Whether the running widget shows the working copy rather than the saved config
+    applied: bool,
 
     // Save status
     save_error: Option<String>,
@@ -216,14 +347,7 @@ impl Application for ConfigApp {
         let config = Config::load().unwrap_or_default();
         let original_config = config.clone();
 
-        let available_themes = vec![
-            "cosmic_dark".to_string(),
-            "light".to_string(),
-            "transparent_dark".to_string(),
-            "transparent_light".to_string(),
-            "glass".to_string(),
-            "custom".to_string(),
-        ];
+        let available_themes = available_themes();
 
         let available_sounds = vec![
             "alarm".to_string(),
@@ -232,45 +356,47 @@ impl Application for ConfigApp {
             "beep".to_string(),
         ];
 
-        // All available widget types (12 total)
+        // All available widget types (20 total)
         let available_widget_types = vec![
             "battery".to_string(),
             "calendar".to_string(),
+            "carousel".to_string(),
             "clock".to_string(),
             "countdown".to_string(),
             "crypto".to_string(),
+            "custom".to_string(),
+            "dnd".to_string(),
+            "launcher".to_string(),
             "mpris".to_string(),
             "news".to_string(),
+            "night_light".to_string(),
+            "notifications".to_string(),
             "pomodoro".to_string(),
+            "power_profile".to_string(),
             "quotes".to_string(),
             "stocks".to_string(),
             "system_monitor".to_string(),
+            "vpn".to_string(),
             "weather".to_string(),
         ];
 
-        // Initialize theme config from existing or default
-        let theme_config = config.theme_config.clone().unwrap_or_default();
-
-        // Initialize widget configuration inputs
-        let widget_width_inputs: Vec<String> = config.widgets
-            .iter()
-            .map(|w| w.width.map(|v| v.to_string()).unwrap_or_else(|| "250".to_string()))
-            .collect();
+        // Widgets discovered from manifests in the config directory
+        let registry = WidgetRegistry::with_user_widgets();
+        let mut available_widget_types = available_widget_types;
+        for manifest in registry.manifests() {
+            available_widget_types.push(manifest.widget_type.clone());
+        }
 
-        let widget_height_inputs: Vec<String> = config.widgets
-            .iter()
-            .map(|w| w.height.map(|v| v.to_string()).unwrap_or_else(|| "90".to_string()))
-            .collect();
+        // Hide widget types whose required features were compiled out
+        available_widget_types.retain(|widget_type| registry.is_available(widget_type));
 
-        let widget_margin_inputs: Vec<(String, String, String, String)> = config.widgets
+        let opacity = config.panel.background_opacity.unwrap_or(0.9);
+        let mut renderer = Renderer::with_theme(config.get_theme());
+        let widget_thumbnails = available_widget_types
             .iter()
-            .map(|w| {
-                (
-                    w.margin_top.map(|v| v.to_string()).unwrap_or_else(|| "10".to_string()),
-                    w.margin_right.map(|v| v.to_string()).unwrap_or_else(|| "20".to_string()),
-                    w.margin_bottom.map(|v| v.to_string()).unwrap_or_else(|| "0".to_string()),
-                    w.margin_left.map(|v| v.to_string()).unwrap_or_else(|| "0".to_string()),
-                )
+            .filter_map(|widget_type| {
+                let thumbnail = render_thumbnail(&mut renderer, &registry, widget_type, opacity)?;
+                Some((widget_type.clone(), thumbnail))
             })
             .collect();
 
@@ -283,36 +409,57 @@ impl Application for ConfigApp {
             .insert(|b| b.text(Tab::Widgets.title()).data(Tab::Widgets))
             .build();
 
-        let app = ConfigApp {
+        // Inputs are filled in from the config by reload_inputs below
+        let mut app = ConfigApp {
             core,
             current_tab: Tab::General,
             tab_model,
-            width_input: config.panel.width.to_string(),
-            height_input: config.panel.height.to_string(),
-            margin_top_input: config.panel.margin.top.to_string(),
-            margin_right_input: config.panel.margin.right.to_string(),
-            margin_bottom_input: config.panel.margin.bottom.to_string(),
-            margin_left_input: config.panel.margin.left.to_string(),
-            theme_background_input: theme_config.colors.background.clone(),
-            theme_text_primary_input: theme_config.colors.text_primary.clone(),
-            theme_text_secondary_input: theme_config.colors.text_secondary.clone(),
-            theme_accent_input: theme_config.colors.accent.clone(),
-            theme_border_input: theme_config.colors.border.clone(),
-            gradient_start_input: theme_config.gradient.as_ref().map(|g| g.start_color.clone()).unwrap_or_else(|| "#1e1e2e".to_string()),
-            gradient_end_input: theme_config.gradient.as_ref().map(|g| g.end_color.clone()).unwrap_or_else(|| "#313244".to_string()),
-            theme_config,
-            alarm_repeat_input: config.sounds.alarm.repeat.to_string(),
+            width_input: String::new(),
+            height_input: String::new(),
+            margin_top_input: String::new(),
+            margin_right_input: String::new(),
+            margin_bottom_input: String::new(),
+            margin_left_input: String::new(),
+            theme_background_input: String::new(),
+            theme_text_primary_input: String::new(),
+            theme_text_secondary_input: String::new(),
+            theme_accent_input: String::new(),
+            theme_border_input: String::new(),
+            gradient_start_input: String::new(),
+            gradient_end_input: String::new(),
+            theme_config: ThemeConfig::default(),
+            color_picker: ColorPickerModel::new("Hex", "RGB", None, None),
+            picking_color: None,
+            alarm_repeat_input: String::new(),
             config,
             original_config,
             available_themes,
             available_sounds,
             available_widget_types,
+            widget_registry: registry,
+            widget_thumbnails,
+            theme_previews: std::collections::HashMap::new(),
+            custom_preview_theme: None,
             expanded_widget: None,
-            widget_width_inputs,
-            widget_height_inputs,
-            widget_margin_inputs,
+            widget_width_inputs: Vec::new(),
+            widget_height_inputs: Vec::new(),
+            widget_margin_inputs: Vec::new(),
+            widget_field_inputs: std::collections::HashMap::new(),
+            outputs: Vec::new(),
+            outputs_error: None,
+            output_choices: Vec::new(),
+            layout_cursor: Point::ORIGIN,
+            layout_drag: None,
+            theme_file_input: String::new(),
+            theme_file_status: None,
+            config_file_status: None,
+            pending_import: None,
+            applied: false,
             save_error: None,
         };
+        app.reload_inputs();
+        app.refresh_outputs();
+        app.render_theme_previews();
 
         (app, Task::none())
     }
@@ -346,19 +493,29 @@ impl Application for ConfigApp {
             .apply(container)
             .padding([0, spacing.space_s]);
 
-        // Action buttons following COSMIC patterns
-        let buttons = row::with_capacity(3)
+        // Action buttons following COSMIC patterns; invalid input blocks saving
+        let has_errors = self.has_errors();
+        let mut buttons = row::with_capacity(5)
             .push(
                 button::standard("Cancel")
                     .on_press(Message::Cancel)
             )
-            .push(horizontal_space())
+            .push(horizontal_space());
+        if has_errors {
+            buttons = buttons.push(text::caption("Fix the highlighted settings to save"));
+        }
+        let buttons = buttons
+            .push(
+                button::standard("Apply")
+                    .on_press_maybe((!has_errors).then_some(Message::Apply))
+            )
             .push(
                 button::suggested("Save")
-                    .on_press(Message::Save)
+                    .on_press_maybe((!has_errors).then_some(Message::Save))
             )
             .spacing(spacing.space_s)
-            .padding([spacing.space_s, spacing.space_m]);
+            .padding([spacing.space_s, spacing.space_m])
+            .align_y(Alignment::Center);
 
         // Main content layout
         let mut main_content = column::with_capacity(5)
@@ -397,6 +554,10 @@ impl Application for ConfigApp {
                 if let Some(tab) = self.tab_model.data::<Tab>(entity) {
                     self.current_tab = *tab;
                 }
+                // Pick up edits to the custom theme
+                if self.current_tab == Tab::Appearance {
+                    self.refresh_custom_preview();
+                }
             }
 
             // General settings updates
@@ -457,32 +618,68 @@ impl Application for ConfigApp {
             Message::OpacityChanged(opacity) => {
                 self.config.panel.background_opacity = Some(opacity);
             }
+            Message::ThemeFileInputChanged(value) => {
+                self.theme_file_input = value;
+            }
+            Message::ThemeImport => {
+                let path = expand_home(self.theme_file_input.trim());
+                match Theme::import(&path) {
+                    Ok(name) => {
+                        self.available_themes = available_themes();
+                        self.render_theme_previews();
+                        self.config.panel.theme = name.clone();
+                        self.theme_file_status = Some(format!("Imported theme '{}'", name));
+                    }
+                    Err(e) => {
+                        self.theme_file_status = Some(format!("Import failed: {:#}", e));
+                    }
+                }
+            }
+            Message::ThemeExport => {
+                let path = expand_home(self.theme_file_input.trim());
+                self.theme_file_status = Some(match self.config.get_theme().save_file(&path) {
+                    Ok(()) => format!("Exported theme to {}", path.display()),
+                    Err(e) => format!("Export failed: {:#}", e),
+                });
+            }
 
             // Theme editor settings
-            Message::ThemeBackgroundChanged(value) => {
-                self.theme_background_input = value.clone();
-                self.theme_config.colors.background = value;
-                self.config.theme_config = Some(self.theme_config.clone());
-            }
-            Message::ThemeTextPrimaryChanged(value) => {
-                self.theme_text_primary_input = value.clone();
-                self.theme_config.colors.text_primary = value;
-                self.config.theme_config = Some(self.theme_config.clone());
+            Message::ThemeColorInput(field, value) => {
+                // Typed colors only reach the theme once they are valid
+                if WidgetColor::from_hex(&value).is_some() {
+                    self.set_theme_color(field, value.trim().to_string());
+                }
+                *self.color_input_mut(field) = value;
             }
-            Message::ThemeTextSecondaryChanged(value) => {
-                self.theme_text_secondary_input = value.clone();
-                self.theme_config.colors.text_secondary = value;
-                self.config.theme_config = Some(self.theme_config.clone());
+            Message::ThemeColorAlphaChanged(field, percent) => {
+                if let Some(color) = WidgetColor::from_hex(self.theme_color(field)) {
+                    let alpha = (percent * 2.55).round() as u8;
+                    self.pick_theme_color(field, color.with_alpha(alpha));
+                }
             }
-            Message::ThemeAccentChanged(value) => {
-                self.theme_accent_input = value.clone();
-                self.theme_config.colors.accent = value;
-                self.config.theme_config = Some(self.theme_config.clone());
+            Message::ThemeColorPickerToggled(field) => {
+                if self.picking_color == Some(field) {
+                    self.picking_color = None;
+                } else {
+                    let current = WidgetColor::from_hex(self.theme_color(field)).map(iced_color);
+                    self.color_picker = ColorPickerModel::new("Hex", "RGB", None, current);
+                    self.picking_color = Some(field);
+                }
             }
-            Message::ThemeBorderChanged(value) => {
-                self.theme_border_input = value.clone();
-                self.theme_config.colors.border = value;
-                self.config.theme_config = Some(self.theme_config.clone());
+            Message::ThemeColorPicker(update) => {
+                let task = self.color_picker.update::<Message>(update);
+                if let (Some(field), Some(picked)) =
+                    (self.picking_color, self.color_picker.get_applied_color())
+                {
+                    // The picker has no alpha channel, so the color keeps its own
+                    let current = WidgetColor::from_hex(self.theme_color(field));
+                    let [r, g, b, _] = picked.into_rgba8();
+                    let color = WidgetColor::new(r, g, b, current.map_or(255, |c| c.a));
+                    if current.map(WidgetColor::to_array) != Some(color.to_array()) {
+                        self.pick_theme_color(field, color);
+                    }
+                }
+                return task;
             }
             Message::ThemeCornerRadiusChanged(value) => {
                 self.theme_config.style.corner_radius = value;
@@ -505,26 +702,6 @@ impl Application for ConfigApp {
                 }
                 self.config.theme_config = Some(self.theme_config.clone());
             }
-            Message::GradientStartChanged(value) => {
-                self.gradient_start_input = value.clone();
-                if self.theme_config.gradient.is_none() {
-                    self.theme_config.gradient = Some(GradientConfig::default());
-                }
-                if let Some(ref mut gradient) = self.theme_config.gradient {
-                    gradient.start_color = value;
-                }
-                self.config.theme_config = Some(self.theme_config.clone());
-            }
-            Message::GradientEndChanged(value) => {
-                self.gradient_end_input = value.clone();
-                if self.theme_config.gradient.is_none() {
-                    self.theme_config.gradient = Some(GradientConfig::default());
-                }
-                if let Some(ref mut gradient) = self.theme_config.gradient {
-                    gradient.end_color = value;
-                }
-                self.config.theme_config = Some(self.theme_config.clone());
-            }
             Message::GradientAngleChanged(value) => {
                 if self.theme_config.gradient.is_none() {
                     self.theme_config.gradient = Some(GradientConfig::default());
@@ -542,21 +719,35 @@ impl Application for ConfigApp {
             Message::SoundsMasterVolumeChanged(volume) => {
                 self.config.sounds.volume = volume;
             }
+            Message::SoundThemeChanged(theme) => {
+                self.config.sounds.theme = theme;
+            }
             Message::AlarmSoundSelected(sound) => {
                 self.config.sounds.alarm.effect = sound;
             }
+            Message::AlarmSoundFileChanged(path) => {
+                // Clearing the path goes back to the built-in alarm
+                let path = path.trim();
+                self.config.sounds.alarm.effect = if path.is_empty() { "alarm".to_string() } else { path.to_string() };
+            }
             Message::AlarmVolumeChanged(volume) => {
                 self.config.sounds.alarm.volume = volume;
             }
             Message::AlarmRepeatChanged(value) => {
                 self.alarm_repeat_input = value.clone();
                 if let Ok(repeat) = value.parse::<u32>() {
-                    self.config.sounds.alarm.repeat = repeat.clamp(1, 10);
+                    if (1..=10).contains(&repeat) {
+                        self.config.sounds.alarm.repeat = repeat;
+                    }
                 }
             }
             Message::NotificationSoundSelected(sound) => {
                 self.config.sounds.notification.effect = sound;
             }
+            Message::NotificationSoundFileChanged(path) => {
+                let path = path.trim();
+                self.config.sounds.notification.effect = if path.is_empty() { "notification".to_string() } else { path.to_string() };
+            }
             Message::NotificationVolumeChanged(volume) => {
                 self.config.sounds.notification.volume = volume;
             }
@@ -574,11 +765,13 @@ impl Application for ConfigApp {
             Message::WidgetMoveUp(index) => {
                 if index > 0 && index < self.config.widgets.len() {
                     self.config.widgets.swap(index - 1, index);
+                    self.widget_field_inputs.clear();
                 }
             }
             Message::WidgetMoveDown(index) => {
                 if index < self.config.widgets.len() - 1 {
                     self.config.widgets.swap(index, index + 1);
+                    self.widget_field_inputs.clear();
                 }
             }
             Message::WidgetExpanded(index) => {
@@ -602,14 +795,18 @@ impl Application for ConfigApp {
                     if index < self.widget_margin_inputs.len() {
                         self.widget_margin_inputs.remove(index);
                     }
+                    self.widget_field_inputs.clear();
                     // Reset expanded state
                     self.expanded_widget = None;
+                    self.layout_drag = None;
                 }
             }
             Message::WidgetAdd(widget_type) => {
                 use cosmic_desktop_widget::WidgetInstance;
                 let new_widget = WidgetInstance::new(&widget_type);
                 self.config.widgets.push(new_widget);
+                // A second widget of a type needs an id of its own
+                self.config.assign_instance_ids();
                 // Add input state for new widget
                 self.widget_width_inputs.push("250".to_string());
                 self.widget_height_inputs.push("90".to_string());
@@ -691,12 +888,104 @@ impl Application for ConfigApp {
                     widget.opacity = Some(opacity);
                 }
             }
+            Message::WidgetAccentChanged(index, value) => {
+                if let Some(widget) = self.config.widgets.get_mut(index) {
+                    let value = value.trim();
+                    widget.accent = (!value.is_empty()).then(|| value.to_string());
+                }
+            }
+            Message::WidgetSoundChanged(index, value) => {
+                if let Some(widget) = self.config.widgets.get_mut(index) {
+                    let value = value.trim();
+                    widget.sound = (!value.is_empty()).then(|| value.to_string());
+                }
+            }
+            Message::WidgetFramelessToggled(index, frameless) => {
+                if let Some(widget) = self.config.widgets.get_mut(index) {
+                    widget.frameless = frameless;
+                }
+            }
+            Message::WidgetFieldChanged(index, key, value) => {
+                if let Some(widget) = self.config.widgets.get_mut(index) {
+                    match value {
+                        Some(value) => widget.config.insert(key.to_string(), value),
+                        None => widget.config.remove(key),
+                    };
+                }
+            }
+            Message::WidgetFieldInput(index, key, value) => {
+                let field = self.config.widgets.get(index)
+                    .and_then(|w| self.widget_registry.config_schema(&w.widget_type))
+                    .and_then(|schema| schema.field(key).cloned());
+                if let (Some(field), Some(widget)) = (field, self.config.widgets.get_mut(index)) {
+                    // Keep the last valid value in the config while the user is typing
+                    if value.trim().is_empty() {
+                        widget.config.remove(key);
+                    } else if let Some(parsed) = field.kind.parse_input(&value) {
+                        widget.config.insert(key.to_string(), parsed);
+                    }
+                }
+                self.widget_field_inputs.insert((index, key), value);
+            }
+
+            Message::WidgetOutputChanged(index, choice) => {
+                let output = match choice {
+                    0 => None,
+                    choice => self.output_choices.get(choice).cloned(),
+                };
+                if let Some(widget) = self.config.widgets.get_mut(index) {
+                    widget.output = output;
+                }
+            }
+
+            // Monitor layout
+            Message::LayoutRefresh => {
+                self.refresh_outputs();
+            }
+            Message::LayoutCursorMoved(point) => {
+                self.layout_cursor = point;
+            }
+            Message::LayoutPressed => {
+                if let Some(scale) = LayoutScale::fit(&self.outputs) {
+                    let cursor = self.layout_cursor;
+                    // Topmost box under the cursor; later widgets are drawn on top
+                    self.layout_drag = self
+                        .layout_widgets()
+                        .into_iter()
+                        .rev()
+                        .find_map(|(index, x, y, width, height)| {
+                            let (left, top) = scale.to_canvas(x, y);
+                            let (right, bottom) = (left + width as f32 * scale.scale, top + height as f32 * scale.scale);
+                            (cursor.x >= left && cursor.x < right && cursor.y >= top && cursor.y < bottom)
+                                .then(|| (index, cursor.x - left, cursor.y - top))
+                        });
+                }
+            }
+            Message::LayoutReleased => {
+                if let Some((index, grab_x, grab_y)) = self.layout_drag.take() {
+                    self.drop_widget(index, self.layout_cursor.x - grab_x, self.layout_cursor.y - grab_y);
+                }
+            }
+            Message::LayoutExited => {
+                self.layout_drag = None;
+            }
 
             // Actions
+            Message::Apply => {
+                return match toml::to_string(&self.config) {
+                    Ok(config) => send_config_request(IpcRequest::ApplyConfig { config }, Message::ConfigApplied),
+                    Err(e) => {
+                        self.save_error = Some(format!("Failed to apply: {}", e));
+                        Task::none()
+                    }
+                };
+            }
             Message::Save => {
                 match self.config.save() {
                     Ok(_) => {
+                        // The running widget picks the saved file up itself
                         self.original_config = self.config.clone();
+                        self.applied = false;
                         self.save_error = None;
                     }
                     Err(e) => {
@@ -706,18 +995,114 @@ impl Application for ConfigApp {
             }
             Message::Cancel => {
                 self.config = self.original_config.clone();
-                self.width_input = self.config.panel.width.to_string();
-                self.height_input = self.config.panel.height.to_string();
-                self.margin_top_input = self.config.panel.margin.top.to_string();
-                self.margin_right_input = self.config.panel.margin.right.to_string();
-                self.margin_bottom_input = self.config.panel.margin.bottom.to_string();
-                self.margin_left_input = self.config.panel.margin.left.to_string();
+                self.reload_inputs();
                 self.save_error = None;
+                // Put the saved config back on screen too
+                if self.applied {
+                    self.applied = false;
+                    return send_config_request(IpcRequest::RevertConfig, Message::ConfigReverted);
+                }
+            }
+
+            // Configuration import/export
+            Message::ConfigExport => {
+                let config = self.config.clone();
+                return cosmic::task::future(async move {
+                    let dialog = file_chooser::save::Dialog::new()
+                        .title("Export Configuration")
+                        .file_name("cosmic-desktop-widget.toml");
+                    let status = match dialog.save_file().await {
+                        Ok(response) => match response.url().and_then(|url| url.to_file_path().ok()) {
+                            Some(path) => match config.save_to(&path) {
+                                Ok(()) => format!("Exported configuration to {}", path.display()),
+                                Err(e) => format!("Export failed: {:#}", e),
+                            },
+                            None => "Export failed: only local files are supported".to_string(),
+                        },
+                        Err(file_chooser::Error::Cancelled) => return Message::ConfigFileStatus(None),
+                        Err(e) => format!("Export failed: {}", e),
+                    };
+                    Message::ConfigFileStatus(Some(status))
+                });
+            }
+            Message::ConfigImport => {
+                return cosmic::task::future(async move {
+                    let dialog = file_chooser::open::Dialog::new()
+                        .title("Import Configuration")
+                        .filter(FileFilter::new("TOML files").glob("*.toml"));
+                    match dialog.open_file().await {
+                        Ok(response) => match response.url().to_file_path() {
+                            Ok(path) => Message::ConfigImportLoaded(
+                                Config::import(&path)
+                                    .map(Box::new)
+                                    .map_err(|e| format!("{:#}", e)),
+                            ),
+                            Err(()) => Message::ConfigFileStatus(Some(
+                                "Import failed: only local files are supported".to_string(),
+                            )),
+                        },
+                        Err(file_chooser::Error::Cancelled) => Message::ConfigFileStatus(None),
+                        Err(e) => Message::ConfigFileStatus(Some(format!("Import failed: {}", e))),
+                    }
+                });
+            }
+            Message::ConfigImportLoaded(Ok(config)) => {
+                // Widget settings are checked against the widgets this build has
+                let invalid = config.widgets.iter().find_map(|w| {
+                    self.widget_registry
+                        .validate_config(&w.widget_type, &w.config)
+                        .err()
+                });
+                if let Some(e) = invalid {
+                    self.config_file_status = Some(format!("Import failed: {:#}", e));
+                } else {
+                    match self.original_config.diff(&config) {
+                        Ok(diff) => {
+                            self.pending_import = Some((*config, diff));
+                            self.config_file_status = None;
+                        }
+                        Err(e) => self.config_file_status = Some(format!("Import failed: {:#}", e)),
+                    }
+                }
+            }
+            Message::ConfigImportLoaded(Err(e)) => {
+                self.config_file_status = Some(format!("Import failed: {}", e));
+            }
+            Message::ConfigImportConfirmed => {
+                if let Some((config, _)) = self.pending_import.take() {
+                    self.config_file_status = Some(match config.save() {
+                        Ok(()) => {
+                            self.original_config = config.clone();
+                            self.config = config;
+                            self.reload_inputs();
+                            "Imported configuration".to_string()
+                        }
+                        Err(e) => format!("Import failed: {:#}", e),
+                    });
+                }
+            }
+            Message::ConfigImportDiscarded => {
+                self.pending_import = None;
+            }
+            Message::ConfigFileStatus(status) => {
+                self.config_file_status = status;
             }
 
             Message::ConfigSaved(_) => {
                 // Informational message
             }
+            Message::ConfigApplied(Ok(())) => {
+                self.applied = true;
+                self.save_error = None;
+            }
+            Message::ConfigApplied(Err(e)) => {
+                self.save_error = Some(format!("Failed to apply: {}", e));
+            }
+            Message::ConfigReverted(result) => {
+                if let Err(e) = result {
+                    self.save_error = Some(format!("Failed to restore the saved config: {}", e));
+                }
+            }
         }
 
         Task::none()
@@ -725,6 +1110,203 @@ impl Application for ConfigApp {
 }
 
 impl ConfigApp {
+    /// Fill the inputs from the working config, e.g. after it was replaced
+    fn reload_inputs(&mut self) {
+        let config = &self.config;
+        self.width_input = config.panel.width.to_string();
+        self.height_input = config.panel.height.to_string();
+        self.margin_top_input = config.panel.margin.top.to_string();
+        self.margin_right_input = config.panel.margin.right.to_string();
+        self.margin_bottom_input = config.panel.margin.bottom.to_string();
+        self.margin_left_input = config.panel.margin.left.to_string();
+        self.alarm_repeat_input = config.sounds.alarm.repeat.to_string();
+
+        // Initialize theme config from existing or default
+        self.theme_config = config.theme_config.clone().unwrap_or_default();
+        for field in ThemeColorField::COLORS
+            .into_iter()
+            .chain([ThemeColorField::GradientStart, ThemeColorField::GradientEnd])
+        {
+            let color = self.theme_color(field).to_string();
+            *self.color_input_mut(field) = color;
+        }
+        self.picking_color = None;
+
+        // Widget configuration inputs
+        let config = &self.config;
+        self.widget_width_inputs = config.widgets
+            .iter()
+            .map(|w| w.width.map(|v| v.to_string()).unwrap_or_else(|| "250".to_string()))
+            .collect();
+
+        self.widget_height_inputs = config.widgets
+            .iter()
+            .map(|w| w.height.map(|v| v.to_string()).unwrap_or_else(|| "90".to_string()))
+            .collect();
+
+        self.widget_margin_inputs = config.widgets
+            .iter()
+            .map(|w| {
+                (
+                    w.margin_top.map(|v| v.to_string()).unwrap_or_else(|| "10".to_string()),
+                    w.margin_right.map(|v| v.to_string()).unwrap_or_else(|| "20".to_string()),
+                    w.margin_bottom.map(|v| v.to_string()).unwrap_or_else(|| "0".to_string()),
+                    w.margin_left.map(|v| v.to_string()).unwrap_or_else(|| "0".to_string()),
+                )
+            })
+            .collect();
+        self.widget_field_inputs.clear();
+        self.expanded_widget = None;
+    }
+
+    /// Render the theme preview cards of the Appearance tab
+    ///
+    /// Each theme is resolved like the running widget would, so the
+    /// opacity and border overrides and the custom theme show up too.
+    fn render_theme_previews(&mut self) {
+        let mut renderer = Renderer::new();
+        let mut preview_config = self.config.clone();
+        let mut custom_theme = None;
+        self.theme_previews = self
+            .available_themes
+            .iter()
+            .filter_map(|name| {
+                preview_config.panel.theme = name.clone();
+                let theme = preview_config.get_theme();
+                if name == "custom" {
+                    custom_theme = Some(theme.clone());
+                }
+                let opacity = theme.opacity;
+                renderer.set_theme(theme);
+                let preview = render_thumbnail(&mut renderer, &self.widget_registry, "clock", opacity)?;
+                Some((name.clone(), preview))
+            })
+            .collect();
+        self.custom_preview_theme = custom_theme;
+    }
+
+    /// Re-render only the custom theme's preview card, if the theme editor
+    /// changed it since it was last rendered
+    fn refresh_custom_preview(&mut self) {
+        let mut preview_config = self.config.clone();
+        preview_config.panel.theme = "custom".to_string();
+        let theme = preview_config.get_theme();
+        if self.custom_preview_theme.as_ref() == Some(&theme) {
+            return;
+        }
+
+        let opacity = theme.opacity;
+        let mut renderer = Renderer::with_theme(theme.clone());
+        let registry = &self.widget_registry;
+        if let Some(preview) = render_thumbnail(&mut renderer, registry, "clock", opacity) {
+            self.theme_previews.insert("custom".to_string(), preview);
+        }
+        self.custom_preview_theme = Some(theme);
+    }
+
+    /// Ask the compositor which outputs are connected, for the monitor layout
+    fn refresh_outputs(&mut self) {
+        match outputs::list_outputs() {
+            Ok(outputs) => {
+                self.outputs = outputs;
+                self.outputs_error = None;
+            }
+            Err(e) => {
+                self.outputs = Vec::new();
+                self.outputs_error = Some(format!("Couldn't list the monitors: {}", e));
+            }
+        }
+        self.output_choices = std::iter::once("Any output".to_string())
+            .chain(self.outputs.iter().map(|output| output.name.clone()))
+            .collect();
+        self.layout_drag = None;
+    }
+
+    /// Where each enabled widget shows up on the outputs, as
+    /// (index, x, y, width, height) in global coordinates
+    ///
+    /// Widgets that aren't pinned, or are pinned to an output that isn't
+    /// connected, are shown on the first output.
+    fn layout_widgets(&self) -> Vec<(usize, i32, i32, u32, u32)> {
+        let panel = &self.config.panel;
+        self.config
+            .widgets
+            .iter()
+            .enumerate()
+            .filter(|(_, widget)| widget.enabled)
+            .filter_map(|(index, widget)| {
+                let output = widget
+                    .output
+                    .as_deref()
+                    .and_then(|name| self.outputs.iter().find(|o| o.name == name))
+                    .or_else(|| self.outputs.first())?;
+                let (position, margin) = widget.absolute_placement().unwrap_or_else(|| {
+                    (
+                        widget.effective_position(&panel.position),
+                        widget.effective_margin(&panel.margin),
+                    )
+                });
+                let width = widget.effective_width(panel.width);
+                let height = widget.effective_height(panel.height);
+                let (x, y) = output.place(position, &margin, width, height);
+                Some((index, x, y, width, height))
+            })
+            .collect()
+    }
+
+    /// Pin the widget at `index` to the output it was dropped on, anchored
+    /// to the nearest corner or edge
+    ///
+    /// `x`,`y` is where its top-left corner was dropped on the layout.
+    fn drop_widget(&mut self, index: usize, x: f32, y: f32) {
+        let Some(scale) = LayoutScale::fit(&self.outputs) else {
+            return;
+        };
+        let Some(widget) = self.config.widgets.get(index) else {
+            return;
+        };
+        let width = widget.effective_width(self.config.panel.width);
+        let height = widget.effective_height(self.config.panel.height);
+        let (x, y) = scale.to_global(x, y);
+
+        // The output under the widget's center, or else the closest one
+        let (center_x, center_y) = (x + width as i32 / 2, y + height as i32 / 2);
+        let distance = |output: &&OutputGeometry| {
+            let dx = (output.x + output.width as i32 / 2 - center_x) as i64;
+            let dy = (output.y + output.height as i32 / 2 - center_y) as i64;
+            dx * dx + dy * dy
+        };
+        let Some(output) = self
+            .outputs
+            .iter()
+            .find(|output| output.contains(center_x, center_y))
+            .or_else(|| self.outputs.iter().min_by_key(distance))
+        else {
+            return;
+        };
+        let (position, margin) = output.anchor_at(x, y, width, height);
+        let output = output.name.clone();
+
+        if let Some(widget) = self.config.widgets.get_mut(index) {
+            widget.output = Some(output);
+            widget.position = Some(position.as_str().to_string());
+            widget.x = None;
+            widget.y = None;
+            widget.margin_top = Some(margin.top);
+            widget.margin_right = Some(margin.right);
+            widget.margin_bottom = Some(margin.bottom);
+            widget.margin_left = Some(margin.left);
+        }
+        if let Some(inputs) = self.widget_margin_inputs.get_mut(index) {
+            *inputs = (
+                margin.top.to_string(),
+                margin.right.to_string(),
+                margin.bottom.to_string(),
+                margin.left.to_string(),
+            );
+        }
+    }
+
     /// View for General settings tab - follows COSMIC settings patterns
     fn view_general(&self) -> Element<Message> {
         let spacing = theme::active().cosmic().spacing;
@@ -732,24 +1314,24 @@ impl ConfigApp {
         // Size section with description
         let size_section = settings::section()
             .title("Panel Size")
-            .add(
+            .add(with_error(
                 settings::item(
                     "Width",
                     text_input("450", &self.width_input)
                         .on_input(Message::WidthChanged)
                         .width(Length::Fixed(80.0)),
-                )
-
-            )
-            .add(
+                ),
+                size_error(&self.width_input),
+            ))
+            .add(with_error(
                 settings::item(
                     "Height",
                     text_input("180", &self.height_input)
                         .on_input(Message::HeightChanged)
                         .width(Length::Fixed(80.0)),
-                )
-
-            );
+                ),
+                size_error(&self.height_input),
+            ));
 
         // Position section with visual grid
         let position_section = settings::section()
@@ -765,42 +1347,42 @@ impl ConfigApp {
         // Margins section with descriptions
         let margin_section = settings::section()
             .title("Screen Margins")
-            .add(
+            .add(with_error(
                 settings::item(
                     "Top",
                     text_input("10", &self.margin_top_input)
                         .on_input(Message::MarginTopChanged)
                         .width(Length::Fixed(80.0)),
-                )
-
-            )
-            .add(
+                ),
+                margin_error(&self.margin_top_input),
+            ))
+            .add(with_error(
                 settings::item(
                     "Right",
                     text_input("20", &self.margin_right_input)
                         .on_input(Message::MarginRightChanged)
                         .width(Length::Fixed(80.0)),
-                )
-
-            )
-            .add(
+                ),
+                margin_error(&self.margin_right_input),
+            ))
+            .add(with_error(
                 settings::item(
                     "Bottom",
                     text_input("0", &self.margin_bottom_input)
                         .on_input(Message::MarginBottomChanged)
                         .width(Length::Fixed(80.0)),
-                )
-
-            )
-            .add(
+                ),
+                margin_error(&self.margin_bottom_input),
+            ))
+            .add(with_error(
                 settings::item(
                     "Left",
                     text_input("0", &self.margin_left_input)
                         .on_input(Message::MarginLeftChanged)
                         .width(Length::Fixed(80.0)),
-                )
-
-            );
+                ),
+                margin_error(&self.margin_left_input),
+            ));
 
         // Layout section with sliders
         let layout_section = settings::section()
@@ -836,13 +1418,35 @@ impl ConfigApp {
 
             );
 
+        let mut file_section = settings::section()
+            .title("Configuration File")
+            .add(
+                settings::item_row(vec![
+                    button::standard("Import Config…")
+                        .on_press_maybe(self.pending_import.is_none().then_some(Message::ConfigImport))
+                        .into(),
+                    button::standard("Export Config…")
+                        .on_press(Message::ConfigExport)
+                        .into(),
+                ]),
+            );
+        if let Some(status) = &self.config_file_status {
+            file_section = file_section
+                .add(settings::item_row(vec![text::caption(status.clone()).into()]));
+        }
+
         // Use settings::view_column for proper COSMIC layout
-        let content = settings::view_column(vec![
+        let mut sections = vec![
             size_section.into(),
             position_section.into(),
             margin_section.into(),
             layout_section.into(),
-        ])
+            file_section.into(),
+        ];
+        if let Some((_, diff)) = &self.pending_import {
+            sections.push(self.view_import_diff(diff));
+        }
+        let content = settings::view_column(sections)
         .padding(spacing.space_m);
 
         container(widget::scrollable(content))
@@ -851,37 +1455,76 @@ impl ConfigApp {
             .into()
     }
 
+    /// Changes an import would make, to confirm before they replace the config
+    fn view_import_diff(&self, diff: &[DiffLine]) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        // Changed lines, each under the [section] it belongs to
+        let mut changes = column::with_capacity(diff.len());
+        let mut table = None;
+        for line in diff {
+            let (sign, line_text) = match line {
+                DiffLine::Same(line_text) => {
+                    if line_text.starts_with('[') {
+                        table = Some(line_text);
+                    }
+                    continue;
+                }
+                DiffLine::Added(line_text) => ('+', line_text),
+                DiffLine::Removed(line_text) => ('-', line_text),
+            };
+            if let Some(table) = table.take() {
+                changes = changes.push(text::monotext(format!("  {}", table)));
+            }
+            changes = changes.push(text::monotext(format!("{} {}", sign, line_text)));
+        }
+
+        let summary = if diff.iter().any(DiffLine::is_change) {
+            "Importing replaces the saved configuration with these changes. Unsaved changes are lost."
+        } else {
+            "The imported configuration is the same as the saved one."
+        };
+
+        settings::section()
+            .title("Replace Configuration?")
+            .add(settings::item_row(vec![text::body(summary).into()]))
+            .add(
+                container(widget::scrollable(changes.padding(spacing.space_xs)))
+                    .max_height(240.0)
+                    .width(Length::Fill),
+            )
+            .add(
+                settings::item_row(vec![
+                    button::standard("Keep Current")
+                        .on_press(Message::ConfigImportDiscarded)
+                        .into(),
+                    button::destructive("Replace")
+                        .on_press(Message::ConfigImportConfirmed)
+                        .into(),
+                ]),
+            )
+            .into()
+    }
+
     /// View for Appearance settings tab - follows COSMIC patterns
     fn view_appearance(&self) -> Element<Message> {
         let spacing = theme::active().cosmic().spacing;
 
-        let current_theme_idx = self.available_themes
+        // A preview card per theme; clicking one selects it
+        let cards: Vec<Element<'_, Message>> = self.available_themes
             .iter()
-            .position(|t| t == &self.config.panel.theme);
+            .map(|name| self.view_theme_card(name))
+            .collect();
 
         let theme_section = settings::section()
             .title("Theme")
             .add(
-                settings::item(
-                    "Color Scheme",
-                    dropdown(
-                        &self.available_themes,
-                        current_theme_idx,
-                        move |idx| {
-                            let themes = vec![
-                                "cosmic_dark".to_string(),
-                                "light".to_string(),
-                                "transparent_dark".to_string(),
-                                "transparent_light".to_string(),
-                                "glass".to_string(),
-                                "custom".to_string(),
-                            ];
-                            Message::ThemeSelected(themes.get(idx).cloned().unwrap_or_default())
-                        },
-                    )
-                    .width(Length::Fixed(200.0)),
-                )
-
+                widget::flex_row(cards)
+                    .row_spacing(spacing.space_s)
+                    .column_spacing(spacing.space_s)
+                    .apply(container)
+                    .padding([spacing.space_xxs, 0])
+                    .width(Length::Fill),
             );
 
         let opacity = self.config.panel.background_opacity.unwrap_or(0.9);
@@ -903,6 +1546,32 @@ impl ConfigApp {
 
             );
 
+        let has_path = !self.theme_file_input.trim().is_empty();
+        let mut files_section = settings::section()
+            .title("Theme Files")
+            .add(
+                settings::item(
+                    "File",
+                    text_input("~/Downloads/my-theme.toml", &self.theme_file_input)
+                        .on_input(Message::ThemeFileInputChanged)
+                        .width(Length::Fixed(260.0)),
+                )
+            )
+            .add(
+                settings::item_row(vec![
+                    button::standard("Import")
+                        .on_press_maybe(has_path.then_some(Message::ThemeImport))
+                        .into(),
+                    button::standard("Export Current Theme")
+                        .on_press_maybe(has_path.then_some(Message::ThemeExport))
+                        .into(),
+                ]),
+            );
+        if let Some(status) = &self.theme_file_status {
+            files_section = files_section
+                .add(settings::item_row(vec![text::caption(status.clone()).into()]));
+        }
+
         let hint_section = settings::section()
             .title("Advanced")
             .add(
@@ -914,6 +1583,7 @@ impl ConfigApp {
         let content = settings::view_column(vec![
             theme_section.into(),
             opacity_section.into(),
+            files_section.into(),
             hint_section.into(),
         ])
         .padding(spacing.space_m);
@@ -924,58 +1594,47 @@ impl ConfigApp {
             .into()
     }
 
-    /// View for Theme editor tab - follows COSMIC patterns
-    fn view_theme(&self) -> Element<Message> {
+    /// A clickable preview of the theme `name`, highlighted when it's selected
+    fn view_theme_card(&self, name: &str) -> Element<'_, Message> {
         let spacing = theme::active().cosmic().spacing;
 
-        // Colors section with hex input fields
-        let colors_section = settings::section()
-            .title("Custom Colors")
-            .add(
-                settings::item(
-                    "Background",
-                    text_input("#1e1e2e", &self.theme_background_input)
-                        .on_input(Message::ThemeBackgroundChanged)
-                        .width(Length::Fixed(100.0)),
-                )
-
-            )
-            .add(
-                settings::item(
-                    "Primary Text",
-                    text_input("#cdd6f4", &self.theme_text_primary_input)
-                        .on_input(Message::ThemeTextPrimaryChanged)
-                        .width(Length::Fixed(100.0)),
-                )
-
-            )
-            .add(
-                settings::item(
-                    "Secondary Text",
-                    text_input("#a6adc8", &self.theme_text_secondary_input)
-                        .on_input(Message::ThemeTextSecondaryChanged)
-                        .width(Length::Fixed(100.0)),
-                )
+        // Themes that failed to render still get a card to click
+        let preview: Element<'_, Message> = match self.theme_previews.get(name) {
+            Some(preview) => widget::image(preview.clone())
+                .width(Length::Fixed(THUMBNAIL_WIDTH as f32))
+                .height(Length::Fixed(THUMBNAIL_HEIGHT as f32))
+                .into(),
+            None => container(icon::from_name("preferences-desktop-theme-symbolic").size(48).icon())
+                .width(Length::Fixed(THUMBNAIL_WIDTH as f32))
+                .height(Length::Fixed(THUMBNAIL_HEIGHT as f32))
+                .align_x(Horizontal::Center)
+                .align_y(Vertical::Center)
+                .into(),
+        };
 
-            )
-            .add(
-                settings::item(
-                    "Accent",
-                    text_input("#89b4fa", &self.theme_accent_input)
-                        .on_input(Message::ThemeAccentChanged)
-                        .width(Length::Fixed(100.0)),
-                )
+        let content = column::with_capacity(2)
+            .push(preview)
+            .push(text::body(theme_label(name)))
+            .spacing(spacing.space_xxs)
+            .padding(spacing.space_xxs)
+            .align_x(Alignment::Center);
+
+        button::custom(content)
+            .on_press(Message::ThemeSelected(name.to_string()))
+            .class(theme::Button::Image)
+            .selected(self.config.panel.theme == name)
+            .into()
+    }
 
-            )
-            .add(
-                settings::item(
-                    "Border",
-                    text_input("#45475a", &self.theme_border_input)
-                        .on_input(Message::ThemeBorderChanged)
-                        .width(Length::Fixed(100.0)),
-                )
+    /// View for Theme editor tab - follows COSMIC patterns
+    fn view_theme(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
 
-            );
+        // Colors section with a picker, hex input and opacity per color
+        let mut colors_section = settings::section().title("Custom Colors");
+        for field in ThemeColorField::COLORS {
+            colors_section = colors_section.add(self.view_color_field(field));
+        }
 
         // Style section with sliders and toggles
         let style_section = settings::section()
@@ -1033,24 +1692,8 @@ impl ConfigApp {
                 )
 
             )
-            .add(
-                settings::item(
-                    "Start Color",
-                    text_input("#1e1e2e", &self.gradient_start_input)
-                        .on_input(Message::GradientStartChanged)
-                        .width(Length::Fixed(100.0)),
-                )
-
-            )
-            .add(
-                settings::item(
-                    "End Color",
-                    text_input("#313244", &self.gradient_end_input)
-                        .on_input(Message::GradientEndChanged)
-                        .width(Length::Fixed(100.0)),
-                )
-
-            )
+            .add(self.view_color_field(ThemeColorField::GradientStart))
+            .add(self.view_color_field(ThemeColorField::GradientEnd))
             .add(
                 settings::item(
                     "Angle",
@@ -1090,6 +1733,147 @@ impl ConfigApp {
             .into()
     }
 
+    /// A theme color: swatch opening the color picker, hex input and opacity
+    fn view_color_field(&self, field: ThemeColorField) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let input = self.color_input(field);
+        let color = WidgetColor::from_hex(self.theme_color(field));
+        let opacity = color.map_or(100.0, |c| (c.a as f32 / 2.55).round());
+
+        let controls = row::with_capacity(4)
+            .push(
+                color_picker::color_button(
+                    Some(Message::ThemeColorPickerToggled(field)),
+                    color.map(iced_color),
+                    Length::Fixed(24.0),
+                )
+                .width(Length::Fixed(32.0))
+                .height(Length::Fixed(32.0)),
+            )
+            .push(
+                text_input(field.placeholder(), input)
+                    .on_input(move |value| Message::ThemeColorInput(field, value))
+                    .width(Length::Fixed(110.0)),
+            )
+            .push(
+                slider(0.0..=100.0, opacity, move |value| {
+                    Message::ThemeColorAlphaChanged(field, value)
+                })
+                .width(Length::Fixed(100.0))
+                .step(1.0),
+            )
+            .push(text::body(format!("{:.0}%", opacity)))
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center);
+
+        let mut content = column::with_capacity(3)
+            .push(settings::item(field.label(), controls))
+            .spacing(spacing.space_xxs);
+
+        if WidgetColor::from_hex(input).is_none() {
+            content = content.push(
+                text::caption("Use #rrggbb, or #rrggbbaa to include opacity")
+                    .apply(container)
+                    .padding([0, spacing.space_s]),
+            );
+        }
+
+        if self.picking_color == Some(field) {
+            content = content.push(
+                column::with_capacity(2)
+                    .push(
+                        self.color_picker
+                            .builder(Message::ThemeColorPicker)
+                            .width(Length::Fixed(254.0))
+                            .height(Length::Fixed(158.0))
+                            .build("Recent Colors", "Copy to clipboard", "Copied to clipboard"),
+                    )
+                    .push(button::standard("Done").on_press(Message::ThemeColorPickerToggled(field)))
+                    .spacing(spacing.space_s)
+                    .align_x(Alignment::End)
+                    .padding([0, spacing.space_s]),
+            );
+        }
+
+        content.into()
+    }
+
+    /// Color currently set in the theme for `field`
+    fn theme_color(&self, field: ThemeColorField) -> &str {
+        let colors = &self.theme_config.colors;
+        let gradient = self.theme_config.gradient.as_ref();
+        match field {
+            ThemeColorField::Background => &colors.background,
+            ThemeColorField::TextPrimary => &colors.text_primary,
+            ThemeColorField::TextSecondary => &colors.text_secondary,
+            ThemeColorField::Accent => &colors.accent,
+            ThemeColorField::Border => &colors.border,
+            ThemeColorField::GradientStart => gradient
+                .map(|g| g.start_color.as_str())
+                .unwrap_or(field.placeholder()),
+            ThemeColorField::GradientEnd => gradient
+                .map(|g| g.end_color.as_str())
+                .unwrap_or(field.placeholder()),
+        }
+    }
+
+    /// Text of the hex input for `field`, as typed
+    fn color_input(&self, field: ThemeColorField) -> &str {
+        match field {
+            ThemeColorField::Background => &self.theme_background_input,
+            ThemeColorField::TextPrimary => &self.theme_text_primary_input,
+            ThemeColorField::TextSecondary => &self.theme_text_secondary_input,
+            ThemeColorField::Accent => &self.theme_accent_input,
+            ThemeColorField::Border => &self.theme_border_input,
+            ThemeColorField::GradientStart => &self.gradient_start_input,
+            ThemeColorField::GradientEnd => &self.gradient_end_input,
+        }
+    }
+
+    fn color_input_mut(&mut self, field: ThemeColorField) -> &mut String {
+        match field {
+            ThemeColorField::Background => &mut self.theme_background_input,
+            ThemeColorField::TextPrimary => &mut self.theme_text_primary_input,
+            ThemeColorField::TextSecondary => &mut self.theme_text_secondary_input,
+            ThemeColorField::Accent => &mut self.theme_accent_input,
+            ThemeColorField::Border => &mut self.theme_border_input,
+            ThemeColorField::GradientStart => &mut self.gradient_start_input,
+            ThemeColorField::GradientEnd => &mut self.gradient_end_input,
+        }
+    }
+
+    /// Write `hex` to the working theme config
+    fn set_theme_color(&mut self, field: ThemeColorField, hex: String) {
+        let colors = &mut self.theme_config.colors;
+        match field {
+            ThemeColorField::Background => colors.background = hex,
+            ThemeColorField::TextPrimary => colors.text_primary = hex,
+            ThemeColorField::TextSecondary => colors.text_secondary = hex,
+            ThemeColorField::Accent => colors.accent = hex,
+            ThemeColorField::Border => colors.border = hex,
+            ThemeColorField::GradientStart => {
+                self.theme_config
+                    .gradient
+                    .get_or_insert_with(GradientConfig::default)
+                    .start_color = hex;
+            }
+            ThemeColorField::GradientEnd => {
+                self.theme_config
+                    .gradient
+                    .get_or_insert_with(GradientConfig::default)
+                    .end_color = hex;
+            }
+        }
+        self.config.theme_config = Some(self.theme_config.clone());
+    }
+
+    /// Set a color chosen with the picker or opacity slider, updating its input
+    fn pick_theme_color(&mut self, field: ThemeColorField, color: WidgetColor) {
+        let hex = color_hex(color);
+        *self.color_input_mut(field) = hex.clone();
+        self.set_theme_color(field, hex);
+    }
+
     /// View for Sounds settings tab - follows COSMIC patterns
     fn view_sounds(&self) -> Element<Message> {
         let spacing = theme::active().cosmic().spacing;
@@ -1119,6 +1903,15 @@ impl ConfigApp {
                         .align_y(Alignment::Center),
                 )
 
+            )
+            .add(
+                settings::item(
+                    "Sound Theme",
+                    text_input("freedesktop", &self.config.sounds.theme)
+                        .on_input(Message::SoundThemeChanged)
+                        .width(Length::Fixed(200.0)),
+                )
+
             );
 
         // Alarm sound settings
@@ -1156,6 +1949,15 @@ impl ConfigApp {
                         .align_y(Alignment::Center),
                 )
 
+            )
+            .add(
+                settings::item(
+                    "Custom File",
+                    text_input("~/sounds/alarm.ogg", custom_sound_path(&self.config.sounds.alarm.effect))
+                        .on_input(Message::AlarmSoundFileChanged)
+                        .width(Length::Fixed(250.0)),
+                )
+
             )
             .add(
                 settings::item(
@@ -1172,15 +1974,15 @@ impl ConfigApp {
                 )
 
             )
-            .add(
+            .add(with_error(
                 settings::item(
                     "Repeat Count",
                     text_input("3", &self.alarm_repeat_input)
                         .on_input(Message::AlarmRepeatChanged)
                         .width(Length::Fixed(60.0)),
-                )
-
-            );
+                ),
+                repeat_error(&self.alarm_repeat_input),
+            ));
 
         // Notification sound settings
         let notification_sound_idx = self.available_sounds
@@ -1217,6 +2019,15 @@ impl ConfigApp {
                         .align_y(Alignment::Center),
                 )
 
+            )
+            .add(
+                settings::item(
+                    "Custom File",
+                    text_input("~/sounds/notification.ogg", custom_sound_path(&self.config.sounds.notification.effect))
+                        .on_input(Message::NotificationSoundFileChanged)
+                        .width(Length::Fixed(250.0)),
+                )
+
             )
             .add(
                 settings::item(
@@ -1269,42 +2080,22 @@ impl ConfigApp {
             .filter(|t| !existing_types.contains(&t.as_str()))
             .collect();
 
-        // Add widget section (only show if there are widgets left to add)
+        // Gallery of widget types to add (only show if there are widgets left to add)
         let add_widget_section = if !available_to_add.is_empty() {
-            let add_buttons: Vec<Element<'_, Message>> = available_to_add.iter().map(|widget_type| {
-                let display_name = widget_type
-                    .chars()
-                    .next()
-                    .map(|c| c.to_uppercase().collect::<String>() + &widget_type[1..])
-                    .unwrap_or_else(|| (*widget_type).clone())
-                    .replace('_', " ");
-
-                let wt = (*widget_type).clone();
-                button::standard(format!("+ {}", display_name))
-                    .on_press(Message::WidgetAdd(wt))
-                    .into()
-            }).collect();
-
-            // Create a flex row that wraps
-            let mut button_row = row::with_capacity(add_buttons.len())
-                .spacing(spacing.space_xs);
-            for btn in add_buttons {
-                button_row = button_row.push(btn);
-            }
+            let cards: Vec<Element<'_, Message>> = available_to_add
+                .iter()
+                .map(|widget_type| self.view_gallery_card(widget_type))
+                .collect();
 
             settings::section()
                 .title("Add Widget")
                 .add(
-                    settings::item_row(vec![
-                        text::body("Click to add a new widget type:").into(),
-                    ])
-                )
-                .add(
-                    settings::item_row(vec![
-                        container(
-                            button_row.padding([spacing.space_xxs, 0])
-                        ).width(Length::Fill).into(),
-                    ])
+                    widget::flex_row(cards)
+                        .row_spacing(spacing.space_s)
+                        .column_spacing(spacing.space_s)
+                        .apply(container)
+                        .padding([spacing.space_xxs, 0])
+                        .width(Length::Fill),
                 )
         } else {
             settings::section()
@@ -1329,13 +2120,7 @@ impl ConfigApp {
         }
 
         for (index, widget_instance) in self.config.widgets.iter().enumerate() {
-            // Capitalize widget type for display
-            let display_name = widget_instance.widget_type
-                .chars()
-                .next()
-                .map(|c| c.to_uppercase().collect::<String>() + &widget_instance.widget_type[1..])
-                .unwrap_or_else(|| widget_instance.widget_type.clone())
-                .replace('_', " ");
+            let display_name = self.widget_metadata(&widget_instance.widget_type).name;
 
             let is_expanded = self.expanded_widget == Some(index);
             let expand_icon = if is_expanded {
@@ -1345,13 +2130,17 @@ impl ConfigApp {
             };
 
             // Widget header row with expand/collapse button
-            let header_row = row::with_capacity(7)
+            let mut header_row = row::with_capacity(8)
                 .push(
                     button::icon(icon::from_name(expand_icon))
                         .on_press(Message::WidgetExpanded(index))
                         .padding([spacing.space_xxs, spacing.space_xs])
                 )
-                .push(text::body(display_name))
+                .push(text::body(display_name));
+            if self.widget_has_errors(index) {
+                header_row = header_row.push(text::caption("Invalid settings"));
+            }
+            let header_row = header_row
                 .push(horizontal_space())
                 .push(
                     toggler(widget_instance.enabled)
@@ -1420,6 +2209,7 @@ impl ConfigApp {
 
         let content = settings::view_column(vec![
             add_widget_section.into(),
+            self.view_monitor_layout(),
             widgets_section.into(),
             help_section.into(),
         ])
@@ -1431,6 +2221,160 @@ impl ConfigApp {
             .into()
     }
 
+    /// The outputs as they're arranged, with a box for each enabled widget
+    /// that can be dragged to another monitor, corner or edge
+    fn view_monitor_layout(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let refresh_row = row::with_capacity(2)
+            .push(horizontal_space())
+            .push(button::standard("Refresh").on_press(Message::LayoutRefresh));
+
+        let Some(scale) = LayoutScale::fit(&self.outputs) else {
+            let message = self
+                .outputs_error
+                .as_deref()
+                .unwrap_or("No monitors found.");
+            return settings::section()
+                .title("Monitor Layout")
+                .add(settings::item_row(vec![text::body(message).into()]))
+                .add(refresh_row)
+                .into();
+        };
+
+        let mut layers: Vec<Element<'_, Message>> = Vec::new();
+        layers.push(
+            vertical_space()
+                .width(Length::Fixed(LAYOUT_WIDTH))
+                .height(Length::Fixed(LAYOUT_HEIGHT))
+                .into(),
+        );
+
+        for output in &self.outputs {
+            let (x, y) = scale.to_canvas(output.x, output.y);
+            let monitor = container(text::caption(output.name.as_str()))
+                .padding(spacing.space_xxs)
+                .width(Length::Fixed(output.width as f32 * scale.scale))
+                .height(Length::Fixed(output.height as f32 * scale.scale))
+                .class(theme::Container::Card);
+            layers.push(layout_placed(monitor, x, y));
+        }
+
+        for (index, x, y, width, height) in self.layout_widgets() {
+            let (x, y) = match self.layout_drag {
+                Some((dragged, grab_x, grab_y)) if dragged == index => {
+                    (self.layout_cursor.x - grab_x, self.layout_cursor.y - grab_y)
+                }
+                _ => scale.to_canvas(x, y),
+            };
+            let name = self.widget_metadata(&self.config.widgets[index].widget_type).name;
+            let widget_box = container(text::caption(name))
+                .padding([0, spacing.space_xxxs])
+                .width(Length::Fixed((width as f32 * scale.scale).max(6.0)))
+                .height(Length::Fixed((height as f32 * scale.scale).max(6.0)))
+                .clip(true)
+                .class(theme::Container::custom(|theme| {
+                    let cosmic = theme.cosmic();
+                    container::Style {
+                        text_color: Some(cosmic.on_accent_color().into()),
+                        background: Some(cosmic::iced::Color::from(cosmic.accent_color()).into()),
+                        border: cosmic::iced::Border {
+                            radius: cosmic.corner_radii.radius_xs.into(),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    }
+                }));
+            layers.push(layout_placed(widget_box, x, y));
+        }
+
+        let canvas = cosmic::iced::widget::mouse_area(
+            cosmic::iced::widget::Stack::with_children(layers)
+                .width(Length::Fixed(LAYOUT_WIDTH))
+                .height(Length::Fixed(LAYOUT_HEIGHT)),
+        )
+        .on_move(Message::LayoutCursorMoved)
+        .on_press(Message::LayoutPressed)
+        .on_release(Message::LayoutReleased)
+        .on_exit(Message::LayoutExited);
+
+        settings::section()
+            .title("Monitor Layout")
+            .add(
+                container(canvas)
+                    .center_x(Length::Fill)
+                    .padding([spacing.space_xxs, 0]),
+            )
+            .add(
+                settings::item_row(vec![
+                    text::caption("Drag a widget onto a monitor. It snaps to the nearest corner or edge, and its output, position and margins are set from where it's dropped.").into(),
+                ])
+            )
+            .add(refresh_row)
+            .into()
+    }
+
+    /// Name, description and icon of a widget type
+    fn widget_metadata(&self, widget_type: &str) -> WidgetMetadata {
+        self.widget_registry
+            .metadata(widget_type)
+            .unwrap_or_else(|| WidgetMetadata::for_type(widget_type))
+    }
+
+    /// A gallery card for a widget type: example, description, requirements and an Add button
+    fn view_gallery_card(&self, widget_type: &str) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let metadata = self.widget_metadata(widget_type);
+
+        // Widgets without an example rendering show their icon instead
+        let preview: Element<'_, Message> = match self.widget_thumbnails.get(widget_type) {
+            Some(thumbnail) => widget::image(thumbnail.clone())
+                .width(Length::Fixed(THUMBNAIL_WIDTH as f32))
+                .height(Length::Fixed(THUMBNAIL_HEIGHT as f32))
+                .into(),
+            None => container(icon::from_name(metadata.icon.as_str()).size(48).icon())
+                .width(Length::Fixed(THUMBNAIL_WIDTH as f32))
+                .height(Length::Fixed(THUMBNAIL_HEIGHT as f32))
+                .align_x(Horizontal::Center)
+                .align_y(Vertical::Center)
+                .into(),
+        };
+
+        let mut card = column::with_capacity(5)
+            .push(preview)
+            .push(text::heading(metadata.name))
+            .push(text::caption(metadata.description).width(Length::Fill));
+
+        // What the widget relies on; being clickable isn't a requirement
+        let mut requirements: Vec<&str> = self
+            .widget_registry
+            .capabilities(widget_type)
+            .map(|capabilities| capabilities.labels())
+            .unwrap_or_default();
+        requirements.retain(|label| *label != "Interactive");
+        if self
+            .widget_registry
+            .config_schema(widget_type)
+            .is_some_and(|schema| schema.needs_api_key())
+        {
+            requirements.push("API key");
+        }
+        if !requirements.is_empty() {
+            card = card.push(text::caption(format!("Needs: {}", requirements.join(", "))));
+        }
+
+        card.push(
+            button::standard("Add")
+                .on_press(Message::WidgetAdd(widget_type.to_string())),
+        )
+        .spacing(spacing.space_xxs)
+        .width(Length::Fixed(THUMBNAIL_WIDTH as f32 + 2.0 * spacing.space_xs as f32))
+        .padding(spacing.space_xs)
+        .apply(container)
+        .class(theme::Container::Card)
+        .into()
+    }
+
     /// View for per-widget configuration - follows COSMIC patterns
     fn view_widget_config(&self, index: usize, widget: &cosmic_desktop_widget::WidgetInstance) -> Element<'_, Message> {
         let spacing = theme::active().cosmic().spacing;
@@ -1462,6 +2406,24 @@ impl ConfigApp {
             .spacing(spacing.space_s)
             .align_y(Alignment::Center);
 
+        // Output dropdown: "Any output" or a connected output
+        let output_idx = match &widget.output {
+            None => Some(0),
+            Some(name) => self.output_choices.iter().skip(1).position(|o| o == name).map(|i| i + 1),
+        };
+        let output_row = row::with_capacity(2)
+            .push(text::body("Output:").width(Length::Fixed(100.0)))
+            .push(
+                dropdown(
+                    &self.output_choices,
+                    output_idx,
+                    move |idx| Message::WidgetOutputChanged(index, idx),
+                )
+                .width(Length::Fixed(150.0))
+            )
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center);
+
         // Size inputs
         let width_input = if let Some(input) = self.widget_width_inputs.get(index) {
             input.as_str()
@@ -1475,6 +2437,7 @@ impl ConfigApp {
             "90"
         };
 
+        let size_message = size_error(width_input).or_else(|| size_error(height_input));
         let size_row = row::with_capacity(5)
             .push(text::body("Size:").width(Length::Fixed(100.0)))
             .push(text::body("Width"))
@@ -1500,6 +2463,9 @@ impl ConfigApp {
                 ("10", "20", "0", "0")
             };
 
+        let margin_message = [margin_top, margin_right, margin_bottom, margin_left]
+            .into_iter()
+            .find_map(margin_error);
         let margin_row = row::with_capacity(9)
             .push(text::body("Margins:").width(Length::Fixed(100.0)))
             .push(text::body("T"))
@@ -1542,15 +2508,199 @@ impl ConfigApp {
             .spacing(spacing.space_s)
             .align_y(Alignment::Center);
 
+        // Accent override (hex or "@token")
+        let accent_row = row::with_capacity(2)
+            .push(text::body("Accent:").width(Length::Fixed(100.0)))
+            .push(
+                text_input("Theme accent", widget.accent.as_deref().unwrap_or(""))
+                    .on_input(move |value| Message::WidgetAccentChanged(index, value))
+                    .width(Length::Fixed(200.0))
+            )
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center);
+
+        // Sound override (built-in name or file path)
+        let sound_row = row::with_capacity(2)
+            .push(text::body("Sound:").width(Length::Fixed(100.0)))
+            .push(
+                text_input("Default sound", widget.sound.as_deref().unwrap_or(""))
+                    .on_input(move |value| Message::WidgetSoundChanged(index, value))
+                    .width(Length::Fixed(200.0))
+            )
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center);
+
+        // Frameless: text only, no background or border
+        let frameless_row = row::with_capacity(2)
+            .push(text::body("Frameless:").width(Length::Fixed(100.0)))
+            .push(
+                toggler(widget.frameless)
+                    .on_toggle(move |value| Message::WidgetFramelessToggled(index, value))
+            )
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center);
+
         // Build configuration column
-        column::with_capacity(4)
+        let mut config_column = column::with_capacity(10)
             .push(position_row)
-            .push(size_row)
-            .push(margin_row)
+            .push(output_row)
+            .push(with_error(size_row, size_message))
+            .push(with_error(margin_row, margin_message))
             .push(opacity_row)
+            .push(accent_row)
+            .push(sound_row)
+            .push(frameless_row)
             .spacing(spacing.space_s)
-            .padding([spacing.space_s, spacing.space_m])
-            .into()
+            .padding([spacing.space_s, spacing.space_m]);
+
+        // Widget-specific settings generated from the factory's config schema
+        if let Some(caps) = self.widget_registry.capabilities(&widget.widget_type) {
+            let labels = caps.labels();
+            if !labels.is_empty() {
+                config_column = config_column.push(text::caption(format!("Uses: {}", labels.join(", "))));
+            }
+        }
+        if let Some(schema) = self.widget_registry.config_schema(&widget.widget_type) {
+            for field in schema.fields() {
+                config_column = config_column.push(self.view_widget_field(index, widget, field));
+            }
+        }
+
+        config_column.into()
+    }
+
+    /// Form row for a single schema-described widget setting
+    fn view_widget_field(
+        &self,
+        index: usize,
+        widget: &cosmic_desktop_widget::WidgetInstance,
+        field: &ConfigField,
+    ) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let key = field.key;
+        let current = widget.config.get(key);
+
+        let control: Element<'_, Message> = match &field.kind {
+            FieldKind::Bool => {
+                let enabled = current.and_then(|v| v.as_bool()).unwrap_or(false);
+                toggler(enabled)
+                    .on_toggle(move |value| {
+                        Message::WidgetFieldChanged(index, key, Some(toml::Value::Boolean(value)))
+                    })
+                    .into()
+            }
+            FieldKind::Choice(options) => {
+                let options: &'static [&'static str] = options;
+                let selected = current
+                    .and_then(|v| v.as_str())
+                    .and_then(|v| options.iter().position(|o| *o == v));
+                dropdown(options, selected, move |idx| {
+                    let value = options.get(idx).map(|o| toml::Value::String(o.to_string()));
+                    Message::WidgetFieldChanged(index, key, value)
+                })
+                .width(Length::Fixed(150.0))
+                .into()
+            }
+            kind => {
+                let value = self
+                    .widget_field_inputs
+                    .get(&(index, key))
+                    .cloned()
+                    .or_else(|| current.map(|v| kind.format_value(v)))
+                    .unwrap_or_default();
+                let input = text_input(field.description.unwrap_or(""), value)
+                    .on_input(move |value| Message::WidgetFieldInput(index, key, value))
+                    .width(Length::Fill);
+                if matches!(kind, FieldKind::Secret) {
+                    input.password().into()
+                } else {
+                    input.into()
+                }
+            }
+        };
+
+        let row = row::with_capacity(2)
+            .push(text::body(format!("{}:", field.label)).width(Length::Fixed(180.0)))
+            .push(control)
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center);
+        with_error(row, self.widget_field_error(index, widget, field))
+    }
+
+    /// Error text for a per-widget setting, None when it's valid
+    ///
+    /// Checks the text as typed where there is any, since the config only
+    /// keeps the last value that parsed.
+    fn widget_field_error(
+        &self,
+        index: usize,
+        widget: &cosmic_desktop_widget::WidgetInstance,
+        field: &ConfigField,
+    ) -> Option<String> {
+        let invalid = match self.widget_field_inputs.get(&(index, field.key)) {
+            Some(input) if input.trim().is_empty() => return field.required.then(|| "Required".to_string()),
+            Some(input) => field.kind.parse_input(input).is_none(),
+            None => match widget.config.get(field.key) {
+                Some(value) => !field.kind.accepts(value),
+                None => return field.required.then(|| "Required".to_string()),
+            },
+        };
+        invalid.then(|| field_hint(&field.kind))
+    }
+
+    /// Whether any setting of the widget at `index` is invalid
+    fn widget_has_errors(&self, index: usize) -> bool {
+        let Some(widget) = self.config.widgets.get(index) else {
+            return false;
+        };
+        let size_inputs = [
+            self.widget_width_inputs.get(index),
+            self.widget_height_inputs.get(index),
+        ];
+        if size_inputs.into_iter().flatten().any(|input| size_error(input).is_some()) {
+            return true;
+        }
+        if let Some((top, right, bottom, left)) = self.widget_margin_inputs.get(index) {
+            if [top, right, bottom, left].into_iter().any(|input| margin_error(input).is_some()) {
+                return true;
+            }
+        }
+        self.widget_registry
+            .config_schema(&widget.widget_type)
+            .is_some_and(|schema| {
+                schema
+                    .fields()
+                    .iter()
+                    .any(|field| self.widget_field_error(index, widget, field).is_some())
+            })
+    }
+
+    /// Whether any input holds a value that can't be saved
+    fn has_errors(&self) -> bool {
+        let general = [&self.width_input, &self.height_input]
+            .into_iter()
+            .any(|input| size_error(input).is_some());
+        let margins = [
+            &self.margin_top_input,
+            &self.margin_right_input,
+            &self.margin_bottom_input,
+            &self.margin_left_input,
+        ]
+        .into_iter()
+        .any(|input| margin_error(input).is_some());
+        let mut colors = ThemeColorField::COLORS.to_vec();
+        if self.theme_config.gradient.is_some() {
+            colors.extend([ThemeColorField::GradientStart, ThemeColorField::GradientEnd]);
+        }
+        let colors = colors
+            .into_iter()
+            .any(|field| WidgetColor::from_hex(self.color_input(field)).is_none());
+
+        general
+            || margins
+            || colors
+            || repeat_error(&self.alarm_repeat_input).is_some()
+            || (0..self.config.widgets.len()).any(|index| self.widget_has_errors(index))
     }
 
     /// Create a 3x3 grid for position selection - follows COSMIC patterns
@@ -1573,15 +2723,15 @@ impl ConfigApp {
 
                 // Use shorter labels for cleaner grid
                 let label = match position {
-                    Position::TopLeft => "↖ Top Left",
-                    Position::TopCenter => "↑ Top",
-                    Position::TopRight => "↗ Top Right",
-                    Position::CenterLeft => "← Left",
-                    Position::Center => "● Center",
-                    Position::CenterRight => "→ Right",
-                    Position::BottomLeft => "↙ Bottom Left",
-                    Position::BottomCenter => "↓ Bottom",
-                    Position::BottomRight => "↘ Bottom Right",
+                    Position::TopLeft => fl!("position-top-left"),
+                    Position::TopCenter => fl!("position-top-center"),
+                    Position::TopRight => fl!("position-top-right"),
+                    Position::CenterLeft => fl!("position-center-left"),
+                    Position::Center => fl!("position-center"),
+                    Position::CenterRight => fl!("position-center-right"),
+                    Position::BottomLeft => fl!("position-bottom-left"),
+                    Position::BottomCenter => fl!("position-bottom-center"),
+                    Position::BottomRight => fl!("position-bottom-right"),
                 };
 
                 let btn = if is_selected {
@@ -1603,3 +2753,237 @@ impl ConfigApp {
         grid_column.into()
     }
 }
+
+/// Send a config request to the running widget, off the UI thread
+///
+/// The widget answers from its event loop, which can take a moment.
+fn send_config_request(
+    request: IpcRequest,
+    on_done: fn(Result<(), String>) -> Message,
+) -> Task<Message> {
+    cosmic::task::future(async move {
+        let response = tokio::task::spawn_blocking(move || ipc::send(&request)).await;
+        let result = match response {
+            Ok(Ok(IpcResponse::Applied)) => Ok(()),
+            Ok(Ok(IpcResponse::Error { message })) => Err(message),
+            Ok(Ok(other)) => Err(format!("Unexpected response: {:?}", other)),
+            Ok(Err(e)) => Err(format!("{:#}", e)),
+            Err(e) => Err(e.to_string()),
+        };
+        on_done(result)
+    })
+}
+
+/// Render a widget type with its default settings for the gallery
+///
+/// D-Bus widgets are skipped rather than have them talk to the session bus
+/// just to be previewed, as are widgets that can't be created without setup
+/// (e.g. an API key).
+fn render_thumbnail(
+    renderer: &mut Renderer,
+    registry: &WidgetRegistry,
+    widget_type: &str,
+    opacity: f32,
+) -> Option<widget::image::Handle> {
+    if registry.capabilities(widget_type)?.dbus {
+        return None;
+    }
+    let mut widget = registry.create_default(widget_type).ok()?;
+    widget.update();
+
+    let mut pixmap = tiny_skia::Pixmap::new(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)?;
+    renderer.render_single_widget(
+        pixmap.data_mut(),
+        THUMBNAIL_WIDTH,
+        THUMBNAIL_HEIGHT,
+        widget.as_ref(),
+        opacity,
+        None,
+        false,
+        Freshness::Fresh,
+    );
+
+    // The renderer draws premultiplied RGBA
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    Some(widget::image::Handle::from_rgba(
+        THUMBNAIL_WIDTH,
+        THUMBNAIL_HEIGHT,
+        pixels,
+    ))
+}
+
+/// Display name for a theme, e.g. "Transparent Dark" for `transparent_dark`
+fn theme_label(name: &str) -> String {
+    name.split(['_', '-'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Hex for a theme color, leaving out the alpha when opaque
+fn color_hex(color: WidgetColor) -> String {
+    if color.a == 255 {
+        format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+    } else {
+        color.to_hex()
+    }
+}
+
+fn iced_color(color: WidgetColor) -> cosmic::iced::Color {
+    cosmic::iced::Color::from_rgba8(color.r, color.g, color.b, color.a as f32 / 255.0)
+}
+
+/// Built-in and special themes followed by theme files, in dropdown order
+fn available_themes() -> Vec<String> {
+    BUILTIN_THEMES
+        .iter()
+        .chain(&["custom", "pywal", "matugen"])
+        .map(|name| name.to_string())
+        .chain(Theme::user_theme_names())
+        .collect()
+}
+
+/// Maps the outputs' global coordinates onto the monitor layout
+#[derive(Debug, Clone, Copy)]
+struct LayoutScale {
+    scale: f32,
+    min_x: i32,
+    min_y: i32,
+    offset_x: f32,
+    offset_y: f32,
+}
+
+impl LayoutScale {
+    /// Scale that fits all `outputs` into the layout, centered
+    fn fit(outputs: &[OutputGeometry]) -> Option<Self> {
+        let min_x = outputs.iter().map(|o| o.x).min()?;
+        let min_y = outputs.iter().map(|o| o.y).min()?;
+        let max_x = outputs.iter().map(|o| o.x + o.width as i32).max()?;
+        let max_y = outputs.iter().map(|o| o.y + o.height as i32).max()?;
+        let (width, height) = ((max_x - min_x) as f32, (max_y - min_y) as f32);
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        let scale = ((LAYOUT_WIDTH - 2.0 * LAYOUT_PADDING) / width)
+            .min((LAYOUT_HEIGHT - 2.0 * LAYOUT_PADDING) / height);
+        Some(Self {
+            scale,
+            min_x,
+            min_y,
+            offset_x: (LAYOUT_WIDTH - width * scale) / 2.0,
+            offset_y: (LAYOUT_HEIGHT - height * scale) / 2.0,
+        })
+    }
+
+    fn to_canvas(&self, x: i32, y: i32) -> (f32, f32) {
+        (
+            self.offset_x + (x - self.min_x) as f32 * self.scale,
+            self.offset_y + (y - self.min_y) as f32 * self.scale,
+        )
+    }
+
+    fn to_global(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            self.min_x + ((x - self.offset_x) / self.scale).round() as i32,
+            self.min_y + ((y - self.offset_y) / self.scale).round() as i32,
+        )
+    }
+}
+
+/// `content` placed at `x`,`y` in the monitor layout's stack
+fn layout_placed<'a>(content: impl Into<Element<'a, Message>>, x: f32, y: f32) -> Element<'a, Message> {
+    container(content)
+        .padding(Padding {
+            top: y.max(0.0),
+            right: 0.0,
+            bottom: 0.0,
+            left: x.max(0.0),
+        })
+        .into()
+}
+
+/// Error text for a size input, None when it's valid
+fn size_error(input: &str) -> Option<String> {
+    match input.trim().parse::<u32>() {
+        Ok(size) if (1..=10000).contains(&size) => None,
+        _ => Some("Enter a whole number from 1 to 10000".to_string()),
+    }
+}
+
+/// Error text for a margin input, None when it's valid
+fn margin_error(input: &str) -> Option<String> {
+    match input.trim().parse::<i32>() {
+        Ok(_) => None,
+        Err(_) => Some("Enter a whole number of pixels".to_string()),
+    }
+}
+
+/// Error text for the alarm repeat count, None when it's valid
+fn repeat_error(input: &str) -> Option<String> {
+    match input.trim().parse::<u32>() {
+        Ok(repeat) if (1..=10).contains(&repeat) => None,
+        _ => Some("Enter a number from 1 to 10".to_string()),
+    }
+}
+
+/// What a schema field accepts, shown when the typed value doesn't parse
+fn field_hint(kind: &FieldKind) -> String {
+    let (what, range) = match kind {
+        FieldKind::Integer { min, max } => ("a whole number", (min.map(|v| v as f64), max.map(|v| v as f64))),
+        FieldKind::Float { min, max } => ("a number", (*min, *max)),
+        _ => return "Invalid value".to_string(),
+    };
+    match range {
+        (Some(min), Some(max)) => format!("Enter {} from {} to {}", what, min, max),
+        (Some(min), None) => format!("Enter {} of at least {}", what, min),
+        (None, Some(max)) => format!("Enter {} of at most {}", what, max),
+        (None, None) => format!("Enter {}", what),
+    }
+}
+
+/// `item` with `error` as a caption below it
+fn with_error<'a>(item: impl Into<Element<'a, Message>>, error: Option<String>) -> Element<'a, Message> {
+    let spacing = theme::active().cosmic().spacing;
+    let mut content = column::with_capacity(2)
+        .push(item)
+        .spacing(spacing.space_xxs);
+    if let Some(error) = error {
+        content = content.push(
+            text::caption(error)
+                .apply(container)
+                .padding([0, spacing.space_s]),
+        );
+    }
+    content.into()
+}
+
+/// Expand a leading `~/` in a path typed by the user
+fn expand_home(path: &str) -> std::path::PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => std::path::PathBuf::from(path),
+    }
+}
+
+/// The configured sound if it's a file path, empty for built-in sounds
+fn custom_sound_path(effect: &str) -> &str {
+    if BUILTIN_SOUNDS.contains(&effect) {
+        ""
+    } else {
+        effect
+    }
+}
@@ -0,0 +1,81 @@
+//! Line diff between two configurations, shown before one replaces the other
+
+/// A line of a configuration diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Line in both configurations
+    Same(String),
+    /// Line only in the new configuration
+    Added(String),
+    /// Line only in the old configuration
+    Removed(String),
+}
+
+impl DiffLine {
+    /// Whether the line differs between the configurations
+    pub fn is_change(&self) -> bool {
+        !matches!(self, Self::Same(_))
+    }
+}
+
+/// Diff `old` against `new` line by line
+///
+/// Builds the full longest-common-subsequence table, which is fine at the
+/// size of a config file.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j]: length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    lines.extend(new[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let old = "[panel]\nwidth = 450\nheight = 180\n";
+        let new = "[panel]\nwidth = 500\nheight = 180\ntheme = \"nord\"\n";
+        assert_eq!(
+            diff_lines(old, new),
+            vec![
+                DiffLine::Same("[panel]".to_string()),
+                DiffLine::Removed("width = 450".to_string()),
+                DiffLine::Added("width = 500".to_string()),
+                DiffLine::Same("height = 180".to_string()),
+                DiffLine::Added("theme = \"nord\"".to_string()),
+            ]
+        );
+        assert!(diff_lines(old, old).iter().all(|line| !line.is_change()));
+    }
+}
@@ -220,15 +220,25 @@ fn convert_old_to_new(old: OldConfig) -> Config {
                 bottom: old.margin.bottom,
                 left: old.margin.left,
             },
+            panel_detection: Default::default(),
             theme: old.theme,
             background_opacity: None,
+            border_style: None,
             padding: old.padding,
             spacing: old.spacing,
         },
         widgets,
         custom_theme: None,
         theme_config: None,
+        palette: None,
+        theme_schedule: None,
         sounds: super::SoundsConfig::default(),
+        power: super::PowerConfig::default(),
+        performance: super::PerformanceConfig::default(),
+        accessibility: super::AccessibilityConfig::default(),
+        network: super::NetworkConfig::default(),
+        icons: super::IconsConfig::default(),
+        groups: std::collections::HashMap::new(),
     }
 }
 
@@ -1,15 +1,22 @@
 // Configuration management
 
-use crate::audio::SoundConfig;
+use crate::audio::{sound_theme, SoundConfig, SoundEffect, SoundTheme};
+use crate::layout::{Alignment, LayoutDirection};
+use crate::panel::PanelBackendKind;
 use crate::position::Position;
-use crate::theme::Theme;
-use crate::widget::WidgetInstance;
+use crate::text::TextScale;
+use crate::theme::{BorderStyle, Color, PaletteSource, Theme, ThemeSchedule};
+use crate::widget::{SoundCue, WidgetInstance};
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
+pub mod diff;
 pub mod migration;
 
+pub use diff::DiffLine;
+
 /// Panel configuration settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PanelConfig {
@@ -30,7 +37,14 @@ pub struct PanelConfig {
     /// Margins from screen edges
     pub margin: Margin,
 
-    /// Theme name: "cosmic_dark", "light", "transparent_dark", "transparent_light", "glass", or "custom"
+    /// How desktop panels are found so widgets stay clear of them:
+    /// "auto", "cosmic", "sway", "hyprland" or "exclusive_zone"
+    #[serde(default)]
+    pub panel_detection: PanelBackendKind,
+
+    /// Theme name: "cosmic_dark", "light", "transparent_dark", "transparent_light", "glass", "custom",
+    /// a wallpaper palette: "pywal" / "matugen" (see [`PaletteConfig`]), or the name of a
+    /// file in the themes directory (see [`Theme::themes_dir`])
     pub theme: String,
 
     /// Override background opacity (0.0 = fully transparent, 1.0 = fully opaque)
@@ -38,6 +52,10 @@ pub struct PanelConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub background_opacity: Option<f32>,
 
+    /// Override the theme's border style (gradient, stripes or image)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border_style: Option<BorderStyle>,
+
     /// Layout padding in pixels
     pub padding: f32,
 
@@ -71,6 +89,31 @@ impl Default for ThemeConfig {
     }
 }
 
+impl ThemeConfig {
+    /// Apply the editor's colors and style on top of `theme`
+    ///
+    /// Colors that aren't valid hex are skipped. The background gradient
+    /// isn't drawn by the renderer, so it's left out.
+    pub fn apply(&self, theme: &mut Theme) {
+        let colors = [
+            (&self.colors.background, &mut theme.background),
+            (&self.colors.text_primary, &mut theme.text_primary),
+            (&self.colors.text_secondary, &mut theme.text_secondary),
+            (&self.colors.accent, &mut theme.accent),
+            (&self.colors.border, &mut theme.border),
+        ];
+        for (hex, color) in colors {
+            if let Some(parsed) = Color::from_hex(hex) {
+                *color = parsed;
+            }
+        }
+
+        theme.corner_radius = self.style.corner_radius;
+        theme.border_width = self.style.border_width;
+        theme.blur_enabled = self.style.blur_enabled;
+    }
+}
+
 /// Theme color settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThemeColors {
@@ -142,7 +185,7 @@ pub struct ThemeStyle {
     #[serde(default = "default_border_width")]
     pub border_width: f32,
 
-    /// Enable compositor blur hint
+    /// Ask the compositor to blur what's behind the widget
     #[serde(default)]
     pub blur_enabled: bool,
 }
@@ -209,6 +252,31 @@ impl Default for GradientConfig {
     }
 }
 
+/// Wallpaper palette settings (used when theme = "pywal" or "matugen")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaletteConfig {
+    /// Palette file; defaults to the generator's cache location
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// Matugen scheme to use: "dark" or "light"
+    #[serde(default = "default_palette_mode")]
+    pub mode: String,
+}
+
+fn default_palette_mode() -> String {
+    "dark".to_string()
+}
+
+impl Default for PaletteConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            mode: default_palette_mode(),
+        }
+    }
+}
+
 /// Global sound settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SoundsConfig {
@@ -227,12 +295,43 @@ pub struct SoundsConfig {
     /// Notification sound settings
     #[serde(default)]
     pub notification: SoundConfig,
+
+    /// Freedesktop sound theme for named sounds; empty for bundled sounds only
+    #[serde(default = "default_sound_theme")]
+    pub theme: String,
+
+    /// Sounds for named widget events (e.g. "hour" for the hourly chime)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub events: HashMap<String, SoundConfig>,
 }
 
 fn default_master_volume() -> f32 {
     0.8
 }
 
+fn default_sound_theme() -> String {
+    sound_theme::FALLBACK_THEME.to_string()
+}
+
+impl SoundsConfig {
+    /// The configured sound theme, if any
+    pub fn sound_theme(&self) -> Option<SoundTheme> {
+        let name = self.theme.trim();
+        (!name.is_empty()).then(|| SoundTheme::new(name))
+    }
+
+    /// Sound settings used for a widget's sound cue
+    ///
+    /// Events without their own entry use the notification sound.
+    pub fn for_cue(&self, cue: SoundCue) -> &SoundConfig {
+        match cue {
+            SoundCue::Alarm => &self.alarm,
+            SoundCue::Notification => &self.notification,
+            SoundCue::Event(name) => self.events.get(name).unwrap_or(&self.notification),
+        }
+    }
+}
+
 impl Default for SoundsConfig {
     fn default() -> Self {
         Self {
@@ -250,6 +349,209 @@ impl Default for SoundsConfig {
                 volume: 0.7,
                 repeat: 1,
             },
+            theme: default_sound_theme(),
+            events: HashMap::new(),
+        }
+    }
+}
+
+/// How the widgets of a group are arranged in their shared surface
+///
+/// ```toml
+/// [groups.status]
+/// direction = "horizontal"
+/// align = "center"
+///
+/// [groups.dashboard]
+/// direction = "grid"
+/// columns = 2
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GroupConfig {
+    /// Stack widgets vertically (default), place them side by side, or
+    /// lay them out in a grid
+    #[serde(default)]
+    pub direction: LayoutDirection,
+
+    /// Columns of a grid layout (default 2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<u32>,
+
+    /// Cross-axis alignment of widgets that don't fill their line
+    #[serde(default)]
+    pub align: Alignment,
+
+    /// Surface width; defaults to the width of the group's first widget
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+}
+
+/// Power saving settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerConfig {
+    /// Stop updating and redrawing widgets while the session is idle
+    #[serde(default = "default_true")]
+    pub pause_when_idle: bool,
+
+    /// Stop updating and redrawing widgets while the screen is locked
+    #[serde(default = "default_true")]
+    pub pause_when_locked: bool,
+
+    /// Remove the widget surfaces while the screen is locked, so they can
+    /// never show above the lock screen
+    #[serde(default = "default_true")]
+    pub hide_when_locked: bool,
+
+    /// Stop polling network widgets while there's no network connection
+    #[serde(default = "default_true")]
+    pub pause_when_offline: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            pause_when_idle: true,
+            pause_when_locked: true,
+            hide_when_locked: true,
+            pause_when_offline: true,
+        }
+    }
+}
+
+/// Rendering and polling performance tuning
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceConfig {
+    /// Maximum number of rasterized glyphs kept in memory
+    #[serde(default = "default_glyph_cache_size")]
+    pub glyph_cache_size: usize,
+
+    /// Rasterize clock digits at startup so the first frames don't stutter
+    #[serde(default = "default_true")]
+    pub prewarm_glyphs: bool,
+
+    /// Random variation of network refresh intervals, as a fraction (0.0-0.5)
+    #[serde(default = "default_poll_jitter")]
+    pub poll_jitter: f64,
+
+    /// Spread the first network fetches of widgets out instead of firing
+    /// them all at once
+    #[serde(default = "default_true")]
+    pub stagger_polling: bool,
+
+    /// Keep a third buffer per widget, for compositors that hold on to
+    /// buffers for longer than a frame
+    #[serde(default)]
+    pub triple_buffering: bool,
+}
+
+fn default_glyph_cache_size() -> usize {
+    crate::text::DEFAULT_CACHE_CAPACITY
+}
+
+fn default_poll_jitter() -> f64 {
+    crate::update::tasks::DEFAULT_JITTER
+}
+
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            glyph_cache_size: default_glyph_cache_size(),
+            prewarm_glyphs: true,
+            poll_jitter: default_poll_jitter(),
+            stagger_polling: true,
+            triple_buffering: false,
+        }
+    }
+}
+
+/// Settings for low-vision users and motion sensitivity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessibilityConfig {
+    /// Draw with a high-contrast variant of the theme: opaque background,
+    /// pure black or white text and a thick border
+    #[serde(default)]
+    pub high_contrast: bool,
+
+    /// Factor for all text sizes (0.5-3.0)
+    #[serde(default = "default_text_scale")]
+    pub text_scale: f32,
+
+    /// Smallest text size in pixels; 0 for no minimum
+    #[serde(default)]
+    pub min_font_size: f32,
+
+    /// Skip animations, e.g. show collapsing widgets at their new size at once
+    #[serde(default)]
+    pub reduced_motion: bool,
+}
+
+fn default_text_scale() -> f32 {
+    1.0
+}
+
+impl AccessibilityConfig {
+    /// Text scaling the renderer applies
+    pub fn text_scale(&self) -> TextScale {
+        TextScale {
+            scale: self.text_scale,
+            min_size: self.min_font_size,
+        }
+    }
+}
+
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        Self {
+            high_contrast: false,
+            text_scale: default_text_scale(),
+            min_font_size: 0.0,
+            reduced_motion: false,
+        }
+    }
+}
+
+/// Settings shared by widgets that fetch data over the network
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// Mark a widget's data as stale once this many update intervals pass
+    /// without a successful fetch; 0 never marks it
+    #[serde(default = "default_stale_after")]
+    pub stale_after: f32,
+
+    /// Proxy for all requests, e.g. `http://proxy.example.com:3128`;
+    /// overrides `HTTP_PROXY` and friends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+
+    /// Comma-separated hosts to reach without the `proxy`; defaults to
+    /// `NO_PROXY`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+}
+
+/// Where to find the user's own icons
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IconsConfig {
+    /// Directory of SVG and PNG files, referenced from widget configs by
+    /// file name; defaults to `~/.config/cosmic-desktop-widget/icons`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+}
+
+fn default_stale_after() -> f32 {
+    2.0
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            stale_after: default_stale_after(),
+            proxy: None,
+            no_proxy: None,
         }
     }
 }
@@ -261,8 +563,10 @@ impl Default for PanelConfig {
             height: 180,
             position: Position::default(), // TopRight
             margin: Margin::default(),
+            panel_detection: PanelBackendKind::default(),
             theme: "cosmic_dark".to_string(),
             background_opacity: None,
+            border_style: None,
             padding: 20.0,
             spacing: 10.0,
         }
@@ -305,9 +609,41 @@ pub struct Config {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub theme_config: Option<ThemeConfig>,
 
+    /// Wallpaper palette settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub palette: Option<PaletteConfig>,
+
+    /// Automatic day/night theme switching
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme_schedule: Option<ThemeSchedule>,
+
     /// Sound settings
     #[serde(default)]
     pub sounds: SoundsConfig,
+
+    /// Power saving settings
+    #[serde(default)]
+    pub power: PowerConfig,
+
+    /// Rendering and polling performance settings
+    #[serde(default)]
+    pub performance: PerformanceConfig,
+
+    /// Text size, contrast and motion settings
+    #[serde(default)]
+    pub accessibility: AccessibilityConfig,
+
+    /// Settings for widgets that fetch over the network
+    #[serde(default)]
+    pub network: NetworkConfig,
+
+    /// User icon directory
+    #[serde(default)]
+    pub icons: IconsConfig,
+
+    /// Layout of widget groups, keyed by the `group` name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub groups: HashMap<String, GroupConfig>,
 }
 
 fn default_widgets() -> Vec<WidgetInstance> {
@@ -343,7 +679,15 @@ impl Default for Config {
             widgets: default_widgets(),
             custom_theme: None,
             theme_config: None,
+            palette: None,
+            theme_schedule: None,
             sounds: SoundsConfig::default(),
+            power: PowerConfig::default(),
+            performance: PerformanceConfig::default(),
+            accessibility: AccessibilityConfig::default(),
+            network: NetworkConfig::default(),
+            icons: IconsConfig::default(),
+            groups: HashMap::new(),
         }
     }
 }
@@ -352,7 +696,14 @@ impl Config {
     /// Load configuration from file or create default
     ///
     /// This method handles migration from old config format automatically.
+    /// Widgets get ids of their own, see [`Config::assign_instance_ids`].
     pub fn load() -> Result<Self> {
+        let mut config = Self::load_file()?;
+        config.assign_instance_ids();
+        Ok(config)
+    }
+
+    fn load_file() -> Result<Self> {
         let config_path = Self::config_path()?;
 
         if config_path.exists() {
@@ -410,6 +761,35 @@ impl Config {
         Ok(config)
     }
 
+    /// Read a configuration file to import, in either format
+    ///
+    /// Unlike [`Config::load`], a file that doesn't parse or validate is an
+    /// error rather than a fallback to the defaults.
+    pub fn import(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&content)
+    }
+
+    /// Parse and validate the contents of a configuration file, in either format
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut config = if migration::is_old_format(content) {
+            migration::migrate_from_old_format(content)?
+        } else {
+            toml::from_str::<Config>(content).context("Not a valid configuration file")?
+        };
+        config.validate()?;
+        config.assign_instance_ids();
+        Ok(config)
+    }
+
+    /// Line diff from this configuration to `other`, as saved to disk
+    pub fn diff(&self, other: &Config) -> Result<Vec<DiffLine>> {
+        let old = toml::to_string_pretty(self).context("Failed to serialize config")?;
+        let new = toml::to_string_pretty(other).context("Failed to serialize config")?;
+        Ok(diff::diff_lines(&old, &new))
+    }
+
     /// Validate configuration values
     pub fn validate(&self) -> Result<()> {
         // Validate panel settings
@@ -431,20 +811,140 @@ impl Config {
 
         // Position is now type-safe, no validation needed
 
+        if self.performance.glyph_cache_size == 0 {
+            bail!("performance.glyph_cache_size must be at least 1");
+        }
+
+        if !(0.0..=0.5).contains(&self.performance.poll_jitter) {
+            bail!(
+                "performance.poll_jitter must be between 0.0 and 0.5 (got {})",
+                self.performance.poll_jitter
+            );
+        }
+
+        if !(0.5..=3.0).contains(&self.accessibility.text_scale) {
+            bail!(
+                "accessibility.text_scale must be between 0.5 and 3.0 (got {})",
+                self.accessibility.text_scale
+            );
+        }
+
+        if !(0.0..=72.0).contains(&self.accessibility.min_font_size) {
+            bail!(
+                "accessibility.min_font_size must be between 0 and 72 (got {})",
+                self.accessibility.min_font_size
+            );
+        }
+
+        if !(0.0..=100.0).contains(&self.network.stale_after) {
+            bail!(
+                "network.stale_after must be between 0 and 100 (got {})",
+                self.network.stale_after
+            );
+        }
+
+        crate::http::validate(&self.network)?;
+
         // Validate widgets
         if self.widgets.is_empty() {
             tracing::warn!("No widgets configured");
         }
 
+        for widget in &self.widgets {
+            if widget.is_absolute() && (widget.x.is_none() || widget.y.is_none()) {
+                tracing::warn!(
+                    widget = %widget.instance_id(),
+                    "Absolute widget without both x and y, missing coordinates are 0"
+                );
+            }
+            if let Some(accent) = &widget.accent {
+                if Theme::default().resolve_color(accent).is_none() {
+                    tracing::warn!(
+                        widget = %widget.instance_id(),
+                        accent = %accent,
+                        "Invalid widget accent, using the theme accent"
+                    );
+                }
+            }
+        }
+
+        // Missing sound files are worth a warning, but not worth the whole config
+        if self.sounds.enabled {
+            let theme = self.sounds.sound_theme();
+            if let Some(theme) = theme.as_ref().filter(|t| !t.exists()) {
+                tracing::warn!(theme = theme.name(), "Sound theme not installed");
+            }
+            let sounds = [
+                ("alarm", &self.sounds.alarm),
+                ("notification", &self.sounds.notification),
+            ];
+            let events = self
+                .sounds
+                .events
+                .iter()
+                .map(|(name, sound)| (name.as_str(), sound));
+            for (name, sound) in sounds.into_iter().chain(events) {
+                if let Err(e) = sound.sound_effect().validate(theme.as_ref()) {
+                    tracing::warn!(sound = name, error = %e, "Invalid sound");
+                }
+            }
+            for widget in &self.widgets {
+                if let Some(sound) = &widget.sound {
+                    if let Err(e) = SoundEffect::from_config(sound).validate(theme.as_ref()) {
+                        tracing::warn!(
+                            widget = %widget.instance_id(),
+                            error = %e,
+                            "Invalid widget sound"
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Some(schedule) = &self.theme_schedule {
+            if let Err(e) = schedule.validate() {
+                tracing::warn!(error = %e, "Invalid theme schedule");
+            }
+        }
+
         Ok(())
     }
 
+    /// Switch `panel.theme` to the scheduled theme for `now`
+    ///
+    /// Returns true if the theme changed. Only the running widget applies the
+    /// schedule, so the saved config keeps the theme the user picked.
+    pub fn apply_theme_schedule<Tz: chrono::TimeZone>(
+        &mut self,
+        now: &chrono::DateTime<Tz>,
+    ) -> bool {
+        let Some(schedule) = self.theme_schedule.as_ref().filter(|s| s.enabled) else {
+            return false;
+        };
+
+        let name = schedule.theme_at(now);
+        if self.panel.theme == name {
+            return false;
+        }
+
+        tracing::info!(from = %self.panel.theme, to = %name, "Scheduled theme switch");
+        self.panel.theme = name.to_string();
+        true
+    }
+
     /// Get the theme based on configuration
     pub fn get_theme(&self) -> Theme {
         let mut theme = if self.panel.theme == "custom" {
-            self.custom_theme.clone().unwrap_or_default()
+            let mut theme = self.custom_theme.clone().unwrap_or_default();
+            // Edits made in the GUI's theme editor
+            if let Some(theme_config) = &self.theme_config {
+                theme_config.apply(&mut theme);
+            }
+            theme
+        } else if let Some(source) = PaletteSource::from_theme_name(&self.panel.theme) {
+            self.palette_theme(source)
         } else {
-            Theme::from_name(&self.panel.theme)
+            Theme::find(&self.panel.theme)
         };
 
         // Apply opacity override if set
@@ -452,26 +952,143 @@ impl Config {
             theme.opacity = opacity.clamp(0.0, 1.0);
         }
 
+        if let Some(border_style) = &self.panel.border_style {
+            theme.border_style = border_style.clone();
+        }
+
+        // Resolve "@accent"-style references now that the colors are final
+        theme.border_style = theme.border_style.resolve_tokens(&theme);
+
+        if self.accessibility.high_contrast {
+            theme = theme.high_contrast();
+        }
+
         theme
     }
 
+    /// Palette file to read when the theme is "pywal" or "matugen"
+    pub fn palette_path(&self) -> Option<PathBuf> {
+        let source = PaletteSource::from_theme_name(&self.panel.theme)?;
+        match self.palette.as_ref().and_then(|p| p.path.as_deref()) {
+            Some(path) => Some(match path.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir()?.join(rest),
+                None => PathBuf::from(path),
+            }),
+            None => source.default_path(),
+        }
+    }
+
+    /// Directory searched for icons before the built-in ones and the icon
+    /// theme
+    pub fn icons_dir(&self) -> Option<PathBuf> {
+        match self.icons.directory.as_deref() {
+            Some(path) => Some(match path.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir()?.join(rest),
+                None => PathBuf::from(path),
+            }),
+            None => dirs::config_dir().map(|dir| dir.join("cosmic-desktop-widget").join("icons")),
+        }
+    }
+
+    fn palette_theme(&self, source: PaletteSource) -> Theme {
+        let mode = self.palette.as_ref().map_or("dark", |p| p.mode.as_str());
+        let colors = self
+            .palette_path()
+            .context("Could not determine palette location")
+            .and_then(|path| source.load(&path, mode));
+
+        match colors {
+            Ok(colors) => Theme::from_colors(&colors),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to load wallpaper palette, using cosmic_dark");
+                Theme::cosmic_dark()
+            }
+        }
+    }
+
+    /// Give every widget an id no other widget has
+    ///
+    /// Data sources, fetch results and everything remembered per widget are
+    /// keyed by [`WidgetInstance::instance_id`], which falls back to the
+    /// widget type. The first widget of a type without an `id` keeps that
+    /// fallback; later ones, and widgets repeating an id, get `clock#2`,
+    /// `clock#3` and so on, in config order. Returns how many were assigned.
+    pub fn assign_instance_ids(&mut self) -> usize {
+        let reserved: HashSet<String> = self.widgets.iter().filter_map(|w| w.id.clone()).collect();
+        let mut used = HashSet::new();
+        let mut assigned = 0;
+        for widget in &mut self.widgets {
+            let id = widget.instance_id();
+            if used.insert(id.clone()) {
+                continue;
+            }
+            if widget.id.is_some() {
+                tracing::warn!(widget = %id, "Duplicate widget id, renaming the later widget");
+            }
+            let mut n = 2;
+            let unique = loop {
+                let candidate = format!("{}#{}", id, n);
+                if !used.contains(&candidate) && !reserved.contains(&candidate) {
+                    break candidate;
+                }
+                n += 1;
+            };
+            used.insert(unique.clone());
+            widget.id = Some(unique);
+            assigned += 1;
+        }
+        assigned
+    }
+
     /// Get enabled widgets in order
     pub fn enabled_widgets(&self) -> impl Iterator<Item = &WidgetInstance> {
         self.widgets.iter().filter(|w| w.enabled)
     }
 
+    /// Layout settings for `group` (defaults if it has no `[groups]` entry)
+    pub fn group(&self, group: &str) -> GroupConfig {
+        self.groups.get(group).cloned().unwrap_or_default()
+    }
+
+    /// Enabled widgets arranged by the surface that shows them
+    ///
+    /// Indices are into [`Config::enabled_widgets`]. Widgets sharing a
+    /// `group` are collected, in config order, where the first of them
+    /// appears; every other widget gets a surface of its own.
+    pub fn surface_groups(&self) -> Vec<Vec<usize>> {
+        let mut surfaces: Vec<Vec<usize>> = Vec::new();
+        let mut groups: HashMap<&str, usize> = HashMap::new();
+        for (index, instance) in self.enabled_widgets().enumerate() {
+            let Some(group) = instance.group.as_deref() else {
+                surfaces.push(vec![index]);
+                continue;
+            };
+            match groups.get(group) {
+                Some(&surface) => surfaces[surface].push(index),
+                None => {
+                    groups.insert(group, surfaces.len());
+                    surfaces.push(vec![index]);
+                }
+            }
+        }
+        surfaces
+    }
+
     /// Save configuration to file
     pub fn save(&self) -> Result<()> {
-        let config_path = Self::config_path()?;
+        self.save_to(&Self::config_path()?)
+    }
 
+    /// Save configuration to `path`, e.g. to export it
+    pub fn save_to(&self, path: &Path) -> Result<()> {
         // Create parent directory if it doesn't exist
-        if let Some(parent) = config_path.parent() {
+        if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent).context("Failed to create config directory")?;
         }
 
         let content = toml::to_string_pretty(self).context("Failed to serialize config")?;
 
-        std::fs::write(&config_path, content).context("Failed to write config file")?;
+        std::fs::write(path, content).context("Failed to write config file")?;
 
         Ok(())
     }
@@ -540,6 +1157,39 @@ mod tests {
         assert!(!config.widgets.is_empty());
     }
 
+    #[test]
+    fn test_import_exported_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("exported.toml");
+
+        let mut config = Config::default();
+        config.panel.width = 600;
+        config.save_to(&path).unwrap();
+        let imported = Config::import(&path).unwrap();
+        assert_eq!(imported.panel.width, 600);
+
+        let changes: Vec<DiffLine> = Config::default()
+            .diff(&imported)
+            .unwrap()
+            .into_iter()
+            .filter(DiffLine::is_change)
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                DiffLine::Removed("width = 450".to_string()),
+                DiffLine::Added("width = 600".to_string()),
+            ]
+        );
+
+        // Unlike loading, a broken file is refused rather than replaced by defaults
+        std::fs::write(&path, "[panel]\nwidth = \"wide\"\n").unwrap();
+        assert!(Config::import(&path).is_err());
+        config.panel.width = 0;
+        config.save_to(&path).unwrap();
+        assert!(Config::import(&path).is_err());
+    }
+
     #[test]
     fn test_config_serialization() {
         let config = Config::default();
@@ -548,6 +1198,65 @@ mod tests {
         assert_eq!(config.panel.width, deserialized.panel.width);
     }
 
+    #[test]
+    fn test_accessibility_config() {
+        let config: Config = toml::from_str(
+            r#"
+            [panel]
+            width = 450
+            height = 180
+            position = "top-right"
+            theme = "glass"
+            padding = 20.0
+            spacing = 10.0
+            [panel.margin]
+            top = 10
+            right = 20
+            bottom = 0
+            left = 0
+
+            [accessibility]
+            high_contrast = true
+            min_font_size = 16
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.accessibility.text_scale, 1.0);
+        assert!(!config.accessibility.reduced_motion);
+        assert_eq!(config.accessibility.text_scale().apply(10.0), 16.0);
+        assert!(config.validate().is_ok());
+
+        let theme = config.get_theme();
+        assert!(!theme.blur_enabled);
+        assert_eq!(theme.background.a, 255);
+
+        let mut config = Config::default();
+        config.accessibility.text_scale = 5.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_network_config() {
+        let mut config = Config::default();
+        assert_eq!(config.network.stale_after, 2.0);
+        let toml = toml::to_string(&config).unwrap();
+        let without_network = toml.replace("[network]\nstale_after = 2.0\n", "");
+        assert_ne!(toml, without_network);
+        let parsed: Config = toml::from_str(&without_network).unwrap();
+        assert_eq!(parsed.network, NetworkConfig::default());
+
+        config.network.stale_after = 0.0;
+        assert!(config.validate().is_ok());
+        config.network.stale_after = -1.0;
+        assert!(config.validate().is_err());
+
+        config.network.stale_after = 2.0;
+        config.network.proxy = Some("http://proxy.example.com:3128".to_string());
+        assert!(config.validate().is_ok());
+        config.network.proxy = Some("http://proxy.example.com:port".to_string());
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_enabled_widgets() {
         let mut config = Config::default();
@@ -557,6 +1266,41 @@ mod tests {
         assert_eq!(enabled.len(), 1);
     }
 
+    #[test]
+    fn test_surface_groups() {
+        let mut config = Config::default();
+        config.widgets = ["clock", "weather", "quotes", "system_monitor", "news"]
+            .into_iter()
+            .map(WidgetInstance::new)
+            .collect();
+        config.widgets[0].group = Some("sidebar".to_string());
+        config.widgets[2].group = Some("sidebar".to_string());
+        config.widgets[3].enabled = false;
+        config.widgets[4].group = Some("sidebar".to_string());
+
+        // Indices skip the disabled widget
+        assert_eq!(config.surface_groups(), vec![vec![0, 2, 3], vec![1]]);
+    }
+
+    #[test]
+    fn test_group_config() {
+        let mut text = toml::to_string(&Config::default()).unwrap();
+        text.push_str("\n[groups.status]\ndirection = \"horizontal\"\nalign = \"center\"\n");
+        text.push_str("\n[groups.dashboard]\ndirection = \"grid\"\ncolumns = 3\n");
+        let config: Config = toml::from_str(&text).unwrap();
+
+        let status = &config.groups["status"];
+        assert_eq!(status.direction, LayoutDirection::Horizontal);
+        assert_eq!(status.align, Alignment::Center);
+        assert_eq!(status.width, None);
+        assert_eq!(status.columns, None);
+
+        let dashboard = &config.groups["dashboard"];
+        assert_eq!(dashboard.direction, LayoutDirection::Grid);
+        assert_eq!(dashboard.columns, Some(3));
+        assert_eq!(config.group("sidebar"), GroupConfig::default());
+    }
+
     #[test]
     fn test_validation() {
         let config = Config::default();
@@ -565,5 +1309,138 @@ mod tests {
         let mut invalid = Config::default();
         invalid.panel.width = 0;
         assert!(invalid.validate().is_err());
+
+        let mut invalid = Config::default();
+        invalid.performance.glyph_cache_size = 0;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = Config::default();
+        invalid.performance.poll_jitter = 0.9;
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_assign_instance_ids() {
+        let widget = |widget_type: &str, id: Option<&str>| {
+            let mut widget = WidgetInstance::new(widget_type);
+            widget.id = id.map(str::to_string);
+            widget
+        };
+        let mut config = Config {
+            widgets: vec![
+                widget("stocks", None),
+                widget("stocks", None),
+                widget("clock", Some("desk")),
+                widget("clock", Some("desk")),
+                widget("stocks", Some("stocks#2")),
+            ],
+            ..Config::default()
+        };
+        assert_eq!(config.assign_instance_ids(), 2);
+        let ids: Vec<String> = config.widgets.iter().map(|w| w.instance_id()).collect();
+        assert_eq!(ids, ["stocks", "stocks#3", "desk", "desk#2", "stocks#2"]);
+
+        // Already unique, so nothing changes the second time
+        assert_eq!(config.assign_instance_ids(), 0);
+    }
+
+    #[test]
+    fn test_palette_theme() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("colors.json");
+        std::fs::write(
+            &path,
+            r##"{"colors": {"light": {
+                "surface": "#fef7ff", "on_surface": "#1d1b20", "on_surface_variant": "#49454f",
+                "primary": "#6750a4", "outline": "#7a757f"
+            }}}"##,
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        assert!(config.palette_path().is_none());
+
+        config.panel.theme = "matugen".to_string();
+        config.palette = Some(PaletteConfig {
+            path: Some(path.display().to_string()),
+            mode: "light".to_string(),
+        });
+        assert_eq!(config.palette_path(), Some(path));
+
+        let theme = config.get_theme();
+        assert_eq!((theme.accent.r, theme.accent.g, theme.accent.b), (0x67, 0x50, 0xa4));
+
+        // A missing palette falls back to the default theme
+        config.palette.as_mut().unwrap().path = Some("/nonexistent/colors.json".to_string());
+        assert_eq!(
+            config.get_theme().accent.to_array(),
+            Theme::cosmic_dark().accent.to_array()
+        );
+    }
+
+    #[test]
+    fn test_custom_theme_config() {
+        let mut config = Config::default();
+        config.panel.theme = "custom".to_string();
+        assert_eq!(config.get_theme(), Theme::default());
+
+        let mut theme_config = ThemeConfig::default();
+        theme_config.colors.accent = "#ff0000".to_string();
+        theme_config.colors.border = "not a color".to_string();
+        theme_config.style.corner_radius = 4.0;
+        config.theme_config = Some(theme_config);
+
+        let theme = config.get_theme();
+        assert_eq!(theme.accent, Color::rgb(255, 0, 0));
+        assert_eq!(theme.border, Theme::default().border);
+        assert_eq!(theme.corner_radius, 4.0);
+        assert_ne!(theme, Theme::default());
+
+        // Only the custom theme takes the editor's settings
+        config.panel.theme = "cosmic_dark".to_string();
+        assert_eq!(config.get_theme(), Theme::cosmic_dark());
+    }
+
+    #[test]
+    fn test_apply_theme_schedule() {
+        use chrono::{TimeZone, Utc};
+
+        let mut config = Config::default();
+        let noon = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let midnight = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        assert!(!config.apply_theme_schedule(&noon));
+
+        config.theme_schedule = Some(ThemeSchedule::default());
+        assert!(config.apply_theme_schedule(&noon));
+        assert_eq!(config.panel.theme, "light");
+        assert!(!config.apply_theme_schedule(&noon));
+
+        assert!(config.apply_theme_schedule(&midnight));
+        assert_eq!(config.panel.theme, "cosmic_dark");
+
+        config.theme_schedule.as_mut().unwrap().enabled = false;
+        assert!(!config.apply_theme_schedule(&noon));
+    }
+
+    #[test]
+    fn test_sound_for_event_cue() {
+        let sounds: SoundsConfig = toml::from_str(
+            r#"
+            enabled = true
+
+            [events.hour]
+            enabled = true
+            effect = "chime"
+            volume = 0.4
+            "#,
+        )
+        .unwrap();
+
+        let hour = sounds.for_cue(SoundCue::Event("hour"));
+        assert_eq!((hour.effect.as_str(), hour.volume), ("chime", 0.4));
+
+        // Unconfigured events use the notification sound
+        let alert = sounds.for_cue(SoundCue::Event("price_alert"));
+        assert_eq!(alert.effect, "notification");
     }
 }
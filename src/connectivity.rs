@@ -0,0 +1,223 @@
+// Network connectivity from NetworkManager or ConnMan
+
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::thread;
+
+use zbus::zvariant::{OwnedValue, Value};
+
+const NM_DESTINATION: &str = "org.freedesktop.NetworkManager";
+const NM_PATH: &str = "/org/freedesktop/NetworkManager";
+const NM_INTERFACE: &str = "org.freedesktop.NetworkManager";
+
+const CONNMAN_DESTINATION: &str = "net.connman";
+const CONNMAN_PATH: &str = "/";
+const CONNMAN_INTERFACE: &str = "net.connman.Manager";
+
+/// How connected the machine is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Connectivity {
+    /// No network daemon, or it hasn't checked yet
+    #[default]
+    Unknown,
+    /// No network connection at all
+    None,
+    /// Behind a captive portal
+    Portal,
+    /// Connected, but the internet doesn't seem reachable
+    Limited,
+    /// Connected to the internet
+    Full,
+}
+
+impl Connectivity {
+    /// Map NetworkManager's `NMConnectivityState`
+    pub fn from_networkmanager(state: u32) -> Self {
+        match state {
+            1 => Self::None,
+            2 => Self::Portal,
+            3 => Self::Limited,
+            4 => Self::Full,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Map ConnMan's global `State` property
+    pub fn from_connman(state: &str) -> Self {
+        match state {
+            "offline" | "idle" => Self::None,
+            "ready" => Self::Limited,
+            "online" => Self::Full,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Whether network widgets should keep polling
+    ///
+    /// Only a definite "no connection" counts as offline. Limited and
+    /// portal states are often misreported, and when the daemon can't tell
+    /// it's better to try.
+    pub fn is_online(&self) -> bool {
+        *self != Self::None
+    }
+}
+
+/// Connectivity watcher
+///
+/// Follows NetworkManager's `Connectivity` property, or ConnMan's `State`
+/// when NetworkManager isn't running, on a background thread and sends each
+/// change through a channel. Without either the watcher stays silent and
+/// network widgets are polled as usual.
+pub struct ConnectivityWatcher {
+    receiver: mpsc::Receiver<Connectivity>,
+}
+
+impl ConnectivityWatcher {
+    /// Start watching the system's connectivity
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+
+        let spawned = thread::Builder::new()
+            .name("connectivity-watcher".to_string())
+            .spawn(move || {
+                let connection = match zbus::blocking::Connection::system() {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Connectivity tracking unavailable");
+                        return;
+                    }
+                };
+                if let Err(nm_error) = watch_networkmanager(&connection, &tx) {
+                    tracing::debug!(error = %nm_error, "NetworkManager not available, trying ConnMan");
+                    if let Err(e) = watch_connman(&connection, &tx) {
+                        tracing::warn!(error = %e, "Connectivity tracking unavailable");
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            tracing::warn!(error = %e, "Failed to spawn connectivity watcher");
+        }
+
+        Self { receiver: rx }
+    }
+
+    /// Try to receive the latest connectivity change (non-blocking)
+    ///
+    /// Returns Some(connectivity) if it changed since the last call, None otherwise.
+    pub fn try_recv(&self) -> Option<Connectivity> {
+        self.receiver.try_iter().last()
+    }
+}
+
+impl Default for ConnectivityWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn watch_networkmanager(
+    connection: &zbus::blocking::Connection,
+    tx: &mpsc::Sender<Connectivity>,
+) -> zbus::Result<()> {
+    let manager = zbus::blocking::Proxy::new(connection, NM_DESTINATION, NM_PATH, NM_INTERFACE)?;
+    let properties = zbus::blocking::fdo::PropertiesProxy::builder(connection)
+        .destination(NM_DESTINATION)?
+        .path(NM_PATH)?
+        .build()?;
+
+    // Subscribe before reading so no change slips in between
+    let changes = properties.receive_properties_changed()?;
+
+    let mut state = Connectivity::from_networkmanager(manager.get_property("Connectivity")?);
+    tracing::info!(connectivity = ?state, "Connectivity watcher started (NetworkManager)");
+    if tx.send(state).is_err() {
+        return Ok(());
+    }
+
+    for signal in changes {
+        let Ok(args) = signal.args() else {
+            continue;
+        };
+        if args.interface_name().as_str() != NM_INTERFACE {
+            continue;
+        }
+        let Some(Value::U32(value)) = args.changed_properties().get("Connectivity") else {
+            continue;
+        };
+
+        let changed = Connectivity::from_networkmanager(*value);
+        if changed != state {
+            state = changed;
+            tracing::debug!(connectivity = ?state, "Connectivity changed");
+            if tx.send(state).is_err() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn watch_connman(
+    connection: &zbus::blocking::Connection,
+    tx: &mpsc::Sender<Connectivity>,
+) -> zbus::Result<()> {
+    let manager = zbus::blocking::Proxy::new(
+        connection,
+        CONNMAN_DESTINATION,
+        CONNMAN_PATH,
+        CONNMAN_INTERFACE,
+    )?;
+
+    // ConnMan predates org.freedesktop.DBus.Properties and has its own signal
+    let changes = manager.receive_signal("PropertyChanged")?;
+
+    let properties: HashMap<String, OwnedValue> = manager.call("GetProperties", &())?;
+    let mut state = match properties.get("State").map(|value| &**value) {
+        Some(Value::Str(value)) => Connectivity::from_connman(value.as_str()),
+        _ => Connectivity::Unknown,
+    };
+    tracing::info!(connectivity = ?state, "Connectivity watcher started (ConnMan)");
+    if tx.send(state).is_err() {
+        return Ok(());
+    }
+
+    for message in changes {
+        let Ok((name, value)) = message.body().deserialize::<(String, OwnedValue)>() else {
+            continue;
+        };
+        let Value::Str(value) = &*value else {
+            continue;
+        };
+        if name != "State" {
+            continue;
+        }
+
+        let changed = Connectivity::from_connman(value.as_str());
+        if changed != state {
+            state = changed;
+            tracing::debug!(connectivity = ?state, "Connectivity changed");
+            if tx.send(state).is_err() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connectivity_mapping() {
+        assert_eq!(Connectivity::from_networkmanager(1), Connectivity::None);
+        assert_eq!(Connectivity::from_networkmanager(4), Connectivity::Full);
+        assert_eq!(Connectivity::from_networkmanager(0), Connectivity::Unknown);
+        assert_eq!(Connectivity::from_connman("idle"), Connectivity::None);
+        assert_eq!(Connectivity::from_connman("online"), Connectivity::Full);
+
+        assert!(!Connectivity::None.is_online());
+        assert!(Connectivity::Limited.is_online());
+        assert!(Connectivity::Unknown.is_online());
+    }
+}
@@ -0,0 +1,300 @@
+//! Crash reports
+//!
+//! A panic hook writes a report to `$XDG_STATE_HOME/cosmic-desktop-widget/crashes/`
+//! with the panic message, a backtrace, the configuration in use and a
+//! summary of every widget's state. The main loop keeps the last two up to
+//! date through [`record_config`] and [`record_widgets`].
+//!
+//! Reports are written for every panic, including the ones a
+//! [`GuardedWidget`](crate::widget::GuardedWidget) recovers from, since
+//! those are bugs worth reporting too.
+
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::Local;
+
+use crate::config::Config;
+use crate::widget::{Widget, WidgetInstance};
+
+/// Reports kept in the crash directory; older ones are removed
+const MAX_REPORTS: usize = 10;
+
+/// What the process was doing, for the next report
+#[derive(Debug, Default)]
+struct CrashContext {
+    config: Option<String>,
+    widgets: Vec<WidgetEntry>,
+}
+
+/// A widget's line in the report, with the state it was written from
+#[derive(Debug)]
+struct WidgetEntry {
+    id: String,
+    widget_type: String,
+    error: Option<String>,
+    ready: bool,
+    interval: Duration,
+    summary: String,
+}
+
+impl WidgetEntry {
+    fn new(instance: &WidgetInstance, widget: &dyn Widget) -> Self {
+        Self {
+            id: instance.instance_id(),
+            widget_type: instance.widget_type.clone(),
+            error: widget.error().map(str::to_string),
+            ready: widget.is_ready(),
+            interval: widget.update_interval(),
+            summary: widget_summary(instance, widget),
+        }
+    }
+
+    /// Whether the summary still describes `widget`, checked without
+    /// allocating since it runs every tick
+    fn describes(&self, instance: &WidgetInstance, widget: &dyn Widget) -> bool {
+        self.id == instance.id.as_deref().unwrap_or(&instance.widget_type)
+            && self.widget_type == instance.widget_type
+            && self.error.as_deref() == widget.error()
+            && self.ready == widget.is_ready()
+            && self.interval == widget.update_interval()
+    }
+}
+
+static CONTEXT: Mutex<CrashContext> = Mutex::new(CrashContext {
+    config: None,
+    widgets: Vec::new(),
+});
+
+/// Directory crash reports are written to
+pub fn crash_dir() -> Option<PathBuf> {
+    crate::state::state_dir().map(|dir| dir.join("crashes"))
+}
+
+/// Write a crash report for every panic, after the usual panic message
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+
+        let Some(dir) = crash_dir() else {
+            return;
+        };
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+            .unwrap_or_else(|| "unknown".to_string());
+        let report = report_for(&panic_message(info.payload()), &location);
+        match write_report(&dir, &report) {
+            Ok(path) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write crash report: {:#}", e),
+        }
+    }));
+}
+
+/// Remember the configuration in use for crash reports
+pub fn record_config(config: &Config) {
+    let text = toml::to_string_pretty(config)
+        .unwrap_or_else(|e| format!("# Failed to serialize config: {}", e));
+    if let Ok(mut context) = CONTEXT.lock() {
+        context.config = Some(text);
+    }
+}
+
+/// One line on how `widget` is doing, for [`record_widgets`]
+pub fn widget_summary(instance: &WidgetInstance, widget: &dyn Widget) -> String {
+    let state = match widget.error() {
+        Some(error) => format!("error: {}", error),
+        None if !widget.is_ready() => "loading".to_string(),
+        None => "ok".to_string(),
+    };
+    format!(
+        "{} ({}): {}, every {:?}",
+        instance.instance_id(),
+        instance.widget_type,
+        state,
+        widget.update_interval()
+    )
+}
+
+/// Remember a one-line summary of each widget for crash reports
+///
+/// Only the summaries of widgets whose state changed since the last call
+/// are rebuilt.
+pub fn record_widgets<'a>(widgets: impl IntoIterator<Item = (&'a WidgetInstance, &'a dyn Widget)>) {
+    let Ok(mut context) = CONTEXT.lock() else {
+        return;
+    };
+    let mut count = 0;
+    for (i, (instance, widget)) in widgets.into_iter().enumerate() {
+        count = i + 1;
+        match context.widgets.get_mut(i) {
+            Some(entry) if entry.describes(instance, widget) => {}
+            Some(entry) => *entry = WidgetEntry::new(instance, widget),
+            None => context.widgets.push(WidgetEntry::new(instance, widget)),
+        }
+    }
+    context.widgets.truncate(count);
+}
+
+fn report_for(message: &str, location: &str) -> String {
+    let thread = std::thread::current();
+
+    // The panic may have happened while the context was locked; a report
+    // without it beats a deadlock
+    let context = CONTEXT.try_lock().ok();
+    let widgets: Vec<String> = context.as_ref().map_or_else(Vec::new, |c| {
+        c.widgets
+            .iter()
+            .map(|entry| entry.summary.clone())
+            .collect()
+    });
+    format_report(
+        message,
+        location,
+        thread.name().unwrap_or("unnamed"),
+        &Backtrace::force_capture().to_string(),
+        context.as_ref().and_then(|c| c.config.as_deref()),
+        &widgets,
+    )
+}
+
+/// The message a panic was raised with
+pub fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "(no message)".to_string()
+    }
+}
+
+fn format_report(
+    message: &str,
+    location: &str,
+    thread: &str,
+    backtrace: &str,
+    config: Option<&str>,
+    widgets: &[String],
+) -> String {
+    let mut report = String::new();
+    let _ = writeln!(
+        report,
+        "cosmic-desktop-widget {} crash report",
+        env!("CARGO_PKG_VERSION")
+    );
+    let _ = writeln!(report, "Time: {}", Local::now().to_rfc3339());
+    let _ = writeln!(report, "Thread: {}", thread);
+    let _ = writeln!(report, "Location: {}", location);
+    let _ = writeln!(report, "Message: {}", message);
+
+    let _ = writeln!(report, "\n== Widgets ==");
+    if widgets.is_empty() {
+        let _ = writeln!(report, "(none recorded)");
+    }
+    for widget in widgets {
+        let _ = writeln!(report, "{}", widget);
+    }
+
+    let _ = writeln!(report, "\n== Backtrace ==\n{}", backtrace.trim_end());
+    let _ = writeln!(
+        report,
+        "\n== Configuration ==\n{}",
+        config.unwrap_or("(none recorded)").trim_end()
+    );
+    report
+}
+
+/// Write `report` to a new file in `dir`, removing the oldest reports
+/// beyond [`MAX_REPORTS`]
+fn write_report(dir: &Path, report: &str) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let stamp = Local::now().format("%Y%m%d-%H%M%S%.3f");
+    let path = dir.join(format!("crash-{}.txt", stamp));
+    fs::write(&path, report).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    // Names sort by time
+    let mut reports: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("crash-") && n.ends_with(".txt"))
+        })
+        .collect();
+    reports.sort();
+    let excess = reports.len().saturating_sub(MAX_REPORTS);
+    for old in &reports[..excess] {
+        let _ = fs::remove_file(old);
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_report() {
+        let report = format_report(
+            "index out of bounds",
+            "src/widget/news.rs:10:5",
+            "main",
+            "0: news::update",
+            Some("[panel]\nwidth = 450\n"),
+            &["news (headlines): ready".to_string()],
+        );
+        assert!(report.contains("Message: index out of bounds"));
+        assert!(report.contains("Location: src/widget/news.rs:10:5"));
+        assert!(report.contains("== Widgets ==\nnews (headlines): ready"));
+        assert!(report.contains("== Backtrace ==\n0: news::update"));
+        assert!(report.contains("== Configuration ==\n[panel]\nwidth = 450"));
+
+        let empty = format_report("boom", "here", "main", "", None, &[]);
+        assert!(empty.contains("(none recorded)"));
+    }
+
+    #[test]
+    fn test_widget_entry_tracks_state() {
+        let instance = WidgetInstance::new("clock");
+        let widget = crate::widget::ClockWidget::new("24h", false, true);
+        let entry = WidgetEntry::new(&instance, &widget);
+        assert!(entry.summary.starts_with("clock (clock): "));
+        assert!(entry.describes(&instance, &widget));
+
+        let mut renamed = instance.clone();
+        renamed.id = Some("desk-clock".to_string());
+        assert!(!entry.describes(&renamed, &widget));
+    }
+
+    #[test]
+    fn test_write_report_keeps_recent() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for i in 0..MAX_REPORTS + 2 {
+            let old = dir.path().join(format!("crash-2020010{}.txt", i));
+            fs::write(old, "old").unwrap();
+        }
+
+        let path = write_report(dir.path(), "report").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "report");
+        let count = fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(count, MAX_REPORTS);
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let payload = std::panic::catch_unwind(|| panic!("plain")).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "plain");
+        let payload = std::panic::catch_unwind(|| panic!("formatted {}", 1)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "formatted 1");
+    }
+}
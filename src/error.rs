@@ -68,6 +68,31 @@ pub enum WeatherError {
     CityNotFound(String),
 }
 
+/// Stock quote provider errors
+#[derive(Error, Debug)]
+pub enum QuoteError {
+    #[error("HTTP request failed: {0}")]
+    HttpError(#[from] reqwest::Error),
+
+    #[error("{provider} rate limit reached")]
+    RateLimited {
+        provider: &'static str,
+        retry_after: Option<std::time::Duration>,
+    },
+
+    #[error("Invalid API response: {0}")]
+    InvalidResponse(String),
+
+    #[error("No data returned for symbol: {0}")]
+    SymbolNotFound(String),
+
+    #[error("{0} does not provide intraday prices")]
+    Unsupported(&'static str),
+
+    #[error("No quote provider available")]
+    NoProvider,
+}
+
 // Convenience type aliases for common Result types
 pub type Result<T> = std::result::Result<T, WidgetError>;
 pub type ConfigResult<T> = std::result::Result<T, ConfigError>;
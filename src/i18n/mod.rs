@@ -0,0 +1,266 @@
+//! Translations of user-visible text
+//!
+//! Text is looked up by message id in [Fluent](https://projectfluent.org)
+//! files bundled from `i18n/<language>/cosmic_desktop_widget.ftl`, usually
+//! through the [`fl!`](crate::fl) macro:
+//!
+//! ```
+//! use cosmic_desktop_widget::fl;
+//!
+//! assert_eq!(fl!("error", message = "timeout"), "Error: timeout");
+//! ```
+//!
+//! Until a program calls [`init_from_env`] everything is in English, which
+//! keeps library users and tests independent of the user's locale. Messages
+//! a language doesn't translate fall back to English.
+
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::FluentResource;
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::{FluentArgs, FluentValue};
+
+/// Language every message exists in
+const FALLBACK_LANGUAGE: &str = "en";
+
+/// Bundled translations, by language
+const TRANSLATIONS: &[(&str, &str)] = &[
+    (
+        "en",
+        include_str!("../../i18n/en/cosmic_desktop_widget.ftl"),
+    ),
+    (
+        "de",
+        include_str!("../../i18n/de/cosmic_desktop_widget.ftl"),
+    ),
+    (
+        "nb",
+        include_str!("../../i18n/nb/cosmic_desktop_widget.ftl"),
+    ),
+];
+
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+/// Messages in one language, falling back to English
+pub struct Localizer {
+    /// The chosen language first, English last
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+impl Localizer {
+    /// Messages in `language`, or English without a translation for it
+    pub fn new(language: Option<&LanguageIdentifier>) -> Self {
+        let translation = language.and_then(|language| {
+            // "no" is the old code for Norwegian, nearly always Bokmål
+            let wanted = match language.language.as_str() {
+                "no" => "nb",
+                other => other,
+            };
+            TRANSLATIONS
+                .iter()
+                .find(|(code, _)| *code == wanted && *code != FALLBACK_LANGUAGE)
+        });
+
+        let mut bundles = Vec::new();
+        if let Some((code, source)) = translation {
+            bundles.push(bundle(code, source));
+        }
+        bundles.push(bundle(FALLBACK_LANGUAGE, TRANSLATIONS[0].1));
+        Self { bundles }
+    }
+
+    /// Language of the translation in use
+    pub fn language(&self) -> &LanguageIdentifier {
+        &self.bundles[0].locales[0]
+    }
+
+    /// The message `id`, filled in with `args`
+    ///
+    /// Unknown ids come back as they are, so a missing message shows up
+    /// rather than leaving a gap.
+    pub fn text(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        for bundle in &self.bundles {
+            let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) else {
+                continue;
+            };
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+            if !errors.is_empty() {
+                tracing::debug!(id, ?errors, "Errors formatting message");
+            }
+            return text.into_owned();
+        }
+        tracing::debug!(id, "No such message");
+        id.to_string()
+    }
+}
+
+fn bundle(code: &str, source: &str) -> FluentBundle<FluentResource> {
+    let language: LanguageIdentifier = code.parse().unwrap_or_default();
+    let resource =
+        FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, errors)| {
+            tracing::warn!(language = code, ?errors, "Errors in bundled translation");
+            resource
+        });
+
+    let mut bundle = FluentBundle::new_concurrent(vec![language]);
+    // Unicode isolation marks around arguments would be drawn as boxes
+    bundle.set_use_isolating(false);
+    if let Err(errors) = bundle.add_resource(resource) {
+        tracing::warn!(
+            language = code,
+            ?errors,
+            "Duplicate messages in bundled translation"
+        );
+    }
+    bundle
+}
+
+/// Language the environment asks for, if any
+///
+/// Checked like gettext does: the first entry of `LANGUAGE`, then
+/// `LC_ALL`, `LC_MESSAGES` and `LANG`. `C` and `POSIX` mean no preference.
+pub fn language_from(var: impl Fn(&str) -> Option<String>) -> Option<LanguageIdentifier> {
+    let language = var("LANGUAGE").and_then(|list| list.split(':').next().map(str::to_string));
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .find_map(|name| var(name).filter(|value| !value.is_empty()));
+
+    [language, locale]
+        .into_iter()
+        .flatten()
+        .find_map(|value| parse_locale(&value))
+}
+
+/// Language of a POSIX locale like `de_DE.UTF-8@euro`
+fn parse_locale(locale: &str) -> Option<LanguageIdentifier> {
+    let name = locale.split(['.', '@']).next()?.trim();
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return None;
+    }
+    name.replace('_', "-").parse().ok()
+}
+
+/// Use the language the environment asks for
+///
+/// Call once at startup, before any text is produced; text produced
+/// earlier is in English, and later calls change nothing.
+pub fn init_from_env() {
+    let language = language_from(|name| std::env::var(name).ok());
+    let localizer = Localizer::new(language.as_ref());
+    tracing::debug!(
+        requested = ?language.as_ref().map(|l| l.to_string()),
+        using = %localizer.language(),
+        "Picked translation"
+    );
+    let _ = LOCALIZER.set(localizer);
+}
+
+/// The message `id` in the language in use; see [`fl!`](crate::fl)
+pub fn text(id: &str, args: Option<&FluentArgs>) -> String {
+    LOCALIZER
+        .get_or_init(|| Localizer::new(None))
+        .text(id, args)
+}
+
+/// Translated text for a message id, with optional `name = value` arguments
+#[macro_export]
+macro_rules! fl {
+    ($id:literal) => {
+        $crate::i18n::text($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = $crate::i18n::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::text($id, Some(&args))
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn language(code: &str) -> LanguageIdentifier {
+        code.parse().unwrap()
+    }
+
+    #[test]
+    fn test_language_from_environment() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert_eq!(
+            language_from(env(&[("LANG", "de_DE.UTF-8")])),
+            Some(language("de-DE"))
+        );
+        assert_eq!(
+            language_from(env(&[
+                ("LANG", "en_US.UTF-8"),
+                ("LC_MESSAGES", "nb_NO.UTF-8")
+            ])),
+            Some(language("nb-NO"))
+        );
+        assert_eq!(
+            language_from(env(&[("LANGUAGE", "fr:en"), ("LC_ALL", "de_DE@euro")])),
+            Some(language("fr"))
+        );
+        assert_eq!(language_from(env(&[("LC_ALL", "C.UTF-8")])), None);
+        assert_eq!(language_from(env(&[])), None);
+    }
+
+    #[test]
+    fn test_localizer_fallback() {
+        let german = Localizer::new(Some(&language("de-AT")));
+        assert_eq!(german.language(), &language("de"));
+        assert_eq!(german.text("tab-general", None), "Allgemein");
+
+        let mut args = FluentArgs::new();
+        args.set("message", "timeout");
+        assert_eq!(german.text("error", Some(&args)), "Fehler: timeout");
+
+        // Unknown languages and messages
+        let english = Localizer::new(Some(&language("xx")));
+        assert_eq!(english.language(), &language("en"));
+        assert_eq!(english.text("no-such-message", None), "no-such-message");
+        assert_eq!(
+            Localizer::new(Some(&language("no"))).language(),
+            &language("nb")
+        );
+    }
+
+    #[test]
+    fn test_fl_macro_is_english_by_default() {
+        assert_eq!(fl!("now"), "Now!");
+        assert_eq!(fl!("weather-humidity", humidity = 65), "65% humidity");
+        assert_eq!(
+            fl!("battery-time-left", hours = 2, minutes = "05"),
+            "2h 05m"
+        );
+    }
+
+    #[test]
+    fn test_translations_are_complete() {
+        // Every line starting a message is `id = ...`
+        let ids = |source: &str| -> Vec<String> {
+            assert!(FluentResource::try_new(source.to_string()).is_ok());
+            source
+                .lines()
+                .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+                .filter_map(|line| line.split_once(" = ").map(|(id, _)| id.to_string()))
+                .collect()
+        };
+
+        let english = ids(TRANSLATIONS[0].1);
+        assert!(english.len() > 40);
+        for (code, source) in &TRANSLATIONS[1..] {
+            assert_eq!(ids(source), english, "messages of {}", code);
+        }
+    }
+}
@@ -1,12 +1,24 @@
 //! Icon support for widgets
 //!
 //! Provides icon loading, caching, and rendering for widget content.
-//! Supports both SVG and PNG formats with embedded common icons.
+//! Supports both SVG and PNG formats with embedded common icons; other
+//! names are looked up in the desktop's icon theme. A user icon directory,
+//! searched before both, lets configs name icons of their own.
+//!
+//! Icons are rasterized once per size and tint and kept in the
+//! [`IconCache`]; drawing one is a single tiny-skia blit.
+
+mod theme;
+mod watcher;
+
+pub use theme::IconTheme;
+pub use watcher::IconWatcher;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use thiserror::Error;
-use tiny_skia::{Pixmap, PixmapMut};
+use tiny_skia::{FilterQuality, Pixmap, PixmapMut, PixmapPaint, PremultipliedColorU8, Transform};
 use tracing::debug;
 use usvg::TreeParsing;
 
@@ -70,6 +82,31 @@ impl Icon {
         })
     }
 
+    /// Load an SVG or PNG file at `size` pixels square
+    pub fn from_file(path: &Path, size: u32) -> IconResult<Self> {
+        let is_svg = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+        let pixmap = if is_svg {
+            let svg_data = std::fs::read_to_string(path)
+                .map_err(|e| IconError::NotFound(format!("{}: {}", path.display(), e)))?;
+            Self::render_svg(&svg_data, size)?
+        } else {
+            let png_data = std::fs::read(path)
+                .map_err(|e| IconError::NotFound(format!("{}: {}", path.display(), e)))?;
+            let pixmap = Self::load_png(&png_data)?;
+            if pixmap.width() == size && pixmap.height() == size {
+                pixmap
+            } else {
+                Self::scale_pixmap(&pixmap, size)?
+            }
+        };
+        Ok(Self {
+            pixmap: Arc::new(pixmap),
+            source: IconSource::File(path.display().to_string()),
+        })
+    }
+
     /// Get the cached pixmap
     pub fn pixmap(&self) -> &Pixmap {
         &self.pixmap
@@ -87,12 +124,7 @@ impl Icon {
                     source: self.source.clone(),
                 })
             }
-            IconSource::File(_path) => {
-                // TODO: Implement file loading
-                Err(IconError::InvalidSize(
-                    "File loading not implemented".to_string(),
-                ))
-            }
+            IconSource::File(path) => Self::from_file(Path::new(path), size),
         }
     }
 
@@ -160,129 +192,199 @@ impl Icon {
         Ok(pixmap)
     }
 
-    /// Scale pixmap to new size (simple nearest-neighbor for now)
+    /// Scale pixmap to new size, filtered so photos and app icons stay smooth
     fn scale_pixmap(pixmap: &Pixmap, new_size: u32) -> IconResult<Pixmap> {
         let mut new_pixmap = Pixmap::new(new_size, new_size)
             .ok_or_else(|| IconError::InvalidSize("Failed to create scaled pixmap".to_string()))?;
 
-        let scale_x = pixmap.width() as f32 / new_size as f32;
-        let scale_y = pixmap.height() as f32 / new_size as f32;
-
-        for y in 0..new_size {
-            for x in 0..new_size {
-                let src_x = (x as f32 * scale_x) as u32;
-                let src_y = (y as f32 * scale_y) as u32;
+        let scale_x = new_size as f32 / pixmap.width() as f32;
+        let scale_y = new_size as f32 / pixmap.height() as f32;
+        let paint = PixmapPaint {
+            quality: FilterQuality::Bicubic,
+            ..Default::default()
+        };
+        new_pixmap.draw_pixmap(
+            0,
+            0,
+            pixmap.as_ref(),
+            &paint,
+            Transform::from_scale(scale_x, scale_y),
+            None,
+        );
 
-                let src_idx = (src_y * pixmap.width() + src_x) as usize * 4;
-                let dst_idx = (y * new_size + x) as usize * 4;
+        Ok(new_pixmap)
+    }
 
-                if src_idx + 3 < pixmap.data().len() && dst_idx + 3 < new_pixmap.data().len() {
-                    new_pixmap.data_mut()[dst_idx..dst_idx + 4]
-                        .copy_from_slice(&pixmap.data()[src_idx..src_idx + 4]);
-                }
+    /// A copy in `color`, keeping only the shape of the icon
+    ///
+    /// For symbolic icons, which are drawn in one color meant to be
+    /// replaced by the text color. `color` is RGBA.
+    pub fn tinted(&self, color: [u8; 4]) -> Self {
+        let mut pixmap = (*self.pixmap).clone();
+        let [r, g, b, a] = color.map(u32::from);
+        for pixel in pixmap.pixels_mut() {
+            let alpha = pixel.alpha() as u32 * a / 255;
+            // Premultiplied, so no channel exceeds the alpha
+            let channel = |c: u32| (c * alpha / 255) as u8;
+            if let Some(tinted) =
+                PremultipliedColorU8::from_rgba(channel(r), channel(g), channel(b), alpha as u8)
+            {
+                *pixel = tinted;
             }
         }
-
-        Ok(new_pixmap)
+        Self {
+            pixmap: Arc::new(pixmap),
+            source: self.source.clone(),
+        }
     }
 
-    /// Draw icon onto a canvas at specified position
+    /// Draw icon onto a canvas at specified position, blending it over
+    /// what is already there
     pub fn draw(&self, canvas: &mut PixmapMut, x: i32, y: i32) {
-        // Simple pixel copy with alpha blending
-        let icon_width = self.pixmap.width() as i32;
-        let icon_height = self.pixmap.height() as i32;
-        let canvas_width = canvas.width() as i32;
-        let canvas_height = canvas.height() as i32;
-
-        for icon_y in 0..icon_height {
-            for icon_x in 0..icon_width {
-                let canvas_x = x + icon_x;
-                let canvas_y = y + icon_y;
-
-                // Skip if outside canvas bounds
-                if canvas_x < 0
-                    || canvas_x >= canvas_width
-                    || canvas_y < 0
-                    || canvas_y >= canvas_height
-                {
-                    continue;
-                }
-
-                let icon_idx = (icon_y * icon_width + icon_x) as usize * 4;
-                let canvas_idx = (canvas_y * canvas_width + canvas_x) as usize * 4;
-
-                // Get icon pixel (premultiplied ARGB)
-                let icon_data = self.pixmap.data();
-                let b = icon_data[icon_idx];
-                let g = icon_data[icon_idx + 1];
-                let r = icon_data[icon_idx + 2];
-                let a = icon_data[icon_idx + 3];
-
-                if a == 255 {
-                    // Fully opaque, direct copy
-                    canvas.data_mut()[canvas_idx] = b;
-                    canvas.data_mut()[canvas_idx + 1] = g;
-                    canvas.data_mut()[canvas_idx + 2] = r;
-                    canvas.data_mut()[canvas_idx + 3] = a;
-                } else if a > 0 {
-                    // Alpha blend with existing pixel
-                    let a_f = a as f32 / 255.0;
-                    let inv_a = 1.0 - a_f;
-
-                    let canvas_data = canvas.data_mut();
-                    let bg_b = canvas_data[canvas_idx] as f32;
-                    let bg_g = canvas_data[canvas_idx + 1] as f32;
-                    let bg_r = canvas_data[canvas_idx + 2] as f32;
-                    let bg_a = canvas_data[canvas_idx + 3] as f32;
-
-                    canvas_data[canvas_idx] = (b as f32 + bg_b * inv_a) as u8;
-                    canvas_data[canvas_idx + 1] = (g as f32 + bg_g * inv_a) as u8;
-                    canvas_data[canvas_idx + 2] = (r as f32 + bg_r * inv_a) as u8;
-                    canvas_data[canvas_idx + 3] = (a as f32 + bg_a * inv_a).min(255.0) as u8;
-                }
-            }
-        }
+        canvas.draw_pixmap(
+            x,
+            y,
+            self.pixmap.as_ref(),
+            &PixmapPaint::default(),
+            Transform::identity(),
+            None,
+        );
     }
 }
 
+/// One rendering of an icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Variant {
+    size: u32,
+    /// RGBA color the icon was recolored to
+    tint: Option<[u8; 4]>,
+}
+
 /// Icon cache for efficient reuse
 pub struct IconCache {
-    cache: Mutex<HashMap<(String, u32), Arc<Icon>>>,
+    /// Renderings by icon name, so lookups don't allocate a key
+    cache: Mutex<HashMap<String, HashMap<Variant, Arc<Icon>>>>,
+    /// Icon theme searched for names that aren't embedded
+    theme: IconTheme,
+    /// The user's own icons, searched first
+    user_dir: Option<PathBuf>,
+    /// Names nothing has, so they aren't searched for every frame
+    missing: Mutex<HashSet<String>>,
 }
 
 impl IconCache {
     pub fn new() -> Self {
+        Self::with_theme(IconTheme::system())
+    }
+
+    /// Create a cache that looks names up in `theme`
+    pub fn with_theme(theme: IconTheme) -> Self {
         Self {
             cache: Mutex::new(HashMap::new()),
+            theme,
+            user_dir: None,
+            missing: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Look for icons in `dir` before the embedded ones and the icon theme
+    pub fn with_user_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.set_user_dir(dir);
+        self
+    }
+
+    /// Change the user icon directory, dropping icons already loaded
+    pub fn set_user_dir(&mut self, dir: Option<PathBuf>) {
+        if self.user_dir != dir {
+            self.user_dir = dir;
+            self.clear();
         }
     }
 
+    /// The user icon directory
+    pub fn user_dir(&self) -> Option<&Path> {
+        self.user_dir.as_deref()
+    }
+
     /// Get or create icon at specified size
     pub fn get_or_create(&self, name: &str, size: u32) -> IconResult<Arc<Icon>> {
-        let key = (name.to_string(), size);
+        self.get_variant(name, Variant { size, tint: None })
+    }
 
+    /// Get or create icon at specified size, recolored to `color` (RGBA)
+    ///
+    /// See [`Icon::tinted`]; each color is cached separately.
+    pub fn get_tinted(&self, name: &str, size: u32, color: [u8; 4]) -> IconResult<Arc<Icon>> {
+        self.get_variant(
+            name,
+            Variant {
+                size,
+                tint: Some(color),
+            },
+        )
+    }
+
+    fn get_variant(&self, name: &str, variant: Variant) -> IconResult<Arc<Icon>> {
         // Check cache first
+        if let Some(icon) = self
+            .cache
+            .lock()
+            .unwrap()
+            .get(name)
+            .and_then(|variants| variants.get(&variant))
         {
-            let cache = self.cache.lock().unwrap();
-            if let Some(icon) = cache.get(&key) {
-                debug!(name = name, size = size, "Icon cache hit");
-                return Ok(Arc::clone(icon));
-            }
+            return Ok(Arc::clone(icon));
         }
 
-        debug!(name = name, size = size, "Icon cache miss, loading");
-
-        // Load icon from embedded data
-        let icon = Self::load_embedded(name, size)?;
+        let icon = match variant.tint {
+            Some(color) => {
+                let plain = self.get_or_create(name, variant.size)?;
+                plain.tinted(color)
+            }
+            None => {
+                debug!(name = name, size = variant.size, "Icon cache miss, loading");
+                self.load(name, variant.size)?
+            }
+        };
 
         // Cache for future use
-        let icon_arc = Arc::new(icon);
-        {
-            let mut cache = self.cache.lock().unwrap();
-            cache.insert(key, Arc::clone(&icon_arc));
+        let icon = Arc::new(icon);
+        self.cache
+            .lock()
+            .unwrap()
+            .entry(name.to_string())
+            .or_default()
+            .insert(variant, Arc::clone(&icon));
+        Ok(icon)
+    }
+
+    /// Load `name`: the user's icon first, then the embedded one, then the
+    /// icon theme's
+    fn load(&self, name: &str, size: u32) -> IconResult<Icon> {
+        if self.missing.lock().unwrap().contains(name) {
+            return Err(IconError::NotFound(name.to_string()));
         }
+        let user_path = self
+            .user_dir
+            .as_deref()
+            .and_then(|dir| user_icon_path(dir, name));
+        if let Some(path) = user_path {
+            return Icon::from_file(&path, size);
+        }
+        match Self::load_embedded(name, size) {
+            Err(IconError::NotFound(_)) => self.load_themed(name, size),
+            result => result,
+        }
+    }
 
-        Ok(icon_arc)
+    /// Load an icon from the icon theme, or a file if `name` is a path
+    fn load_themed(&self, name: &str, size: u32) -> IconResult<Icon> {
+        let Some(path) = self.theme.lookup(name, size) else {
+            debug!(name = name, theme = self.theme.name(), "Icon not in theme");
+            self.missing.lock().unwrap().insert(name.to_string());
+            return Err(IconError::NotFound(name.to_string()));
+        };
+        Icon::from_file(&path, size)
     }
 
     /// Load embedded icon by name
@@ -306,6 +408,30 @@ impl IconCache {
             "media-next" => Icon::from_svg(ICON_MEDIA_NEXT, size),
             "media-previous" => Icon::from_svg(ICON_MEDIA_PREVIOUS, size),
 
+            // Notification icons
+            "notifications-on" => Icon::from_svg(ICON_NOTIFICATIONS_ON, size),
+            "notifications-off" => Icon::from_svg(ICON_NOTIFICATIONS_OFF, size),
+
+            // Night light icons
+            "night-light-on" => Icon::from_svg(ICON_NIGHT_LIGHT_ON, size),
+            "night-light-off" => Icon::from_svg(ICON_NIGHT_LIGHT_OFF, size),
+
+            // Power profile icons
+            "power-profile-performance" => Icon::from_svg(ICON_POWER_PERFORMANCE, size),
+            "power-profile-balanced" => Icon::from_svg(ICON_POWER_BALANCED, size),
+            "power-profile-power-saver" => Icon::from_svg(ICON_POWER_SAVER, size),
+
+            // VPN icons
+            "vpn-connected" => Icon::from_svg(ICON_VPN_CONNECTED, size),
+            "vpn-disconnected" => Icon::from_svg(ICON_VPN_DISCONNECTED, size),
+
+            // Error cards
+            "widget-error" => Icon::from_svg(ICON_WIDGET_ERROR, size),
+
+            // Freshness badges
+            "badge-stale" => Icon::from_svg(ICON_BADGE_STALE, size),
+            "badge-offline" => Icon::from_svg(ICON_BADGE_OFFLINE, size),
+
             _ => Err(IconError::NotFound(name.to_string())),
         }
     }
@@ -314,10 +440,34 @@ impl IconCache {
     pub fn clear(&self) {
         let mut cache = self.cache.lock().unwrap();
         cache.clear();
+        self.missing.lock().unwrap().clear();
         debug!("Icon cache cleared");
     }
 }
 
+/// The file for `name` in the user icon directory `dir`: `name` itself if
+/// it has an extension, otherwise `name.svg` or `name.png`
+///
+/// Names may point into subdirectories but not out of `dir`.
+fn user_icon_path(dir: &Path, name: &str) -> Option<PathBuf> {
+    let relative = Path::new(name);
+    let inside = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if name.is_empty() || !inside {
+        return None;
+    }
+    let as_given = relative.extension().map(|_| dir.join(relative));
+    as_given
+        .into_iter()
+        .chain(
+            theme::ICON_EXTENSIONS
+                .iter()
+                .map(|ext| dir.join(format!("{}.{}", name, ext))),
+        )
+        .find(|path| path.is_file())
+}
+
 impl Default for IconCache {
     fn default() -> Self {
         Self::new()
@@ -403,6 +553,72 @@ const ICON_MEDIA_PREVIOUS: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" vie
   <polygon points="12,6 6,12 12,18" fill="currentColor"/>
 </svg>"#;
 
+// Notification icons
+const ICON_NOTIFICATIONS_ON: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <path d="M18 16V11a6 6 0 0 0-12 0v5l-2 2h16z" stroke="currentColor" stroke-width="2" fill="none" stroke-linejoin="round"/>
+  <path d="M10 21a2 2 0 0 0 4 0" stroke="currentColor" stroke-width="2" fill="none" stroke-linecap="round"/>
+</svg>"#;
+
+const ICON_NOTIFICATIONS_OFF: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <path d="M18 16V11a6 6 0 0 0-12 0v5l-2 2h16z" fill="currentColor"/>
+  <path d="M10 21a2 2 0 0 0 4 0" stroke="currentColor" stroke-width="2" fill="none" stroke-linecap="round"/>
+  <line x1="3" y1="3" x2="21" y2="21" stroke="currentColor" stroke-width="2" stroke-linecap="round"/>
+</svg>"#;
+
+// Night light icons
+const ICON_NIGHT_LIGHT_ON: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <path d="M21 12.79A9 9 0 1 1 11.21 3 7 7 0 0 0 21 12.79z" fill="currentColor"/>
+</svg>"#;
+
+const ICON_NIGHT_LIGHT_OFF: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <path d="M21 12.79A9 9 0 1 1 11.21 3 7 7 0 0 0 21 12.79z" stroke="currentColor" stroke-width="2" fill="none" stroke-linejoin="round"/>
+</svg>"#;
+
+// Power profile icons
+const ICON_POWER_PERFORMANCE: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <polygon points="13,2 4,14 11,14 10,22 20,9 13,9" fill="currentColor"/>
+</svg>"#;
+
+const ICON_POWER_BALANCED: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <circle cx="12" cy="12" r="9" stroke="currentColor" stroke-width="2" fill="none"/>
+  <path d="M12 3a9 9 0 0 1 0 18z" fill="currentColor"/>
+</svg>"#;
+
+const ICON_POWER_SAVER: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <path d="M5 19C5 10 11 4 20 4c0 9-6 15-15 15z" fill="currentColor"/>
+  <line x1="4" y1="20" x2="12" y2="12" stroke="currentColor" stroke-width="2" stroke-linecap="round"/>
+</svg>"#;
+
+// VPN icons
+const ICON_VPN_CONNECTED: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <rect x="5" y="11" width="14" height="10" rx="2" fill="currentColor"/>
+  <path d="M8 11V7a4 4 0 0 1 8 0v4" stroke="currentColor" stroke-width="2" fill="none"/>
+</svg>"#;
+
+const ICON_VPN_DISCONNECTED: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <rect x="5" y="11" width="14" height="10" rx="2" stroke="currentColor" stroke-width="2" fill="none"/>
+  <path d="M8 11V7a4 4 0 0 1 7.75-1.4" stroke="currentColor" stroke-width="2" fill="none" stroke-linecap="round"/>
+</svg>"#;
+
+// Warning triangle for widgets that can't show their content
+const ICON_WIDGET_ERROR: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <path d="M12 3L2 20.5h20z" stroke="currentColor" stroke-width="2" fill="none" stroke-linejoin="round"/>
+  <line x1="12" y1="9.5" x2="12" y2="14" stroke="currentColor" stroke-width="2" stroke-linecap="round"/>
+  <circle cx="12" cy="17" r="1.2" fill="currentColor"/>
+</svg>"#;
+
+// Clock face for data that hasn't updated in a while
+const ICON_BADGE_STALE: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <circle cx="12" cy="12" r="9" stroke="currentColor" stroke-width="2" fill="none"/>
+  <path d="M12 7v5l3 2" stroke="currentColor" stroke-width="2" fill="none" stroke-linecap="round" stroke-linejoin="round"/>
+</svg>"#;
+
+// Crossed-out cloud for data that can't be fetched
+const ICON_BADGE_OFFLINE: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <path d="M7 18h10a4 4 0 0 0 .5-7.97A6 6 0 0 0 6.2 9.1 4.5 4.5 0 0 0 7 18z" stroke="currentColor" stroke-width="2" fill="none" stroke-linejoin="round"/>
+  <line x1="3" y1="3" x2="21" y2="21" stroke="currentColor" stroke-width="2" stroke-linecap="round"/>
+</svg>"#;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -460,6 +676,18 @@ mod tests {
             "media-pause",
             "media-next",
             "media-previous",
+            "notifications-on",
+            "notifications-off",
+            "night-light-on",
+            "night-light-off",
+            "power-profile-performance",
+            "power-profile-balanced",
+            "power-profile-power-saver",
+            "vpn-connected",
+            "vpn-disconnected",
+            "widget-error",
+            "badge-stale",
+            "badge-offline",
         ];
 
         for name in icon_names {
@@ -468,6 +696,99 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_themed_icon() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let icon_dir = dir.path().join("hicolor/scalable/apps");
+        std::fs::create_dir_all(&icon_dir).unwrap();
+        std::fs::write(
+            dir.path().join("hicolor/index.theme"),
+            "[Icon Theme]\nDirectories=scalable/apps\n\n[scalable/apps]\nSize=48\nType=Scalable\n",
+        )
+        .unwrap();
+        std::fs::write(icon_dir.join("files.svg"), ICON_WEATHER_CLOUDS).unwrap();
+
+        let cache = IconCache::with_theme(IconTheme::with_base_dirs(
+            "hicolor",
+            vec![dir.path().into()],
+        ));
+        let icon = cache.get_or_create("files", 32).unwrap();
+        assert_eq!(icon.pixmap().width(), 32);
+        assert_eq!(icon.resize(16).unwrap().pixmap().width(), 16);
+        assert!(cache.get_or_create("terminal", 32).is_err());
+    }
+
+    #[test]
+    fn test_user_icons() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("apps")).unwrap();
+        std::fs::write(dir.path().join("apps/backup.svg"), ICON_WEATHER_CLOUDS).unwrap();
+        std::fs::write(dir.path().join("weather-clear.svg"), ICON_WEATHER_RAIN).unwrap();
+
+        assert!(user_icon_path(dir.path(), "apps/backup").is_some());
+        assert!(user_icon_path(dir.path(), "apps/backup.svg").is_some());
+        assert!(user_icon_path(dir.path(), "backup").is_none());
+        assert!(user_icon_path(&dir.path().join("apps"), "../weather-clear").is_none());
+        assert!(user_icon_path(dir.path(), "").is_none());
+
+        let theme = IconTheme::with_base_dirs("hicolor", Vec::new());
+        let cache = IconCache::with_theme(theme).with_user_dir(Some(dir.path().into()));
+        assert_eq!(cache.user_dir(), Some(dir.path()));
+        let backup = cache.get_or_create("apps/backup", 32).unwrap();
+        assert_eq!(backup.pixmap().width(), 32);
+
+        // The user's icon replaces the embedded one of the same name
+        let embedded = Icon::from_svg(ICON_WEATHER_CLEAR, 24).unwrap();
+        let user = cache.get_or_create("weather-clear", 24).unwrap();
+        assert_ne!(user.pixmap().data(), embedded.pixmap().data());
+
+        // Icons added later show up once the cache is cleared
+        assert!(cache.get_or_create("later", 24).is_err());
+        std::fs::write(dir.path().join("later.svg"), ICON_WEATHER_SNOW).unwrap();
+        assert!(cache.get_or_create("later", 24).is_err());
+        cache.clear();
+        assert!(cache.get_or_create("later", 24).is_ok());
+    }
+
+    #[test]
+    fn test_tinted_icon() {
+        let cache = IconCache::new();
+        let plain = cache.get_or_create("weather-clear", 24).unwrap();
+        let red = cache
+            .get_tinted("weather-clear", 24, [255, 0, 0, 255])
+            .unwrap();
+        let again = cache
+            .get_tinted("weather-clear", 24, [255, 0, 0, 255])
+            .unwrap();
+        assert!(Arc::ptr_eq(&red, &again));
+
+        // Same shape, new color
+        for (plain, red) in plain.pixmap().pixels().iter().zip(red.pixmap().pixels()) {
+            assert_eq!(plain.alpha(), red.alpha());
+            assert_eq!(red.red(), red.alpha());
+            assert_eq!(red.green(), 0);
+        }
+    }
+
+    #[test]
+    fn test_draw_blends_over_canvas() {
+        let icon = Icon::from_svg(ICON_WEATHER_CLEAR, 24)
+            .unwrap()
+            .tinted([255, 255, 255, 255]);
+        let mut canvas = Pixmap::new(32, 32).unwrap();
+        canvas.fill(tiny_skia::Color::from_rgba8(0, 0, 255, 255));
+        icon.draw(&mut canvas.as_mut(), 4, 4);
+
+        // The middle of the sun is white, the corner still blue
+        let middle = canvas.pixel(16, 16).unwrap();
+        assert_eq!((middle.red(), middle.blue()), (255, 255));
+        let corner = canvas.pixel(0, 0).unwrap();
+        assert_eq!((corner.red(), corner.blue()), (0, 255));
+
+        // Partly off the canvas is clipped
+        icon.draw(&mut canvas.as_mut(), 20, -10);
+    }
+
     #[test]
     fn test_icon_resize() {
         let icon = Icon::from_svg(ICON_WEATHER_CLEAR, 24).unwrap();
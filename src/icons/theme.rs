@@ -0,0 +1,280 @@
+//! Freedesktop icon theme lookup
+//!
+//! Resolves icon names like `firefox` to files following the XDG Icon Theme
+//! specification: themes live in `icons/<theme>/` under each XDG data
+//! directory, describe their size directories and parent themes in
+//! `index.theme`, and always fall back to `hicolor`. Icons outside any theme
+//! are found in `/usr/share/pixmaps`.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Theme every icon theme implicitly inherits from
+pub const FALLBACK_THEME: &str = "hicolor";
+
+/// Theme used when COSMIC's icon theme setting can't be read
+const DEFAULT_THEME: &str = "Cosmic";
+
+/// Extensions tried for each icon, in order of preference
+pub(super) const ICON_EXTENSIONS: &[&str] = &["svg", "png"];
+
+/// A named icon theme and the directories it is searched in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconTheme {
+    name: String,
+    base_dirs: Vec<PathBuf>,
+}
+
+/// One theme directory and its icon subdirectories, best match first
+struct ThemeDir {
+    path: PathBuf,
+    subdirs: Vec<IconDir>,
+}
+
+/// A size directory from `index.theme`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IconDir {
+    name: String,
+    size: u32,
+    scalable: bool,
+}
+
+impl IconTheme {
+    /// The icon theme COSMIC is set to, searched in the standard XDG icon
+    /// directories
+    pub fn system() -> Self {
+        let name = dirs::config_dir()
+            .and_then(|dir| {
+                std::fs::read_to_string(dir.join("cosmic/com.system76.CosmicTk/v1/icon_theme")).ok()
+            })
+            // Stored as a RON string: "Cosmic"
+            .map(|value| value.trim().trim_matches('"').to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| DEFAULT_THEME.to_string());
+        Self::with_base_dirs(&name, base_dirs())
+    }
+
+    /// Look up `name` in the given `icons` directories instead
+    pub fn with_base_dirs(name: &str, base_dirs: Vec<PathBuf>) -> Self {
+        Self {
+            name: name.to_string(),
+            base_dirs,
+        }
+    }
+
+    /// Theme name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Find the file for an icon at about `size` pixels
+    ///
+    /// Scalable icons are preferred, then the closest fixed size. Absolute
+    /// paths are returned as they are if the file exists.
+    pub fn lookup(&self, icon: &str, size: u32) -> Option<PathBuf> {
+        let path = Path::new(icon);
+        if path.is_absolute() {
+            return path.is_file().then(|| path.to_path_buf());
+        }
+
+        for theme in self.chain(size) {
+            for subdir in &theme.subdirs {
+                for ext in ICON_EXTENSIONS {
+                    let path = theme
+                        .path
+                        .join(&subdir.name)
+                        .join(format!("{}.{}", icon, ext));
+                    if path.is_file() {
+                        return Some(path);
+                    }
+                }
+            }
+        }
+
+        // Unthemed icons
+        ICON_EXTENSIONS
+            .iter()
+            .map(|ext| PathBuf::from("/usr/share/pixmaps").join(format!("{}.{}", icon, ext)))
+            .find(|path| path.is_file())
+    }
+
+    /// Theme directories in lookup order: the theme, its parents, then
+    /// `hicolor`
+    fn chain(&self, size: u32) -> Vec<ThemeDir> {
+        let mut chain = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![self.name.clone()];
+
+        while let Some(name) = pending.pop() {
+            if !visited.insert(name.clone()) {
+                continue;
+            }
+
+            let mut parents = Vec::new();
+            for base in &self.base_dirs {
+                let path = base.join(&name);
+                if !path.is_dir() {
+                    continue;
+                }
+                let mut index = read_index(&path.join("index.theme"));
+                // The first index.theme found defines the inheritance
+                if parents.is_empty() {
+                    parents = index.inherits;
+                }
+                index.directories.sort_by_key(|dir| {
+                    (
+                        !dir.scalable,
+                        dir.size.abs_diff(size),
+                        // Prefer scaling down over scaling up
+                        dir.size < size,
+                    )
+                });
+                chain.push(ThemeDir {
+                    path,
+                    subdirs: index.directories,
+                });
+            }
+
+            // Parents are searched in listed order
+            pending.extend(parents.into_iter().rev());
+            if pending.is_empty() && !visited.contains(FALLBACK_THEME) {
+                pending.push(FALLBACK_THEME.to_string());
+            }
+        }
+        chain
+    }
+}
+
+/// The `index.theme` keys this lookup cares about
+struct ThemeIndex {
+    inherits: Vec<String>,
+    directories: Vec<IconDir>,
+}
+
+fn read_index(path: &Path) -> ThemeIndex {
+    let mut inherits = Vec::new();
+    let mut listed: Vec<String> = Vec::new();
+    let mut sections: Vec<IconDir> = Vec::new();
+
+    let contents = std::fs::read_to_string(path).unwrap_or_default();
+    let mut section = String::new();
+    for line in contents.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.to_string();
+            if section != "Icon Theme" {
+                sections.push(IconDir {
+                    name: section.clone(),
+                    size: 0,
+                    scalable: false,
+                });
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+
+        if section == "Icon Theme" {
+            let list = value
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty());
+            match key {
+                "Inherits" => inherits = list.collect(),
+                "Directories" => listed.extend(list),
+                _ => {}
+            }
+        } else if let Some(dir) = sections.last_mut() {
+            match key {
+                "Size" => dir.size = value.parse().unwrap_or(0),
+                "Type" => dir.scalable = value == "Scalable",
+                _ => {}
+            }
+        }
+    }
+
+    // Only listed directories belong to the theme
+    sections.retain(|dir| listed.contains(&dir.name));
+    ThemeIndex {
+        inherits,
+        directories: sections,
+    }
+}
+
+/// `icons` directories from `~/.icons`, `$XDG_DATA_HOME` and `$XDG_DATA_DIRS`
+fn base_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = dirs::home_dir()
+        .map(|d| d.join(".icons"))
+        .into_iter()
+        .chain(dirs::data_dir().map(|d| d.join("icons")))
+        .collect();
+
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(
+        data_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| PathBuf::from(dir).join("icons")),
+    );
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn touch(path: PathBuf) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"").unwrap();
+    }
+
+    #[test]
+    fn test_lookup_prefers_scalable_then_closest_size() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().to_path_buf();
+
+        std::fs::create_dir_all(base.join("ocean")).unwrap();
+        std::fs::write(
+            base.join("ocean/index.theme"),
+            "[Icon Theme]\nName=Ocean\nInherits=parent\n\
+             Directories=16x16/apps,48x48/apps,scalable/apps\n\n\
+             [16x16/apps]\nSize=16\nType=Fixed\n\n\
+             [48x48/apps]\nSize=48\nType=Fixed\n\n\
+             [scalable/apps]\nSize=64\nType=Scalable\n",
+        )
+        .unwrap();
+        touch(base.join("ocean/16x16/apps/terminal.png"));
+        touch(base.join("ocean/48x48/apps/terminal.png"));
+        touch(base.join("ocean/scalable/apps/files.svg"));
+        touch(base.join("ocean/48x48/apps/files.png"));
+
+        std::fs::create_dir_all(base.join("hicolor")).unwrap();
+        std::fs::write(
+            base.join("hicolor/index.theme"),
+            "[Icon Theme]\nDirectories=48x48/apps\n\n[48x48/apps]\nSize=48\n",
+        )
+        .unwrap();
+        touch(base.join("hicolor/48x48/apps/firefox.png"));
+
+        let theme = IconTheme::with_base_dirs("ocean", vec![base.clone()]);
+        assert_eq!(
+            theme.lookup("files", 48),
+            Some(base.join("ocean/scalable/apps/files.svg"))
+        );
+        assert_eq!(
+            theme.lookup("terminal", 40),
+            Some(base.join("ocean/48x48/apps/terminal.png"))
+        );
+        // Missing parent themes are skipped on the way to hicolor
+        assert_eq!(
+            theme.lookup("firefox", 32),
+            Some(base.join("hicolor/48x48/apps/firefox.png"))
+        );
+        assert_eq!(theme.lookup("no-such-icon-anywhere", 32), None);
+    }
+}
@@ -0,0 +1,99 @@
+//! Watching the user icon directory
+
+use anyhow::{Context, Result};
+use notify::{Config as NotifyConfig, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+/// Reports changes to the user icon directory, so new and edited icons
+/// show up without a restart
+///
+/// Watches recursively, since icons can be sorted into subdirectories.
+pub struct IconWatcher {
+    _watcher: RecommendedWatcher,
+    receiver: mpsc::Receiver<()>,
+    dir: PathBuf,
+}
+
+impl IconWatcher {
+    /// Watch `dir`, which has to exist
+    pub fn new(dir: PathBuf) -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<notify::Event, notify::Error>| match res {
+                Ok(event) => {
+                    if event.kind.is_access() {
+                        return;
+                    }
+                    tracing::debug!(paths = ?event.paths, "Icon file changed");
+                    // Nobody listening just means the widget is shutting down
+                    let _ = tx.send(());
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Icon watcher error");
+                }
+            },
+            NotifyConfig::default(),
+        )
+        .context("Failed to create icon watcher")?;
+
+        watcher
+            .watch(&dir, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch icon directory: {}", dir.display()))?;
+
+        tracing::info!(path = %dir.display(), "Icon watcher initialized");
+
+        Ok(Self {
+            _watcher: watcher,
+            receiver: rx,
+            dir,
+        })
+    }
+
+    /// The directory being watched
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Whether anything changed since the last call (non-blocking)
+    ///
+    /// An editor saving several files counts as one change.
+    pub fn changed(&self) -> bool {
+        let mut changed = false;
+        while self.receiver.try_recv().is_ok() {
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_icon_watcher_detects_new_icons() {
+        let dir = TempDir::new().unwrap();
+        let watcher = IconWatcher::new(dir.path().to_path_buf()).unwrap();
+        assert_eq!(watcher.dir(), dir.path());
+        assert!(!watcher.changed());
+
+        std::fs::create_dir(dir.path().join("apps")).unwrap();
+        std::fs::write(dir.path().join("apps/backup.svg"), "<svg/>").unwrap();
+
+        // Give the watcher time to detect the change
+        std::thread::sleep(Duration::from_millis(200));
+
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+    }
+
+    #[test]
+    fn test_icon_watcher_needs_directory() {
+        let dir = TempDir::new().unwrap();
+        assert!(IconWatcher::new(dir.path().join("missing")).is_err());
+    }
+}
@@ -66,22 +66,12 @@ impl InputState {
 
         // Send leave event to previous widget
         if let Some(old_index) = self.hovered_widget {
-            if let Some(widget) = widgets.get_mut(old_index) {
-                if widget.is_interactive() {
-                    widget.on_pointer_leave();
-                    debug!(widget_index = old_index, "Pointer left widget");
-                }
-            }
+            send_pointer_leave(old_index, widgets);
         }
 
         // Send enter event to new widget
         if let Some(new_index) = widget_index {
-            if let Some(widget) = widgets.get_mut(new_index) {
-                if widget.is_interactive() {
-                    widget.on_pointer_enter();
-                    debug!(widget_index = new_index, "Pointer entered widget");
-                }
-            }
+            send_pointer_enter(new_index, widgets);
         }
 
         self.hovered_widget = widget_index;
@@ -99,6 +89,133 @@ impl Default for InputState {
     }
 }
 
+fn send_pointer_enter(index: usize, widgets: &mut [Box<dyn Widget>]) {
+    if let Some(widget) = widgets.get_mut(index) {
+        if widget.is_interactive() {
+            widget.on_pointer_enter();
+            debug!(widget_index = index, "Pointer entered widget");
+        }
+    }
+}
+
+fn send_pointer_leave(index: usize, widgets: &mut [Box<dyn Widget>]) {
+    if let Some(widget) = widgets.get_mut(index) {
+        if widget.is_interactive() {
+            widget.on_pointer_leave();
+            debug!(widget_index = index, "Pointer left widget");
+        }
+    }
+}
+
+/// Pointer state for every seat
+///
+/// Each seat has a pointer of its own, with its own position and hovered
+/// widget. A widget under several pointers at once is entered when the
+/// first arrives and left when the last one goes.
+pub struct SeatInputs<S> {
+    seats: Vec<(S, InputState)>,
+}
+
+impl<S: PartialEq> SeatInputs<S> {
+    /// Create a tracker with no seats
+    pub fn new() -> Self {
+        Self { seats: Vec::new() }
+    }
+
+    /// Start tracking a seat
+    pub fn add(&mut self, seat: S) {
+        if self.get(&seat).is_none() {
+            self.seats.push((seat, InputState::new()));
+        }
+    }
+
+    /// Stop tracking a seat, leaving the widget its pointer was over
+    pub fn remove(&mut self, seat: &S, widgets: &mut [Box<dyn Widget>]) {
+        self.update_hover(seat, None, widgets);
+        self.seats.retain(|(s, _)| s != seat);
+    }
+
+    /// Pointer state of a seat
+    pub fn get(&self, seat: &S) -> Option<&InputState> {
+        self.seats
+            .iter()
+            .find(|(s, _)| s == seat)
+            .map(|(_, state)| state)
+    }
+
+    /// Mutable pointer state of a seat
+    pub fn get_mut(&mut self, seat: &S) -> Option<&mut InputState> {
+        self.seats
+            .iter_mut()
+            .find(|(s, _)| s == seat)
+            .map(|(_, state)| state)
+    }
+
+    /// Move a seat's pointer onto a widget, or off every widget with `None`
+    pub fn update_hover(
+        &mut self,
+        seat: &S,
+        widget_index: Option<usize>,
+        widgets: &mut [Box<dyn Widget>],
+    ) {
+        let Some(state) = self.get_mut(seat) else {
+            return;
+        };
+        let previous = state.hovered_widget;
+        if previous == widget_index {
+            return;
+        }
+        state.hovered_widget = widget_index;
+
+        if let Some(old_index) = previous {
+            if !self.is_hovered(old_index) {
+                send_pointer_leave(old_index, widgets);
+            }
+        }
+        if let Some(new_index) = widget_index {
+            if self.hover_count(new_index) == 1 {
+                send_pointer_enter(new_index, widgets);
+            }
+        }
+    }
+
+    /// Whether any seat's pointer is over a widget
+    pub fn is_hovered(&self, widget_index: usize) -> bool {
+        self.hover_count(widget_index) > 0
+    }
+
+    /// Take every pointer off its surface, e.g. before the surfaces or the
+    /// widgets under them are replaced
+    pub fn leave_all(&mut self, widgets: &mut [Box<dyn Widget>]) {
+        let mut hovered: Vec<usize> = self
+            .seats
+            .iter()
+            .filter_map(|(_, state)| state.hovered_widget)
+            .collect();
+        hovered.sort_unstable();
+        hovered.dedup();
+        for index in hovered {
+            send_pointer_leave(index, widgets);
+        }
+        for (_, state) in &mut self.seats {
+            state.pointer_leave();
+        }
+    }
+
+    fn hover_count(&self, widget_index: usize) -> usize {
+        self.seats
+            .iter()
+            .filter(|(_, state)| state.hovered_widget == Some(widget_index))
+            .count()
+    }
+}
+
+impl<S: PartialEq> Default for SeatInputs<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Hit-test to find which widget is at the given coordinates
 ///
 /// Returns the index of the widget that was hit, or None if no widget was hit.
@@ -165,6 +282,10 @@ pub fn execute_action(action: WidgetAction) -> Result<()> {
             info!(command = %command, "Executing action: RunCommand");
             run_command(&command)?;
         }
+        WidgetAction::CopyText(text) => {
+            info!(chars = text.chars().count(), "Executing action: CopyText");
+            copy_to_clipboard(&text)?;
+        }
         WidgetAction::NextItem => {
             debug!("Executing action: NextItem (handled by widget)");
         }
@@ -209,6 +330,25 @@ fn run_command(command: &str) -> Result<()> {
     Ok(())
 }
 
+/// Copy text to the clipboard with `wl-copy`
+fn copy_to_clipboard(text: &str) -> Result<()> {
+    use std::io::Write;
+
+    let mut child = std::process::Command::new("wl-copy")
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .context("Failed to run wl-copy (is wl-clipboard installed?)")?;
+    // Dropping stdin after writing closes it, which lets wl-copy take over
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .context("Failed to send text to wl-copy")?;
+    }
+
+    info!("Copied text to clipboard");
+    Ok(())
+}
+
 /// Convert Wayland button code to MouseButton
 pub fn button_code_to_mouse_button(code: u32) -> MouseButton {
     match code {
@@ -299,6 +439,90 @@ mod tests {
         assert_eq!(state.pointer_position(), (100.0, 200.0));
     }
 
+    /// Counts pointer enters and leaves as (enters, leaves)
+    struct HoverWidget {
+        hovers: std::sync::Arc<std::sync::Mutex<(u32, u32)>>,
+    }
+
+    impl Widget for HoverWidget {
+        fn info(&self) -> WidgetInfo {
+            WidgetInfo {
+                id: "hover",
+                name: "Hover",
+                preferred_height: 50.0,
+                min_height: 30.0,
+                expand: false,
+            }
+        }
+
+        fn update(&mut self) {}
+
+        fn content(&self) -> WidgetContent {
+            WidgetContent::Empty
+        }
+
+        fn is_interactive(&self) -> bool {
+            true
+        }
+
+        fn on_pointer_enter(&mut self) {
+            self.hovers.lock().unwrap().0 += 1;
+        }
+
+        fn on_pointer_leave(&mut self) {
+            self.hovers.lock().unwrap().1 += 1;
+        }
+    }
+
+    #[test]
+    fn test_seat_inputs_shared_hover() {
+        let hovers = std::sync::Arc::new(std::sync::Mutex::new((0, 0)));
+        let mut widgets: Vec<Box<dyn Widget>> = vec![Box::new(HoverWidget {
+            hovers: hovers.clone(),
+        })];
+        let mut seats = SeatInputs::new();
+        seats.add(1);
+        seats.add(2);
+        seats.add(2);
+
+        // Both pointers over the widget: entered once
+        seats.update_hover(&1, Some(0), &mut widgets);
+        seats.update_hover(&2, Some(0), &mut widgets);
+        assert_eq!(*hovers.lock().unwrap(), (1, 0));
+
+        // Left only once the second seat goes away too
+        seats.update_hover(&1, None, &mut widgets);
+        assert!(seats.is_hovered(0));
+        assert_eq!(*hovers.lock().unwrap(), (1, 0));
+        seats.remove(&2, &mut widgets);
+        assert!(!seats.is_hovered(0));
+        assert!(seats.get(&2).is_none());
+        assert_eq!(*hovers.lock().unwrap(), (1, 1));
+
+        // Unknown seats are ignored
+        seats.update_hover(&3, Some(0), &mut widgets);
+        assert!(!seats.is_hovered(0));
+    }
+
+    #[test]
+    fn test_seat_inputs_leave_all() {
+        let hovers = std::sync::Arc::new(std::sync::Mutex::new((0, 0)));
+        let mut widgets: Vec<Box<dyn Widget>> = vec![Box::new(HoverWidget {
+            hovers: hovers.clone(),
+        })];
+        let mut seats = SeatInputs::new();
+        seats.add("seat0");
+        seats.add("seat1");
+        seats.get_mut(&"seat0").unwrap().pointer_enter();
+        seats.update_hover(&"seat0", Some(0), &mut widgets);
+        seats.update_hover(&"seat1", Some(0), &mut widgets);
+
+        seats.leave_all(&mut widgets);
+        assert_eq!(*hovers.lock().unwrap(), (1, 1));
+        assert!(!seats.get(&"seat0").unwrap().is_pointer_over());
+        assert_eq!(seats.get(&"seat1").unwrap().hovered_widget(), None);
+    }
+
     #[test]
     fn test_hit_test_widgets() {
         let widgets: Vec<Box<dyn Widget>> = vec![
@@ -0,0 +1,304 @@
+//! Control socket for the running widget
+//!
+//! The widget listens on a Unix socket in `$XDG_RUNTIME_DIR`. Each connection
+//! sends one JSON request line and reads one JSON response line back, so the
+//! socket can be driven from scripts as well as the `perf` and `metrics`
+//! subcommands:
+//!
+//! ```text
+//! $ echo '{"command":"metrics"}' | socat - UNIX-CONNECT:$XDG_RUNTIME_DIR/cosmic-desktop-widget.sock
+//! ```
+//!
+//! The server never blocks the event loop: [`IpcServer::try_recv`] returns
+//! immediately when nobody is connecting.
+
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Socket file name inside the runtime directory
+pub const SOCKET_NAME: &str = "cosmic-desktop-widget.sock";
+
+/// How long a client gets to send its request line
+const READ_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// A command sent to the running widget
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum IpcRequest {
+    /// Show or hide the performance overlay; toggles when `enabled` is absent
+    Perf {
+        #[serde(default)]
+        enabled: Option<bool>,
+    },
+    /// Dump the current performance metrics
+    Metrics,
+    /// Show `config` (the TOML of a whole config file) without saving it
+    ///
+    /// It stays until `revert_config`, or until the config file changes.
+    ApplyConfig { config: String },
+    /// Go back to the saved config after `apply_config`
+    RevertConfig,
+}
+
+/// The widget's answer to a request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum IpcResponse {
+    /// Performance overlay state after a `perf` request
+    Perf { enabled: bool },
+    /// Metrics snapshot
+    Metrics { metrics: serde_json::Value },
+    /// The config from `apply_config` or `revert_config` is in use
+    Applied,
+    /// The request could not be handled
+    Error { message: String },
+}
+
+/// Default socket path: `$XDG_RUNTIME_DIR/cosmic-desktop-widget.sock`
+pub fn socket_path() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(SOCKET_NAME)
+}
+
+/// Listening side of the control socket
+///
+/// The socket file is removed when the server is dropped.
+#[derive(Debug)]
+pub struct IpcServer {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+/// A received request waiting for its response
+#[derive(Debug)]
+pub struct IpcConnection {
+    /// What the client asked for
+    pub request: IpcRequest,
+    stream: UnixStream,
+}
+
+impl IpcServer {
+    /// Listen on the default socket path
+    pub fn bind_default() -> Result<Self> {
+        Self::bind(&socket_path())
+    }
+
+    /// Listen on `path`, replacing a stale socket left by a crashed instance
+    pub fn bind(path: &Path) -> Result<Self> {
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                bail!(
+                    "Another instance is already listening on {}",
+                    path.display()
+                );
+            }
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+        }
+
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to bind control socket {}", path.display()))?;
+        listener
+            .set_nonblocking(true)
+            .context("Failed to make control socket non-blocking")?;
+
+        tracing::info!(path = %path.display(), "Control socket listening");
+        Ok(Self {
+            listener,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Socket path this server listens on
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Accept a pending request, if there is one
+    ///
+    /// Malformed requests are answered with an error here and skipped.
+    pub fn try_recv(&self) -> Option<IpcConnection> {
+        loop {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return None,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to accept control connection");
+                    return None;
+                }
+            };
+
+            match read_request(&stream) {
+                Ok(request) => {
+                    tracing::debug!(request = ?request, "Control request received");
+                    return Some(IpcConnection { request, stream });
+                }
+                Err(e) => {
+                    tracing::debug!(error = %e, "Invalid control request");
+                    let connection = IpcConnection {
+                        request: IpcRequest::Metrics,
+                        stream,
+                    };
+                    connection.reply(&IpcResponse::Error {
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl IpcConnection {
+    /// Send the response and close the connection
+    pub fn reply(mut self, response: &IpcResponse) {
+        let result = serde_json::to_string(response)
+            .map_err(std::io::Error::other)
+            .and_then(|line| writeln!(self.stream, "{}", line));
+        if let Err(e) = result {
+            tracing::debug!(error = %e, "Failed to send control response");
+        }
+    }
+}
+
+fn read_request(stream: &UnixStream) -> Result<IpcRequest> {
+    // The listener is non-blocking; the connection itself shouldn't be
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    serde_json::from_str(line.trim()).context("Malformed request")
+}
+
+/// Send a request to the running widget on the default socket
+pub fn send(request: &IpcRequest) -> Result<IpcResponse> {
+    send_to(&socket_path(), request)
+}
+
+/// Send a request to the widget listening on `path`
+pub fn send_to(path: &Path, request: &IpcRequest) -> Result<IpcResponse> {
+    let mut stream = UnixStream::connect(path).with_context(|| {
+        format!(
+            "Failed to connect to {}. Is cosmic-desktop-widget running?",
+            path.display()
+        )
+    })?;
+    writeln!(stream, "{}", serde_json::to_string(request)?)?;
+
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    serde_json::from_str(line.trim()).context("Malformed response from widget")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Serve requests on `server` until `client` finishes
+    fn serve<T: Send + 'static>(
+        server: &IpcServer,
+        client: std::thread::JoinHandle<T>,
+        respond: impl Fn(&IpcRequest) -> IpcResponse,
+    ) -> T {
+        while !client.is_finished() {
+            match server.try_recv() {
+                Some(connection) => {
+                    let response = respond(&connection.request);
+                    connection.reply(&response);
+                }
+                None => std::thread::sleep(Duration::from_millis(5)),
+            }
+        }
+        client.join().unwrap()
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(SOCKET_NAME);
+        let server = IpcServer::bind(&path).unwrap();
+        assert!(server.try_recv().is_none());
+
+        let client_path = path.clone();
+        let client = std::thread::spawn(move || {
+            send_to(&client_path, &IpcRequest::Perf { enabled: None }).unwrap()
+        });
+        let response = serve(&server, client, |request| match request {
+            IpcRequest::Perf { enabled } => IpcResponse::Perf {
+                enabled: enabled.unwrap_or(true),
+            },
+            _ => unreachable!(),
+        });
+        assert_eq!(response, IpcResponse::Perf { enabled: true });
+    }
+
+    #[test]
+    fn test_malformed_request_gets_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(SOCKET_NAME);
+        let server = IpcServer::bind(&path).unwrap();
+
+        let client_path = path.clone();
+        let client = std::thread::spawn(move || {
+            let mut stream = UnixStream::connect(&client_path).unwrap();
+            writeln!(stream, "{{\"command\":\"reboot\"}}").unwrap();
+            let mut line = String::new();
+            BufReader::new(&stream).read_line(&mut line).unwrap();
+            serde_json::from_str::<IpcResponse>(line.trim()).unwrap()
+        });
+        let response = serve(&server, client, |_| unreachable!());
+        assert!(matches!(response, IpcResponse::Error { .. }));
+    }
+
+    #[test]
+    fn test_stale_socket_replaced_and_removed() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(SOCKET_NAME);
+
+        // A socket file nobody listens on, as left by a crash
+        drop(UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let server = IpcServer::bind(&path).unwrap();
+        assert!(IpcServer::bind(&path).is_err());
+        drop(server);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_request_format() {
+        let json = serde_json::to_string(&IpcRequest::Perf {
+            enabled: Some(false),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"command":"perf","enabled":false}"#);
+        assert_eq!(
+            serde_json::from_str::<IpcRequest>(r#"{"command":"perf"}"#).unwrap(),
+            IpcRequest::Perf { enabled: None }
+        );
+        assert_eq!(
+            serde_json::from_str::<IpcRequest>(r#"{"command":"apply_config","config":"[panel]"}"#)
+                .unwrap(),
+            IpcRequest::ApplyConfig {
+                config: "[panel]".to_string()
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&IpcResponse::Applied).unwrap(),
+            r#"{"status":"applied"}"#
+        );
+    }
+}
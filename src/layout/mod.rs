@@ -1,5 +1,7 @@
 //! Layout system for positioning widgets within the container
 
+use serde::{Deserialize, Serialize};
+
 /// Widget position configuration
 #[derive(Debug, Clone, Copy)]
 pub struct WidgetPosition {
@@ -10,10 +12,81 @@ pub struct WidgetPosition {
 }
 
 /// Layout direction
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LayoutDirection {
+    /// Stacked top to bottom
+    #[default]
     Vertical,
+    /// Side by side, left to right
     Horizontal,
+    /// Rows of equal-width columns, filled left to right, top to bottom
+    Grid,
+}
+
+/// Where widgets sit across the layout direction
+///
+/// Horizontally in a vertical layout, vertically in a horizontal one or in
+/// a grid cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Alignment {
+    /// Fill the whole line (column width or row height)
+    #[default]
+    Stretch,
+    /// Keep their own size, at the left (or top)
+    Start,
+    /// Keep their own size, centered
+    Center,
+    /// Keep their own size, at the right (or bottom)
+    End,
+}
+
+impl Alignment {
+    /// Offset of an item of `size` within a line of `line` pixels
+    fn offset(self, size: f32, line: f32) -> f32 {
+        match self {
+            Self::Stretch | Self::Start => 0.0,
+            Self::Center => (line - size) / 2.0,
+            Self::End => line - size,
+        }
+    }
+}
+
+/// A widget to lay out: its height, and its width if it asks for one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutItem {
+    pub width: Option<f32>,
+    pub height: f32,
+    /// Grid columns the item covers
+    pub column_span: usize,
+    /// Grid rows the item covers
+    pub row_span: usize,
+}
+
+impl LayoutItem {
+    /// An item taking whatever width the layout gives it
+    pub fn new(height: f32) -> Self {
+        Self {
+            width: None,
+            height,
+            column_span: 1,
+            row_span: 1,
+        }
+    }
+
+    /// An item asking for a fixed width
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Cover several grid cells (ignored outside a grid)
+    pub fn with_span(mut self, columns: usize, rows: usize) -> Self {
+        self.column_span = columns.max(1);
+        self.row_span = rows.max(1);
+        self
+    }
 }
 
 /// Layout manager for arranging widgets
@@ -23,6 +96,9 @@ pub struct LayoutManager {
     padding: f32,
     spacing: f32,
     direction: LayoutDirection,
+    alignment: Alignment,
+    /// Columns in a grid layout
+    columns: usize,
 }
 
 impl LayoutManager {
@@ -33,6 +109,8 @@ impl LayoutManager {
             padding: 20.0,
             spacing: 10.0,
             direction: LayoutDirection::Vertical,
+            alignment: Alignment::Stretch,
+            columns: 2,
         }
     }
 
@@ -51,37 +129,102 @@ impl LayoutManager {
         self
     }
 
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_columns(mut self, columns: usize) -> Self {
+        self.columns = columns.max(1);
+        self
+    }
+
     /// Calculate positions for a list of widgets with given heights
     pub fn calculate_positions(&self, widget_heights: &[f32]) -> Vec<WidgetPosition> {
-        let mut positions = Vec::new();
+        let items: Vec<LayoutItem> = widget_heights.iter().map(|&h| LayoutItem::new(h)).collect();
+        self.arrange(&items)
+    }
+
+    /// Calculate positions for widgets that may ask for their own width
+    ///
+    /// In a horizontal layout, widgets with a width keep it and the rest
+    /// share what is left equally. In a vertical one a width only matters
+    /// when the alignment isn't [`Alignment::Stretch`].
+    pub fn arrange(&self, items: &[LayoutItem]) -> Vec<WidgetPosition> {
+        let mut positions = Vec::with_capacity(items.len());
         let available_width = self.container_width as f32 - (self.padding * 2.0);
 
         match self.direction {
             LayoutDirection::Vertical => {
                 let mut current_y = self.padding;
-                for &height in widget_heights {
+                for item in items {
+                    let width = match (self.alignment, item.width) {
+                        (Alignment::Stretch, _) | (_, None) => available_width,
+                        (_, Some(width)) => width.min(available_width),
+                    };
                     positions.push(WidgetPosition {
-                        x: self.padding,
+                        x: self.padding + self.alignment.offset(width, available_width),
                         y: current_y,
-                        width: available_width,
-                        height,
+                        width,
+                        height: item.height,
                     });
-                    current_y += height + self.spacing;
+                    current_y += item.height + self.spacing;
                 }
             }
             LayoutDirection::Horizontal => {
-                let total_widgets = widget_heights.len();
-                let widget_width = (available_width - (self.spacing * (total_widgets - 1) as f32))
-                    / total_widgets as f32;
+                let gaps = items.len().saturating_sub(1) as f32;
+                let fixed: f32 = items.iter().filter_map(|item| item.width).sum();
+                let flexible = items.iter().filter(|item| item.width.is_none()).count();
+                let flexible_width = if flexible > 0 {
+                    ((available_width - self.spacing * gaps - fixed) / flexible as f32).max(0.0)
+                } else {
+                    0.0
+                };
+                let row_height = items.iter().map(|item| item.height).fold(0.0, f32::max);
+
                 let mut current_x = self.padding;
-                for &height in widget_heights {
+                for item in items {
+                    let width = item.width.unwrap_or(flexible_width);
+                    let height = match self.alignment {
+                        Alignment::Stretch => row_height,
+                        _ => item.height,
+                    };
                     positions.push(WidgetPosition {
                         x: current_x,
-                        y: self.padding,
-                        width: widget_width,
+                        y: self.padding + self.alignment.offset(height, row_height),
+                        width,
+                        height,
+                    });
+                    current_x += width + self.spacing;
+                }
+            }
+            LayoutDirection::Grid => {
+                let columns = self.columns;
+                let gaps = (columns - 1) as f32;
+                let column_width =
+                    ((available_width - self.spacing * gaps) / columns as f32).max(0.0);
+                let (cells, row_heights) = self.grid_cells(items);
+                let span_height = |row: usize, rows: usize| {
+                    row_heights[row..row + rows].iter().sum::<f32>()
+                        + self.spacing * (rows - 1) as f32
+                };
+
+                for (item, &(row, column)) in items.iter().zip(&cells) {
+                    let span = item.column_span.min(columns);
+                    let cell_height = span_height(row, item.row_span);
+                    let height = match self.alignment {
+                        Alignment::Stretch => cell_height,
+                        _ => item.height.min(cell_height),
+                    };
+                    let top = self.padding
+                        + row_heights[..row].iter().sum::<f32>()
+                        + self.spacing * row as f32;
+                    positions.push(WidgetPosition {
+                        x: self.padding + column as f32 * (column_width + self.spacing),
+                        y: top + self.alignment.offset(height, cell_height),
+                        width: column_width * span as f32 + self.spacing * (span - 1) as f32,
                         height,
                     });
-                    current_x += widget_width + self.spacing;
                 }
             }
         }
@@ -89,6 +232,92 @@ impl LayoutManager {
         positions
     }
 
+    /// Place items in the grid, returning each one's (row, column) and the
+    /// height of every row
+    ///
+    /// Items fill the next free cells they fit in, in order, like CSS grid
+    /// auto-placement. A row is as tall as its tallest single-row item; an
+    /// item spanning rows that still doesn't fit makes its last row taller.
+    fn grid_cells(&self, items: &[LayoutItem]) -> (Vec<(usize, usize)>, Vec<f32>) {
+        let columns = self.columns;
+        let mut occupied: Vec<Vec<bool>> = Vec::new();
+        let mut cells = Vec::with_capacity(items.len());
+        let mut cursor = 0;
+
+        for item in items {
+            let span = item.column_span.min(columns);
+            let rows = item.row_span;
+            let free = |occupied: &Vec<Vec<bool>>, row: usize, column: usize| {
+                (row..row + rows).all(|r| {
+                    (column..column + span)
+                        .all(|c| !occupied.get(r).is_some_and(|cells: &Vec<bool>| cells[c]))
+                })
+            };
+
+            let mut index = cursor;
+            let (row, column) = loop {
+                let (row, column) = (index / columns, index % columns);
+                if column + span <= columns && free(&occupied, row, column) {
+                    break (row, column);
+                }
+                index += 1;
+            };
+
+            while occupied.len() < row + rows {
+                occupied.push(vec![false; columns]);
+            }
+            for line in &mut occupied[row..row + rows] {
+                line[column..column + span].fill(true);
+            }
+            cursor = index + span;
+            cells.push((row, column));
+        }
+
+        let mut row_heights = vec![0.0_f32; occupied.len()];
+        for (item, &(row, _)) in items.iter().zip(&cells) {
+            if item.row_span == 1 {
+                row_heights[row] = row_heights[row].max(item.height);
+            }
+        }
+        for (item, &(row, _)) in items.iter().zip(&cells) {
+            if item.row_span > 1 {
+                let last = row + item.row_span - 1;
+                let spanned = row_heights[row..=last].iter().sum::<f32>()
+                    + self.spacing * (item.row_span - 1) as f32;
+                if spanned < item.height {
+                    row_heights[last] += item.height - spanned;
+                }
+            }
+        }
+
+        (cells, row_heights)
+    }
+
+    /// Container height needed to fit widgets of the given heights
+    ///
+    /// Used to size a surface shared by a group of widgets.
+    pub fn required_height(&self, widget_heights: &[f32]) -> f32 {
+        let items: Vec<LayoutItem> = widget_heights.iter().map(|&h| LayoutItem::new(h)).collect();
+        self.required_height_for(&items)
+    }
+
+    /// Container height needed to fit `items`, including grid spans
+    pub fn required_height_for(&self, items: &[LayoutItem]) -> f32 {
+        let content = match self.direction {
+            LayoutDirection::Vertical => {
+                let gaps = items.len().saturating_sub(1) as f32;
+                items.iter().map(|item| item.height).sum::<f32>() + self.spacing * gaps
+            }
+            LayoutDirection::Horizontal => items.iter().map(|item| item.height).fold(0.0, f32::max),
+            LayoutDirection::Grid => {
+                let (_, row_heights) = self.grid_cells(items);
+                let gaps = row_heights.len().saturating_sub(1) as f32;
+                row_heights.iter().sum::<f32>() + self.spacing * gaps
+            }
+        };
+        content + self.padding * 2.0
+    }
+
     /// Get position for clock widget
     pub fn clock_position(&self, show_weather: bool) -> WidgetPosition {
         if show_weather {
@@ -179,6 +408,114 @@ mod tests {
         assert_eq!(positions[1].x, 205.0);
     }
 
+    #[test]
+    fn test_horizontal_width_distribution() {
+        let layout = LayoutManager::new(400, 100)
+            .with_padding(10.0)
+            .with_spacing(10.0)
+            .with_direction(LayoutDirection::Horizontal);
+
+        // 380 available, minus two gaps and the fixed 100 leaves 260 to share
+        let items = [
+            LayoutItem::new(40.0).with_width(100.0),
+            LayoutItem::new(60.0),
+            LayoutItem::new(20.0),
+        ];
+        let positions = layout.arrange(&items);
+        assert_eq!(positions[0].width, 100.0);
+        assert_eq!(positions[1].width, 130.0);
+        assert_eq!(positions[2].x, 260.0); // 10 + 100 + 10 + 130 + 10
+
+        // Stretched to the tallest widget by default
+        assert!(positions.iter().all(|p| p.height == 60.0 && p.y == 10.0));
+
+        let centered = layout.with_alignment(Alignment::Center).arrange(&items);
+        assert_eq!(centered[0].y, 20.0); // 10 + (60 - 40) / 2
+        assert_eq!(centered[0].height, 40.0);
+        assert_eq!(centered[1].y, 10.0);
+    }
+
+    #[test]
+    fn test_vertical_alignment() {
+        let layout = LayoutManager::new(400, 200)
+            .with_padding(10.0)
+            .with_alignment(Alignment::End);
+
+        let positions = layout.arrange(&[
+            LayoutItem::new(30.0).with_width(80.0),
+            LayoutItem::new(30.0),
+        ]);
+        assert_eq!(positions[0].x, 310.0); // 10 + 380 - 80
+        assert_eq!(positions[0].width, 80.0);
+        assert_eq!(positions[1].x, 10.0);
+        assert_eq!(positions[1].width, 380.0);
+    }
+
+    #[test]
+    fn test_grid_layout() {
+        let layout = LayoutManager::new(420, 0)
+            .with_padding(10.0)
+            .with_spacing(10.0)
+            .with_direction(LayoutDirection::Grid)
+            .with_columns(2);
+
+        // A 2x2 dashboard: 400 available, two 195 columns
+        let items = [
+            LayoutItem::new(60.0),
+            LayoutItem::new(40.0),
+            LayoutItem::new(80.0),
+            LayoutItem::new(50.0),
+        ];
+        let positions = layout.arrange(&items);
+        assert_eq!(positions[0].width, 195.0);
+        assert_eq!(positions[1].x, 215.0); // 10 + 195 + 10
+        assert_eq!(positions[1].height, 60.0); // stretched to the row
+        assert_eq!(positions[2].y, 80.0); // 10 + 60 + 10
+        assert_eq!(positions[3].height, 80.0);
+        assert_eq!(layout.required_height_for(&items), 170.0); // 60 + 80 + 10 + 20
+    }
+
+    #[test]
+    fn test_grid_spans() {
+        let layout = LayoutManager::new(420, 0)
+            .with_padding(10.0)
+            .with_spacing(10.0)
+            .with_direction(LayoutDirection::Grid)
+            .with_columns(2);
+
+        // Calendar down the left, two small widgets stacked beside it, then
+        // a full-width row
+        let items = [
+            LayoutItem::new(200.0).with_span(1, 2),
+            LayoutItem::new(40.0),
+            LayoutItem::new(40.0),
+            LayoutItem::new(30.0).with_span(2, 1),
+        ];
+        let positions = layout.arrange(&items);
+        assert_eq!((positions[1].x, positions[1].y), (215.0, 10.0));
+        assert_eq!((positions[2].x, positions[2].y), (215.0, 60.0));
+
+        // The second row grows so the calendar fits: 40 + 10 + 150
+        assert_eq!(positions[0].height, 200.0);
+        assert_eq!(positions[2].height, 150.0);
+
+        assert_eq!(positions[3].x, 10.0);
+        assert_eq!(positions[3].y, 220.0);
+        assert_eq!(positions[3].width, 400.0);
+    }
+
+    #[test]
+    fn test_required_height() {
+        let layout = LayoutManager::new(400, 0)
+            .with_padding(10.0)
+            .with_spacing(5.0);
+        assert_eq!(layout.required_height(&[30.0, 20.0, 25.0]), 105.0); // 75 + 2*5 + 2*10
+        assert_eq!(layout.required_height(&[]), 20.0);
+
+        let row = layout.with_direction(LayoutDirection::Horizontal);
+        assert_eq!(row.required_height(&[30.0, 50.0]), 70.0);
+    }
+
     #[test]
     fn test_clock_position_with_weather() {
         let layout = LayoutManager::new(400, 150);
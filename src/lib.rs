@@ -7,17 +7,29 @@
 #![warn(missing_docs)]
 
 pub mod audio;
+pub mod autostart;
+pub mod benchmark;
 pub mod config;
 pub mod config_watcher;
+pub mod connectivity;
+pub mod crash;
 pub mod error;
+pub mod http;
+pub mod i18n;
 pub mod icons;
 pub mod input;
+pub mod ipc;
 pub mod layout;
 pub mod metrics;
+pub mod notifications;
 pub mod panel;
+pub mod plugin_watcher;
 pub mod position;
 pub mod render;
+pub mod session;
+pub mod state;
 pub mod surface;
+pub mod template;
 pub mod text;
 pub mod theme;
 pub mod update;
@@ -31,17 +43,24 @@ pub use config::{
     ThemeStyle,
 };
 pub use config_watcher::{ConfigReloadEvent, ConfigWatcher};
-pub use error::{ConfigError, WeatherError, WidgetError};
+pub use error::{ConfigError, QuoteError, WeatherError, WidgetError};
 pub use input::{
     button_code_to_mouse_button, execute_action, hit_test_widgets, scroll_to_direction, InputState,
+    SeatInputs,
 };
-pub use layout::{LayoutDirection, LayoutManager, WidgetPosition};
+pub use layout::{Alignment, LayoutDirection, LayoutItem, LayoutManager, WidgetPosition};
 pub use metrics::{CacheMetrics, RenderMetrics, Timer, WidgetMetrics};
-pub use panel::{MarginAdjustments, PanelAnchor, PanelDetection, PanelInfo, PanelSize};
+pub use notifications::Notification;
+pub use panel::{
+    MarginAdjustments, PanelAnchor, PanelBackend, PanelBackendKind, PanelDetection, PanelInfo,
+    PanelSize, PanelWatcher,
+};
+pub use plugin_watcher::{PluginReloadEvent, PluginWatcher};
 pub use position::Position;
+pub use template::{Template, TemplateVars};
 pub use theme::{Color, Theme};
 pub use update::{UpdateFlags, UpdateScheduler};
-pub use audio::{AudioPlayer, SoundConfig, SoundEffect};
+pub use audio::{AudioPlayer, SoundConfig, SoundEffect, SoundPriority};
 pub use text::FontWeight;
 pub use widget::{
     ClockWidget, CountdownWidget, DynWidgetFactory, FontSize, MouseButton, ProgressBar,
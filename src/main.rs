@@ -7,11 +7,12 @@ use smithay_client_toolkit::{
     compositor::{CompositorHandler, CompositorState},
     delegate_compositor, delegate_layer, delegate_output, delegate_pointer, delegate_registry,
     delegate_seat, delegate_shm,
-    output::{OutputHandler, OutputState},
+    globals::GlobalData,
+    output::{OutputHandler, OutputInfo, OutputState},
     registry::{ProvidesRegistryState, RegistryState},
     registry_handlers,
     seat::{
-        pointer::{PointerEvent, PointerEventKind, PointerHandler},
+        pointer::{PointerData, PointerEvent, PointerEventKind, PointerHandler},
         Capability, SeatHandler, SeatState,
     },
     shell::{
@@ -23,25 +24,60 @@ use smithay_client_toolkit::{
     },
     shm::{Shm, ShmHandler},
 };
+use std::collections::BTreeMap;
 use std::time::Duration;
 use wayland_client::{
     globals::registry_queue_init,
-    protocol::{wl_output, wl_surface},
-    Connection, QueueHandle,
+    protocol::{wl_output, wl_pointer, wl_seat, wl_surface},
+    Connection, Proxy, QueueHandle,
+};
+use wayland_protocols::wp::idle_inhibit::zv1::client::{
+    zwp_idle_inhibit_manager_v1::ZwpIdleInhibitManagerV1, zwp_idle_inhibitor_v1::ZwpIdleInhibitorV1,
+};
+use wayland_protocols_plasma::blur::client::{
+    org_kde_kwin_blur::OrgKdeKwinBlur, org_kde_kwin_blur_manager::OrgKdeKwinBlurManager,
+};
+use wayland_protocols_wlr::output_power_management::v1::client::{
+    zwlr_output_power_manager_v1::ZwlrOutputPowerManagerV1, zwlr_output_power_v1::ZwlrOutputPowerV1,
 };
 
 use cosmic_desktop_widget::{
-    config::Config,
+    autostart, benchmark, button_code_to_mouse_button,
+    config::{Config, Margin},
     config_watcher::ConfigWatcher,
-    metrics::{Timer, WidgetMetrics, TARGET_RENDER_TIME_MS},
-    panel::{MarginAdjustments, PanelDetection},
-    render::Renderer,
-    surface::WidgetSurface,
-    update::UpdateScheduler,
-    widget::{ClockWidget, WeatherWidget, Widget, WidgetRegistry},
-    InputState,
+    connectivity::{Connectivity, ConnectivityWatcher},
+    crash, execute_action, http, i18n,
+    icons::IconWatcher,
+    ipc::{self, IpcRequest, IpcResponse, IpcServer},
+    layout::{LayoutItem, LayoutManager},
+    metrics::{PerfStats, Timer, WidgetMetrics, TARGET_RENDER_TIME_MS},
+    panel::{PanelDetection, PanelWatcher},
+    plugin_watcher::{PluginReloadEvent, PluginWatcher},
+    render::{GroupMember, Renderer},
+    scroll_to_direction,
+    session::{SessionState, SessionWatcher},
+    state::{self, CollapseState, PlacementMemory, SavedPlacement, WidgetStates},
+    surface::{Collapse, WidgetSurface, COLLAPSE_DURATION},
+    theme::{files::BUILTIN_THEMES, Color, PaletteWatcher, Theme},
+    update::{DataService, TaskResult, UpdateScheduler},
+    wayland::{
+        outputs, BlurState, IdleInhibitState, OutputPowerHandler, OutputPowerState, OutputsModel,
+        Placement,
+    },
+    widget::{
+        google_calendar, ClockWidget, FailedWidget, Freshness, GoogleCredentials, MouseButton,
+        Recorder, Recording, ScrollDirection, SoundCue, WeatherWidget, Widget, WidgetInstance,
+        WidgetManifest, WidgetRegistry,
+    },
+    AudioPlayer, Notification, SeatInputs, SoundEffect,
 };
 
+/// Instance id of the performance overlay added by `perf on`
+const PERF_OVERLAY_ID: &str = "perf-overlay";
+
+/// How often to look for an icon directory created or removed while running
+const ICON_DIR_POLL: Duration = Duration::from_secs(5);
+
 /// Main application state
 struct DesktopWidget {
     // Wayland states
@@ -51,6 +87,9 @@ struct DesktopWidget {
     shm_state: Shm,
     layer_shell: LayerShell,
     seat_state: SeatState,
+    blur_state: BlurState,
+    output_power: OutputPowerState,
+    idle_inhibit: IdleInhibitState,
 
     // Multiple widget surfaces (one per widget)
     widget_surfaces: Vec<WidgetSurface>,
@@ -71,17 +110,65 @@ struct DesktopWidget {
     // Update coordination
     update_scheduler: UpdateScheduler,
 
+    // Polls every widget's data source off the event loop
+    data: DataService,
+
     // Configuration
     config: Config,
 
-    // Panel-aware margins
-    panel_margins: MarginAdjustments,
+    // Desktop panels, whose space is added to each surface's margins
+    panels: PanelDetection,
 
     // Performance metrics
     metrics: WidgetMetrics,
 
-    // Input handling
-    input_state: InputState,
+    // Input handling, per seat
+    seat_inputs: SeatInputs<wl_seat::WlSeat>,
+    pointers: Vec<wl_pointer::WlPointer>,
+
+    // Collapsed widgets, remembered across restarts
+    collapse_state: CollapseState,
+
+    // Widget placement per monitor setup, and the current setup's fingerprint
+    placements: PlacementMemory,
+    output_setup: String,
+    // Placement as in the config file, to tell what a reload moved
+    file_placements: BTreeMap<String, SavedPlacement>,
+
+    // What widgets asked to keep across restarts, by instance id
+    widget_states: WidgetStates,
+
+    // Sound playback (None when sounds are disabled)
+    audio: Option<AudioPlayer>,
+
+    // Performance overlay toggled over the control socket
+    perf_overlay: bool,
+
+    // Session idle/lock state; widgets aren't updated or redrawn while paused
+    session: SessionState,
+    paused: bool,
+
+    // Surfaces are removed while the screen is locked
+    hidden: bool,
+
+    // Network state; network widgets don't fetch while offline
+    connectivity: Connectivity,
+
+    // Last known rotation of each output
+    output_transforms: Vec<(wl_output::WlOutput, wl_output::Transform)>,
+
+    // Watches the user icon directory, while it exists
+    icon_watcher: Option<IconWatcher>,
+
+    // `--record`: widget content is appended here as it changes
+    recorder: Option<Recorder>,
+    // `--replay`: widgets in here show their recorded content instead
+    replay: Option<Recording>,
+
+    // Per-widget accents and freshness of the surface being drawn, kept
+    // between frames so drawing doesn't allocate
+    frame_accents: Vec<Option<Color>>,
+    frame_freshness: Vec<Freshness>,
 
     // State
     first_frame: bool,
@@ -103,9 +190,13 @@ impl CompositorHandler for DesktopWidget {
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
         _surface: &wl_surface::WlSurface,
-        _new_transform: wl_output::Transform,
+        new_transform: wl_output::Transform,
     ) {
-        // Handle rotation if needed
+        // This is only a hint for direct scanout. Layer surfaces are laid
+        // out in the output's logical, already rotated space, so buffers
+        // are drawn upright and the compositor rotates them; rotating them
+        // ourselves would buy nothing for a few small widgets
+        tracing::debug!(transform = ?new_transform, "Preferred buffer transform changed");
     }
 
     fn frame(
@@ -117,7 +208,9 @@ impl CompositorHandler for DesktopWidget {
     ) {
         // Find which widget surface this frame callback is for
         if let Some(idx) = self.widget_surfaces.iter().position(|s| &s.wl_surface == surface) {
-            self.draw_widget_surface(idx, qh);
+            if !self.step_animation(idx, qh) {
+                self.draw_widget_surface(idx, qh);
+            }
         }
     }
 }
@@ -130,25 +223,78 @@ impl OutputHandler for DesktopWidget {
     fn new_output(
         &mut self,
         _conn: &Connection,
-        _qh: &QueueHandle<Self>,
-        _output: wl_output::WlOutput,
+        qh: &QueueHandle<Self>,
+        output: wl_output::WlOutput,
     ) {
+        self.output_power.add_output(&output, qh);
+        if let Some(info) = self.output_state.info(&output) {
+            self.output_transforms.push((output, info.transform));
+        }
+        self.outputs_changed(qh);
     }
 
     fn update_output(
         &mut self,
         _conn: &Connection,
-        _qh: &QueueHandle<Self>,
-        _output: wl_output::WlOutput,
+        qh: &QueueHandle<Self>,
+        output: wl_output::WlOutput,
     ) {
+        let Some(info) = self.output_state.info(&output) else {
+            return;
+        };
+        let known = self
+            .output_transforms
+            .iter_mut()
+            .find(|(o, _)| o == &output);
+        match known {
+            Some((_, transform)) if *transform == info.transform => {}
+            Some((_, transform)) => {
+                *transform = info.transform;
+                self.output_rotated(&info, qh);
+            }
+            None => self.output_transforms.push((output, info.transform)),
+        }
     }
 
     fn output_destroyed(
         &mut self,
         _conn: &Connection,
-        _qh: &QueueHandle<Self>,
-        _output: wl_output::WlOutput,
+        qh: &QueueHandle<Self>,
+        output: wl_output::WlOutput,
+    ) {
+        self.output_power.remove_output(&output);
+        self.output_transforms.retain(|(o, _)| o != &output);
+        self.outputs_changed(qh);
+    }
+}
+
+impl OutputPowerHandler for DesktopWidget {
+    fn output_power_state(&mut self) -> &mut OutputPowerState {
+        &mut self.output_power
+    }
+
+    fn output_power_changed(
+        &mut self,
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+        output: &wl_output::WlOutput,
+        on: bool,
     ) {
+        if !on {
+            return;
+        }
+
+        // Surfaces on this output missed every frame while it was off
+        let on_output: Vec<usize> = self
+            .widget_surfaces
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| OutputPowerState::surface_on_output(&s.wl_surface, output))
+            .map(|(i, _)| i)
+            .collect();
+        for idx in on_output {
+            self.draw_widget_surface(idx, qh);
+        }
     }
 }
 
@@ -190,7 +336,7 @@ impl LayerShellHandler for DesktopWidget {
         surface.configured = true;
 
         tracing::info!(
-            widget_index = surface.widget_index,
+            widgets = ?surface.widget_indices,
             width = surface.width,
             height = surface.height,
             "Surface configured"
@@ -212,25 +358,27 @@ impl SeatHandler for DesktopWidget {
         &mut self.seat_state
     }
 
-    fn new_seat(
-        &mut self,
-        _conn: &Connection,
-        _qh: &QueueHandle<Self>,
-        _seat: wayland_client::protocol::wl_seat::WlSeat,
-    ) {
-        tracing::debug!("New seat available");
+    fn new_seat(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, seat: wl_seat::WlSeat) {
+        tracing::debug!(seat = %seat.id(), "New seat available");
+        self.seat_inputs.add(seat);
     }
 
     fn new_capability(
         &mut self,
         _conn: &Connection,
         qh: &QueueHandle<Self>,
-        seat: wayland_client::protocol::wl_seat::WlSeat,
+        seat: wl_seat::WlSeat,
         capability: Capability,
     ) {
         if capability == Capability::Pointer {
-            tracing::info!("Pointer capability available, initializing pointer");
-            let _ = self.seat_state.get_pointer(qh, &seat);
+            tracing::info!(seat = %seat.id(), "Pointer capability available, initializing pointer");
+            // Seats already present at startup may not have gone through
+            // new_seat
+            self.seat_inputs.add(seat.clone());
+            match self.seat_state.get_pointer(qh, &seat) {
+                Ok(pointer) => self.pointers.push(pointer),
+                Err(e) => tracing::warn!(error = %e, "Failed to get pointer"),
+            }
         }
     }
 
@@ -238,22 +386,19 @@ impl SeatHandler for DesktopWidget {
         &mut self,
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
-        _seat: wayland_client::protocol::wl_seat::WlSeat,
+        seat: wl_seat::WlSeat,
         capability: Capability,
     ) {
         if capability == Capability::Pointer {
-            tracing::info!("Pointer capability removed");
-            // Pointer cleanup is handled automatically by SeatState
+            tracing::info!(seat = %seat.id(), "Pointer capability removed");
+            self.release_pointer(&seat);
         }
     }
 
-    fn remove_seat(
-        &mut self,
-        _conn: &Connection,
-        _qh: &QueueHandle<Self>,
-        _seat: wayland_client::protocol::wl_seat::WlSeat,
-    ) {
-        tracing::debug!("Seat removed");
+    fn remove_seat(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, seat: wl_seat::WlSeat) {
+        tracing::debug!(seat = %seat.id(), "Seat removed");
+        self.release_pointer(&seat);
+        self.seat_inputs.remove(&seat, &mut self.widgets);
     }
 }
 
@@ -261,32 +406,63 @@ impl PointerHandler for DesktopWidget {
     fn pointer_frame(
         &mut self,
         _conn: &Connection,
-        _qh: &QueueHandle<Self>,
-        _pointer: &wayland_client::protocol::wl_pointer::WlPointer,
+        qh: &QueueHandle<Self>,
+        pointer: &wl_pointer::WlPointer,
         events: &[PointerEvent],
     ) {
+        let Some(seat) = pointer
+            .data::<PointerData>()
+            .map(|data| data.seat().clone())
+        else {
+            return;
+        };
+
+        // Every event names the surface it's for, so events from any seat
+        // go to the right widget; only hover state is kept per seat
         for event in events {
             match &event.kind {
                 PointerEventKind::Enter { .. } => {
-                    self.input_state.pointer_enter();
+                    if let Some(state) = self.seat_inputs.get_mut(&seat) {
+                        state.pointer_enter();
+                        state.update_position(event.position.0, event.position.1);
+                    }
+                    self.hover_widget(&seat, &event.surface, event.position);
                 }
                 PointerEventKind::Leave { .. } => {
-                    self.input_state.pointer_leave();
-                    self.input_state
-                        .update_hover(None, &mut self.widgets);
+                    self.seat_inputs
+                        .update_hover(&seat, None, &mut self.widgets);
+                    if let Some(state) = self.seat_inputs.get_mut(&seat) {
+                        state.pointer_leave();
+                    }
                 }
                 PointerEventKind::Motion { time: _ } => {
                     let (x, y) = event.position;
-                    self.input_state.update_position(x, y);
-                    // Hover state tracking disabled with multi-surface architecture
+                    if let Some(state) = self.seat_inputs.get_mut(&seat) {
+                        state.update_position(x, y);
+                    }
+                    self.hover_widget(&seat, &event.surface, event.position);
                 }
                 PointerEventKind::Press {
                     time: _,
-                    button: _,
+                    button,
                     serial: _,
                 } => {
-                    // Mouse input handling is disabled for desktop widgets
-                    // (KeyboardInteractivity::None means no input events)
+                    // Left click collapses or expands a collapsible widget;
+                    // anything else is the widget's own
+                    let button = button_code_to_mouse_button(*button);
+                    if let Some(idx) = self
+                        .widget_surfaces
+                        .iter()
+                        .position(|s| s.wl_surface == event.surface)
+                    {
+                        if button == MouseButton::Left
+                            && self.widget_surfaces[idx].collapse.is_some()
+                        {
+                            self.toggle_collapsed(idx, qh);
+                        } else {
+                            self.click_widget(idx, event.position, button, qh);
+                        }
+                    }
                 }
                 PointerEventKind::Release { .. } => {
                     // Currently no action on release
@@ -294,11 +470,12 @@ impl PointerHandler for DesktopWidget {
                 PointerEventKind::Axis {
                     time: _,
                     horizontal: _,
-                    vertical: _,
+                    vertical,
                     source: _,
                 } => {
-                    // Scroll input handling is disabled for desktop widgets
-                    // (KeyboardInteractivity::None means no input events)
+                    if let Some(direction) = scroll_to_direction(vertical.absolute) {
+                        self.scroll_widget(&event.surface, event.position, direction, qh);
+                    }
                 }
             }
         }
@@ -306,6 +483,7 @@ impl PointerHandler for DesktopWidget {
 }
 
 impl DesktopWidget {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         registry_state: RegistryState,
         output_state: OutputState,
@@ -313,14 +491,23 @@ impl DesktopWidget {
         shm_state: Shm,
         layer_shell: LayerShell,
         seat_state: SeatState,
+        blur_state: BlurState,
+        output_power: OutputPowerState,
+        idle_inhibit: IdleInhibitState,
+        data: DataService,
         config: Config,
     ) -> Self {
         // Get theme from config
         let theme = config.get_theme();
+        if theme.blur_enabled && !blur_state.is_available() {
+            tracing::info!(
+                "Compositor does not support blur, theme background will be translucent only"
+            );
+        }
 
-        // Detect COSMIC panels to avoid overlap
-        let panel_detection = PanelDetection::detect();
-        let panel_margins = panel_detection.margin_adjustments();
+        // Detect desktop panels to avoid overlap
+        let panels = PanelDetection::detect_with(config.panel.panel_detection);
+        let panel_margins = panels.margin_adjustments();
         tracing::info!(
             top = panel_margins.top,
             bottom = panel_margins.bottom,
@@ -330,7 +517,7 @@ impl DesktopWidget {
         );
 
         // Create widgets using the new registry system
-        let registry = WidgetRegistry::with_builtins();
+        let registry = WidgetRegistry::with_user_widgets();
         let mut widgets: Vec<Box<dyn Widget>> = Vec::new();
         let mut clock_widget: Option<ClockWidget> = None;
         let mut weather_widget: Option<WeatherWidget> = None;
@@ -399,6 +586,7 @@ impl DesktopWidget {
                         error = %e,
                         "Failed to create widget"
                     );
+                    widgets.push(Box::new(FailedWidget::new(&e)));
                 }
             }
         }
@@ -415,6 +603,16 @@ impl DesktopWidget {
             min_update_interval_ms = min_interval.as_millis(),
             "Widgets initialized"
         );
+        for widget in &mut widgets {
+            widget.on_start();
+        }
+
+        let mut renderer = Renderer::with_theme(theme);
+        renderer.set_icon_dir(config.icons_dir());
+        let icon_watcher = renderer.icon_dir().and_then(watch_icons);
+
+        let audio = create_audio_player(&config);
+        let file_placements = state::placements_of(&config.widgets);
 
         Self {
             registry_state,
@@ -423,8 +621,11 @@ impl DesktopWidget {
             shm_state,
             layer_shell,
             seat_state,
+            blur_state,
+            output_power,
+            idle_inhibit,
             widget_surfaces: Vec::new(), // Created separately
-            renderer: Renderer::with_theme(theme),
+            renderer,
             widgets,
             widget_positions: Vec::new(), // Populated during first layout
             clock_widget,
@@ -433,50 +634,205 @@ impl DesktopWidget {
                 Duration::from_secs(1),   // Clock updates every second
                 Duration::from_secs(600), // Default weather interval
             ),
+            data,
             config,
-            panel_margins,
+            panels,
             metrics: WidgetMetrics::new(),
-            input_state: InputState::new(),
+            seat_inputs: SeatInputs::new(),
+            pointers: Vec::new(),
+            collapse_state: CollapseState::load(),
+            placements: PlacementMemory::load(),
+            output_setup: String::new(),
+            file_placements,
+            widget_states: WidgetStates::load(),
+            audio,
+            perf_overlay: false,
+            session: SessionState::default(),
+            paused: false,
+            hidden: false,
+            output_transforms: Vec::new(),
+            connectivity: Connectivity::default(),
+            icon_watcher,
+            recorder: None,
+            replay: None,
+            frame_accents: Vec::new(),
+            frame_freshness: Vec::new(),
             first_frame: true,
         }
     }
 
     /// Create Layer Shell surfaces for all enabled widgets
+    ///
+    /// Widgets sharing a `group` get one surface between them, sized to fit
+    /// them as arranged by the [`LayoutManager`] and placed like its first
+    /// widget.
     fn create_widget_surfaces(&mut self, qh: &QueueHandle<Self>) {
         self.widget_surfaces.clear();
+        if self.hidden {
+            tracing::debug!("Screen locked, not creating widget surfaces");
+            return;
+        }
 
-        for (widget_index, widget_config) in self.config.widgets.iter().enumerate() {
-            if !widget_config.enabled {
-                continue;
-            }
+        // Don't reserve space; with a zone of 0 instead of -1 the compositor
+        // also moves the widgets clear of other bars
+        let exclusive_zone = if self
+            .config
+            .panel
+            .panel_detection
+            .backend()
+            .uses_exclusive_zones()
+        {
+            0
+        } else {
+            -1
+        };
+
+        let model = OutputsModel::from_state(&self.output_state);
+        let mut placements = Vec::new();
+
+        let instances: Vec<&WidgetInstance> = self.config.enabled_widgets().collect();
+        for members in self.config.surface_groups() {
+            let widget_index = members[0];
+            let widget_config = instances[widget_index];
 
             // Get effective settings (widget-specific or panel defaults)
             let position = widget_config.effective_position(&self.config.panel.position);
-            let width = widget_config.effective_width(self.config.panel.width);
-            let height = widget_config.effective_height(self.config.panel.height);
+            let group = widget_config.group.as_deref();
+            let group_config = group.map(|group| self.config.group(group));
+            let group_width = group_config.as_ref().and_then(|g| g.width);
+            let width = group_width
+                .unwrap_or_else(|| widget_config.effective_width(self.config.panel.width));
             let opacity = widget_config.effective_opacity(
                 self.config.panel.background_opacity.unwrap_or(0.9)
             );
 
-            // Get effective margins (widget-specific or panel defaults)
-            let margin = widget_config.effective_margin(&self.config.panel.margin);
+            // A group lays its widgets out at their own (or preferred) sizes;
+            // the first one's width is the surface's unless the group sets it
+            let (height, layout) = match group_config {
+                Some(group_config) => {
+                    let items: Vec<LayoutItem> = members
+                        .iter()
+                        .map(|&i| {
+                            let preferred = self
+                                .widgets
+                                .get(i)
+                                .map_or(self.config.panel.height as f32, |w| {
+                                    w.info().preferred_height
+                                });
+                            let item = LayoutItem::new(
+                                instances[i].height.map_or(preferred, |h| h as f32),
+                            )
+                            .with_span(
+                                instances[i].column_span.unwrap_or(1) as usize,
+                                instances[i].row_span.unwrap_or(1) as usize,
+                            );
+                            match instances[i].width {
+                                Some(w) if i != widget_index || group_width.is_some() => {
+                                    item.with_width(w as f32)
+                                }
+                                _ => item,
+                            }
+                        })
+                        .collect();
+                    let layout = LayoutManager::new(width, 0)
+                        .with_padding(self.config.panel.padding)
+                        .with_spacing(self.config.panel.spacing)
+                        .with_direction(group_config.direction)
+                        .with_alignment(group_config.align)
+                        .with_columns(group_config.columns.unwrap_or(2) as usize);
+                    let height = layout.required_height_for(&items).ceil() as u32;
+                    (height, layout.arrange(&items))
+                }
+                None => (
+                    widget_config.effective_height(self.config.panel.height),
+                    Vec::new(),
+                ),
+            };
+
+            // A collapsible widget starts the way it was left
+            let collapse = (group.is_none() && widget_config.collapsible).then(|| Collapse {
+                compact: widget_config.effective_compact_height(height),
+                expanded: height,
+                collapsed: self
+                    .collapse_state
+                    .is_collapsed(&widget_config.instance_id()),
+            });
+            let height = collapse.map_or(height, |collapse| collapse.height());
+            if let (Some(collapse), Some(widget)) = (collapse, self.widgets.get_mut(widget_index)) {
+                widget.set_compact(collapse.collapsed);
+            }
+
+            let (mut margin_top, mut margin_right, mut margin_bottom, mut margin_left) =
+                self.surface_margins(widget_config);
+            let geometry = model.for_widget(widget_config.output.as_deref());
+
+            // Coordinates past the edge of the output would put an absolute
+            // widget partly off screen, so it's kept on where possible
+            if let (true, Some(geometry)) = (widget_config.is_absolute(), geometry) {
+                let margin = Margin {
+                    top: margin_top,
+                    right: margin_right,
+                    bottom: margin_bottom,
+                    left: margin_left,
+                };
+                let clamped = geometry.clamp_margin(position, &margin, width, height);
+                if clamped != margin {
+                    tracing::warn!(
+                        widget = %widget_config.instance_id(),
+                        output = %geometry.name,
+                        "Absolute position is off the output, moving the widget onto it"
+                    );
+                }
+                (margin_top, margin_right, margin_bottom, margin_left) =
+                    (clamped.top, clamped.right, clamped.bottom, clamped.left);
+            }
+            if let Some(geometry) = geometry {
+                let margin = Margin {
+                    top: margin_top,
+                    right: margin_right,
+                    bottom: margin_bottom,
+                    left: margin_left,
+                };
+                let (x, y) = geometry.place(position, &margin, width, height);
+                placements.push((
+                    widget_config.instance_id(),
+                    Placement {
+                        x,
+                        y,
+                        width,
+                        height,
+                    },
+                ));
+            }
 
-            // Combine with auto-detected panel margins
-            let margin_top = margin.top + self.panel_margins.top;
-            let margin_right = margin.right + self.panel_margins.right;
-            let margin_bottom = margin.bottom + self.panel_margins.bottom;
-            let margin_left = margin.left + self.panel_margins.left;
+            // A widget pinned to an output that isn't connected goes wherever
+            // the compositor puts it, until that output shows up
+            let output = widget_config.output.as_deref().and_then(|name| {
+                let output = outputs::find_output(&self.output_state, name);
+                if output.is_none() {
+                    tracing::warn!(
+                        output = name,
+                        connected = ?outputs::output_names(&self.output_state),
+                        "Output not connected, letting the compositor place the widget"
+                    );
+                }
+                output
+            });
 
             // Create Wayland surface
             let wl_surface = self.compositor_state.create_surface(qh);
 
             // Create Layer Shell surface
+            let namespace = match group {
+                Some(group) => format!("cosmic-widget-{}", group),
+                None => format!("cosmic-widget-{}", widget_index),
+            };
             let layer = self.layer_shell.create_layer_surface(
                 qh,
                 wl_surface.clone(),
                 Layer::Bottom, // Below windows, above wallpaper
-                Some(format!("cosmic-widget-{}", widget_index)),
-                None, // All outputs
+                Some(namespace),
+                output.as_ref(),
             );
 
             // Configure position using position enum
@@ -485,12 +841,23 @@ impl DesktopWidget {
             layer.set_size(width, height);
             layer.set_margin(margin_top, margin_right, margin_bottom, margin_left);
             layer.set_keyboard_interactivity(KeyboardInteractivity::None);
-            layer.set_exclusive_zone(-1); // Don't reserve space
+            let reserved = widget_config
+                .reserve_space
+                .then(|| position.exclusive_zone(width, height))
+                .flatten();
+            if widget_config.reserve_space && reserved.is_none() {
+                tracing::warn!(
+                    widget = %widget_config.instance_id(),
+                    position = %position,
+                    "reserve_space needs a top-center, bottom-center, center-left or center-right widget"
+                );
+            }
+            layer.set_exclusive_zone(reserved.unwrap_or(exclusive_zone));
 
             layer.commit();
 
             // Create widget surface
-            let surface = WidgetSurface::new(
+            let mut surface = WidgetSurface::new(
                 layer,
                 wl_surface,
                 width,
@@ -499,9 +866,19 @@ impl DesktopWidget {
                 position,
                 opacity,
             );
+            if let Some(group) = group {
+                surface = surface.with_group(group, members, layout);
+            }
+            if let Some(collapse) = collapse {
+                surface = surface.with_collapse(collapse);
+            }
+            if reserved.is_some() {
+                surface = surface.with_reserve_space();
+            }
 
             tracing::info!(
-                widget_index = widget_index,
+                widgets = ?surface.widget_indices,
+                group = ?surface.group,
                 position = %position,
                 width = width,
                 height = height,
@@ -511,8 +888,195 @@ impl DesktopWidget {
 
             self.widget_surfaces.push(surface);
         }
+
+        // Overlap is allowed, but rarely what was meant
+        let rects: Vec<Placement> = placements.iter().map(|(_, rect)| *rect).collect();
+        for (a, b) in outputs::overlapping(&rects) {
+            tracing::warn!(
+                widget = %placements[a].0,
+                other = %placements[b].0,
+                "Widgets overlap"
+            );
+        }
+    }
+
+    /// Follow an output coming or going
+    ///
+    /// On a monitor setup seen before the widgets go back to where they
+    /// were on it; otherwise only widgets pinned to an output move. Nothing
+    /// happens before the first surfaces exist.
+    fn outputs_changed(&mut self, qh: &QueueHandle<Self>) {
+        if self.widget_surfaces.is_empty() && !self.hidden {
+            return;
+        }
+        if !self.recall_placements() {
+            self.replace_pinned_widgets(qh);
+            return;
+        }
+        self.create_widget_surfaces(qh);
+        self.first_frame = true;
+    }
+
+    /// Move the widgets to where they were on the current monitor setup
+    ///
+    /// Returns whether the setup changed to one with remembered placements.
+    /// A setup seen for the first time starts out with the current ones.
+    fn recall_placements(&mut self) -> bool {
+        let setup = outputs::setup_fingerprint(&self.output_state);
+        if setup.is_empty() || setup == self.output_setup {
+            return false;
+        }
+        tracing::info!(setup = %setup, "Monitor setup changed");
+        self.output_setup = setup;
+
+        if self
+            .placements
+            .recall(&self.output_setup, &mut self.config.widgets)
+        {
+            tracing::info!("Restored widget placement for this monitor setup");
+            return true;
+        }
+        self.remember_placements();
+        false
+    }
+
+    /// Remember the configured placement for the current monitor setup
+    fn remember_placements(&mut self) {
+        if self.output_setup.is_empty() {
+            return;
+        }
+        let widgets: Vec<WidgetInstance> = self
+            .config
+            .widgets
+            .iter()
+            .filter(|instance| instance.id.as_deref() != Some(PERF_OVERLAY_ID))
+            .cloned()
+            .collect();
+        if let Err(e) = self.placements.remember(&self.output_setup, &widgets) {
+            tracing::warn!(error = %e, "Failed to save widget placement");
+        }
+    }
+
+    /// Recreate the surfaces after an output came or went, if any widget is
+    /// pinned to an output
+    ///
+    /// Pinned widgets move onto their output when it's connected and off it
+    /// when it's gone. Nothing happens before the first surfaces exist.
+    fn replace_pinned_widgets(&mut self, qh: &QueueHandle<Self>) {
+        if self.widget_surfaces.is_empty()
+            || !self.config.enabled_widgets().any(|w| w.output.is_some())
+        {
+            return;
+        }
+        tracing::info!("Outputs changed, placing pinned widgets again");
+        self.create_widget_surfaces(qh);
+        self.first_frame = true;
+    }
+
+    /// Place the widgets again after an output was rotated
+    ///
+    /// Anchors are relative to the output as the user sees it, so a
+    /// top-right widget stays top-right, but panels move with the rotation
+    /// and their margins are measured again before the surfaces are
+    /// recreated.
+    fn output_rotated(&mut self, info: &OutputInfo, qh: &QueueHandle<Self>) {
+        tracing::info!(
+            output = ?info.name,
+            transform = ?info.transform,
+            portrait = outputs::swaps_axes(info.transform),
+            "Output rotated, placing widgets again"
+        );
+        self.panels = PanelDetection::detect_with(self.config.panel.panel_detection);
+        if self.widget_surfaces.is_empty() {
+            return;
+        }
+        self.create_widget_surfaces(qh);
+        self.first_frame = true;
+    }
+
+    /// Apply the configured text scale and glyph cache size and pre-warm
+    /// widget glyphs
+    ///
+    /// Rasterizing the clock's digits up front keeps the first frames from
+    /// stalling on glyph rendering.
+    fn prepare_glyph_cache(&mut self) {
+        self.renderer
+            .set_text_scale(self.config.accessibility.text_scale());
+        let performance = &self.config.performance;
+        self.renderer
+            .set_glyph_cache_capacity(performance.glyph_cache_size);
+        if !performance.prewarm_glyphs {
+            return;
+        }
+
+        for surface in &self.widget_surfaces {
+            for (i, &widget_index) in surface.widget_indices.iter().enumerate() {
+                let Some(widget) = self.widgets.get(widget_index) else {
+                    continue;
+                };
+                let height = surface
+                    .layout
+                    .get(i)
+                    .map_or(surface.height, |rect| rect.height as u32);
+                self.renderer.prewarm_glyphs(widget.as_ref(), height);
+            }
+        }
     }
 
+    /// Layer-shell margins (top, right, bottom, left) for a surface placed
+    /// like `instance`
+    fn surface_margins(&self, instance: &WidgetInstance) -> (i32, i32, i32, i32) {
+        // Absolute coordinates are relative to the output itself, so
+        // neither configured nor panel margins apply to them
+        if let Some((_, margin)) = instance.absolute_placement() {
+            return (margin.top, margin.right, margin.bottom, margin.left);
+        }
+
+        // Get effective margins (widget-specific or panel defaults)
+        let margin = instance.effective_margin(&self.config.panel.margin);
+
+        // Combine with the panels on the widget's output
+        let panel_margins = self
+            .panels
+            .margin_adjustments_for(instance.output.as_deref());
+        (
+            margin.top + panel_margins.top,
+            margin.right + panel_margins.right,
+            margin.bottom + panel_margins.bottom,
+            margin.left + panel_margins.left,
+        )
+    }
+
+    /// Detect the panels again and move every surface clear of them
+    ///
+    /// Called when the panel configuration changes, so the widgets follow
+    /// the panel without being recreated.
+    fn update_panel_margins(&mut self) {
+        let panels = PanelDetection::detect_with(self.config.panel.panel_detection);
+        if panels == self.panels {
+            return;
+        }
+        let panel_margins = panels.margin_adjustments();
+        tracing::info!(
+            panels = panels.panels.len(),
+            top = panel_margins.top,
+            bottom = panel_margins.bottom,
+            left = panel_margins.left,
+            right = panel_margins.right,
+            "Panel changed, adjusting margins"
+        );
+        self.panels = panels;
+
+        let instances: Vec<&WidgetInstance> = self.config.enabled_widgets().collect();
+        for surface in &self.widget_surfaces {
+            let Some(instance) = instances.get(surface.widget_indices[0]) else {
+                continue;
+            };
+            let (top, right, bottom, left) = self.surface_margins(instance);
+            surface.layer.set_margin(top, right, bottom, left);
+            surface.layer.commit();
+        }
+    }
 
     /// Update widget layout positions for hit-testing
     ///
@@ -546,7 +1110,7 @@ impl DesktopWidget {
         tracing::info!("Reloading configuration");
 
         // Load new configuration
-        let new_config = match Config::load() {
+        let mut new_config = match Config::load() {
             Ok(cfg) => cfg,
             Err(e) => {
                 tracing::error!(error = %e, "Failed to load config during reload, keeping current config");
@@ -554,21 +1118,50 @@ impl DesktopWidget {
             }
         };
 
+        // Widgets the file doesn't move stay where they are on this monitor
+        // setup; what it does move is remembered for the setup
+        let file_placements = state::placements_of(&new_config.widgets);
+        state::carry_placements(
+            &self.file_placements,
+            &self.config.widgets,
+            &mut new_config.widgets,
+        );
+        self.file_placements = file_placements;
+
+        self.apply_config(new_config, qh);
+        self.remember_placements();
+        Ok(())
+    }
+
+    /// Switch to `new_config`, recreating widgets, audio and surfaces
+    fn apply_config(&mut self, mut new_config: Config, qh: &QueueHandle<Self>) {
+        // The overlay isn't part of the file; re-add it if it is switched on
+        new_config
+            .widgets
+            .retain(|instance| instance.id.as_deref() != Some(PERF_OVERLAY_ID));
+        if self.perf_overlay {
+            let mut overlay = WidgetInstance::new("perf");
+            overlay.id = Some(PERF_OVERLAY_ID.to_string());
+            overlay.position = Some("top-right".to_string());
+            new_config.widgets.push(overlay);
+        }
+
+        // Keep the scheduled theme rather than flashing the configured one
+        new_config.apply_theme_schedule(&chrono::Local::now());
+
         // Note: With multi-surface architecture, individual widget changes trigger surface recreation
         // No need to track panel-level size/position changes separately
 
-        // Update theme if changed
-        let theme_changed = new_config.panel.theme != self.config.panel.theme
-            || new_config.panel.background_opacity != self.config.panel.background_opacity;
-
-        if theme_changed {
-            let new_theme = new_config.get_theme();
+        // Update theme if changed, comparing the resolved theme so edits to
+        // the custom theme or a theme file count too
+        let new_theme = new_config.get_theme();
+        if new_theme != *self.renderer.theme() {
             self.renderer = Renderer::with_theme(new_theme);
             tracing::info!("Theme updated");
         }
 
         // Recreate widgets from new config
-        let registry = WidgetRegistry::with_builtins();
+        let registry = WidgetRegistry::with_user_widgets();
         let mut new_widgets: Vec<Box<dyn Widget>> = Vec::new();
         let mut new_clock_widget: Option<ClockWidget> = None;
         let mut new_weather_widget: Option<WeatherWidget> = None;
@@ -636,12 +1229,28 @@ impl DesktopWidget {
                         error = %e,
                         "Failed to create widget during config reload"
                     );
+                    new_widgets.push(Box::new(FailedWidget::new(&e)));
                 }
             }
         }
 
-        // Update widgets
+        // Update widgets; sources of the old ones are dropped along with
+        // their running fetches, and the new ones fetch right away
+        self.seat_inputs.leave_all(&mut self.widgets);
+        self.stop_widgets();
+        self.data.clear();
         self.widgets = new_widgets;
+        self.start_widgets();
+        let performance = &new_config.performance;
+        self.data
+            .set_spread(performance.poll_jitter, performance.stagger_polling);
+        for pool in self
+            .widget_surfaces
+            .iter_mut()
+            .filter_map(|surface| surface.buffer_pool.as_mut())
+        {
+            pool.set_triple_buffering(performance.triple_buffering);
+        }
         self.clock_widget = new_clock_widget;
         self.weather_widget = new_weather_widget;
 
@@ -652,10 +1261,24 @@ impl DesktopWidget {
 
         // Update config
         self.config = new_config;
+        crash::record_config(&self.config);
+        self.follow_icon_dir();
+        if let Err(e) = http::configure(&self.config.network) {
+            tracing::warn!(error = %e, "Keeping previous network proxy settings");
+        }
+        self.restore_widget_states();
+        self.apply_replay();
+        self.register_sources();
+
+        // Sound files may have changed along with the config
+        self.audio = create_audio_player(&self.config);
+
+        // The pause settings may have changed too
+        self.set_session_state(self.session, qh);
+        self.set_connectivity(self.connectivity);
 
         // Recalculate panel margins
-        let panel_detection = PanelDetection::detect();
-        self.panel_margins = panel_detection.margin_adjustments();
+        self.panels = PanelDetection::detect_with(self.config.panel.panel_detection);
 
         // Update widget positions for hit-testing
         self.update_widget_positions();
@@ -668,94 +1291,600 @@ impl DesktopWidget {
 
         // Create new surfaces with updated config
         self.create_widget_surfaces(qh);
+        self.prepare_glyph_cache();
 
         tracing::info!("Configuration reload complete");
-        Ok(())
     }
 
-    /// Draw a specific widget surface
-    fn draw_widget_surface(&mut self, surface_idx: usize, qh: &QueueHandle<Self>) {
-        // Check if surface index is valid
-        if surface_idx >= self.widget_surfaces.len() {
-            tracing::error!(surface_idx = surface_idx, "Invalid surface index");
+    /// Pause or resume widget updates after a session idle/lock change, and
+    /// hide the widgets while the screen is locked
+    fn set_session_state(&mut self, state: SessionState, qh: &QueueHandle<Self>) {
+        self.session = state;
+        let power = &self.config.power;
+        let paused = state.should_pause(power.pause_when_idle, power.pause_when_locked);
+        self.set_hidden(state.should_hide(power.hide_when_locked), qh);
+        if paused == self.paused {
             return;
         }
 
-        let surface = &mut self.widget_surfaces[surface_idx];
-
-        if !surface.configured {
-            return;
+        self.paused = paused;
+        if paused {
+            tracing::info!(
+                idle = state.idle,
+                locked = state.locked,
+                "Session inactive, pausing widget updates"
+            );
+            for widget in &mut self.widgets {
+                widget.on_suspend();
+            }
+        } else {
+            // Everything is stale after the pause; redraw it all
+            tracing::info!("Session active, resuming widget updates");
+            for widget in &mut self.widgets {
+                widget.on_resume();
+            }
+            self.first_frame = true;
         }
+    }
 
-        // Get the widget for this surface
-        let widget_index = surface.widget_index;
-        if widget_index >= self.widgets.len() {
-            tracing::error!(widget_index = widget_index, "Invalid widget index");
+    /// Remove or recreate every widget surface
+    ///
+    /// Some compositors stack bottom-layer surfaces above the lock screen,
+    /// or show them while it fades in. Destroying the surfaces is the only
+    /// way to be sure they're gone; they're recreated once unlocked.
+    fn set_hidden(&mut self, hidden: bool, qh: &QueueHandle<Self>) {
+        if hidden == self.hidden {
             return;
         }
+        self.hidden = hidden;
+        if hidden {
+            tracing::info!("Screen locked, hiding widgets");
+            self.seat_inputs.leave_all(&mut self.widgets);
+            self.widget_surfaces.clear();
+        } else {
+            tracing::info!("Screen unlocked, showing widgets");
+            self.create_widget_surfaces(qh);
+            self.first_frame = true;
+        }
+    }
 
-        // Create buffer pool if needed
-        if surface.buffer_pool.is_none() {
-            if let Err(e) = surface.init_buffer_pool(&self.shm_state, qh) {
-                tracing::error!(
-                    error = %e,
-                    widget_index = widget_index,
-                    "Failed to create buffer pool for widget surface"
-                );
-                return;
+    /// Start freshly created widgets, suspended straight away if paused
+    fn start_widgets(&mut self) {
+        for widget in &mut self.widgets {
+            widget.on_start();
+            if self.paused {
+                widget.on_suspend();
             }
         }
+    }
 
-        let buffer_pool = match surface.buffer_pool.as_mut() {
-            Some(pool) => pool,
-            None => {
-                tracing::error!(widget_index = widget_index, "Buffer pool not initialized");
-                return;
+    /// Hand each widget the state it saved before the last restart
+    fn restore_widget_states(&mut self) {
+        for (widget, instance) in self.widgets.iter_mut().zip(self.config.enabled_widgets()) {
+            if let Some(state) = self.widget_states.get(&instance.instance_id()) {
+                widget.restore_state(state);
             }
-        };
+        }
+    }
 
-        let (buffer, canvas) = match buffer_pool.get_buffer() {
-            Ok(buf) => buf,
-            Err(e) => {
-                tracing::error!(
-                    error = %e,
-                    widget_index = widget_index,
-                    "Failed to get buffer, skipping frame"
-                );
-                return;
+    /// Write out the state of every widget that keeps some
+    fn save_widget_states(&mut self) {
+        for (widget, instance) in self.widgets.iter().zip(self.config.enabled_widgets()) {
+            let Some(state) = widget.save_state() else {
+                continue;
+            };
+            let id = instance.instance_id();
+            if let Err(e) = self.widget_states.set(&id, state) {
+                tracing::warn!(widget = %id, error = %e, "Failed to save widget state");
             }
+        }
+    }
+
+    /// Swap the widgets in the `--replay` recording for their replays
+    fn apply_replay(&mut self) {
+        let Some(recording) = &self.replay else {
+            return;
         };
+        for (widget, instance) in self.widgets.iter_mut().zip(self.config.enabled_widgets()) {
+            if let Some(mut replay) = recording.replay(&instance.instance_id(), widget.info()) {
+                widget.on_stop();
+                replay.on_start();
+                *widget = Box::new(replay);
+            }
+        }
+    }
 
-        // Time the render operation
-        let render_timer = Timer::start();
+    /// Hand every widget's data source to the data service
+    fn register_sources(&mut self) {
+        let instances = self.config.enabled_widgets();
+        for (widget, instance) in self.widgets.iter().zip(instances) {
+            if let Some(source) = widget.data_source() {
+                let widget_id = instance.id.as_deref().unwrap_or(&instance.widget_type);
+                self.data.register(widget_id, source);
+            }
+        }
+    }
 
-        // Render single widget with its opacity
-        let widget = &self.widgets[widget_index];
-        self.renderer.render_single_widget(
-            canvas,
-            surface.width,
-            surface.height,
-            widget.as_ref(),
-            surface.opacity,
-        );
+    /// Let every widget close its connections before it is dropped
+    fn stop_widgets(&mut self) {
+        for widget in &mut self.widgets {
+            widget.on_stop();
+        }
+    }
 
-        // Record render metrics
-        let render_time = render_timer.stop();
-        self.metrics.render.record_render(render_time);
+    /// Hold or resume network fetches after a connectivity change
+    fn set_connectivity(&mut self, connectivity: Connectivity) {
+        self.connectivity = connectivity;
+        let online = connectivity.is_online() || !self.config.power.pause_when_offline;
+        if !self.data.set_online(online) {
+            return;
+        }
 
-        // Log warning if over frame budget
-        if render_time.as_millis() > TARGET_RENDER_TIME_MS as u128 {
-            tracing::warn!(
-                render_ms = %render_time.as_secs_f64() * 1000.0,
-                target_ms = %TARGET_RENDER_TIME_MS,
-                widget_index = widget_index,
-                "Render exceeded frame budget"
-            );
+        if online {
+            tracing::info!(?connectivity, "Back online, refreshing network widgets");
         } else {
-            tracing::trace!(
-                render_ms = %render_time.as_secs_f64() * 1000.0,
-                widget_index = widget_index,
-                "Widget render complete"
+            tracing::info!("Offline, pausing network widget polling");
+        }
+    }
+
+    /// Answer a request from the control socket
+    fn handle_ipc(&mut self, request: &IpcRequest, qh: &QueueHandle<Self>) -> IpcResponse {
+        match request {
+            IpcRequest::Perf { enabled } => {
+                let enabled = enabled.unwrap_or(!self.perf_overlay);
+                if enabled != self.perf_overlay {
+                    tracing::info!(enabled, "Toggling performance overlay");
+                    self.perf_overlay = enabled;
+                    self.apply_config(self.config.clone(), qh);
+                    self.first_frame = true;
+                }
+                IpcResponse::Perf { enabled }
+            }
+            IpcRequest::Metrics => match serde_json::to_value(self.perf_stats()) {
+                Ok(metrics) => IpcResponse::Metrics { metrics },
+                Err(e) => IpcResponse::Error {
+                    message: e.to_string(),
+                },
+            },
+            IpcRequest::ApplyConfig { config } => match Config::parse(config) {
+                Ok(config) => {
+                    tracing::info!("Applying unsaved configuration from control socket");
+                    self.apply_config(config, qh);
+                    self.first_frame = true;
+                    IpcResponse::Applied
+                }
+                Err(e) => IpcResponse::Error {
+                    message: format!("{:#}", e),
+                },
+            },
+            IpcRequest::RevertConfig => match self.reload_config(qh) {
+                Ok(()) => {
+                    self.first_frame = true;
+                    IpcResponse::Applied
+                }
+                Err(e) => IpcResponse::Error {
+                    message: format!("{:#}", e),
+                },
+            },
+        }
+    }
+
+    /// Reload widgets backed by changed plugin manifests or scripts
+    ///
+    /// Unlike [`reload_config`](Self::reload_config) this only touches the
+    /// affected widgets: each one is dropped and re-created in place from
+    /// its existing instance config, so surfaces and other widgets are left
+    /// alone. Widgets whose manifest was deleted are stopped and replaced by
+    /// a placeholder saying so. Returns the freshly discovered manifests.
+    fn reload_plugins(
+        &mut self,
+        event: &PluginReloadEvent,
+        qh: &QueueHandle<Self>,
+    ) -> Vec<WidgetManifest> {
+        let registry = WidgetRegistry::with_user_widgets();
+        let manifests = registry.manifests().to_vec();
+
+        let instances: Vec<_> = self.config.enabled_widgets().cloned().collect();
+        if instances.len() != self.widgets.len() {
+            // Some instance failed to create earlier (possibly because its
+            // manifest was broken), so indices don't line up. Rebuild everything.
+            tracing::info!("Widget set changed, falling back to full reload");
+            if let Err(e) = self.reload_config(qh) {
+                tracing::error!(error = %e, "Failed to reload widgets after plugin change");
+            }
+            return manifests;
+        }
+
+        let changed: Vec<&str> = manifests
+            .iter()
+            .filter(|manifest| event.affects(manifest))
+            .map(|manifest| manifest.widget_type.as_str())
+            .collect();
+
+        let mut reloaded = 0;
+        let mut removed = 0;
+        let paused = self.paused;
+        for (widget, instance) in self.widgets.iter_mut().zip(&instances) {
+            // Still running although its type is gone: the manifest was deleted
+            let deleted =
+                !registry.has_widget(&instance.widget_type) && widget.info().id != "failed";
+            if !deleted && !changed.contains(&instance.widget_type.as_str()) {
+                continue;
+            }
+            // Replayed widgets stay replays
+            if self
+                .replay
+                .as_ref()
+                .is_some_and(|replay| replay.contains(&instance.instance_id()))
+            {
+                continue;
+            }
+
+            let widget_id = instance.id.as_deref().unwrap_or(&instance.widget_type);
+            match registry.create(&instance.widget_type, &instance.config) {
+                Ok(new_widget) => {
+                    widget.on_stop();
+                    *widget = new_widget;
+                    widget.on_start();
+                    if let Some(state) = self.widget_states.get(&instance.instance_id()) {
+                        widget.restore_state(state);
+                    }
+                    if paused {
+                        widget.on_suspend();
+                    }
+                    match widget.data_source() {
+                        Some(source) => self.data.register(widget_id, source),
+                        None => self.data.unregister(widget_id),
+                    }
+                    reloaded += 1;
+                }
+                Err(e) if deleted => {
+                    tracing::info!(
+                        widget = %widget_id,
+                        widget_type = %instance.widget_type,
+                        "Plugin manifest removed, stopping its widget"
+                    );
+                    widget.on_stop();
+                    *widget = Box::new(FailedWidget::new(&e));
+                    self.data.unregister(widget_id);
+                    removed += 1;
+                }
+                Err(e) => {
+                    tracing::error!(
+                        widget_type = %instance.widget_type,
+                        error = %e,
+                        "Failed to reload plugin widget, keeping previous instance"
+                    );
+                }
+            }
+        }
+
+        tracing::info!(
+            changed_types = ?changed,
+            reloaded = reloaded,
+            removed = removed,
+            "Plugin reload complete"
+        );
+        manifests
+    }
+
+    /// Collapse or expand the widget on a collapsible surface, remembering
+    /// the new state
+    fn toggle_collapsed(&mut self, surface_idx: usize, qh: &QueueHandle<Self>) {
+        let duration = if self.config.accessibility.reduced_motion {
+            std::time::Duration::ZERO
+        } else {
+            COLLAPSE_DURATION
+        };
+        let surface = &mut self.widget_surfaces[surface_idx];
+        let Some(collapsed) = surface.toggle_collapsed(std::time::Instant::now(), duration) else {
+            return;
+        };
+
+        let widget_index = surface.widget_index();
+        if let Some(widget) = self.widgets.get_mut(widget_index) {
+            widget.set_compact(collapsed);
+        }
+        if let Some(instance) = self.config.enabled_widgets().nth(widget_index) {
+            let id = instance.instance_id();
+            tracing::debug!(widget = %id, collapsed = collapsed, "Toggled collapsible widget");
+            if let Err(e) = self.collapse_state.set_collapsed(&id, collapsed) {
+                tracing::warn!(error = %e, "Failed to save collapsed widgets");
+            }
+        }
+
+        self.step_animation(surface_idx, qh);
+    }
+
+    /// Release a seat's pointer, if it has one
+    fn release_pointer(&mut self, seat: &wl_seat::WlSeat) {
+        self.seat_inputs.update_hover(seat, None, &mut self.widgets);
+        if let Some(state) = self.seat_inputs.get_mut(seat) {
+            state.pointer_leave();
+        }
+        self.pointers.retain(|pointer| {
+            let is_seat = pointer
+                .data::<PointerData>()
+                .is_some_and(|data| data.seat() == seat);
+            // wl_pointer.release only exists from version 3
+            if is_seat && pointer.version() >= 3 {
+                pointer.release();
+            }
+            !is_seat
+        });
+    }
+
+    /// Move a seat's hover onto the interactive widget under its pointer
+    fn hover_widget(
+        &mut self,
+        seat: &wl_seat::WlSeat,
+        surface: &wl_surface::WlSurface,
+        (x, y): (f64, f64),
+    ) {
+        let widget_index = self
+            .widget_surfaces
+            .iter()
+            .find(|s| &s.wl_surface == surface)
+            .and_then(|s| s.widget_at(x, y))
+            .map(|(index, _, _)| index)
+            .filter(|&index| {
+                self.widgets
+                    .get(index)
+                    .is_some_and(|widget| widget.is_interactive())
+            });
+        self.seat_inputs
+            .update_hover(seat, widget_index, &mut self.widgets);
+    }
+
+    /// Hand a click to the interactive widget under the pointer, redrawing
+    /// its surface straight away if that changed what it shows
+    fn click_widget(
+        &mut self,
+        surface_idx: usize,
+        (x, y): (f64, f64),
+        button: MouseButton,
+        qh: &QueueHandle<Self>,
+    ) {
+        let Some((widget_index, x, y)) = self.widget_surfaces[surface_idx].widget_at(x, y) else {
+            return;
+        };
+        let Some(widget) = self.widgets.get_mut(widget_index) else {
+            return;
+        };
+        if !widget.is_interactive() {
+            return;
+        }
+
+        if let Some(action) = widget.on_click(button, x, y) {
+            if let Err(e) = execute_action(action) {
+                tracing::warn!(error = %e, "Widget action failed");
+            }
+        }
+        if !self.is_surface_current(surface_idx) {
+            self.draw_widget_surface(surface_idx, qh);
+        }
+    }
+
+    /// Hand a scroll to the interactive widget under the pointer, redrawing
+    /// its surface straight away if that changed what it shows
+    fn scroll_widget(
+        &mut self,
+        surface: &wl_surface::WlSurface,
+        (x, y): (f64, f64),
+        direction: ScrollDirection,
+        qh: &QueueHandle<Self>,
+    ) {
+        let Some(surface_idx) = self
+            .widget_surfaces
+            .iter()
+            .position(|s| &s.wl_surface == surface)
+        else {
+            return;
+        };
+        let Some((widget_index, x, y)) = self.widget_surfaces[surface_idx].widget_at(x, y) else {
+            return;
+        };
+        let Some(widget) = self.widgets.get_mut(widget_index) else {
+            return;
+        };
+        if !widget.is_interactive() {
+            return;
+        }
+
+        if let Some(action) = widget.on_scroll(direction, x, y) {
+            if let Err(e) = execute_action(action) {
+                tracing::warn!(error = %e, "Widget action failed");
+            }
+        }
+        if !self.is_surface_current(surface_idx) {
+            self.draw_widget_surface(surface_idx, qh);
+        }
+    }
+
+    /// Move a surface's height animation on by one frame
+    ///
+    /// Each new size is committed with a request for the next frame, and
+    /// the compositor's configure for it redraws the surface. Returns false
+    /// if nothing is animating.
+    fn step_animation(&mut self, surface_idx: usize, qh: &QueueHandle<Self>) -> bool {
+        let surface = &mut self.widget_surfaces[surface_idx];
+        let Some(height) = surface.animate(std::time::Instant::now()) else {
+            return false;
+        };
+
+        if surface.animation.is_some() {
+            surface.wl_surface.frame(qh, surface.wl_surface.clone());
+        }
+        if height != surface.height {
+            surface.layer.set_size(surface.width, height);
+            // A collapsed dock gives the space back to windows
+            if let Some(zone) = surface.exclusive_zone(height) {
+                surface.layer.set_exclusive_zone(zone);
+            }
+        }
+        surface.wl_surface.commit();
+        true
+    }
+
+    /// Draw a specific widget surface
+    fn draw_widget_surface(&mut self, surface_idx: usize, qh: &QueueHandle<Self>) {
+        // Check if surface index is valid
+        if surface_idx >= self.widget_surfaces.len() {
+            tracing::error!(surface_idx = surface_idx, "Invalid surface index");
+            return;
+        }
+
+        let surface = &mut self.widget_surfaces[surface_idx];
+
+        if !surface.configured {
+            return;
+        }
+
+        // Nothing would show the frame; redrawn when an output powers back on
+        if !self.output_power.is_surface_visible(&surface.wl_surface) {
+            return;
+        }
+
+        // Get the widgets for this surface
+        let widget_index = surface.widget_index();
+        if let Some(&invalid) = surface
+            .widget_indices
+            .iter()
+            .find(|&&i| i >= self.widgets.len())
+        {
+            tracing::error!(widget_index = invalid, "Invalid widget index");
+            return;
+        }
+
+        // Create buffer pool if needed
+        if surface.buffer_pool.is_none() {
+            let triple_buffering = self.config.performance.triple_buffering;
+            if let Err(e) = surface.init_buffer_pool(&self.shm_state, qh, triple_buffering) {
+                tracing::error!(
+                    error = %e,
+                    widget_index = widget_index,
+                    "Failed to create buffer pool for widget surface"
+                );
+                return;
+            }
+        }
+
+        let buffer_pool = match surface.buffer_pool.as_mut() {
+            Some(pool) => pool,
+            None => {
+                tracing::error!(widget_index = widget_index, "Buffer pool not initialized");
+                return;
+            }
+        };
+
+        // Follows the surface's size within the same pool
+        if let Err(e) = buffer_pool.resize(surface.width, surface.height) {
+            tracing::error!(
+                error = %e,
+                widget_index = widget_index,
+                "Failed to resize buffer pool, skipping frame"
+            );
+            return;
+        }
+
+        let (buffer, canvas) = match buffer_pool.get_buffer() {
+            Ok(buf) => buf,
+            Err(e) => {
+                tracing::error!(
+                    error = %e,
+                    widget_index = widget_index,
+                    "Failed to get buffer, skipping frame"
+                );
+                return;
+            }
+        };
+
+        // Time the render operation
+        let render_timer = Timer::start();
+
+        // Render the widget (or group) with its opacity, accents and frame
+        // style; a group takes its frame style from its first widget
+        let now = std::time::Instant::now();
+        self.frame_accents.clear();
+        self.frame_freshness.clear();
+        for &i in &surface.widget_indices {
+            let widget = self.widgets[i].as_ref();
+            let Some(instance) = self.config.enabled_widgets().nth(i) else {
+                self.frame_accents.push(None);
+                self.frame_freshness.push(Freshness::Fresh);
+                continue;
+            };
+            let accent = instance
+                .accent
+                .is_some()
+                .then(|| instance.effective_accent(self.renderer.theme()));
+            self.frame_accents.push(accent);
+            let freshness = widget_freshness(widget, instance, &self.data, &self.config, now);
+            self.frame_freshness.push(freshness);
+        }
+        let (accents, freshness) = (&self.frame_accents, &self.frame_freshness);
+        let instance = self.config.enabled_widgets().nth(widget_index);
+        let frameless = instance.is_some_and(|instance| instance.frameless);
+        if surface.group.is_some() {
+            let members = surface
+                .widget_indices
+                .iter()
+                .zip(&surface.layout)
+                .zip(accents)
+                .zip(freshness)
+                .map(|(((&i, &rect), &accent), &freshness)| GroupMember {
+                    widget: self.widgets[i].as_ref(),
+                    rect,
+                    accent,
+                    freshness,
+                });
+            self.renderer.render_group(
+                canvas,
+                surface.width,
+                surface.height,
+                members,
+                surface.opacity,
+                frameless,
+            );
+        } else {
+            self.renderer.render_single_widget(
+                canvas,
+                surface.width,
+                surface.height,
+                self.widgets[widget_index].as_ref(),
+                surface.opacity,
+                accents[0],
+                frameless,
+                freshness[0],
+            );
+        }
+
+        // Record render metrics, per widget (or group) where it is known
+        let render_time = render_timer.stop();
+        match (&surface.group, instance) {
+            (Some(group), _) => self.metrics.render.record_widget_render(group, render_time),
+            (None, Some(instance)) => {
+                let widget_id = instance.id.as_deref().unwrap_or(&instance.widget_type);
+                self.metrics
+                    .render
+                    .record_widget_render(widget_id, render_time);
+            }
+            (None, None) => self.metrics.render.record_render(render_time),
+        }
+
+        // Log warning if over frame budget
+        if render_time.as_millis() > TARGET_RENDER_TIME_MS as u128 {
+            tracing::warn!(
+                render_ms = %render_time.as_secs_f64() * 1000.0,
+                target_ms = %TARGET_RENDER_TIME_MS,
+                widget_index = widget_index,
+                "Render exceeded frame budget"
+            );
+        } else {
+            tracing::trace!(
+                render_ms = %render_time.as_secs_f64() * 1000.0,
+                widget_index = widget_index,
+                "Widget render complete"
             );
         }
 
@@ -773,7 +1902,28 @@ impl DesktopWidget {
             return;
         }
 
+        // Blur is double-buffered state, applied along with this commit
+        let theme = self.renderer.theme();
+        self.blur_state.update(
+            &mut surface.blur,
+            &surface.wl_surface,
+            &self.compositor_state,
+            theme.blur_enabled && !frameless,
+            surface.width,
+            surface.height,
+            theme.corner_radius,
+            qh,
+        );
+
         surface.wl_surface.commit();
+        surface.last_content = Some(
+            surface
+                .widget_indices
+                .iter()
+                .map(|&i| self.widgets[i].content())
+                .zip(freshness)
+                .collect(),
+        );
 
         // Mark first frame as rendered
         if surface.first_frame {
@@ -782,18 +1932,288 @@ impl DesktopWidget {
         }
     }
 
+    /// Play the sounds widgets asked for during their last update
+    fn play_sound_cues(&mut self) {
+        let instances = self.config.enabled_widgets();
+        for (widget, instance) in self.widgets.iter_mut().zip(instances) {
+            let Some(cue) = widget.take_sound_cue() else {
+                continue;
+            };
+            let Some(player) = &self.audio else {
+                continue;
+            };
+
+            let sound = instance.effective_sound(self.config.sounds.for_cue(cue));
+            tracing::debug!(widget = %instance.instance_id(), cue = ?cue, "Playing widget sound");
+            if let Err(e) = player.play_config(&sound, cue.priority()) {
+                tracing::warn!(error = %e, widget = %instance.instance_id(), "Failed to play sound");
+            }
+        }
+    }
+
+    /// Hand every widget's upcoming reminders to the update scheduler
+    fn sync_reminders(&mut self) {
+        let instances = self.config.enabled_widgets();
+        let reminders = self
+            .widgets
+            .iter()
+            .zip(instances)
+            .flat_map(|(widget, instance)| {
+                let widget_id = instance.instance_id();
+                widget
+                    .reminders()
+                    .into_iter()
+                    .map(move |reminder| (widget_id.clone(), reminder))
+            })
+            .collect();
+        self.update_scheduler.set_reminders(reminders);
+    }
+
+    /// Hold an idle inhibitor on each surface whose widgets want the screen
+    /// kept awake
+    ///
+    /// Released as soon as the widgets are done, and while the screen is
+    /// locked, where keeping it on would only waste power.
+    fn update_idle_inhibitors(&mut self, qh: &QueueHandle<Self>) {
+        let locked = self.session.locked;
+        for surface in &mut self.widget_surfaces {
+            let inhibit = !locked
+                && surface
+                    .widget_indices
+                    .iter()
+                    .any(|&i| self.widgets.get(i).is_some_and(|w| w.inhibits_idle()));
+            self.idle_inhibit.update(
+                &mut surface.idle_inhibitor,
+                &surface.wl_surface,
+                inhibit,
+                qh,
+            );
+        }
+    }
+
+    /// Show the reminders that are due as notifications
+    ///
+    /// Runs on every tick, paused or not, so reminders don't wait for the
+    /// screen to come back.
+    fn fire_due_reminders(&mut self) {
+        for (widget_id, reminder) in self
+            .update_scheduler
+            .take_due_reminders(chrono::Local::now())
+        {
+            tracing::info!(widget = %widget_id, reminder = %reminder.summary, "Firing reminder");
+            Notification::new(reminder.summary, reminder.body).send();
+            if !reminder.sound {
+                continue;
+            }
+            let Some(player) = &self.audio else {
+                continue;
+            };
+            let instance = self
+                .config
+                .enabled_widgets()
+                .find(|instance| instance.instance_id() == widget_id);
+            let sound = self.config.sounds.for_cue(SoundCue::Notification);
+            let sound = match instance {
+                Some(instance) => instance.effective_sound(sound),
+                None => sound.clone(),
+            };
+            if let Err(e) = player.play_config(&sound, SoundCue::Notification.priority()) {
+                tracing::warn!(error = %e, widget = %widget_id, "Failed to play reminder sound");
+            }
+        }
+    }
+
+    /// Whether a surface already shows its widgets' current content and
+    /// freshness badges
+    fn is_surface_current(&self, surface_idx: usize) -> bool {
+        let surface = &self.widget_surfaces[surface_idx];
+        let count = self.widgets.len();
+        if surface.widget_indices.iter().any(|&i| i >= count) {
+            return false;
+        }
+        let now = std::time::Instant::now();
+        let shown = surface.widget_indices.iter().map(|&i| {
+            let widget = self.widgets[i].as_ref();
+            let freshness = self
+                .config
+                .enabled_widgets()
+                .nth(i)
+                .map_or(Freshness::Fresh, |instance| {
+                    widget_freshness(widget, instance, &self.data, &self.config, now)
+                });
+            (widget.content_ref(), freshness)
+        });
+        surface.shows(shown)
+    }
+
+    /// Hand a finished background fetch to the widget that started it
+    ///
+    /// The surface is redrawn on the next tick if the content changed.
+    fn handle_task_result(&mut self, result: TaskResult) {
+        if !self.data.is_current(&result) {
+            tracing::debug!(
+                widget = %result.widget_id,
+                "Dropping fetch result from before a reload"
+            );
+            return;
+        }
+
+        let error = result.output.as_ref().err().map(|e| format!("{:#}", e));
+        let retry_in = self.data.finish(&result);
+        if let Some(retry_in) = retry_in.filter(|_| !self.data.is_held(&result.widget_id)) {
+            tracing::warn!(
+                widget = %result.widget_id,
+                failures = self.data.failures(&result.widget_id),
+                retry_in_secs = retry_in.as_secs(),
+                "Widget fetch failed, backing off"
+            );
+        }
+        self.metrics
+            .updates
+            .record_fetch(&result.widget_id, result.duration, error.as_deref());
+
+        let instances = self.config.enabled_widgets();
+        let widget = self
+            .widgets
+            .iter_mut()
+            .zip(instances)
+            .find(|(_, instance)| {
+                instance.id.as_deref().unwrap_or(&instance.widget_type) == result.widget_id
+            });
+        match widget {
+            // Offline, a widget with data keeps it under the offline badge
+            // rather than showing every failure
+            Some((widget, _))
+                if error.is_some()
+                    && self.data.is_held(&result.widget_id)
+                    && widget.last_success().is_some() =>
+            {
+                tracing::debug!(
+                    widget = %result.widget_id,
+                    "Keeping cached data while offline"
+                );
+            }
+            Some((widget, _)) => widget.apply_fetch(result.output),
+            None => tracing::debug!(
+                widget = %result.widget_id,
+                "Dropping fetch result for a removed widget"
+            ),
+        }
+        self.sync_reminders();
+    }
+
+    /// Load icons from the configured icon directory and watch it
+    ///
+    /// Runs on config reloads; [`check_icon_dir`](Self::check_icon_dir)
+    /// follows the directory being created or removed in between.
+    fn follow_icon_dir(&mut self) {
+        let dir = self.config.icons_dir();
+        let watched = self.icon_watcher.as_ref().map(IconWatcher::dir);
+        if watched.is_none() || watched != dir.as_deref() {
+            self.icon_watcher = dir.as_deref().and_then(watch_icons);
+            // Icons looked up before the directory existed are cached as missing
+            self.renderer.reload_icons();
+        }
+        self.renderer.set_icon_dir(dir);
+    }
+
+    /// Start watching the icon directory once it exists, and stop once it
+    /// is gone, reloading the icons either way
+    fn check_icon_dir(&mut self) {
+        let exists = self
+            .renderer
+            .icon_dir()
+            .is_some_and(std::path::Path::is_dir);
+        match &self.icon_watcher {
+            Some(watcher) if !exists => {
+                tracing::info!(path = %watcher.dir().display(), "Icon directory removed");
+                self.icon_watcher = None;
+            }
+            None if exists => {
+                self.icon_watcher = self.renderer.icon_dir().and_then(watch_icons);
+            }
+            _ => return,
+        }
+        self.renderer.reload_icons();
+        self.first_frame = true;
+    }
+
+    /// Current metrics, with the health of the fetch runtime
+    fn perf_stats(&self) -> PerfStats {
+        let mut stats = self.metrics.stats();
+        stats.workers = self.data.worker_stats(std::time::Instant::now());
+        stats
+    }
+
     /// Draw all widget surfaces
     fn draw_all_surfaces(&mut self, qh: &QueueHandle<Self>) {
-        // Update all widgets first
-        for widget in &mut self.widgets {
+        // Update all widgets first, timing each against its instance id
+        let stats = self.perf_stats();
+        let now = std::time::Instant::now();
+        let instances = self.config.enabled_widgets();
+        for (widget, instance) in self.widgets.iter_mut().zip(instances) {
+            let widget_id = instance.id.as_deref().unwrap_or(&instance.widget_type);
+            widget.observe_metrics(&stats);
+
+            let update_timer = Timer::start();
             widget.update();
+            self.metrics
+                .updates
+                .record_update(widget_id, update_timer.stop(), widget.error());
+
+            for fetch in widget.take_fetch_reports() {
+                self.metrics.updates.record_fetch(
+                    widget_id,
+                    fetch.duration,
+                    fetch.error.as_deref(),
+                );
+            }
         }
 
-        // Draw each surface
+        // Keep the crash report's picture of the widgets current
+        crash::record_widgets(
+            self.config
+                .enabled_widgets()
+                .zip(self.widgets.iter().map(|widget| widget.as_ref())),
+        );
+
+        self.save_widget_states();
+
+        // Append changed content to the `--record` recording
+        if let Some(recorder) = &mut self.recorder {
+            let recorded = self
+                .widgets
+                .iter()
+                .zip(self.config.enabled_widgets())
+                .try_for_each(|(widget, instance)| {
+                    recorder.record(&instance.instance_id(), &widget.content_ref())
+                });
+            if let Err(e) = recorded {
+                tracing::error!(error = %e, "Stopping recording");
+                self.recorder = None;
+            }
+        }
+
+        // Start fetches for sources that are due
+        self.data.poll(now);
+
+        self.play_sound_cues();
+        self.sync_reminders();
+
+        // Redraw surfaces whose widget content changed; everything after a
+        // theme or config change
+        let force = std::mem::take(&mut self.first_frame);
         for i in 0..self.widget_surfaces.len() {
+            if !force && self.is_surface_current(i) {
+                self.metrics.render.record_skip();
+                continue;
+            }
             self.draw_widget_surface(i, qh);
         }
 
+        // The glyph cache lives in the renderer; mirror its counters
+        self.metrics.glyph_cache = self.renderer.glyph_cache_metrics().clone();
+
         // Periodically log metrics summary
         self.metrics.maybe_log_summary();
     }
@@ -813,6 +2233,342 @@ delegate_layer!(DesktopWidget);
 delegate_seat!(DesktopWidget);
 delegate_pointer!(DesktopWidget);
 delegate_registry!(DesktopWidget);
+wayland_client::delegate_dispatch!(DesktopWidget: [OrgKdeKwinBlurManager: GlobalData] => BlurState);
+wayland_client::delegate_dispatch!(DesktopWidget: [OrgKdeKwinBlur: ()] => BlurState);
+wayland_client::delegate_dispatch!(DesktopWidget: [ZwlrOutputPowerManagerV1: GlobalData] => OutputPowerState);
+wayland_client::delegate_dispatch!(DesktopWidget: [ZwlrOutputPowerV1: wl_output::WlOutput] => OutputPowerState);
+wayland_client::delegate_dispatch!(DesktopWidget: [ZwpIdleInhibitManagerV1: GlobalData] => IdleInhibitState);
+wayland_client::delegate_dispatch!(DesktopWidget: [ZwpIdleInhibitorV1: ()] => IdleInhibitState);
+
+/// How current `widget`'s data is, judged against how often its source is
+/// fetched
+fn widget_freshness(
+    widget: &dyn Widget,
+    instance: &WidgetInstance,
+    data: &DataService,
+    config: &Config,
+    now: std::time::Instant,
+) -> Freshness {
+    let widget_id = instance.id.as_deref().unwrap_or(&instance.widget_type);
+    if data.is_held(widget_id) && widget.last_success().is_some() {
+        return Freshness::Offline;
+    }
+    let Some(interval) = data.refresh_interval(widget_id) else {
+        return Freshness::Fresh;
+    };
+    Freshness::at(
+        widget.last_success(),
+        interval,
+        widget.error().is_some(),
+        config.network.stale_after,
+        now,
+    )
+}
+
+/// Open the audio output if sounds are enabled, decoding configured sounds up front
+fn create_audio_player(config: &Config) -> Option<AudioPlayer> {
+    if !config.sounds.enabled {
+        return None;
+    }
+
+    let mut player = match AudioPlayer::new() {
+        Ok(player) => player,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to open audio output, sounds disabled");
+            return None;
+        }
+    };
+    player.set_volume(config.sounds.volume);
+    player.set_sound_theme(config.sounds.sound_theme());
+
+    let effects = [&config.sounds.alarm, &config.sounds.notification]
+        .into_iter()
+        .chain(config.sounds.events.values())
+        .map(|sound| sound.sound_effect())
+        .chain(
+            config
+                .enabled_widgets()
+                .filter_map(|w| w.sound.as_deref())
+                .map(SoundEffect::from_config),
+        );
+    for effect in effects {
+        if let Err(e) = player.preload(&effect) {
+            tracing::warn!(error = %e, sound = ?effect, "Failed to load sound");
+        }
+    }
+
+    Some(player)
+}
+
+/// Handle `cosmic-desktop-widget theme list | import FILE | export [NAME] FILE`
+fn run_theme_command(args: &[String]) -> Result<()> {
+    match args {
+        [cmd] if cmd == "list" => {
+            println!("Built-in themes:");
+            for name in BUILTIN_THEMES {
+                println!("  {}", name);
+            }
+            let user_themes = Theme::user_theme_names();
+            if !user_themes.is_empty() {
+                println!("Theme files:");
+                for name in user_themes {
+                    println!("  {}", name);
+                }
+            }
+        }
+        [cmd, path] if cmd == "import" => {
+            let name = Theme::import(std::path::Path::new(path))?;
+            println!(
+                "Imported theme '{}'. Select it with: theme = \"{}\"",
+                name, name
+            );
+        }
+        [cmd, path] if cmd == "export" => {
+            let config = Config::load()?;
+            config.get_theme().save_file(std::path::Path::new(path))?;
+            println!("Exported theme '{}' to {}", config.panel.theme, path);
+        }
+        [cmd, name, path] if cmd == "export" => {
+            Theme::find(name).save_file(std::path::Path::new(path))?;
+            println!("Exported theme '{}' to {}", name, path);
+        }
+        _ => anyhow::bail!(
+            "Usage: cosmic-desktop-widget theme <list | import FILE | export [NAME] FILE>"
+        ),
+    }
+    Ok(())
+}
+
+/// Handle `cosmic-desktop-widget --benchmark [ITERATIONS]`
+fn run_benchmark(args: &[String]) -> Result<()> {
+    let iterations = match args {
+        [] => benchmark::DEFAULT_ITERATIONS,
+        [count] => count
+            .parse()
+            .ok()
+            .filter(|&count| count > 0)
+            .with_context(|| format!("Invalid iteration count '{}'", count))?,
+        _ => anyhow::bail!("Usage: cosmic-desktop-widget --benchmark [ITERATIONS]"),
+    };
+
+    let config = Config::load()?;
+    if let Err(e) = http::configure(&config.network) {
+        tracing::warn!(error = %e, "Ignoring network proxy settings");
+    }
+    let report = benchmark::run(&config, iterations)?;
+    print!("{}", report);
+    Ok(())
+}
+
+/// Handle `cosmic-desktop-widget render --widget ID --out FILE`
+///
+/// Renders one widget from the config, with fresh data, to a PNG without a
+/// compositor. `--width`, `--height` and `--theme` override the config.
+fn run_render_command(args: &[String]) -> Result<()> {
+    const USAGE: &str = "Usage: cosmic-desktop-widget render --widget ID --out FILE \
+                         [--width PX] [--height PX] [--theme NAME]";
+
+    let mut options = BTreeMap::new();
+    let mut rest = args.iter();
+    while let Some(flag) = rest.next() {
+        let name = flag
+            .strip_prefix("--")
+            .filter(|name| matches!(*name, "widget" | "out" | "width" | "height" | "theme"))
+            .with_context(|| format!("Unknown option '{}'\n{}", flag, USAGE))?;
+        let value = rest
+            .next()
+            .with_context(|| format!("{} needs a value\n{}", flag, USAGE))?;
+        options.insert(name, value.as_str());
+    }
+    let (Some(&id), Some(&out)) = (options.get("widget"), options.get("out")) else {
+        anyhow::bail!(USAGE);
+    };
+    let pixels = |key: &str| -> Result<Option<u32>> {
+        options
+            .get(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .ok()
+                    .filter(|&px| px > 0)
+                    .with_context(|| format!("Invalid --{} '{}'", key, value))
+            })
+            .transpose()
+    };
+    let (width, height) = (pixels("width")?, pixels("height")?);
+
+    let mut config = Config::load()?;
+    if let Some(&theme) = options.get("theme") {
+        config.panel.theme = theme.to_string();
+    }
+    if let Err(e) = http::configure(&config.network) {
+        tracing::warn!(error = %e, "Ignoring network proxy settings");
+    }
+
+    // Disabled widgets can be rendered too, for trying them out
+    let instance = config
+        .widgets
+        .iter()
+        .find(|instance| instance.instance_id() == id)
+        .with_context(|| format!("No widget '{}' in the config", id))?;
+    let mut widget = WidgetRegistry::with_user_widgets()
+        .create(&instance.widget_type, &instance.config)
+        .with_context(|| format!("Failed to create widget '{}'", id))?;
+    widget.on_start();
+    benchmark::prepare_widget(widget.as_mut(), id);
+
+    let width = width.unwrap_or_else(|| instance.effective_width(config.panel.width));
+    let height = height.unwrap_or_else(|| instance.effective_height(config.panel.height));
+    let mut theme = config.get_theme();
+    theme.opacity = instance.effective_opacity(config.panel.background_opacity.unwrap_or(0.9));
+    if instance.accent.is_some() {
+        theme.accent = instance.effective_accent(&theme);
+    }
+    if instance.frameless {
+        theme = theme.frameless();
+    }
+
+    let png = Renderer::new().render_widget_to_png(widget.as_ref(), (width, height), &theme)?;
+    widget.on_stop();
+    std::fs::write(out, png).with_context(|| format!("Failed to write {}", out))?;
+    println!("Rendered '{}' at {}x{} to {}", id, width, height, out);
+    Ok(())
+}
+
+/// Handle `cosmic-desktop-widget install-autostart [--systemd | --xdg] [--uninstall]`
+///
+/// Without a method, installs a systemd unit when a user manager is running
+/// and uninstalls whichever is installed.
+fn run_autostart_command(args: &[String]) -> Result<()> {
+    const USAGE: &str =
+        "Usage: cosmic-desktop-widget install-autostart [--systemd | --xdg] [--uninstall]";
+
+    let mut method = None;
+    let mut uninstall = false;
+    for arg in args {
+        match arg.as_str() {
+            "--systemd" if method.is_none() => method = Some(autostart::Method::Systemd),
+            "--xdg" if method.is_none() => method = Some(autostart::Method::Xdg),
+            "--uninstall" => uninstall = true,
+            _ => anyhow::bail!(USAGE),
+        }
+    }
+
+    if uninstall {
+        let methods = method.map_or(autostart::Method::ALL.to_vec(), |method| vec![method]);
+        let mut removed = false;
+        for method in methods {
+            if let Some(path) = autostart::uninstall(method)? {
+                println!("Removed {}", path.display());
+                removed = true;
+            }
+        }
+        if !removed {
+            println!("Autostart was not installed");
+        }
+        return Ok(());
+    }
+
+    let method = method.unwrap_or_else(autostart::Method::detect);
+    let path = autostart::install(method)?;
+    println!("Wrote {}", path.display());
+    match method {
+        autostart::Method::Systemd => println!(
+            "Enabled {}; it starts with your next login, or now with: \
+             systemctl --user start {}",
+            autostart::SERVICE_NAME,
+            autostart::SERVICE_NAME
+        ),
+        autostart::Method::Xdg => println!("The widget starts with your next login"),
+    }
+    Ok(())
+}
+
+/// Handle `cosmic-desktop-widget google-auth`
+///
+/// Signs in with the OAuth client of the first calendar widget using
+/// `source = "google"`.
+fn run_google_auth_command() -> Result<()> {
+    let config = Config::load()?;
+    let credentials = config
+        .widgets
+        .iter()
+        .filter(|instance| instance.widget_type == "calendar")
+        .filter(|instance| instance.config.get("source").and_then(|v| v.as_str()) == Some("google"))
+        .find_map(|instance| GoogleCredentials::from_config(&instance.config))
+        .context("No calendar widget has source = \"google\" with an OAuth client configured")?;
+
+    let token = google_calendar::authorize(&credentials, |code| {
+        println!(
+            "To connect Google Calendar, open {} and enter the code: {}",
+            code.verification_url, code.user_code
+        );
+        println!("Waiting for approval...");
+    })?;
+    token.save()?;
+    println!("Signed in. The calendar widget will pick up events on its next update.");
+    Ok(())
+}
+
+/// Handle `cosmic-desktop-widget perf [on | off | toggle]` and `metrics`
+fn run_control_command(command: &str, args: &[String]) -> Result<()> {
+    let request = match (command, args) {
+        ("metrics", []) => IpcRequest::Metrics,
+        ("perf", []) => IpcRequest::Perf { enabled: None },
+        ("perf", [arg]) if arg == "toggle" => IpcRequest::Perf { enabled: None },
+        ("perf", [arg]) if arg == "on" => IpcRequest::Perf {
+            enabled: Some(true),
+        },
+        ("perf", [arg]) if arg == "off" => IpcRequest::Perf {
+            enabled: Some(false),
+        },
+        _ => anyhow::bail!("Usage: cosmic-desktop-widget <perf [on | off | toggle] | metrics>"),
+    };
+
+    match ipc::send(&request)? {
+        IpcResponse::Perf { enabled } => {
+            println!(
+                "Performance overlay {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+        IpcResponse::Metrics { metrics } => {
+            println!("{}", serde_json::to_string_pretty(&metrics)?);
+        }
+        IpcResponse::Applied => anyhow::bail!("Unexpected response from widget"),
+        IpcResponse::Error { message } => anyhow::bail!("Widget rejected request: {}", message),
+    }
+    Ok(())
+}
+
+/// Watch the user icon directory, if it exists
+fn watch_icons(dir: &std::path::Path) -> Option<IconWatcher> {
+    if !dir.is_dir() {
+        return None;
+    }
+    match IconWatcher::new(dir.to_path_buf()) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to create icon watcher, icon changes need a restart");
+            None
+        }
+    }
+}
+
+/// Watch the wallpaper palette file if the configured theme uses one
+fn watch_palette(config: &Config) -> Option<PaletteWatcher> {
+    let path = config.palette_path()?;
+    match PaletteWatcher::new(path) {
+        Ok(watcher) => {
+            tracing::info!("Palette watcher enabled");
+            Some(watcher)
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to create palette watcher, palette changes will not recolor widgets");
+            None
+        }
+    }
+}
 
 fn main() -> Result<()> {
     // Initialize logging
@@ -822,11 +2578,56 @@ fn main() -> Result<()> {
                 .add_directive(tracing::Level::INFO.into()),
         )
         .init();
+    i18n::init_from_env();
+    crash::install_panic_hook();
+
+    // Subcommands that don't need a compositor
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("theme") {
+        return run_theme_command(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("google-auth") {
+        return run_google_auth_command();
+    }
+    if let Some(command @ ("perf" | "metrics")) = args.first().map(String::as_str) {
+        return run_control_command(command, &args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("--benchmark") {
+        return run_benchmark(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("render") {
+        return run_render_command(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("install-autostart") {
+        return run_autostart_command(&args[1..]);
+    }
+
+    // `--record FILE` keeps what the widgets show, `--replay FILE` shows it
+    // again in place of live data
+    let (recorder, replay) = match args.as_slice() {
+        [flag, path] if flag == "--record" => {
+            tracing::info!(path = %path, "Recording widget content");
+            (Some(Recorder::create(std::path::Path::new(path))?), None)
+        }
+        [flag, path] if flag == "--replay" => {
+            tracing::info!(path = %path, "Replaying widget content");
+            (None, Some(Recording::load(std::path::Path::new(path))?))
+        }
+        [flag] if flag == "--record" || flag == "--replay" => {
+            anyhow::bail!("Usage: cosmic-desktop-widget {} FILE", flag)
+        }
+        _ => (None, None),
+    };
 
     tracing::info!("Starting COSMIC Desktop Widget");
 
     // Load configuration
-    let config = Config::load()?;
+    let mut config = Config::load()?;
+    config.apply_theme_schedule(&chrono::Local::now());
+    crash::record_config(&config);
+    if let Err(e) = http::configure(&config.network) {
+        tracing::warn!(error = %e, "Ignoring network proxy settings");
+    }
     tracing::info!(
         widgets = config.widgets.len(),
         panel_width = config.panel.width,
@@ -840,7 +2641,7 @@ fn main() -> Result<()> {
 
     tracing::info!("Connected to Wayland");
 
-    let (globals, event_queue) =
+    let (globals, mut event_queue) =
         registry_queue_init(&conn).context("Failed to initialize Wayland registry")?;
     let qh = event_queue.handle();
 
@@ -856,6 +2657,18 @@ fn main() -> Result<()> {
         "zwlr_layer_shell_v1 not available. Your compositor must support the Layer Shell protocol.",
     )?;
     let seat_state = SeatState::new(&globals, &qh);
+    // Optional: frosted backgrounds on compositors that implement KDE's blur protocol
+    let blur_state = BlurState::bind(&globals, &qh);
+    // Optional: skip drawing to outputs that are powered off
+    let output_power = OutputPowerState::bind(&globals, &qh);
+    // Optional: keep the screen awake while timers finish
+    let idle_inhibit = IdleInhibitState::bind(&globals, &qh);
+    // One async runtime shared by every widget that fetches data
+    let (mut data, task_results) = DataService::new()?;
+    data.set_spread(
+        config.performance.poll_jitter,
+        config.performance.stagger_polling,
+    );
 
     let mut widget = DesktopWidget::new(
         registry_state,
@@ -864,11 +2677,29 @@ fn main() -> Result<()> {
         shm_state,
         layer_shell,
         seat_state,
+        blur_state,
+        output_power,
+        idle_inhibit,
+        data,
         config,
     );
+    widget.recorder = recorder;
+    widget.replay = replay;
+    widget.restore_widget_states();
+    widget.apply_replay();
+    widget.register_sources();
+
+    // Learn the output names before pinning widgets to outputs
+    event_queue
+        .roundtrip(&mut widget)
+        .context("Failed to query Wayland outputs")?;
+
+    // Start with the layout last used on this monitor setup
+    widget.recall_placements();
 
     // Create widget surfaces (one per enabled widget)
     widget.create_widget_surfaces(&qh);
+    widget.prepare_glyph_cache();
 
     // Setup config file watcher for hot-reload
     let config_watcher = match Config::config_path() {
@@ -888,6 +2719,49 @@ fn main() -> Result<()> {
         }
     };
 
+    // Setup plugin watcher for hot-reloading manifest widgets and their scripts
+    let plugin_watcher = match WidgetManifest::manifest_dir() {
+        Some(dir) => match PluginWatcher::new(&[dir]) {
+            Ok(mut watcher) => {
+                watcher.watch_scripts(WidgetRegistry::with_user_widgets().manifests());
+                tracing::info!("Plugin watcher enabled");
+                Some(watcher)
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to create plugin watcher, plugin hot-reload disabled");
+                None
+            }
+        },
+        None => None,
+    };
+
+    // Follow the COSMIC panel as the user moves, resizes or hides it
+    let mut panel_watcher = match PanelWatcher::new() {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to create panel watcher, panel changes need a restart");
+            None
+        }
+    };
+
+    // Setup palette watcher so pywal/matugen themes recolor when regenerated
+    let palette_watcher = watch_palette(&widget.config);
+
+    // Pause rendering while the session is idle or locked
+    let session_watcher = SessionWatcher::new();
+
+    // Hold network fetches while there's no connection
+    let connectivity_watcher = ConnectivityWatcher::new();
+
+    // Control socket for `perf` / `metrics` and scripts
+    let ipc_server = match IpcServer::bind_default() {
+        Ok(server) => Some(server),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to open control socket, IPC disabled");
+            None
+        }
+    };
+
     // Setup event loop
     let mut event_loop =
         calloop::EventLoop::<DesktopWidget>::try_new().context("Failed to create event loop")?;
@@ -899,6 +2773,8 @@ fn main() -> Result<()> {
 
     // Store config watcher in a shared state for the timer callback
     let config_watcher_shared = std::sync::Arc::new(std::sync::Mutex::new(config_watcher));
+    let plugin_watcher_shared = std::sync::Arc::new(std::sync::Mutex::new(plugin_watcher));
+    let palette_watcher_shared = std::sync::Arc::new(std::sync::Mutex::new(palette_watcher));
 
     // Timer for periodic updates - uses dynamic interval based on widget needs
     // Performance optimization: Instead of fixed 100ms polling, we sleep until
@@ -907,6 +2783,9 @@ fn main() -> Result<()> {
     let timer = calloop::timer::Timer::from_duration(Duration::from_secs(1));
     let qh_clone = qh.clone();
     let config_watcher_clone = config_watcher_shared.clone();
+    let plugin_watcher_clone = plugin_watcher_shared.clone();
+    let palette_watcher_clone = palette_watcher_shared.clone();
+    let mut palette_path = widget.config.palette_path();
     event_loop
         .handle()
         .insert_source(timer, move |_deadline, _metadata, widget| {
@@ -927,9 +2806,108 @@ fn main() -> Result<()> {
                 }
             }
 
+            // Check for plugin reload events
+            if let Ok(mut watcher_guard) = plugin_watcher_clone.lock() {
+                if let Some(ref mut watcher) = *watcher_guard {
+                    if let Some(reload_event) = watcher.try_recv() {
+                        tracing::info!("Plugin reload triggered by file change");
+
+                        let manifests = widget.reload_plugins(&reload_event, &qh_clone);
+                        // New manifests may point at scripts in other directories
+                        watcher.watch_scripts(&manifests);
+                        widget.first_frame = true;
+                    }
+                }
+            }
+
+            // Move clear of the panel when its configuration changes
+            if let Some(ref mut watcher) = panel_watcher {
+                if watcher.try_recv().is_some() {
+                    watcher.follow_new_panels();
+                    widget.update_panel_margins();
+                }
+            }
+
+            // Switch between the scheduled light and dark themes
+            if widget.config.apply_theme_schedule(&chrono::Local::now()) {
+                widget.renderer.set_theme(widget.config.get_theme());
+                widget.first_frame = true;
+            }
+
+            // Check for palette changes, following theme switches from config reloads
+            if let Ok(mut watcher_guard) = palette_watcher_clone.lock() {
+                let current_path = widget.config.palette_path();
+                if current_path != palette_path {
+                    *watcher_guard = watch_palette(&widget.config);
+                    palette_path = current_path;
+                }
+
+                if let Some(ref watcher) = *watcher_guard {
+                    if watcher.try_recv().is_some() {
+                        tracing::info!("Palette regenerated, recoloring widgets");
+                        widget.renderer.set_theme(widget.config.get_theme());
+                        widget.first_frame = true;
+                    }
+                }
+            }
+
+            // Reload user icons when they are added or edited
+            if widget
+                .icon_watcher
+                .as_ref()
+                .is_some_and(IconWatcher::changed)
+            {
+                tracing::info!("Icons changed, reloading them");
+                widget.renderer.reload_icons();
+                widget.first_frame = true;
+            }
+
+            // Answer control socket requests
+            if let Some(ref server) = ipc_server {
+                while let Some(connection) = server.try_recv() {
+                    let response = widget.handle_ipc(&connection.request, &qh_clone);
+                    connection.reply(&response);
+                }
+            }
+
+            // Follow session idle/lock changes
+            if let Some(state) = session_watcher.try_recv() {
+                widget.set_session_state(state, &qh_clone);
+            }
+
+            // Follow network connectivity changes
+            if let Some(connectivity) = connectivity_watcher.try_recv() {
+                widget.set_connectivity(connectivity);
+            }
+
+            // Update widgets and redraw their surfaces, unless nobody is looking
+            if !widget.paused {
+                widget.draw_all_surfaces(&qh_clone);
+            }
+            widget.fire_due_reminders();
+            widget.update_idle_inhibitors(&qh_clone);
+
+            // Start sounds that were waiting for others to finish
+            if let Some(player) = &widget.audio {
+                player.update();
+            }
+
             // Calculate time until next widget needs updating
             // This is typically 1 second for clock updates, longer for weather
-            let next_update = widget.update_scheduler.time_until_next_update();
+            // While paused, only wake up to watch for the session resuming
+            let next_update = if widget.paused {
+                Duration::from_secs(1)
+            } else {
+                // Wake for staggered fetches too, not just on the clock tick
+                let next_fetch = widget
+                    .data
+                    .time_until_next_due(std::time::Instant::now())
+                    .unwrap_or(Duration::MAX);
+                widget
+                    .update_scheduler
+                    .time_until_next_update()
+                    .min(next_fetch)
+            };
 
             // Clamp to reasonable bounds:
             // - Minimum 50ms to avoid busy-looping on edge cases
@@ -949,13 +2927,37 @@ fn main() -> Result<()> {
         })
         .map_err(|e| anyhow::anyhow!("Failed to insert timer source: {:?}", e))?;
 
+    // Follow the icon directory being created or removed, which is rare
+    // enough not to check on every tick
+    event_loop
+        .handle()
+        .insert_source(
+            calloop::timer::Timer::from_duration(ICON_DIR_POLL),
+            |_deadline, _metadata, widget| {
+                widget.check_icon_dir();
+                calloop::timer::TimeoutAction::ToDuration(ICON_DIR_POLL)
+            },
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to insert icon directory timer: {:?}", e))?;
+
+    // Deliver background fetch results to their widgets
+    event_loop
+        .handle()
+        .insert_source(task_results, |event, _, widget| {
+            if let calloop::channel::Event::Msg(result) = event {
+                widget.handle_task_result(result);
+            }
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to insert task result source: {:?}", e))?;
+
     // Signal handling for graceful shutdown
     let signals = calloop::signals::Signals::new(&[calloop::signals::Signal::SIGINT])
         .context("Failed to create signal handler for graceful shutdown")?;
     event_loop
         .handle()
-        .insert_source(signals, |_signal, _metadata, _widget| {
+        .insert_source(signals, |_signal, _metadata, widget| {
             tracing::info!("Received SIGINT, exiting gracefully");
+            widget.stop_widgets();
             std::process::exit(0);
         })
         .map_err(|e| anyhow::anyhow!("Failed to insert signal handler: {:?}", e))?;
@@ -4,6 +4,7 @@
 //! cache efficiency, and memory usage to ensure the widget stays
 //! within performance budgets.
 
+use std::collections::{BTreeMap, HashMap};
 use std::time::{Duration, Instant};
 
 /// Performance targets
@@ -12,6 +13,103 @@ pub const TARGET_IDLE_CPU_PERCENT: f64 = 0.1;
 pub const TARGET_ACTIVE_CPU_PERCENT: f64 = 1.0;
 pub const TARGET_MEMORY_MB: u64 = 50;
 
+/// Upper bounds of the frame-time histogram buckets, in microseconds
+///
+/// Anything slower than the last bound lands in a final overflow bucket.
+pub const FRAME_BUCKETS_US: [u64; 10] = [
+    500, 1_000, 2_000, 4_000, 8_000, 16_000, 33_000, 50_000, 100_000, 250_000,
+];
+
+/// Bucketed distribution of frame times
+///
+/// Percentiles are resolved to the upper bound of the bucket they fall in
+/// (or the slowest frame seen, for the overflow bucket), so they are
+/// conservative estimates rather than exact values.
+#[derive(Debug, Clone, Default)]
+pub struct FrameHistogram {
+    buckets: [u64; FRAME_BUCKETS_US.len() + 1],
+    count: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl FrameHistogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one frame time
+    pub fn record(&mut self, duration: Duration) {
+        let micros = duration.as_micros();
+        let bucket = FRAME_BUCKETS_US
+            .iter()
+            .position(|bound| micros <= *bound as u128)
+            .unwrap_or(FRAME_BUCKETS_US.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.total += duration;
+        self.max = self.max.max(duration);
+    }
+
+    /// Frame counts per bucket, overflow bucket last
+    pub fn buckets(&self) -> &[u64] {
+        &self.buckets
+    }
+
+    /// Number of frames recorded
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Mean frame time
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_nanos((self.total.as_nanos() / self.count as u128) as u64)
+        }
+    }
+
+    /// Slowest frame recorded
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Frame time below which `percentile` percent of frames fall
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+
+        let rank = ((percentile / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (i, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let bound = match FRAME_BUCKETS_US.get(i) {
+                    Some(micros) => Duration::from_micros(*micros),
+                    None => self.max,
+                };
+                // A bucket's bound can overshoot the slowest frame actually seen
+                return bound.min(self.max);
+            }
+        }
+        self.max
+    }
+
+    pub fn p50(&self) -> Duration {
+        self.percentile(50.0)
+    }
+
+    pub fn p95(&self) -> Duration {
+        self.percentile(95.0)
+    }
+
+    pub fn p99(&self) -> Duration {
+        self.percentile(99.0)
+    }
+}
+
 /// Tracks render performance metrics
 #[derive(Debug)]
 pub struct RenderMetrics {
@@ -20,6 +118,9 @@ pub struct RenderMetrics {
     max_render_time: Duration,
     render_count: u64,
     frames_over_budget: u64,
+    frames_skipped: u64,
+    histogram: FrameHistogram,
+    per_widget: HashMap<String, FrameHistogram>,
 }
 
 impl RenderMetrics {
@@ -30,6 +131,9 @@ impl RenderMetrics {
             max_render_time: Duration::ZERO,
             render_count: 0,
             frames_over_budget: 0,
+            frames_skipped: 0,
+            histogram: FrameHistogram::new(),
+            per_widget: HashMap::new(),
         }
     }
 
@@ -37,6 +141,7 @@ impl RenderMetrics {
     pub fn record_render(&mut self, duration: Duration) {
         self.last_render_time = duration;
         self.render_count += 1;
+        self.histogram.record(duration);
 
         // Update max
         if duration > self.max_render_time {
@@ -54,6 +159,44 @@ impl RenderMetrics {
         }
     }
 
+    /// Record a render duration for the widget with instance id `widget_id`
+    pub fn record_widget_render(&mut self, widget_id: &str, duration: Duration) {
+        self.record_render(duration);
+        match self.per_widget.get_mut(widget_id) {
+            Some(histogram) => histogram.record(duration),
+            None => {
+                let mut histogram = FrameHistogram::new();
+                histogram.record(duration);
+                self.per_widget.insert(widget_id.to_string(), histogram);
+            }
+        }
+    }
+
+    /// Record a redraw skipped because the widget's content was unchanged
+    pub fn record_skip(&mut self) {
+        self.frames_skipped += 1;
+    }
+
+    /// Get the number of redraws skipped for unchanged content
+    pub fn frames_skipped(&self) -> u64 {
+        self.frames_skipped
+    }
+
+    /// Distribution of all render times
+    pub fn histogram(&self) -> &FrameHistogram {
+        &self.histogram
+    }
+
+    /// Distribution of render times for one widget
+    pub fn widget_histogram(&self, widget_id: &str) -> Option<&FrameHistogram> {
+        self.per_widget.get(widget_id)
+    }
+
+    /// Per-widget render time distributions, keyed by instance id
+    pub fn widget_histograms(&self) -> impl Iterator<Item = (&str, &FrameHistogram)> {
+        self.per_widget.iter().map(|(id, h)| (id.as_str(), h))
+    }
+
     /// Get average render time
     pub fn avg_render_time(&self) -> Duration {
         self.avg_render_time
@@ -101,7 +244,7 @@ impl Default for RenderMetrics {
 }
 
 /// Tracks cache performance metrics
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CacheMetrics {
     hits: u64,
     misses: u64,
@@ -169,6 +312,288 @@ impl Default for CacheMetrics {
     }
 }
 
+/// Update and fetch counters for one widget
+#[derive(Debug, Default)]
+struct UpdateCounters {
+    updates: u64,
+    update_failures: u64,
+    update_total: Duration,
+    update_max: Duration,
+    fetches: u64,
+    fetch_failures: u64,
+    fetch_total: Duration,
+    consecutive_failures: u32,
+    last_error: Option<String>,
+    slow_warned: bool,
+}
+
+impl UpdateCounters {
+    /// Track a success or failure, returning true if the widget just
+    /// started failing
+    fn record_outcome(&mut self, error: Option<&str>) -> bool {
+        match error {
+            Some(error) => {
+                self.consecutive_failures += 1;
+                if self.last_error.as_deref() != Some(error) {
+                    self.last_error = Some(error.to_string());
+                }
+                self.consecutive_failures == 1
+            }
+            None => {
+                self.consecutive_failures = 0;
+                self.last_error = None;
+                false
+            }
+        }
+    }
+}
+
+/// Tracks `Widget::update()` and background fetch outcomes per widget
+///
+/// Updates run on the event loop, so one taking longer than the frame
+/// budget delays every surface; those are warned about once per widget.
+/// A widget is failing while its updates report an error or its fetches
+/// fail, and is logged when it starts.
+#[derive(Debug, Default)]
+pub struct UpdateMetrics {
+    widgets: HashMap<String, UpdateCounters>,
+}
+
+impl UpdateMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn counters(&mut self, widget_id: &str) -> &mut UpdateCounters {
+        self.widgets.entry(widget_id.to_string()).or_default()
+    }
+
+    /// Record one `update()` call and the widget's error state afterwards
+    pub fn record_update(&mut self, widget_id: &str, duration: Duration, error: Option<&str>) {
+        let counters = self.counters(widget_id);
+        counters.updates += 1;
+        counters.update_total += duration;
+        counters.update_max = counters.update_max.max(duration);
+        if error.is_some() {
+            counters.update_failures += 1;
+        }
+
+        if duration.as_millis() > TARGET_RENDER_TIME_MS as u128 && !counters.slow_warned {
+            counters.slow_warned = true;
+            tracing::warn!(
+                widget = %widget_id,
+                update_ms = %as_ms(duration),
+                target_ms = %TARGET_RENDER_TIME_MS,
+                "Widget update blocked the event loop longer than a frame"
+            );
+        }
+
+        if counters.record_outcome(error) {
+            tracing::warn!(widget = %widget_id, error = ?error, "Widget started failing");
+        }
+    }
+
+    /// Record one background fetch
+    pub fn record_fetch(&mut self, widget_id: &str, duration: Duration, error: Option<&str>) {
+        let counters = self.counters(widget_id);
+        counters.fetches += 1;
+        counters.fetch_total += duration;
+        if error.is_some() {
+            counters.fetch_failures += 1;
+        }
+
+        if counters.record_outcome(error) {
+            tracing::warn!(widget = %widget_id, error = ?error, "Widget fetches started failing");
+        }
+    }
+
+    /// Snapshot for one widget
+    pub fn widget_stats(&self, widget_id: &str) -> Option<WidgetUpdateStats> {
+        self.widgets.get(widget_id).map(WidgetUpdateStats::from)
+    }
+
+    /// Snapshots for all widgets, keyed by instance id
+    pub fn all_stats(&self) -> BTreeMap<String, WidgetUpdateStats> {
+        self.widgets
+            .iter()
+            .map(|(id, counters)| (id.clone(), WidgetUpdateStats::from(counters)))
+            .collect()
+    }
+
+    /// Reset all metrics
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// Update and fetch figures for a single widget
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct WidgetUpdateStats {
+    pub update_count: u64,
+    pub update_failures: u64,
+    pub avg_update_ms: f64,
+    pub max_update_ms: f64,
+    pub fetch_count: u64,
+    pub fetch_failures: u64,
+    pub avg_fetch_ms: f64,
+    /// Failed updates or fetches in a row, 0 when healthy
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+}
+
+impl WidgetUpdateStats {
+    /// Whether the most recent update or fetch failed
+    pub fn is_failing(&self) -> bool {
+        self.consecutive_failures > 0
+    }
+}
+
+impl From<&UpdateCounters> for WidgetUpdateStats {
+    fn from(counters: &UpdateCounters) -> Self {
+        let avg = |total: Duration, count: u64| {
+            if count == 0 {
+                0.0
+            } else {
+                as_ms(total) / count as f64
+            }
+        };
+        Self {
+            update_count: counters.updates,
+            update_failures: counters.update_failures,
+            avg_update_ms: avg(counters.update_total, counters.updates),
+            max_update_ms: as_ms(counters.update_max),
+            fetch_count: counters.fetches,
+            fetch_failures: counters.fetch_failures,
+            avg_fetch_ms: avg(counters.fetch_total, counters.fetches),
+            consecutive_failures: counters.consecutive_failures,
+            last_error: counters.last_error.clone(),
+        }
+    }
+}
+
+/// Health of a background worker, such as the fetch runtime
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct WorkerStats {
+    /// Running and keeping up
+    pub healthy: bool,
+    /// Times it was replaced after dying or stalling
+    pub restarts: u32,
+    /// Milliseconds since it last showed signs of life
+    pub last_heartbeat_ms: f64,
+    /// Fetches abandoned for running too long
+    pub fetch_timeouts: u64,
+    /// Fetches that panicked
+    pub fetch_panics: u64,
+    /// What last went wrong, if anything has
+    pub last_problem: Option<String>,
+}
+
+/// How often [`MemoryMetrics::maybe_sample`] reads the process RSS
+pub const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Tracks process memory usage against [`TARGET_MEMORY_MB`]
+///
+/// The first sample is the baseline; growth is measured against it so a
+/// slow leak shows up in the summary log long before it becomes a problem.
+#[derive(Debug, Default)]
+pub struct MemoryMetrics {
+    baseline: Option<(Instant, u64)>,
+    current: Option<u64>,
+    peak: u64,
+    samples: u64,
+    over_budget: bool,
+    last_sample: Option<Instant>,
+}
+
+impl MemoryMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sample RSS if [`MEMORY_SAMPLE_INTERVAL`] has passed since the last one
+    pub fn maybe_sample(&mut self) {
+        let now = Instant::now();
+        let due = match self.last_sample {
+            None => true,
+            Some(last) => now.duration_since(last) >= MEMORY_SAMPLE_INTERVAL,
+        };
+        if !due {
+            return;
+        }
+
+        self.last_sample = Some(now);
+        if let Some(bytes) = process_rss_bytes() {
+            self.record_sample(bytes, now);
+        }
+    }
+
+    /// Record an RSS reading taken at `now`
+    ///
+    /// Warns once each time usage climbs above the memory budget.
+    pub fn record_sample(&mut self, bytes: u64, now: Instant) {
+        self.baseline.get_or_insert((now, bytes));
+        self.current = Some(bytes);
+        self.peak = self.peak.max(bytes);
+        self.samples += 1;
+
+        let over_budget = bytes > TARGET_MEMORY_MB * 1024 * 1024;
+        if over_budget && !self.over_budget {
+            tracing::warn!(
+                rss_mb = bytes / (1024 * 1024),
+                target_mb = TARGET_MEMORY_MB,
+                growth_mb = self.growth_bytes() as f64 / (1024.0 * 1024.0),
+                "Memory usage exceeds budget"
+            );
+        }
+        self.over_budget = over_budget;
+    }
+
+    /// Most recent RSS reading in bytes
+    pub fn current_bytes(&self) -> Option<u64> {
+        self.current
+    }
+
+    /// Highest RSS seen in bytes
+    pub fn peak_bytes(&self) -> u64 {
+        self.peak
+    }
+
+    /// Number of samples taken
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Change in RSS since the first sample, in bytes
+    pub fn growth_bytes(&self) -> i64 {
+        match (self.baseline, self.current) {
+            (Some((_, baseline)), Some(current)) => current as i64 - baseline as i64,
+            _ => 0,
+        }
+    }
+
+    /// Average RSS growth per hour since the first sample, in MB
+    pub fn growth_mb_per_hour(&self, now: Instant) -> f64 {
+        let Some((started, _)) = self.baseline else {
+            return 0.0;
+        };
+        let hours = now.duration_since(started).as_secs_f64() / 3600.0;
+        if hours <= 0.0 {
+            return 0.0;
+        }
+        self.growth_bytes() as f64 / (1024.0 * 1024.0) / hours
+    }
+
+    /// Whether the last sample was over [`TARGET_MEMORY_MB`]
+    pub fn is_over_budget(&self) -> bool {
+        self.over_budget
+    }
+
+    /// Reset all metrics
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
 /// A simple timer for measuring operation duration
 #[derive(Debug)]
 pub struct Timer {
@@ -194,11 +619,98 @@ impl Timer {
     }
 }
 
+/// Point-in-time copy of the headline metrics, for display and IPC
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct PerfStats {
+    /// Most recent render time in milliseconds
+    pub last_render_ms: f64,
+    /// Average render time in milliseconds
+    pub avg_render_ms: f64,
+    /// Slowest render time in milliseconds
+    pub max_render_ms: f64,
+    /// Frames per second the renderer could sustain at the average render time
+    pub fps_equivalent: f64,
+    /// Total frames rendered
+    pub render_count: u64,
+    /// Percentage of frames over the 16ms budget
+    pub frames_over_budget_pct: f64,
+    /// Redraws skipped because the content hadn't changed
+    pub frames_skipped: u64,
+    /// Median render time in milliseconds
+    pub p50_render_ms: f64,
+    /// 95th percentile render time in milliseconds
+    pub p95_render_ms: f64,
+    /// 99th percentile render time in milliseconds
+    pub p99_render_ms: f64,
+    /// Frame counts per [`FRAME_BUCKETS_US`] bucket, overflow bucket last
+    pub render_histogram: Vec<u64>,
+    /// Render times broken down by widget instance id
+    pub widgets: BTreeMap<String, WidgetRenderStats>,
+    /// Update and fetch outcomes by widget instance id
+    pub widget_updates: BTreeMap<String, WidgetUpdateStats>,
+    /// Background worker health by worker name, filled in by the owner of
+    /// the workers
+    pub workers: BTreeMap<String, WorkerStats>,
+    /// Glyph cache hit rate as a percentage
+    pub glyph_cache_hit_rate: f64,
+    /// Glyphs evicted from the cache to stay within its capacity
+    pub glyph_cache_evictions: u64,
+    /// Resident set size of the process in bytes, if it could be read
+    pub rss_bytes: Option<u64>,
+    /// Highest sampled resident set size in bytes
+    pub peak_rss_bytes: u64,
+    /// RSS change since the first sample in bytes
+    pub rss_growth_bytes: i64,
+}
+
+/// Render times for a single widget, in milliseconds
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct WidgetRenderStats {
+    pub render_count: u64,
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl From<&FrameHistogram> for WidgetRenderStats {
+    fn from(histogram: &FrameHistogram) -> Self {
+        Self {
+            render_count: histogram.count(),
+            avg_ms: as_ms(histogram.mean()),
+            p50_ms: as_ms(histogram.p50()),
+            p95_ms: as_ms(histogram.p95()),
+            p99_ms: as_ms(histogram.p99()),
+            max_ms: as_ms(histogram.max()),
+        }
+    }
+}
+
+fn as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Read the process's resident set size from `/proc/self/status`
+pub fn process_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_rss(&status)
+}
+
+/// Parse the `VmRSS:` line (in kB) of a `/proc/<pid>/status` file
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
 /// Aggregated performance metrics for the entire widget
 #[derive(Debug, Default)]
 pub struct WidgetMetrics {
     pub render: RenderMetrics,
     pub glyph_cache: CacheMetrics,
+    pub memory: MemoryMetrics,
+    pub updates: UpdateMetrics,
     last_report: Option<Instant>,
 }
 
@@ -207,12 +719,18 @@ impl WidgetMetrics {
         Self {
             render: RenderMetrics::new(),
             glyph_cache: CacheMetrics::new(),
+            memory: MemoryMetrics::new(),
+            updates: UpdateMetrics::new(),
             last_report: None,
         }
     }
 
     /// Log metrics summary if enough time has passed (every 60 seconds)
+    ///
+    /// Also takes a memory sample when one is due.
     pub fn maybe_log_summary(&mut self) {
+        self.memory.maybe_sample();
+
         let should_log = match self.last_report {
             None => true,
             Some(last) => last.elapsed() >= Duration::from_secs(60),
@@ -224,19 +742,99 @@ impl WidgetMetrics {
         }
     }
 
+    /// Snapshot of the current metrics, including a fresh RSS reading
+    pub fn stats(&self) -> PerfStats {
+        let avg = self.render.avg_render_time().as_secs_f64();
+        let histogram = self.render.histogram();
+        PerfStats {
+            last_render_ms: as_ms(self.render.last_render_time()),
+            avg_render_ms: avg * 1000.0,
+            max_render_ms: as_ms(self.render.max_render_time()),
+            fps_equivalent: if avg > 0.0 { 1.0 / avg } else { 0.0 },
+            render_count: self.render.render_count(),
+            frames_over_budget_pct: self.render.frames_over_budget_percent(),
+            frames_skipped: self.render.frames_skipped(),
+            p50_render_ms: as_ms(histogram.p50()),
+            p95_render_ms: as_ms(histogram.p95()),
+            p99_render_ms: as_ms(histogram.p99()),
+            render_histogram: histogram.buckets().to_vec(),
+            widgets: self
+                .render
+                .widget_histograms()
+                .map(|(id, h)| (id.to_string(), WidgetRenderStats::from(h)))
+                .collect(),
+            widget_updates: self.updates.all_stats(),
+            workers: BTreeMap::new(),
+            glyph_cache_hit_rate: self.glyph_cache.hit_rate(),
+            glyph_cache_evictions: self.glyph_cache.evictions(),
+            rss_bytes: process_rss_bytes(),
+            peak_rss_bytes: self.memory.peak_bytes(),
+            rss_growth_bytes: self.memory.growth_bytes(),
+        }
+    }
+
     /// Log a summary of all metrics
     pub fn log_summary(&self) {
         tracing::debug!(
             render_count = %self.render.render_count(),
             avg_render_ms = %self.render.avg_render_time().as_secs_f64() * 1000.0,
             max_render_ms = %self.render.max_render_time().as_secs_f64() * 1000.0,
+            p50_render_ms = %as_ms(self.render.histogram().p50()),
+            p95_render_ms = %as_ms(self.render.histogram().p95()),
+            p99_render_ms = %as_ms(self.render.histogram().p99()),
             frames_over_budget_pct = %self.render.frames_over_budget_percent(),
+            frames_skipped = %self.render.frames_skipped(),
             cache_hit_rate_pct = %self.glyph_cache.hit_rate(),
             cache_hits = %self.glyph_cache.hits(),
             cache_misses = %self.glyph_cache.misses(),
             cache_evictions = %self.glyph_cache.evictions(),
             "Performance metrics summary"
         );
+
+        if let Some(current) = self.memory.current_bytes() {
+            tracing::debug!(
+                rss_mb = %current as f64 / (1024.0 * 1024.0),
+                peak_rss_mb = %self.memory.peak_bytes() as f64 / (1024.0 * 1024.0),
+                growth_mb = %self.memory.growth_bytes() as f64 / (1024.0 * 1024.0),
+                growth_mb_per_hour = %self.memory.growth_mb_per_hour(Instant::now()),
+                target_mb = %TARGET_MEMORY_MB,
+                "Memory usage summary"
+            );
+        }
+
+        for (widget, histogram) in self.render.widget_histograms() {
+            tracing::debug!(
+                widget = %widget,
+                render_count = %histogram.count(),
+                avg_render_ms = %as_ms(histogram.mean()),
+                p95_render_ms = %as_ms(histogram.p95()),
+                max_render_ms = %as_ms(histogram.max()),
+                "Widget render times"
+            );
+        }
+
+        for (widget, stats) in self.updates.all_stats() {
+            if stats.is_failing() {
+                tracing::info!(
+                    widget = %widget,
+                    consecutive_failures = %stats.consecutive_failures,
+                    update_failures = %stats.update_failures,
+                    fetch_failures = %stats.fetch_failures,
+                    last_error = ?stats.last_error,
+                    "Widget is failing"
+                );
+            } else {
+                tracing::debug!(
+                    widget = %widget,
+                    update_count = %stats.update_count,
+                    avg_update_ms = %stats.avg_update_ms,
+                    max_update_ms = %stats.max_update_ms,
+                    fetch_count = %stats.fetch_count,
+                    avg_fetch_ms = %stats.avg_fetch_ms,
+                    "Widget update times"
+                );
+            }
+        }
     }
 }
 
@@ -269,6 +867,53 @@ mod tests {
         assert_eq!(metrics.frames_over_budget_percent(), 50.0);
     }
 
+    #[test]
+    fn test_frame_histogram_percentiles() {
+        let mut histogram = FrameHistogram::new();
+        assert_eq!(histogram.p99(), Duration::ZERO);
+
+        // 90 fast frames, 9 around the budget and one very slow one
+        for _ in 0..90 {
+            histogram.record(Duration::from_micros(1500));
+        }
+        for _ in 0..9 {
+            histogram.record(Duration::from_millis(12));
+        }
+        histogram.record(Duration::from_millis(400));
+
+        assert_eq!(histogram.count(), 100);
+        assert_eq!(histogram.p50(), Duration::from_millis(2));
+        assert_eq!(histogram.p95(), Duration::from_millis(16));
+        assert_eq!(histogram.p99(), Duration::from_millis(16));
+        assert_eq!(histogram.percentile(100.0), Duration::from_millis(400));
+        assert_eq!(histogram.buckets()[FRAME_BUCKETS_US.len()], 1);
+    }
+
+    #[test]
+    fn test_per_widget_render_stats() {
+        let mut metrics = WidgetMetrics::new();
+        metrics
+            .render
+            .record_widget_render("clock", Duration::from_millis(1));
+        metrics
+            .render
+            .record_widget_render("weather", Duration::from_millis(30));
+        metrics
+            .render
+            .record_widget_render("weather", Duration::from_millis(10));
+
+        assert_eq!(metrics.render.render_count(), 3);
+        assert_eq!(
+            metrics.render.widget_histogram("weather").unwrap().count(),
+            2
+        );
+
+        let stats = metrics.stats();
+        assert_eq!(stats.widgets.len(), 2);
+        assert_eq!(stats.widgets["weather"].max_ms, 30.0);
+        assert_eq!(stats.widgets["clock"].p99_ms, 1.0);
+    }
+
     #[test]
     fn test_cache_metrics() {
         let mut metrics = CacheMetrics::new();
@@ -288,6 +933,61 @@ mod tests {
         assert_eq!(metrics.hit_rate(), 0.0);
     }
 
+    #[test]
+    fn test_update_metrics() {
+        let mut metrics = UpdateMetrics::new();
+        metrics.record_update("clock", Duration::from_millis(1), None);
+        metrics.record_update("clock", Duration::from_millis(3), None);
+        metrics.record_fetch("weather", Duration::from_millis(200), Some("timeout"));
+        metrics.record_fetch("weather", Duration::from_millis(400), Some("timeout"));
+
+        let clock = metrics.widget_stats("clock").unwrap();
+        assert_eq!(clock.update_count, 2);
+        assert!((clock.avg_update_ms - 2.0).abs() < 0.01);
+        assert_eq!(clock.max_update_ms, 3.0);
+        assert!(!clock.is_failing());
+
+        let weather = metrics.widget_stats("weather").unwrap();
+        assert_eq!(weather.fetch_failures, 2);
+        assert_eq!(weather.consecutive_failures, 2);
+        assert_eq!(weather.last_error.as_deref(), Some("timeout"));
+        assert!((weather.avg_fetch_ms - 300.0).abs() < 0.01);
+
+        // A successful fetch clears the failure streak but not the totals
+        metrics.record_fetch("weather", Duration::from_millis(100), None);
+        let weather = metrics.widget_stats("weather").unwrap();
+        assert!(!weather.is_failing());
+        assert_eq!(weather.fetch_failures, 2);
+        assert_eq!(metrics.all_stats().len(), 2);
+    }
+
+    #[test]
+    fn test_memory_metrics() {
+        const MB: u64 = 1024 * 1024;
+        let start = Instant::now();
+        let mut metrics = MemoryMetrics::new();
+        assert_eq!(metrics.growth_bytes(), 0);
+
+        metrics.record_sample(20 * MB, start);
+        metrics.record_sample(30 * MB, start + Duration::from_secs(1800));
+        assert_eq!(metrics.current_bytes(), Some(30 * MB));
+        assert_eq!(metrics.growth_bytes(), (10 * MB) as i64);
+        let rate = metrics.growth_mb_per_hour(start + Duration::from_secs(1800));
+        assert!((rate - 20.0).abs() < 0.01);
+        assert!(!metrics.is_over_budget());
+
+        metrics.record_sample(
+            (TARGET_MEMORY_MB + 5) * MB,
+            start + Duration::from_secs(3600),
+        );
+        assert!(metrics.is_over_budget());
+
+        metrics.record_sample(25 * MB, start + Duration::from_secs(5400));
+        assert!(!metrics.is_over_budget());
+        assert_eq!(metrics.peak_bytes(), (TARGET_MEMORY_MB + 5) * MB);
+        assert_eq!(metrics.samples(), 4);
+    }
+
     #[test]
     fn test_timer() {
         let timer = Timer::start();
@@ -307,4 +1007,27 @@ mod tests {
         assert_eq!(metrics.render.render_count(), 1);
         assert_eq!(metrics.glyph_cache.hit_rate(), 50.0);
     }
+
+    #[test]
+    fn test_perf_stats() {
+        let mut metrics = WidgetMetrics::new();
+        assert_eq!(metrics.stats().fps_equivalent, 0.0);
+
+        metrics.render.record_render(Duration::from_millis(4));
+        let stats = metrics.stats();
+        assert_eq!(stats.render_count, 1);
+        assert!((stats.avg_render_ms - 4.0).abs() < 0.01);
+        assert!((stats.fps_equivalent - 250.0).abs() < 0.5);
+
+        metrics.render.record_skip();
+        assert_eq!(metrics.stats().frames_skipped, 1);
+        assert_eq!(metrics.render.render_count(), 1);
+    }
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tcosmic-desktop\nVmPeak:\t  52000 kB\nVmRSS:\t   23456 kB\n";
+        assert_eq!(parse_vm_rss(status), Some(23456 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tkthreadd\n"), None);
+    }
 }
@@ -0,0 +1,76 @@
+//! Desktop notifications over the freedesktop notification service
+
+use std::collections::HashMap;
+use std::thread;
+
+use zbus::zvariant::Value;
+
+const DESTINATION: &str = "org.freedesktop.Notifications";
+const PATH: &str = "/org/freedesktop/Notifications";
+const INTERFACE: &str = "org.freedesktop.Notifications";
+const APP_NAME: &str = "COSMIC Desktop Widget";
+
+/// A desktop notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Title line
+    pub summary: String,
+    /// Text below the title
+    pub body: String,
+    /// Icon name from the icon theme
+    pub icon: String,
+}
+
+impl Notification {
+    /// A notification with the default icon
+    pub fn new(summary: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            summary: summary.into(),
+            body: body.into(),
+            icon: "appointment-soon".to_string(),
+        }
+    }
+
+    /// Use `icon` from the icon theme
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = icon.into();
+        self
+    }
+
+    /// Show the notification
+    ///
+    /// Sent from a background thread so a slow or missing notification
+    /// daemon never holds up the event loop; failures are only logged.
+    pub fn send(self) {
+        let spawned = thread::Builder::new()
+            .name("notification".to_string())
+            .spawn(move || {
+                if let Err(e) = self.send_blocking() {
+                    tracing::warn!(error = %e, summary = %self.summary, "Failed to show notification");
+                }
+            });
+        if let Err(e) = spawned {
+            tracing::warn!(error = %e, "Failed to spawn notification thread");
+        }
+    }
+
+    fn send_blocking(&self) -> zbus::Result<u32> {
+        let connection = zbus::blocking::Connection::session()?;
+        let proxy = zbus::blocking::Proxy::new(&connection, DESTINATION, PATH, INTERFACE)?;
+        let actions: Vec<&str> = Vec::new();
+        let hints: HashMap<&str, Value<'_>> = HashMap::new();
+        proxy.call(
+            "Notify",
+            &(
+                APP_NAME,
+                0u32,
+                self.icon.as_str(),
+                self.summary.as_str(),
+                self.body.as_str(),
+                actions,
+                hints,
+                -1i32,
+            ),
+        )
+    }
+}
@@ -0,0 +1,119 @@
+//! Hyprland bar detection
+//!
+//! Hyprland reports, for every monitor, how much each edge is reserved by
+//! layer-shell exclusive zones (waybar, ags, eww and the like) and by the
+//! user's `monitor` reserved-area settings. That's read from its request
+//! socket with `j/monitors`, the same as `hyprctl -j monitors`.
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::{reserved_edges, PanelBackend, PanelInfo};
+
+/// How long to wait for Hyprland to answer
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Deserialize)]
+struct Monitor {
+    name: String,
+    /// Left, top, right and bottom
+    reserved: [i32; 4],
+}
+
+/// Bars on Hyprland, found through its IPC socket
+pub struct HyprlandPanels {
+    socket: Option<PathBuf>,
+}
+
+impl HyprlandPanels {
+    /// Use the socket of the instance in `$HYPRLAND_INSTANCE_SIGNATURE`
+    ///
+    /// Hyprland 0.40 moved its sockets from `/tmp/hypr` into
+    /// `$XDG_RUNTIME_DIR/hypr`; both are tried.
+    pub fn from_env() -> Self {
+        let socket = std::env::var("HYPRLAND_INSTANCE_SIGNATURE")
+            .ok()
+            .filter(|signature| !signature.is_empty())
+            .and_then(|signature| {
+                dirs::runtime_dir()
+                    .into_iter()
+                    .chain(Some(PathBuf::from("/tmp")))
+                    .map(|dir| dir.join("hypr").join(&signature).join(".socket.sock"))
+                    .find(|socket| socket.exists())
+            });
+        Self { socket }
+    }
+
+    /// Use the request socket at `socket`
+    pub fn with_socket(socket: impl Into<PathBuf>) -> Self {
+        Self {
+            socket: Some(socket.into()),
+        }
+    }
+}
+
+impl PanelBackend for HyprlandPanels {
+    fn name(&self) -> &'static str {
+        "hyprland"
+    }
+
+    fn detect(&self) -> Result<Vec<PanelInfo>> {
+        let socket = self
+            .socket
+            .as_ref()
+            .context("Hyprland IPC socket not found")?;
+        let mut stream = UnixStream::connect(socket)
+            .with_context(|| format!("Failed to connect to {}", socket.display()))?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+
+        stream
+            .write_all(b"j/monitors")
+            .context("Failed to send Hyprland IPC request")?;
+        // Hyprland closes the connection after answering
+        let mut reply = String::new();
+        stream
+            .read_to_string(&mut reply)
+            .context("Failed to read Hyprland IPC reply")?;
+        parse_monitors(&reply)
+    }
+}
+
+fn parse_monitors(reply: &str) -> Result<Vec<PanelInfo>> {
+    let monitors: Vec<Monitor> =
+        serde_json::from_str(reply).context("Failed to parse Hyprland monitors")?;
+    Ok(monitors
+        .iter()
+        .flat_map(|monitor| {
+            let [left, top, right, bottom] = monitor.reserved;
+            reserved_edges(Some(&monitor.name), top, right, bottom, left)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::panel::PanelAnchor;
+
+    #[test]
+    fn test_parse_monitors() {
+        let reply = r#"[
+            {"id": 0, "name": "eDP-1", "width": 1920, "height": 1080,
+             "reserved": [0, 0, 0, 42], "scale": 1.0, "focused": true},
+            {"id": 1, "name": "DP-2", "width": 2560, "height": 1440,
+             "reserved": [0, 0, 0, 0], "scale": 1.0, "focused": false}
+        ]"#;
+        let panels = parse_monitors(reply).unwrap();
+        assert_eq!(panels.len(), 1);
+        assert_eq!(panels[0].anchor, PanelAnchor::Bottom);
+        assert_eq!(panels[0].reserved_space(), 42);
+        assert_eq!(panels[0].output.as_deref(), Some("eDP-1"));
+
+        assert!(parse_monitors("unknown request").is_err());
+    }
+}
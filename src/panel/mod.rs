@@ -0,0 +1,892 @@
+//! Panel detection for avoiding overlap
+//!
+//! Reads cosmic-panel's own configuration to determine where each panel is
+//! positioned, how big it is, whether it hides and which output it's on,
+//! allowing widgets to avoid overlap. The [`PanelWatcher`] subscribes to
+//! that configuration, so margins can be adjusted as soon as the user adds,
+//! moves, resizes or auto-hides a panel.
+//!
+//! Other compositors are supported through [`PanelBackend`]: Sway and
+//! Hyprland are asked over their IPC sockets how much of each output their
+//! bars reserve, and anywhere else the compositor itself keeps widgets clear
+//! of exclusive zones.
+
+mod hyprland;
+mod sway;
+
+pub use hyprland::HyprlandPanels;
+pub use sway::SwayPanels;
+
+use anyhow::{Context, Result};
+use notify::{Config as NotifyConfig, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Instant;
+use tracing::{debug, warn};
+
+/// cosmic-config component listing the configured panels; each panel's
+/// settings are in `<ENTRIES_COMPONENT>.<entry>`
+const ENTRIES_COMPONENT: &str = "com.system76.CosmicPanel";
+
+/// Panels cosmic-panel ships with, used when the list can't be read
+const DEFAULT_ENTRIES: [&str; 2] = ["Panel", "Dock"];
+
+/// cosmic-config version of the panel settings
+const CONFIG_VERSION: &str = "v1";
+
+/// Panel anchor position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanelAnchor {
+    #[default]
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// Panel size preset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanelSize {
+    XS,
+    #[default]
+    S,
+    M,
+    L,
+    XL,
+    /// Exact size, from a custom cosmic-panel size or another compositor's
+    /// bar
+    Pixels(i32),
+}
+
+impl PanelSize {
+    /// Convert panel size to approximate pixel height/width
+    pub fn to_pixels(self) -> i32 {
+        match self {
+            PanelSize::XS => 28,
+            PanelSize::S => 32,
+            PanelSize::M => 36,
+            PanelSize::L => 40,
+            PanelSize::XL => 48,
+            PanelSize::Pixels(pixels) => pixels,
+        }
+    }
+}
+
+/// Information about a detected panel
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PanelInfo {
+    pub anchor: PanelAnchor,
+    pub size: PanelSize,
+    pub exclusive_zone: bool,
+    pub margin: i32,
+    /// Whether the panel hides until the pointer reaches its edge
+    pub autohide: bool,
+    /// Output the panel is on, or `None` for every output
+    pub output: Option<String>,
+}
+
+impl PanelInfo {
+    /// A bar reserving exactly `pixels` along one edge of `output`
+    pub fn reserved(anchor: PanelAnchor, pixels: i32, output: Option<String>) -> Self {
+        Self {
+            anchor,
+            size: PanelSize::Pixels(pixels),
+            exclusive_zone: true,
+            margin: 0,
+            autohide: false,
+            output,
+        }
+    }
+
+    /// Whether the panel is shown on `output`
+    pub fn is_on(&self, output: &str) -> bool {
+        self.output.as_deref().map_or(true, |name| name == output)
+    }
+
+    /// Get the total space reserved by this panel (size + margin)
+    ///
+    /// A hidden panel only slides over the desktop when it is needed, so it
+    /// reserves nothing.
+    pub fn reserved_space(&self) -> i32 {
+        if self.exclusive_zone && !self.autohide {
+            self.size.to_pixels() + self.margin
+        } else {
+            0
+        }
+    }
+}
+
+// ============================================================================
+// Backends
+// ============================================================================
+
+/// A way of finding out where a compositor's panels are
+pub trait PanelBackend {
+    /// Name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Panels currently reserving screen space
+    fn detect(&self) -> Result<Vec<PanelInfo>>;
+
+    /// Whether widget surfaces should let the compositor move them clear of
+    /// panels, instead of adding margins themselves
+    fn uses_exclusive_zones(&self) -> bool {
+        false
+    }
+}
+
+/// Which [`PanelBackend`] to use, set with `panel_detection` in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelBackendKind {
+    /// Pick one from the running session
+    #[default]
+    Auto,
+    /// cosmic-panel's configuration files
+    Cosmic,
+    /// Sway's IPC socket
+    Sway,
+    /// Hyprland's IPC socket
+    Hyprland,
+    /// Leave it to the compositor's layer-shell exclusive zones
+    ExclusiveZone,
+}
+
+impl PanelBackendKind {
+    /// Resolve `Auto` to the backend for the running compositor
+    pub fn resolve(self) -> Self {
+        if self != Self::Auto {
+            return self;
+        }
+        let env = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+        if env("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            Self::Hyprland
+        } else if env("SWAYSOCK").is_some() {
+            Self::Sway
+        } else if std::env::var("XDG_CURRENT_DESKTOP")
+            .is_ok_and(|desktop| desktop.split(':').any(|d| d.eq_ignore_ascii_case("cosmic")))
+            || CosmicPanels::system().is_configured()
+        {
+            Self::Cosmic
+        } else {
+            Self::ExclusiveZone
+        }
+    }
+
+    /// Create the backend this resolves to
+    pub fn backend(self) -> Box<dyn PanelBackend> {
+        match self.resolve() {
+            Self::Cosmic | Self::Auto => Box::new(CosmicPanels::system()),
+            Self::Sway => Box::new(SwayPanels::from_env()),
+            Self::Hyprland => Box::new(HyprlandPanels::from_env()),
+            Self::ExclusiveZone => Box::new(ExclusiveZones),
+        }
+    }
+}
+
+/// COSMIC panels and docks, read from cosmic-panel's configuration
+///
+/// Settings are looked up the way cosmic-config does: the user's value in
+/// `~/.config/cosmic` wins over the system default in
+/// `$XDG_DATA_DIRS/cosmic`, one file per key.
+pub struct CosmicPanels {
+    /// `cosmic` configuration roots, user first
+    roots: Vec<PathBuf>,
+}
+
+impl CosmicPanels {
+    /// Read the user's configuration and the system defaults
+    pub fn system() -> Self {
+        let data_dirs = std::env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+        let roots = dirs::config_dir()
+            .into_iter()
+            .chain(
+                data_dirs
+                    .split(':')
+                    .filter(|dir| !dir.is_empty())
+                    .map(PathBuf::from),
+            )
+            .map(|dir| dir.join("cosmic"))
+            .collect();
+        Self { roots }
+    }
+
+    /// Read only the configuration under `config_dir`
+    pub fn with_config_dir(config_dir: impl AsRef<Path>) -> Self {
+        Self {
+            roots: vec![config_dir.as_ref().join("cosmic")],
+        }
+    }
+
+    /// Whether cosmic-panel is installed or configured
+    pub fn is_configured(&self) -> bool {
+        self.entries().iter().any(|entry| self.has_component(entry))
+    }
+
+    /// Names of the configured panels, e.g. "Panel" and "Dock"
+    pub fn entries(&self) -> Vec<String> {
+        self.read_key(ENTRIES_COMPONENT, "entries")
+            .map(|value| parse_entries(&value))
+            .filter(|entries| !entries.is_empty())
+            .unwrap_or_else(|| DEFAULT_ENTRIES.iter().map(|e| e.to_string()).collect())
+    }
+
+    /// The user's configuration directories to subscribe to: every panel's,
+    /// then the panel list's
+    pub fn component_dirs(&self) -> Vec<PathBuf> {
+        let Some(root) = self.roots.first() else {
+            return Vec::new();
+        };
+        self.entries()
+            .iter()
+            .map(|entry| root.join(component(entry)))
+            .chain(Some(root.join(ENTRIES_COMPONENT)))
+            .collect()
+    }
+
+    /// Read every panel that has configuration
+    pub fn panels(&self) -> Vec<PanelInfo> {
+        self.entries()
+            .iter()
+            .filter(|entry| self.has_component(entry))
+            .map(|entry| {
+                let panel = self.read_panel(&component(entry));
+                debug!(
+                    panel = %entry,
+                    anchor = ?panel.anchor,
+                    size = ?panel.size,
+                    output = ?panel.output,
+                    reserved = panel.reserved_space(),
+                    "Detected COSMIC panel"
+                );
+                panel
+            })
+            .collect()
+    }
+
+    fn has_component(&self, entry: &str) -> bool {
+        self.roots
+            .iter()
+            .any(|root| root.join(component(entry)).join(CONFIG_VERSION).is_dir())
+    }
+
+    /// Value of one key, from the first root that has it
+    fn read_key(&self, component: &str, key: &str) -> Option<String> {
+        self.roots.iter().find_map(|root| {
+            fs::read_to_string(root.join(component).join(CONFIG_VERSION).join(key)).ok()
+        })
+    }
+
+    /// Read one panel's settings; missing keys get cosmic-panel's defaults
+    fn read_panel(&self, component: &str) -> PanelInfo {
+        let key = |name: &str| self.read_key(component, name);
+
+        let anchor = key("anchor")
+            .and_then(|s| PanelDetection::parse_anchor(&s))
+            .unwrap_or_default();
+
+        let size = key("size")
+            .and_then(|s| PanelDetection::parse_size(&s))
+            .unwrap_or_default();
+
+        let exclusive_zone = key("exclusive_zone")
+            .map(|s| s.trim() == "true")
+            .unwrap_or(true);
+
+        let margin = key("margin")
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(0);
+
+        // Stored as a RON option: "None" or "Some((wait_time: ..))"
+        let autohide = key("autohide")
+            .map(|s| s.trim().starts_with("Some"))
+            .unwrap_or(false);
+
+        let output = key("output").and_then(|s| parse_output(&s));
+
+        PanelInfo {
+            anchor,
+            size,
+            exclusive_zone,
+            margin,
+            autohide,
+            output,
+        }
+    }
+}
+
+impl PanelBackend for CosmicPanels {
+    fn name(&self) -> &'static str {
+        "cosmic"
+    }
+
+    fn detect(&self) -> Result<Vec<PanelInfo>> {
+        Ok(self.panels())
+    }
+}
+
+/// cosmic-config component of the panel named `entry`
+fn component(entry: &str) -> String {
+    format!("{}.{}", ENTRIES_COMPONENT, entry)
+}
+
+/// Parse the RON panel list: `["Panel", "Dock"]`
+fn parse_entries(value: &str) -> Vec<String> {
+    value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|entry| entry.trim().trim_matches('"').to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+/// Parse the RON output setting: `All`, `Active` or `Name("DP-1")`
+///
+/// Only a named output pins the panel; the others show it wherever the
+/// widgets could be too.
+fn parse_output(value: &str) -> Option<String> {
+    let name = value
+        .trim()
+        .strip_prefix("Name(")?
+        .strip_suffix(')')?
+        .trim()
+        .trim_matches('"');
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Generic layer-shell compositors
+///
+/// Nothing is detected; widget surfaces are instead created with an
+/// exclusive zone of 0, which asks the compositor to place them outside
+/// every bar's exclusive zone.
+pub struct ExclusiveZones;
+
+impl PanelBackend for ExclusiveZones {
+    fn name(&self) -> &'static str {
+        "exclusive_zone"
+    }
+
+    fn detect(&self) -> Result<Vec<PanelInfo>> {
+        Ok(Vec::new())
+    }
+
+    fn uses_exclusive_zones(&self) -> bool {
+        true
+    }
+}
+
+/// Panels along each edge from the space reserved on one output
+fn reserved_edges(
+    output: Option<&str>,
+    top: i32,
+    right: i32,
+    bottom: i32,
+    left: i32,
+) -> Vec<PanelInfo> {
+    [
+        (PanelAnchor::Top, top),
+        (PanelAnchor::Right, right),
+        (PanelAnchor::Bottom, bottom),
+        (PanelAnchor::Left, left),
+    ]
+    .into_iter()
+    .filter(|&(_, pixels)| pixels > 0)
+    .map(|(anchor, pixels)| PanelInfo::reserved(anchor, pixels, output.map(String::from)))
+    .collect()
+}
+
+// ============================================================================
+// Detection
+// ============================================================================
+
+/// Detected panels on the system
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PanelDetection {
+    pub panels: Vec<PanelInfo>,
+}
+
+impl PanelDetection {
+    /// Detect panels with the backend for the running compositor
+    pub fn detect() -> Self {
+        Self::detect_with(PanelBackendKind::Auto)
+    }
+
+    /// Detect panels with the given backend
+    pub fn detect_with(kind: PanelBackendKind) -> Self {
+        Self::from_backend(kind.backend().as_ref())
+    }
+
+    /// Detect panels with `backend`, finding none if it fails
+    pub fn from_backend(backend: &dyn PanelBackend) -> Self {
+        match backend.detect() {
+            Ok(panels) => {
+                debug!(
+                    backend = backend.name(),
+                    count = panels.len(),
+                    "Detected panels"
+                );
+                Self { panels }
+            }
+            Err(e) => {
+                warn!(backend = backend.name(), error = %e, "Panel detection failed");
+                Self::default()
+            }
+        }
+    }
+
+    /// Detect panels from the COSMIC configuration under `config_dir`
+    pub fn detect_in(config_dir: &Path) -> Self {
+        let detection = Self {
+            panels: CosmicPanels::with_config_dir(config_dir).panels(),
+        };
+        if detection.panels.is_empty() {
+            debug!("No panels detected, using defaults");
+        }
+        detection
+    }
+
+    /// Configuration directories of the panels under `config_dir`
+    pub fn component_dirs(config_dir: &Path) -> Vec<PathBuf> {
+        CosmicPanels::with_config_dir(config_dir).component_dirs()
+    }
+
+    /// Parse anchor string
+    fn parse_anchor(s: &str) -> Option<PanelAnchor> {
+        match s.trim() {
+            "Top" => Some(PanelAnchor::Top),
+            "Bottom" => Some(PanelAnchor::Bottom),
+            "Left" => Some(PanelAnchor::Left),
+            "Right" => Some(PanelAnchor::Right),
+            other => {
+                warn!(value = other, "Unknown panel anchor");
+                None
+            }
+        }
+    }
+
+    /// Parse size string
+    fn parse_size(s: &str) -> Option<PanelSize> {
+        match s.trim() {
+            "XS" => Some(PanelSize::XS),
+            "S" => Some(PanelSize::S),
+            "M" => Some(PanelSize::M),
+            "L" => Some(PanelSize::L),
+            "XL" => Some(PanelSize::XL),
+            other => {
+                let custom = other
+                    .strip_prefix("Custom(")
+                    .and_then(|s| s.strip_suffix(')'))
+                    .and_then(|s| s.trim().parse().ok());
+                if custom.is_none() {
+                    warn!(value = other, "Unknown panel size");
+                }
+                custom.map(PanelSize::Pixels)
+            }
+        }
+    }
+
+    /// Get margin adjustments needed to avoid all panels
+    pub fn margin_adjustments(&self) -> MarginAdjustments {
+        self.margin_adjustments_for(None)
+    }
+
+    /// Get margin adjustments for a widget on `output`
+    ///
+    /// Panels pinned to other outputs are ignored. Without an output the
+    /// compositor may put the widget anywhere, so every panel counts.
+    pub fn margin_adjustments_for(&self, output: Option<&str>) -> MarginAdjustments {
+        let mut adjustments = MarginAdjustments::default();
+
+        let panels = self
+            .panels
+            .iter()
+            .filter(|panel| output.map_or(true, |output| panel.is_on(output)));
+        for panel in panels {
+            let space = panel.reserved_space();
+            match panel.anchor {
+                PanelAnchor::Top => adjustments.top = adjustments.top.max(space),
+                PanelAnchor::Bottom => adjustments.bottom = adjustments.bottom.max(space),
+                PanelAnchor::Left => adjustments.left = adjustments.left.max(space),
+                PanelAnchor::Right => adjustments.right = adjustments.right.max(space),
+            }
+        }
+
+        // Add a small gap between panel and widget
+        const PANEL_GAP: i32 = 8;
+        if adjustments.top > 0 {
+            adjustments.top += PANEL_GAP;
+        }
+        if adjustments.bottom > 0 {
+            adjustments.bottom += PANEL_GAP;
+        }
+        if adjustments.left > 0 {
+            adjustments.left += PANEL_GAP;
+        }
+        if adjustments.right > 0 {
+            adjustments.right += PANEL_GAP;
+        }
+
+        adjustments
+    }
+}
+
+/// Margin adjustments to avoid panels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MarginAdjustments {
+    pub top: i32,
+    pub bottom: i32,
+    pub left: i32,
+    pub right: i32,
+}
+
+// ============================================================================
+// Watcher
+// ============================================================================
+
+/// Panel configuration change event
+#[derive(Debug, Clone)]
+pub struct PanelChangeEvent {
+    /// Timestamp of the most recent change
+    pub timestamp: Instant,
+}
+
+/// Panel configuration watcher
+///
+/// cosmic-panel keeps its settings as cosmic-config files, one per key,
+/// which it rewrites whenever the user changes the panel. This subscribes
+/// to every panel's component and to the panel list, the same files a
+/// cosmic-config watcher follows, and reports every change so the caller
+/// can run [`PanelDetection::detect`] again. Components that don't exist
+/// are skipped.
+pub struct PanelWatcher {
+    watcher: RecommendedWatcher,
+    receiver: mpsc::Receiver<PanelChangeEvent>,
+    watched: Vec<PathBuf>,
+    /// Where to look for panels added later, for watchers from [`Self::new`]
+    config_dir: Option<PathBuf>,
+}
+
+impl PanelWatcher {
+    /// Watch the panel configuration in the user's config directory
+    pub fn new() -> Result<Self> {
+        let config_dir = dirs::config_dir().context("No config directory")?;
+        let mut watcher = Self::watch_dirs(&PanelDetection::component_dirs(&config_dir))?;
+        watcher.config_dir = Some(config_dir);
+        Ok(watcher)
+    }
+
+    /// Watch the panel configuration in `dirs`, which don't have to exist
+    pub fn watch_dirs(dirs: &[PathBuf]) -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<notify::Event, notify::Error>| match res {
+                Ok(event) => {
+                    if event.kind.is_access() || event.paths.is_empty() {
+                        return;
+                    }
+                    tracing::debug!(paths = ?event.paths, "Panel config changed");
+                    if let Err(e) = tx.send(PanelChangeEvent {
+                        timestamp: Instant::now(),
+                    }) {
+                        tracing::error!(error = %e, "Failed to send panel change event");
+                    }
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Panel watcher error");
+                }
+            },
+            NotifyConfig::default(),
+        )
+        .context("Failed to create panel watcher")?;
+
+        let mut watched = Vec::new();
+        for dir in dirs.iter().filter(|dir| dir.is_dir()) {
+            // Versioned subdirectories (v1, ...) hold the actual keys
+            watcher
+                .watch(dir, RecursiveMode::Recursive)
+                .with_context(|| format!("Failed to watch panel config: {}", dir.display()))?;
+            tracing::info!(path = %dir.display(), "Panel config watcher initialized");
+            watched.push(dir.clone());
+        }
+
+        Ok(Self {
+            watcher,
+            receiver: rx,
+            watched,
+            config_dir: None,
+        })
+    }
+
+    /// Number of panel components being watched
+    pub fn watched(&self) -> usize {
+        self.watched.len()
+    }
+
+    /// Start watching panels that were added since the last call
+    ///
+    /// Call this after a change, which may have been a new panel being
+    /// added to the panel list.
+    pub fn follow_new_panels(&mut self) {
+        let Some(config_dir) = &self.config_dir else {
+            return;
+        };
+        for dir in PanelDetection::component_dirs(config_dir) {
+            if !dir.is_dir() || self.watched.contains(&dir) {
+                continue;
+            }
+            match self.watcher.watch(&dir, RecursiveMode::Recursive) {
+                Ok(()) => {
+                    tracing::info!(path = %dir.display(), "Watching new panel config");
+                    self.watched.push(dir);
+                }
+                Err(e) => {
+                    tracing::warn!(path = %dir.display(), error = %e, "Failed to watch panel config");
+                }
+            }
+        }
+    }
+
+    /// Try to receive a change event (non-blocking)
+    ///
+    /// A panel change rewrites several keys at once, so everything queued
+    /// since the last poll is merged into one event.
+    pub fn try_recv(&self) -> Option<PanelChangeEvent> {
+        self.receiver.try_iter().last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panel_size_to_pixels() {
+        assert_eq!(PanelSize::XS.to_pixels(), 28);
+        assert_eq!(PanelSize::S.to_pixels(), 32);
+        assert_eq!(PanelSize::M.to_pixels(), 36);
+        assert_eq!(PanelSize::L.to_pixels(), 40);
+        assert_eq!(PanelSize::XL.to_pixels(), 48);
+    }
+
+    #[test]
+    fn test_panel_info_reserved_space() {
+        let panel = PanelInfo {
+            anchor: PanelAnchor::Top,
+            size: PanelSize::S,
+            exclusive_zone: true,
+            margin: 4,
+            autohide: false,
+            output: None,
+        };
+        assert_eq!(panel.reserved_space(), 36); // 32 + 4
+
+        let no_exclusive = PanelInfo {
+            exclusive_zone: false,
+            ..panel.clone()
+        };
+        assert_eq!(no_exclusive.reserved_space(), 0);
+
+        let autohide = PanelInfo {
+            autohide: true,
+            ..panel
+        };
+        assert_eq!(autohide.reserved_space(), 0);
+    }
+
+    #[test]
+    fn test_parse_anchor() {
+        assert_eq!(PanelDetection::parse_anchor("Top"), Some(PanelAnchor::Top));
+        assert_eq!(
+            PanelDetection::parse_anchor("Bottom\n"),
+            Some(PanelAnchor::Bottom)
+        );
+        assert_eq!(PanelDetection::parse_anchor("Unknown"), None);
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(PanelDetection::parse_size("XS"), Some(PanelSize::XS));
+        assert_eq!(PanelDetection::parse_size("M\n"), Some(PanelSize::M));
+        assert_eq!(PanelDetection::parse_size("XXL"), None);
+    }
+
+    #[test]
+    fn test_margin_adjustments() {
+        let detection = PanelDetection {
+            panels: vec![
+                PanelInfo {
+                    anchor: PanelAnchor::Top,
+                    size: PanelSize::S,
+                    exclusive_zone: true,
+                    margin: 0,
+                    autohide: false,
+                    output: None,
+                },
+                PanelInfo {
+                    anchor: PanelAnchor::Bottom,
+                    size: PanelSize::L,
+                    exclusive_zone: true,
+                    margin: 4,
+                    autohide: false,
+                    output: None,
+                },
+            ],
+        };
+
+        let adjustments = detection.margin_adjustments();
+        assert_eq!(adjustments.top, 32 + 8); // S size + gap
+        assert_eq!(adjustments.bottom, 44 + 8); // L size + margin + gap
+        assert_eq!(adjustments.left, 0);
+        assert_eq!(adjustments.right, 0);
+    }
+
+    #[test]
+    fn test_detect_in_config_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(PanelDetection::detect_in(dir.path()).panels.is_empty());
+
+        let panel_dir = dir.path().join("cosmic/com.system76.CosmicPanel.Panel/v1");
+        fs::create_dir_all(&panel_dir).unwrap();
+        fs::write(panel_dir.join("anchor"), "Bottom").unwrap();
+        fs::write(panel_dir.join("size"), "M").unwrap();
+        fs::write(panel_dir.join("autohide"), "None").unwrap();
+
+        let adjustments = PanelDetection::detect_in(dir.path()).margin_adjustments();
+        assert_eq!(adjustments.bottom, 36 + 8);
+
+        fs::write(
+            panel_dir.join("autohide"),
+            "Some((wait_time: 1000, transition_time: 200, handle_size: 4))",
+        )
+        .unwrap();
+        let adjustments = PanelDetection::detect_in(dir.path()).margin_adjustments();
+        assert_eq!(adjustments, MarginAdjustments::default());
+    }
+
+    #[test]
+    fn test_cosmic_backend() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = CosmicPanels::with_config_dir(dir.path());
+        assert!(!backend.is_configured());
+        assert!(backend.detect().unwrap().is_empty());
+
+        let panel_dir = dir.path().join("cosmic/com.system76.CosmicPanel.Dock/v1");
+        fs::create_dir_all(&panel_dir).unwrap();
+        fs::write(panel_dir.join("anchor"), "Left").unwrap();
+        assert!(backend.is_configured());
+
+        let adjustments = PanelDetection::from_backend(&backend).margin_adjustments();
+        assert_eq!(adjustments.left, 32 + 8);
+        assert!(!backend.uses_exclusive_zones());
+        assert!(ExclusiveZones.uses_exclusive_zones());
+    }
+
+    #[test]
+    fn test_cosmic_panel_settings() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cosmic = dir.path().join("cosmic");
+        let entries_dir = cosmic.join("com.system76.CosmicPanel/v1");
+        fs::create_dir_all(&entries_dir).unwrap();
+        fs::write(
+            entries_dir.join("entries"),
+            "[\n    \"Panel\",\n    \"Side\",\n]",
+        )
+        .unwrap();
+
+        let side_dir = cosmic.join("com.system76.CosmicPanel.Side/v1");
+        fs::create_dir_all(&side_dir).unwrap();
+        fs::write(side_dir.join("anchor"), "Right").unwrap();
+        fs::write(side_dir.join("size"), "Custom(56)").unwrap();
+        fs::write(side_dir.join("output"), "Name(\"HDMI-A-1\")").unwrap();
+
+        let backend = CosmicPanels::with_config_dir(dir.path());
+        assert_eq!(backend.entries(), vec!["Panel", "Side"]);
+        // Subscribed to even before the panel has settings
+        assert_eq!(
+            backend.component_dirs(),
+            vec![
+                cosmic.join("com.system76.CosmicPanel.Panel"),
+                cosmic.join("com.system76.CosmicPanel.Side"),
+                cosmic.join("com.system76.CosmicPanel"),
+            ]
+        );
+
+        let detection = PanelDetection::detect_in(dir.path());
+        assert_eq!(detection.panels.len(), 1);
+        assert_eq!(detection.panels[0].size, PanelSize::Pixels(56));
+        assert_eq!(detection.panels[0].output.as_deref(), Some("HDMI-A-1"));
+
+        assert_eq!(
+            detection.margin_adjustments_for(Some("HDMI-A-1")).right,
+            56 + 8
+        );
+        assert_eq!(
+            detection.margin_adjustments_for(Some("eDP-1")),
+            MarginAdjustments::default()
+        );
+        assert_eq!(detection.margin_adjustments().right, 56 + 8);
+    }
+
+    #[test]
+    fn test_parse_output() {
+        assert_eq!(parse_output("All"), None);
+        assert_eq!(parse_output("Active\n"), None);
+        assert_eq!(parse_output("Name(\"DP-2\")"), Some("DP-2".to_string()));
+    }
+
+    #[test]
+    fn test_reserved_edges() {
+        let detection = PanelDetection {
+            panels: reserved_edges(None, 30, 0, 0, 52),
+        };
+        assert_eq!(detection.panels.len(), 2);
+
+        let adjustments = detection.margin_adjustments();
+        assert_eq!(adjustments.top, 30 + 8);
+        assert_eq!(adjustments.left, 52 + 8);
+        assert_eq!(adjustments.right, 0);
+    }
+
+    #[test]
+    fn test_backend_kind_config() {
+        #[derive(Deserialize)]
+        struct Panel {
+            panel_detection: PanelBackendKind,
+        }
+        let panel: Panel = toml::from_str("panel_detection = \"exclusive_zone\"").unwrap();
+        assert_eq!(panel.panel_detection, PanelBackendKind::ExclusiveZone);
+        assert_eq!(
+            PanelBackendKind::Sway.resolve(),
+            PanelBackendKind::Sway,
+            "explicit choices are kept"
+        );
+    }
+
+    #[test]
+    fn test_panel_watcher_detects_changes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let dirs = PanelDetection::component_dirs(dir.path());
+        let panel_dir = dirs[0].join("v1");
+        fs::create_dir_all(&panel_dir).unwrap();
+
+        let watcher = PanelWatcher::watch_dirs(&dirs).unwrap();
+        assert_eq!(watcher.watched(), 1);
+
+        fs::write(panel_dir.join("anchor"), "Left").unwrap();
+        fs::write(panel_dir.join("size"), "L").unwrap();
+
+        // Give the watcher time to detect the change
+        std::thread::sleep(std::time::Duration::from_millis(200));
+
+        assert!(watcher.try_recv().is_some());
+        assert!(watcher.try_recv().is_none());
+    }
+}
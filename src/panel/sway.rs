@@ -0,0 +1,170 @@
+//! Sway bar detection
+//!
+//! Sway doesn't say where its bars are, but every workspace covers only
+//! what's left of its output once bars (swaybar, waybar or any other
+//! layer-shell client with an exclusive zone) have reserved their space.
+//! The difference between the two rectangles, taken from the i3-compatible
+//! IPC socket, is the space to keep clear. Sway's outer gaps are counted
+//! too.
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use super::{reserved_edges, PanelBackend, PanelInfo};
+
+/// Every IPC message starts with this
+const MAGIC: &[u8; 6] = b"i3-ipc";
+const GET_WORKSPACES: u32 = 1;
+const GET_OUTPUTS: u32 = 3;
+
+/// How long to wait for sway to answer
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct Output {
+    name: String,
+    #[serde(default)]
+    active: bool,
+    rect: Rect,
+    current_workspace: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Workspace {
+    name: String,
+    rect: Rect,
+}
+
+/// Bars on Sway, found through its IPC socket
+pub struct SwayPanels {
+    socket: Option<PathBuf>,
+}
+
+impl SwayPanels {
+    /// Use the socket in `$SWAYSOCK`
+    pub fn from_env() -> Self {
+        Self {
+            socket: std::env::var_os("SWAYSOCK")
+                .filter(|socket| !socket.is_empty())
+                .map(PathBuf::from),
+        }
+    }
+
+    /// Use the IPC socket at `socket`
+    pub fn with_socket(socket: impl Into<PathBuf>) -> Self {
+        Self {
+            socket: Some(socket.into()),
+        }
+    }
+}
+
+impl PanelBackend for SwayPanels {
+    fn name(&self) -> &'static str {
+        "sway"
+    }
+
+    fn detect(&self) -> Result<Vec<PanelInfo>> {
+        let socket = self.socket.as_ref().context("SWAYSOCK is not set")?;
+        let mut stream = UnixStream::connect(socket)
+            .with_context(|| format!("Failed to connect to {}", socket.display()))?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+
+        let outputs: Vec<Output> = request(&mut stream, GET_OUTPUTS)?;
+        let workspaces: Vec<Workspace> = request(&mut stream, GET_WORKSPACES)?;
+        Ok(reserved_panels(&outputs, &workspaces))
+    }
+}
+
+/// Send a message without payload and parse the reply
+fn request<T: serde::de::DeserializeOwned>(stream: &mut UnixStream, kind: u32) -> Result<T> {
+    let mut message = MAGIC.to_vec();
+    message.extend_from_slice(&0u32.to_ne_bytes());
+    message.extend_from_slice(&kind.to_ne_bytes());
+    stream
+        .write_all(&message)
+        .context("Failed to send sway IPC request")?;
+
+    let mut header = [0u8; 14];
+    stream
+        .read_exact(&mut header)
+        .context("Failed to read sway IPC reply")?;
+    if &header[..6] != MAGIC {
+        bail!("Not a sway IPC reply");
+    }
+    let length = u32::from_ne_bytes([header[6], header[7], header[8], header[9]]) as usize;
+
+    let mut payload = vec![0u8; length];
+    stream
+        .read_exact(&mut payload)
+        .context("Failed to read sway IPC reply")?;
+    serde_json::from_slice(&payload).context("Failed to parse sway IPC reply")
+}
+
+/// Space reserved on each active output, found from its visible workspace
+fn reserved_panels(outputs: &[Output], workspaces: &[Workspace]) -> Vec<PanelInfo> {
+    let mut panels = Vec::new();
+    for output in outputs.iter().filter(|output| output.active) {
+        let Some(workspace) = output
+            .current_workspace
+            .as_deref()
+            .and_then(|name| workspaces.iter().find(|ws| ws.name == name))
+        else {
+            continue;
+        };
+        let (out, ws) = (output.rect, workspace.rect);
+        panels.extend(reserved_edges(
+            Some(&output.name),
+            ws.y - out.y,
+            (out.x + out.width) - (ws.x + ws.width),
+            (out.y + out.height) - (ws.y + ws.height),
+            ws.x - out.x,
+        ));
+    }
+    panels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::panel::PanelAnchor;
+
+    #[test]
+    fn test_reserved_panels() {
+        let outputs: Vec<Output> = serde_json::from_str(
+            r#"[
+                {"name": "DP-1", "active": true, "current_workspace": "1",
+                 "rect": {"x": 0, "y": 0, "width": 2560, "height": 1440}},
+                {"name": "HDMI-A-1", "active": false, "current_workspace": null,
+                 "rect": {"x": 0, "y": 0, "width": 0, "height": 0}}
+            ]"#,
+        )
+        .unwrap();
+        let workspaces: Vec<Workspace> = serde_json::from_str(
+            r#"[
+                {"name": "1", "output": "DP-1",
+                 "rect": {"x": 0, "y": 30, "width": 2560, "height": 1410}},
+                {"name": "2", "output": "DP-1",
+                 "rect": {"x": 0, "y": 0, "width": 2560, "height": 1440}}
+            ]"#,
+        )
+        .unwrap();
+
+        let panels = reserved_panels(&outputs, &workspaces);
+        assert_eq!(panels.len(), 1);
+        assert_eq!(panels[0].anchor, PanelAnchor::Top);
+        assert_eq!(panels[0].reserved_space(), 30);
+    }
+}
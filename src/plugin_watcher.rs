@@ -0,0 +1,215 @@
+// Plugin watcher for hot-reloading manifest widgets and their scripts
+
+use anyhow::{Context, Result};
+use notify::{
+    event::EventKind, Config as NotifyConfig, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Instant;
+
+use crate::widget::WidgetManifest;
+
+/// Plugin reload event
+#[derive(Debug, Clone)]
+pub struct PluginReloadEvent {
+    /// Files that were created, modified or removed
+    pub paths: Vec<PathBuf>,
+    /// Timestamp of the most recent change
+    pub timestamp: Instant,
+}
+
+impl PluginReloadEvent {
+    /// Whether `manifest` is affected by any of the changed files
+    pub fn affects(&self, manifest: &WidgetManifest) -> bool {
+        self.paths.iter().any(|path| manifest.is_affected_by(path))
+    }
+}
+
+/// Plugin directory watcher
+///
+/// Works like [`ConfigWatcher`](crate::ConfigWatcher) but watches directories
+/// instead of a single file: the widget manifest directory and every
+/// directory holding a plugin script. Changes are collected per path, and
+/// [`try_recv`](Self::try_recv) merges everything queued since the last poll
+/// into one event, so an editor writing several files only triggers one reload.
+pub struct PluginWatcher {
+    watcher: RecommendedWatcher,
+    receiver: mpsc::Receiver<PluginReloadEvent>,
+    watched: HashSet<PathBuf>,
+}
+
+impl PluginWatcher {
+    /// Create a new plugin watcher
+    ///
+    /// Directories that don't exist are skipped.
+    pub fn new(dirs: &[PathBuf]) -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+
+        let watcher = RecommendedWatcher::new(
+            move |res: Result<notify::Event, notify::Error>| match res {
+                Ok(event) => match event.kind {
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
+                        if event.paths.is_empty() {
+                            return;
+                        }
+
+                        tracing::debug!(paths = ?event.paths, "Plugin file changed");
+                        let reload_event = PluginReloadEvent {
+                            paths: event.paths,
+                            timestamp: Instant::now(),
+                        };
+
+                        if let Err(e) = tx.send(reload_event) {
+                            tracing::error!(error = %e, "Failed to send plugin reload event");
+                        }
+                    }
+                    _ => {
+                        tracing::trace!(kind = ?event.kind, "Ignoring plugin file event");
+                    }
+                },
+                Err(e) => {
+                    tracing::error!(error = %e, "Plugin watcher error");
+                }
+            },
+            NotifyConfig::default(),
+        )
+        .context("Failed to create plugin watcher")?;
+
+        let mut plugin_watcher = Self {
+            watcher,
+            receiver: rx,
+            watched: HashSet::new(),
+        };
+
+        for dir in dirs {
+            plugin_watcher.watch(dir)?;
+        }
+
+        Ok(plugin_watcher)
+    }
+
+    /// Start watching `dir` if it exists and isn't watched yet
+    pub fn watch(&mut self, dir: &Path) -> Result<()> {
+        if !dir.is_dir() || self.watched.contains(dir) {
+            return Ok(());
+        }
+
+        self.watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch plugin directory: {}", dir.display()))?;
+        self.watched.insert(dir.to_path_buf());
+
+        tracing::info!(path = %dir.display(), "Plugin directory watcher initialized");
+        Ok(())
+    }
+
+    /// Watch the directories holding the scripts of `manifests`
+    pub fn watch_scripts(&mut self, manifests: &[WidgetManifest]) {
+        for manifest in manifests {
+            let Some(dir) = manifest
+                .script_path()
+                .and_then(|p| p.parent().map(Path::to_path_buf))
+            else {
+                continue;
+            };
+            if let Err(e) = self.watch(&dir) {
+                tracing::warn!(error = %e, "Plugin script changes will not be reloaded");
+            }
+        }
+    }
+
+    /// Directories currently being watched
+    pub fn watched(&self) -> impl Iterator<Item = &Path> {
+        self.watched.iter().map(PathBuf::as_path)
+    }
+
+    /// Try to receive a reload event (non-blocking)
+    ///
+    /// Drains every pending change and returns them as a single event with
+    /// de-duplicated paths, or None if nothing changed.
+    pub fn try_recv(&self) -> Option<PluginReloadEvent> {
+        let mut merged: Option<PluginReloadEvent> = None;
+
+        while let Ok(event) = self.receiver.try_recv() {
+            match merged.as_mut() {
+                Some(pending) => {
+                    for path in event.paths {
+                        if !pending.paths.contains(&path) {
+                            pending.paths.push(path);
+                        }
+                    }
+                    pending.timestamp = event.timestamp;
+                }
+                None => merged = Some(event),
+            }
+        }
+
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plugin_watcher_skips_missing_dirs() {
+        let dir = TempDir::new().unwrap();
+        let missing = dir.path().join("missing");
+
+        let watcher = PluginWatcher::new(&[dir.path().to_path_buf(), missing]).unwrap();
+        assert_eq!(watcher.watched().count(), 1);
+    }
+
+    #[test]
+    fn test_plugin_watcher_detects_changes() {
+        let dir = TempDir::new().unwrap();
+        let watcher = PluginWatcher::new(&[dir.path().to_path_buf()]).unwrap();
+
+        let script = dir.path().join("status.sh");
+        std::fs::write(&script, "echo one").unwrap();
+        std::fs::write(&script, "echo two").unwrap();
+
+        // Give the watcher time to detect the change
+        std::thread::sleep(Duration::from_millis(200));
+
+        let event = watcher
+            .try_recv()
+            .expect("Expected reload event after write");
+        assert_eq!(
+            event
+                .paths
+                .iter()
+                .filter(|p| p.ends_with("status.sh"))
+                .count(),
+            1,
+            "Changes to the same file should be merged"
+        );
+        assert!(watcher.try_recv().is_none());
+    }
+
+    #[test]
+    fn test_watch_scripts_adds_plugin_dirs() {
+        let manifests = TempDir::new().unwrap();
+        let scripts = TempDir::new().unwrap();
+        let path = manifests.path().join("status.toml");
+        std::fs::write(
+            &path,
+            format!(
+                "name = \"Status\"\ntype = \"status\"\nexec = \"{}/status.sh\"\n",
+                scripts.path().display()
+            ),
+        )
+        .unwrap();
+        let manifest = WidgetManifest::load(&path).unwrap();
+
+        let mut watcher = PluginWatcher::new(&[manifests.path().to_path_buf()]).unwrap();
+        watcher.watch_scripts(&[manifest.clone(), manifest]);
+        assert_eq!(watcher.watched().count(), 2);
+        assert!(watcher.watched().any(|dir| dir == scripts.path()));
+    }
+}
@@ -161,6 +161,19 @@ impl Position {
                 | Position::BottomCenter
         )
     }
+
+    /// Exclusive zone that reserves the space of a `width`x`height` widget
+    /// at this position, like a dock
+    ///
+    /// Layer-shell only honours the exclusive zone of a surface anchored to
+    /// a single edge, so corners and the center can't reserve space.
+    pub fn exclusive_zone(self, width: u32, height: u32) -> Option<i32> {
+        match self {
+            Position::TopCenter | Position::BottomCenter => Some(height as i32),
+            Position::CenterLeft | Position::CenterRight => Some(width as i32),
+            _ => None,
+        }
+    }
 }
 
 impl FromStr for Position {
@@ -333,6 +346,14 @@ mod tests {
         assert!(Position::BottomCenter.is_centered());
     }
 
+    #[test]
+    fn test_exclusive_zone() {
+        assert_eq!(Position::BottomCenter.exclusive_zone(400, 64), Some(64));
+        assert_eq!(Position::CenterLeft.exclusive_zone(80, 600), Some(80));
+        assert_eq!(Position::TopRight.exclusive_zone(400, 64), None);
+        assert_eq!(Position::Center.exclusive_zone(400, 64), None);
+    }
+
     #[test]
     fn test_all_variants() {
         let variants = Position::all_variants();
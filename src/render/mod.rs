@@ -7,15 +7,33 @@
 // - Efficient partial updates
 
 use crate::config::Config;
-use crate::icons::IconCache;
-use crate::text::{FontWeight, TextRenderer};
+use crate::icons::{Icon, IconCache, IconResult};
+use crate::layout::WidgetPosition;
+use crate::text::{FontWeight, TextRenderer, TextScale};
 use crate::theme::Theme;
-use crate::widget::traits::{ProgressBar, ProgressColor, TextSegment, Widget};
+use crate::widget::traits::{
+    ChartSeries, FontSize, Freshness, GridCell, MediaControl, ProgressBar, ProgressColor,
+    TextSegment, Widget, WidgetContent,
+};
 use crate::widget::{ClockWidget, WeatherWidget};
 use chrono::Timelike;
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tiny_skia::*;
 use tracing::{instrument, trace, warn};
 
+/// One widget's place in a surface shared by a group
+pub struct GroupMember<'a> {
+    pub widget: &'a dyn Widget,
+    /// Where the widget is drawn, from the group's layout
+    pub rect: WidgetPosition,
+    /// Accent replacing the theme accent for this widget
+    pub accent: Option<crate::theme::Color>,
+    /// Whether to badge the widget's data as stale or offline
+    pub freshness: Freshness,
+}
+
 /// Target width percentage for clock text (0.0-1.0)
 const CLOCK_WIDTH_RATIO: f32 = 0.80;
 /// Minimum and maximum font sizes
@@ -144,6 +162,11 @@ pub struct Renderer {
     cache: RenderCache,
     /// Whether this is the first render (always do full draw)
     first_render: bool,
+    /// Tile for image borders, loaded when the theme is set
+    border_image: Option<Pixmap>,
+    /// Outline of the last frame drawn, keyed by width, height and corner
+    /// radius, since most frames are redrawn at the same size
+    frame_path: Option<((u32, u32, u32), Path)>,
 }
 
 impl Renderer {
@@ -155,20 +178,91 @@ impl Renderer {
             dirty_region: DirtyRegion::default(),
             cache: RenderCache::new(),
             first_render: true,
+            border_image: None,
+            frame_path: None,
         }
     }
 
     pub fn with_theme(theme: Theme) -> Self {
         Self {
             text_renderer: TextRenderer::new(),
+            border_image: theme.border_style.load_image(),
             theme,
             icon_cache: IconCache::new(),
             dirty_region: DirtyRegion::default(),
             cache: RenderCache::new(),
             first_render: true,
+            frame_path: None,
         }
     }
 
+    /// Active theme
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Swap the theme in place, keeping glyph and icon caches
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.border_image = theme.border_style.load_image();
+        self.theme = theme;
+        self.first_render = true;
+        self.mark_dirty();
+    }
+
+    /// Scale all text, e.g. for `[accessibility]` text size settings
+    pub fn set_text_scale(&mut self, scale: TextScale) {
+        self.text_renderer.set_scale(scale);
+        self.cache = RenderCache::new();
+        self.first_render = true;
+        self.mark_dirty();
+    }
+
+    /// Look for icons in `dir` before the built-in ones and the icon theme
+    pub fn set_icon_dir(&mut self, dir: Option<PathBuf>) {
+        self.icon_cache.set_user_dir(dir);
+    }
+
+    /// The directory set with [`set_icon_dir`](Self::set_icon_dir)
+    pub fn icon_dir(&self) -> Option<&std::path::Path> {
+        self.icon_cache.user_dir()
+    }
+
+    /// Forget loaded icons, so changed icon files are read again
+    pub fn reload_icons(&self) {
+        self.icon_cache.clear();
+    }
+
+    /// Limit how many rasterized glyphs are kept
+    pub fn set_glyph_cache_capacity(&mut self, capacity: usize) {
+        self.text_renderer.set_glyph_cache_capacity(capacity);
+    }
+
+    /// Glyph cache hit, miss and eviction counts
+    pub fn glyph_cache_metrics(&self) -> &crate::metrics::CacheMetrics {
+        self.text_renderer.glyph_cache_metrics()
+    }
+
+    /// Rasterize the glyphs `widget` will draw on a surface `height` pixels tall
+    ///
+    /// Text that ends up auto-scaled to fit is drawn at a smaller size and
+    /// still rasterizes on first use.
+    pub fn prewarm_glyphs(&mut self, widget: &dyn Widget, height: u32) {
+        let Some(glyphs) = widget.glyph_set() else {
+            return;
+        };
+        let Some(size) = content_font_size(&widget.content(), height) else {
+            return;
+        };
+        self.text_renderer
+            .prewarm(&glyphs, size, FontWeight::Regular);
+        trace!(
+            widget = widget.info().id,
+            size,
+            glyphs = glyphs.len(),
+            "Pre-warmed glyphs"
+        );
+    }
+
     /// Mark the entire surface as dirty (needs full redraw)
     pub fn mark_dirty(&mut self) {
         self.dirty_region.mark_dirty();
@@ -379,6 +473,17 @@ impl Renderer {
         low.clamp(MIN_FONT_SIZE, max_size)
     }
 
+    /// `name` at `size` from the icon cache; symbolic icons take the
+    /// theme's text color, as they do elsewhere on the desktop
+    fn icon(&self, name: &str, size: u32) -> IconResult<Arc<Icon>> {
+        if name.ends_with("-symbolic") {
+            let color = self.theme.text_primary.to_array();
+            self.icon_cache.get_tinted(name, size, color)
+        } else {
+            self.icon_cache.get_or_create(name, size)
+        }
+    }
+
     /// Draw a rounded rectangle background
     fn draw_rounded_rect(
         &self,
@@ -405,12 +510,24 @@ impl Renderer {
         }
     }
 
+    /// Paint for the widget border, following the theme's border style
+    fn border_paint(&self, width: u32, height: u32, opacity: f32) -> Paint<'_> {
+        Paint {
+            shader: self.theme.border_style.shader(
+                self.theme.border,
+                width as f32,
+                height as f32,
+                opacity,
+                self.border_image.as_ref(),
+            ),
+            anti_alias: true,
+            ..Default::default()
+        }
+    }
+
     /// Draw a rounded border
     fn draw_rounded_border(&self, pixmap: &mut PixmapMut, width: u32, height: u32, radius: f32) {
-        let mut paint = Paint::default();
-        let border = self.theme.border.to_array();
-        paint.set_color_rgba8(border[0], border[1], border[2], border[3]);
-        paint.anti_alias = true;
+        let paint = self.border_paint(width, height, 1.0);
 
         let stroke = Stroke {
             width: self.theme.border_width,
@@ -636,6 +753,7 @@ impl Renderer {
         match color_mode {
             ProgressColor::Accent => self.theme.accent.to_array(),
             ProgressColor::Custom(color) => *color,
+            ProgressColor::Token(token) => self.theme.token(*token).to_array(),
             ProgressColor::Threshold {
                 green_below,
                 yellow_below,
@@ -694,7 +812,15 @@ impl Renderer {
             .text_renderer
             .measure_text_weighted(&bar.label, label_size, FontWeight::Regular);
         let bar_x_start = x_start + label_width + 10.0;
-        let bar_width = total_width - label_width - 50.0; // Reserve space for percentage
+        let percent_text = bar
+            .value_text
+            .clone()
+            .unwrap_or_else(|| format!("{:.0}%", progress * 100.0));
+        // Reserve space for the percentage, or wider value text
+        let value_width =
+            self.text_renderer
+                .measure_text_weighted(&percent_text, label_size, FontWeight::Bold);
+        let bar_width = total_width - label_width - (value_width + 12.0).max(50.0);
 
         // Draw background track
         let mut bg_paint = Paint::default();
@@ -734,7 +860,6 @@ impl Renderer {
         }
 
         // Draw percentage on the right
-        let percent_text = format!("{:.0}%", progress * 100.0);
         let percent_x = bar_x_start + bar_width + 8.0;
 
         // Shadow for percentage
@@ -795,7 +920,7 @@ impl Renderer {
         let icon_spacing = text_size * 0.3; // Space between icon and text
 
         // Load icon from cache
-        match self.icon_cache.get_or_create(icon_name, icon_size) {
+        match self.icon(icon_name, icon_size) {
             Ok(icon) => {
                 // Draw icon
                 // Position icon vertically centered with text baseline
@@ -818,6 +943,273 @@ impl Renderer {
         }
     }
 
+    /// Render a table of cells filling `rect`
+    ///
+    /// Every line (title, header and each row of cells) gets the same
+    /// height, and every column the same width, with each cell's text
+    /// centered in it.
+    fn render_grid(
+        &mut self,
+        pixmap: &mut PixmapMut,
+        title: Option<&str>,
+        header: &[String],
+        cells: &[GridCell],
+        rect: WidgetPosition,
+        padding: f32,
+    ) {
+        let columns = header.len().max(1);
+        let rows = cells.len().div_ceil(columns);
+        let lines = rows + usize::from(title.is_some()) + usize::from(!header.is_empty());
+        if lines == 0 {
+            return;
+        }
+
+        let vertical_padding = (padding / 2.0).min(rect.height * 0.05);
+        let row_height = (rect.height - vertical_padding * 2.0) / lines as f32;
+        let cell_width = (rect.width - padding * 2.0).max(0.0) / columns as f32;
+        let font_size = (row_height * 0.55).clamp(8.0, 18.0);
+        let accent = self.theme.accent.to_array();
+        let secondary = self.theme.text_secondary.to_array();
+        let mut top = rect.y + vertical_padding;
+
+        if let Some(title) = title {
+            let width = self.text_renderer.measure_text(title, font_size);
+            let x = rect.x + (rect.width - width) / 2.0;
+            let y = self
+                .text_renderer
+                .baseline_for_center(font_size, top + row_height / 2.0);
+            self.render_text_weighted(pixmap, title, x, y, font_size, FontWeight::Bold);
+            top += row_height;
+        }
+
+        if !header.is_empty() {
+            let y = self
+                .text_renderer
+                .baseline_for_center(font_size, top + row_height / 2.0);
+            for (column, heading) in header.iter().enumerate() {
+                let width = self.text_renderer.measure_text(heading, font_size);
+                let x = rect.x + padding + (column as f32 + 0.5) * cell_width - width / 2.0;
+                self.text_renderer
+                    .render_text(pixmap, heading, x, y, font_size, secondary);
+            }
+            top += row_height;
+        }
+
+        for (i, cell) in cells.iter().enumerate() {
+            let center_x = rect.x + padding + ((i % columns) as f32 + 0.5) * cell_width;
+            let center_y = top + ((i / columns) as f32 + 0.5) * row_height;
+
+            if cell.highlighted {
+                let radius = cell_width.min(row_height) * 0.45;
+                fill_circle(pixmap, center_x, center_y, radius, accent);
+            }
+            if !cell.text.is_empty() {
+                let width = self.text_renderer.measure_text(&cell.text, font_size);
+                let y = self.text_renderer.baseline_for_center(font_size, center_y);
+                self.render_text(pixmap, &cell.text, center_x - width / 2.0, y, font_size);
+            }
+            if cell.marked {
+                // Under the text, and visible on a highlighted cell too
+                let radius = (font_size * 0.12).max(1.5);
+                let color = if cell.highlighted {
+                    self.theme.text_primary.to_array()
+                } else {
+                    accent
+                };
+                let dot_y = center_y + row_height / 2.0 - radius * 1.5;
+                fill_circle(pixmap, center_x, dot_y, radius, color);
+            }
+        }
+    }
+
+    /// Render sparklines in `columns` equal cells filling `rect`
+    ///
+    /// Each cell has its label and value text on one line, with the graph
+    /// filling the space below. Samples are spread across the full cell
+    /// width, newest on the right.
+    fn render_chart(
+        &mut self,
+        pixmap: &mut PixmapMut,
+        series: &[ChartSeries],
+        columns: usize,
+        rect: WidgetPosition,
+        padding: f32,
+    ) {
+        if series.is_empty() {
+            return;
+        }
+        let columns = columns.clamp(1, series.len());
+        let rows = series.len().div_ceil(columns);
+
+        let vertical_padding = (padding / 2.0).min(rect.height * 0.05);
+        let gap = 8.0;
+        let cell_width =
+            ((rect.width - padding * 2.0 - gap * (columns - 1) as f32) / columns as f32).max(0.0);
+        let cell_height = ((rect.height - vertical_padding * 2.0 - gap * (rows - 1) as f32)
+            / rows as f32)
+            .max(0.0);
+        let font_size = (cell_height * 0.3).clamp(8.0, 14.0);
+        let secondary = self.theme.text_secondary.to_array();
+
+        for (i, item) in series.iter().enumerate() {
+            let left = rect.x + padding + (i % columns) as f32 * (cell_width + gap);
+            let top = rect.y + vertical_padding + (i / columns) as f32 * (cell_height + gap);
+
+            let baseline = top + self.text_renderer.ascent(font_size);
+            self.text_renderer.render_text(
+                pixmap,
+                &item.label,
+                left,
+                baseline,
+                font_size,
+                secondary,
+            );
+            let value_width = self.text_renderer.measure_text(&item.value_text, font_size);
+            self.render_text(
+                pixmap,
+                &item.value_text,
+                left + cell_width - value_width,
+                baseline,
+                font_size,
+            );
+
+            let graph_top = top + font_size * 1.3;
+            let graph_height = top + cell_height - graph_top;
+            if graph_height <= 1.0 || item.values.len() < 2 {
+                continue;
+            }
+            let latest = item.values.last().copied().unwrap_or(0.0);
+            let color = self.get_progress_color(latest, &item.color);
+            self.draw_sparkline(
+                pixmap,
+                &item.values,
+                left,
+                graph_top,
+                cell_width,
+                graph_height,
+                color,
+            );
+        }
+    }
+
+    /// Render a track line over transport controls and a position bar
+    ///
+    /// The controls fill [`MediaControl::AREA_WIDTH`] of the lower half from
+    /// the left edge, which is where [`MediaControl::at`] hit-tests clicks.
+    #[allow(clippy::too_many_arguments)]
+    fn render_media(
+        &mut self,
+        pixmap: &mut PixmapMut,
+        title: &str,
+        playing: bool,
+        progress: Option<f32>,
+        position_text: &str,
+        rect: WidgetPosition,
+        padding: f32,
+        font_size: f32,
+    ) {
+        let half = rect.height / 2.0;
+        let available_width = rect.width - padding * 2.0;
+
+        let mut fs = font_size;
+        let mut title_width = self.text_renderer.measure_text(title, fs);
+        if title_width > available_width && available_width > 0.0 {
+            fs = (fs * available_width / title_width).max(10.0);
+            title_width = self.text_renderer.measure_text(title, fs);
+        }
+        let y = self
+            .text_renderer
+            .baseline_for_center(fs, rect.y + half * 0.6);
+        let x = rect.x + (rect.width - title_width) / 2.0;
+        self.render_text(pixmap, title, x, y, fs);
+
+        let row_center = rect.y + half * 1.5;
+        let slot = rect.width * MediaControl::AREA_WIDTH / MediaControl::ALL.len() as f32;
+        let size = (half * 0.45).min(slot * 0.6);
+        let color = self.theme.text_primary.to_array();
+        for (i, control) in MediaControl::ALL.into_iter().enumerate() {
+            let x = rect.x + slot * (i as f32 + 0.5);
+            draw_media_control(pixmap, control, playing, x, row_center, size, color);
+        }
+
+        let text_size = (half * 0.4).clamp(8.0, 14.0);
+        let text_width = self.text_renderer.measure_text(position_text, text_size);
+        let text_x = rect.x + rect.width - padding - text_width;
+        let baseline = self
+            .text_renderer
+            .baseline_for_center(text_size, row_center);
+        self.text_renderer.render_text(
+            pixmap,
+            position_text,
+            text_x,
+            baseline,
+            text_size,
+            self.theme.text_secondary.to_array(),
+        );
+
+        if let Some(progress) = progress {
+            let bar_start = rect.x + rect.width * MediaControl::AREA_WIDTH + 8.0;
+            let bar_end = text_x - 10.0;
+            if bar_end > bar_start {
+                self.draw_progress_bar(pixmap, bar_start, bar_end, row_center - 4.0, progress);
+            }
+        }
+    }
+
+    /// Draw `values` (0.0 to 1.0) as a filled line graph in the given box
+    #[allow(clippy::too_many_arguments)]
+    fn draw_sparkline(
+        &self,
+        pixmap: &mut PixmapMut,
+        values: &[f32],
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        color: [u8; 4],
+    ) {
+        let step = width / (values.len() - 1) as f32;
+        let point = |i: usize, value: f32| {
+            (
+                x + i as f32 * step,
+                y + height * (1.0 - value.clamp(0.0, 1.0)),
+            )
+        };
+
+        let mut line = PathBuilder::new();
+        let mut area = PathBuilder::new();
+        area.move_to(x, y + height);
+        for (i, &value) in values.iter().enumerate() {
+            let (px, py) = point(i, value);
+            if i == 0 {
+                line.move_to(px, py);
+            } else {
+                line.line_to(px, py);
+            }
+            area.line_to(px, py);
+        }
+        area.line_to(x + width, y + height);
+        area.close();
+
+        let mut fill = Paint::default();
+        fill.set_color_rgba8(color[0], color[1], color[2], 60);
+        fill.anti_alias = true;
+        if let Some(path) = area.finish() {
+            pixmap.fill_path(&path, &fill, FillRule::Winding, Transform::identity(), None);
+        }
+
+        let mut stroke_paint = Paint::default();
+        stroke_paint.set_color_rgba8(color[0], color[1], color[2], color[3]);
+        stroke_paint.anti_alias = true;
+        let stroke = Stroke {
+            width: 1.5,
+            ..Default::default()
+        };
+        if let Some(path) = line.finish() {
+            pixmap.stroke_path(&path, &stroke_paint, &stroke, Transform::identity(), None);
+        }
+    }
+
     /// Draw a horizontal progress bar
     /// x_start: left edge of bar
     /// x_end: right edge of bar
@@ -872,150 +1264,66 @@ impl Renderer {
         }
     }
 
-    /// Render dynamic widgets from registry
-    pub fn render_dynamic_widgets(
+    /// Render a group of widgets sharing one surface
+    ///
+    /// The surface gets a single background and border; each member is
+    /// drawn into its [`WidgetPosition`] from the [`LayoutManager`] the way
+    /// it would be on a surface of its own, with its own accent.
+    ///
+    /// [`LayoutManager`]: crate::layout::LayoutManager
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_group<'a>(
         &mut self,
         canvas: &mut [u8],
         width: u32,
         height: u32,
-        widgets: &[Box<dyn Widget>],
-        config: &Config,
+        members: impl IntoIterator<Item = GroupMember<'a>>,
+        opacity: f32,
+        frameless: bool,
     ) {
-        use crate::widget::traits::{FontSize, WidgetContent};
-
-        // Create pixmap from canvas
         let Some(mut pixmap) = PixmapMut::from_bytes(canvas, width, height) else {
-            tracing::error!("Failed to create pixmap for dynamic widgets");
+            tracing::error!("Failed to create pixmap for widget group");
             return;
         };
 
-        // Clear with fully transparent so rounded corners show through to wallpaper
-        let bg = self.theme.background_with_opacity();
+        let saved_theme = frameless.then(|| {
+            let frameless = self.theme.frameless();
+            std::mem::replace(&mut self.theme, frameless)
+        });
         pixmap.fill(tiny_skia::Color::from_rgba8(0, 0, 0, 0));
+        if !frameless {
+            self.draw_frame(&mut pixmap, width, height, opacity);
+        }
 
-        // Draw rounded rectangle background (only this shape gets the bg color)
-        let corner_radius = self.theme.corner_radius;
-        self.draw_rounded_rect(&mut pixmap, width, height, corner_radius, &bg);
-        self.draw_rounded_border(&mut pixmap, width, height, corner_radius);
-
-        let padding = config.padding();
-        let spacing = config.panel.spacing;
-        let mut y_offset = padding;
-
-        // Render each widget
-        for widget in widgets {
-            let info = widget.info();
-            let content = widget.content();
-
-            // Calculate font size based on widget preference
-            let font_size = match &content {
-                WidgetContent::Text { size, .. } => match size {
-                    FontSize::Large => 48.0,
-                    FontSize::Medium => 24.0,
-                    FontSize::Small => 16.0,
-                    FontSize::Custom(s) => *s,
-                },
-                WidgetContent::MultiLine { lines } => {
-                    if let Some((_, size)) = lines.first() {
-                        match size {
-                            FontSize::Large => 48.0,
-                            FontSize::Medium => 24.0,
-                            FontSize::Small => 16.0,
-                            FontSize::Custom(s) => *s,
-                        }
-                    } else {
-                        16.0
-                    }
-                }
-                WidgetContent::IconText { size, .. } => match size {
-                    FontSize::Large => 48.0,
-                    FontSize::Medium => 24.0,
-                    FontSize::Small => 16.0,
-                    FontSize::Custom(s) => *s,
-                },
-                WidgetContent::StyledText { size, .. } => match size {
-                    FontSize::Large => 48.0,
-                    FontSize::Medium => 24.0,
-                    FontSize::Small => 16.0,
-                    FontSize::Custom(s) => *s,
-                },
-                WidgetContent::Progress { .. } => 16.0,
-                WidgetContent::MultiProgress { .. } => 14.0,
-                WidgetContent::Empty => continue,
-            };
-
-            // Render based on content type
-            let ascent = self.text_renderer.ascent(font_size);
-            match content {
-                WidgetContent::Text { text, .. } => {
-                    // Center text horizontally
-                    let text_width = self.text_renderer.measure_text(&text, font_size);
-                    let x = (width as f32 - text_width) / 2.0;
-                    self.render_text(&mut pixmap, &text, x, y_offset + ascent, font_size);
-                    y_offset += font_size + spacing;
-                }
-                WidgetContent::MultiLine { lines } => {
-                    for (text, size) in lines {
-                        let fs = match size {
-                            FontSize::Large => 48.0,
-                            FontSize::Medium => 24.0,
-                            FontSize::Small => 16.0,
-                            FontSize::Custom(s) => s,
-                        };
-                        let line_ascent = self.text_renderer.ascent(fs);
-                        let text_width = self.text_renderer.measure_text(&text, fs);
-                        let x = (width as f32 - text_width) / 2.0;
-                        self.render_text(&mut pixmap, &text, x, y_offset + line_ascent, fs);
-                        y_offset += fs * 1.3 + spacing * 0.5;
-                    }
-                    y_offset += spacing * 0.5;
-                }
-                WidgetContent::IconText { icon, text, .. } => {
-                    let x = padding;
-                    self.render_icon_text(&mut pixmap, &icon, &text, x, y_offset + ascent, font_size);
-                    y_offset += font_size + spacing;
-                }
-                WidgetContent::StyledText { segments, .. } => {
-                    // Center styled text horizontally
-                    let total_width = self.measure_styled_text(&segments, font_size);
-                    let x = (width as f32 - total_width) / 2.0;
-                    self.render_styled_text(&mut pixmap, &segments, x, y_offset + ascent, font_size);
-                    y_offset += font_size + spacing;
-                }
-                WidgetContent::Progress { value, label } => {
-                    // Render progress bar
-                    let bar_y = y_offset + 10.0;
-                    self.draw_progress_bar(&mut pixmap, padding, width as f32 - padding, bar_y, value);
-                    if let Some(label_text) = label {
-                        let label_width = self.text_renderer.measure_text(&label_text, 14.0);
-                        let x = (width as f32 - label_width) / 2.0;
-                        let label_baseline = self.text_renderer.baseline_for_center(14.0, bar_y + 20.0);
-                        self.render_text(&mut pixmap, &label_text, x, label_baseline, 14.0);
-                    }
-                    y_offset += 30.0 + spacing;
-                }
-                WidgetContent::MultiProgress { bars } => {
-                    // Render multiple colored progress bars
-                    let bar_height = self.render_multi_progress(
-                        &mut pixmap,
-                        &bars,
-                        padding,
-                        y_offset,
-                        width as f32 - padding * 2.0,
-                        font_size,
-                    );
-                    y_offset += bar_height + spacing;
-                }
-                WidgetContent::Empty => {}
-            }
-
-            tracing::trace!(widget = info.id, y_offset = y_offset, "Rendered widget");
+        let base_accent = self.theme.accent;
+        let mut count = 0;
+        for member in members {
+            self.theme.accent = member.accent.unwrap_or(base_accent);
+            self.draw_content(&mut pixmap, member.widget, member.rect);
+            self.draw_badge(&mut pixmap, member.freshness, member.rect);
+            count += 1;
+        }
+        self.theme.accent = base_accent;
+        if let Some(theme) = saved_theme {
+            self.theme = theme;
         }
 
-        self.first_render = false;
+        tracing::trace!(
+            members = count,
+            width = width,
+            height = height,
+            "Rendered widget group"
+        );
     }
 
     /// Render a single widget to its own surface with custom opacity
+    ///
+    /// `accent` replaces the theme accent for this widget only, so progress
+    /// bars and other accent-colored details can differ per widget.
+    /// `frameless` skips the background and border and draws only shadowed
+    /// text and icons on a fully transparent buffer. Stale or offline
+    /// `freshness` adds a badge in the corner.
+    #[allow(clippy::too_many_arguments)]
     pub fn render_single_widget(
         &mut self,
         canvas: &mut [u8],
@@ -1023,9 +1331,80 @@ impl Renderer {
         height: u32,
         widget: &dyn Widget,
         opacity: f32,
+        accent: Option<crate::theme::Color>,
+        frameless: bool,
+        freshness: Freshness,
     ) {
-        use crate::widget::traits::{FontSize, WidgetContent};
+        // Only the accent changes for most widgets, which needs no copy of
+        // the whole theme
+        let saved_accent = accent.map(|accent| std::mem::replace(&mut self.theme.accent, accent));
+        let saved_theme = frameless.then(|| {
+            let frameless = self.theme.frameless();
+            std::mem::replace(&mut self.theme, frameless)
+        });
+        self.draw_single_widget(canvas, width, height, widget, opacity, frameless, freshness);
+        if let Some(theme) = saved_theme {
+            self.theme = theme;
+        }
+        if let Some(accent) = saved_accent {
+            self.theme.accent = accent;
+        }
+    }
 
+    /// Render `widget` with `theme` into a new pixmap of `size` (width,
+    /// height), as it would look on the desktop
+    ///
+    /// Needs no surface, so it works for previews and snapshot tests. The
+    /// renderer's own theme is left as it was.
+    pub fn render_widget_to_pixmap(
+        &mut self,
+        widget: &dyn Widget,
+        size: (u32, u32),
+        theme: &Theme,
+    ) -> anyhow::Result<Pixmap> {
+        let (width, height) = size;
+        let mut pixmap = Pixmap::new(width, height)
+            .ok_or_else(|| anyhow::anyhow!("Invalid preview size {}x{}", width, height))?;
+        let saved_theme = std::mem::replace(&mut self.theme, theme.clone());
+        let saved_border =
+            std::mem::replace(&mut self.border_image, theme.border_style.load_image());
+        self.draw_single_widget(
+            pixmap.data_mut(),
+            width,
+            height,
+            widget,
+            theme.opacity,
+            false,
+            Freshness::Fresh,
+        );
+        self.theme = saved_theme;
+        self.border_image = saved_border;
+        Ok(pixmap)
+    }
+
+    /// Render `widget` like [`Renderer::render_widget_to_pixmap`] and encode
+    /// it as PNG
+    pub fn render_widget_to_png(
+        &mut self,
+        widget: &dyn Widget,
+        size: (u32, u32),
+        theme: &Theme,
+    ) -> anyhow::Result<Vec<u8>> {
+        let pixmap = self.render_widget_to_pixmap(widget, size, theme)?;
+        Ok(pixmap.encode_png()?)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn draw_single_widget(
+        &mut self,
+        canvas: &mut [u8],
+        width: u32,
+        height: u32,
+        widget: &dyn Widget,
+        opacity: f32,
+        frameless: bool,
+        freshness: Freshness,
+    ) {
         // Create pixmap from canvas
         let Some(mut pixmap) = PixmapMut::from_bytes(canvas, width, height) else {
             tracing::error!("Failed to create pixmap for single widget");
@@ -1033,158 +1412,229 @@ impl Renderer {
         };
 
         // Clear with fully transparent so rounded corners show through to wallpaper
+        pixmap.fill(tiny_skia::Color::from_rgba8(0, 0, 0, 0));
+
+        // Frameless widgets are just text on the wallpaper: no background,
+        // border or corners (a zero-width stroke would still draw a hairline)
+        if !frameless {
+            self.draw_frame(&mut pixmap, width, height, opacity);
+        }
+
+        let rect = WidgetPosition {
+            x: 0.0,
+            y: 0.0,
+            width: width as f32,
+            height: height as f32,
+        };
+        self.draw_content(&mut pixmap, widget, rect);
+        self.draw_badge(&mut pixmap, freshness, rect);
+
+        tracing::trace!(
+            widget = widget.info().id,
+            width = width,
+            height = height,
+            opacity = opacity,
+            "Rendered single widget"
+        );
+    }
+
+    /// Draw the rounded background and border filling the surface
+    fn draw_frame(&mut self, pixmap: &mut PixmapMut, width: u32, height: u32, opacity: f32) {
         let mut bg = self.theme.background.clone();
         bg.a = (bg.a as f32 * opacity) as u8;
-        pixmap.fill(tiny_skia::Color::from_rgba8(0, 0, 0, 0));
 
-        // Draw rounded rectangle background (only this shape gets the bg color)
         let corner_radius = self.theme.corner_radius;
-        self.draw_rounded_rect(&mut pixmap, width, height, corner_radius, &bg);
+        let key = (width, height, corner_radius.to_bits());
+        if self.frame_path.as_ref().map(|(cached, _)| *cached) != Some(key) {
+            self.frame_path = self
+                .create_rounded_rect_path(width as f32, height as f32, corner_radius)
+                .map(|path| (key, path));
+        }
+        let Some((_, path)) = &self.frame_path else {
+            return;
+        };
 
-        // Draw border with opacity applied
-        let mut border_paint = Paint::default();
-        let border_color = self.theme.border.clone();
-        let border_rgba = [
-            border_color.r,
-            border_color.g,
-            border_color.b,
-            (border_color.a as f32 * opacity) as u8,
-        ];
-        border_paint.set_color_rgba8(
-            border_rgba[0],
-            border_rgba[1],
-            border_rgba[2],
-            border_rgba[3],
+        // Only the rounded shape gets the background color
+        let mut bg_paint = Paint::default();
+        let rgba = bg.to_array();
+        bg_paint.set_color_rgba8(rgba[0], rgba[1], rgba[2], rgba[3]);
+        bg_paint.anti_alias = true;
+        pixmap.fill_path(
+            path,
+            &bg_paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
         );
-        border_paint.anti_alias = true;
+
+        // Draw border with opacity applied
+        let border_paint = self.border_paint(width, height, opacity);
 
         let stroke = Stroke {
             width: self.theme.border_width,
             ..Default::default()
         };
+        pixmap.stroke_path(path, &border_paint, &stroke, Transform::identity(), None);
+    }
 
-        let path = self.create_rounded_rect_path(width as f32, height as f32, corner_radius);
-        if let Some(path) = path {
-            pixmap.stroke_path(&path, &border_paint, &stroke, Transform::identity(), None);
+    /// Draw the stale or offline badge in the top right corner of `rect`
+    fn draw_badge(&mut self, pixmap: &mut PixmapMut, freshness: Freshness, rect: WidgetPosition) {
+        let Some(icon_name) = freshness.badge_icon() else {
+            return;
+        };
+        let size = (rect.height * 0.18).clamp(10.0, 16.0) as u32;
+        let inset = (self.theme.corner_radius / 2.0).max(4.0);
+        let x = rect.x + rect.width - inset - size as f32;
+        let y = rect.y + inset;
+        match self.icon(icon_name, size) {
+            Ok(icon) => icon.draw(pixmap, x as i32, y as i32),
+            Err(e) => warn!(icon = icon_name, error = %e, "Failed to load badge icon"),
         }
+    }
 
+    /// Draw a widget's content centered in `rect`
+    fn draw_content(&mut self, pixmap: &mut PixmapMut, widget: &dyn Widget, rect: WidgetPosition) {
         let padding = 16.0; // Internal padding for individual widgets
-        let content = widget.content();
+        let mut content = widget.content_ref();
+        if let WidgetContent::Error { message } = content.as_ref() {
+            content = Cow::Owned(WidgetContent::error_card(message));
+        }
+        let height = rect.height as u32;
 
         // Calculate font size based on widget preference
-        let font_size = match &content {
-            WidgetContent::Text { size, .. } => match size {
-                FontSize::Large => (height as f32 * 0.5).min(72.0),
-                FontSize::Medium => (height as f32 * 0.35).min(32.0),
-                FontSize::Small => (height as f32 * 0.25).min(20.0),
-                FontSize::Custom(s) => *s,
-            },
-            WidgetContent::MultiLine { lines } => {
-                if let Some((_, size)) = lines.first() {
-                    match size {
-                        FontSize::Large => (height as f32 * 0.4).min(48.0),
-                        FontSize::Medium => (height as f32 * 0.3).min(24.0),
-                        FontSize::Small => (height as f32 * 0.2).min(16.0),
-                        FontSize::Custom(s) => *s,
-                    }
-                } else {
-                    16.0
-                }
-            }
-            WidgetContent::IconText { size, .. } => match size {
-                FontSize::Large => (height as f32 * 0.5).min(48.0),
-                FontSize::Medium => (height as f32 * 0.35).min(28.0),
-                FontSize::Small => (height as f32 * 0.25).min(18.0),
-                FontSize::Custom(s) => *s,
-            },
-            WidgetContent::StyledText { size, .. } => match size {
-                FontSize::Large => (height as f32 * 0.5).min(72.0),
-                FontSize::Medium => (height as f32 * 0.35).min(32.0),
-                FontSize::Small => (height as f32 * 0.25).min(20.0),
-                FontSize::Custom(s) => *s,
-            },
-            WidgetContent::Progress { .. } => 16.0,
-            WidgetContent::MultiProgress { .. } => (height as f32 * 0.15).min(14.0),
-            WidgetContent::Empty => return,
+        let Some(font_size) = content_font_size(&content, height) else {
+            return;
         };
 
         // Render widget content centered
-        let y_center = height as f32 / 2.0;
-        let available_width = width as f32 - padding * 2.0;
+        let x_center = rect.x + rect.width / 2.0;
+        let y_center = rect.y + rect.height / 2.0;
+        let available_width = rect.width - padding * 2.0;
 
-        match content {
+        match &*content {
             WidgetContent::Text { text, .. } => {
                 // Auto-scale font size if text is wider than available space
                 let mut fs = font_size;
-                let mut text_width = self.text_renderer.measure_text(&text, fs);
+                let mut text_width = self.text_renderer.measure_text(text, fs);
                 if text_width > available_width && available_width > 0.0 {
                     fs = (fs * available_width / text_width).max(10.0);
-                    text_width = self.text_renderer.measure_text(&text, fs);
+                    text_width = self.text_renderer.measure_text(text, fs);
                 }
-                let x = ((width as f32) - text_width) / 2.0;
+                let x = x_center - text_width / 2.0;
                 let y = self.text_renderer.baseline_for_center(fs, y_center);
-                self.render_text(&mut pixmap, &text, x, y, fs);
+                self.render_text(pixmap, text, x, y, fs);
             }
             WidgetContent::MultiLine { lines } => {
                 let line_count = lines.len() as f32;
-                let line_height = font_size * 1.4;
+                // Lines move apart as text scaling makes them taller
+                let line_height = self.text_renderer.scaled_size(font_size) * 1.4;
                 let total_height = line_count * line_height;
                 let ascent = self.text_renderer.ascent(font_size);
                 let mut y = y_center - total_height / 2.0 + ascent;
 
                 for (text, size) in lines {
                     let mut fs = match size {
-                        FontSize::Large => (height as f32 * 0.4).min(36.0),
-                        FontSize::Medium => (height as f32 * 0.3).min(22.0),
-                        FontSize::Small => (height as f32 * 0.2).min(14.0),
-                        FontSize::Custom(s) => s,
+                        FontSize::Large => (rect.height * 0.4).min(36.0),
+                        FontSize::Medium => (rect.height * 0.3).min(22.0),
+                        FontSize::Small => (rect.height * 0.2).min(14.0),
+                        FontSize::Custom(s) => *s,
                     };
-                    let mut text_width = self.text_renderer.measure_text(&text, fs);
+                    let mut text_width = self.text_renderer.measure_text(text, fs);
                     if text_width > available_width && available_width > 0.0 {
                         fs = (fs * available_width / text_width).max(10.0);
-                        text_width = self.text_renderer.measure_text(&text, fs);
+                        text_width = self.text_renderer.measure_text(text, fs);
                     }
-                    let x = ((width as f32) - text_width) / 2.0;
-                    self.render_text(&mut pixmap, &text, x, y, fs);
+                    let x = x_center - text_width / 2.0;
+                    self.render_text(pixmap, text, x, y, fs);
                     y += line_height;
                 }
             }
             WidgetContent::IconText { icon, text, .. } => {
-                let text_width = self.text_renderer.measure_text(&text, font_size);
+                let text_width = self.text_renderer.measure_text(text, font_size);
                 let icon_size = (font_size * 1.2) as u32;
                 let icon_spacing = font_size * 0.3;
                 let total_width = icon_size as f32 + icon_spacing + text_width;
-                let x_start = ((width as f32) - total_width) / 2.0;
+                let x_start = x_center - total_width / 2.0;
                 let y = self.text_renderer.baseline_for_center(font_size, y_center);
 
-                self.render_icon_text(&mut pixmap, &icon, &text, x_start.max(padding), y, font_size);
+                self.render_icon_text(
+                    pixmap,
+                    icon,
+                    text,
+                    x_start.max(rect.x + padding),
+                    y,
+                    font_size,
+                );
+            }
+            WidgetContent::IconLines { icon, lines } => {
+                let line_height = self.text_renderer.scaled_size(font_size) * 1.4;
+                let x = rect.x + padding;
+
+                // The icon is about two lines tall, centered beside the text
+                let icon_size = (line_height * 2.0).min(rect.height - padding) as u32;
+                let drawn = !icon.is_empty()
+                    && icon_size > 0
+                    && match self.icon(icon, icon_size) {
+                        Ok(image) => {
+                            let icon_y = y_center - icon_size as f32 / 2.0;
+                            image.draw(pixmap, x as i32, icon_y as i32);
+                            true
+                        }
+                        Err(e) => {
+                            trace!(icon = %icon, error = %e, "Failed to load icon, rendering text only");
+                            false
+                        }
+                    };
+                let text_x = if drawn {
+                    x + icon_size as f32 + padding * 0.75
+                } else {
+                    x
+                };
+                let text_width = rect.x + rect.width - padding - text_x;
+
+                let ascent = self.text_renderer.ascent(font_size);
+                let mut y = y_center - lines.len() as f32 * line_height / 2.0 + ascent;
+                for (text, size) in lines {
+                    let mut fs = match size {
+                        FontSize::Large => (rect.height * 0.4).min(36.0),
+                        FontSize::Medium => (rect.height * 0.3).min(22.0),
+                        FontSize::Small => (rect.height * 0.2).min(14.0),
+                        FontSize::Custom(s) => *s,
+                    };
+                    let width = self.text_renderer.measure_text(text, fs);
+                    if width > text_width && text_width > 0.0 {
+                        fs = (fs * text_width / width).max(10.0);
+                    }
+                    self.render_text(pixmap, text, text_x, y, fs);
+                    y += line_height;
+                }
             }
             WidgetContent::StyledText { segments, .. } => {
                 // Auto-scale styled text if wider than available space
                 let mut fs = font_size;
-                let mut total_width = self.measure_styled_text(&segments, fs);
+                let mut total_width = self.measure_styled_text(segments, fs);
                 if total_width > available_width && available_width > 0.0 {
                     fs = (fs * available_width / total_width).max(10.0);
-                    total_width = self.measure_styled_text(&segments, fs);
+                    total_width = self.measure_styled_text(segments, fs);
                 }
-                let x = ((width as f32) - total_width) / 2.0;
+                let x = x_center - total_width / 2.0;
                 let y = self.text_renderer.baseline_for_center(fs, y_center);
-                self.render_styled_text(&mut pixmap, &segments, x, y, fs);
+                self.render_styled_text(pixmap, segments, x, y, fs);
             }
             WidgetContent::Progress { value, label } => {
                 let bar_y = y_center - 4.0;
                 self.draw_progress_bar(
-                    &mut pixmap,
-                    padding,
-                    width as f32 - padding,
+                    pixmap,
+                    rect.x + padding,
+                    rect.x + rect.width - padding,
                     bar_y,
-                    value,
+                    *value,
                 );
                 if let Some(label_text) = label {
-                    let label_width = self.text_renderer.measure_text(&label_text, 14.0);
-                    let x = ((width as f32) - label_width) / 2.0;
+                    let label_width = self.text_renderer.measure_text(label_text, 14.0);
+                    let x = x_center - label_width / 2.0;
                     let label_y = self.text_renderer.baseline_for_center(14.0, bar_y + 20.0);
-                    self.render_text(&mut pixmap, &label_text, x, label_y, 14.0);
+                    self.render_text(pixmap, label_text, x, label_y, 14.0);
                 }
             }
             WidgetContent::MultiProgress { bars } => {
@@ -1194,24 +1644,94 @@ impl Renderer {
                 let y_start = y_center - total_bars_height / 2.0 + bar_spacing / 2.0;
 
                 self.render_multi_progress(
-                    &mut pixmap,
-                    &bars,
-                    padding,
+                    pixmap,
+                    bars,
+                    rect.x + padding,
                     y_start,
-                    width as f32 - padding * 2.0,
+                    rect.width - padding * 2.0,
                     font_size,
                 );
             }
-            WidgetContent::Empty => {}
-        }
+            WidgetContent::Grid {
+                title,
+                header,
+                cells,
+            } => {
+                self.render_grid(pixmap, title.as_deref(), header, cells, rect, padding);
+            }
+            WidgetContent::Chart { series, columns } => {
+                self.render_chart(pixmap, series, *columns, rect, padding);
+            }
+            WidgetContent::Media {
+                title,
+                playing,
+                progress,
+                position_text,
+            } => {
+                self.render_media(
+                    pixmap,
+                    title,
+                    *playing,
+                    *progress,
+                    position_text,
+                    rect,
+                    padding,
+                    font_size,
+                );
+            }
+            WidgetContent::IconRow { icons, labels } if !icons.is_empty() => {
+                // Equal slots across the whole width, matching icon_row_slot
+                let slot_width = rect.width / icons.len() as f32;
+                let label_height = if labels.is_empty() {
+                    0.0
+                } else {
+                    font_size * 1.4
+                };
+                let icon_size = (slot_width - padding)
+                    .min(rect.height - padding * 2.0 - label_height)
+                    .max(0.0) as u32;
+                let top = y_center - (icon_size as f32 + label_height) / 2.0;
+
+                for (i, icon) in icons.iter().enumerate() {
+                    let slot_center = rect.x + slot_width * (i as f32 + 0.5);
+                    let icon_x = slot_center - icon_size as f32 / 2.0;
+                    match self.icon(icon, icon_size) {
+                        Ok(image) => image.draw(pixmap, icon_x as i32, top as i32),
+                        Err(e) => {
+                            trace!(icon = %icon, error = %e, "Failed to load icon, drawing initial");
+                            // The caption's first letter stands in for the icon
+                            let initial: String = labels
+                                .get(i)
+                                .unwrap_or(icon)
+                                .chars()
+                                .next()
+                                .map(|c| c.to_uppercase().collect())
+                                .unwrap_or_default();
+                            let fs = icon_size as f32 * 0.6;
+                            let width = self.text_renderer.measure_text(&initial, fs);
+                            let y = self
+                                .text_renderer
+                                .baseline_for_center(fs, top + icon_size as f32 / 2.0);
+                            self.render_text(pixmap, &initial, slot_center - width / 2.0, y, fs);
+                        }
+                    }
 
-        tracing::trace!(
-            widget = widget.info().id,
-            width = width,
-            height = height,
-            opacity = opacity,
-            "Rendered single widget"
-        );
+                    if let Some(label) = labels.get(i) {
+                        let mut fs = font_size;
+                        let mut width = self.text_renderer.measure_text(label, fs);
+                        let max_width = slot_width - padding / 2.0;
+                        if width > max_width && max_width > 0.0 {
+                            fs = (fs * max_width / width).max(8.0);
+                            width = self.text_renderer.measure_text(label, fs);
+                        }
+                        let label_center = top + icon_size as f32 + label_height / 2.0;
+                        let y = self.text_renderer.baseline_for_center(fs, label_center);
+                        self.render_text(pixmap, label, slot_center - width / 2.0, y, fs);
+                    }
+                }
+            }
+            WidgetContent::IconRow { .. } | WidgetContent::Error { .. } | WidgetContent::Empty => {}
+        }
     }
 }
 
@@ -1220,3 +1740,130 @@ impl Default for Renderer {
         Self::new()
     }
 }
+
+/// Base font size for a widget's content at the given surface height
+///
+/// None for empty content, which draws nothing.
+fn content_font_size(content: &WidgetContent, height: u32) -> Option<f32> {
+    let size = match content {
+        WidgetContent::Text { size, .. } => match size {
+            FontSize::Large => (height as f32 * 0.5).min(72.0),
+            FontSize::Medium => (height as f32 * 0.35).min(32.0),
+            FontSize::Small => (height as f32 * 0.25).min(20.0),
+            FontSize::Custom(s) => *s,
+        },
+        WidgetContent::MultiLine { lines } | WidgetContent::IconLines { lines, .. } => {
+            if let Some((_, size)) = lines.first() {
+                match size {
+                    FontSize::Large => (height as f32 * 0.4).min(48.0),
+                    FontSize::Medium => (height as f32 * 0.3).min(24.0),
+                    FontSize::Small => (height as f32 * 0.2).min(16.0),
+                    FontSize::Custom(s) => *s,
+                }
+            } else {
+                16.0
+            }
+        }
+        WidgetContent::IconText { size, .. } => match size {
+            FontSize::Large => (height as f32 * 0.5).min(48.0),
+            FontSize::Medium => (height as f32 * 0.35).min(28.0),
+            FontSize::Small => (height as f32 * 0.25).min(18.0),
+            FontSize::Custom(s) => *s,
+        },
+        WidgetContent::StyledText { size, .. } => match size {
+            FontSize::Large => (height as f32 * 0.5).min(72.0),
+            FontSize::Medium => (height as f32 * 0.35).min(32.0),
+            FontSize::Small => (height as f32 * 0.25).min(20.0),
+            FontSize::Custom(s) => *s,
+        },
+        WidgetContent::Progress { .. } => 16.0,
+        WidgetContent::MultiProgress { .. } => (height as f32 * 0.15).min(14.0),
+        // Grids size their text to their rows
+        WidgetContent::Grid { .. } => (height as f32 * 0.1).min(16.0),
+        // Charts size their text to their cells
+        WidgetContent::Chart { .. } => (height as f32 * 0.1).min(14.0),
+        // The track line takes the upper half
+        WidgetContent::Media { .. } => (height as f32 * 0.25).min(18.0),
+        // Captions under the icons
+        WidgetContent::IconRow { .. } => (height as f32 * 0.15).min(13.0),
+        // Drawn as an error card, headed by a medium line
+        WidgetContent::Error { .. } => (height as f32 * 0.3).min(24.0),
+        WidgetContent::Empty => return None,
+    };
+    Some(size)
+}
+
+/// Draw a transport control `size` tall, centered on (`x`, `y`)
+///
+/// Play/pause shows pause while `playing`.
+fn draw_media_control(
+    pixmap: &mut PixmapMut,
+    control: MediaControl,
+    playing: bool,
+    x: f32,
+    y: f32,
+    size: f32,
+    color: [u8; 4],
+) {
+    let half = size / 2.0;
+    let bar_width = size * 0.18;
+    // A triangle with its base at `base` and its tip at `tip`
+    let triangle = |path: &mut PathBuilder, base: f32, tip: f32| {
+        path.move_to(base, y - half);
+        path.line_to(tip, y);
+        path.line_to(base, y + half);
+        path.close();
+    };
+    let bar = |path: &mut PathBuilder, left: f32| {
+        if let Some(rect) = Rect::from_xywh(left, y - half, bar_width, size) {
+            path.push_rect(rect);
+        }
+    };
+
+    let mut path = PathBuilder::new();
+    match control {
+        MediaControl::Previous => {
+            bar(&mut path, x - half);
+            triangle(&mut path, x + half, x - half + bar_width);
+        }
+        MediaControl::Next => {
+            triangle(&mut path, x - half, x + half - bar_width);
+            bar(&mut path, x + half - bar_width);
+        }
+        MediaControl::PlayPause if playing => {
+            bar(&mut path, x - half * 0.6);
+            bar(&mut path, x + half * 0.6 - bar_width);
+        }
+        MediaControl::PlayPause => triangle(&mut path, x - half * 0.7, x + half * 0.9),
+    }
+    let Some(path) = path.finish() else {
+        return;
+    };
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color[0], color[1], color[2], color[3]);
+    paint.anti_alias = true;
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+}
+
+/// Fill a circle centered on (`x`, `y`)
+fn fill_circle(pixmap: &mut PixmapMut, x: f32, y: f32, radius: f32, color: [u8; 4]) {
+    let Some(path) = PathBuilder::from_circle(x, y, radius) else {
+        return;
+    };
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color[0], color[1], color[2], color[3]);
+    paint.anti_alias = true;
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+}
@@ -0,0 +1,205 @@
+// Session idle and lock state from systemd-logind
+
+use std::sync::mpsc;
+use std::thread;
+
+use zbus::zvariant::Value;
+
+const LOGIND_DESTINATION: &str = "org.freedesktop.login1";
+/// logind resolves `auto` to the caller's own session
+const SESSION_PATH: &str = "/org/freedesktop/login1/session/auto";
+const SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+
+/// Idle and lock state of the user's session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionState {
+    /// The compositor (or idle daemon) reported the session idle
+    pub idle: bool,
+    /// The screen locker is active
+    pub locked: bool,
+}
+
+impl SessionState {
+    /// Apply a changed logind property, returning true if the state changed
+    pub fn apply(&mut self, property: &str, value: bool) -> bool {
+        let field = match property {
+            "IdleHint" => &mut self.idle,
+            "LockedHint" => &mut self.locked,
+            _ => return false,
+        };
+        let changed = *field != value;
+        *field = value;
+        changed
+    }
+
+    /// Whether rendering should pause given the `[power]` settings
+    pub fn should_pause(&self, pause_when_idle: bool, pause_when_locked: bool) -> bool {
+        (self.idle && pause_when_idle) || (self.locked && pause_when_locked)
+    }
+
+    /// Whether widget surfaces should be removed given the `[power]`
+    /// settings
+    pub fn should_hide(&self, hide_when_locked: bool) -> bool {
+        self.locked && hide_when_locked
+    }
+}
+
+/// Session state watcher
+///
+/// Follows the logind session's `IdleHint` and `LockedHint` properties on a
+/// background thread and sends each change through a channel. Screen
+/// lockers set `LockedHint`; COSMIC and most idle daemons set `IdleHint`.
+/// A lock taken with ext-session-lock is only visible to the locker itself,
+/// so lockers that don't also set `LockedHint` go unnoticed.
+/// Without a system bus the watcher stays silent and widgets never pause.
+pub struct SessionWatcher {
+    receiver: mpsc::Receiver<SessionState>,
+}
+
+impl SessionWatcher {
+    /// Start watching the current session
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+
+        let spawned = thread::Builder::new()
+            .name("session-watcher".to_string())
+            .spawn(move || {
+                if let Err(e) = watch_logind(&tx) {
+                    tracing::warn!(error = %e, "Session idle/lock tracking unavailable");
+                }
+            });
+        if let Err(e) = spawned {
+            tracing::warn!(error = %e, "Failed to spawn session watcher");
+        }
+
+        Self { receiver: rx }
+    }
+
+    /// Try to receive the latest state change (non-blocking)
+    ///
+    /// Returns Some(state) if it changed since the last call, None otherwise.
+    pub fn try_recv(&self) -> Option<SessionState> {
+        self.receiver.try_iter().last()
+    }
+}
+
+impl Default for SessionWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn watch_logind(tx: &mpsc::Sender<SessionState>) -> zbus::Result<()> {
+    let connection = zbus::blocking::Connection::system()?;
+    let session = zbus::blocking::Proxy::new(
+        &connection,
+        LOGIND_DESTINATION,
+        SESSION_PATH,
+        SESSION_INTERFACE,
+    )?;
+    let properties = zbus::blocking::fdo::PropertiesProxy::builder(&connection)
+        .destination(LOGIND_DESTINATION)?
+        .path(SESSION_PATH)?
+        .build()?;
+
+    // Subscribe before reading so no change slips in between
+    let changes = properties.receive_properties_changed()?;
+
+    let mut state = SessionState {
+        idle: session.get_property("IdleHint")?,
+        locked: session.get_property("LockedHint")?,
+    };
+    tracing::info!(
+        idle = state.idle,
+        locked = state.locked,
+        "Session watcher started"
+    );
+    if tx.send(state).is_err() {
+        return Ok(());
+    }
+
+    for signal in changes {
+        let Ok(args) = signal.args() else {
+            continue;
+        };
+        if args.interface_name().as_str() != SESSION_INTERFACE {
+            continue;
+        }
+
+        let mut changed = false;
+        for (name, value) in args.changed_properties() {
+            if let Value::Bool(value) = value {
+                changed |= state.apply(name, *value);
+            }
+        }
+
+        if changed {
+            tracing::debug!(
+                idle = state.idle,
+                locked = state.locked,
+                "Session state changed"
+            );
+            if tx.send(state).is_err() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_properties() {
+        let mut state = SessionState::default();
+        assert!(state.apply("LockedHint", true));
+        assert!(!state.apply("LockedHint", true));
+        assert!(!state.apply("Active", false));
+        assert!(state.locked && !state.idle);
+
+        assert!(state.apply("IdleHint", true));
+        assert!(state.apply("LockedHint", false));
+        assert_eq!(
+            state,
+            SessionState {
+                idle: true,
+                locked: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_should_pause() {
+        let idle = SessionState {
+            idle: true,
+            locked: false,
+        };
+        assert!(idle.should_pause(true, true));
+        assert!(!idle.should_pause(false, true));
+
+        let locked = SessionState {
+            idle: false,
+            locked: true,
+        };
+        assert!(locked.should_pause(false, true));
+        assert!(!SessionState::default().should_pause(true, true));
+    }
+
+    #[test]
+    fn test_should_hide() {
+        let locked = SessionState {
+            idle: true,
+            locked: true,
+        };
+        assert!(locked.should_hide(true));
+        assert!(!locked.should_hide(false));
+
+        let idle = SessionState {
+            idle: true,
+            locked: false,
+        };
+        assert!(!idle.should_hide(true));
+    }
+}
@@ -0,0 +1,603 @@
+//! State that outlives the process
+//!
+//! Unlike the config, this is written by the widget itself as the user
+//! interacts with it, and lives in `$XDG_STATE_HOME/cosmic-desktop-widget`.
+//!
+//! Per-widget state is keyed by [`WidgetInstance::instance_id`], which
+//! [`Config::assign_instance_ids`](crate::config::Config::assign_instance_ids)
+//! makes unique when the config is loaded, so two clocks without an `id`
+//! don't share one entry.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::widget::WidgetInstance;
+
+const COLLAPSE_FILE: &str = "collapsed.toml";
+const PLACEMENT_FILE: &str = "placements.toml";
+const POMODORO_FILE: &str = "pomodoro.toml";
+const NEWS_FILE: &str = "news.toml";
+const WIDGET_STATE_FILE: &str = "widgets.toml";
+
+/// How long completed pomodoros are kept in the log
+const POMODORO_HISTORY_DAYS: i64 = 90;
+
+/// How long read headlines are remembered; feeds drop them well before
+const NEWS_HISTORY_DAYS: i64 = 30;
+
+/// Directory for state files
+///
+/// Falls back to the local data directory where there is no state
+/// directory.
+pub fn state_dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("cosmic-desktop-widget"))
+}
+
+/// Contents of a state file
+pub trait StateData: Serialize + DeserializeOwned + Default {
+    /// File name in the [`state_dir`]
+    const FILE: &'static str;
+}
+
+/// State kept in a file, saved again whenever it changes
+#[derive(Debug, Clone, Default)]
+pub struct StateFile<T> {
+    data: T,
+
+    /// File the state is saved to; None keeps it in memory only
+    path: Option<PathBuf>,
+}
+
+impl<T: StateData> StateFile<T> {
+    /// Load the saved state, starting empty if there is none
+    pub fn load() -> Self {
+        match state_dir() {
+            Some(dir) => Self::load_from(&dir.join(T::FILE)),
+            None => Self::default(),
+        }
+    }
+
+    /// Load from `path`, which is also where changes are saved
+    pub fn load_from(path: &Path) -> Self {
+        let data = match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|e| {
+                tracing::warn!(path = %path.display(), error = %e, "Ignoring unreadable state file");
+                T::default()
+            }),
+            Err(_) => T::default(),
+        };
+        Self {
+            data,
+            path: Some(path.to_path_buf()),
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let text = toml::to_string(&self.data)
+            .with_context(|| format!("Failed to serialize {}", path.display()))?;
+        write_state(path, &text)
+    }
+}
+
+/// Which collapsible widgets were left collapsed, by instance id
+pub type CollapseState = StateFile<CollapsedWidgets>;
+
+/// Contents of [`CollapseState`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CollapsedWidgets {
+    #[serde(default)]
+    collapsed: BTreeSet<String>,
+}
+
+impl StateData for CollapsedWidgets {
+    const FILE: &'static str = COLLAPSE_FILE;
+}
+
+impl CollapseState {
+    /// Whether the widget `id` is collapsed
+    pub fn is_collapsed(&self, id: &str) -> bool {
+        self.data.collapsed.contains(id)
+    }
+
+    /// Record whether the widget `id` is collapsed and save the change
+    pub fn set_collapsed(&mut self, id: &str, collapsed: bool) -> Result<()> {
+        let changed = if collapsed {
+            self.data.collapsed.insert(id.to_string())
+        } else {
+            self.data.collapsed.remove(id)
+        };
+        if changed {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+/// Where a widget was placed: its position, coordinates, output and margins
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedPlacement {
+    pub position: Option<String>,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    pub output: Option<String>,
+    pub margin_top: Option<i32>,
+    pub margin_right: Option<i32>,
+    pub margin_bottom: Option<i32>,
+    pub margin_left: Option<i32>,
+}
+
+impl SavedPlacement {
+    /// The placement of `instance`
+    pub fn of(instance: &WidgetInstance) -> Self {
+        Self {
+            position: instance.position.clone(),
+            x: instance.x,
+            y: instance.y,
+            output: instance.output.clone(),
+            margin_top: instance.margin_top,
+            margin_right: instance.margin_right,
+            margin_bottom: instance.margin_bottom,
+            margin_left: instance.margin_left,
+        }
+    }
+
+    /// Put `instance` back where it was
+    pub fn apply_to(&self, instance: &mut WidgetInstance) {
+        instance.position = self.position.clone();
+        instance.x = self.x;
+        instance.y = self.y;
+        instance.output = self.output.clone();
+        instance.margin_top = self.margin_top;
+        instance.margin_right = self.margin_right;
+        instance.margin_bottom = self.margin_bottom;
+        instance.margin_left = self.margin_left;
+    }
+}
+
+/// Widget placements remembered for each monitor setup
+///
+/// Setups are keyed by the fingerprint of their monitors (see
+/// [`setup_fingerprint`](crate::wayland::outputs::setup_fingerprint)) and
+/// widgets by instance id.
+pub type PlacementMemory = StateFile<SetupPlacements>;
+
+/// Contents of [`PlacementMemory`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetupPlacements {
+    #[serde(default)]
+    setups: BTreeMap<String, BTreeMap<String, SavedPlacement>>,
+}
+
+impl StateData for SetupPlacements {
+    const FILE: &'static str = PLACEMENT_FILE;
+}
+
+impl PlacementMemory {
+    /// Whether anything is remembered for `setup`
+    pub fn knows(&self, setup: &str) -> bool {
+        self.data.setups.contains_key(setup)
+    }
+
+    /// Move the widgets back to where they were on `setup`
+    ///
+    /// Widgets added since keep their configured placement. Returns whether
+    /// anything was remembered for the setup.
+    pub fn recall(&self, setup: &str, widgets: &mut [WidgetInstance]) -> bool {
+        let Some(placements) = self.data.setups.get(setup) else {
+            return false;
+        };
+        for instance in widgets {
+            if let Some(placement) = placements.get(&instance.instance_id()) {
+                placement.apply_to(instance);
+            }
+        }
+        true
+    }
+
+    /// Remember the placement of `widgets` on `setup` and save the change
+    pub fn remember(&mut self, setup: &str, widgets: &[WidgetInstance]) -> Result<()> {
+        let placements = placements_of(widgets);
+        if self.data.setups.get(setup) == Some(&placements) {
+            return Ok(());
+        }
+        self.data.setups.insert(setup.to_string(), placements);
+        self.save()
+    }
+}
+
+/// Placement of each widget, by instance id
+pub fn placements_of(widgets: &[WidgetInstance]) -> BTreeMap<String, SavedPlacement> {
+    widgets
+        .iter()
+        .map(|instance| (instance.instance_id(), SavedPlacement::of(instance)))
+        .collect()
+}
+
+/// Carry the current placements over a config reload
+///
+/// `file` is how the config file placed the widgets before. Widgets the new
+/// config still places that way keep their `current` placement, which may
+/// have been recalled for the monitor setup; widgets it moved go where it
+/// says.
+pub fn carry_placements(
+    file: &BTreeMap<String, SavedPlacement>,
+    current: &[WidgetInstance],
+    new: &mut [WidgetInstance],
+) {
+    for instance in new {
+        let id = instance.instance_id();
+        if file.get(&id) != Some(&SavedPlacement::of(instance)) {
+            continue;
+        }
+        if let Some(current) = current.iter().find(|w| w.instance_id() == id) {
+            SavedPlacement::of(current).apply_to(instance);
+        }
+    }
+}
+
+/// The pomodoro session that was running, so it resumes after a restart
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PomodoroSession {
+    /// Phase name ("working", "short_break", ...)
+    pub phase: String,
+    /// When the phase started, in seconds since the epoch; None while it
+    /// waits to be started
+    pub started_at: Option<i64>,
+    /// Work sessions completed in the current cycle
+    pub completed: u32,
+}
+
+/// Completed pomodoros and the running session
+pub type PomodoroLog = StateFile<PomodoroHistory>;
+
+/// Contents of [`PomodoroLog`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PomodoroHistory {
+    /// End times of completed work sessions, in seconds since the epoch
+    #[serde(default)]
+    completed: Vec<i64>,
+
+    #[serde(default)]
+    session: Option<PomodoroSession>,
+}
+
+impl StateData for PomodoroHistory {
+    const FILE: &'static str = POMODORO_FILE;
+}
+
+impl PomodoroLog {
+    /// The session that was running when the log was saved
+    pub fn session(&self) -> Option<&PomodoroSession> {
+        self.data.session.as_ref()
+    }
+
+    /// Remember the running session and save the change
+    pub fn set_session(&mut self, session: Option<PomodoroSession>) -> Result<()> {
+        if self.data.session != session {
+            self.data.session = session;
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Record a work session completed at `at` and save the change
+    pub fn record_completed(&mut self, at: DateTime<Local>) -> Result<()> {
+        let cutoff = (at - Duration::days(POMODORO_HISTORY_DAYS)).timestamp();
+        self.data.completed.retain(|&t| t > cutoff);
+        self.data.completed.push(at.timestamp());
+        self.save()
+    }
+
+    /// Work sessions completed since local midnight
+    pub fn today(&self, now: DateTime<Local>) -> usize {
+        self.count_since(now.date_naive())
+    }
+
+    /// Work sessions completed since Monday
+    pub fn this_week(&self, now: DateTime<Local>) -> usize {
+        let days = now.weekday().num_days_from_monday() as i64;
+        self.count_since(now.date_naive() - Duration::days(days))
+    }
+
+    fn count_since(&self, date: NaiveDate) -> usize {
+        let Some(start) = Local
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .earliest()
+        else {
+            return 0;
+        };
+        let start = start.timestamp();
+        self.data.completed.iter().filter(|&&t| t >= start).count()
+    }
+}
+
+/// Headlines already shown, so they aren't shown as new after a restart
+pub type NewsReadState = StateFile<ReadHeadlines>;
+
+/// Contents of [`NewsReadState`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadHeadlines {
+    /// When each headline was read, in seconds since the epoch, by its id
+    /// (the feed's guid or link)
+    #[serde(default)]
+    read: BTreeMap<String, i64>,
+}
+
+impl StateData for ReadHeadlines {
+    const FILE: &'static str = NEWS_FILE;
+}
+
+impl NewsReadState {
+    /// Whether the headline `id` was read
+    pub fn is_read(&self, id: &str) -> bool {
+        self.data.read.contains_key(id)
+    }
+
+    /// Record the headline `id` as read at `at` and save the change
+    pub fn mark_read(&mut self, id: &str, at: DateTime<Local>) -> Result<()> {
+        if self.is_read(id) {
+            return Ok(());
+        }
+        let cutoff = (at - Duration::days(NEWS_HISTORY_DAYS)).timestamp();
+        self.data.read.retain(|_, &mut t| t > cutoff);
+        self.data.read.insert(id.to_string(), at.timestamp());
+        self.save()
+    }
+}
+
+/// What each widget asked to keep across restarts, by instance id
+///
+/// See [`Widget::save_state`](crate::widget::Widget::save_state). Widgets
+/// with more to remember, like the pomodoro log and read headlines, have
+/// files of their own.
+pub type WidgetStates = StateFile<SavedWidgetStates>;
+
+/// Contents of [`WidgetStates`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedWidgetStates {
+    #[serde(default)]
+    widgets: BTreeMap<String, toml::Table>,
+}
+
+impl StateData for SavedWidgetStates {
+    const FILE: &'static str = WIDGET_STATE_FILE;
+}
+
+impl WidgetStates {
+    /// The saved state of the widget `id`
+    pub fn get(&self, id: &str) -> Option<&toml::Table> {
+        self.data.widgets.get(id)
+    }
+
+    /// Record the state of the widget `id` and save it if it changed
+    pub fn set(&mut self, id: &str, state: toml::Table) -> Result<()> {
+        if self.data.widgets.get(id) == Some(&state) {
+            return Ok(());
+        }
+        self.data.widgets.insert(id.to_string(), state);
+        self.save()
+    }
+}
+
+/// Write `text` to a temporary file next to `path` and rename it over
+/// `path`, so a crash mid-write never leaves a truncated file behind
+fn write_state(path: &Path, text: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let temp = path.with_extension("toml.tmp");
+    fs::write(&temp, text).with_context(|| format!("Failed to write {}", temp.display()))?;
+    fs::rename(&temp, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapse_state_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("state").join(COLLAPSE_FILE);
+
+        let mut state = CollapseState::load_from(&path);
+        assert!(!state.is_collapsed("weather"));
+        state.set_collapsed("weather", true).unwrap();
+        state.set_collapsed("sysmon", true).unwrap();
+        state.set_collapsed("sysmon", false).unwrap();
+
+        let state = CollapseState::load_from(&path);
+        assert!(state.is_collapsed("weather"));
+        assert!(!state.is_collapsed("sysmon"));
+    }
+
+    #[test]
+    fn test_collapse_state_unreadable_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(COLLAPSE_FILE);
+        fs::write(&path, "collapsed = 3").unwrap();
+
+        let state = CollapseState::load_from(&path);
+        assert!(!state.is_collapsed("weather"));
+    }
+
+    #[test]
+    fn test_placement_memory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(PLACEMENT_FILE);
+        let laptop = "BOE 0x095F";
+        let docked = "BOE 0x095F + Dell Inc. DELL U2720Q";
+
+        let mut clock = WidgetInstance::new("clock");
+        clock.position = Some("top-right".to_string());
+        let mut memory = PlacementMemory::load_from(&path);
+        memory
+            .remember(laptop, std::slice::from_ref(&clock))
+            .unwrap();
+
+        clock.position = Some("bottom-left".to_string());
+        clock.output = Some("DP-3".to_string());
+        clock.margin_left = Some(40);
+        memory
+            .remember(docked, std::slice::from_ref(&clock))
+            .unwrap();
+
+        // Undocked: back to the laptop layout, leaving new widgets alone
+        let memory = PlacementMemory::load_from(&path);
+        let mut widgets = vec![clock, WidgetInstance::new("weather")];
+        widgets[1].position = Some("center".to_string());
+        assert!(memory.recall(laptop, &mut widgets));
+        assert_eq!(widgets[0].position.as_deref(), Some("top-right"));
+        assert_eq!(widgets[0].output, None);
+        assert_eq!(widgets[0].margin_left, None);
+        assert_eq!(widgets[1].position.as_deref(), Some("center"));
+
+        assert!(memory.recall(docked, &mut widgets));
+        assert_eq!(widgets[0].output.as_deref(), Some("DP-3"));
+        assert!(!memory.knows("HEADLESS-1"));
+        assert!(!memory.recall("HEADLESS-1", &mut widgets));
+    }
+
+    #[test]
+    fn test_carry_placements() {
+        let mut clock = WidgetInstance::new("clock");
+        clock.position = Some("top-right".to_string());
+        let mut weather = WidgetInstance::new("weather");
+        weather.position = Some("top-left".to_string());
+        let file = placements_of(&[clock.clone(), weather.clone()]);
+
+        // Both were moved for the docked setup
+        let mut current = vec![clock.clone(), weather.clone()];
+        current[0].position = Some("bottom-right".to_string());
+        current[1].position = Some("bottom-left".to_string());
+
+        // The file now moves the weather only
+        let mut new = vec![clock, weather];
+        new[1].position = Some("center".to_string());
+        carry_placements(&file, &current, &mut new);
+        assert_eq!(new[0].position.as_deref(), Some("bottom-right"));
+        assert_eq!(new[1].position.as_deref(), Some("center"));
+    }
+
+    #[test]
+    fn test_same_type_widgets_placed_apart() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(PLACEMENT_FILE);
+        let setup = "BOE 0x095F";
+
+        let mut config = crate::config::Config {
+            widgets: vec![WidgetInstance::new("clock"), WidgetInstance::new("clock")],
+            ..Default::default()
+        };
+        config.assign_instance_ids();
+        config.widgets[0].position = Some("top-left".to_string());
+        config.widgets[1].position = Some("top-right".to_string());
+        let file = placements_of(&config.widgets);
+        assert_eq!(file.len(), 2);
+
+        let mut memory = PlacementMemory::load_from(&path);
+        memory.remember(setup, &config.widgets).unwrap();
+
+        let mut widgets = config.widgets.clone();
+        widgets[0].position = None;
+        widgets[1].position = None;
+        assert!(memory.recall(setup, &mut widgets));
+        assert_eq!(widgets[0].position.as_deref(), Some("top-left"));
+        assert_eq!(widgets[1].position.as_deref(), Some("top-right"));
+
+        // A reload leaving both alone keeps each where it was moved to
+        let mut current = config.widgets.clone();
+        current[1].position = Some("bottom-right".to_string());
+        let mut new = config.widgets.clone();
+        carry_placements(&file, &current, &mut new);
+        assert_eq!(new[0].position.as_deref(), Some("top-left"));
+        assert_eq!(new[1].position.as_deref(), Some("bottom-right"));
+    }
+
+    #[test]
+    fn test_news_read_state() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(NEWS_FILE);
+        let now = Local.with_ymd_and_hms(2026, 3, 4, 15, 0, 0).unwrap();
+
+        let mut state = NewsReadState::load_from(&path);
+        state
+            .mark_read("https://example.com/old", now - Duration::days(40))
+            .unwrap();
+        state.mark_read("https://example.com/a", now).unwrap();
+
+        let state = NewsReadState::load_from(&path);
+        assert!(state.is_read("https://example.com/a"));
+        assert!(!state.is_read("https://example.com/old"));
+        assert!(!state.is_read("https://example.com/b"));
+    }
+
+    #[test]
+    fn test_pomodoro_log_counts() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(POMODORO_FILE);
+        // A Wednesday
+        let now = Local.with_ymd_and_hms(2026, 3, 4, 15, 0, 0).unwrap();
+
+        let mut log = PomodoroLog::load_from(&path);
+        log.record_completed(now - Duration::days(100)).unwrap();
+        log.record_completed(now - Duration::days(3)).unwrap();
+        log.record_completed(now - Duration::days(1)).unwrap();
+        log.record_completed(now - Duration::hours(2)).unwrap();
+        log.record_completed(now).unwrap();
+        log.set_session(Some(PomodoroSession {
+            phase: "short_break".to_string(),
+            started_at: Some(now.timestamp()),
+            completed: 2,
+        }))
+        .unwrap();
+
+        let log = PomodoroLog::load_from(&path);
+        assert_eq!(log.today(now), 2);
+        assert_eq!(log.this_week(now), 3);
+        // The session from 100 days ago was pruned
+        assert_eq!(log.data.completed.len(), 4);
+        assert_eq!(log.session().unwrap().completed, 2);
+    }
+
+    #[test]
+    fn test_widget_states_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(WIDGET_STATE_FILE);
+
+        let mut quote = toml::Table::new();
+        quote.insert("index".to_string(), toml::Value::Integer(7));
+        let mut states = WidgetStates::load_from(&path);
+        assert!(states.get("quotes").is_none());
+        states.set("quotes", quote.clone()).unwrap();
+        states.set("work.quotes", toml::Table::new()).unwrap();
+
+        let states = WidgetStates::load_from(&path);
+        assert_eq!(states.get("quotes"), Some(&quote));
+        assert_eq!(states.get("work.quotes"), Some(&toml::Table::new()));
+    }
+
+    #[test]
+    fn test_write_state_replaces_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(WIDGET_STATE_FILE);
+        fs::write(&path, "widgets = 3").unwrap();
+
+        let mut states = WidgetStates::load_from(&path);
+        states.set("quotes", toml::Table::new()).unwrap();
+
+        let files: Vec<_> = fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(files.len(), 1, "temporary file left behind");
+        assert!(WidgetStates::load_from(&path).get("quotes").is_some());
+    }
+}
@@ -1,10 +1,17 @@
 //! Individual widget surface management
 //!
-//! Each widget gets its own Layer Shell surface with independent:
+//! Each widget gets its own Layer Shell surface, unless it shares a `group`
+//! with others, which are then laid out together on one surface. Surfaces
+//! have independent:
 //! - Position and anchoring
 //! - Size (width/height)
 //! - Opacity/transparency
 //! - Buffer pool for rendering
+//! - Compositor blur
+//! - Idle inhibitor
+
+use std::borrow::Cow;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use smithay_client_toolkit::{
@@ -16,8 +23,75 @@ use wayland_client::{
     QueueHandle,
 };
 
+use crate::layout::WidgetPosition;
 use crate::position::Position;
-use crate::wayland::BufferPool;
+use crate::wayland::{BufferPool, SurfaceBlur, SurfaceInhibitor};
+use crate::widget::{Freshness, WidgetContent};
+
+/// How long a collapsible surface takes to change height
+pub const COLLAPSE_DURATION: Duration = Duration::from_millis(180);
+
+/// The two heights of a collapsible surface and which one it is at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Collapse {
+    /// Height while collapsed
+    pub compact: u32,
+    /// Height while expanded
+    pub expanded: u32,
+    /// Whether the surface is collapsed
+    pub collapsed: bool,
+}
+
+impl Collapse {
+    /// Height for the current state
+    pub fn height(&self) -> u32 {
+        if self.collapsed {
+            self.compact
+        } else {
+            self.expanded
+        }
+    }
+}
+
+/// A surface height change in progress, eased out over its duration
+#[derive(Debug, Clone, Copy)]
+pub struct HeightAnimation {
+    from: u32,
+    to: u32,
+    start: Instant,
+    duration: Duration,
+}
+
+impl HeightAnimation {
+    /// Animate from `from` to `to`, starting at `start`
+    pub fn new(from: u32, to: u32, start: Instant, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            start,
+            duration,
+        }
+    }
+
+    /// Height at `now`
+    pub fn height_at(&self, now: Instant) -> u32 {
+        let t = if self.duration.is_zero() {
+            1.0
+        } else {
+            (now.saturating_duration_since(self.start).as_secs_f32() / self.duration.as_secs_f32())
+                .min(1.0)
+        };
+        // Ease out cubic: fast at first, settling into place
+        let eased = 1.0 - (1.0 - t).powi(3);
+        let height = self.from as f32 + (self.to as f32 - self.from as f32) * eased;
+        height.round() as u32
+    }
+
+    /// Whether the animation has reached its final height at `now`
+    pub fn is_done(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) >= self.duration
+    }
+}
 
 /// Represents a single widget's Layer Shell surface
 pub struct WidgetSurface {
@@ -37,8 +111,14 @@ pub struct WidgetSurface {
     /// Whether the surface has been configured by the compositor
     pub configured: bool,
 
-    /// Index of the widget this surface displays
-    pub widget_index: usize,
+    /// Indices of the widgets this surface displays, into the enabled widgets
+    pub widget_indices: Vec<usize>,
+
+    /// Group name for a surface shared by several widgets
+    pub group: Option<String>,
+
+    /// Where each widget of a group is drawn, in `widget_indices` order
+    pub layout: Vec<WidgetPosition>,
 
     /// Position configuration
     pub position: Position,
@@ -48,6 +128,25 @@ pub struct WidgetSurface {
 
     /// Whether this is the first frame
     pub first_frame: bool,
+
+    /// Compositor blur behind the surface, if enabled
+    pub blur: Option<SurfaceBlur>,
+
+    /// Keeps the screen awake while one of the surface's widgets asks for it
+    pub idle_inhibitor: Option<SurfaceInhibitor>,
+
+    /// Widget content and freshness badge in the last committed frame, one
+    /// entry per widget
+    pub last_content: Option<Vec<(WidgetContent, Freshness)>>,
+
+    /// Compact and expanded heights, for a collapsible widget
+    pub collapse: Option<Collapse>,
+
+    /// Height change in progress after collapsing or expanding
+    pub animation: Option<HeightAnimation>,
+
+    /// Whether the surface reserves its space with an exclusive zone
+    pub reserve_space: bool,
 }
 
 impl WidgetSurface {
@@ -68,11 +167,74 @@ impl WidgetSurface {
             width,
             height,
             configured: false,
-            widget_index,
+            widget_indices: vec![widget_index],
+            group: None,
+            layout: Vec::new(),
             position,
             opacity,
             first_frame: true,
+            blur: None,
+            idle_inhibitor: None,
+            last_content: None,
+            collapse: None,
+            animation: None,
+            reserve_space: false,
+        }
+    }
+
+    /// Reserve the surface's space, keeping windows clear of it
+    pub fn with_reserve_space(mut self) -> Self {
+        self.reserve_space = true;
+        self
+    }
+
+    /// Exclusive zone for the surface at `height`, if it reserves space
+    pub fn exclusive_zone(&self, height: u32) -> Option<i32> {
+        if !self.reserve_space {
+            return None;
         }
+        self.position.exclusive_zone(self.width, height)
+    }
+
+    /// Let the surface collapse to a compact height and expand again
+    pub fn with_collapse(mut self, collapse: Collapse) -> Self {
+        self.collapse = Some(collapse);
+        self
+    }
+
+    /// Show the widgets of `group` at `layout` instead of a single widget
+    pub fn with_group(
+        mut self,
+        group: &str,
+        widget_indices: Vec<usize>,
+        layout: Vec<WidgetPosition>,
+    ) -> Self {
+        self.group = Some(group.to_string());
+        self.widget_indices = widget_indices;
+        self.layout = layout;
+        self
+    }
+
+    /// Index of the (first) widget this surface displays
+    pub fn widget_index(&self) -> usize {
+        self.widget_indices[0]
+    }
+
+    /// The widget at surface coordinates `(x, y)`, with the point relative
+    /// to that widget's area (0.0 to 1.0 across each axis)
+    pub fn widget_at(&self, x: f64, y: f64) -> Option<(usize, f32, f32)> {
+        let (x, y) = (x as f32, y as f32);
+        if self.layout.is_empty() {
+            let (width, height) = (self.width.max(1) as f32, self.height.max(1) as f32);
+            return Some((self.widget_index(), x / width, y / height));
+        }
+        self.widget_indices
+            .iter()
+            .zip(&self.layout)
+            .find(|(_, rect)| {
+                x >= rect.x && x < rect.x + rect.width && y >= rect.y && y < rect.y + rect.height
+            })
+            .map(|(&index, rect)| (index, (x - rect.x) / rect.width, (y - rect.y) / rect.height))
     }
 
     /// Initialize the buffer pool for this surface
@@ -80,24 +242,72 @@ impl WidgetSurface {
         &mut self,
         shm_state: &Shm,
         qh: &QueueHandle<T>,
+        triple_buffering: bool,
     ) -> Result<()> {
-        let pool = BufferPool::new(self.width, self.height, shm_state, qh)?;
+        let pool = BufferPool::new(self.width, self.height, shm_state, qh)?
+            .with_triple_buffering(triple_buffering);
         self.buffer_pool = Some(pool);
         Ok(())
     }
 
+    /// Whether `contents` match what the surface already shows
+    ///
+    /// Takes the contents one by one, so widgets can lend theirs rather
+    /// than copy them every frame.
+    pub fn shows<'a>(
+        &self,
+        contents: impl IntoIterator<Item = (Cow<'a, WidgetContent>, Freshness)>,
+    ) -> bool {
+        let Some(shown) = &self.last_content else {
+            return false;
+        };
+        let mut contents = contents.into_iter();
+        let same = shown.iter().all(|(content, freshness)| {
+            contents.next().is_some_and(|(other, other_freshness)| {
+                *other == *content && other_freshness == *freshness
+            })
+        });
+        same && contents.next().is_none()
+    }
+
+    /// Collapse or expand the surface, animating to the new height over
+    /// `duration` (usually [`COLLAPSE_DURATION`]; zero jumps straight there)
+    ///
+    /// Returns whether it is now collapsed, or None if it isn't collapsible.
+    pub fn toggle_collapsed(&mut self, now: Instant, duration: Duration) -> Option<bool> {
+        let collapse = self.collapse.as_mut()?;
+        collapse.collapsed = !collapse.collapsed;
+        // Start from wherever a running animation has got to
+        let from = self
+            .animation
+            .map_or(self.height, |animation| animation.height_at(now));
+        self.animation = Some(HeightAnimation::new(from, collapse.height(), now, duration));
+        Some(collapse.collapsed)
+    }
+
+    /// Advance the height animation, returning the height for this frame
+    ///
+    /// Returns None when nothing is animating. The animation is dropped once
+    /// it returns its final height.
+    pub fn animate(&mut self, now: Instant) -> Option<u32> {
+        let animation = self.animation?;
+        if animation.is_done(now) {
+            self.animation = None;
+        }
+        Some(animation.height_at(now))
+    }
+
     /// Check if the surface is ready to render
     pub fn is_ready(&self) -> bool {
         self.configured && self.buffer_pool.is_some()
     }
 
-    /// Update surface size and mark buffer pool for recreation
+    /// Update surface size; the buffer pool follows on the next draw
     pub fn resize(&mut self, width: u32, height: u32) {
         if self.width != width || self.height != height {
             self.width = width;
             self.height = height;
-            // Drop old buffer pool - will be recreated on next draw
-            self.buffer_pool = None;
+            self.last_content = None;
         }
     }
 }
@@ -106,9 +316,47 @@ impl Drop for WidgetSurface {
     fn drop(&mut self) {
         // Layer surface cleanup is automatic via smithay-client-toolkit
         tracing::debug!(
-            widget_index = self.widget_index,
+            widgets = ?self.widget_indices,
             position = %self.position,
             "Dropping widget surface"
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_height_animation() {
+        let start = Instant::now();
+        let animation = HeightAnimation::new(48, 148, start, Duration::from_millis(200));
+
+        assert_eq!(animation.height_at(start), 48);
+        let halfway = animation.height_at(start + Duration::from_millis(100));
+        assert!(
+            halfway > 98 && halfway < 148,
+            "eased past the midpoint: {halfway}"
+        );
+        assert_eq!(animation.height_at(start + Duration::from_millis(200)), 148);
+        assert_eq!(animation.height_at(start + Duration::from_secs(5)), 148);
+        assert!(!animation.is_done(start + Duration::from_millis(199)));
+        assert!(animation.is_done(start + Duration::from_millis(200)));
+
+        // Collapsing animates downwards just the same
+        let animation = HeightAnimation::new(148, 48, start, Duration::from_millis(200));
+        assert_eq!(animation.height_at(start + Duration::from_millis(200)), 48);
+    }
+
+    #[test]
+    fn test_collapse_height() {
+        let mut collapse = Collapse {
+            compact: 48,
+            expanded: 120,
+            collapsed: false,
+        };
+        assert_eq!(collapse.height(), 120);
+        collapse.collapsed = true;
+        assert_eq!(collapse.height(), 48);
+    }
+}
@@ -0,0 +1,558 @@
+//! Mini template engine for user-customisable widget text
+//!
+//! Widgets that accept a `format` config key render their text through a
+//! [`Template`]. Placeholders use a subset of Rust's `format!` syntax:
+//!
+//! - `{temp}` - plain value
+//! - `{temp:.1}` - fixed precision (numbers) or maximum length (text)
+//! - `{symbol:<6}`, `{price:>8.2}`, `{name:^10}` - alignment and width
+//! - `{name:*^10}` - custom fill character
+//! - `{hour:02}` - zero padding
+//! - `{change:+.2}` - always show the sign
+//! - `{{` and `}}` - literal braces
+//!
+//! ```
+//! use cosmic_desktop_widget::template::{Template, TemplateVars};
+//!
+//! let template = Template::parse("{temp:.0}°{unit} {condition}").unwrap();
+//! let vars = TemplateVars::new()
+//!     .with("temp", 21.4)
+//!     .with("unit", "C")
+//!     .with("condition", "Clouds");
+//! assert_eq!(template.render(&vars), "21°C Clouds");
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use thiserror::Error;
+
+/// Errors produced while parsing a template
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum TemplateError {
+    #[error("unclosed placeholder starting at position {0}")]
+    UnclosedPlaceholder(usize),
+
+    #[error("unmatched '}}' at position {0} (use '}}}}' for a literal brace)")]
+    UnmatchedBrace(usize),
+
+    #[error("empty placeholder at position {0}")]
+    EmptyPlaceholder(usize),
+
+    #[error("invalid format spec '{spec}' for placeholder '{name}'")]
+    InvalidSpec { name: String, spec: String },
+
+    #[error("unknown placeholder '{{{name}}}', expected one of: {allowed}")]
+    UnknownPlaceholder { name: String, allowed: String },
+}
+
+/// A value that can be substituted into a template
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateValue {
+    /// Free-form text
+    Text(String),
+    /// A number; precision and sign flags apply
+    Number(f64),
+}
+
+impl From<&str> for TemplateValue {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+impl From<String> for TemplateValue {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl From<f64> for TemplateValue {
+    fn from(n: f64) -> Self {
+        Self::Number(n)
+    }
+}
+
+impl From<f32> for TemplateValue {
+    fn from(n: f32) -> Self {
+        Self::Number(n as f64)
+    }
+}
+
+impl From<i64> for TemplateValue {
+    fn from(n: i64) -> Self {
+        Self::Number(n as f64)
+    }
+}
+
+impl From<u64> for TemplateValue {
+    fn from(n: u64) -> Self {
+        Self::Number(n as f64)
+    }
+}
+
+impl From<u32> for TemplateValue {
+    fn from(n: u32) -> Self {
+        Self::Number(n as f64)
+    }
+}
+
+/// Named values available to a template while rendering
+#[derive(Debug, Clone, Default)]
+pub struct TemplateVars {
+    values: HashMap<&'static str, TemplateValue>,
+}
+
+impl TemplateVars {
+    /// Create an empty set of variables
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a variable (builder style)
+    pub fn with(mut self, name: &'static str, value: impl Into<TemplateValue>) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Add or replace a variable
+    pub fn set(&mut self, name: &'static str, value: impl Into<TemplateValue>) {
+        self.values.insert(name, value.into());
+    }
+
+    /// Look up a variable
+    pub fn get(&self, name: &str) -> Option<&TemplateValue> {
+        self.values.get(name)
+    }
+}
+
+/// Horizontal alignment inside a padded field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// Parsed `:spec` portion of a placeholder
+#[derive(Debug, Clone, Default, PartialEq)]
+struct FormatSpec {
+    fill: Option<char>,
+    align: Option<Align>,
+    sign: bool,
+    zero: bool,
+    width: Option<usize>,
+    precision: Option<usize>,
+}
+
+impl FormatSpec {
+    fn parse(name: &str, spec: &str) -> Result<Self, TemplateError> {
+        let invalid = || TemplateError::InvalidSpec {
+            name: name.to_string(),
+            spec: spec.to_string(),
+        };
+
+        let chars: Vec<char> = spec.chars().collect();
+        let mut result = Self::default();
+        let mut i = 0;
+
+        let align_of = |c: char| match c {
+            '<' => Some(Align::Left),
+            '^' => Some(Align::Center),
+            '>' => Some(Align::Right),
+            _ => None,
+        };
+
+        // [[fill]align]
+        if chars.len() >= 2 && align_of(chars[1]).is_some() {
+            result.fill = Some(chars[0]);
+            result.align = align_of(chars[1]);
+            i = 2;
+        } else if let Some(align) = chars.first().and_then(|&c| align_of(c)) {
+            result.align = Some(align);
+            i = 1;
+        }
+
+        // [sign]
+        if chars.get(i) == Some(&'+') {
+            result.sign = true;
+            i += 1;
+        }
+
+        // [0]
+        if chars.get(i) == Some(&'0') {
+            result.zero = true;
+            i += 1;
+        }
+
+        // [width]
+        let start = i;
+        while chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+            i += 1;
+        }
+        if i > start {
+            let digits: String = chars[start..i].iter().collect();
+            result.width = Some(digits.parse().map_err(|_| invalid())?);
+        }
+
+        // [.precision]
+        if chars.get(i) == Some(&'.') {
+            i += 1;
+            let start = i;
+            while chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+                i += 1;
+            }
+            if i == start {
+                return Err(invalid());
+            }
+            let digits: String = chars[start..i].iter().collect();
+            result.precision = Some(digits.parse().map_err(|_| invalid())?);
+        }
+
+        if i != chars.len() {
+            return Err(invalid());
+        }
+
+        Ok(result)
+    }
+
+    fn apply(&self, value: &TemplateValue) -> String {
+        match value {
+            TemplateValue::Number(n) => self.apply_number(*n),
+            TemplateValue::Text(s) => self.apply_text(s),
+        }
+    }
+
+    fn apply_number(&self, n: f64) -> String {
+        let mut body = match self.precision {
+            Some(precision) => format!("{:.*}", precision, n),
+            None if n.fract() == 0.0 && n.abs() < 1e15 => format!("{}", n as i64),
+            None => format!("{}", n),
+        };
+
+        if self.sign && !body.starts_with('-') {
+            body.insert(0, '+');
+        }
+
+        if self.zero && self.align.is_none() {
+            if let Some(width) = self.width {
+                let len = body.chars().count();
+                if len < width {
+                    // Zeros go between the sign and the digits
+                    let sign_len = usize::from(body.starts_with(['+', '-']));
+                    let zeros = "0".repeat(width - len);
+                    body.insert_str(sign_len, &zeros);
+                }
+                return body;
+            }
+        }
+
+        self.pad(body, Align::Right)
+    }
+
+    fn apply_text(&self, s: &str) -> String {
+        let text = match self.precision {
+            Some(max) => s.chars().take(max).collect(),
+            None => s.to_string(),
+        };
+        self.pad(text, Align::Left)
+    }
+
+    fn pad(&self, text: String, default_align: Align) -> String {
+        let Some(width) = self.width else {
+            return text;
+        };
+
+        let len = text.chars().count();
+        if len >= width {
+            return text;
+        }
+
+        let fill = self.fill.unwrap_or(' ');
+        let total = width - len;
+        let (left, right) = match self.align.unwrap_or(default_align) {
+            Align::Left => (0, total),
+            Align::Right => (total, 0),
+            Align::Center => (total / 2, total - total / 2),
+        };
+
+        let mut out = String::with_capacity(text.len() + total);
+        out.extend(std::iter::repeat(fill).take(left));
+        out.push_str(&text);
+        out.extend(std::iter::repeat(fill).take(right));
+        out
+    }
+}
+
+/// One piece of a parsed template
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Placeholder {
+        name: String,
+        spec: FormatSpec,
+        /// Original `{...}` text, rendered verbatim if the variable is missing
+        raw: String,
+    },
+}
+
+/// A parsed, reusable format template
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// Parse a template string
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.char_indices().peekable();
+
+        while let Some((pos, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().map(|&(_, c)| c) == Some('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek().map(|&(_, c)| c) == Some('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace(pos)),
+                '{' => {
+                    let mut inner = String::new();
+                    let mut closed = false;
+                    for (_, c) in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        inner.push(c);
+                    }
+                    if !closed {
+                        return Err(TemplateError::UnclosedPlaceholder(pos));
+                    }
+
+                    let (name, spec) = match inner.split_once(':') {
+                        Some((name, spec)) => (name.trim(), spec),
+                        None => (inner.trim(), ""),
+                    };
+                    if name.is_empty() {
+                        return Err(TemplateError::EmptyPlaceholder(pos));
+                    }
+
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Placeholder {
+                        name: name.to_string(),
+                        spec: FormatSpec::parse(name, spec)?,
+                        raw: format!("{{{}}}", inner),
+                    });
+                }
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            parts,
+        })
+    }
+
+    /// Parse a template and check it only uses the given placeholder names
+    pub fn parse_with_names(source: &str, allowed: &[&str]) -> Result<Self, TemplateError> {
+        let template = Self::parse(source)?;
+        template.validate_names(allowed)?;
+        Ok(template)
+    }
+
+    /// Read an optional template from a widget config table
+    ///
+    /// Returns `Ok(None)` when the key is absent.
+    pub fn from_config(
+        config: &toml::Table,
+        key: &str,
+        allowed: &[&str],
+    ) -> anyhow::Result<Option<Self>> {
+        match config.get(key) {
+            None => Ok(None),
+            Some(value) => {
+                let source = value
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("'{}' must be a string", key))?;
+                let template = Self::parse_with_names(source, allowed)
+                    .map_err(|e| anyhow::anyhow!("Invalid '{}' template: {}", key, e))?;
+                Ok(Some(template))
+            }
+        }
+    }
+
+    /// Check that every placeholder is one of `allowed`
+    pub fn validate_names(&self, allowed: &[&str]) -> Result<(), TemplateError> {
+        for name in self.placeholders() {
+            if !allowed.contains(&name) {
+                return Err(TemplateError::UnknownPlaceholder {
+                    name: name.to_string(),
+                    allowed: allowed.join(", "),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Names of all placeholders in the template, in order
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Placeholder { name, .. } => Some(name.as_str()),
+            Part::Literal(_) => None,
+        })
+    }
+
+    /// The original template string
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Render the template; unknown placeholders are left as written
+    pub fn render(&self, vars: &TemplateVars) -> String {
+        let mut out = String::with_capacity(self.source.len() + 16);
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Placeholder { name, spec, raw } => match vars.get(name) {
+                    Some(value) => out.push_str(&spec.apply(value)),
+                    None => out.push_str(raw),
+                },
+            }
+        }
+        out
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl std::str::FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(source: &str, vars: &TemplateVars) -> String {
+        Template::parse(source).unwrap().render(vars)
+    }
+
+    #[test]
+    fn test_plain_substitution() {
+        let vars = TemplateVars::new().with("a", "x").with("b", 3u32);
+        assert_eq!(render("{a}-{b}", &vars), "x-3");
+        assert_eq!(render("no placeholders", &vars), "no placeholders");
+    }
+
+    #[test]
+    fn test_precision() {
+        let vars = TemplateVars::new().with("t", 21.456).with("s", "abcdef");
+        assert_eq!(render("{t:.1}", &vars), "21.5");
+        assert_eq!(render("{t:.0}", &vars), "21");
+        assert_eq!(render("{s:.3}", &vars), "abc");
+    }
+
+    #[test]
+    fn test_width_and_alignment() {
+        let vars = TemplateVars::new().with("n", 42u32).with("s", "ab");
+        assert_eq!(render("[{n:5}]", &vars), "[   42]");
+        assert_eq!(render("[{n:<5}]", &vars), "[42   ]");
+        assert_eq!(render("[{s:5}]", &vars), "[ab   ]");
+        assert_eq!(render("[{s:>5}]", &vars), "[   ab]");
+        assert_eq!(render("[{s:^6}]", &vars), "[  ab  ]");
+        assert_eq!(render("[{s:*^6}]", &vars), "[**ab**]");
+    }
+
+    #[test]
+    fn test_zero_padding_and_sign() {
+        let vars = TemplateVars::new()
+            .with("h", 7u32)
+            .with("up", 1.5)
+            .with("down", -2.26);
+        assert_eq!(render("{h:02}", &vars), "07");
+        assert_eq!(render("{up:+.2}", &vars), "+1.50");
+        assert_eq!(render("{down:+.1}", &vars), "-2.3");
+        assert_eq!(render("{down:06.2}", &vars), "-02.26");
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        let vars = TemplateVars::new().with("v", "x");
+        assert_eq!(render("{{{v}}}", &vars), "{x}");
+    }
+
+    #[test]
+    fn test_missing_variable_left_verbatim() {
+        let vars = TemplateVars::new();
+        assert_eq!(render("a {missing:.2} b", &vars), "a {missing:.2} b");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            Template::parse("{open"),
+            Err(TemplateError::UnclosedPlaceholder(0))
+        ));
+        assert!(matches!(
+            Template::parse("a } b"),
+            Err(TemplateError::UnmatchedBrace(2))
+        ));
+        assert!(matches!(
+            Template::parse("{}"),
+            Err(TemplateError::EmptyPlaceholder(0))
+        ));
+        assert!(matches!(
+            Template::parse("{x:abc}"),
+            Err(TemplateError::InvalidSpec { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_names() {
+        let template = Template::parse("{temp} {unit}").unwrap();
+        assert!(template.validate_names(&["temp", "unit"]).is_ok());
+        assert!(template.validate_names(&["temp"]).is_err());
+        assert_eq!(
+            template.placeholders().collect::<Vec<_>>(),
+            vec!["temp", "unit"]
+        );
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut config = toml::Table::new();
+        assert!(Template::from_config(&config, "format", &["a"])
+            .unwrap()
+            .is_none());
+
+        config.insert("format".to_string(), toml::Value::String("{a}".to_string()));
+        assert!(Template::from_config(&config, "format", &["a"])
+            .unwrap()
+            .is_some());
+        assert!(Template::from_config(&config, "format", &["b"]).is_err());
+
+        config.insert("format".to_string(), toml::Value::Integer(1));
+        assert!(Template::from_config(&config, "format", &["a"]).is_err());
+    }
+}
@@ -1,12 +1,13 @@
 // Font loading and management
 
 use fontdue::{Font, FontSettings};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, warn};
 
 /// Font weight for text rendering
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum FontWeight {
     /// Regular/normal weight
     #[default]
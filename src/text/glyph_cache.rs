@@ -5,13 +5,26 @@ use crate::metrics::CacheMetrics;
 use fontdue::Font;
 use std::collections::HashMap;
 
-const MAX_CACHE_SIZE: usize = 512;
+/// Number of glyphs kept when no capacity is configured
+pub const DEFAULT_CACHE_CAPACITY: usize = 512;
 
+/// Glyph bitmap cache with least-recently-used eviction
+///
+/// When full, the oldest eighth of the entries is dropped in one go so the
+/// eviction scan doesn't run on every miss.
 pub struct GlyphCache {
-    cache: HashMap<GlyphKey, RasterizedGlyph>,
+    cache: HashMap<GlyphKey, CachedGlyph>,
+    capacity: usize,
+    /// Access counter used as the LRU clock
+    tick: u64,
     metrics: CacheMetrics,
 }
 
+struct CachedGlyph {
+    glyph: RasterizedGlyph,
+    last_used: u64,
+}
+
 #[derive(Hash, Eq, PartialEq, Clone)]
 struct GlyphKey {
     character: char,
@@ -32,12 +45,33 @@ pub struct RasterizedGlyph {
 
 impl GlyphCache {
     pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CACHE_CAPACITY)
+    }
+
+    /// Create a cache holding at most `capacity` glyphs
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
         Self {
-            cache: HashMap::with_capacity(MAX_CACHE_SIZE),
+            cache: HashMap::with_capacity(capacity),
+            capacity,
+            tick: 0,
             metrics: CacheMetrics::new(),
         }
     }
 
+    /// Maximum number of cached glyphs
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the capacity, evicting least recently used glyphs if it shrank
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        if self.cache.len() > self.capacity {
+            self.evict(self.cache.len() - self.capacity);
+        }
+    }
+
     pub fn get_or_rasterize(
         &mut self,
         font: &Font,
@@ -51,42 +85,63 @@ impl GlyphCache {
             weight,
         };
 
+        self.tick += 1;
+
         // Check if we have a cache hit
-        if self.cache.contains_key(&key) {
+        if let Some(entry) = self.cache.get_mut(&key) {
             self.metrics.record_hit();
+            entry.last_used = self.tick;
         } else {
             self.metrics.record_miss();
+            if self.cache.len() >= self.capacity {
+                self.evict((self.capacity / 8).max(1));
+            }
+
             // Insert new glyph with full positioning metrics
             let (metrics, bitmap) = font.rasterize(c, size);
             self.cache.insert(
                 key.clone(),
-                RasterizedGlyph {
-                    bitmap,
-                    width: metrics.width,
-                    height: metrics.height,
-                    advance_width: metrics.advance_width,
-                    xmin: metrics.xmin,
-                    ymin: metrics.ymin,
+                CachedGlyph {
+                    glyph: RasterizedGlyph {
+                        bitmap,
+                        width: metrics.width,
+                        height: metrics.height,
+                        advance_width: metrics.advance_width,
+                        xmin: metrics.xmin,
+                        ymin: metrics.ymin,
+                    },
+                    last_used: self.tick,
                 },
             );
         }
 
-        self.cache
+        &self
+            .cache
             .get(&key)
             .expect("Glyph must exist after insertion")
+            .glyph
     }
 
-    pub fn clear_if_full(&mut self) {
-        if self.cache.len() >= MAX_CACHE_SIZE {
-            let eviction_count = self.cache.len() as u64;
-            self.cache.clear();
-            self.metrics.record_eviction(eviction_count);
-            tracing::debug!(
-                evicted = eviction_count,
-                hit_rate_pct = %self.metrics.hit_rate(),
-                "Cleared glyph cache"
-            );
+    /// Drop the `count` least recently used glyphs
+    fn evict(&mut self, count: usize) {
+        let count = count.min(self.cache.len());
+        if count == 0 {
+            return;
         }
+
+        // Ticks are unique per access, so everything at or below the
+        // count-th oldest tick is exactly `count` entries
+        let mut ticks: Vec<u64> = self.cache.values().map(|e| e.last_used).collect();
+        let (_, &mut cutoff, _) = ticks.select_nth_unstable(count - 1);
+        self.cache.retain(|_, entry| entry.last_used > cutoff);
+
+        self.metrics.record_eviction(count as u64);
+        tracing::debug!(
+            evicted = count,
+            capacity = self.capacity,
+            hit_rate_pct = %self.metrics.hit_rate(),
+            "Evicted least recently used glyphs"
+        );
     }
 
     /// Get current cache metrics
@@ -168,30 +223,46 @@ mod tests {
     }
 
     #[test]
-    fn test_cache_clear() {
+    fn test_lru_eviction() {
+        let font = get_test_font();
+        let mut cache = GlyphCache::with_capacity(4);
+
+        for c in ['A', 'B', 'C', 'D'] {
+            cache.get_or_rasterize(&font, c, 16.0, FontWeight::Regular);
+        }
+        // Touch 'A' so 'B' becomes the oldest
+        cache.get_or_rasterize(&font, 'A', 16.0, FontWeight::Regular);
+        assert_eq!(cache.metrics().evictions(), 0);
+
+        cache.get_or_rasterize(&font, 'E', 16.0, FontWeight::Regular);
+        assert_eq!(cache.len(), 4);
+        assert_eq!(cache.metrics().evictions(), 1);
+
+        // 'A' survived, 'B' has to be rasterized again
+        let misses = cache.metrics().misses();
+        cache.get_or_rasterize(&font, 'A', 16.0, FontWeight::Regular);
+        assert_eq!(cache.metrics().misses(), misses);
+        cache.get_or_rasterize(&font, 'B', 16.0, FontWeight::Regular);
+        assert_eq!(cache.metrics().misses(), misses + 1);
+    }
+
+    #[test]
+    fn test_set_capacity_shrinks() {
+        let font = get_test_font();
         let mut cache = GlyphCache::new();
-        assert_eq!(cache.len(), 0);
-
-        // Fill cache beyond limit
-        cache.cache.insert(
-            GlyphKey {
-                character: 'A',
-                size_px: 160,
-                weight: FontWeight::Regular,
-            },
-            RasterizedGlyph {
-                bitmap: vec![],
-                width: 10,
-                height: 10,
-                advance_width: 10.0,
-                xmin: 0,
-                ymin: 0,
-            },
-        );
+        assert_eq!(cache.capacity(), DEFAULT_CACHE_CAPACITY);
 
-        cache.clear_if_full();
-        // Should only clear if at MAX_CACHE_SIZE
-        assert_eq!(cache.len(), 1);
+        for c in "0123456789".chars() {
+            cache.get_or_rasterize(&font, c, 16.0, FontWeight::Regular);
+        }
+        cache.set_capacity(3);
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.metrics().evictions(), 7);
+
+        // The most recent glyphs are the ones kept
+        let misses = cache.metrics().misses();
+        cache.get_or_rasterize(&font, '9', 16.0, FontWeight::Regular);
+        assert_eq!(cache.metrics().misses(), misses);
     }
 
     #[test]
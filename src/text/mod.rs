@@ -5,5 +5,5 @@ mod glyph_cache;
 mod renderer;
 
 pub use font::{FontManager, FontWeight};
-pub use glyph_cache::GlyphCache;
-pub use renderer::TextRenderer;
+pub use glyph_cache::{GlyphCache, DEFAULT_CACHE_CAPACITY};
+pub use renderer::{TextRenderer, TextScale};
@@ -6,12 +6,43 @@
 // - Efficient alpha blending
 
 use super::{FontManager, FontWeight, GlyphCache};
+use crate::metrics::CacheMetrics;
 use tiny_skia::PixmapMut;
 use tracing::trace;
 
+/// Scaling applied to every text size the renderer is asked for
+///
+/// Widgets and layouts pick sizes as usual; the renderer multiplies them by
+/// `scale` and never goes below `min_size`, so text stays readable however
+/// small a widget would draw it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextScale {
+    /// Factor for all text sizes (1.0 = as designed)
+    pub scale: f32,
+    /// Smallest size text is drawn at, in pixels (0.0 = no minimum)
+    pub min_size: f32,
+}
+
+impl TextScale {
+    /// Size to draw text requested at `size`
+    pub fn apply(&self, size: f32) -> f32 {
+        (size * self.scale).max(self.min_size)
+    }
+}
+
+impl Default for TextScale {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            min_size: 0.0,
+        }
+    }
+}
+
 pub struct TextRenderer {
     font_manager: FontManager,
     glyph_cache: GlyphCache,
+    scale: TextScale,
 }
 
 impl TextRenderer {
@@ -19,9 +50,20 @@ impl TextRenderer {
         Self {
             font_manager: FontManager::new(),
             glyph_cache: GlyphCache::new(),
+            scale: TextScale::default(),
         }
     }
 
+    /// Scale all text sizes from now on
+    pub fn set_scale(&mut self, scale: TextScale) {
+        self.scale = scale;
+    }
+
+    /// Size text requested at `size` is actually drawn at
+    pub fn scaled_size(&self, size: f32) -> f32 {
+        self.scale.apply(size)
+    }
+
     /// Render text with default (regular) weight
     pub fn render_text(
         &mut self,
@@ -50,13 +92,12 @@ impl TextRenderer {
         color: [u8; 4], // RGBA
         weight: FontWeight,
     ) {
+        let size = self.scale.apply(size);
         trace!(
             "Rendering text: '{}' at ({}, {}) size {} weight {:?}",
             text, x, y, size, weight
         );
 
-        self.glyph_cache.clear_if_full();
-
         let mut cursor_x = x;
         let font = self.font_manager.font(weight);
 
@@ -171,6 +212,7 @@ impl TextRenderer {
 
     /// Calculate text width with specified font weight
     pub fn measure_text_weighted(&mut self, text: &str, size: f32, weight: FontWeight) -> f32 {
+        let size = self.scale.apply(size);
         let font = self.font_manager.font(weight);
         let mut width = 0.0;
 
@@ -182,8 +224,28 @@ impl TextRenderer {
         width
     }
 
+    /// Rasterize `glyphs` ahead of time so their first frame doesn't pay for it
+    pub fn prewarm(&mut self, glyphs: &str, size: f32, weight: FontWeight) {
+        let size = self.scale.apply(size);
+        let font = self.font_manager.font(weight);
+        for c in glyphs.chars() {
+            self.glyph_cache.get_or_rasterize(font, c, size, weight);
+        }
+    }
+
+    /// Limit how many glyphs the cache holds
+    pub fn set_glyph_cache_capacity(&mut self, capacity: usize) {
+        self.glyph_cache.set_capacity(capacity);
+    }
+
+    /// Glyph cache hit, miss and eviction counts
+    pub fn glyph_cache_metrics(&self) -> &CacheMetrics {
+        self.glyph_cache.metrics()
+    }
+
     /// Get the font ascent for a given size (distance from baseline to top of tallest glyph)
     pub fn ascent(&self, size: f32) -> f32 {
+        let size = self.scale.apply(size);
         let font = self.font_manager.font(FontWeight::Regular);
         if let Some(metrics) = font.horizontal_line_metrics(size) {
             metrics.ascent
@@ -194,6 +256,7 @@ impl TextRenderer {
 
     /// Get the font descent for a given size (distance from baseline to bottom of lowest glyph, typically negative)
     pub fn descent(&self, size: f32) -> f32 {
+        let size = self.scale.apply(size);
         let font = self.font_manager.font(FontWeight::Regular);
         if let Some(metrics) = font.horizontal_line_metrics(size) {
             metrics.descent
@@ -262,4 +325,20 @@ mod tests {
             [255, 255, 255, 255],
         );
     }
+
+    #[test]
+    fn test_text_scale() {
+        let mut renderer = TextRenderer::new();
+        let normal = renderer.measure_text("Hello", 16.0);
+
+        renderer.set_scale(TextScale {
+            scale: 1.5,
+            min_size: 20.0,
+        });
+        assert_eq!(renderer.scaled_size(16.0), 24.0);
+        assert_eq!(renderer.scaled_size(8.0), 20.0);
+        assert!(renderer.measure_text("Hello", 16.0) > normal);
+        // Both below the minimum once scaled
+        assert_eq!(renderer.ascent(8.0), renderer.ascent(12.0));
+    }
 }
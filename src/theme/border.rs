@@ -0,0 +1,343 @@
+//! Border styles beyond a single solid color
+//!
+//! A theme's `border_style` selects how the border stroke is painted. The
+//! solid style uses [`Theme::border`](super::Theme::border); the others build
+//! a tiny-skia shader sized to the widget so the stroke can carry a gradient,
+//! repeating stripes or a tiled image.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tiny_skia::{
+    FilterQuality, GradientStop, LinearGradient, Pattern, Pixmap, Point, Shader, SpreadMode,
+    Transform,
+};
+
+use super::{Color, Theme, ThemeToken};
+
+/// How a widget border is painted
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BorderStyle {
+    /// Single color taken from the theme's `border`
+    #[default]
+    Solid,
+
+    /// Linear gradient across the widget
+    Gradient {
+        /// Hex colors or `"@token"` references, evenly spaced along the gradient
+        colors: Vec<String>,
+        /// Direction in degrees: 0 runs left to right, 90 top to bottom
+        #[serde(default)]
+        angle: f32,
+    },
+
+    /// Repeating hard-edged stripes, cycling through the colors
+    Stripes {
+        /// Hex colors or `"@token"` references, one per stripe
+        colors: Vec<String>,
+        /// Width of each stripe in pixels
+        #[serde(default = "default_stripe_width")]
+        stripe_width: f32,
+        /// Stripe direction in degrees
+        #[serde(default = "default_stripe_angle")]
+        angle: f32,
+    },
+
+    /// PNG image tiled along the border
+    Image {
+        /// Path to the image; `~/` is expanded
+        path: String,
+    },
+}
+
+fn default_stripe_width() -> f32 {
+    8.0
+}
+
+fn default_stripe_angle() -> f32 {
+    45.0
+}
+
+impl BorderStyle {
+    /// Whether this is the plain solid style
+    pub fn is_solid(&self) -> bool {
+        matches!(self, Self::Solid)
+    }
+
+    /// Load the tile image for [`BorderStyle::Image`]
+    ///
+    /// Returns None for other styles or if the image can't be read.
+    pub fn load_image(&self) -> Option<Pixmap> {
+        let Self::Image { path } = self else {
+            return None;
+        };
+
+        let path = match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()?.join(rest),
+            None => PathBuf::from(path),
+        };
+        match Pixmap::load_png(&path) {
+            Ok(pixmap) => Some(pixmap),
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Failed to load border image");
+                None
+            }
+        }
+    }
+
+    /// Replace `"@token"` colors with the theme's actual colors
+    pub fn resolve_tokens(&self, theme: &Theme) -> Self {
+        let resolve = |colors: &[String]| -> Vec<String> {
+            colors
+                .iter()
+                .map(|value| match ThemeToken::parse(value) {
+                    Some(token) => theme.token(token).to_hex(),
+                    None => value.clone(),
+                })
+                .collect()
+        };
+
+        match self {
+            Self::Gradient { colors, angle } => Self::Gradient {
+                colors: resolve(colors),
+                angle: *angle,
+            },
+            Self::Stripes {
+                colors,
+                stripe_width,
+                angle,
+            } => Self::Stripes {
+                colors: resolve(colors),
+                stripe_width: *stripe_width,
+                angle: *angle,
+            },
+            other => other.clone(),
+        }
+    }
+
+    /// Build the shader for a border around a `width` x `height` widget
+    ///
+    /// `solid` is used for [`BorderStyle::Solid`] and as the fallback when a
+    /// gradient has no valid colors or the image is missing. `opacity`
+    /// scales the alpha of every color.
+    pub fn shader<'a>(
+        &self,
+        solid: Color,
+        width: f32,
+        height: f32,
+        opacity: f32,
+        image: Option<&'a Pixmap>,
+    ) -> Shader<'a> {
+        let fallback = Shader::SolidColor(with_opacity(solid, opacity).to_tiny_skia());
+
+        match self {
+            Self::Solid => fallback,
+            Self::Gradient { colors, angle } => {
+                let colors = parse_colors(colors, opacity);
+                if colors.len() < 2 {
+                    return colors.first().map_or(fallback, |c| Shader::SolidColor(*c));
+                }
+
+                let last = (colors.len() - 1) as f32;
+                let stops = colors
+                    .iter()
+                    .enumerate()
+                    .map(|(i, color)| GradientStop::new(i as f32 / last, *color))
+                    .collect();
+                let (start, end) = gradient_line(width, height, *angle);
+                LinearGradient::new(start, end, stops, SpreadMode::Pad, Transform::identity())
+                    .unwrap_or(fallback)
+            }
+            Self::Stripes {
+                colors,
+                stripe_width,
+                angle,
+            } => {
+                let colors = parse_colors(colors, opacity);
+                if colors.len() < 2 {
+                    return colors.first().map_or(fallback, |c| Shader::SolidColor(*c));
+                }
+
+                // Two stops per stripe at the same boundary give hard edges;
+                // repeating the gradient turns one period into a pattern.
+                let count = colors.len() as f32;
+                let mut stops = Vec::with_capacity(colors.len() * 2);
+                for (i, color) in colors.iter().enumerate() {
+                    stops.push(GradientStop::new(i as f32 / count, *color));
+                    stops.push(GradientStop::new((i + 1) as f32 / count, *color));
+                }
+
+                let period = stripe_width.max(1.0) * count;
+                let (sin, cos) = angle.to_radians().sin_cos();
+                LinearGradient::new(
+                    Point::from_xy(0.0, 0.0),
+                    Point::from_xy(cos * period, sin * period),
+                    stops,
+                    SpreadMode::Repeat,
+                    Transform::identity(),
+                )
+                .unwrap_or(fallback)
+            }
+            Self::Image { .. } => match image {
+                Some(image) => Pattern::new(
+                    image.as_ref(),
+                    SpreadMode::Repeat,
+                    FilterQuality::Bilinear,
+                    opacity.clamp(0.0, 1.0),
+                    Transform::identity(),
+                ),
+                None => fallback,
+            },
+        }
+    }
+}
+
+fn with_opacity(mut color: Color, opacity: f32) -> Color {
+    color.a = (color.a as f32 * opacity) as u8;
+    color
+}
+
+fn parse_colors(colors: &[String], opacity: f32) -> Vec<tiny_skia::Color> {
+    colors
+        .iter()
+        .filter_map(|hex| {
+            let color = Color::from_hex(hex);
+            if color.is_none() {
+                tracing::warn!(color = %hex, "Invalid border color, skipping");
+            }
+            color
+        })
+        .map(|color| with_opacity(color, opacity).to_tiny_skia())
+        .collect()
+}
+
+/// Endpoints of a gradient through the widget's center at `angle` degrees
+///
+/// The line is long enough that the first and last colors land exactly on
+/// the widget's outermost corners.
+fn gradient_line(width: f32, height: f32, angle: f32) -> (Point, Point) {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let half = (width / 2.0 * cos).abs() + (height / 2.0 * sin).abs();
+    let (cx, cy) = (width / 2.0, height / 2.0);
+    (
+        Point::from_xy(cx - cos * half, cy - sin * half),
+        Point::from_xy(cx + cos * half, cy + sin * half),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tiny_skia::{Paint, PathBuilder, Rect, Stroke};
+
+    #[test]
+    fn test_border_style_from_toml() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            border_style: BorderStyle,
+        }
+
+        let parsed: Wrapper = toml::from_str(
+            r##"
+            [border_style]
+            type = "gradient"
+            colors = ["#ff0000", "#00ff00", "#0000ff"]
+            angle = 90
+            "##,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.border_style,
+            BorderStyle::Gradient {
+                colors: vec!["#ff0000".into(), "#00ff00".into(), "#0000ff".into()],
+                angle: 90.0,
+            }
+        );
+
+        let parsed: Wrapper = toml::from_str(
+            "[border_style]\ntype = \"stripes\"\ncolors = [\"#000000\", \"#ffcc00\"]\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            parsed.border_style,
+            BorderStyle::Stripes { stripe_width, angle, .. } if stripe_width == 8.0 && angle == 45.0
+        ));
+    }
+
+    #[test]
+    fn test_resolve_tokens() {
+        let theme = Theme::cosmic_dark();
+        let style = BorderStyle::Stripes {
+            colors: vec!["@accent".into(), "#000000".into()],
+            stripe_width: 4.0,
+            angle: 0.0,
+        };
+        let BorderStyle::Stripes { colors, .. } = style.resolve_tokens(&theme) else {
+            panic!("resolve_tokens changed the style");
+        };
+        assert_eq!(colors, vec![theme.accent.to_hex(), "#000000".to_string()]);
+    }
+
+    #[test]
+    fn test_gradient_line_covers_widget() {
+        let (start, end) = gradient_line(200.0, 100.0, 0.0);
+        assert_eq!((start.x, start.y), (0.0, 50.0));
+        assert_eq!((end.x, end.y), (200.0, 50.0));
+
+        let (start, end) = gradient_line(200.0, 100.0, 90.0);
+        assert!((start.x - 100.0).abs() < 1e-3 && start.y.abs() < 1e-3);
+        assert!((end.x - 100.0).abs() < 1e-3 && (end.y - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_shader_fallbacks() {
+        let solid = Color::new(10, 20, 30, 200);
+        let shader = BorderStyle::Solid.shader(solid, 100.0, 50.0, 0.5, None);
+        assert!(
+            matches!(shader, Shader::SolidColor(c) if c == Color::new(10, 20, 30, 100).to_tiny_skia())
+        );
+
+        let invalid = BorderStyle::Gradient {
+            colors: vec!["nope".into(), "#00ff00".into()],
+            angle: 0.0,
+        };
+        assert!(matches!(
+            invalid.shader(solid, 100.0, 50.0, 1.0, None),
+            Shader::SolidColor(c) if c == Color::new(0, 255, 0, 255).to_tiny_skia()
+        ));
+
+        let image = BorderStyle::Image {
+            path: "/nonexistent/border.png".into(),
+        };
+        assert!(image.load_image().is_none());
+        assert!(matches!(
+            image.shader(solid, 100.0, 50.0, 1.0, None),
+            Shader::SolidColor(_)
+        ));
+    }
+
+    #[test]
+    fn test_gradient_stroke_changes_color_along_border() {
+        let style = BorderStyle::Gradient {
+            colors: vec!["#ff0000".into(), "#0000ff".into()],
+            angle: 0.0,
+        };
+        let paint = Paint {
+            shader: style.shader(Color::new(0, 0, 0, 255), 100.0, 20.0, 1.0, None),
+            ..Default::default()
+        };
+        let path = PathBuilder::from_rect(Rect::from_xywh(0.0, 0.0, 100.0, 20.0).unwrap());
+        let stroke = Stroke {
+            width: 4.0,
+            ..Default::default()
+        };
+
+        let mut pixmap = Pixmap::new(100, 20).unwrap();
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+
+        let left = pixmap.pixel(0, 10).unwrap();
+        let right = pixmap.pixel(99, 10).unwrap();
+        assert!(left.red() > left.blue());
+        assert!(right.blue() > right.red());
+    }
+}
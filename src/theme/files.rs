@@ -0,0 +1,178 @@
+//! Standalone theme files
+//!
+//! Complete themes can live in `~/.config/cosmic-desktop-widget/themes/` as
+//! `<name>.toml`, using the same fields as `[custom_theme]`. Setting
+//! `theme = "<name>"` selects one, which makes themes easy to share: export
+//! the active theme to a file, and import someone else's into the directory.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+use super::Theme;
+
+/// Themes built into [`Theme::from_name`]
+pub const BUILTIN_THEMES: &[&str] = &[
+    "cosmic_dark",
+    "light",
+    "transparent_dark",
+    "transparent_light",
+    "glass",
+    "high_contrast",
+];
+
+/// Whether `name` belongs to a built-in or special theme
+///
+/// Theme files with these names would never be selected, so they're skipped.
+pub fn is_reserved_name(name: &str) -> bool {
+    BUILTIN_THEMES.contains(&name) || matches!(name, "custom" | "pywal" | "wal" | "matugen")
+}
+
+impl Theme {
+    /// Directory scanned for theme files
+    pub fn themes_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("cosmic-desktop-widget").join("themes"))
+    }
+
+    /// Load a theme from a TOML file
+    pub fn load_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read theme: {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse theme: {}", path.display()))
+    }
+
+    /// Write this theme to a TOML file
+    pub fn save_file(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).context("Failed to create theme directory")?;
+        }
+
+        let content = toml::to_string_pretty(self).context("Failed to serialize theme")?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write theme: {}", path.display()))
+    }
+
+    /// Names of the themes in the themes directory, sorted
+    pub fn user_theme_names() -> Vec<String> {
+        Self::themes_dir()
+            .map(|dir| theme_names(&dir))
+            .unwrap_or_default()
+    }
+
+    /// Resolve a theme by name: built-in first, then the themes directory
+    ///
+    /// Unknown names fall back to `cosmic_dark`, like [`Theme::from_name`].
+    pub fn find(name: &str) -> Self {
+        if is_reserved_name(name) {
+            return Self::from_name(name);
+        }
+
+        let Some(dir) = Self::themes_dir() else {
+            return Self::from_name(name);
+        };
+        let path = dir.join(format!("{}.toml", name));
+        if !path.exists() {
+            warn!(theme = %name, "Unknown theme, using cosmic_dark");
+            return Self::cosmic_dark();
+        }
+
+        match Self::load_file(&path) {
+            Ok(theme) => theme,
+            Err(e) => {
+                warn!(error = %e, "Failed to load theme file, using cosmic_dark");
+                Self::cosmic_dark()
+            }
+        }
+    }
+
+    /// Copy a theme file into the themes directory
+    ///
+    /// Returns the name the theme can be selected by (the file stem).
+    pub fn import(path: &Path) -> Result<String> {
+        let dir = Self::themes_dir().context("Failed to get config directory")?;
+        import_into(&dir, path)
+    }
+}
+
+fn theme_names(dir: &Path) -> Vec<String> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            debug!(dir = %dir.display(), error = %e, "No themes directory");
+            return Vec::new();
+        }
+    };
+
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("toml"))
+        .filter_map(|path| path.file_stem()?.to_str().map(str::to_string))
+        .filter(|name| !is_reserved_name(name))
+        .collect();
+    names.sort();
+    names
+}
+
+fn import_into(dir: &Path, path: &Path) -> Result<String> {
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| !stem.is_empty())
+        .context("Theme file has no name")?
+        .to_string();
+    if is_reserved_name(&name) {
+        bail!(
+            "'{}' is a built-in theme name; rename the file before importing",
+            name
+        );
+    }
+
+    // Parse before copying so a broken file never lands in the directory
+    let theme = Theme::load_file(path)?;
+    theme.save_file(&dir.join(format!("{}.toml", name)))?;
+
+    tracing::info!(theme = %name, "Imported theme");
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_theme_file_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("glass.toml");
+
+        let theme = Theme::glass();
+        theme.save_file(&path).unwrap();
+        let loaded = Theme::load_file(&path).unwrap();
+        assert_eq!(loaded.background.to_array(), theme.background.to_array());
+        assert_eq!(loaded.corner_radius, theme.corner_radius);
+    }
+
+    #[test]
+    fn test_import_into_themes_dir() {
+        let source = TempDir::new().unwrap();
+        let themes = TempDir::new().unwrap();
+
+        let path = source.path().join("nord.toml");
+        Theme::light().save_file(&path).unwrap();
+        assert_eq!(import_into(themes.path(), &path).unwrap(), "nord");
+        assert_eq!(theme_names(themes.path()), vec!["nord"]);
+
+        // Built-in names can't be shadowed
+        let reserved = source.path().join("light.toml");
+        Theme::light().save_file(&reserved).unwrap();
+        assert!(import_into(themes.path(), &reserved).is_err());
+
+        // Invalid files are rejected without being copied
+        let broken = source.path().join("broken.toml");
+        std::fs::write(&broken, "background = 3").unwrap();
+        assert!(import_into(themes.path(), &broken).is_err());
+        assert_eq!(theme_names(themes.path()), vec!["nord"]);
+    }
+}
@@ -8,8 +8,19 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::ThemeColors;
+
+pub mod border;
+pub mod files;
+pub mod palette;
+pub mod schedule;
+
+pub use border::BorderStyle;
+pub use palette::{PaletteSource, PaletteWatcher};
+pub use schedule::ThemeSchedule;
+
 /// RGBA color representation
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -38,10 +49,61 @@ impl Color {
     pub fn to_tiny_skia(self) -> tiny_skia::Color {
         tiny_skia::Color::from_rgba8(self.r, self.g, self.b, self.a)
     }
+
+    /// Parse "#rrggbb" or "#rrggbbaa" (the leading '#' is optional)
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim().trim_start_matches('#');
+        if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+        Some(Self::new(channel(0)?, channel(2)?, channel(4)?, alpha))
+    }
+
+    /// Format as "#rrggbbaa"
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
+
+    /// Approximate relative luminance (0.0 = black, 1.0 = white)
+    pub fn luminance(self) -> f32 {
+        (0.2126 * self.r as f32 + 0.7152 * self.g as f32 + 0.0722 * self.b as f32) / 255.0
+    }
+}
+
+/// Named theme color that config values can reference as `"@name"`
+///
+/// Lets a color setting follow the active theme, e.g. `color = "@accent"`
+/// stays in sync when switching from `cosmic_dark` to a pywal palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeToken {
+    Background,
+    Border,
+    TextPrimary,
+    TextSecondary,
+    Accent,
+    TextShadow,
+}
+
+impl ThemeToken {
+    /// Parse a reference like "@accent"; None if `value` isn't a token
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().strip_prefix('@')? {
+            "background" => Some(Self::Background),
+            "border" => Some(Self::Border),
+            "text_primary" => Some(Self::TextPrimary),
+            "text_secondary" => Some(Self::TextSecondary),
+            "accent" => Some(Self::Accent),
+            "text_shadow" => Some(Self::TextShadow),
+            _ => None,
+        }
+    }
 }
 
 /// Widget theme configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Theme {
     /// Background color
     pub background: Color,
@@ -49,6 +111,10 @@ pub struct Theme {
     /// Border color
     pub border: Color,
 
+    /// How the border is painted (solid, gradient, stripes or image)
+    #[serde(default, skip_serializing_if = "BorderStyle::is_solid")]
+    pub border_style: BorderStyle,
+
     /// Primary text color (for data values - WCAG AAA: 7:1 contrast)
     pub text_primary: Color,
 
@@ -61,7 +127,7 @@ pub struct Theme {
     /// Background transparency (0.0 = transparent, 1.0 = opaque)
     pub opacity: f32,
 
-    /// Enable compositor blur hint (for Wayland compositors that support it)
+    /// Ask the compositor to blur what's behind the widget (if supported)
     #[serde(default)]
     pub blur_enabled: bool,
 
@@ -113,6 +179,7 @@ impl Theme {
             corner_radius: 12.0,
             text_shadow: Color::new(0, 0, 0, 128),
             text_shadow_enabled: true,
+            border_style: BorderStyle::Solid,
         }
     }
 
@@ -130,6 +197,7 @@ impl Theme {
             corner_radius: 12.0,
             text_shadow: Color::new(255, 255, 255, 100),
             text_shadow_enabled: false,
+            border_style: BorderStyle::Solid,
         }
     }
 
@@ -148,6 +216,7 @@ impl Theme {
             corner_radius: 12.0,
             text_shadow: Color::new(0, 0, 0, 153),
             text_shadow_enabled: true,
+            border_style: BorderStyle::Solid,
         }
     }
 
@@ -165,14 +234,15 @@ impl Theme {
             corner_radius: 12.0,
             text_shadow: Color::new(255, 255, 255, 100),
             text_shadow_enabled: false,
+            border_style: BorderStyle::Solid,
         }
     }
 
-    /// Glass theme (frosted glass with blur hint)
+    /// Glass theme (frosted glass with compositor blur)
     ///
     /// Uses lower background opacity because compositor blur
-    /// provides additional contrast. Falls back gracefully if
-    /// blur is not supported.
+    /// provides additional contrast. Without blur support the
+    /// background is simply translucent.
     pub fn glass() -> Self {
         Self {
             // Lower opacity because blur helps with contrast
@@ -187,6 +257,7 @@ impl Theme {
             corner_radius: 16.0,
             text_shadow: Color::new(0, 0, 0, 153),
             text_shadow_enabled: true,
+            border_style: BorderStyle::Solid,
         }
     }
 
@@ -200,6 +271,140 @@ impl Theme {
         )
     }
 
+    /// Build a theme from hex colors, e.g. an imported wallpaper palette
+    ///
+    /// Style (radius, shadows, border width) comes from `cosmic_dark` or
+    /// `light` depending on how dark the background is. Colors given without
+    /// an alpha channel keep that base theme's transparency, so a palette's
+    /// solid background still ends up translucent. Unparsable colors fall back
+    /// to the base theme.
+    pub fn from_colors(colors: &ThemeColors) -> Self {
+        let is_light = Color::from_hex(&colors.background)
+            .map(|bg| bg.luminance() > 0.5)
+            .unwrap_or(false);
+        let base = if is_light {
+            Self::light()
+        } else {
+            Self::cosmic_dark()
+        };
+
+        let pick = |hex: &str, fallback: Color| match Color::from_hex(hex) {
+            // Token references are resolved once the literal colors are known
+            None if ThemeToken::parse(hex).is_some() => fallback,
+            Some(color) if hex.trim().trim_start_matches('#').len() == 6 => {
+                color.with_alpha(fallback.a)
+            }
+            Some(color) => color,
+            None => {
+                tracing::warn!(color = %hex, "Invalid theme color, using default");
+                fallback
+            }
+        };
+
+        let mut theme = Self {
+            background: pick(&colors.background, base.background),
+            border: pick(&colors.border, base.border),
+            text_primary: pick(&colors.text_primary, base.text_primary),
+            text_secondary: pick(&colors.text_secondary, base.text_secondary),
+            accent: pick(&colors.accent, base.accent),
+            ..base
+        };
+
+        // `border = "@accent"` and friends refer to the colors picked above
+        let resolved = theme.clone();
+        let token = |value: &str| ThemeToken::parse(value).map(|t| resolved.token(t));
+        if let Some(color) = token(&colors.background) {
+            theme.background = color;
+        }
+        if let Some(color) = token(&colors.border) {
+            theme.border = color;
+        }
+        if let Some(color) = token(&colors.text_primary) {
+            theme.text_primary = color;
+        }
+        if let Some(color) = token(&colors.text_secondary) {
+            theme.text_secondary = color;
+        }
+        if let Some(color) = token(&colors.accent) {
+            theme.accent = color;
+        }
+
+        theme
+    }
+
+    /// Color a token refers to in this theme
+    pub fn token(&self, token: ThemeToken) -> Color {
+        match token {
+            ThemeToken::Background => self.background,
+            ThemeToken::Border => self.border,
+            ThemeToken::TextPrimary => self.text_primary,
+            ThemeToken::TextSecondary => self.text_secondary,
+            ThemeToken::Accent => self.accent,
+            ThemeToken::TextShadow => self.text_shadow,
+        }
+    }
+
+    /// Resolve a color setting: a hex color or a `"@token"` reference
+    pub fn resolve_color(&self, value: &str) -> Option<Color> {
+        match ThemeToken::parse(value) {
+            Some(token) => Some(self.token(token)),
+            None => Color::from_hex(value),
+        }
+    }
+
+    /// Variant for frameless "floating text" widgets
+    ///
+    /// No background, border or blur, and an always-on, near-opaque text
+    /// shadow so text stays readable directly on the wallpaper.
+    pub fn frameless(&self) -> Self {
+        let mut theme = self.clone();
+        theme.background.a = 0;
+        theme.opacity = 0.0;
+        theme.border_width = 0.0;
+        theme.blur_enabled = false;
+        theme.text_shadow_enabled = true;
+        theme.text_shadow.a = theme.text_shadow.a.max(230);
+        theme
+    }
+
+    /// High-contrast variant, for `[accessibility] high_contrast`
+    ///
+    /// Keeps whether the theme is light or dark but makes the background
+    /// opaque black or white, all text the opposite, and the border thick
+    /// and solid. Transparency, blur and gradients all cost contrast, so they
+    /// go; text shadows would only blur the glyph edges.
+    pub fn high_contrast(&self) -> Self {
+        let is_light = self.background.luminance() > 0.5;
+        let (background, text, accent) = if is_light {
+            (
+                Color::rgb(255, 255, 255),
+                Color::rgb(0, 0, 0),
+                Color::rgb(0, 60, 170),
+            )
+        } else {
+            (
+                Color::rgb(0, 0, 0),
+                Color::rgb(255, 255, 255),
+                Color::rgb(255, 214, 0),
+            )
+        };
+
+        Self {
+            background,
+            border: text,
+            border_style: BorderStyle::Solid,
+            text_primary: text,
+            text_secondary: text,
+            accent,
+            opacity: 1.0,
+            blur_enabled: false,
+            border_width: self.border_width.max(2.0),
+            corner_radius: self.corner_radius,
+            text_shadow: self.text_shadow,
+            text_shadow_enabled: false,
+        }
+    }
+
     /// Load theme by name
     pub fn from_name(name: &str) -> Self {
         match name {
@@ -208,6 +413,7 @@ impl Theme {
             "transparent_dark" => Self::transparent_dark(),
             "transparent_light" => Self::transparent_light(),
             "glass" => Self::glass(),
+            "high_contrast" => Self::cosmic_dark().high_contrast(),
             _ => Self::cosmic_dark(),
         }
     }
@@ -272,6 +478,9 @@ mod tests {
         let glass = Theme::from_name("glass");
         assert!(glass.blur_enabled);
 
+        let high_contrast = Theme::from_name("high_contrast");
+        assert_eq!(high_contrast.background.to_array(), [0, 0, 0, 255]);
+
         // Unknown theme defaults to cosmic_dark
         let unknown = Theme::from_name("unknown");
         assert_eq!(unknown.accent.r, 52);
@@ -284,10 +493,110 @@ mod tests {
         assert_eq!(array, [255, 128, 64, 200]);
     }
 
+    #[test]
+    fn test_color_from_hex() {
+        let color = Color::from_hex("#7aa2f7").unwrap();
+        assert_eq!(color.to_array(), [0x7a, 0xa2, 0xf7, 255]);
+
+        let color = Color::from_hex("1a1b26cc").unwrap();
+        assert_eq!(color.to_array(), [0x1a, 0x1b, 0x26, 0xcc]);
+
+        assert!(Color::from_hex("#fff").is_none());
+        assert!(Color::from_hex("#gggggg").is_none());
+    }
+
+    #[test]
+    fn test_resolve_color_tokens() {
+        let theme = Theme::cosmic_dark();
+        assert_eq!(
+            ThemeToken::parse("@text_secondary"),
+            Some(ThemeToken::TextSecondary)
+        );
+        assert_eq!(ThemeToken::parse("accent"), None);
+        assert_eq!(ThemeToken::parse("@unknown"), None);
+
+        let accent = theme.resolve_color("@accent").unwrap();
+        assert_eq!(accent.to_array(), theme.accent.to_array());
+        let hex = theme.resolve_color("#ff000080").unwrap();
+        assert_eq!(hex.to_array(), [255, 0, 0, 128]);
+        assert_eq!(
+            Color::from_hex(&hex.to_hex()).unwrap().to_array(),
+            hex.to_array()
+        );
+        assert!(theme.resolve_color("@nope").is_none());
+    }
+
+    #[test]
+    fn test_theme_colors_reference_tokens() {
+        let theme = Theme::from_colors(&ThemeColors {
+            accent: "#ff8800".to_string(),
+            border: "@accent".to_string(),
+            ..ThemeColors::default()
+        });
+        assert_eq!(theme.border.to_array(), theme.accent.to_array());
+    }
+
+    #[test]
+    fn test_theme_from_colors() {
+        let colors = ThemeColors {
+            background: "#1a1b26".to_string(),
+            text_primary: "#c0caf5".to_string(),
+            text_secondary: "#a9b1d6".to_string(),
+            accent: "#7aa2f7cc".to_string(),
+            border: "not a color".to_string(),
+        };
+        let theme = Theme::from_colors(&colors);
+        let base = Theme::cosmic_dark();
+
+        // Solid palette colors keep the base theme's alpha
+        assert_eq!(theme.background.r, 0x1a);
+        assert_eq!(theme.background.a, base.background.a);
+        // Explicit alpha wins
+        assert_eq!(theme.accent.a, 0xcc);
+        // Invalid colors fall back to the base theme
+        assert_eq!(theme.border.to_array(), base.border.to_array());
+        assert!(theme.text_shadow_enabled);
+
+        let light = Theme::from_colors(&ThemeColors {
+            background: "#fef7ff".to_string(),
+            ..ThemeColors::default()
+        });
+        assert!(!light.text_shadow_enabled);
+    }
+
     #[test]
     fn test_text_shadow_defaults() {
         let theme = Theme::cosmic_dark();
         assert!(theme.text_shadow_enabled);
         assert!(theme.text_shadow.a > 0);
     }
+
+    #[test]
+    fn test_high_contrast_theme() {
+        let dark = Theme::glass().high_contrast();
+        assert_eq!(dark.background_with_opacity().a, 255);
+        assert_eq!(dark.text_primary.to_array(), [255, 255, 255, 255]);
+        assert_eq!(dark.text_secondary.to_array(), dark.text_primary.to_array());
+        assert!(!dark.blur_enabled);
+        assert!(dark.border_width >= 2.0);
+
+        let light = Theme::transparent_light().high_contrast();
+        assert_eq!(light.background.to_array(), [255, 255, 255, 255]);
+        assert_eq!(light.text_primary.to_array(), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_frameless_theme() {
+        let theme = Theme::light().frameless();
+        assert_eq!(theme.background.a, 0);
+        assert_eq!(theme.border_width, 0.0);
+        assert!(!theme.blur_enabled);
+        assert!(theme.text_shadow_enabled);
+        assert!(theme.text_shadow.a >= 230);
+        // Text colors are untouched
+        assert_eq!(
+            theme.text_primary.to_array(),
+            Theme::light().text_primary.to_array()
+        );
+    }
 }
@@ -0,0 +1,329 @@
+//! Wallpaper palette import (pywal / matugen)
+//!
+//! Tools like [pywal](https://github.com/dylanaraps/pywal) and
+//! [matugen](https://github.com/InioX/matugen) generate a color scheme from the
+//! current wallpaper. Setting `theme = "pywal"` or `theme = "matugen"` maps
+//! their JSON output onto [`ThemeColors`], and a [`PaletteWatcher`] lets the
+//! widget recolor as soon as the palette is regenerated.
+
+use anyhow::{Context, Result};
+use notify::{Config as NotifyConfig, RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::config::ThemeColors;
+
+/// Where a wallpaper palette comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteSource {
+    /// `~/.cache/wal/colors.json` written by `wal`
+    Pywal,
+    /// JSON written by `matugen ... --json hex`
+    Matugen,
+}
+
+impl PaletteSource {
+    /// Palette source selected by a theme name, if any
+    pub fn from_theme_name(name: &str) -> Option<Self> {
+        match name {
+            "pywal" | "wal" => Some(Self::Pywal),
+            "matugen" => Some(Self::Matugen),
+            _ => None,
+        }
+    }
+
+    /// Default location of the palette file
+    pub fn default_path(&self) -> Option<PathBuf> {
+        let cache = dirs::cache_dir()?;
+        Some(match self {
+            Self::Pywal => cache.join("wal").join("colors.json"),
+            Self::Matugen => cache.join("matugen").join("colors.json"),
+        })
+    }
+
+    /// Parse palette JSON into theme colors
+    ///
+    /// `mode` selects the matugen scheme ("dark" or "light"); pywal ignores it.
+    pub fn parse(&self, json: &str, mode: &str) -> Result<ThemeColors> {
+        let root: Value = serde_json::from_str(json).context("Palette is not valid JSON")?;
+        match self {
+            Self::Pywal => parse_pywal(&root),
+            Self::Matugen => parse_matugen(&root, mode),
+        }
+    }
+
+    /// Read and parse a palette file
+    pub fn load(&self, path: &Path, mode: &str) -> Result<ThemeColors> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read palette: {}", path.display()))?;
+        self.parse(&json, mode)
+            .with_context(|| format!("Failed to parse palette: {}", path.display()))
+    }
+}
+
+fn str_at<'a>(root: &'a Value, pointer: &str) -> Option<&'a str> {
+    root.pointer(pointer).and_then(Value::as_str)
+}
+
+/// Map pywal's 16-color terminal scheme onto theme roles
+///
+/// `color4` (usually the blue slot) is used as the accent and `color8`
+/// (bright black) as the border.
+fn parse_pywal(root: &Value) -> Result<ThemeColors> {
+    let color = |name: &str| -> Result<String> {
+        str_at(root, &format!("/colors/{}", name))
+            .map(str::to_string)
+            .with_context(|| format!("pywal palette is missing colors.{}", name))
+    };
+
+    let mut background = match str_at(root, "/special/background") {
+        Some(background) => background.to_string(),
+        None => color("color0")?,
+    };
+    let foreground = match str_at(root, "/special/foreground") {
+        Some(foreground) => foreground.to_string(),
+        None => color("color15")?,
+    };
+
+    // `wal -a 80` records the requested background transparency as a percentage
+    let alpha = root.get("alpha").and_then(|v| match v {
+        Value::String(s) => s.parse::<f32>().ok(),
+        Value::Number(n) => n.as_f64().map(|n| n as f32),
+        _ => None,
+    });
+    if let Some(alpha) = alpha {
+        if background.len() == 7 {
+            let alpha = (alpha.clamp(0.0, 100.0) / 100.0 * 255.0).round() as u8;
+            background = format!("{}{:02x}", background, alpha);
+        }
+    }
+
+    Ok(ThemeColors {
+        background,
+        text_primary: foreground,
+        text_secondary: color("color7")?,
+        accent: color("color4")?,
+        border: color("color8")?,
+    })
+}
+
+/// Map a Material You scheme onto theme roles
+///
+/// Accepts both layouts matugen has used: `colors.<mode>.<role>` and
+/// `colors.<role>.<mode>`.
+fn parse_matugen(root: &Value, mode: &str) -> Result<ThemeColors> {
+    let role = |name: &str| -> Result<String> {
+        str_at(root, &format!("/colors/{}/{}", mode, name))
+            .or_else(|| str_at(root, &format!("/colors/{}/{}", name, mode)))
+            .map(str::to_string)
+            .with_context(|| format!("matugen palette is missing {} color '{}'", mode, name))
+    };
+
+    Ok(ThemeColors {
+        background: role("surface")?,
+        text_primary: role("on_surface")?,
+        text_secondary: role("on_surface_variant")?,
+        accent: role("primary")?,
+        border: role("outline_variant").or_else(|_| role("outline"))?,
+    })
+}
+
+// ============================================================================
+// Watcher
+// ============================================================================
+
+/// Palette change event
+#[derive(Debug, Clone)]
+pub struct PaletteChangeEvent {
+    /// Timestamp when the event was generated
+    pub timestamp: Instant,
+}
+
+/// Palette file watcher
+///
+/// Palette generators usually replace the file rather than editing it in
+/// place, so this watches the parent directory and filters by file name.
+pub struct PaletteWatcher {
+    _watcher: RecommendedWatcher,
+    receiver: mpsc::Receiver<PaletteChangeEvent>,
+    path: PathBuf,
+}
+
+impl PaletteWatcher {
+    /// Watch a palette file for changes
+    pub fn new(path: PathBuf) -> Result<Self> {
+        let dir = path
+            .parent()
+            .map(Path::to_path_buf)
+            .context("Palette path has no parent directory")?;
+        let file_name = path.file_name().map(|name| name.to_os_string());
+
+        let (tx, rx) = mpsc::channel();
+        let mut last_event: Option<Instant> = None;
+        const DEBOUNCE_DURATION: Duration = Duration::from_millis(100);
+
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<notify::Event, notify::Error>| match res {
+                Ok(event) => {
+                    if event.kind.is_access() {
+                        return;
+                    }
+                    let touches_palette = event
+                        .paths
+                        .iter()
+                        .any(|p| p.file_name().map(|n| n.to_os_string()) == file_name);
+                    if !touches_palette {
+                        return;
+                    }
+
+                    let now = Instant::now();
+                    if let Some(last) = last_event {
+                        if now.duration_since(last) < DEBOUNCE_DURATION {
+                            tracing::trace!("Palette change debounced");
+                            return;
+                        }
+                    }
+                    last_event = Some(now);
+
+                    tracing::info!("Palette changed, triggering recolor");
+                    if let Err(e) = tx.send(PaletteChangeEvent { timestamp: now }) {
+                        tracing::error!(error = %e, "Failed to send palette change event");
+                    }
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Palette watcher error");
+                }
+            },
+            NotifyConfig::default(),
+        )
+        .context("Failed to create palette watcher")?;
+
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch palette directory: {}", dir.display()))?;
+
+        tracing::info!(path = %path.display(), "Palette watcher initialized");
+
+        Ok(Self {
+            _watcher: watcher,
+            receiver: rx,
+            path,
+        })
+    }
+
+    /// The palette file being watched
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Try to receive a change event (non-blocking)
+    pub fn try_recv(&self) -> Option<PaletteChangeEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PYWAL: &str = r##"{
+        "wallpaper": "/home/user/wall.png",
+        "alpha": "80",
+        "special": {
+            "background": "#1a1b26",
+            "foreground": "#c0caf5",
+            "cursor": "#c0caf5"
+        },
+        "colors": {
+            "color0": "#1a1b26", "color1": "#f7768e", "color2": "#9ece6a",
+            "color3": "#e0af68", "color4": "#7aa2f7", "color5": "#bb9af7",
+            "color6": "#7dcfff", "color7": "#a9b1d6", "color8": "#414868",
+            "color9": "#f7768e", "color10": "#9ece6a", "color11": "#e0af68",
+            "color12": "#7aa2f7", "color13": "#bb9af7", "color14": "#7dcfff",
+            "color15": "#c0caf5"
+        }
+    }"##;
+
+    #[test]
+    fn test_source_from_theme_name() {
+        assert_eq!(
+            PaletteSource::from_theme_name("pywal"),
+            Some(PaletteSource::Pywal)
+        );
+        assert_eq!(
+            PaletteSource::from_theme_name("wal"),
+            Some(PaletteSource::Pywal)
+        );
+        assert_eq!(
+            PaletteSource::from_theme_name("matugen"),
+            Some(PaletteSource::Matugen)
+        );
+        assert_eq!(PaletteSource::from_theme_name("cosmic_dark"), None);
+    }
+
+    #[test]
+    fn test_parse_pywal() {
+        let colors = PaletteSource::Pywal.parse(PYWAL, "dark").unwrap();
+        assert_eq!(colors.background, "#1a1b26cc");
+        assert_eq!(colors.text_primary, "#c0caf5");
+        assert_eq!(colors.text_secondary, "#a9b1d6");
+        assert_eq!(colors.accent, "#7aa2f7");
+        assert_eq!(colors.border, "#414868");
+    }
+
+    #[test]
+    fn test_parse_pywal_missing_color() {
+        let json =
+            r##"{"special": {"background": "#000000", "foreground": "#ffffff"}, "colors": {}}"##;
+        assert!(PaletteSource::Pywal.parse(json, "dark").is_err());
+    }
+
+    #[test]
+    fn test_parse_matugen_layouts() {
+        let by_mode = r##"{"colors": {"dark": {
+            "surface": "#141218", "on_surface": "#e6e0e9", "on_surface_variant": "#cac4d0",
+            "primary": "#d0bcff", "outline_variant": "#49454f"
+        }}}"##;
+        let colors = PaletteSource::Matugen.parse(by_mode, "dark").unwrap();
+        assert_eq!(colors.background, "#141218");
+        assert_eq!(colors.accent, "#d0bcff");
+        assert_eq!(colors.border, "#49454f");
+
+        let by_role = r##"{"colors": {
+            "surface": {"dark": "#141218", "light": "#fef7ff"},
+            "on_surface": {"dark": "#e6e0e9", "light": "#1d1b20"},
+            "on_surface_variant": {"dark": "#cac4d0", "light": "#49454f"},
+            "primary": {"dark": "#d0bcff", "light": "#6750a4"},
+            "outline": {"dark": "#938f99", "light": "#7a757f"}
+        }}"##;
+        let colors = PaletteSource::Matugen.parse(by_role, "light").unwrap();
+        assert_eq!(colors.background, "#fef7ff");
+        assert_eq!(colors.text_primary, "#1d1b20");
+        assert_eq!(colors.border, "#7a757f");
+    }
+
+    #[test]
+    fn test_palette_watcher_detects_regeneration() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("colors.json");
+        std::fs::write(&path, PYWAL).unwrap();
+
+        let watcher = PaletteWatcher::new(path.clone()).unwrap();
+        assert_eq!(watcher.path(), path);
+
+        // Unrelated files in the same directory are ignored
+        std::fs::write(dir.path().join("colors.sh"), "export color0=#000000").unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(watcher.try_recv().is_none());
+
+        // Replace the palette the way generators do
+        let tmp = dir.path().join("colors.json.tmp");
+        std::fs::write(&tmp, PYWAL).unwrap();
+        std::fs::rename(&tmp, &path).unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(watcher.try_recv().is_some());
+    }
+}
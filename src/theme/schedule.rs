@@ -0,0 +1,313 @@
+//! Day/night theme scheduling
+//!
+//! A [`ThemeSchedule`] picks between a light and a dark theme based on the
+//! time of day, either at fixed clock times or at the computed sunrise and
+//! sunset for a location.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How the schedule decides when day starts and ends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleMode {
+    /// Switch at `day_start` / `night_start`
+    #[default]
+    Fixed,
+    /// Switch at sunrise / sunset for `latitude` / `longitude`
+    Sun,
+}
+
+/// Automatic light/dark theme switching
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeSchedule {
+    /// Whether the schedule is active
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Fixed times or sunrise/sunset
+    #[serde(default)]
+    pub mode: ScheduleMode,
+
+    /// Theme used during the day
+    #[serde(default = "default_light_theme")]
+    pub light_theme: String,
+
+    /// Theme used at night
+    #[serde(default = "default_dark_theme")]
+    pub dark_theme: String,
+
+    /// Start of day in fixed mode ("HH:MM")
+    #[serde(default = "default_day_start")]
+    pub day_start: String,
+
+    /// Start of night in fixed mode ("HH:MM")
+    #[serde(default = "default_night_start")]
+    pub night_start: String,
+
+    /// Latitude in degrees (north positive), for sun mode
+    #[serde(default)]
+    pub latitude: Option<f64>,
+
+    /// Longitude in degrees (east positive), for sun mode
+    #[serde(default)]
+    pub longitude: Option<f64>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_light_theme() -> String {
+    "light".to_string()
+}
+
+fn default_dark_theme() -> String {
+    "cosmic_dark".to_string()
+}
+
+fn default_day_start() -> String {
+    "07:00".to_string()
+}
+
+fn default_night_start() -> String {
+    "19:00".to_string()
+}
+
+impl Default for ThemeSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            mode: ScheduleMode::default(),
+            light_theme: default_light_theme(),
+            dark_theme: default_dark_theme(),
+            day_start: default_day_start(),
+            night_start: default_night_start(),
+            latitude: None,
+            longitude: None,
+        }
+    }
+}
+
+impl ThemeSchedule {
+    /// Theme name to use at `now`
+    pub fn theme_at<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> &str {
+        if self.is_day(now) {
+            &self.light_theme
+        } else {
+            &self.dark_theme
+        }
+    }
+
+    /// Whether `now` falls in the daytime part of the schedule
+    pub fn is_day<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> bool {
+        if self.mode == ScheduleMode::Sun {
+            if let (Some(lat), Some(lon)) = (self.latitude, self.longitude) {
+                let utc = now.with_timezone(&Utc);
+                return match sun_times(utc.date_naive(), lat, lon) {
+                    SunTimes::Normal { sunrise, sunset } if sunrise <= sunset => {
+                        utc >= sunrise && utc < sunset
+                    }
+                    // Near the date line sunset can fall "before" sunrise in UTC
+                    SunTimes::Normal { sunrise, sunset } => utc >= sunrise || utc < sunset,
+                    SunTimes::PolarDay => true,
+                    SunTimes::PolarNight => false,
+                };
+            }
+            // Missing coordinates are reported by validate(); use the fixed times
+        }
+
+        let day_start = parse_time(&self.day_start).unwrap_or(NaiveTime::MIN);
+        let night_start = parse_time(&self.night_start).unwrap_or(NaiveTime::MIN);
+        let time = now.time();
+        if day_start <= night_start {
+            time >= day_start && time < night_start
+        } else {
+            time >= day_start || time < night_start
+        }
+    }
+
+    /// Check the schedule's times and coordinates
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (key, value) in [
+            ("day_start", &self.day_start),
+            ("night_start", &self.night_start),
+        ] {
+            if parse_time(value).is_none() {
+                anyhow::bail!("theme_schedule.{} must be HH:MM, got '{}'", key, value);
+            }
+        }
+        if self.mode == ScheduleMode::Sun {
+            match (self.latitude, self.longitude) {
+                (Some(lat), Some(lon)) if lat.abs() <= 90.0 && lon.abs() <= 180.0 => {}
+                (Some(_), Some(_)) => anyhow::bail!("theme_schedule coordinates are out of range"),
+                _ => anyhow::bail!("theme_schedule mode \"sun\" needs latitude and longitude"),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// Sunrise and sunset for a day
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SunTimes {
+    /// The sun rises and sets
+    Normal {
+        sunrise: DateTime<Utc>,
+        sunset: DateTime<Utc>,
+    },
+    /// The sun stays up all day
+    PolarDay,
+    /// The sun stays down all day
+    PolarNight,
+}
+
+/// Compute sunrise and sunset (official zenith, 90.833°)
+///
+/// Uses the sunrise/sunset algorithm from the *Almanac for Computers*, which
+/// is accurate to a couple of minutes outside the polar regions.
+pub fn sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> SunTimes {
+    let day_of_year = date.ordinal() as f64;
+    let lng_hour = longitude / 15.0;
+
+    let event_hour = |rising: bool| -> Option<f64> {
+        let t = day_of_year + ((if rising { 6.0 } else { 18.0 }) - lng_hour) / 24.0;
+
+        // Sun's mean anomaly and true longitude
+        let m = 0.9856 * t - 3.289;
+        let l = (m + 1.916 * m.to_radians().sin() + 0.020 * (2.0 * m).to_radians().sin() + 282.634)
+            .rem_euclid(360.0);
+
+        // Right ascension, in the same quadrant as L, in hours
+        let mut ra = (0.91764 * l.to_radians().tan())
+            .atan()
+            .to_degrees()
+            .rem_euclid(360.0);
+        ra += (l / 90.0).floor() * 90.0 - (ra / 90.0).floor() * 90.0;
+        ra /= 15.0;
+
+        // Declination and local hour angle
+        let sin_dec = 0.39782 * l.to_radians().sin();
+        let cos_dec = sin_dec.asin().cos();
+        let cos_h = (90.833_f64.to_radians().cos() - sin_dec * latitude.to_radians().sin())
+            / (cos_dec * latitude.to_radians().cos());
+        if !(-1.0..=1.0).contains(&cos_h) {
+            return None;
+        }
+
+        let h = if rising {
+            360.0 - cos_h.acos().to_degrees()
+        } else {
+            cos_h.acos().to_degrees()
+        } / 15.0;
+
+        let local_mean_time = h + ra - 0.06571 * t - 6.622;
+        Some((local_mean_time - lng_hour).rem_euclid(24.0))
+    };
+
+    match (event_hour(true), event_hour(false)) {
+        (Some(rise), Some(set)) => {
+            let midnight = Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN));
+            let at = |hours: f64| midnight + Duration::seconds((hours * 3600.0) as i64);
+            SunTimes::Normal {
+                sunrise: at(rise),
+                sunset: at(set),
+            }
+        }
+        _ => {
+            // No crossing: check which side of the horizon the sun is on at noon
+            let sin_dec = 0.39782 * sun_longitude(day_of_year).to_radians().sin();
+            if sin_dec * latitude.signum() > 0.0 {
+                SunTimes::PolarDay
+            } else {
+                SunTimes::PolarNight
+            }
+        }
+    }
+}
+
+/// Sun's true longitude around local noon
+fn sun_longitude(day_of_year: f64) -> f64 {
+    let m = 0.9856 * (day_of_year + 0.5) - 3.289;
+    (m + 1.916 * m.to_radians().sin() + 0.020 * (2.0 * m).to_radians().sin() + 282.634)
+        .rem_euclid(360.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+
+    #[test]
+    fn test_fixed_schedule() {
+        let schedule = ThemeSchedule::default();
+        let at = |h, m| Utc.with_ymd_and_hms(2024, 3, 1, h, m, 0).unwrap();
+
+        assert_eq!(schedule.theme_at(&at(6, 59)), "cosmic_dark");
+        assert_eq!(schedule.theme_at(&at(7, 0)), "light");
+        assert_eq!(schedule.theme_at(&at(18, 59)), "light");
+        assert_eq!(schedule.theme_at(&at(19, 0)), "cosmic_dark");
+
+        // Night shift: day runs across midnight
+        let inverted = ThemeSchedule {
+            day_start: "22:00".into(),
+            night_start: "06:00".into(),
+            ..ThemeSchedule::default()
+        };
+        assert!(inverted.is_day(&at(23, 0)));
+        assert!(!inverted.is_day(&at(12, 0)));
+    }
+
+    #[test]
+    fn test_sun_times_london_midsummer() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let SunTimes::Normal { sunrise, sunset } = sun_times(date, 51.5074, -0.1278) else {
+            panic!("London has a sunrise in June");
+        };
+
+        // Sunrise ~03:43 UTC, sunset ~20:21 UTC
+        let minutes = |t: DateTime<Utc>| t.hour() * 60 + t.minute();
+        assert!(
+            minutes(sunrise).abs_diff(3 * 60 + 43) <= 5,
+            "sunrise {}",
+            sunrise
+        );
+        assert!(
+            minutes(sunset).abs_diff(20 * 60 + 21) <= 5,
+            "sunset {}",
+            sunset
+        );
+    }
+
+    #[test]
+    fn test_sun_times_polar() {
+        let midsummer = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let midwinter = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        // Tromsø
+        assert_eq!(sun_times(midsummer, 69.65, 18.96), SunTimes::PolarDay);
+        assert_eq!(sun_times(midwinter, 69.65, 18.96), SunTimes::PolarNight);
+    }
+
+    #[test]
+    fn test_sun_schedule() {
+        let schedule = ThemeSchedule {
+            mode: ScheduleMode::Sun,
+            latitude: Some(51.5074),
+            longitude: Some(-0.1278),
+            ..ThemeSchedule::default()
+        };
+        assert!(schedule.validate().is_ok());
+        assert!(schedule.is_day(&Utc.with_ymd_and_hms(2024, 6, 21, 12, 0, 0).unwrap()));
+        assert!(!schedule.is_day(&Utc.with_ymd_and_hms(2024, 6, 21, 23, 0, 0).unwrap()));
+
+        let missing = ThemeSchedule {
+            mode: ScheduleMode::Sun,
+            ..ThemeSchedule::default()
+        };
+        assert!(missing.validate().is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//! Exponential backoff for failing data sources
+
+use std::time::Duration;
+
+/// Longest a failing source is left alone before it is tried again
+pub const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+/// Exponential backoff state for one source
+///
+/// Each failure doubles the wait, starting from `initial` and capped at
+/// `max`. A success resets it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    failures: u32,
+}
+
+impl Backoff {
+    /// Start with `initial`, never waiting longer than `max`
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max: max.max(initial),
+            failures: 0,
+        }
+    }
+
+    /// Record a failure and return how long to wait before retrying
+    pub fn fail(&mut self) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+        self.delay()
+    }
+
+    /// Wait for the current number of failures (`initial` when there are none)
+    pub fn delay(&self) -> Duration {
+        let doublings = self.failures.saturating_sub(1).min(31);
+        self.initial.saturating_mul(1 << doublings).min(self.max)
+    }
+
+    /// Record a success, returning to the initial delay
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+
+    /// Failures since the last success
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Whether the last attempt failed
+    pub fn is_failing(&self) -> bool {
+        self.failures > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let mut backoff = Backoff::new(Duration::from_secs(60), Duration::from_secs(300));
+        assert_eq!(backoff.delay(), Duration::from_secs(60));
+        assert!(!backoff.is_failing());
+
+        assert_eq!(backoff.fail(), Duration::from_secs(60));
+        assert_eq!(backoff.fail(), Duration::from_secs(120));
+        assert_eq!(backoff.fail(), Duration::from_secs(240));
+        assert_eq!(backoff.fail(), Duration::from_secs(300));
+        for _ in 0..100 {
+            backoff.fail();
+        }
+        assert_eq!(backoff.delay(), Duration::from_secs(300));
+        assert_eq!(backoff.failures(), 104);
+
+        backoff.reset();
+        assert!(!backoff.is_failing());
+        assert_eq!(backoff.fail(), Duration::from_secs(60));
+    }
+}
@@ -1,7 +1,23 @@
 //! Update coordination system for widgets
 
+pub mod backoff;
+pub mod reminder;
+pub mod schedule;
+pub mod service;
+pub mod supervisor;
+pub mod tasks;
+
+pub use backoff::Backoff;
+pub use reminder::{Reminder, ReminderQueue};
+pub use schedule::{Schedule, ScheduleError, Ticker};
+pub use service::{DataService, NetworkState, Source};
+pub use supervisor::Supervisor;
+pub use tasks::{FetchError, TaskFuture, TaskManager, TaskOutput, TaskResult};
+
 use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Local};
+
 /// Tracks what needs to be updated
 #[derive(Debug, Clone, Copy, Default)]
 pub struct UpdateFlags {
@@ -51,6 +67,9 @@ pub struct UpdateScheduler {
 
     /// Pending updates
     pending: UpdateFlags,
+
+    /// Widget reminders waiting for their time
+    reminders: ReminderQueue,
 }
 
 impl UpdateScheduler {
@@ -62,6 +81,7 @@ impl UpdateScheduler {
             clock_interval,
             weather_interval,
             pending: UpdateFlags::default(),
+            reminders: ReminderQueue::new(),
         }
     }
 
@@ -99,6 +119,17 @@ impl UpdateScheduler {
         self.pending.weather = true;
     }
 
+    /// Replace the pending reminders with those the widgets reported,
+    /// keyed by widget instance id
+    pub fn set_reminders(&mut self, reminders: Vec<(String, Reminder)>) {
+        self.reminders.set(reminders);
+    }
+
+    /// Take the reminders that are due, with the widget each belongs to
+    pub fn take_due_reminders(&mut self, now: DateTime<Local>) -> Vec<(String, Reminder)> {
+        self.reminders.take_due(now)
+    }
+
     /// Get time until next update
     pub fn time_until_next_update(&self) -> Duration {
         let now = Instant::now();
@@ -113,7 +144,14 @@ impl UpdateScheduler {
             .checked_sub(now.duration_since(self.last_weather_update))
             .unwrap_or(Duration::ZERO);
 
-        clock_remaining.min(weather_remaining)
+        let reminder_remaining = self
+            .reminders
+            .time_until_next(Local::now())
+            .unwrap_or(Duration::MAX);
+
+        clock_remaining
+            .min(weather_remaining)
+            .min(reminder_remaining)
     }
 }
 
@@ -0,0 +1,143 @@
+//! Reminders that fire at a wall-clock time
+//!
+//! Widgets hand over their upcoming reminders, and the update scheduler
+//! holds them from then on. That way a reminder still fires on time while
+//! the widget isn't being updated or redrawn, e.g. with the screen off.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+
+/// Keep track of fired reminders for this long, so reloads don't repeat them
+const FIRED_RETENTION_HOURS: i64 = 24;
+
+/// Something to tell the user about at a set time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reminder {
+    /// Identifies the reminder across updates, so it only fires once
+    pub id: String,
+    /// When to fire
+    pub at: DateTime<Local>,
+    /// Notification title
+    pub summary: String,
+    /// Notification text
+    pub body: String,
+    /// Whether to play the notification sound too
+    pub sound: bool,
+}
+
+/// Pending reminders of every widget, by widget instance id
+#[derive(Debug, Default)]
+pub struct ReminderQueue {
+    pending: Vec<(String, Reminder)>,
+    /// Ids of reminders that already fired, with when they were due
+    fired: HashMap<String, DateTime<Local>>,
+}
+
+impl ReminderQueue {
+    /// Create an empty queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the pending reminders with `reminders`
+    ///
+    /// Reminders that already fired are left out.
+    pub fn set(&mut self, reminders: Vec<(String, Reminder)>) {
+        self.pending = reminders
+            .into_iter()
+            .filter(|(_, reminder)| !self.fired.contains_key(&reminder.id))
+            .collect();
+    }
+
+    /// Take the reminders due at `now`, with the widget each belongs to
+    pub fn take_due(&mut self, now: DateTime<Local>) -> Vec<(String, Reminder)> {
+        let (due, pending) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|(_, reminder)| reminder.at <= now);
+        self.pending = pending;
+
+        let cutoff = now - chrono::Duration::hours(FIRED_RETENTION_HOURS);
+        self.fired.retain(|_, at| *at > cutoff);
+        for (_, reminder) in &due {
+            self.fired.insert(reminder.id.clone(), reminder.at);
+        }
+        due
+    }
+
+    /// Time from `now` until the next reminder is due
+    pub fn time_until_next(&self, now: DateTime<Local>) -> Option<Duration> {
+        self.pending
+            .iter()
+            .map(|(_, reminder)| (reminder.at - now).to_std().unwrap_or(Duration::ZERO))
+            .min()
+    }
+
+    /// Number of reminders waiting to fire
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Whether no reminders are waiting
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn reminder(id: &str, at: DateTime<Local>) -> (String, Reminder) {
+        (
+            "calendar".to_string(),
+            Reminder {
+                id: id.to_string(),
+                at,
+                summary: id.to_string(),
+                body: String::new(),
+                sound: false,
+            },
+        )
+    }
+
+    #[test]
+    fn test_reminders_fire_once() {
+        let now = Local.with_ymd_and_hms(2026, 3, 2, 9, 50, 0).unwrap();
+        let mut queue = ReminderQueue::new();
+        queue.set(vec![
+            reminder("standup", now + chrono::Duration::minutes(5)),
+            reminder("lunch", now + chrono::Duration::hours(2)),
+        ]);
+        assert_eq!(
+            queue.time_until_next(now),
+            Some(Duration::from_secs(5 * 60))
+        );
+        assert!(queue.take_due(now).is_empty());
+
+        let later = now + chrono::Duration::minutes(6);
+        let due = queue.take_due(later);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].1.id, "standup");
+
+        // The widget reporting it again after a reload doesn't repeat it
+        queue.set(vec![
+            reminder("standup", now + chrono::Duration::minutes(5)),
+            reminder("lunch", now + chrono::Duration::hours(2)),
+        ]);
+        assert_eq!(queue.len(), 1);
+        assert!(queue.take_due(later).is_empty());
+    }
+
+    #[test]
+    fn test_overdue_reminder_is_due_now() {
+        let now = Local.with_ymd_and_hms(2026, 3, 2, 9, 50, 0).unwrap();
+        let mut queue = ReminderQueue::new();
+        queue.set(vec![reminder("late", now - chrono::Duration::minutes(1))]);
+        assert_eq!(queue.time_until_next(now), Some(Duration::ZERO));
+        assert_eq!(queue.take_due(now).len(), 1);
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,510 @@
+//! Fixed-interval and cron-style schedules
+//!
+//! Widgets that refresh or rotate on a timer (quotes, news, custom commands)
+//! take either a plain interval in seconds or a `schedule` key holding a cron
+//! expression:
+//!
+//! ```toml
+//! [widgets.config]
+//! schedule = "0 */15 * * * *"   # every quarter hour, on the dot
+//! ```
+//!
+//! Expressions have six fields (second, minute, hour, day of month, month,
+//! day of week) or the classic five without seconds. Each field is `*`, a
+//! number, a range `a-b`, a step `*/n` or `a-b/n`, or a comma-separated list
+//! of those. Months and weekdays also accept three-letter names, and Sunday
+//! is both 0 and 7. The macros `@yearly`, `@monthly`, `@weekly`, `@daily`
+//! and `@hourly` are shorthands.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+use thiserror::Error;
+
+/// How far ahead to look for the next match before giving up
+const SEARCH_DAYS: u32 = 366 * 5;
+
+/// Errors produced while parsing a cron expression
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ScheduleError {
+    #[error("expected 5 or 6 fields, found {0}")]
+    FieldCount(usize),
+
+    #[error("unknown macro '{0}'")]
+    UnknownMacro(String),
+
+    #[error("invalid {field} field '{value}'")]
+    InvalidField { field: &'static str, value: String },
+
+    #[error("{field} value {value} is outside {min}-{max}")]
+    OutOfRange {
+        field: &'static str,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+}
+
+/// When something should happen
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    /// Every fixed interval
+    Every(Duration),
+    /// Whenever the cron expression matches local time
+    Cron(Cron),
+}
+
+impl Schedule {
+    /// Parse a cron expression or macro
+    pub fn parse(expression: &str) -> Result<Self, ScheduleError> {
+        Cron::parse(expression).map(Self::Cron)
+    }
+
+    /// Read `schedule` from a widget's config, falling back to `interval_key`
+    /// seconds (or `default_secs` if that is missing too)
+    pub fn from_config(
+        config: &toml::Table,
+        interval_key: &str,
+        default_secs: u64,
+    ) -> anyhow::Result<Self> {
+        if let Some(value) = config.get("schedule") {
+            let expression = value
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'schedule' must be a string"))?;
+            return Self::parse(expression)
+                .map_err(|e| anyhow::anyhow!("Invalid 'schedule' '{}': {}", expression, e));
+        }
+
+        let secs = config
+            .get(interval_key)
+            .and_then(|v| v.as_integer())
+            .map_or(default_secs, |secs| secs.max(1) as u64);
+        Ok(Self::Every(Duration::from_secs(secs)))
+    }
+
+    /// Time from `now` until the schedule next fires
+    ///
+    /// A cron expression that never matches (say February 30th) waits a day
+    /// and looks again.
+    pub fn delay_from(&self, now: DateTime<Local>) -> Duration {
+        match self {
+            Self::Every(interval) => *interval,
+            Self::Cron(cron) => cron
+                .next_after(now)
+                .and_then(|next| (next - now).to_std().ok())
+                .unwrap_or(Duration::from_secs(24 * 60 * 60)),
+        }
+    }
+
+    /// Usual time between runs, judged from the next two after `now` for
+    /// a cron schedule
+    ///
+    /// None for a cron expression that never matches.
+    pub fn period(&self, now: DateTime<Local>) -> Option<Duration> {
+        match self {
+            Self::Every(interval) => Some(*interval),
+            Self::Cron(cron) => {
+                let next = cron.next_after(now)?;
+                let after = cron.next_after(next)?;
+                (after - next).to_std().ok()
+            }
+        }
+    }
+
+    /// Whether this is a cron schedule rather than a fixed interval
+    pub fn is_cron(&self) -> bool {
+        matches!(self, Self::Cron(_))
+    }
+}
+
+impl From<Duration> for Schedule {
+    fn from(interval: Duration) -> Self {
+        Self::Every(interval)
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Every(interval) => write!(f, "every {}s", interval.as_secs()),
+            Self::Cron(cron) => write!(f, "{}", cron.expression),
+        }
+    }
+}
+
+/// A parsed cron expression
+///
+/// Each field is a bit set of the values it allows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    expression: String,
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day of month or weekday restricted (not `*`), for cron's rule that
+    /// either may match when both are
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl Cron {
+    /// Parse a five- or six-field expression, or a macro like `@hourly`
+    pub fn parse(expression: &str) -> Result<Self, ScheduleError> {
+        let trimmed = expression.trim();
+        let expanded = match trimmed {
+            "@yearly" | "@annually" => "0 0 0 1 1 *",
+            "@monthly" => "0 0 0 1 * *",
+            "@weekly" => "0 0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 0 * * *",
+            "@hourly" => "0 0 * * * *",
+            other if other.starts_with('@') => {
+                return Err(ScheduleError::UnknownMacro(other.to_string()))
+            }
+            other => other,
+        };
+
+        let mut fields: Vec<&str> = expanded.split_whitespace().collect();
+        match fields.len() {
+            6 => {}
+            5 => fields.insert(0, "0"),
+            count => return Err(ScheduleError::FieldCount(count)),
+        }
+
+        Ok(Self {
+            expression: trimmed.to_string(),
+            seconds: parse_field(fields[0], "second", 0, 59, &[])?,
+            minutes: parse_field(fields[1], "minute", 0, 59, &[])?,
+            hours: parse_field(fields[2], "hour", 0, 23, &[])?,
+            days: parse_field(fields[3], "day of month", 1, 31, &[])?,
+            months: parse_field(fields[4], "month", 1, 12, &MONTHS)?,
+            weekdays: fold_sunday(parse_field(fields[5], "day of week", 0, 7, &WEEKDAYS)?),
+            days_restricted: fields[3] != "*",
+            weekdays_restricted: fields[5] != "*",
+        })
+    }
+
+    /// First time strictly after `after` that matches
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut from = after.naive_local().with_nanosecond(0)? + chrono::Duration::seconds(1);
+        // A match inside a DST gap doesn't exist locally; keep looking
+        for _ in 0..4 {
+            let next = self.next_naive(from)?;
+            if let Some(local) = Local.from_local_datetime(&next).earliest() {
+                return Some(local);
+            }
+            from = next + chrono::Duration::seconds(1);
+        }
+        None
+    }
+
+    /// First naive time at or after `from` that matches
+    fn next_naive(&self, from: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut date = from.date();
+        for _ in 0..SEARCH_DAYS {
+            if self.matches_date(date) {
+                let start = if date == from.date() {
+                    from.time()
+                } else {
+                    NaiveTime::MIN
+                };
+                if let Some(time) = self.first_time_from(start) {
+                    return Some(date.and_time(time));
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !has(self.months, date.month()) {
+            return false;
+        }
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    fn first_time_from(&self, start: NaiveTime) -> Option<NaiveTime> {
+        for hour in start.hour()..24 {
+            if !has(self.hours, hour) {
+                continue;
+            }
+            let first_minute = if hour == start.hour() {
+                start.minute()
+            } else {
+                0
+            };
+            for minute in first_minute..60 {
+                if !has(self.minutes, minute) {
+                    continue;
+                }
+                let first_second = if hour == start.hour() && minute == start.minute() {
+                    start.second()
+                } else {
+                    0
+                };
+                if let Some(second) = (first_second..60).find(|s| has(self.seconds, *s)) {
+                    return NaiveTime::from_hms_opt(hour, minute, second);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Tells a widget when its schedule has fired
+///
+/// For widgets that rotate or refresh on their own rather than through the
+/// data service.
+#[derive(Debug, Clone)]
+pub struct Ticker {
+    schedule: Schedule,
+    next: Instant,
+}
+
+impl Ticker {
+    /// Start ticking; the first tick is one period from now
+    pub fn new(schedule: Schedule) -> Self {
+        let next = Instant::now() + schedule.delay_from(Local::now());
+        Self { schedule, next }
+    }
+
+    /// Whether the schedule fired since the last call that returned true
+    pub fn tick(&mut self) -> bool {
+        let now = Instant::now();
+        if now < self.next {
+            return false;
+        }
+        self.restart();
+        true
+    }
+
+    /// Start the period over from now, e.g. after a manual advance
+    pub fn restart(&mut self) {
+        self.next = Instant::now() + self.schedule.delay_from(Local::now());
+    }
+
+    /// Time until the next tick
+    pub fn remaining(&self) -> Duration {
+        self.next.saturating_duration_since(Instant::now())
+    }
+
+    /// The schedule being followed
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Treat weekday 7 as Sunday (0)
+fn fold_sunday(weekdays: u64) -> u64 {
+    if has(weekdays, 7) {
+        (weekdays | 1) & !(1 << 7)
+    } else {
+        weekdays
+    }
+}
+
+fn parse_field(
+    field: &str,
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> Result<u64, ScheduleError> {
+    let invalid = || ScheduleError::InvalidField {
+        field: name,
+        value: field.to_string(),
+    };
+    let value = |text: &str| -> Result<u32, ScheduleError> {
+        let lower = text.to_ascii_lowercase();
+        let number = match names.iter().position(|n| *n == lower) {
+            // Names count from the field's minimum (January is 1, Sunday 0)
+            Some(index) => index as u32 + min,
+            None => text.parse().map_err(|_| invalid())?,
+        };
+        if number < min || number > max {
+            return Err(ScheduleError::OutOfRange {
+                field: name,
+                value: number,
+                min,
+                max,
+            });
+        }
+        Ok(number)
+    };
+
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/10` means from 5 to the end in steps of 10
+                None if step > 1 => (value(range)?, max),
+                None => {
+                    let single = value(range)?;
+                    (single, single)
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+
+        for v in (start..=end).step_by(step as usize) {
+            set |= 1 << v;
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Local> {
+        let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").unwrap();
+        Local.from_local_datetime(&naive).earliest().unwrap()
+    }
+
+    fn next(expression: &str, after: &str) -> String {
+        Cron::parse(expression)
+            .unwrap()
+            .next_after(at(after))
+            .unwrap()
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        assert_eq!(
+            next("0 */15 * * * *", "2024-03-05 10:07:30"),
+            "2024-03-05 10:15:00"
+        );
+        // Strictly after, even when `after` itself matches
+        assert_eq!(
+            next("0 */15 * * * *", "2024-03-05 10:15:00"),
+            "2024-03-05 10:30:00"
+        );
+        assert_eq!(
+            next("@hourly", "2024-03-05 23:59:59"),
+            "2024-03-06 00:00:00"
+        );
+        assert_eq!(
+            next("30 9 * * mon-fri", "2024-03-08 10:00:00"),
+            "2024-03-11 09:30:00"
+        );
+        assert_eq!(
+            next("@yearly", "2024-06-01 00:00:00"),
+            "2025-01-01 00:00:00"
+        );
+        assert_eq!(
+            next("0 0 12 29 feb *", "2024-03-01 00:00:00"),
+            "2028-02-29 12:00:00"
+        );
+
+        // Day of month or weekday when both are restricted
+        assert_eq!(
+            next("0 0 0 13 * 5", "2024-09-01 00:00:00"),
+            "2024-09-06 00:00:00"
+        );
+        // Sunday as 7
+        assert_eq!(
+            next("0 0 8 * * 7", "2024-03-05 00:00:00"),
+            "2024-03-10 08:00:00"
+        );
+    }
+
+    #[test]
+    fn test_cron_parse_errors() {
+        assert_eq!(
+            Cron::parse("* * *").unwrap_err(),
+            ScheduleError::FieldCount(3)
+        );
+        assert!(matches!(
+            Cron::parse("@fortnightly"),
+            Err(ScheduleError::UnknownMacro(_))
+        ));
+        assert!(matches!(
+            Cron::parse("0 60 * * * *"),
+            Err(ScheduleError::OutOfRange {
+                field: "minute",
+                ..
+            })
+        ));
+        assert!(matches!(
+            Cron::parse("0 */0 * * * *"),
+            Err(ScheduleError::InvalidField { .. })
+        ));
+        assert!(Cron::parse("0 0 * * foo").is_err());
+    }
+
+    #[test]
+    fn test_schedule_from_config() {
+        let mut config = toml::Table::new();
+        assert_eq!(
+            Schedule::from_config(&config, "interval", 30).unwrap(),
+            Schedule::Every(Duration::from_secs(30))
+        );
+
+        config.insert("interval".into(), toml::Value::Integer(90));
+        assert_eq!(
+            Schedule::from_config(&config, "interval", 30).unwrap(),
+            Schedule::Every(Duration::from_secs(90))
+        );
+
+        config.insert("schedule".into(), toml::Value::String("@daily".into()));
+        let schedule = Schedule::from_config(&config, "interval", 30).unwrap();
+        assert!(schedule.is_cron());
+        assert_eq!(schedule.to_string(), "@daily");
+        assert!(schedule.delay_from(Local::now()) <= Duration::from_secs(25 * 60 * 60));
+
+        config.insert("schedule".into(), toml::Value::String("every day".into()));
+        assert!(Schedule::from_config(&config, "interval", 30).is_err());
+    }
+
+    #[test]
+    fn test_schedule_period() {
+        let now = at("2024-03-05 10:07:30");
+        assert_eq!(
+            Schedule::Every(Duration::from_secs(90)).period(now),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            Schedule::parse("0 */15 * * * *").unwrap().period(now),
+            Some(Duration::from_secs(15 * 60))
+        );
+        assert_eq!(Schedule::parse("0 0 0 30 2 *").unwrap().period(now), None);
+    }
+}
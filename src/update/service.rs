@@ -0,0 +1,522 @@
+//! Data-source service
+//!
+//! Widgets don't poll anything themselves. Each one describes where its data
+//! comes from as a [`Source`], registered once when the widget starts. The
+//! [`DataService`] owns every source from then on: it decides when each is
+//! due (via the [`TaskManager`]), runs it on the shared runtime, and the
+//! typed result comes back over the event loop to the widget, which only has
+//! to present it.
+//!
+//! The service also decides when the network is gone. Besides what the
+//! connectivity watcher reports, a run of fetches that fail to connect at
+//! all marks it unreachable: network sources are then held, so widgets keep
+//! their last data, and one of them at a time is fetched as a probe until a
+//! fetch gets through.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use calloop::channel::Channel;
+
+use super::backoff::Backoff;
+use super::schedule::Schedule;
+use super::tasks::{self, TaskFuture, TaskManager, TaskResult};
+use crate::metrics::WorkerStats;
+
+/// Network fetches in a row that fail to connect before the network counts
+/// as unreachable
+const OFFLINE_AFTER_FAILURES: u32 = 3;
+
+/// Wait before the first probe once the network is unreachable
+const PROBE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Longest wait between probes
+const MAX_PROBE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Starts one fetch of a source
+pub type FetchFn = Arc<dyn Fn() -> TaskFuture + Send + Sync>;
+
+/// Where a widget's data comes from and how often to fetch it
+#[derive(Clone)]
+pub struct Source {
+    schedule: Schedule,
+    needs_network: bool,
+    fetch: FetchFn,
+}
+
+impl Source {
+    /// A source fetched every `interval` by calling `fetch`
+    pub fn new<F>(interval: Duration, fetch: F) -> Self
+    where
+        F: Fn() -> TaskFuture + Send + Sync + 'static,
+    {
+        Self {
+            schedule: Schedule::Every(interval),
+            needs_network: true,
+            fetch: Arc::new(fetch),
+        }
+    }
+
+    /// A source whose fetch blocks (commands, files), run off the runtime
+    pub fn blocking<T, F>(interval: Duration, fetch: F) -> Self
+    where
+        T: Send + 'static,
+        F: Fn() -> Result<T> + Send + Sync + 'static,
+    {
+        let fetch = Arc::new(fetch);
+        Self::new(interval, move || {
+            let fetch = Arc::clone(&fetch);
+            tasks::task(async move {
+                tokio::task::spawn_blocking(move || fetch())
+                    .await
+                    .context("Fetch thread failed")?
+            })
+        })
+    }
+
+    /// Whether the source needs a network connection (true by default)
+    ///
+    /// Sources that don't are still polled while offline.
+    pub fn with_network(mut self, needs_network: bool) -> Self {
+        self.needs_network = needs_network;
+        self
+    }
+
+    /// Fetch on `schedule` instead of the interval, e.g. a cron expression
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// When the source is fetched
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Whether the source needs a network connection
+    pub fn needs_network(&self) -> bool {
+        self.needs_network
+    }
+
+    /// Start one fetch
+    pub fn fetch(&self) -> TaskFuture {
+        (self.fetch)()
+    }
+}
+
+impl std::fmt::Debug for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Source")
+            .field("schedule", &self.schedule)
+            .field("needs_network", &self.needs_network)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Whether network sources are being fetched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NetworkState {
+    /// Fetched on their schedules
+    #[default]
+    Online,
+    /// The system reports no connection; held until it reconnects
+    Disconnected,
+    /// Fetches kept failing to connect; held, with one source fetched now
+    /// and then as a probe
+    Unreachable,
+}
+
+impl NetworkState {
+    /// Whether network sources are held
+    pub fn is_offline(&self) -> bool {
+        *self != Self::Online
+    }
+}
+
+/// Owns every registered source and polls them on the shared runtime
+pub struct DataService {
+    tasks: TaskManager,
+    /// Registered sources, keyed by widget instance id
+    sources: HashMap<String, Source>,
+    network: NetworkState,
+    /// Network fetches in a row that failed to connect
+    connection_failures: u32,
+    /// Spaces out probes while unreachable
+    probe_backoff: Backoff,
+    /// When the next probe is due, while unreachable
+    next_probe: Option<Instant>,
+    /// Source whose fetch is the running probe
+    probing: Option<String>,
+}
+
+impl DataService {
+    /// Start the service
+    ///
+    /// Returns it along with the channel results arrive on, which belongs in
+    /// the event loop.
+    pub fn new() -> Result<(Self, Channel<TaskResult>)> {
+        let (tasks, results) = TaskManager::new()?;
+        Ok((
+            Self {
+                tasks,
+                sources: HashMap::new(),
+                network: NetworkState::Online,
+                connection_failures: 0,
+                probe_backoff: Backoff::new(PROBE_INTERVAL, MAX_PROBE_INTERVAL),
+                next_probe: None,
+                probing: None,
+            },
+            results,
+        ))
+    }
+
+    /// Set the interval jitter and whether first fetches are staggered
+    pub fn set_spread(&mut self, jitter: f64, stagger: bool) {
+        self.tasks.set_spread(jitter, stagger);
+    }
+
+    /// Poll `source` for `widget_id`, replacing any source it had
+    pub fn register(&mut self, widget_id: &str, source: Source) {
+        tracing::debug!(widget = %widget_id, ?source, "Registering data source");
+        self.tasks.cancel(widget_id);
+        self.sources.insert(widget_id.to_string(), source);
+    }
+
+    /// Stop polling for `widget_id`, cancelling a running fetch
+    pub fn unregister(&mut self, widget_id: &str) {
+        self.tasks.cancel(widget_id);
+        self.sources.remove(widget_id);
+    }
+
+    /// Drop every source and cancel their fetches, e.g. on a config reload
+    pub fn clear(&mut self) {
+        self.tasks.cancel_all();
+        self.sources.clear();
+    }
+
+    /// Hold network sources while the system reports no connection, and
+    /// make everything due on reconnecting
+    ///
+    /// Returns true if the state changed.
+    pub fn set_online(&mut self, online: bool) -> bool {
+        let state = if online {
+            NetworkState::Online
+        } else {
+            NetworkState::Disconnected
+        };
+        self.set_network(state)
+    }
+
+    /// Whether network sources are being fetched
+    pub fn network(&self) -> NetworkState {
+        self.network
+    }
+
+    /// Whether `widget_id`'s source is held because the network is gone
+    pub fn is_held(&self, widget_id: &str) -> bool {
+        self.network.is_offline()
+            && self
+                .sources
+                .get(widget_id)
+                .is_some_and(Source::needs_network)
+    }
+
+    fn set_network(&mut self, state: NetworkState) -> bool {
+        if state == self.network {
+            return false;
+        }
+        self.network = state;
+        self.connection_failures = 0;
+        self.probe_backoff.reset();
+        self.probing = None;
+        self.next_probe = match state {
+            NetworkState::Unreachable => Some(Instant::now() + self.probe_backoff.delay()),
+            _ => None,
+        };
+        if state == NetworkState::Online {
+            // Failures while offline say nothing about the sources
+            self.tasks.reschedule_all();
+        }
+        true
+    }
+
+    /// Start a fetch for every source that is due, and the probe while the
+    /// network is unreachable
+    ///
+    /// The runtime the fetches run on is checked first, and replaced if it
+    /// died or stalled.
+    pub fn poll(&mut self, now: Instant) {
+        self.tasks.supervise(now);
+        for (widget_id, source) in &self.sources {
+            if self.may_fetch(source) && self.tasks.is_due(widget_id, now) {
+                self.tasks
+                    .spawn(widget_id, source.schedule.clone(), source.fetch());
+            }
+        }
+
+        if self.probing.is_none() && self.next_probe.is_some_and(|due| now >= due) {
+            self.start_probe(now);
+        }
+    }
+
+    /// Fetch one network source to find out whether the network is back
+    fn start_probe(&mut self, now: Instant) {
+        // Any network source will do; skip ones still fetching
+        let probe = self
+            .sources
+            .iter()
+            .filter(|(_, source)| source.needs_network)
+            .filter(|(widget_id, _)| self.tasks.time_until_due(widget_id, now).is_some())
+            .min_by(|(a, _), (b, _)| a.cmp(b));
+        let Some((widget_id, source)) = probe else {
+            self.next_probe = Some(now + self.probe_backoff.delay());
+            return;
+        };
+
+        tracing::debug!(widget = %widget_id, "Probing the network");
+        self.tasks
+            .spawn(widget_id, source.schedule.clone(), source.fetch());
+        self.probing = Some(widget_id.clone());
+        self.next_probe = None;
+    }
+
+    /// Time until the next source or probe is due, if any are waiting
+    pub fn time_until_next_due(&self, now: Instant) -> Option<Duration> {
+        let probe = self
+            .next_probe
+            .filter(|_| self.probing.is_none())
+            .map(|due| due.saturating_duration_since(now));
+        self.sources
+            .iter()
+            .filter(|(_, source)| self.may_fetch(source))
+            .filter_map(|(widget_id, _)| self.tasks.time_until_due(widget_id, now))
+            .chain(probe)
+            .min()
+    }
+
+    /// Whether `result` belongs to the current sources
+    pub fn is_current(&self, result: &TaskResult) -> bool {
+        self.tasks.is_current(result)
+    }
+
+    /// Mark a fetch as finished, returning the backoff delay if it failed
+    ///
+    /// Network fetches also decide whether the network is reachable: enough
+    /// connection failures in a row mark it unreachable, and any answer from
+    /// a server while unreachable brings it back. A failed probe returns
+    /// None; the source isn't to blame.
+    pub fn finish(&mut self, result: &TaskResult) -> Option<Duration> {
+        let widget_id = result.widget_id.as_str();
+        self.tasks.record_failure(result);
+        let retry_in = self.tasks.finish(widget_id, result.output.is_ok());
+        let was_probe = self.probing.as_deref() == Some(widget_id);
+        if was_probe {
+            self.probing = None;
+        }
+        if !self
+            .sources
+            .get(widget_id)
+            .is_some_and(Source::needs_network)
+        {
+            return retry_in;
+        }
+
+        let connected = match &result.output {
+            Ok(_) => true,
+            Err(e) => !is_connection_error(e),
+        };
+        match (self.network, connected) {
+            (NetworkState::Online, true) => self.connection_failures = 0,
+            (NetworkState::Online, false) => {
+                self.connection_failures += 1;
+                if self.connection_failures >= OFFLINE_AFTER_FAILURES {
+                    tracing::warn!(
+                        failures = self.connection_failures,
+                        "Network unreachable, holding network widgets"
+                    );
+                    self.set_network(NetworkState::Unreachable);
+                }
+            }
+            (NetworkState::Unreachable, true) => {
+                tracing::info!(widget = %widget_id, "Network reachable again, refreshing network widgets");
+                self.set_network(NetworkState::Online);
+            }
+            (NetworkState::Unreachable, false) if was_probe => {
+                let delay = self.probe_backoff.fail();
+                tracing::debug!(retry_in_secs = delay.as_secs(), "Network still unreachable");
+                self.next_probe = Some(Instant::now() + delay);
+                return None;
+            }
+            _ => {}
+        }
+        retry_in
+    }
+
+    /// Fetches in a row that have failed for `widget_id`
+    pub fn failures(&self, widget_id: &str) -> u32 {
+        self.tasks.failures(widget_id)
+    }
+
+    /// Health of the fetch runtime, keyed by worker name
+    pub fn worker_stats(&self, now: Instant) -> BTreeMap<String, WorkerStats> {
+        BTreeMap::from([("widget-runtime".to_string(), self.tasks.worker_stats(now))])
+    }
+
+    /// How often `widget_id`'s source expects fresh data, if it has one
+    pub fn refresh_interval(&self, widget_id: &str) -> Option<Duration> {
+        self.sources
+            .get(widget_id)?
+            .schedule
+            .period(chrono::Local::now())
+    }
+
+    fn may_fetch(&self, source: &Source) -> bool {
+        !self.network.is_offline() || !source.needs_network
+    }
+}
+
+/// Whether `error` means the network couldn't be reached, as opposed to a
+/// server answering with an error
+fn is_connection_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return e.is_connect() || e.is_timeout();
+        }
+        cause.downcast_ref::<std::io::Error>().is_some_and(|e| {
+            matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::NotConnected
+                    | std::io::ErrorKind::AddrNotAvailable
+            )
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn counting_source(count: &Arc<AtomicUsize>) -> Source {
+        let count = Arc::clone(count);
+        Source::new(Duration::from_secs(60), move || {
+            count.fetch_add(1, Ordering::SeqCst);
+            tasks::task(async { Ok(()) })
+        })
+    }
+
+    #[test]
+    fn test_offline_holds_network_sources() {
+        let (mut service, _results) = DataService::new().unwrap();
+        service.set_spread(0.0, false);
+        let network = Arc::new(AtomicUsize::new(0));
+        let local = Arc::new(AtomicUsize::new(0));
+        service.register("weather", counting_source(&network));
+        service.register("uptime", counting_source(&local).with_network(false));
+
+        assert!(service.set_online(false));
+        assert!(!service.set_online(false));
+        let now = Instant::now();
+        service.poll(now);
+        assert_eq!(network.load(Ordering::SeqCst), 0);
+        assert_eq!(local.load(Ordering::SeqCst), 1);
+        assert_eq!(service.time_until_next_due(now), None);
+
+        // Reconnecting fetches network sources straight away
+        assert!(service.set_online(true));
+        service.poll(now);
+        assert_eq!(network.load(Ordering::SeqCst), 1);
+    }
+
+    fn failed(widget_id: &str, error: anyhow::Error) -> TaskResult {
+        TaskResult {
+            widget_id: widget_id.to_string(),
+            duration: Duration::ZERO,
+            output: Err(error),
+            generation: 0,
+        }
+    }
+
+    fn timed_out() -> anyhow::Error {
+        anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::TimedOut))
+            .context("Request to https://example.com failed")
+    }
+
+    #[test]
+    fn test_unreachable_network_probes() {
+        let (mut service, _results) = DataService::new().unwrap();
+        service.set_spread(0.0, false);
+        let weather = Arc::new(AtomicUsize::new(0));
+        let stocks = Arc::new(AtomicUsize::new(0));
+        service.register("weather", counting_source(&weather));
+        service.register("stocks", counting_source(&stocks));
+
+        // A server error says the network works
+        for _ in 0..OFFLINE_AFTER_FAILURES {
+            service.finish(&failed("weather", anyhow::anyhow!("HTTP 500")));
+        }
+        assert_eq!(service.network(), NetworkState::Online);
+
+        for _ in 0..OFFLINE_AFTER_FAILURES {
+            service.finish(&failed("weather", timed_out()));
+        }
+        assert_eq!(service.network(), NetworkState::Unreachable);
+        assert!(service.is_held("stocks"));
+
+        // Only one source is fetched, once the probe is due
+        let now = Instant::now();
+        service.poll(now);
+        assert_eq!(
+            weather.load(Ordering::SeqCst) + stocks.load(Ordering::SeqCst),
+            0
+        );
+        service.poll(now + PROBE_INTERVAL);
+        assert_eq!(stocks.load(Ordering::SeqCst), 1);
+        assert_eq!(weather.load(Ordering::SeqCst), 0);
+        assert_eq!(service.time_until_next_due(now), None);
+
+        // A failed probe backs off without blaming the source
+        assert_eq!(service.finish(&failed("stocks", timed_out())), None);
+        assert_eq!(service.network(), NetworkState::Unreachable);
+        assert!(service.time_until_next_due(Instant::now()).is_some());
+
+        // Any answer brings everything back
+        service.finish(&TaskResult {
+            widget_id: "stocks".to_string(),
+            duration: Duration::ZERO,
+            output: Ok(Box::new(())),
+            generation: 0,
+        });
+        assert_eq!(service.network(), NetworkState::Online);
+        assert!(!service.is_held("stocks"));
+    }
+
+    #[test]
+    fn test_unregister_stops_polling() {
+        let (mut service, _results) = DataService::new().unwrap();
+        service.set_spread(0.0, false);
+        let count = Arc::new(AtomicUsize::new(0));
+        service.register("stocks", counting_source(&count));
+        service.unregister("stocks");
+
+        service.poll(Instant::now());
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        assert_eq!(service.time_until_next_due(Instant::now()), None);
+    }
+
+    #[test]
+    fn test_blocking_source() {
+        let source =
+            Source::blocking(Duration::from_secs(1), || Ok("42".to_string())).with_network(false);
+        assert!(!source.needs_network());
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let output = runtime.block_on(source.fetch());
+        assert_eq!(tasks::take_output::<String>(output).unwrap(), "42");
+    }
+}
@@ -0,0 +1,263 @@
+//! Supervision of the fetch runtime
+//!
+//! Every widget fetch runs on one tokio runtime in the `widget-runtime`
+//! thread. If that thread died, or a fetch blocked it, every widget with a
+//! data source would freeze on its last data without a word in the logs.
+//! The [`Supervisor`] runs a heartbeat on the runtime and checks it from the
+//! event loop: a runtime whose thread has exited, or that missed its
+//! heartbeat for [`STALL_AFTER`], is replaced by a fresh one. Replacements
+//! that keep failing are spaced out by a growing backoff.
+//!
+//! A replaced runtime is told to shut down, which it does as soon as it
+//! gets the chance, dropping whatever was still running on it.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+
+use super::backoff::Backoff;
+use super::tasks::FetchError;
+use crate::metrics::WorkerStats;
+
+/// How often the runtime proves it is alive
+pub const HEARTBEAT: Duration = Duration::from_secs(1);
+
+/// Missed heartbeats for this long mean the runtime is stuck
+pub const STALL_AFTER: Duration = Duration::from_secs(30);
+
+/// Wait before replacing a runtime again after a replacement
+const RESTART_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest wait between replacements
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A runtime that has been healthy this long resets the restart backoff
+const STABLE_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// One runtime thread
+struct Runtime {
+    handle: tokio::runtime::Handle,
+    thread: thread::JoinHandle<()>,
+    started: Instant,
+    /// Milliseconds after `started` of the last heartbeat
+    heartbeat: Arc<AtomicU64>,
+    /// Tells the thread to exit once it gets the chance
+    shutdown: Arc<AtomicBool>,
+}
+
+impl Runtime {
+    fn spawn() -> Result<Self> {
+        let (handle_tx, handle_rx) = mpsc::channel();
+        let started = Instant::now();
+        let heartbeat = Arc::new(AtomicU64::new(0));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let beat = Arc::clone(&heartbeat);
+        let stop = Arc::clone(&shutdown);
+        let thread = thread::Builder::new()
+            .name("widget-runtime".to_string())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        let _ = handle_tx.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = handle_tx.send(Ok(runtime.handle().clone()));
+
+                // Spawned tasks run while the heartbeat does, for as long
+                // as the runtime is wanted
+                runtime.block_on(async move {
+                    while !stop.load(Ordering::Relaxed) {
+                        beat.store(started.elapsed().as_millis() as u64, Ordering::Relaxed);
+                        tokio::time::sleep(HEARTBEAT).await;
+                    }
+                });
+            })
+            .context("Failed to spawn widget runtime thread")?;
+
+        let handle = handle_rx
+            .recv()
+            .context("Widget runtime thread exited")?
+            .context("Failed to build widget runtime")?;
+        Ok(Self {
+            handle,
+            thread,
+            started,
+            heartbeat,
+            shutdown,
+        })
+    }
+
+    /// Time since the last heartbeat
+    fn silent_for(&self, now: Instant) -> Duration {
+        let last = self.started + Duration::from_millis(self.heartbeat.load(Ordering::Relaxed));
+        now.saturating_duration_since(last)
+    }
+
+    /// What is wrong with the runtime, if anything
+    fn problem(&self, now: Instant, stall_after: Duration) -> Option<String> {
+        if self.thread.is_finished() {
+            return Some("Widget runtime thread exited".to_string());
+        }
+        let silent = self.silent_for(now);
+        (silent > stall_after).then(|| {
+            format!(
+                "Widget runtime unresponsive for {}s, a fetch may be blocking it",
+                silent.as_secs()
+            )
+        })
+    }
+}
+
+impl Drop for Runtime {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Keeps a working runtime for widget fetches
+pub struct Supervisor {
+    runtime: Runtime,
+    stall_after: Duration,
+    backoff: Backoff,
+    /// No replacement before this, while the backoff runs
+    restart_at: Option<Instant>,
+    restarts: u32,
+    fetch_timeouts: u64,
+    fetch_panics: u64,
+    last_problem: Option<String>,
+}
+
+impl Supervisor {
+    /// Start the runtime thread
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            runtime: Runtime::spawn()?,
+            stall_after: STALL_AFTER,
+            backoff: Backoff::new(RESTART_BACKOFF, MAX_RESTART_BACKOFF),
+            restart_at: None,
+            restarts: 0,
+            fetch_timeouts: 0,
+            fetch_panics: 0,
+            last_problem: None,
+        })
+    }
+
+    /// Count the runtime as stuck after `stall_after` without a heartbeat
+    pub fn with_stall_after(mut self, stall_after: Duration) -> Self {
+        self.stall_after = stall_after.max(HEARTBEAT * 2);
+        self
+    }
+
+    /// Handle to spawn fetches on
+    pub fn handle(&self) -> &tokio::runtime::Handle {
+        &self.runtime.handle
+    }
+
+    /// Replace the runtime if it died or stalled
+    ///
+    /// Returns true if it was replaced; fetches that were running on the
+    /// old one won't report back.
+    pub fn check(&mut self, now: Instant) -> bool {
+        let Some(problem) = self.runtime.problem(now, self.stall_after) else {
+            if self.backoff.is_failing() && now >= self.runtime.started + STABLE_AFTER {
+                self.backoff.reset();
+            }
+            return false;
+        };
+        if self.restart_at.is_some_and(|at| now < at) {
+            return false;
+        }
+
+        tracing::error!(restarts = self.restarts, "{}, restarting it", problem);
+        self.last_problem = Some(problem);
+        let replaced = match Runtime::spawn() {
+            Ok(runtime) => {
+                // Dropping the old runtime tells its thread to exit
+                self.runtime = runtime;
+                self.restarts += 1;
+                true
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to restart widget runtime");
+                false
+            }
+        };
+        self.restart_at = Some(now + self.backoff.fail());
+        replaced
+    }
+
+    /// Count a fetch that timed out or panicked
+    pub fn record_fetch_error(&mut self, widget_id: &str, error: &FetchError) {
+        match error {
+            FetchError::TimedOut(_) => self.fetch_timeouts += 1,
+            FetchError::Panicked(_) => self.fetch_panics += 1,
+        }
+        self.last_problem = Some(format!("{}: {}", widget_id, error));
+    }
+
+    /// Health figures for the metrics
+    pub fn stats(&self, now: Instant) -> WorkerStats {
+        WorkerStats {
+            healthy: self.runtime.problem(now, self.stall_after).is_none(),
+            restarts: self.restarts,
+            last_heartbeat_ms: self.runtime.silent_for(now).as_secs_f64() * 1000.0,
+            fetch_timeouts: self.fetch_timeouts,
+            fetch_panics: self.fetch_panics,
+            last_problem: self.last_problem.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether a task spawned on `supervisor`'s runtime gets to run
+    fn runs_tasks(supervisor: &Supervisor) -> bool {
+        let (tx, rx) = mpsc::channel();
+        supervisor.handle().spawn(async move {
+            let _ = tx.send(());
+        });
+        rx.recv_timeout(Duration::from_secs(5)).is_ok()
+    }
+
+    #[test]
+    fn test_healthy_runtime_is_left_alone() {
+        let mut supervisor = Supervisor::new().unwrap();
+        assert!(runs_tasks(&supervisor));
+        assert!(!supervisor.check(Instant::now()));
+
+        let stats = supervisor.stats(Instant::now());
+        assert!(stats.healthy);
+        assert_eq!(stats.restarts, 0);
+    }
+
+    #[test]
+    fn test_stalled_runtime_is_replaced() {
+        let mut supervisor = Supervisor::new()
+            .unwrap()
+            .with_stall_after(Duration::from_secs(2));
+
+        // As seen once a fetch has blocked the runtime's thread for a while
+        let later = Instant::now() + Duration::from_secs(3);
+        assert!(!supervisor.stats(later).healthy);
+
+        assert!(supervisor.check(later));
+        assert!(runs_tasks(&supervisor));
+        let stats = supervisor.stats(Instant::now());
+        assert_eq!(stats.restarts, 1);
+        assert!(stats.last_problem.unwrap().contains("unresponsive"));
+
+        // Backs off before replacing it again
+        assert!(!supervisor.check(later));
+    }
+}
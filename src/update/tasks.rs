@@ -0,0 +1,688 @@
+//! Shared async runtime for widget fetches
+//!
+//! Widgets don't start threads or runtimes of their own. Their fetches are
+//! handed to the [`TaskManager`] (by the [`DataService`](super::DataService)),
+//! which runs them on a single tokio runtime in a background thread and sends
+//! each result back over one calloop channel, tagged with the widget's
+//! instance id. The event loop passes each result to the widget it belongs to.
+//!
+//! Fetches are spread out so widgets sharing an interval don't all hit the
+//! network at once: first fetches are staggered [`STAGGER_STEP`] apart, and
+//! every interval after that is stretched or shrunk by a random jitter.
+//!
+//! Sources on a cron [`Schedule`] run when their expression next matches,
+//! without jitter, so "on the hour" means on the hour.
+//!
+//! A widget whose fetch fails backs off exponentially: its interval doubles
+//! with each failure in a row, up to [`MAX_BACKOFF`], and drops back to
+//! normal after the next success.
+//!
+//! Replacing the widgets (a config reload) cancels every fetch still running
+//! for the old ones. Each result carries the generation it was started in, so
+//! anything that finished just before the cancel is dropped too. Replacing a
+//! single widget (a plugin reload) starts a new generation for that widget
+//! alone.
+//!
+//! A fetch that panics, or runs longer than [`FETCH_TIMEOUT`], ends with a
+//! [`FetchError`] instead of leaving its widget waiting forever. The runtime
+//! itself is watched by a [`Supervisor`], which replaces it if it dies or
+//! stops responding.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use calloop::channel::{channel, Channel, Sender};
+use thiserror::Error;
+use tokio::task::AbortHandle;
+
+use super::backoff::{Backoff, MAX_BACKOFF};
+use super::schedule::Schedule;
+use super::supervisor::Supervisor;
+use crate::crash;
+use crate::metrics::WorkerStats;
+
+/// Gap between the first fetches of widgets that become due together
+pub const STAGGER_STEP: Duration = Duration::from_millis(500);
+
+/// Default jitter, as a fraction of the interval either way
+pub const DEFAULT_JITTER: f64 = 0.1;
+
+/// First retry for a failing cron source, rather than waiting a whole period
+pub const CRON_RETRY: Duration = Duration::from_secs(60);
+
+/// Longest a fetch may run before it is abandoned
+pub const FETCH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Why a fetch ended without producing anything of its own
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FetchError {
+    #[error("Fetch abandoned after {}s", .0.as_secs())]
+    TimedOut(Duration),
+
+    #[error("Fetch panicked: {0}")]
+    Panicked(String),
+}
+
+/// Value produced by a widget task, downcast by the widget that started it
+pub type TaskOutput = Box<dyn Any + Send>;
+
+/// A widget's background fetch
+pub type TaskFuture = Pin<Box<dyn Future<Output = Result<TaskOutput>> + Send>>;
+
+/// Box a fetch so it can be handed to the [`TaskManager`]
+pub fn task<T, F>(future: F) -> TaskFuture
+where
+    T: Send + 'static,
+    F: Future<Output = Result<T>> + Send + 'static,
+{
+    Box::pin(async move { future.await.map(|value| Box::new(value) as TaskOutput) })
+}
+
+/// Unpack the result of a fetch built with [`task`]
+pub fn take_output<T: 'static>(result: Result<TaskOutput>) -> Result<T> {
+    result?
+        .downcast::<T>()
+        .map(|value| *value)
+        .map_err(|_| anyhow!("Task returned an unexpected type"))
+}
+
+/// A finished fetch, tagged with the widget that started it
+pub struct TaskResult {
+    /// Instance id of the widget
+    pub widget_id: String,
+    /// How long the fetch took
+    pub duration: Duration,
+    /// What the fetch produced
+    pub output: Result<TaskOutput>,
+    /// Widget generation the fetch was started in
+    pub generation: u64,
+}
+
+/// Runs widget fetches on the shared runtime and tracks when each is due
+pub struct TaskManager {
+    runtime: Supervisor,
+    results: Sender<TaskResult>,
+    /// Longest a fetch may run
+    fetch_timeout: Duration,
+    /// Fetches still running, so they can be cancelled
+    in_flight: HashMap<String, AbortHandle>,
+    /// Generation of every widget without one of its own, renewed each
+    /// time the widgets are replaced
+    generation: u64,
+    /// Generations of widgets replaced on their own since then
+    widget_generations: HashMap<String, u64>,
+    /// Last generation handed out, so none is used twice
+    last_generation: u64,
+    next_due: HashMap<String, Instant>,
+    /// Backoff for each widget that has fetched, keyed by instance id
+    backoff: HashMap<String, Backoff>,
+    /// Fraction of each interval to randomly add or remove
+    jitter: f64,
+    /// Whether first fetches are spread [`STAGGER_STEP`] apart
+    stagger: bool,
+    /// Slot handed to the most recently staggered first fetch
+    last_slot: Option<Instant>,
+}
+
+impl TaskManager {
+    /// Start the runtime thread
+    ///
+    /// Returns the manager along with the channel results arrive on, which
+    /// belongs in the event loop.
+    pub fn new() -> Result<(Self, Channel<TaskResult>)> {
+        let runtime = Supervisor::new()?;
+        let (results, channel) = channel();
+
+        tracing::debug!("Widget runtime started");
+        Ok((
+            Self {
+                runtime,
+                results,
+                fetch_timeout: FETCH_TIMEOUT,
+                in_flight: HashMap::new(),
+                generation: 0,
+                widget_generations: HashMap::new(),
+                last_generation: 0,
+                next_due: HashMap::new(),
+                backoff: HashMap::new(),
+                jitter: DEFAULT_JITTER,
+                stagger: true,
+                last_slot: None,
+            },
+            channel,
+        ))
+    }
+
+    /// Set the interval jitter (clamped to 0..=0.5) and whether to stagger
+    pub fn set_spread(&mut self, jitter: f64, stagger: bool) {
+        self.jitter = jitter.clamp(0.0, 0.5);
+        self.stagger = stagger;
+    }
+
+    /// Abandon fetches that run longer than `timeout`
+    pub fn set_fetch_timeout(&mut self, timeout: Duration) {
+        self.fetch_timeout = timeout;
+    }
+
+    /// Replace the runtime if it died or stalled
+    ///
+    /// The fetches that were running on it are lost, so those widgets are
+    /// due again straight away.
+    pub fn supervise(&mut self, now: Instant) {
+        if !self.runtime.check(now) {
+            return;
+        }
+        for (widget_id, _) in self.in_flight.drain() {
+            self.next_due.insert(widget_id, now);
+        }
+        // Anything the old runtime still manages to send is stale
+        self.renew_generation();
+    }
+
+    /// Health of the runtime, for the metrics
+    pub fn worker_stats(&self, now: Instant) -> WorkerStats {
+        self.runtime.stats(now)
+    }
+
+    /// Count a fetch that timed out or panicked in the runtime's health
+    pub fn record_failure(&mut self, result: &TaskResult) {
+        let error = match &result.output {
+            Err(e) => e.downcast_ref::<FetchError>(),
+            Ok(_) => None,
+        };
+        if let Some(error) = error {
+            tracing::warn!(widget = %result.widget_id, "{}", error);
+            self.runtime.record_fetch_error(&result.widget_id, error);
+        }
+    }
+
+    /// Whether `widget_id` should start a new fetch
+    ///
+    /// A widget that never fetched is given the next free stagger slot, then
+    /// is due each time its interval has passed, but never while the previous
+    /// fetch is still running.
+    pub fn is_due(&mut self, widget_id: &str, now: Instant) -> bool {
+        if self.in_flight.contains_key(widget_id) {
+            return false;
+        }
+        let due = match self.next_due.get(widget_id) {
+            Some(due) => *due,
+            None => {
+                let slot = self.first_slot(now);
+                self.next_due.insert(widget_id.to_string(), slot);
+                slot
+            }
+        };
+        now >= due
+    }
+
+    /// Time until `widget_id` is due, or None while its fetch is running
+    ///
+    /// A widget that hasn't been scheduled yet is due right away.
+    pub fn time_until_due(&self, widget_id: &str, now: Instant) -> Option<Duration> {
+        if self.in_flight.contains_key(widget_id) {
+            return None;
+        }
+        Some(
+            self.next_due
+                .get(widget_id)
+                .map_or(Duration::ZERO, |due| due.saturating_duration_since(now)),
+        )
+    }
+
+    fn first_slot(&mut self, now: Instant) -> Instant {
+        if !self.stagger {
+            return now;
+        }
+        let slot = match self.last_slot {
+            Some(last) if last + STAGGER_STEP > now => last + STAGGER_STEP,
+            _ => now,
+        };
+        self.last_slot = Some(slot);
+        slot
+    }
+
+    /// Run `task` for `widget_id`; it is due again after about one interval,
+    /// or when a cron schedule next matches
+    pub fn spawn(&mut self, widget_id: &str, schedule: impl Into<Schedule>, task: TaskFuture) {
+        let started = Instant::now();
+        let (delay, retry) = match schedule.into() {
+            Schedule::Every(interval) => {
+                (jittered(interval, self.jitter, rand::random()), interval)
+            }
+            cron => (cron.delay_from(chrono::Local::now()), CRON_RETRY),
+        };
+        self.backoff
+            .entry(widget_id.to_string())
+            .or_insert_with(|| Backoff::new(retry, MAX_BACKOFF));
+        self.next_due.insert(widget_id.to_string(), started + delay);
+
+        tracing::debug!(widget = %widget_id, "Starting widget fetch");
+        let results = self.results.clone();
+        let generation = self.generation_of(widget_id);
+        let id = widget_id.to_string();
+        let timeout = self.fetch_timeout;
+        let runtime = self.runtime.handle();
+
+        // The fetch runs as its own task so a panic stays inside it
+        let fetch = runtime.spawn(task);
+        let abort = fetch.abort_handle();
+        self.in_flight
+            .insert(widget_id.to_string(), fetch.abort_handle());
+        runtime.spawn(async move {
+            let output = match tokio::time::timeout(timeout, fetch).await {
+                Ok(Ok(output)) => output,
+                Ok(Err(e)) if e.is_panic() => {
+                    Err(FetchError::Panicked(crash::panic_message(&*e.into_panic())).into())
+                }
+                // Cancelled
+                Ok(Err(_)) => return,
+                Err(_) => {
+                    abort.abort();
+                    Err(FetchError::TimedOut(timeout).into())
+                }
+            };
+            // Only fails once the event loop is gone
+            let _ = results.send(TaskResult {
+                widget_id: id,
+                duration: started.elapsed(),
+                output,
+                generation,
+            });
+        });
+    }
+
+    /// Whether `result` belongs to the current widgets
+    ///
+    /// False for fetches started before the last [`TaskManager::cancel_all`],
+    /// or [`TaskManager::cancel`] for their widget, that finished before they
+    /// could be aborted; drop those.
+    pub fn is_current(&self, result: &TaskResult) -> bool {
+        result.generation == self.generation_of(&result.widget_id)
+    }
+
+    /// Generation fetches for `widget_id` are started in
+    fn generation_of(&self, widget_id: &str) -> u64 {
+        self.widget_generations
+            .get(widget_id)
+            .copied()
+            .unwrap_or(self.generation)
+    }
+
+    /// A generation not used before
+    fn next_generation(&mut self) -> u64 {
+        self.last_generation += 1;
+        self.last_generation
+    }
+
+    /// Start a new generation for every widget
+    fn renew_generation(&mut self) {
+        self.generation = self.next_generation();
+        self.widget_generations.clear();
+    }
+
+    /// Mark the fetch for `widget_id` as finished
+    ///
+    /// After a failure the next fetch is pushed back by the widget's backoff,
+    /// which is returned; a success resets it.
+    pub fn finish(&mut self, widget_id: &str, succeeded: bool) -> Option<Duration> {
+        self.in_flight.remove(widget_id);
+        let backoff = self.backoff.get_mut(widget_id)?;
+        if succeeded {
+            if backoff.is_failing() {
+                tracing::info!(widget = %widget_id, "Widget fetch recovered");
+            }
+            backoff.reset();
+            return None;
+        }
+
+        let delay = jittered(backoff.fail(), self.jitter, rand::random());
+        self.next_due
+            .insert(widget_id.to_string(), Instant::now() + delay);
+        Some(delay)
+    }
+
+    /// Fetches in a row that have failed for `widget_id`
+    pub fn failures(&self, widget_id: &str) -> u32 {
+        self.backoff.get(widget_id).map_or(0, Backoff::failures)
+    }
+
+    /// Make every widget due again, e.g. after its config changed
+    pub fn reschedule_all(&mut self) {
+        self.next_due.clear();
+        self.backoff.clear();
+    }
+
+    /// Abort every running fetch and start a new generation
+    ///
+    /// Called when the widgets are replaced, so fetches for the old ones
+    /// neither keep running nor deliver results to their successors. Every
+    /// widget is due again afterwards.
+    pub fn cancel_all(&mut self) {
+        if !self.in_flight.is_empty() {
+            tracing::debug!(count = self.in_flight.len(), "Cancelling widget fetches");
+        }
+        for (_, handle) in self.in_flight.drain() {
+            handle.abort();
+        }
+        self.renew_generation();
+        self.reschedule_all();
+    }
+
+    /// Abort the running fetch for `widget_id`, e.g. when it alone is replaced
+    ///
+    /// Starts a new generation for the widget, so a result that was already
+    /// on its way doesn't reach its replacement.
+    pub fn cancel(&mut self, widget_id: &str) {
+        if let Some(handle) = self.in_flight.remove(widget_id) {
+            handle.abort();
+        }
+        let generation = self.next_generation();
+        self.widget_generations
+            .insert(widget_id.to_string(), generation);
+        self.next_due.remove(widget_id);
+        self.backoff.remove(widget_id);
+    }
+}
+
+/// Scale `interval` by `1 ± jitter`, with `unit` in `0.0..1.0` picking where
+fn jittered(interval: Duration, jitter: f64, unit: f64) -> Duration {
+    interval.mul_f64(1.0 + jitter * (unit * 2.0 - 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_tagged_by_widget() {
+        let (mut tasks, channel) = TaskManager::new().unwrap();
+        let mut event_loop = calloop::EventLoop::<Vec<TaskResult>>::try_new().unwrap();
+        event_loop
+            .handle()
+            .insert_source(channel, |event, _, results| {
+                if let calloop::channel::Event::Msg(result) = event {
+                    results.push(result);
+                }
+            })
+            .unwrap();
+
+        let interval = Duration::from_secs(60);
+        tasks.spawn("weather", interval, task(async { Ok(21.5_f32) }));
+        tasks.spawn(
+            "stocks",
+            interval,
+            task(async { Err::<f32, _>(anyhow!("offline")) }),
+        );
+
+        let mut results = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while results.len() < 2 && Instant::now() < deadline {
+            event_loop
+                .dispatch(Some(Duration::from_millis(50)), &mut results)
+                .unwrap();
+        }
+        results.sort_by(|a, b| a.widget_id.cmp(&b.widget_id));
+
+        let mut results = results.into_iter();
+        let stocks = results.next().unwrap();
+        assert_eq!(stocks.widget_id, "stocks");
+        assert!(take_output::<f32>(stocks.output).is_err());
+
+        let weather = results.next().unwrap();
+        assert_eq!(weather.widget_id, "weather");
+        assert_eq!(take_output::<f32>(weather.output).unwrap(), 21.5);
+    }
+
+    #[test]
+    fn test_cancel_all() {
+        let (mut tasks, channel) = TaskManager::new().unwrap();
+        let mut event_loop = calloop::EventLoop::<Vec<TaskResult>>::try_new().unwrap();
+        event_loop
+            .handle()
+            .insert_source(channel, |event, _, results| {
+                if let calloop::channel::Event::Msg(result) = event {
+                    results.push(result);
+                }
+            })
+            .unwrap();
+
+        let interval = Duration::from_secs(60);
+        tasks.spawn(
+            "weather",
+            interval,
+            task(async {
+                std::future::pending::<()>().await;
+                Ok(())
+            }),
+        );
+        tasks.spawn("crypto", interval, task(async { Ok(()) }));
+        assert!(!tasks.is_due("weather", Instant::now()));
+
+        let mut results = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while results.is_empty() && Instant::now() < deadline {
+            event_loop
+                .dispatch(Some(Duration::from_millis(50)), &mut results)
+                .unwrap();
+        }
+        assert!(tasks.is_current(&results[0]));
+
+        // The stuck fetch is aborted and late results are recognised
+        tasks.cancel_all();
+        assert!(tasks.in_flight.is_empty());
+        assert!(!tasks.is_current(&results[0]));
+        tasks.set_spread(0.0, false);
+        assert!(tasks.is_due("weather", Instant::now()));
+    }
+
+    #[test]
+    fn test_cancel_one_widget() {
+        let (mut tasks, channel) = TaskManager::new().unwrap();
+        let mut event_loop = calloop::EventLoop::<Vec<TaskResult>>::try_new().unwrap();
+        event_loop
+            .handle()
+            .insert_source(channel, |event, _, results| {
+                if let calloop::channel::Event::Msg(result) = event {
+                    results.push(result);
+                }
+            })
+            .unwrap();
+
+        let interval = Duration::from_secs(60);
+        tasks.spawn("plugin", interval, task(async { Ok(()) }));
+        tasks.spawn("crypto", interval, task(async { Ok(()) }));
+
+        let mut results = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while results.len() < 2 && Instant::now() < deadline {
+            event_loop
+                .dispatch(Some(Duration::from_millis(50)), &mut results)
+                .unwrap();
+        }
+        results.sort_by(|a, b| a.widget_id.cmp(&b.widget_id));
+        assert!(results.iter().all(|result| tasks.is_current(result)));
+
+        // A result that arrives after its widget was replaced is dropped,
+        // others are still delivered
+        tasks.cancel("plugin");
+        assert!(tasks.is_current(&results[0]));
+        assert!(!tasks.is_current(&results[1]));
+
+        // The replacement's own fetches count again
+        tasks.spawn("plugin", interval, task(async { Ok(()) }));
+        results.clear();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while results.is_empty() && Instant::now() < deadline {
+            event_loop
+                .dispatch(Some(Duration::from_millis(50)), &mut results)
+                .unwrap();
+        }
+        assert!(tasks.is_current(&results[0]));
+    }
+
+    #[test]
+    fn test_stuck_and_panicking_fetches() {
+        let (mut tasks, channel) = TaskManager::new().unwrap();
+        let mut event_loop = calloop::EventLoop::<Vec<TaskResult>>::try_new().unwrap();
+        event_loop
+            .handle()
+            .insert_source(channel, |event, _, results| {
+                if let calloop::channel::Event::Msg(result) = event {
+                    results.push(result);
+                }
+            })
+            .unwrap();
+
+        let timeout = Duration::from_millis(100);
+        tasks.set_fetch_timeout(timeout);
+        tasks.spawn(
+            "weather",
+            Duration::from_secs(60),
+            task(async {
+                std::future::pending::<()>().await;
+                Ok(())
+            }),
+        );
+        tasks.spawn(
+            "crypto",
+            Duration::from_secs(60),
+            task(async {
+                let response: Option<u32> = None;
+                Ok(response.expect("bad response"))
+            }),
+        );
+
+        let mut results = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while results.len() < 2 && Instant::now() < deadline {
+            event_loop
+                .dispatch(Some(Duration::from_millis(50)), &mut results)
+                .unwrap();
+        }
+        results.sort_by(|a, b| a.widget_id.cmp(&b.widget_id));
+
+        let error = |result: &TaskResult| {
+            let error = result.output.as_ref().err().unwrap();
+            error.downcast_ref::<FetchError>().cloned()
+        };
+        assert_eq!(
+            error(&results[0]),
+            Some(FetchError::Panicked("bad response".to_string()))
+        );
+        assert_eq!(error(&results[1]), Some(FetchError::TimedOut(timeout)));
+
+        for result in &results {
+            tasks.record_failure(result);
+            tasks.finish(&result.widget_id, false);
+        }
+        let stats = tasks.worker_stats(Instant::now());
+        assert_eq!((stats.fetch_timeouts, stats.fetch_panics), (1, 1));
+        assert!(stats.healthy);
+    }
+
+    #[test]
+    fn test_schedule() {
+        let (mut tasks, _channel) = TaskManager::new().unwrap();
+        tasks.set_spread(0.0, false);
+        let now = Instant::now();
+        assert!(tasks.is_due("crypto", now));
+
+        let interval = Duration::from_secs(60);
+        tasks.spawn("crypto", interval, task(async { Ok(()) }));
+        assert!(!tasks.is_due("crypto", now + interval * 2));
+
+        assert_eq!(tasks.finish("crypto", true), None);
+        assert!(!tasks.is_due("crypto", now));
+        assert!(tasks.is_due("crypto", now + interval * 2));
+
+        tasks.reschedule_all();
+        assert!(tasks.is_due("crypto", now));
+    }
+
+    #[test]
+    fn test_cron_schedule() {
+        let (mut tasks, _channel) = TaskManager::new().unwrap();
+        tasks.set_spread(0.5, false);
+        let now = Instant::now();
+
+        let hourly = Schedule::parse("@hourly").unwrap();
+        let expected = hourly.delay_from(chrono::Local::now());
+        tasks.spawn("quotes", hourly, task(async { Ok(()) }));
+        tasks.finish("quotes", true);
+
+        // Not jittered: due within a second of the top of the hour
+        let remaining = tasks.time_until_due("quotes", now).unwrap();
+        assert!(remaining.max(expected) - remaining.min(expected) < Duration::from_secs(2));
+
+        // Failures retry well before the next match
+        tasks.spawn(
+            "quotes",
+            Schedule::parse("@daily").unwrap(),
+            task(async { Ok(()) }),
+        );
+        let delay = tasks.finish("quotes", false).unwrap();
+        assert!(delay <= CRON_RETRY.mul_f64(1.5));
+    }
+
+    #[test]
+    fn test_failures_back_off() {
+        let (mut tasks, _channel) = TaskManager::new().unwrap();
+        tasks.set_spread(0.0, false);
+        let interval = Duration::from_secs(60);
+
+        for expected in [interval, interval * 2, interval * 4] {
+            tasks.spawn("stocks", interval, task(async { Ok(()) }));
+            assert_eq!(tasks.finish("stocks", false), Some(expected));
+        }
+        assert_eq!(tasks.failures("stocks"), 3);
+        assert!(!tasks.is_due("stocks", Instant::now() + interval * 3));
+
+        tasks.spawn("stocks", interval, task(async { Ok(()) }));
+        assert_eq!(tasks.finish("stocks", true), None);
+        assert_eq!(tasks.failures("stocks"), 0);
+        tasks.spawn("stocks", interval, task(async { Ok(()) }));
+        assert_eq!(tasks.finish("stocks", false), Some(interval));
+    }
+
+    #[test]
+    fn test_first_fetches_staggered() {
+        let (mut tasks, _channel) = TaskManager::new().unwrap();
+        let now = Instant::now();
+
+        assert!(tasks.is_due("weather", now));
+        assert!(!tasks.is_due("crypto", now));
+        assert!(!tasks.is_due("stocks", now));
+        assert_eq!(tasks.time_until_due("weather", now), Some(Duration::ZERO));
+        assert_eq!(tasks.time_until_due("stocks", now), Some(STAGGER_STEP * 2));
+
+        assert!(tasks.is_due("crypto", now + STAGGER_STEP));
+        assert!(!tasks.is_due("stocks", now + STAGGER_STEP));
+        assert!(tasks.is_due("stocks", now + STAGGER_STEP * 2));
+
+        // Without staggering everything is due at once
+        tasks.set_spread(DEFAULT_JITTER, false);
+        tasks.reschedule_all();
+        assert!(tasks.is_due("weather", now));
+        assert!(tasks.is_due("crypto", now));
+    }
+
+    #[test]
+    fn test_jittered() {
+        let minute = Duration::from_secs(60);
+        assert_eq!(jittered(minute, 0.0, 0.9), minute);
+        assert_eq!(jittered(minute, 0.1, 0.5), minute);
+        assert!((jittered(minute, 0.1, 0.0).as_secs_f64() - 54.0).abs() < 1e-6);
+        assert!(jittered(minute, 0.1, 0.999) <= Duration::from_secs(66));
+    }
+
+    #[test]
+    fn test_take_output_type_mismatch() {
+        let output: Result<TaskOutput> = Ok(Box::new("text"));
+        assert!(take_output::<u32>(output).is_err());
+    }
+}
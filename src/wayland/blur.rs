@@ -0,0 +1,242 @@
+//! Compositor blur behind widget surfaces
+//!
+//! Uses KDE's `org_kde_kwin_blur_manager` protocol to ask the compositor to
+//! blur whatever lies behind a surface, which is what makes the glass theme
+//! look frosted rather than just translucent. The blur region follows the
+//! widget's rounded corners so the blur doesn't poke out past them.
+//!
+//! The protocol is optional: on compositors that don't advertise it,
+//! [`BlurState::is_available`] is false and surfaces are simply drawn with
+//! their translucent background.
+
+use smithay_client_toolkit::{
+    compositor::{CompositorState, Region},
+    globals::GlobalData,
+};
+use wayland_client::{
+    globals::GlobalList, protocol::wl_surface::WlSurface, Connection, Dispatch, QueueHandle,
+};
+use wayland_protocols_plasma::blur::client::{
+    org_kde_kwin_blur::OrgKdeKwinBlur, org_kde_kwin_blur_manager::OrgKdeKwinBlurManager,
+};
+
+/// Blur manager global, if the compositor provides one
+#[derive(Debug)]
+pub struct BlurState {
+    manager: Option<OrgKdeKwinBlurManager>,
+}
+
+/// Blur applied to one surface
+///
+/// Remembers the geometry the region was built for so it's only rebuilt
+/// when the surface is resized or the corner radius changes.
+#[derive(Debug)]
+pub struct SurfaceBlur {
+    blur: OrgKdeKwinBlur,
+    width: u32,
+    height: u32,
+    radius: u32,
+}
+
+impl Drop for SurfaceBlur {
+    fn drop(&mut self) {
+        self.blur.release();
+    }
+}
+
+impl BlurState {
+    /// Bind the blur manager if the compositor advertises it
+    pub fn bind<D>(globals: &GlobalList, qh: &QueueHandle<D>) -> Self
+    where
+        D: Dispatch<OrgKdeKwinBlurManager, GlobalData> + 'static,
+    {
+        let manager = match globals.bind(qh, 1..=1, GlobalData) {
+            Ok(manager) => {
+                tracing::info!("Compositor blur available (org_kde_kwin_blur_manager)");
+                Some(manager)
+            }
+            Err(e) => {
+                tracing::debug!(error = %e, "Compositor blur not available");
+                None
+            }
+        };
+        Self { manager }
+    }
+
+    /// Whether the compositor supports blur
+    pub fn is_available(&self) -> bool {
+        self.manager.is_some()
+    }
+
+    /// Enable, resize or remove the blur behind `surface`
+    ///
+    /// `current` holds the surface's existing blur and is updated in place.
+    /// Changes take effect on the surface's next commit.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update<D>(
+        &self,
+        current: &mut Option<SurfaceBlur>,
+        surface: &WlSurface,
+        compositor: &CompositorState,
+        enabled: bool,
+        width: u32,
+        height: u32,
+        corner_radius: f32,
+        qh: &QueueHandle<D>,
+    ) where
+        D: Dispatch<OrgKdeKwinBlur, ()> + 'static,
+    {
+        let Some(manager) = &self.manager else {
+            return;
+        };
+
+        if !enabled {
+            if current.take().is_some() {
+                manager.unset(surface);
+            }
+            return;
+        }
+
+        let radius = corner_radius.max(0.0).round() as u32;
+        if let Some(blur) = current {
+            if (blur.width, blur.height, blur.radius) == (width, height, radius) {
+                return;
+            }
+        }
+
+        let region = match Region::new(compositor) {
+            Ok(region) => region,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to create blur region");
+                return;
+            }
+        };
+        for (x, y, w, h) in rounded_rects(width, height, radius) {
+            region.add(x, y, w, h);
+        }
+
+        // Reuse the surface's blur object so only one is ever attached
+        let mut blur = current.take().unwrap_or_else(|| SurfaceBlur {
+            blur: manager.create(surface, qh, ()),
+            width,
+            height,
+            radius,
+        });
+        blur.blur.set_region(Some(region.wl_region()));
+        blur.blur.commit();
+        blur.width = width;
+        blur.height = height;
+        blur.radius = radius;
+        *current = Some(blur);
+    }
+}
+
+/// Rectangles covering a rounded rectangle, row bands merged
+///
+/// Wayland regions are unions of rectangles, so the rounded corners are
+/// approximated one pixel row at a time; rows with the same inset are merged.
+fn rounded_rects(width: u32, height: u32, radius: u32) -> Vec<(i32, i32, i32, i32)> {
+    let (w, h) = (width as i32, height as i32);
+    let r = radius.min(width / 2).min(height / 2) as i32;
+    if r == 0 {
+        return vec![(0, 0, w, h)];
+    }
+
+    // Horizontal inset of each row in the top corner band
+    let insets: Vec<i32> = (0..r)
+        .map(|y| {
+            let dy = r as f32 - y as f32 - 0.5;
+            (r as f32 - (r as f32 * r as f32 - dy * dy).max(0.0).sqrt()).round() as i32
+        })
+        .collect();
+
+    let mut rects = Vec::new();
+    let mut start = 0;
+    while start < insets.len() {
+        let inset = insets[start];
+        let mut end = start + 1;
+        while end < insets.len() && insets[end] == inset {
+            end += 1;
+        }
+        let band = (end - start) as i32;
+        rects.push((inset, start as i32, w - 2 * inset, band));
+        rects.push((inset, h - end as i32, w - 2 * inset, band));
+        start = end;
+    }
+    if h > 2 * r {
+        rects.push((0, r, w, h - 2 * r));
+    }
+    rects
+}
+
+impl<D> Dispatch<OrgKdeKwinBlurManager, GlobalData, D> for BlurState
+where
+    D: Dispatch<OrgKdeKwinBlurManager, GlobalData>,
+{
+    fn event(
+        _state: &mut D,
+        _proxy: &OrgKdeKwinBlurManager,
+        _event: <OrgKdeKwinBlurManager as wayland_client::Proxy>::Event,
+        _data: &GlobalData,
+        _conn: &Connection,
+        _qh: &QueueHandle<D>,
+    ) {
+        unreachable!("org_kde_kwin_blur_manager has no events")
+    }
+}
+
+impl<D> Dispatch<OrgKdeKwinBlur, (), D> for BlurState
+where
+    D: Dispatch<OrgKdeKwinBlur, ()>,
+{
+    fn event(
+        _state: &mut D,
+        _proxy: &OrgKdeKwinBlur,
+        _event: <OrgKdeKwinBlur as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<D>,
+    ) {
+        unreachable!("org_kde_kwin_blur has no events")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(rects: &[(i32, i32, i32, i32)]) -> i32 {
+        rects.iter().map(|(_, _, w, h)| w * h).sum()
+    }
+
+    #[test]
+    fn test_square_corners_are_one_rect() {
+        assert_eq!(rounded_rects(200, 100, 0), vec![(0, 0, 200, 100)]);
+    }
+
+    #[test]
+    fn test_rounded_rects_cut_corners() {
+        let rects = rounded_rects(200, 100, 12);
+
+        // Rows don't overlap and together span the full height
+        let rows: i32 = rects.iter().map(|(_, _, _, h)| h).sum();
+        assert_eq!(rows, 100);
+
+        // Some area is trimmed from the corners, but not much
+        let full = 200 * 100;
+        assert!(area(&rects) < full);
+        assert!(area(&rects) > full - 4 * 12 * 12);
+
+        // The top row is inset the most, the middle band not at all
+        assert!(rects[0].0 > 0);
+        assert_eq!(*rects.last().unwrap(), (0, 12, 200, 76));
+    }
+
+    #[test]
+    fn test_radius_clamped_to_surface() {
+        let rects = rounded_rects(20, 10, 50);
+        let rows: i32 = rects.iter().map(|(_, _, _, h)| h).sum();
+        assert_eq!(rows, 10);
+        assert!(rects.iter().all(|&(x, _, w, _)| x >= 0 && w > 0));
+    }
+}
@@ -0,0 +1,124 @@
+//! Keeping the screen awake while a timer finishes
+//!
+//! Uses the `zwp_idle_inhibit_manager_v1` protocol: while a surface holds
+//! an inhibitor and is visible, the compositor doesn't blank the screen or
+//! start the idle timeout. Widgets ask for it through
+//! [`Widget::inhibits_idle`](crate::widget::Widget::inhibits_idle), e.g. a
+//! pomodoro in its last minutes.
+//!
+//! The protocol is optional: without it the screen idles as usual.
+
+use smithay_client_toolkit::globals::GlobalData;
+use wayland_client::{
+    globals::GlobalList, protocol::wl_surface::WlSurface, Connection, Dispatch, QueueHandle,
+};
+use wayland_protocols::wp::idle_inhibit::zv1::client::{
+    zwp_idle_inhibit_manager_v1::ZwpIdleInhibitManagerV1, zwp_idle_inhibitor_v1::ZwpIdleInhibitorV1,
+};
+
+/// Idle inhibit manager global, if the compositor provides one
+#[derive(Debug)]
+pub struct IdleInhibitState {
+    manager: Option<ZwpIdleInhibitManagerV1>,
+}
+
+/// An inhibitor held for one surface, released when dropped
+#[derive(Debug)]
+pub struct SurfaceInhibitor {
+    inhibitor: ZwpIdleInhibitorV1,
+}
+
+impl Drop for SurfaceInhibitor {
+    fn drop(&mut self) {
+        self.inhibitor.destroy();
+    }
+}
+
+impl IdleInhibitState {
+    /// Bind the idle inhibit manager if the compositor advertises it
+    pub fn bind<D>(globals: &GlobalList, qh: &QueueHandle<D>) -> Self
+    where
+        D: Dispatch<ZwpIdleInhibitManagerV1, GlobalData> + 'static,
+    {
+        let manager = match globals.bind(qh, 1..=1, GlobalData) {
+            Ok(manager) => {
+                tracing::info!("Idle inhibit available (zwp_idle_inhibit_manager_v1)");
+                Some(manager)
+            }
+            Err(e) => {
+                tracing::debug!(error = %e, "Idle inhibit not available");
+                None
+            }
+        };
+        Self { manager }
+    }
+
+    /// Whether the compositor supports idle inhibitors
+    pub fn is_available(&self) -> bool {
+        self.manager.is_some()
+    }
+
+    /// Create or release the inhibitor for `surface`
+    ///
+    /// `current` holds the surface's existing inhibitor and is updated in
+    /// place. Unlike most surface state this takes effect immediately.
+    pub fn update<D>(
+        &self,
+        current: &mut Option<SurfaceInhibitor>,
+        surface: &WlSurface,
+        inhibit: bool,
+        qh: &QueueHandle<D>,
+    ) where
+        D: Dispatch<ZwpIdleInhibitorV1, ()> + 'static,
+    {
+        let Some(manager) = &self.manager else {
+            return;
+        };
+
+        match (inhibit, current.is_some()) {
+            (true, false) => {
+                tracing::debug!("Inhibiting idle");
+                *current = Some(SurfaceInhibitor {
+                    inhibitor: manager.create_inhibitor(surface, qh, ()),
+                });
+            }
+            (false, true) => {
+                tracing::debug!("Releasing idle inhibitor");
+                *current = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<D> Dispatch<ZwpIdleInhibitManagerV1, GlobalData, D> for IdleInhibitState
+where
+    D: Dispatch<ZwpIdleInhibitManagerV1, GlobalData>,
+{
+    fn event(
+        _state: &mut D,
+        _proxy: &ZwpIdleInhibitManagerV1,
+        _event: <ZwpIdleInhibitManagerV1 as wayland_client::Proxy>::Event,
+        _data: &GlobalData,
+        _conn: &Connection,
+        _qh: &QueueHandle<D>,
+    ) {
+        unreachable!("zwp_idle_inhibit_manager_v1 has no events")
+    }
+}
+
+impl<D> Dispatch<ZwpIdleInhibitorV1, (), D> for IdleInhibitState
+where
+    D: Dispatch<ZwpIdleInhibitorV1, ()>,
+{
+    fn event(
+        _state: &mut D,
+        _proxy: &ZwpIdleInhibitorV1,
+        _event: <ZwpIdleInhibitorV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<D>,
+    ) {
+        unreachable!("zwp_idle_inhibitor_v1 has no events")
+    }
+}
@@ -2,9 +2,20 @@
 //
 // Performance optimizations:
 // - Conditional buffer clearing (only when needed)
-// - Buffer slot tracking for efficient reuse
+// - Buffers reused once the compositor releases them
+// - One pool per surface, sub-allocated across resizes
 // - Reduced debug logging in hot paths
 
+pub mod blur;
+pub mod idle_inhibit;
+pub mod output_power;
+pub mod outputs;
+
+pub use blur::{BlurState, SurfaceBlur};
+pub use idle_inhibit::{IdleInhibitState, SurfaceInhibitor};
+pub use output_power::{OutputPowerHandler, OutputPowerState};
+pub use outputs::{OutputGeometry, OutputsModel, Placement};
+
 use crate::error::Result;
 use smithay_client_toolkit::shm::{
     slot::{Buffer, SlotPool},
@@ -13,38 +24,57 @@ use smithay_client_toolkit::shm::{
 use tracing::{debug, info, trace};
 use wayland_client::QueueHandle;
 
+/// Buffers kept per surface: one on screen, one being drawn
+const BUFFERS: usize = 2;
+
+/// Buffers kept with triple buffering, for compositors that hold on to a
+/// buffer for longer than a frame
+const TRIPLE_BUFFERS: usize = 3;
+
+/// Spare room in a new pool, as a fraction of what its buffers need, so
+/// surfaces can grow a little without the pool growing with them
+const POOL_HEADROOM: f64 = 0.25;
+
 /// Buffer pool for Wayland shared memory surfaces
 ///
-/// Manages double-buffered rendering for smooth updates.
-/// Optimized for minimal allocations and efficient reuse.
+/// Keeps two buffers (three with triple buffering) and draws into whichever
+/// one the compositor has released. The shared memory pool is sized with
+/// some headroom and only ever grows, so resizing the surface sub-allocates
+/// new buffers from it rather than creating a new pool.
 pub struct BufferPool {
     pool: SlotPool,
     width: u32,
     height: u32,
+    /// Buffers of the current size, kept for reuse
+    buffers: Vec<Buffer>,
+    /// Most buffers kept at once
+    max_buffers: usize,
     /// Track whether we need to clear the buffer
     needs_clear: bool,
     /// Number of buffers created (for metrics)
     buffer_count: u64,
+    /// Frames for which the compositor still held every buffer
+    held_frames: u64,
 }
 
 impl BufferPool {
     /// Create a new buffer pool for the given dimensions
     ///
-    /// Allocates enough space for double-buffering (2x buffer size).
+    /// Allocates enough space for double-buffering, plus headroom.
     pub fn new<T>(width: u32, height: u32, shm: &Shm, _qh: &QueueHandle<T>) -> Result<Self>
     where
         T: 'static,
     {
-        let buffer_size = (width * height * 4) as usize * 2; // Double buffering
+        let pool_size = pool_size(width, height, BUFFERS);
         info!(
             width = %width,
             height = %height,
-            buffer_size_bytes = %buffer_size,
-            buffer_size_kb = %(buffer_size / 1024),
+            pool_size_bytes = %pool_size,
+            pool_size_kb = %(pool_size / 1024),
             "Creating buffer pool"
         );
 
-        let pool = SlotPool::new(buffer_size, shm).map_err(|e| {
+        let pool = SlotPool::new(pool_size, shm).map_err(|e| {
             crate::error::WidgetError::BufferCreation(format!("Failed to create slot pool: {}", e))
         })?;
 
@@ -54,45 +84,73 @@ impl BufferPool {
             pool,
             width,
             height,
+            buffers: Vec::with_capacity(TRIPLE_BUFFERS),
+            max_buffers: BUFFERS,
             needs_clear: true, // First buffer always needs clearing
             buffer_count: 0,
+            held_frames: 0,
         })
     }
 
+    /// Keep a third buffer for compositors that hold on to buffers for
+    /// longer than one frame
+    pub fn with_triple_buffering(mut self, enabled: bool) -> Self {
+        self.set_triple_buffering(enabled);
+        self
+    }
+
+    /// Turn triple buffering on or off
+    pub fn set_triple_buffering(&mut self, enabled: bool) {
+        self.max_buffers = if enabled { TRIPLE_BUFFERS } else { BUFFERS };
+        self.buffers.truncate(self.max_buffers);
+    }
+
     /// Get a buffer for rendering
     ///
     /// The buffer is only cleared if `needs_clear` is true or `force_clear` is requested.
     /// After the first frame, clearing is typically not needed since the renderer
     /// overwrites the entire buffer content.
-    pub fn get_buffer(&mut self) -> Result<(Buffer, &mut [u8])> {
+    pub fn get_buffer(&mut self) -> Result<(&Buffer, &mut [u8])> {
         self.get_buffer_with_clear(self.needs_clear)
     }
 
     /// Get a buffer with explicit clear control
     ///
     /// Use this when you need fine-grained control over buffer clearing.
-    pub fn get_buffer_with_clear(&mut self, clear: bool) -> Result<(Buffer, &mut [u8])> {
-        let stride = self.width * 4;
-
+    pub fn get_buffer_with_clear(&mut self, clear: bool) -> Result<(&Buffer, &mut [u8])> {
         trace!(
             width = %self.width,
             height = %self.height,
-            stride = %stride,
+            buffers = %self.buffers.len(),
             clear = %clear,
             "Retrieving buffer from pool"
         );
 
-        let (buffer, canvas): (Buffer, &mut [u8]) = self
-            .pool
-            .create_buffer(
-                self.width as i32,
-                self.height as i32,
-                stride as i32,
-                wayland_client::protocol::wl_shm::Format::Argb8888,
-            )
-            .map_err(|e| {
-                crate::error::WidgetError::BufferCreation(format!("Failed to create buffer: {}", e))
-            })?;
+        // A buffer the compositor has released can be drawn into again
+        let released = self
+            .buffers
+            .iter()
+            .position(|buffer| buffer.canvas(&mut self.pool).is_some());
+        let index = match released {
+            Some(index) => index,
+            None => {
+                if self.buffers.len() >= self.max_buffers {
+                    // Every buffer is still on screen: let go of the oldest,
+                    // whose memory returns to the pool once released
+                    self.held_frames += 1;
+                    trace!(held_frames = %self.held_frames, "All buffers held by compositor");
+                    self.buffers.remove(0);
+                }
+                let buffer = self.create_buffer()?;
+                self.buffers.push(buffer);
+                self.buffers.len() - 1
+            }
+        };
+
+        let buffer = &self.buffers[index];
+        let canvas = buffer.canvas(&mut self.pool).ok_or_else(|| {
+            crate::error::WidgetError::BufferCreation("Buffer is still in use".to_string())
+        })?;
 
         // Only clear if needed - the renderer typically overwrites everything
         if clear {
@@ -102,7 +160,6 @@ impl BufferPool {
             trace!("Buffer cleared");
         }
 
-        self.buffer_count += 1;
         // After first buffer, we typically don't need to clear
         // The renderer draws the entire surface
         self.needs_clear = false;
@@ -110,6 +167,25 @@ impl BufferPool {
         Ok((buffer, canvas))
     }
 
+    /// Allocate a buffer of the current size from the pool, growing the
+    /// pool if it is full
+    fn create_buffer(&mut self) -> Result<Buffer> {
+        let stride = self.width * 4;
+        let (buffer, _) = self
+            .pool
+            .create_buffer(
+                self.width as i32,
+                self.height as i32,
+                stride as i32,
+                wayland_client::protocol::wl_shm::Format::Argb8888,
+            )
+            .map_err(|e| {
+                crate::error::WidgetError::BufferCreation(format!("Failed to create buffer: {}", e))
+            })?;
+        self.buffer_count += 1;
+        Ok(buffer)
+    }
+
     /// Mark that the next buffer should be cleared
     ///
     /// Call this when the surface size changes or on theme changes.
@@ -122,19 +198,11 @@ impl BufferPool {
         (self.width, self.height)
     }
 
-    /// Resize the buffer pool for new dimensions
+    /// Resize the buffers for new dimensions
     ///
-    /// This invalidates all existing buffers.
-    pub fn resize<T>(
-        &mut self,
-        width: u32,
-        height: u32,
-        shm: &Shm,
-        qh: &QueueHandle<T>,
-    ) -> Result<()>
-    where
-        T: 'static,
-    {
+    /// Buffers of the old size are dropped and new ones sub-allocated from
+    /// the same pool, which grows if they don't fit.
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<()> {
         if self.width == width && self.height == height {
             return Ok(());
         }
@@ -144,16 +212,26 @@ impl BufferPool {
             old_height = %self.height,
             new_width = %width,
             new_height = %height,
-            "Resizing buffer pool"
+            pool_size_bytes = %self.pool.len(),
+            "Resizing buffers"
         );
 
-        // Create new pool with new size
-        let new_pool = Self::new(width, height, shm, qh)?;
-        self.pool = new_pool.pool;
+        self.buffers.clear();
         self.width = width;
         self.height = height;
         self.needs_clear = true; // New buffers need clearing
 
+        let needed = frame_size(width, height) * self.max_buffers;
+        if needed > self.pool.len() {
+            self.pool
+                .resize(pool_size(width, height, self.max_buffers))
+                .map_err(|e| {
+                    crate::error::WidgetError::BufferCreation(format!(
+                        "Failed to grow slot pool: {}",
+                        e
+                    ))
+                })?;
+        }
         Ok(())
     }
 
@@ -162,12 +240,31 @@ impl BufferPool {
         self.buffer_count
     }
 
+    /// Number of frames for which the compositor still held every buffer
+    ///
+    /// If this keeps growing, triple buffering saves allocating a buffer
+    /// on those frames.
+    pub fn held_frames(&self) -> u64 {
+        self.held_frames
+    }
+
     /// Get the buffer size in bytes
     pub fn buffer_size_bytes(&self) -> usize {
-        (self.width * self.height * 4) as usize
+        frame_size(self.width, self.height)
     }
 }
 
+/// Bytes in one ARGB8888 buffer
+fn frame_size(width: u32, height: u32) -> usize {
+    width as usize * height as usize * 4
+}
+
+/// Bytes for a pool holding `buffers` buffers, with headroom
+fn pool_size(width: u32, height: u32, buffers: usize) -> usize {
+    let needed = frame_size(width, height) * buffers;
+    needed + (needed as f64 * POOL_HEADROOM) as usize
+}
+
 /// Fast buffer clearing using efficient memory operations
 ///
 /// This is optimized for clearing ARGB8888 buffers to transparent black.
@@ -178,3 +275,19 @@ fn clear_buffer_fast(canvas: &mut [u8]) {
     // to use SIMD or memset
     canvas.fill(0);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_size_has_headroom() {
+        let frame = frame_size(400, 200);
+        assert_eq!(frame, 320_000);
+        let pool = pool_size(400, 200, BUFFERS);
+        assert!(pool > frame * BUFFERS);
+
+        // A widget growing by a few pixels still fits
+        assert!(frame_size(410, 210) * BUFFERS <= pool);
+    }
+}
@@ -0,0 +1,209 @@
+//! Output power (DPMS) tracking
+//!
+//! Uses wlroots' `zwlr_output_power_manager_v1` protocol to follow whether
+//! each output is on or off. Surfaces whose outputs are all off are skipped
+//! when drawing, since nothing would show the frame, and get redrawn as soon
+//! as an output powers back on.
+//!
+//! The protocol is optional: without it every output is treated as on.
+
+use smithay_client_toolkit::{compositor::SurfaceData, globals::GlobalData};
+use wayland_client::{
+    globals::GlobalList,
+    protocol::{wl_output::WlOutput, wl_surface::WlSurface},
+    Connection, Dispatch, Proxy, QueueHandle, WEnum,
+};
+use wayland_protocols_wlr::output_power_management::v1::client::{
+    zwlr_output_power_manager_v1::ZwlrOutputPowerManagerV1,
+    zwlr_output_power_v1::{self, Mode, ZwlrOutputPowerV1},
+};
+
+/// Output power manager global and the power state of each output
+#[derive(Debug)]
+pub struct OutputPowerState {
+    manager: Option<ZwlrOutputPowerManagerV1>,
+    outputs: Vec<OutputPower>,
+}
+
+#[derive(Debug)]
+struct OutputPower {
+    output: WlOutput,
+    power: ZwlrOutputPowerV1,
+    on: bool,
+}
+
+impl Drop for OutputPower {
+    fn drop(&mut self) {
+        self.power.destroy();
+    }
+}
+
+/// Notified when an output is switched on or off
+pub trait OutputPowerHandler: Sized {
+    /// The application's output power state
+    fn output_power_state(&mut self) -> &mut OutputPowerState;
+
+    /// `output` was switched on or off
+    fn output_power_changed(
+        &mut self,
+        conn: &Connection,
+        qh: &QueueHandle<Self>,
+        output: &WlOutput,
+        on: bool,
+    );
+}
+
+impl OutputPowerState {
+    /// Bind the output power manager if the compositor advertises it
+    pub fn bind<D>(globals: &GlobalList, qh: &QueueHandle<D>) -> Self
+    where
+        D: Dispatch<ZwlrOutputPowerManagerV1, GlobalData> + 'static,
+    {
+        let manager = match globals.bind(qh, 1..=1, GlobalData) {
+            Ok(manager) => {
+                tracing::info!("Output power tracking available (zwlr_output_power_manager_v1)");
+                Some(manager)
+            }
+            Err(e) => {
+                tracing::debug!(error = %e, "Output power tracking not available");
+                None
+            }
+        };
+        Self {
+            manager,
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Start tracking a new output's power mode
+    pub fn add_output<D>(&mut self, output: &WlOutput, qh: &QueueHandle<D>)
+    where
+        D: Dispatch<ZwlrOutputPowerV1, WlOutput> + 'static,
+    {
+        let Some(manager) = &self.manager else {
+            return;
+        };
+        if self.outputs.iter().any(|o| &o.output == output) {
+            return;
+        }
+        let power = manager.get_output_power(output, qh, output.clone());
+        self.outputs.push(OutputPower {
+            output: output.clone(),
+            power,
+            on: true,
+        });
+    }
+
+    /// Stop tracking a removed output
+    pub fn remove_output(&mut self, output: &WlOutput) {
+        self.outputs.retain(|o| &o.output != output);
+    }
+
+    /// Whether `output` is on; unknown outputs are assumed to be
+    pub fn is_on(&self, output: &WlOutput) -> bool {
+        self.outputs
+            .iter()
+            .find(|o| &o.output == output)
+            .map_or(true, |o| o.on)
+    }
+
+    /// Whether drawing to `surface` can be seen on any output
+    pub fn is_surface_visible(&self, surface: &WlSurface) -> bool {
+        let Some(data) = surface.data::<SurfaceData>() else {
+            return true;
+        };
+        any_output_on(data.outputs().map(|output| self.is_on(&output)))
+    }
+
+    /// Whether `surface` is shown on `output`
+    pub fn surface_on_output(surface: &WlSurface, output: &WlOutput) -> bool {
+        surface
+            .data::<SurfaceData>()
+            .is_some_and(|data| data.outputs().any(|o| &o == output))
+    }
+
+    fn set_mode(&mut self, power: &ZwlrOutputPowerV1, on: bool) -> bool {
+        match self.outputs.iter_mut().find(|o| &o.power == power) {
+            Some(entry) if entry.on != on => {
+                entry.on = on;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A surface is worth drawing unless every output it is on is off
+///
+/// Surfaces not yet placed on any output are drawn, so they get mapped.
+fn any_output_on(mut outputs: impl Iterator<Item = bool>) -> bool {
+    let mut seen = false;
+    let any_on = outputs.any(|on| {
+        seen = true;
+        on
+    });
+    any_on || !seen
+}
+
+impl<D> Dispatch<ZwlrOutputPowerManagerV1, GlobalData, D> for OutputPowerState
+where
+    D: Dispatch<ZwlrOutputPowerManagerV1, GlobalData>,
+{
+    fn event(
+        _state: &mut D,
+        _proxy: &ZwlrOutputPowerManagerV1,
+        _event: <ZwlrOutputPowerManagerV1 as Proxy>::Event,
+        _data: &GlobalData,
+        _conn: &Connection,
+        _qh: &QueueHandle<D>,
+    ) {
+        unreachable!("zwlr_output_power_manager_v1 has no events")
+    }
+}
+
+impl<D> Dispatch<ZwlrOutputPowerV1, WlOutput, D> for OutputPowerState
+where
+    D: Dispatch<ZwlrOutputPowerV1, WlOutput> + OutputPowerHandler,
+{
+    fn event(
+        state: &mut D,
+        proxy: &ZwlrOutputPowerV1,
+        event: zwlr_output_power_v1::Event,
+        output: &WlOutput,
+        conn: &Connection,
+        qh: &QueueHandle<D>,
+    ) {
+        match event {
+            zwlr_output_power_v1::Event::Mode { mode } => {
+                let on = !matches!(mode, WEnum::Value(Mode::Off));
+                if state.output_power_state().set_mode(proxy, on) {
+                    tracing::info!(output = ?output.id(), on, "Output power changed");
+                    state.output_power_changed(conn, qh, output, on);
+                }
+            }
+            zwlr_output_power_v1::Event::Failed => {
+                // Another client owns the output's power mode; assume it's on
+                tracing::debug!(output = ?output.id(), "Output power tracking failed");
+                if state.output_power_state().set_mode(proxy, true) {
+                    state.output_power_changed(conn, qh, output, true);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_any_output_on() {
+        assert!(any_output_on([true].into_iter()));
+        assert!(any_output_on([false, true].into_iter()));
+        assert!(!any_output_on([false, false].into_iter()));
+
+        // Not on any output yet
+        assert!(any_output_on(std::iter::empty()));
+    }
+}
@@ -0,0 +1,636 @@
+//! Output (monitor) names and layout
+//!
+//! Widgets can be pinned to an output by its connector name, e.g. `DP-1`.
+//! The configuration GUI lists the connected outputs with their logical
+//! geometry to draw the monitor layout, and turns a widget dropped onto it
+//! back into a position and margins.
+//!
+//! The daemon keeps an [`OutputsModel`] of the same geometry to keep
+//! absolutely placed widgets on their output and to spot widgets that
+//! overlap.
+
+use smithay_client_toolkit::{
+    delegate_output, delegate_registry,
+    output::{OutputHandler, OutputInfo, OutputState},
+    registry::{ProvidesRegistryState, RegistryState},
+    registry_handlers,
+};
+use wayland_client::{
+    globals::registry_queue_init,
+    protocol::wl_output::{Transform, WlOutput},
+    Connection, QueueHandle,
+};
+
+use crate::config::Margin;
+use crate::error::{Result, WidgetError};
+use crate::panel::PanelDetection;
+use crate::position::Position;
+
+/// An output's name and logical area in the compositor's global space
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputGeometry {
+    /// Connector name, e.g. "eDP-1"
+    pub name: String,
+    /// Human-readable make and model, if the compositor sends one
+    pub description: Option<String>,
+    /// Left edge
+    pub x: i32,
+    /// Top edge
+    pub y: i32,
+    /// Logical width (after scaling)
+    pub width: u32,
+    /// Logical height (after scaling)
+    pub height: u32,
+    /// Integer scale factor, physical pixels per logical pixel
+    pub scale: i32,
+}
+
+/// A rectangle in the compositor's global logical space
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Placement {
+    /// Whether the two rectangles share any area; touching edges don't count
+    pub fn intersects(&self, other: &Placement) -> bool {
+        self.x < other.x + other.width as i32
+            && other.x < self.x + self.width as i32
+            && self.y < other.y + other.height as i32
+            && other.y < self.y + self.height as i32
+    }
+}
+
+impl OutputGeometry {
+    /// An output called `name` covering the given area
+    pub fn new(name: impl Into<String>, x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            x,
+            y,
+            width,
+            height,
+            scale: 1,
+        }
+    }
+
+    /// The same output at a different scale factor
+    pub fn with_scale(mut self, scale: i32) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Geometry from what the compositor told us about an output
+    ///
+    /// Falls back to the current mode divided by the scale when there's no
+    /// xdg-output size, turned on its side for a rotated output since modes
+    /// are always landscape. Returns None for outputs without a name, since
+    /// widgets couldn't be pinned to them anyway.
+    pub fn from_info(info: &OutputInfo) -> Option<Self> {
+        let name = info.name.clone()?;
+        let (x, y) = info.logical_position.unwrap_or(info.location);
+        let (width, height) = info.logical_size.unwrap_or_else(|| {
+            let scale = info.scale_factor.max(1);
+            let (width, height) = info
+                .modes
+                .iter()
+                .find(|mode| mode.current)
+                .map_or((0, 0), |mode| {
+                    (mode.dimensions.0 / scale, mode.dimensions.1 / scale)
+                });
+            if swaps_axes(info.transform) {
+                (height, width)
+            } else {
+                (width, height)
+            }
+        });
+        Some(Self {
+            name,
+            description: info.description.clone(),
+            x,
+            y,
+            width: width.max(0) as u32,
+            height: height.max(0) as u32,
+            scale: info.scale_factor.max(1),
+        })
+    }
+
+    /// Size in physical pixels, as the monitor shows it
+    pub fn physical_size(&self) -> (u32, u32) {
+        let scale = self.scale.max(1) as u32;
+        (self.width * scale, self.height * scale)
+    }
+
+    /// The part of the output left after taking `top`, `right`, `bottom`
+    /// and `left` pixels off its edges
+    pub fn inset(&self, top: i32, right: i32, bottom: i32, left: i32) -> OutputGeometry {
+        let (top, right, bottom, left) = (top.max(0), right.max(0), bottom.max(0), left.max(0));
+        OutputGeometry {
+            x: self.x + left,
+            y: self.y + top,
+            width: (self.width as i32 - left - right).max(0) as u32,
+            height: (self.height as i32 - top - bottom).max(0) as u32,
+            ..self.clone()
+        }
+    }
+
+    /// Margins that keep a `width`x`height` widget at `position` entirely
+    /// on the output
+    ///
+    /// Only anchored edges have margins, so each is at most the space the
+    /// widget leaves free on its axis. A widget larger than the output
+    /// gets no margin on that axis.
+    pub fn clamp_margin(
+        &self,
+        position: Position,
+        margin: &Margin,
+        width: u32,
+        height: u32,
+    ) -> Margin {
+        let free_x = (self.width as i32 - width as i32).max(0);
+        let free_y = (self.height as i32 - height as i32).max(0);
+        Margin {
+            top: margin.top.clamp(0, free_y),
+            right: margin.right.clamp(0, free_x),
+            bottom: margin.bottom.clamp(0, free_y),
+            left: margin.left.clamp(0, free_x),
+        }
+    }
+
+    /// Whether the global point `x`,`y` is on this output
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && x < self.x + self.width as i32
+            && y < self.y + self.height as i32
+    }
+
+    /// Top-left corner, in global coordinates, of a `width`x`height`
+    /// widget placed at `position` with `margin`
+    ///
+    /// Mirrors layer-shell placement: margins apply to anchored edges only.
+    pub fn place(
+        &self,
+        position: Position,
+        margin: &Margin,
+        width: u32,
+        height: u32,
+    ) -> (i32, i32) {
+        let free_x = self.width as i32 - width as i32;
+        let free_y = self.height as i32 - height as i32;
+        let x = if position.is_left() {
+            margin.left
+        } else if position.is_right() {
+            free_x - margin.right
+        } else {
+            free_x / 2
+        };
+        let y = if position.is_top() {
+            margin.top
+        } else if position.is_bottom() {
+            free_y - margin.bottom
+        } else {
+            free_y / 2
+        };
+        (self.x + x, self.y + y)
+    }
+
+    /// Position and margins that put a widget's top-left corner at the
+    /// global point `x`,`y`
+    ///
+    /// The widget is anchored to the ninth of the output its center is in,
+    /// so it stays in the same corner or edge when the output's resolution
+    /// changes. It is kept on the output, and centered axes get no margin.
+    pub fn anchor_at(&self, x: i32, y: i32, width: u32, height: u32) -> (Position, Margin) {
+        let free_x = (self.width as i32 - width as i32).max(0);
+        let free_y = (self.height as i32 - height as i32).max(0);
+        let left = (x - self.x).clamp(0, free_x);
+        let top = (y - self.y).clamp(0, free_y);
+
+        let third = |offset: i32, size: u32, out_of: u32| {
+            let center = offset + size as i32 / 2;
+            let out_of = out_of as i32;
+            if center < out_of / 3 {
+                0
+            } else if center >= out_of * 2 / 3 {
+                2
+            } else {
+                1
+            }
+        };
+        let column = third(left, width, self.width);
+        let row = third(top, height, self.height);
+        let position = match (row, column) {
+            (0, 0) => Position::TopLeft,
+            (0, 1) => Position::TopCenter,
+            (0, _) => Position::TopRight,
+            (1, 0) => Position::CenterLeft,
+            (1, 1) => Position::Center,
+            (1, _) => Position::CenterRight,
+            (_, 0) => Position::BottomLeft,
+            (_, 1) => Position::BottomCenter,
+            _ => Position::BottomRight,
+        };
+
+        let margin = Margin {
+            top: if position.is_top() { top } else { 0 },
+            right: if position.is_right() {
+                free_x - left
+            } else {
+                0
+            },
+            bottom: if position.is_bottom() {
+                free_y - top
+            } else {
+                0
+            },
+            left: if position.is_left() { left } else { 0 },
+        };
+        (position, margin)
+    }
+}
+
+/// The connected outputs and their geometry
+///
+/// A snapshot of what [`OutputState`] knows, sorted left to right, with
+/// the placement maths layer-shell leaves to the compositor done on our
+/// side: where a widget ends up, how much of an output panels leave free
+/// and which widgets cover each other.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputsModel {
+    outputs: Vec<OutputGeometry>,
+}
+
+impl OutputsModel {
+    /// A model of the given outputs
+    pub fn new(mut outputs: Vec<OutputGeometry>) -> Self {
+        outputs.sort_by_key(|output| (output.x, output.y));
+        Self { outputs }
+    }
+
+    /// The outputs the compositor has told us about so far
+    pub fn from_state(state: &OutputState) -> Self {
+        Self::new(
+            state
+                .outputs()
+                .filter_map(|output| OutputGeometry::from_info(&state.info(&output)?))
+                .collect(),
+        )
+    }
+
+    /// Every output, left to right
+    pub fn outputs(&self) -> &[OutputGeometry] {
+        &self.outputs
+    }
+
+    /// The output called `name`
+    pub fn get(&self, name: &str) -> Option<&OutputGeometry> {
+        self.outputs.iter().find(|output| output.name == name)
+    }
+
+    /// The output covering the global point `x`,`y`
+    pub fn at(&self, x: i32, y: i32) -> Option<&OutputGeometry> {
+        self.outputs.iter().find(|output| output.contains(x, y))
+    }
+
+    /// The output a widget lands on
+    ///
+    /// That's the one it's pinned to, or for an unpinned widget the only
+    /// output there is. With several outputs the compositor picks one
+    /// (usually the focused one), which can't be known in advance.
+    pub fn for_widget(&self, output: Option<&str>) -> Option<&OutputGeometry> {
+        match output {
+            Some(name) => self.get(name),
+            None if self.outputs.len() == 1 => self.outputs.first(),
+            None => None,
+        }
+    }
+
+    /// What's left of an output once the panels on it have their space
+    pub fn usable_area(&self, name: &str, panels: &PanelDetection) -> Option<OutputGeometry> {
+        let reserved = panels.margin_adjustments_for(Some(name));
+        self.get(name).map(|output| {
+            output.inset(reserved.top, reserved.right, reserved.bottom, reserved.left)
+        })
+    }
+
+    /// Where a widget ends up in global coordinates, if its output is known
+    pub fn place(
+        &self,
+        output: Option<&str>,
+        position: Position,
+        margin: &Margin,
+        width: u32,
+        height: u32,
+    ) -> Option<Placement> {
+        let (x, y) = self
+            .for_widget(output)?
+            .place(position, margin, width, height);
+        Some(Placement {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+}
+
+/// Index pairs of the placements that overlap, each pair once
+pub fn overlapping(placements: &[Placement]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for (i, a) in placements.iter().enumerate() {
+        for (j, b) in placements.iter().enumerate().skip(i + 1) {
+            if a.intersects(b) {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+/// A name for the set of connected monitors
+///
+/// Built from each monitor's make and model, so it stays the same whichever
+/// connectors they're plugged into. wl_output doesn't send serial numbers:
+/// identical monitors are told apart by connector name instead. Empty while
+/// no output is known.
+pub fn setup_fingerprint(state: &OutputState) -> String {
+    let monitors: Vec<(String, Option<String>)> = state
+        .outputs()
+        .filter_map(|output| state.info(&output))
+        .map(|info| (format!("{} {}", info.make, info.model), info.name))
+        .collect();
+    fingerprint(&monitors)
+}
+
+/// Fingerprint of monitors given as "make model" and connector name
+fn fingerprint(monitors: &[(String, Option<String>)]) -> String {
+    let mut names: Vec<String> = monitors
+        .iter()
+        .map(|(model, connector)| {
+            let model = model.trim();
+            let duplicate = monitors.iter().filter(|(m, _)| m.trim() == model).count() > 1;
+            match connector {
+                Some(connector) if model.is_empty() => connector.clone(),
+                Some(connector) if duplicate => format!("{} ({})", model, connector),
+                _ => model.to_string(),
+            }
+        })
+        .collect();
+    names.sort();
+    names.join(" + ")
+}
+
+/// Whether `transform` turns an output on its side, swapping its width and
+/// height
+pub fn swaps_axes(transform: Transform) -> bool {
+    matches!(
+        transform,
+        Transform::_90 | Transform::_270 | Transform::Flipped90 | Transform::Flipped270
+    )
+}
+
+/// The output called `name`, if it's connected
+pub fn find_output(state: &OutputState, name: &str) -> Option<WlOutput> {
+    state.outputs().find(|output| {
+        state
+            .info(output)
+            .is_some_and(|info| info.name.as_deref() == Some(name))
+    })
+}
+
+/// Names of the connected outputs
+pub fn output_names(state: &OutputState) -> Vec<String> {
+    state
+        .outputs()
+        .filter_map(|output| state.info(&output)?.name)
+        .collect()
+}
+
+/// Ask the compositor for the connected outputs and their layout
+///
+/// Opens a short-lived connection of its own, so it can be used by
+/// programs that don't otherwise talk Wayland directly.
+pub fn list_outputs() -> Result<Vec<OutputGeometry>> {
+    let conn =
+        Connection::connect_to_env().map_err(|e| WidgetError::WaylandConnection(e.to_string()))?;
+    let (globals, mut queue) =
+        registry_queue_init(&conn).map_err(|e| WidgetError::WaylandConnection(e.to_string()))?;
+    let qh = queue.handle();
+    let mut list = OutputList {
+        registry_state: RegistryState::new(&globals),
+        output_state: OutputState::new(&globals, &qh),
+    };
+    // Names and xdg-output geometry arrive in reply to binding the outputs
+    for _ in 0..2 {
+        queue
+            .roundtrip(&mut list)
+            .map_err(|e| WidgetError::WaylandConnection(e.to_string()))?;
+    }
+
+    let state = &list.output_state;
+    let mut outputs: Vec<OutputGeometry> = state
+        .outputs()
+        .filter_map(|output| OutputGeometry::from_info(&state.info(&output)?))
+        .collect();
+    outputs.sort_by_key(|output| (output.x, output.y));
+    Ok(outputs)
+}
+
+/// Minimal Wayland client state for [`list_outputs`]
+struct OutputList {
+    registry_state: RegistryState,
+    output_state: OutputState,
+}
+
+impl OutputHandler for OutputList {
+    fn output_state(&mut self) -> &mut OutputState {
+        &mut self.output_state
+    }
+
+    fn new_output(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _output: WlOutput) {}
+
+    fn update_output(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _output: WlOutput) {}
+
+    fn output_destroyed(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _output: WlOutput) {
+    }
+}
+
+impl ProvidesRegistryState for OutputList {
+    fn registry(&mut self) -> &mut RegistryState {
+        &mut self.registry_state
+    }
+    registry_handlers![OutputState];
+}
+
+delegate_output!(OutputList);
+delegate_registry!(OutputList);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn margin(top: i32, right: i32, bottom: i32, left: i32) -> Margin {
+        Margin {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    #[test]
+    fn test_place_and_anchor_round_trip() {
+        // A 1920x1080 output right of a laptop screen
+        let output = OutputGeometry::new("DP-1", 1280, 0, 1920, 1080);
+
+        let (x, y) = output.place(Position::TopRight, &margin(10, 20, 0, 0), 250, 90);
+        assert_eq!((x, y), (1280 + 1920 - 250 - 20, 10));
+        assert_eq!(
+            output.anchor_at(x, y, 250, 90),
+            (Position::TopRight, margin(10, 20, 0, 0))
+        );
+
+        let (x, y) = output.place(Position::BottomLeft, &margin(0, 0, 30, 40), 250, 90);
+        assert_eq!((x, y), (1280 + 40, 1080 - 90 - 30));
+        assert_eq!(
+            output.anchor_at(x, y, 250, 90),
+            (Position::BottomLeft, margin(0, 0, 30, 40))
+        );
+    }
+
+    #[test]
+    fn test_anchor_snaps_to_nearest_ninth() {
+        let output = OutputGeometry::new("eDP-1", 0, 0, 1200, 900);
+
+        // Dropped around the middle: centered without margins
+        let (position, margin_) = output.anchor_at(480, 400, 240, 100);
+        assert_eq!(position, Position::Center);
+        assert_eq!(margin_, margin(0, 0, 0, 0));
+
+        // Dropped partly off the top edge: kept on the output
+        let (position, margin_) = output.anchor_at(500, -50, 200, 100);
+        assert_eq!(position, Position::TopCenter);
+        assert_eq!(margin_, margin(0, 0, 0, 0));
+
+        assert!(output.contains(0, 0));
+        assert!(!output.contains(1200, 10));
+    }
+
+    #[test]
+    fn test_outputs_model() {
+        let model = OutputsModel::new(vec![
+            OutputGeometry::new("DP-1", 1280, 0, 1920, 1080),
+            OutputGeometry::new("eDP-1", 0, 0, 1280, 800).with_scale(2),
+        ]);
+        assert_eq!(model.outputs()[0].name, "eDP-1");
+        assert_eq!(model.outputs()[0].physical_size(), (2560, 1600));
+        assert_eq!(model.at(1300, 10).map(|o| o.name.as_str()), Some("DP-1"));
+        assert!(model.at(-1, 0).is_none());
+
+        // Unpinned widgets could be on either output
+        assert!(model.for_widget(None).is_none());
+        let placed = model
+            .place(
+                Some("DP-1"),
+                Position::TopLeft,
+                &margin(10, 0, 0, 20),
+                200,
+                100,
+            )
+            .unwrap();
+        assert_eq!(
+            placed,
+            Placement {
+                x: 1300,
+                y: 10,
+                width: 200,
+                height: 100
+            }
+        );
+
+        let usable = model
+            .usable_area("eDP-1", &PanelDetection::default())
+            .unwrap();
+        assert_eq!(usable, model.outputs()[0]);
+    }
+
+    #[test]
+    fn test_inset_and_clamp_margin() {
+        let output = OutputGeometry::new("DP-1", 100, 0, 1920, 1080);
+        let inset = output.inset(40, 0, 0, 60);
+        assert_eq!((inset.x, inset.y), (160, 40));
+        assert_eq!((inset.width, inset.height), (1860, 1040));
+
+        // An absolute widget pushed off the bottom-right is pulled back on
+        let clamped = output.clamp_margin(Position::TopLeft, &margin(1050, 0, 0, 1900), 300, 100);
+        assert_eq!(clamped, margin(980, 0, 0, 1620));
+        let (x, y) = output.place(Position::TopLeft, &clamped, 300, 100);
+        assert!(output.contains(x + 299, y + 99));
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let rect = |x, y| Placement {
+            x,
+            y,
+            width: 100,
+            height: 50,
+        };
+        // The first two touch without overlapping
+        let placements = [rect(0, 0), rect(100, 0), rect(150, 25)];
+        assert_eq!(overlapping(&placements), vec![(1, 2)]);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let monitor =
+            |model: &str, connector: &str| (model.to_string(), Some(connector.to_string()));
+
+        // The same monitors on other connectors are the same setup
+        let docked = fingerprint(&[
+            monitor("BOE 0x095F", "eDP-1"),
+            monitor("Dell Inc. DELL U2720Q", "DP-3"),
+        ]);
+        let other_dock = fingerprint(&[
+            monitor("Dell Inc. DELL U2720Q", "DP-5"),
+            monitor("BOE 0x095F", "eDP-1"),
+        ]);
+        assert_eq!(docked, other_dock);
+        assert_eq!(docked, "BOE 0x095F + Dell Inc. DELL U2720Q");
+        assert_ne!(docked, fingerprint(&[monitor("BOE 0x095F", "eDP-1")]));
+
+        // Twin monitors need their connectors
+        let twins = fingerprint(&[monitor("LG 27GL850", "DP-1"), monitor("LG 27GL850", "DP-2")]);
+        assert_eq!(twins, "LG 27GL850 (DP-1) + LG 27GL850 (DP-2)");
+
+        assert_eq!(fingerprint(&[monitor(" ", "HEADLESS-1")]), "HEADLESS-1");
+        assert_eq!(fingerprint(&[]), "");
+    }
+
+    #[test]
+    fn test_swaps_axes() {
+        assert!(!swaps_axes(Transform::Normal));
+        assert!(!swaps_axes(Transform::_180));
+        assert!(!swaps_axes(Transform::Flipped));
+        assert!(swaps_axes(Transform::_90));
+        assert!(swaps_axes(Transform::Flipped270));
+    }
+
+    #[test]
+    fn test_place_on_portrait_output() {
+        // A 1080x1920 monitor turned on its side: top-right is still the
+        // top-right of what the user sees
+        let output = OutputGeometry::new("DP-2", 0, 0, 1080, 1920);
+        let (x, y) = output.place(Position::TopRight, &margin(10, 20, 0, 0), 250, 90);
+        assert_eq!((x, y), (1080 - 250 - 20, 10));
+        assert_eq!(
+            output.anchor_at(x, y, 250, 90),
+            (Position::TopRight, margin(10, 20, 0, 0))
+        );
+    }
+}
@@ -1,111 +1,31 @@
-// Weather service with async API integration
+// Weather API integration
 //
-// Uses a worker thread pattern with calloop channel to keep async I/O
-// off the main event loop, preventing blocking.
+// Fetches run on the shared widget runtime (see `update::tasks`), which keeps
+// async I/O off the main event loop.
 
-use calloop::channel::{sync_channel, Channel, SyncSender};
-use std::thread;
 use std::time::Duration;
 use tracing::{debug, error, info, warn};
 
 use crate::error::WeatherError;
+use crate::update::Backoff;
 use crate::widget::WeatherData;
 
 /// Result type for weather operations
 pub type WeatherResult = Result<WeatherData, WeatherError>;
 
-/// Weather service that manages async API fetching in a background thread
-pub struct WeatherService {
-    sender: SyncSender<WeatherResult>,
-    pub channel: Channel<WeatherResult>,
-}
-
-impl WeatherService {
-    /// Create a new weather service with channel for result communication
-    pub fn new() -> Self {
-        let (sender, channel) = sync_channel(1);
-        Self { sender, channel }
-    }
-
-    /// Start fetching weather data in a background thread
-    ///
-    /// This spawns a dedicated thread that runs a tokio runtime for async HTTP requests.
-    /// Results are sent back to the main event loop via the calloop channel.
-    pub fn start_fetching(&self, city: String, api_key: String, interval: Duration) {
-        let sender = self.sender.clone();
-
-        info!(
-            city = %city,
-            interval_secs = interval.as_secs(),
-            "Starting weather fetching thread"
-        );
-
-        thread::spawn(move || {
-            // Create tokio runtime in this thread
-            let rt = match tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-            {
-                Ok(runtime) => runtime,
-                Err(e) => {
-                    error!(error = %e, "Failed to create tokio runtime for weather fetching");
-                    let _ = sender.send(Err(WeatherError::InvalidResponse(format!(
-                        "Failed to create async runtime: {}",
-                        e
-                    ))));
-                    return;
-                }
-            };
-
-            // Fetch immediately on start
-            let result = rt.block_on(fetch_weather_data(&city, &api_key));
-            if let Err(ref e) = result {
-                warn!(error = %e, "Initial weather fetch failed");
-            }
-            let _ = sender.send(result);
-
-            // Then fetch periodically
-            loop {
-                thread::sleep(interval);
-
-                debug!(city = %city, "Fetching weather update");
-                let result = rt.block_on(fetch_weather_data(&city, &api_key));
-
-                if let Err(ref e) = result {
-                    warn!(error = %e, city = %city, "Weather fetch failed");
-                }
-
-                // Send result through channel (non-blocking)
-                if sender.send(result).is_err() {
-                    error!("Weather channel disconnected, stopping fetch thread");
-                    break;
-                }
-            }
-        });
-    }
-}
-
-impl Default for WeatherService {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 /// Fetch weather data from OpenWeatherMap API with retry logic
-async fn fetch_weather_data(city: &str, api_key: &str) -> WeatherResult {
+pub async fn fetch_weather_data(city: &str, api_key: &str) -> WeatherResult {
     if api_key.is_empty() {
         warn!("Weather API key not configured");
         return Err(WeatherError::NoApiKey);
     }
 
-    // Retry logic: try up to 3 times with exponential backoff
-    let mut attempts = 0;
+    // Retry quickly a couple of times for blips; longer outages are left to
+    // the task manager's backoff between fetches
     let max_attempts = 3;
-    let mut backoff = Duration::from_secs(1);
+    let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(4));
 
     loop {
-        attempts += 1;
-
         match fetch_weather_attempt(city, api_key).await {
             Ok(data) => {
                 info!(
@@ -117,11 +37,12 @@ async fn fetch_weather_data(city: &str, api_key: &str) -> WeatherResult {
                 return Ok(data);
             }
             Err(e) => {
-                if attempts >= max_attempts {
+                let retry_in = backoff.fail();
+                if backoff.failures() >= max_attempts {
                     error!(
                         error = %e,
                         city = %city,
-                        attempts = attempts,
+                        attempts = backoff.failures(),
                         "Weather fetch failed after all retries"
                     );
                     return Err(e);
@@ -130,13 +51,12 @@ async fn fetch_weather_data(city: &str, api_key: &str) -> WeatherResult {
                 warn!(
                     error = %e,
                     city = %city,
-                    attempt = attempts,
-                    retry_in_secs = backoff.as_secs(),
+                    attempt = backoff.failures(),
+                    retry_in_secs = retry_in.as_secs(),
                     "Weather fetch failed, retrying"
                 );
 
-                tokio::time::sleep(backoff).await;
-                backoff *= 2; // Exponential backoff
+                tokio::time::sleep(retry_in).await;
             }
         }
     }
@@ -151,7 +71,7 @@ async fn fetch_weather_attempt(city: &str, api_key: &str) -> WeatherResult {
 
     debug!(city = %city, "Sending weather API request");
 
-    let response = reqwest::get(&url).await.map_err(|e| {
+    let response = crate::http::client().get(&url).send().await.map_err(|e| {
         debug!(error = %e, "HTTP request failed");
         e
     })?;
@@ -224,13 +144,6 @@ async fn fetch_weather_attempt(city: &str, api_key: &str) -> WeatherResult {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_weather_service_creation() {
-        let service = WeatherService::new();
-        // Should create without panicking
-        drop(service);
-    }
-
     #[test]
     fn test_empty_api_key() {
         let rt = tokio::runtime::Builder::new_current_thread()
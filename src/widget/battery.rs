@@ -14,6 +14,7 @@ use std::time::{Duration, Instant};
 use tracing::{debug, warn};
 
 use super::registry::DynWidgetFactory;
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetMetadata};
 use super::traits::{FontSize, Widget, WidgetContent, WidgetInfo};
 
 /// Battery status information
@@ -269,9 +270,13 @@ impl BatteryWidget {
         let mins = minutes % 60;
 
         if hours > 0 {
-            format!("{}h {:02}m", hours, mins)
+            crate::fl!(
+                "battery-time-left",
+                hours = hours,
+                minutes = format!("{:02}", mins)
+            )
         } else {
-            format!("{}m", mins)
+            crate::fl!("battery-time-left-minutes", minutes = mins)
         }
     }
 
@@ -282,8 +287,8 @@ impl BatteryWidget {
             return self
                 .error_message
                 .as_ref()
-                .map(|e| format!("Battery: {}", e))
-                .unwrap_or_else(|| "No battery".to_string());
+                .map(|e| crate::fl!("battery-error", message = e.as_str()))
+                .unwrap_or_else(|| crate::fl!("battery-none"));
         }
 
         let info = self.battery_info.as_ref().unwrap();
@@ -339,6 +344,12 @@ impl Widget for BatteryWidget {
     }
 
     fn content(&self) -> WidgetContent {
+        // No battery at all isn't an error worth a card
+        if self.battery_info.is_none() && self.battery_path.is_some() {
+            if let Some(error) = &self.error_message {
+                return WidgetContent::error(error);
+            }
+        }
         WidgetContent::Text {
             text: self.display_string(),
             size: FontSize::Medium,
@@ -377,6 +388,14 @@ impl DynWidgetFactory for BatteryWidgetFactory {
         "battery"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Battery",
+            "Charge level and time remaining of the laptop battery",
+            "battery-good-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> anyhow::Result<Box<dyn Widget>> {
         let show_percentage = config
             .get("show_percentage")
@@ -451,6 +470,37 @@ impl DynWidgetFactory for BatteryWidgetFactory {
 
         Ok(())
     }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new(
+                "show_percentage",
+                "Show percentage",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "show_status",
+                "Show status",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "show_time_remaining",
+                "Show time remaining",
+                FieldKind::Bool,
+            ))
+            .with_field(
+                ConfigField::new("battery_path", "Battery path", FieldKind::Path)
+                    .with_description("Defaults to the first battery in /sys/class/power_supply"),
+            )
+            .with_field(ConfigField::new(
+                "update_interval",
+                "Update interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+    }
 }
 
 #[cfg(test)]
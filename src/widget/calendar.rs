@@ -1,17 +1,27 @@
 //! Calendar/Agenda widget
 //!
-//! This widget displays upcoming events from ICS calendar files.
+//! This widget displays upcoming events from ICS calendar files or Google
+//! Calendar, either as an agenda or as a grid of the current month.
 
+use std::collections::{BTreeSet, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context, Result};
-use chrono::{DateTime, Local, TimeZone};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeZone};
 use icalendar::parser::read_calendar;
 use tracing::{debug, warn};
 
+use super::google_calendar::{self, GoogleCredentials};
+use super::recurrence::{self, RecurrenceRule};
 use super::registry::DynWidgetFactory;
-use super::traits::{FontSize, Widget, WidgetContent, WidgetInfo};
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{FontSize, GridCell, Widget, WidgetContent, WidgetInfo};
+use crate::update::tasks::{self, TaskOutput};
+use crate::update::{Reminder, Source};
+
+/// Column headings of the month grid, weeks starting on Monday
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
 
 /// Calendar event with time and title
 #[derive(Debug, Clone)]
@@ -22,6 +32,52 @@ pub struct CalendarEvent {
     pub title: String,
     /// Whether this is an all-day event
     pub all_day: bool,
+    /// Minutes before the start to remind, from the event's own alarm
+    pub reminder_minutes: Option<i64>,
+}
+
+/// How the calendar shows events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CalendarView {
+    /// Upcoming events as a list
+    #[default]
+    Agenda,
+    /// The current month as a grid of days, with a dot on days with events
+    Month,
+}
+
+/// Where the calendar's events come from
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum CalendarSource {
+    /// Local ICS files
+    #[default]
+    Ics,
+    /// Google Calendar, signed in with `cosmic-desktop-widget google-auth`
+    Google {
+        /// OAuth client used to sign in
+        credentials: GoogleCredentials,
+        /// Calendar IDs to show events from
+        calendars: Vec<String>,
+    },
+}
+
+impl CalendarSource {
+    /// Values accepted by the `source` option
+    pub const NAMES: &'static [&'static str] = &["ics", "google"];
+}
+
+impl CalendarView {
+    /// Values accepted by the `view` option
+    pub const NAMES: &'static [&'static str] = &["agenda", "month"];
+
+    /// Parse a `view` option value
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "agenda" => Some(Self::Agenda),
+            "month" => Some(Self::Month),
+            _ => None,
+        }
+    }
 }
 
 /// Calendar widget showing upcoming events
@@ -34,12 +90,28 @@ pub struct CalendarWidget {
     show_all_day: bool,
     /// Number of days ahead to show
     days_ahead: i64,
+    /// Hours ahead to show, overriding `days_ahead`
+    upcoming_hours: Option<i64>,
+    /// ICS files or Google Calendar
+    source: CalendarSource,
     /// Update interval in seconds
     update_interval: Duration,
+    /// Agenda or month grid
+    view: CalendarView,
     /// Cached events
     events: Vec<CalendarEvent>,
-    /// Last update time
-    last_update: Instant,
+    /// Days of the current month with events, for the month grid
+    event_days: BTreeSet<NaiveDate>,
+    /// Minutes before events to remind, for events without their own alarm
+    reminder_minutes: Option<i64>,
+    /// Play the notification sound with reminders
+    reminder_sound: bool,
+    /// Reminders for the upcoming events
+    reminders: Vec<Reminder>,
+    /// Last time events were loaded, None before the first update
+    last_update: Option<Instant>,
+    /// Last time Google Calendar events arrived
+    last_fetch: Option<Instant>,
     /// Error message if any
     error_message: Option<String>,
 }
@@ -53,23 +125,62 @@ impl CalendarWidget {
         days_ahead: i64,
         update_interval: u64,
     ) -> Self {
-        let mut widget = Self {
+        Self {
             calendar_files,
             max_events,
             show_all_day,
             days_ahead,
+            upcoming_hours: None,
+            source: CalendarSource::Ics,
             update_interval: Duration::from_secs(update_interval),
+            view: CalendarView::Agenda,
             events: Vec::new(),
-            last_update: Instant::now(),
+            event_days: BTreeSet::new(),
+            reminder_minutes: None,
+            reminder_sound: false,
+            reminders: Vec::new(),
+            last_update: None,
+            last_fetch: None,
             error_message: None,
-        };
-
-        // Load events on creation
-        if let Err(e) = widget.load_events() {
-            widget.error_message = Some(format!("Failed to load events: {}", e));
         }
+    }
+
+    /// Show events as `view`
+    pub fn with_view(mut self, view: CalendarView) -> Self {
+        self.view = view;
+        self
+    }
 
-        widget
+    /// Show events from `source` instead of the calendar files
+    pub fn with_source(mut self, source: CalendarSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Show events starting within the next `hours` rather than `days_ahead`
+    pub fn with_upcoming_hours(mut self, hours: i64) -> Self {
+        self.upcoming_hours = Some(hours);
+        self
+    }
+
+    /// Remind `minutes` before events that don't set their own alarm
+    pub fn with_reminders(mut self, minutes: i64) -> Self {
+        self.reminder_minutes = Some(minutes);
+        self
+    }
+
+    /// Play the notification sound along with reminders
+    pub fn with_reminder_sound(mut self, sound: bool) -> Self {
+        self.reminder_sound = sound;
+        self
+    }
+
+    /// How far ahead events are shown
+    fn window(&self) -> chrono::Duration {
+        match self.upcoming_hours {
+            Some(hours) => chrono::Duration::hours(hours),
+            None => chrono::Duration::days(self.days_ahead),
+        }
     }
 
     /// Load events from all calendar files
@@ -97,9 +208,25 @@ impl CalendarWidget {
             }
         }
 
-        // Filter events to show only upcoming ones within the time range
+        self.set_events(all_events);
+        Ok(())
+    }
+
+    /// Keep the upcoming events out of `all_events` for display
+    fn set_events(&mut self, mut all_events: Vec<CalendarEvent>) {
+        // The month grid marks every day of this month, past ones included
         let now = Local::now();
-        let end_date = now + chrono::Duration::days(self.days_ahead);
+        let today = now.date_naive();
+        let event_days = all_events
+            .iter()
+            .filter(|event| self.show_all_day || !event.all_day)
+            .map(|event| event.start.date_naive())
+            .filter(|day| day.year() == today.year() && day.month() == today.month())
+            .collect();
+        self.event_days = event_days;
+
+        // Filter events to show only upcoming ones within the time range
+        let end_date = now + self.window();
 
         all_events.retain(|event| {
             // Check if event is in our time range
@@ -118,13 +245,34 @@ impl CalendarWidget {
         // Sort by start time
         all_events.sort_by_key(|e| e.start);
 
+        // Remind about every upcoming event, not only those with room to show
+        self.reminders = all_events
+            .iter()
+            .filter_map(|event| self.reminder_for(event))
+            .collect();
+
         // Limit to max events
         all_events.truncate(self.max_events);
 
         self.events = all_events;
         self.error_message = None;
+    }
 
-        Ok(())
+    /// The reminder for `event`, if it has a lead time
+    fn reminder_for(&self, event: &CalendarEvent) -> Option<Reminder> {
+        let minutes = event.reminder_minutes.or(self.reminder_minutes)?;
+        let body = if event.all_day {
+            event.start.format("%A, %B %-d").to_string()
+        } else {
+            format!("Starts at {}", event.start.format("%H:%M"))
+        };
+        Some(Reminder {
+            id: format!("calendar:{}@{}", event.title, event.start.to_rfc3339()),
+            at: event.start - chrono::Duration::minutes(minutes),
+            summary: event.title.clone(),
+            body,
+            sound: self.reminder_sound,
+        })
     }
 
     /// Parse a single ICS calendar file
@@ -143,6 +291,10 @@ impl CalendarWidget {
             .map_err(|e| anyhow::anyhow!("Failed to parse ICS file: {:?}", e))?;
 
         let mut events = Vec::new();
+        let mut recurring = Vec::new();
+        // Instances of recurring events that were moved or edited, which
+        // appear as events of their own
+        let mut overridden: HashSet<(String, NaiveDateTime)> = HashSet::new();
 
         // Extract events from calendar
         for component in calendar.components {
@@ -154,6 +306,19 @@ impl CalendarWidget {
             let mut title = String::from("Untitled Event");
             let mut start_str: Option<String> = None;
             let mut all_day = false;
+            let mut uid = String::new();
+            let mut rrule: Option<String> = None;
+            let mut exdates = Vec::new();
+            let mut recurrence_id = None;
+            // The earliest alarm set for the event
+            let reminder_minutes = component
+                .components
+                .iter()
+                .filter(|alarm| alarm.name == "VALARM")
+                .flat_map(|alarm| &alarm.properties)
+                .filter(|property| property.name == "TRIGGER")
+                .filter_map(|property| trigger_minutes(property.val.as_str()))
+                .max();
 
             for property in component.properties {
                 match property.name.as_str() {
@@ -171,20 +336,86 @@ impl CalendarWidget {
                             all_day = true;
                         }
                     }
+                    "UID" => uid = property.val.to_string(),
+                    "RRULE" => rrule = Some(property.val.to_string()),
+                    "EXDATE" => exdates.extend(
+                        property
+                            .val
+                            .as_str()
+                            .split(',')
+                            .filter_map(recurrence::parse_ics_value),
+                    ),
+                    "RECURRENCE-ID" => {
+                        recurrence_id = recurrence::parse_ics_value(property.val.as_str());
+                    }
                     _ => {}
                 }
             }
 
-            // Parse start time
-            if let Some(start) = start_str {
-                if let Some(dt) = self.parse_ics_datetime(&start, all_day) {
-                    events.push(CalendarEvent {
-                        start: dt,
-                        title,
-                        all_day,
-                    });
+            if let Some(time) = recurrence_id {
+                overridden.insert((uid.clone(), time));
+            }
+            let Some(start) = start_str else {
+                continue;
+            };
+            if let Some(rule) = rrule {
+                match rule.parse::<RecurrenceRule>() {
+                    Ok(rule) => {
+                        recurring.push((
+                            uid,
+                            title,
+                            start,
+                            all_day,
+                            reminder_minutes,
+                            rule,
+                            exdates,
+                        ));
+                        continue;
+                    }
+                    Err(e) => {
+                        // Still show the first occurrence
+                        warn!(event = %title, error = %e, "Ignoring unsupported recurrence rule");
+                    }
                 }
             }
+
+            // Parse start time
+            if let Some(dt) = self.parse_ics_datetime(&start, all_day) {
+                events.push(CalendarEvent {
+                    start: dt,
+                    title,
+                    all_day,
+                    reminder_minutes,
+                });
+            }
+        }
+
+        // Expand recurring events only as far as they can be shown
+        let (window_start, window_end) = fetch_range(Local::now(), self.window(), self.view);
+        for (uid, title, start, all_day, reminder_minutes, rule, mut exdates) in recurring {
+            let Some(start) = recurrence::parse_ics_value(&start) else {
+                continue;
+            };
+            exdates.extend(
+                overridden
+                    .iter()
+                    .filter(|(id, _)| *id == uid)
+                    .map(|(_, time)| *time),
+            );
+            let occurrences = rule.occurrences(
+                start,
+                window_start.naive_local(),
+                window_end.naive_local(),
+                &exdates,
+            );
+            events.extend(occurrences.into_iter().filter_map(|time| {
+                Some(CalendarEvent {
+                    start: Local.from_local_datetime(&time).earliest()?,
+                    title: title.clone(),
+                    all_day,
+                    reminder_minutes,
+                })
+            }));
         }
 
         Ok(events)
@@ -238,11 +469,11 @@ impl CalendarWidget {
     /// Format events for display
     pub fn display_string(&self) -> String {
         if let Some(ref err) = self.error_message {
-            return format!("Error: {}", err);
+            return crate::fl!("error", message = err.as_str());
         }
 
         if self.events.is_empty() {
-            return "No upcoming events".to_string();
+            return crate::fl!("calendar-no-events");
         }
 
         let now = Local::now();
@@ -302,28 +533,129 @@ impl CalendarWidget {
     }
 }
 
+/// Minutes before the start an alarm `TRIGGER` fires, e.g. 15 for `-PT15M`
+///
+/// Only triggers relative to the start are understood; absolute times and
+/// triggers after the start are ignored.
+fn trigger_minutes(trigger: &str) -> Option<i64> {
+    let duration = trigger.trim().strip_prefix('-')?.strip_prefix('P')?;
+    let (days, time) = match duration.split_once('T') {
+        Some((days, time)) => (days, time),
+        None => (duration, ""),
+    };
+
+    let mut minutes = 0i64;
+    let mut number = String::new();
+    for (part, in_time) in [(days, false), (time, true)] {
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let value: i64 = std::mem::take(&mut number).parse().ok()?;
+            minutes += match (c, in_time) {
+                ('W', false) => value * 7 * 24 * 60,
+                ('D', false) => value * 24 * 60,
+                ('H', true) => value * 60,
+                ('M', true) => value,
+                ('S', true) => value / 60,
+                _ => return None,
+            };
+        }
+    }
+    Some(minutes)
+}
+
+/// Time range to ask Google for: `window` ahead, plus the whole month when
+/// it is shown as a grid
+fn fetch_range(
+    now: DateTime<Local>,
+    window: chrono::Duration,
+    view: CalendarView,
+) -> (DateTime<Local>, DateTime<Local>) {
+    let end = now + window;
+    if view != CalendarView::Month {
+        return (now, end);
+    }
+    let today = now.date_naive();
+    let month_start = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+        .unwrap_or(now);
+    let next_month = month_start + chrono::Months::new(1);
+    (month_start, end.max(next_month))
+}
+
+/// The month containing `today` as a grid of days, with `today` highlighted
+/// and `event_days` marked
+fn month_grid(today: NaiveDate, event_days: &BTreeSet<NaiveDate>) -> WidgetContent {
+    let first = today.with_day(1).unwrap_or(today);
+    let next_month = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    };
+    let days_in_month = next_month.map_or(31, |next| (next - first).num_days() as u32);
+
+    // Blank cells up to the first day's weekday
+    let mut cells = vec![GridCell::default(); first.weekday().num_days_from_monday() as usize];
+    cells.extend((1..=days_in_month).filter_map(|day| {
+        let date = first.with_day(day)?;
+        Some(
+            GridCell::new(day.to_string())
+                .with_highlight(date == today)
+                .with_mark(event_days.contains(&date)),
+        )
+    }));
+
+    WidgetContent::Grid {
+        title: Some(today.format("%B %Y").to_string()),
+        header: WEEKDAYS.iter().map(|day| day.to_string()).collect(),
+        cells,
+    }
+}
+
 impl Widget for CalendarWidget {
     fn info(&self) -> WidgetInfo {
         WidgetInfo {
             id: "calendar",
             name: "Calendar",
-            preferred_height: 60.0,
+            preferred_height: match self.view {
+                CalendarView::Agenda => 60.0,
+                CalendarView::Month => 200.0,
+            },
             min_height: 40.0,
             expand: false,
         }
     }
 
     fn update(&mut self) {
-        // Reload events if update interval has passed
-        if self.last_update.elapsed() >= self.update_interval {
+        // Google events arrive through apply_fetch from the data service
+        if self.source != CalendarSource::Ics {
+            return;
+        }
+
+        // Load events on the first update, once the options are all set,
+        // then reload whenever the update interval has passed
+        let due = self
+            .last_update
+            .map_or(true, |last| last.elapsed() >= self.update_interval);
+        if due {
             if let Err(e) = self.load_events() {
                 self.error_message = Some(format!("Failed to update events: {}", e));
             }
-            self.last_update = Instant::now();
+            self.last_update = Some(Instant::now());
         }
     }
 
     fn content(&self) -> WidgetContent {
+        if let Some(error) = &self.error_message {
+            return WidgetContent::error(error);
+        }
+        if self.view == CalendarView::Month {
+            return month_grid(Local::now().date_naive(), &self.event_days);
+        }
+
         let text = self.display_string();
 
         // Use multiline if we have multiple days
@@ -353,6 +685,46 @@ impl Widget for CalendarWidget {
     fn error(&self) -> Option<&str> {
         self.error_message.as_deref()
     }
+
+    fn last_success(&self) -> Option<Instant> {
+        self.last_fetch
+    }
+
+    fn reminders(&self) -> Vec<Reminder> {
+        self.reminders.clone()
+    }
+
+    fn data_source(&self) -> Option<Source> {
+        let CalendarSource::Google {
+            credentials,
+            calendars,
+        } = &self.source
+        else {
+            return None;
+        };
+        let credentials = credentials.clone();
+        let calendars = calendars.clone();
+        let window = self.window();
+        let view = self.view;
+        Some(Source::new(self.update_interval, move || {
+            let credentials = credentials.clone();
+            let calendars = calendars.clone();
+            tasks::task(async move {
+                let (start, end) = fetch_range(Local::now(), window, view);
+                google_calendar::fetch_events(&credentials, &calendars, start, end).await
+            })
+        }))
+    }
+
+    fn apply_fetch(&mut self, result: anyhow::Result<TaskOutput>) {
+        match tasks::take_output::<Vec<CalendarEvent>>(result) {
+            Ok(events) => {
+                self.set_events(events);
+                self.last_fetch = Some(Instant::now());
+            }
+            Err(e) => self.error_message = Some(format!("Failed to update events: {}", e)),
+        }
+    }
 }
 
 // ============================================================================
@@ -367,6 +739,14 @@ impl DynWidgetFactory for CalendarWidgetFactory {
         "calendar"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Calendar",
+            "Upcoming events from ICS files or Google Calendar, with reminders",
+            "x-office-calendar-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
         // Parse calendar files array
         let calendar_files = if let Some(files) = config.get("calendar_files") {
@@ -419,8 +799,54 @@ impl DynWidgetFactory for CalendarWidgetFactory {
             .and_then(|v| v.as_integer())
             .unwrap_or(300) as u64;
 
+        let view = config
+            .get("view")
+            .and_then(|v| v.as_str())
+            .and_then(CalendarView::from_name)
+            .unwrap_or_default();
+
+        let source = match config.get("source").and_then(|v| v.as_str()) {
+            Some("google") => {
+                let credentials = GoogleCredentials::from_config(config).context(
+                    "'google_client_id' and 'google_client_secret' are needed for Google Calendar",
+                )?;
+                let calendars = config
+                    .get("calendars")
+                    .and_then(|v| v.as_array())
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|v| v.as_str())
+                            .map(str::to_string)
+                            .collect::<Vec<_>>()
+                    })
+                    .filter(|calendars| !calendars.is_empty())
+                    .unwrap_or_else(|| vec!["primary".to_string()]);
+                CalendarSource::Google {
+                    credentials,
+                    calendars,
+                }
+            }
+            _ => CalendarSource::Ics,
+        };
+        let calendar_files = match source {
+            CalendarSource::Ics => calendar_files,
+            CalendarSource::Google { .. } => Vec::new(),
+        };
+
+        let upcoming_hours = config.get("upcoming_hours").and_then(|v| v.as_integer());
+
+        let reminder_minutes = config.get("reminder_minutes").and_then(|v| v.as_integer());
+        let reminder_sound = config
+            .get("reminder_sound")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
         debug!(
             calendar_files = ?calendar_files,
+            view = ?view,
+            google = matches!(source, CalendarSource::Google { .. }),
+            upcoming_hours = ?upcoming_hours,
+            reminder_minutes = ?reminder_minutes,
             max_events = %max_events,
             show_all_day = %show_all_day,
             days_ahead = %days_ahead,
@@ -428,13 +854,24 @@ impl DynWidgetFactory for CalendarWidgetFactory {
             "Creating CalendarWidget"
         );
 
-        Ok(Box::new(CalendarWidget::new(
+        let mut widget = CalendarWidget::new(
             calendar_files,
             max_events,
             show_all_day,
             days_ahead,
             update_interval,
-        )))
+        )
+        .with_view(view)
+        .with_source(source)
+        .with_reminder_sound(reminder_sound);
+        if let Some(hours) = upcoming_hours {
+            widget = widget.with_upcoming_hours(hours);
+        }
+        if let Some(minutes) = reminder_minutes {
+            widget = widget.with_reminders(minutes);
+        }
+
+        Ok(Box::new(widget))
     }
 
     fn default_config(&self) -> toml::Table {
@@ -483,8 +920,157 @@ impl DynWidgetFactory for CalendarWidgetFactory {
             }
         }
 
+        if let Some(hours) = config.get("upcoming_hours") {
+            let val = hours
+                .as_integer()
+                .context("'upcoming_hours' must be an integer")?;
+
+            if val < 1 {
+                bail!("'upcoming_hours' must be at least 1");
+            }
+        }
+
+        if let Some(minutes) = config.get("reminder_minutes") {
+            let val = minutes
+                .as_integer()
+                .context("'reminder_minutes' must be an integer")?;
+
+            if val < 0 {
+                bail!("'reminder_minutes' must be non-negative");
+            }
+        }
+
+        if let Some(sound) = config.get("reminder_sound") {
+            sound
+                .as_bool()
+                .context("'reminder_sound' must be a boolean")?;
+        }
+
+        if let Some(calendars) = config.get("calendars") {
+            if !calendars.is_array() {
+                bail!("'calendars' must be an array of strings");
+            }
+        }
+
+        if let Some(source) = config.get("source") {
+            let source = source.as_str().context("'source' must be a string")?;
+            match source {
+                "ics" => {}
+                "google" => {
+                    if GoogleCredentials::from_config(config).is_none() {
+                        bail!("'google_client_id' and 'google_client_secret' are needed for Google Calendar");
+                    }
+                }
+                _ => bail!(
+                    "'source' must be one of {}, got '{}'",
+                    CalendarSource::NAMES.join(", "),
+                    source
+                ),
+            }
+        }
+
+        if let Some(view) = config.get("view") {
+            let view = view.as_str().context("'view' must be a string")?;
+            if CalendarView::from_name(view).is_none() {
+                bail!(
+                    "'view' must be one of {}, got '{}'",
+                    CalendarView::NAMES.join(", "),
+                    view
+                );
+            }
+        }
+
         Ok(())
     }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        // Only with `source = "google"`
+        WidgetCapabilities::NONE.with_network()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(
+                ConfigField::new("source", "Source", FieldKind::Choice(CalendarSource::NAMES))
+                    .with_description("Local .ics files or Google Calendar"),
+            )
+            .with_field(
+                ConfigField::new("calendar_files", "Calendar files", FieldKind::List)
+                    .with_description("Paths to .ics files"),
+            )
+            .with_field(
+                ConfigField::new("google_client_id", "Google client ID", FieldKind::Text)
+                    .with_description("OAuth client for TVs and limited-input devices"),
+            )
+            .with_field(ConfigField::new(
+                "google_client_secret",
+                "Google client secret",
+                FieldKind::Secret,
+            ))
+            .with_field(
+                ConfigField::new("calendars", "Google calendars", FieldKind::List)
+                    .with_description("Calendar IDs; primary is your own calendar"),
+            )
+            .with_field(
+                ConfigField::new("view", "View", FieldKind::Choice(CalendarView::NAMES))
+                    .with_description("Upcoming events, or a grid of the month"),
+            )
+            .with_field(ConfigField::new(
+                "max_events",
+                "Maximum events",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(ConfigField::new(
+                "show_all_day",
+                "Show all-day events",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "days_ahead",
+                "Days ahead",
+                FieldKind::Integer {
+                    min: Some(0),
+                    max: None,
+                },
+            ))
+            .with_field(
+                ConfigField::new(
+                    "upcoming_hours",
+                    "Upcoming hours",
+                    FieldKind::Integer {
+                        min: Some(1),
+                        max: None,
+                    },
+                )
+                .with_description("Show events this many hours ahead instead of days ahead"),
+            )
+            .with_field(
+                ConfigField::new(
+                    "reminder_minutes",
+                    "Reminder (minutes before)",
+                    FieldKind::Integer {
+                        min: Some(0),
+                        max: None,
+                    },
+                )
+                .with_description("Notify before events without an alarm of their own"),
+            )
+            .with_field(
+                ConfigField::new("reminder_sound", "Reminder sound", FieldKind::Bool)
+                    .with_description("Play the notification sound with reminders"),
+            )
+            .with_field(ConfigField::new(
+                "update_interval",
+                "Update interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+    }
 }
 
 #[cfg(test)]
@@ -551,6 +1137,96 @@ END:VCALENDAR"#;
         assert_eq!(events[1].title, "Doctor Appointment");
     }
 
+    #[test]
+    fn test_parse_recurring_events() {
+        let today = Local::now().date_naive();
+        let day = |offset: i64| (today + chrono::Duration::days(offset)).format("%Y%m%d");
+        let ics_content = format!(
+            "BEGIN:VCALENDAR\nVERSION:2.0\nPRODID:-//Test//Test//EN\n\
+             BEGIN:VEVENT\nUID:standup@example.com\nDTSTART:{start}T235959\n\
+             RRULE:FREQ=DAILY\nEXDATE:{skipped}T235959\nSUMMARY:Standup\nEND:VEVENT\n\
+             BEGIN:VEVENT\nUID:birthday@example.com\nDTSTART;VALUE=DATE:{birthday}\n\
+             RRULE:FREQ=YEARLY\nSUMMARY:Birthday\nEND:VEVENT\n\
+             END:VCALENDAR",
+            start = day(-30),
+            skipped = day(1),
+            birthday = (today - chrono::Months::new(24)).format("%Y%m%d"),
+        );
+
+        let temp_file = create_test_ics(&ics_content);
+        let widget = CalendarWidget::new(vec![], 5, true, 2, 300).with_view(CalendarView::Month);
+        let events = widget.parse_calendar(temp_file.path()).unwrap();
+
+        let standups: Vec<NaiveDate> = events
+            .iter()
+            .filter(|event| event.title == "Standup")
+            .map(|event| event.start.date_naive())
+            .collect();
+        // The month view expands from the 1st, skipping the excluded day
+        assert!(standups.contains(&today));
+        assert!(standups.contains(&today.with_day(1).unwrap()));
+        assert!(!standups.contains(&(today + chrono::Duration::days(1))));
+
+        let birthdays: Vec<&CalendarEvent> = events
+            .iter()
+            .filter(|event| event.title == "Birthday")
+            .collect();
+        assert_eq!(birthdays.len(), 1);
+        assert!(birthdays[0].all_day);
+    }
+
+    #[test]
+    fn test_trigger_minutes() {
+        assert_eq!(trigger_minutes("-PT15M"), Some(15));
+        assert_eq!(trigger_minutes("-PT1H30M"), Some(90));
+        assert_eq!(trigger_minutes("-P1D"), Some(24 * 60));
+        assert_eq!(trigger_minutes("-P1DT2H"), Some(26 * 60));
+        assert_eq!(trigger_minutes("-PT0S"), Some(0));
+        // After the start, or not a duration at all
+        assert_eq!(trigger_minutes("PT5M"), None);
+        assert_eq!(trigger_minutes("20260302T090000Z"), None);
+    }
+
+    #[test]
+    fn test_event_reminders() {
+        let start = (Local::now() + chrono::Duration::hours(3)).format("%Y%m%dT%H%M00");
+        let ics_content = format!(
+            "BEGIN:VCALENDAR\nVERSION:2.0\nPRODID:-//Test//Test//EN\n\
+             BEGIN:VEVENT\nUID:review@example.com\nDTSTART:{start}\nSUMMARY:Review\n\
+             BEGIN:VALARM\nACTION:DISPLAY\nTRIGGER:-PT30M\nEND:VALARM\nEND:VEVENT\n\
+             BEGIN:VEVENT\nUID:sync@example.com\nDTSTART:{start}\nSUMMARY:Sync\nEND:VEVENT\n\
+             END:VCALENDAR"
+        );
+        let temp_file = create_test_ics(&ics_content);
+
+        // Only the event with its own alarm reminds by default
+        let mut widget = CalendarWidget::new(vec![temp_file.path().to_path_buf()], 5, true, 1, 300);
+        widget.update();
+        let reminders = widget.reminders();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].summary, "Review");
+        assert_eq!(
+            widget.events[0].start - reminders[0].at,
+            chrono::Duration::minutes(30)
+        );
+
+        // A global lead time covers the rest
+        let mut widget = CalendarWidget::new(vec![temp_file.path().to_path_buf()], 5, true, 1, 300)
+            .with_reminders(10)
+            .with_reminder_sound(true);
+        widget.update();
+        let mut reminders = widget.reminders();
+        reminders.sort_by(|a, b| a.summary.cmp(&b.summary));
+        assert_eq!(reminders.len(), 2);
+        assert_eq!(reminders[1].summary, "Sync");
+        assert!(reminders[1].sound);
+        assert_eq!(
+            widget.events[0].start - reminders[1].at,
+            chrono::Duration::minutes(10)
+        );
+        assert_ne!(reminders[0].id, reminders[1].id);
+    }
+
     #[test]
     fn test_format_event() {
         let widget = CalendarWidget::new(vec![], 5, true, 2, 300);
@@ -560,6 +1236,7 @@ END:VCALENDAR"#;
             start: now,
             title: "Test Event".to_string(),
             all_day: false,
+            reminder_minutes: None,
         };
 
         let formatted = widget.format_event(&event);
@@ -576,6 +1253,7 @@ END:VCALENDAR"#;
             start: now,
             title: "All Day Event".to_string(),
             all_day: true,
+            reminder_minutes: None,
         };
 
         let formatted = widget.format_event(&event);
@@ -614,5 +1292,131 @@ END:VCALENDAR"#;
         let mut invalid = toml::Table::new();
         invalid.insert("days_ahead".to_string(), toml::Value::Integer(-1));
         assert!(factory.validate_config(&invalid).is_err());
+
+        // Invalid view
+        let mut invalid = toml::Table::new();
+        invalid.insert("view".to_string(), toml::Value::String("week".to_string()));
+        assert!(factory.validate_config(&invalid).is_err());
+
+        // Google without an OAuth client
+        let mut invalid = toml::Table::new();
+        invalid.insert(
+            "source".to_string(),
+            toml::Value::String("google".to_string()),
+        );
+        assert!(factory.validate_config(&invalid).is_err());
+        assert!(factory.create(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_google_source_config() {
+        let factory = CalendarWidgetFactory;
+        let config: toml::Table = toml::from_str(
+            r#"
+            source = "google"
+            google_client_id = "id.apps.googleusercontent.com"
+            google_client_secret = "secret"
+            upcoming_hours = 12
+            "#,
+        )
+        .unwrap();
+        assert!(factory.validate_config(&config).is_ok());
+
+        let widget = factory.create(&config).unwrap();
+        // Fetched by the data service rather than read in update()
+        assert!(widget.data_source().is_some());
+
+        let ics = factory.create(&factory.default_config()).unwrap();
+        assert!(ics.data_source().is_none());
+    }
+
+    #[test]
+    fn test_upcoming_hours_window() {
+        let now = Local::now();
+        let mut widget = CalendarWidget::new(vec![], 5, true, 2, 300).with_upcoming_hours(3);
+        widget.set_events(vec![
+            CalendarEvent {
+                start: now + chrono::Duration::hours(2),
+                title: "Soon".to_string(),
+                all_day: false,
+                reminder_minutes: None,
+            },
+            CalendarEvent {
+                start: now + chrono::Duration::hours(5),
+                title: "Later".to_string(),
+                all_day: false,
+                reminder_minutes: None,
+            },
+        ]);
+        assert_eq!(widget.events.len(), 1);
+        assert_eq!(widget.events[0].title, "Soon");
+    }
+
+    #[test]
+    fn test_fetch_range() {
+        let now = Local.with_ymd_and_hms(2026, 3, 14, 10, 0, 0).unwrap();
+        let window = chrono::Duration::hours(24);
+
+        let (start, end) = fetch_range(now, window, CalendarView::Agenda);
+        assert_eq!((start, end), (now, now + window));
+
+        // The month grid needs the whole month, from the 1st
+        let (start, end) = fetch_range(now, window, CalendarView::Month);
+        assert_eq!(
+            start.date_naive(),
+            NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()
+        );
+        assert_eq!(
+            end.date_naive(),
+            NaiveDate::from_ymd_opt(2026, 4, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_month_grid() {
+        // October 2026 starts on a Thursday and has 31 days
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let event_days = BTreeSet::from([
+            NaiveDate::from_ymd_opt(2026, 10, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 10, 31).unwrap(),
+        ]);
+
+        let WidgetContent::Grid {
+            title,
+            header,
+            cells,
+        } = month_grid(today, &event_days)
+        else {
+            panic!("Expected a grid");
+        };
+        assert_eq!(title.as_deref(), Some("October 2026"));
+        assert_eq!(header.len(), 7);
+        assert_eq!(cells.len(), 3 + 31);
+        assert!(cells[..3].iter().all(|cell| cell.text.is_empty()));
+
+        assert_eq!(cells[3].text, "1");
+        assert!(cells[3].marked);
+        assert!(cells[3 + 14].highlighted);
+        assert_eq!(cells[3 + 14].text, "15");
+        assert!(cells[3 + 30].marked);
+        assert_eq!(cells.iter().filter(|cell| cell.highlighted).count(), 1);
+    }
+
+    #[test]
+    fn test_month_grid_leap_february() {
+        let today = NaiveDate::from_ymd_opt(2028, 2, 1).unwrap();
+        let WidgetContent::Grid { cells, .. } = month_grid(today, &BTreeSet::new()) else {
+            panic!("Expected a grid");
+        };
+        // February 2028 starts on a Tuesday and has 29 days
+        assert_eq!(cells.len(), 1 + 29);
+        assert_eq!(cells.last().unwrap().text, "29");
+    }
+
+    #[test]
+    fn test_month_view_content() {
+        let widget = CalendarWidget::new(vec![], 5, true, 2, 300).with_view(CalendarView::Month);
+        assert!(matches!(widget.content(), WidgetContent::Grid { .. }));
+        assert_eq!(widget.info().preferred_height, 200.0);
     }
 }
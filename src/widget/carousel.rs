@@ -0,0 +1,510 @@
+//! Carousel widget cycling through several child widgets
+//!
+//! The children are ordinary widgets built by the registry from a nested
+//! `children` list, shown one at a time in the carousel's surface. They all
+//! keep updating and fetching in the background, so whichever comes up next
+//! is current.
+
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context};
+use chrono::Local;
+use tracing::{debug, warn};
+
+use super::registry::{DynWidgetFactory, WidgetRegistry};
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{
+    FetchReport, MouseButton, ScrollDirection, SoundCue, Widget, WidgetAction, WidgetContent,
+    WidgetInfo,
+};
+use crate::metrics::PerfStats;
+use crate::update::tasks::{self, TaskFuture, TaskOutput, CRON_RETRY};
+use crate::update::{Schedule, Source, Ticker};
+
+/// What one fetch of the carousel's source returns: each fetched child's
+/// result, by child index
+type ChildResults = Vec<(usize, anyhow::Result<TaskOutput>)>;
+
+/// Carousel widget showing one child at a time
+pub struct CarouselWidget {
+    children: Vec<Box<dyn Widget>>,
+    current: usize,
+    rotation: Ticker,
+}
+
+impl CarouselWidget {
+    /// Cycle through `children` every 10 seconds
+    pub fn new(children: Vec<Box<dyn Widget>>) -> Self {
+        Self {
+            children,
+            current: 0,
+            rotation: Ticker::new(Schedule::Every(Duration::from_secs(10))),
+        }
+    }
+
+    /// Move on to the next child on `schedule` instead
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.rotation = Ticker::new(schedule);
+        self
+    }
+
+    /// Index of the child being shown
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    fn current(&self) -> Option<&dyn Widget> {
+        self.children.get(self.current).map(|child| child.as_ref())
+    }
+
+    fn current_mut(&mut self) -> Option<&mut Box<dyn Widget>> {
+        self.children.get_mut(self.current)
+    }
+
+    /// Show the next child, or the previous one, wrapping around
+    fn advance(&mut self, forward: bool) {
+        let count = self.children.len();
+        if count == 0 {
+            return;
+        }
+        self.current = if forward {
+            (self.current + 1) % count
+        } else {
+            (self.current + count - 1) % count
+        };
+        debug!(index = self.current, "Carousel advanced");
+    }
+}
+
+impl Widget for CarouselWidget {
+    fn info(&self) -> WidgetInfo {
+        let infos: Vec<WidgetInfo> = self.children.iter().map(|child| child.info()).collect();
+        WidgetInfo {
+            id: "carousel",
+            name: "Carousel",
+            preferred_height: infos
+                .iter()
+                .map(|info| info.preferred_height)
+                .fold(40.0, f32::max),
+            min_height: infos
+                .iter()
+                .map(|info| info.min_height)
+                .fold(30.0, f32::max),
+            expand: false,
+        }
+    }
+
+    fn update(&mut self) {
+        for child in &mut self.children {
+            child.update();
+        }
+        if self.rotation.tick() {
+            self.advance(true);
+        }
+    }
+
+    fn content(&self) -> WidgetContent {
+        self.current()
+            .map_or(WidgetContent::Empty, |child| child.content())
+    }
+
+    fn content_ref(&self) -> Cow<'_, WidgetContent> {
+        match self.current() {
+            Some(child) => child.content_ref(),
+            None => Cow::Owned(WidgetContent::Empty),
+        }
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.children
+            .iter()
+            .map(|child| child.update_interval())
+            .min()
+            .unwrap_or(Duration::from_secs(1))
+    }
+
+    fn is_ready(&self) -> bool {
+        self.current().map_or(true, |child| child.is_ready())
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.current().and_then(|child| child.error())
+    }
+
+    fn take_sound_cue(&mut self) -> Option<SoundCue> {
+        self.children
+            .iter_mut()
+            .find_map(|child| child.take_sound_cue())
+    }
+
+    fn observe_metrics(&mut self, stats: &PerfStats) {
+        for child in &mut self.children {
+            child.observe_metrics(stats);
+        }
+    }
+
+    fn take_fetch_reports(&mut self) -> Vec<FetchReport> {
+        self.children
+            .iter_mut()
+            .flat_map(|child| child.take_fetch_reports())
+            .collect()
+    }
+
+    /// One source covering every child that fetches
+    ///
+    /// It is polled as often as the most frequent child needs, and each
+    /// poll fetches only the children whose own schedule is (about) due.
+    fn data_source(&self) -> Option<Source> {
+        let sources: Vec<(usize, Source)> = self
+            .children
+            .iter()
+            .enumerate()
+            .filter_map(|(index, child)| Some((index, child.data_source()?)))
+            .collect();
+        let interval = sources
+            .iter()
+            .map(|(_, source)| match source.schedule() {
+                Schedule::Every(interval) => *interval,
+                Schedule::Cron(_) => CRON_RETRY,
+            })
+            .min()?;
+        let needs_network = sources.iter().any(|(_, source)| source.needs_network());
+
+        // Polls are jittered, so a child counts as due within half a period
+        let slack = interval / 2;
+        let due = Arc::new(Mutex::new(vec![Instant::now(); sources.len()]));
+        let source = Source::new(interval, move || {
+            let now = Instant::now();
+            let mut due = due.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let fetches: Vec<(usize, TaskFuture)> = sources
+                .iter()
+                .zip(due.iter_mut())
+                .filter(|(_, at)| **at <= now + slack)
+                .map(|((index, source), at)| {
+                    *at = now + source.schedule().delay_from(Local::now());
+                    (*index, source.fetch())
+                })
+                .collect();
+            drop(due);
+
+            tasks::task(async move {
+                let mut results: ChildResults = Vec::with_capacity(fetches.len());
+                for (index, fetch) in fetches {
+                    results.push((index, fetch.await));
+                }
+                Ok(results)
+            })
+        });
+        Some(source.with_network(needs_network))
+    }
+
+    fn apply_fetch(&mut self, result: anyhow::Result<TaskOutput>) {
+        match tasks::take_output::<ChildResults>(result) {
+            Ok(results) => {
+                for (index, result) in results {
+                    if let Some(child) = self.children.get_mut(index) {
+                        child.apply_fetch(result);
+                    }
+                }
+            }
+            Err(e) => warn!(error = %e, "Carousel fetch failed"),
+        }
+    }
+
+    fn glyph_set(&self) -> Option<String> {
+        let glyphs: String = self
+            .children
+            .iter()
+            .filter_map(|child| child.glyph_set())
+            .collect();
+        (!glyphs.is_empty()).then_some(glyphs)
+    }
+
+    fn on_start(&mut self) {
+        for child in &mut self.children {
+            child.on_start();
+        }
+    }
+
+    fn on_stop(&mut self) {
+        for child in &mut self.children {
+            child.on_stop();
+        }
+    }
+
+    fn on_suspend(&mut self) {
+        for child in &mut self.children {
+            child.on_suspend();
+        }
+    }
+
+    fn on_resume(&mut self) {
+        for child in &mut self.children {
+            child.on_resume();
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn on_click(&mut self, button: MouseButton, x: f32, y: f32) -> Option<WidgetAction> {
+        self.current_mut()?.on_click(button, x, y)
+    }
+
+    fn on_scroll(&mut self, direction: ScrollDirection, _x: f32, _y: f32) -> Option<WidgetAction> {
+        let forward = matches!(direction, ScrollDirection::Down | ScrollDirection::Right);
+        self.advance(forward);
+        self.rotation.restart();
+        Some(if forward {
+            WidgetAction::NextItem
+        } else {
+            WidgetAction::PreviousItem
+        })
+    }
+
+    fn on_pointer_enter(&mut self) {
+        if let Some(child) = self.current_mut() {
+            child.on_pointer_enter();
+        }
+    }
+
+    fn on_pointer_leave(&mut self) {
+        if let Some(child) = self.current_mut() {
+            child.on_pointer_leave();
+        }
+    }
+
+    fn set_compact(&mut self, compact: bool) {
+        for child in &mut self.children {
+            child.set_compact(compact);
+        }
+    }
+}
+
+/// Split a carousel's `children` list into (type, config) pairs
+///
+/// A child is written like a `[[widgets]]` entry: a `type` and either a
+/// `config` table or its settings inline.
+fn child_configs(config: &toml::Table) -> anyhow::Result<Vec<(String, toml::Table)>> {
+    let children = config
+        .get("children")
+        .context("'children' is required")?
+        .as_array()
+        .context("'children' must be an array of tables")?;
+    if children.is_empty() {
+        bail!("'children' must list at least one widget");
+    }
+
+    children
+        .iter()
+        .enumerate()
+        .map(|(i, child)| {
+            let mut table = child
+                .as_table()
+                .with_context(|| format!("children[{}] must be a table", i))?
+                .clone();
+            let widget_type = match table.remove("type") {
+                Some(toml::Value::String(widget_type)) => widget_type,
+                _ => bail!("children[{}] needs a 'type'", i),
+            };
+            if widget_type == "carousel" {
+                bail!("children[{}]: carousels can't contain other carousels", i);
+            }
+            let config = match table.remove("config") {
+                Some(toml::Value::Table(config)) => config,
+                Some(_) => bail!("children[{}]: 'config' must be a table", i),
+                None => table,
+            };
+            Ok((widget_type, config))
+        })
+        .collect()
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+/// Factory for CarouselWidget
+pub struct CarouselWidgetFactory;
+
+impl DynWidgetFactory for CarouselWidgetFactory {
+    fn widget_type(&self) -> &'static str {
+        "carousel"
+    }
+
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Carousel",
+            "Takes turns showing several widgets in one spot",
+            "view-paged-symbolic",
+        )
+    }
+
+    fn create(&self, config: &toml::Table) -> anyhow::Result<Box<dyn Widget>> {
+        let schedule = Schedule::from_config(config, "rotation_interval", 10)?;
+
+        let registry = WidgetRegistry::with_user_widgets();
+        let children = child_configs(config)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, (widget_type, child))| {
+                registry
+                    .create(&widget_type, &child)
+                    .with_context(|| format!("Failed to create carousel child {}", i))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        debug!(
+            children = children.len(),
+            schedule = %schedule,
+            "Creating CarouselWidget"
+        );
+
+        Ok(Box::new(
+            CarouselWidget::new(children).with_schedule(schedule),
+        ))
+    }
+
+    fn default_config(&self) -> toml::Table {
+        let child = |widget_type: &str| {
+            let mut table = toml::Table::new();
+            table.insert(
+                "type".to_string(),
+                toml::Value::String(widget_type.to_string()),
+            );
+            toml::Value::Table(table)
+        };
+
+        let mut config = toml::Table::new();
+        config.insert("rotation_interval".to_string(), toml::Value::Integer(10));
+        config.insert(
+            "children".to_string(),
+            toml::Value::Array(vec![child("clock"), child("weather")]),
+        );
+        config
+    }
+
+    fn validate_config(&self, config: &toml::Table) -> anyhow::Result<()> {
+        if let Some(interval) = config.get("rotation_interval") {
+            let interval_val = interval
+                .as_integer()
+                .context("'rotation_interval' must be an integer")?;
+            if interval_val < 1 {
+                bail!("'rotation_interval' must be at least 1 second");
+            }
+        }
+        child_configs(config)?;
+        Ok(())
+    }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_interactive()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new(
+                "rotation_interval",
+                "Rotation interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(
+                ConfigField::new("schedule", "Schedule", FieldKind::Text)
+                    .with_description("Cron expression, e.g. 0 */5 * * * * (overrides interval)"),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::{ClockWidget, WeatherWidget};
+
+    fn carousel() -> CarouselWidget {
+        CarouselWidget::new(vec![
+            Box::new(ClockWidget::new("24h", false, false)),
+            Box::new(WeatherWidget::new("London", "test_key", "celsius", 600)),
+        ])
+    }
+
+    #[test]
+    fn test_scroll_cycles_children() {
+        let mut widget = carousel();
+        assert!(matches!(widget.content(), WidgetContent::Text { .. }));
+
+        assert_eq!(
+            widget.on_scroll(ScrollDirection::Down, 0.5, 0.5),
+            Some(WidgetAction::NextItem)
+        );
+        assert_eq!(widget.current_index(), 1);
+        widget.on_scroll(ScrollDirection::Down, 0.5, 0.5);
+        assert_eq!(widget.current_index(), 0);
+        widget.on_scroll(ScrollDirection::Up, 0.5, 0.5);
+        assert_eq!(widget.current_index(), 1);
+    }
+
+    #[test]
+    fn test_fetches_reach_children() {
+        let mut widget = carousel();
+        let source = widget.data_source().unwrap();
+        assert_eq!(
+            source.schedule(),
+            &Schedule::Every(Duration::from_secs(600))
+        );
+        assert!(source.needs_network());
+
+        let results: ChildResults = vec![(1, Err(anyhow::anyhow!("City not found")))];
+        widget.apply_fetch(Ok(Box::new(results)));
+        assert_eq!(widget.error(), None); // the clock is showing
+        widget.on_scroll(ScrollDirection::Down, 0.5, 0.5);
+        assert_eq!(widget.error(), Some("City not found"));
+    }
+
+    #[test]
+    fn test_child_configs() {
+        let config: toml::Table = toml::from_str(
+            r#"
+            children = [
+                { type = "clock", format = "12h" },
+                { type = "weather", config = { city = "Oslo" } },
+            ]
+            "#,
+        )
+        .unwrap();
+        let children = child_configs(&config).unwrap();
+        assert_eq!(children[0].0, "clock");
+        assert_eq!(children[0].1.get("format").unwrap().as_str(), Some("12h"));
+        assert_eq!(children[1].1.get("city").unwrap().as_str(), Some("Oslo"));
+
+        for invalid in [
+            "children = []",
+            "children = [{ format = \"12h\" }]",
+            "children = [{ type = \"carousel\" }]",
+        ] {
+            let config: toml::Table = toml::from_str(invalid).unwrap();
+            assert!(child_configs(&config).is_err(), "accepted {}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_factory_creation() {
+        let factory = CarouselWidgetFactory;
+        let widget = factory.create(&factory.default_config()).unwrap();
+        assert_eq!(widget.info().id, "carousel");
+
+        let mut config = factory.default_config();
+        config.insert(
+            "children".to_string(),
+            toml::Value::Array(vec![toml::Value::Table(
+                toml::from_str("type = \"nonexistent\"").unwrap(),
+            )]),
+        );
+        assert!(factory.create(&config).is_err());
+    }
+}
@@ -1,20 +1,230 @@
 //! Countdown Timer widget
 //!
-//! This widget displays a countdown to a target date/time.
+//! This widget displays a countdown to one or more target dates/times,
+//! stacked or rotating. Targets can repeat (a weekly standup, a yearly
+//! anniversary), rolling over to the next occurrence once reached.
 
 use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context};
-use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono::{DateTime, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
 use tracing::debug;
 
 use super::registry::DynWidgetFactory;
-use super::traits::{FontSize, TextSegment, Widget, WidgetContent, WidgetInfo};
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetMetadata};
+use super::traits::{FontSize, SoundCue, TextSegment, Widget, WidgetContent, WidgetInfo};
+use crate::update::{Reminder, Schedule, Ticker};
 
-/// Countdown widget showing time remaining until a target
-pub struct CountdownWidget {
+/// Minutes before a target the screen is kept awake, with `inhibit_idle` set
+const INHIBIT_IDLE_MINUTES: i64 = 5;
+
+/// How often a countdown target comes around again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Repeat {
+    /// A one-off target
+    #[default]
+    Never,
+    /// Every day at the same time
+    Daily,
+    /// Every week on the same weekday
+    Weekly,
+    /// Every month on the same day (or the last day of shorter months)
+    Monthly,
+    /// Every year on the same date
+    Yearly,
+}
+
+impl Repeat {
+    /// Values accepted by the `repeat` option
+    pub const NAMES: &'static [&'static str] = &["never", "daily", "weekly", "monthly", "yearly"];
+
+    /// Parse a `repeat` option value
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "never" => Some(Self::Never),
+            "daily" => Some(Self::Daily),
+            "weekly" => Some(Self::Weekly),
+            "monthly" => Some(Self::Monthly),
+            "yearly" => Some(Self::Yearly),
+            _ => None,
+        }
+    }
+
+    /// The `n`th occurrence after `anchor`, counting `anchor` itself as 0
+    fn nth(&self, anchor: NaiveDateTime, n: u32) -> Option<NaiveDateTime> {
+        match self {
+            Self::Never => (n == 0).then_some(anchor),
+            Self::Daily => anchor.checked_add_signed(chrono::Duration::days(n.into())),
+            Self::Weekly => anchor.checked_add_signed(chrono::Duration::weeks(n.into())),
+            Self::Monthly => anchor.checked_add_months(Months::new(n)),
+            Self::Yearly => anchor.checked_add_months(Months::new(n.checked_mul(12)?)),
+        }
+    }
+
+    /// The first occurrence of a target anchored at `anchor` after `now`
+    ///
+    /// Counting from the anchor each time keeps monthly targets on the 31st
+    /// from drifting after a short month. One-off targets stay put.
+    pub fn next_after(&self, anchor: DateTime<Local>, now: DateTime<Local>) -> DateTime<Local> {
+        if *self == Self::Never || anchor > now {
+            return anchor;
+        }
+        let naive = anchor.naive_local();
+        let elapsed = now.naive_local() - naive;
+        // Start just short of the answer and step forward
+        let estimate = match self {
+            Self::Never => 0,
+            Self::Daily => elapsed.num_days(),
+            Self::Weekly => elapsed.num_weeks(),
+            Self::Monthly => elapsed.num_days() / 31,
+            Self::Yearly => elapsed.num_days() / 366,
+        };
+        let mut n = u32::try_from(estimate)
+            .unwrap_or(u32::MAX)
+            .saturating_sub(1);
+        while let Some(next) = self.nth(naive, n) {
+            if let Some(next) = Local.from_local_datetime(&next).earliest() {
+                if next > now {
+                    return next;
+                }
+            }
+            n = match n.checked_add(1) {
+                Some(n) => n,
+                None => break,
+            };
+        }
+        anchor
+    }
+}
+
+/// What a countdown shows once its target has passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Completion {
+    /// "Passed!"
+    #[default]
+    Passed,
+    /// "Done"
+    Done,
+    /// Nothing; the target disappears from the widget
+    Hide,
+}
+
+impl Completion {
+    /// Values accepted by the `on_complete` option
+    pub const NAMES: &'static [&'static str] = &["passed", "done", "hide"];
+
+    /// Parse an `on_complete` option value
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "passed" => Some(Self::Passed),
+            "done" => Some(Self::Done),
+            "hide" => Some(Self::Hide),
+            _ => None,
+        }
+    }
+
+    fn text(&self) -> &'static str {
+        match self {
+            Self::Passed | Self::Hide => "Passed!",
+            Self::Done => "Done",
+        }
+    }
+}
+
+/// How several countdowns share the widget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CountdownLayout {
+    /// One line per countdown
+    #[default]
+    Stacked,
+    /// One countdown at a time, taking turns
+    Rotate,
+}
+
+impl CountdownLayout {
+    /// Values accepted by the `display` option
+    pub const NAMES: &'static [&'static str] = &["stacked", "rotate"];
+
+    /// Parse a `display` option value
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "stacked" => Some(Self::Stacked),
+            "rotate" => Some(Self::Rotate),
+            _ => None,
+        }
+    }
+}
+
+/// One date counted down to
+#[derive(Debug, Clone)]
+pub struct CountdownTarget {
     label: String,
+    /// The first occurrence, which repeats are counted from
+    anchor: DateTime<Local>,
+    /// The occurrence currently counted down to
     target: DateTime<Local>,
+    repeat: Repeat,
+    /// Whether the target has been reached (and announced)
+    reached: bool,
+}
+
+impl CountdownTarget {
+    /// Count down to `target`
+    pub fn new(label: &str, target: DateTime<Local>) -> Self {
+        Self {
+            label: label.to_string(),
+            anchor: target,
+            target,
+            repeat: Repeat::Never,
+            // Targets already in the past never play a sound
+            reached: target <= Local::now(),
+        }
+    }
+
+    /// Count down to `date_str` (YYYY-MM-DD or YYYY-MM-DD HH:MM:SS)
+    pub fn from_date_string(label: &str, date_str: &str) -> anyhow::Result<Self> {
+        Ok(Self::new(label, CountdownWidget::parse_datetime(date_str)?))
+    }
+
+    /// Come around again every `repeat`, starting from the next occurrence
+    pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        let now = Local::now();
+        self.target = repeat.next_after(self.anchor, now);
+        self.reached = self.target <= now;
+        self
+    }
+
+    /// Label shown before the time left
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The occurrence currently counted down to
+    pub fn target(&self) -> DateTime<Local> {
+        self.target
+    }
+
+    /// Mark the target reached if it is due at `now`
+    ///
+    /// Returns true the first time. Repeating targets move on to their
+    /// next occurrence instead of staying reached.
+    fn reach(&mut self, now: DateTime<Local>) -> bool {
+        if self.reached || self.target > now {
+            return false;
+        }
+        if self.repeat == Repeat::Never {
+            self.reached = true;
+        } else {
+            self.target = self.repeat.next_after(self.anchor, now);
+        }
+        true
+    }
+}
+
+/// Countdown widget showing time remaining until one or more targets
+pub struct CountdownWidget {
+    targets: Vec<CountdownTarget>,
     last_update: Instant,
 
     // Configuration
@@ -22,6 +232,19 @@ pub struct CountdownWidget {
     show_hours: bool,
     show_minutes: bool,
     show_seconds: bool,
+    on_complete: Completion,
+    layout: CountdownLayout,
+    /// Play the alarm sound when a target is reached
+    sound: bool,
+    /// Show a desktop notification when a target is reached
+    notify: bool,
+    /// Keep the screen awake in the last minutes before a target
+    inhibit_idle: bool,
+
+    /// Target shown when rotating, among the visible ones
+    current: usize,
+    rotation: Ticker,
+    sound_cue: Option<SoundCue>,
 }
 
 impl CountdownWidget {
@@ -33,15 +256,39 @@ impl CountdownWidget {
         show_hours: bool,
         show_minutes: bool,
         show_seconds: bool,
+    ) -> Self {
+        Self::with_targets(
+            vec![CountdownTarget::new(label, target)],
+            show_days,
+            show_hours,
+            show_minutes,
+            show_seconds,
+        )
+    }
+
+    /// Create a widget counting down to several targets
+    pub fn with_targets(
+        targets: Vec<CountdownTarget>,
+        show_days: bool,
+        show_hours: bool,
+        show_minutes: bool,
+        show_seconds: bool,
     ) -> Self {
         Self {
-            label: label.to_string(),
-            target,
+            targets,
             last_update: Instant::now(),
             show_days,
             show_hours,
             show_minutes,
             show_seconds,
+            on_complete: Completion::Passed,
+            layout: CountdownLayout::Stacked,
+            sound: true,
+            notify: false,
+            inhibit_idle: false,
+            current: 0,
+            rotation: Ticker::new(Schedule::Every(Duration::from_secs(5))),
+            sound_cue: None,
         }
     }
 
@@ -65,6 +312,33 @@ impl CountdownWidget {
         ))
     }
 
+    /// Show `on_complete` for targets that have passed
+    pub fn with_completion(mut self, on_complete: Completion) -> Self {
+        self.on_complete = on_complete;
+        self
+    }
+
+    /// Stack the targets, or rotate through them every `rotation_interval`
+    pub fn with_layout(mut self, layout: CountdownLayout, rotation_interval: Duration) -> Self {
+        self.layout = layout;
+        self.rotation = Ticker::new(Schedule::Every(rotation_interval));
+        self
+    }
+
+    /// Whether to play the alarm sound and show a notification when a
+    /// target is reached
+    pub fn with_alerts(mut self, sound: bool, notify: bool) -> Self {
+        self.sound = sound;
+        self.notify = notify;
+        self
+    }
+
+    /// Keep the screen awake during the last minutes before each target
+    pub fn with_inhibit_idle(mut self, inhibit_idle: bool) -> Self {
+        self.inhibit_idle = inhibit_idle;
+        self
+    }
+
     /// Parse a datetime string
     fn parse_datetime(date_str: &str) -> anyhow::Result<DateTime<Local>> {
         // Try parsing as full datetime first
@@ -90,73 +364,104 @@ impl CountdownWidget {
         )
     }
 
-    /// Calculate remaining time
-    fn remaining(&self) -> chrono::Duration {
+    /// Targets that are shown, leaving out hidden completed ones
+    fn visible_targets(&self) -> Vec<&CountdownTarget> {
         let now = Local::now();
-        self.target - now
+        self.targets
+            .iter()
+            .filter(|target| self.on_complete != Completion::Hide || target.target > now)
+            .collect()
     }
 
-    /// Format the countdown display
-    pub fn display_string(&self) -> String {
-        let remaining = self.remaining();
+    /// Targets shown right now: all of them, or the current one when rotating
+    fn shown_targets(&self) -> Vec<&CountdownTarget> {
+        let visible = self.visible_targets();
+        match self.layout {
+            CountdownLayout::Stacked => visible,
+            CountdownLayout::Rotate if visible.is_empty() => visible,
+            CountdownLayout::Rotate => vec![visible[self.current % visible.len()]],
+        }
+    }
+
+    /// Time left until `target`, split into days, hours, minutes and seconds
+    fn remaining_parts(target: &CountdownTarget) -> Option<(i64, i64, i64, i64)> {
+        let remaining = target.target - Local::now();
 
         // Check if countdown has passed
         if remaining < chrono::Duration::zero() {
-            return format!("{}: Passed!", self.label);
+            return None;
         }
 
         let total_seconds = remaining.num_seconds();
-        let days = total_seconds / 86400;
-        let hours = (total_seconds % 86400) / 3600;
-        let minutes = (total_seconds % 3600) / 60;
-        let seconds = total_seconds % 60;
+        Some((
+            total_seconds / 86400,
+            (total_seconds % 86400) / 3600,
+            (total_seconds % 3600) / 60,
+            total_seconds % 60,
+        ))
+    }
+
+    /// Format the countdown display, one line per shown target
+    pub fn display_string(&self) -> String {
+        self.shown_targets()
+            .into_iter()
+            .map(|target| self.target_string(target))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn target_string(&self, target: &CountdownTarget) -> String {
+        let Some((days, hours, minutes, seconds)) = Self::remaining_parts(target) else {
+            return format!("{}: {}", target.label, self.on_complete.text());
+        };
 
         let mut parts = Vec::new();
 
         if self.show_days && days > 0 {
-            parts.push(format!("{}d", days));
+            parts.push(crate::fl!("duration-days", count = days));
         }
 
         if self.show_hours && (hours > 0 || days > 0) {
-            parts.push(format!("{}h", hours));
+            parts.push(crate::fl!("duration-hours", count = hours));
         }
 
         if self.show_minutes && (minutes > 0 || hours > 0 || days > 0) {
-            parts.push(format!("{}m", minutes));
+            parts.push(crate::fl!("duration-minutes", count = minutes));
         }
 
         if self.show_seconds {
-            parts.push(format!("{}s", seconds));
+            parts.push(crate::fl!("duration-seconds", count = seconds));
         }
 
         if parts.is_empty() {
-            format!("{}: Now!", self.label)
+            format!("{}: {}", target.label, crate::fl!("now"))
         } else {
-            format!("{}: {}", self.label, parts.join(" "))
+            format!("{}: {}", target.label, parts.join(" "))
         }
     }
 
     /// Generate styled text segments with bold numbers and regular units
+    ///
+    /// Shows the first target shown; stacked countdowns of more than one
+    /// target are drawn as plain lines instead.
     pub fn styled_segments(&self) -> Vec<TextSegment> {
-        let remaining = self.remaining();
+        match self.shown_targets().first() {
+            Some(target) => self.target_segments(target),
+            None => Vec::new(),
+        }
+    }
 
-        // Check if countdown has passed
-        if remaining < chrono::Duration::zero() {
+    fn target_segments(&self, target: &CountdownTarget) -> Vec<TextSegment> {
+        let Some((days, hours, minutes, seconds)) = Self::remaining_parts(target) else {
             return vec![
-                TextSegment::regular(&self.label),
+                TextSegment::regular(&target.label),
                 TextSegment::regular(": "),
-                TextSegment::bold("Passed!"),
+                TextSegment::bold(self.on_complete.text()),
             ];
-        }
-
-        let total_seconds = remaining.num_seconds();
-        let days = total_seconds / 86400;
-        let hours = (total_seconds % 86400) / 3600;
-        let minutes = (total_seconds % 3600) / 60;
-        let seconds = total_seconds % 60;
+        };
 
         let mut segments = vec![
-            TextSegment::regular(&self.label),
+            TextSegment::regular(&target.label),
             TextSegment::regular(": "),
         ];
 
@@ -196,10 +501,14 @@ impl CountdownWidget {
 
 impl Widget for CountdownWidget {
     fn info(&self) -> WidgetInfo {
+        let lines = match self.layout {
+            CountdownLayout::Stacked => self.targets.len().max(1),
+            CountdownLayout::Rotate => 1,
+        };
         WidgetInfo {
             id: "countdown",
             name: "Countdown",
-            preferred_height: 40.0,
+            preferred_height: 40.0 + 24.0 * (lines - 1) as f32,
             min_height: 30.0,
             expand: false,
         }
@@ -208,12 +517,90 @@ impl Widget for CountdownWidget {
     fn update(&mut self) {
         // Update every second for accurate countdown
         self.last_update = Instant::now();
+
+        let now = Local::now();
+        let mut reached = false;
+        for target in &mut self.targets {
+            if target.reach(now) {
+                debug!(label = %target.label, "Countdown reached");
+                reached = true;
+            }
+        }
+        if reached && self.sound {
+            self.sound_cue = Some(SoundCue::Alarm);
+        }
+
+        if self.layout == CountdownLayout::Rotate && self.rotation.tick() {
+            self.current = self.current.wrapping_add(1);
+        }
+    }
+
+    fn take_sound_cue(&mut self) -> Option<SoundCue> {
+        self.sound_cue.take()
+    }
+
+    fn save_state(&self) -> Option<toml::Table> {
+        if self.layout != CountdownLayout::Rotate {
+            return None;
+        }
+        let mut state = toml::Table::new();
+        state.insert(
+            "current".to_string(),
+            toml::Value::Integer(self.current as i64),
+        );
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &toml::Table) {
+        // Wraps around the visible targets, so any value is fine
+        if let Some(current) = state.get("current").and_then(|v| v.as_integer()) {
+            self.current = current.max(0) as usize;
+        }
+    }
+
+    fn inhibits_idle(&self) -> bool {
+        if !self.inhibit_idle {
+            return false;
+        }
+        let now = Local::now();
+        let window = chrono::Duration::minutes(INHIBIT_IDLE_MINUTES);
+        self.targets.iter().any(|target| {
+            let remaining = target.target - now;
+            remaining > chrono::Duration::zero() && remaining <= window
+        })
+    }
+
+    fn reminders(&self) -> Vec<Reminder> {
+        if !self.notify {
+            return Vec::new();
+        }
+        self.targets
+            .iter()
+            .filter(|target| !target.reached)
+            .map(|target| Reminder {
+                id: format!("countdown:{}@{}", target.label, target.target.to_rfc3339()),
+                at: target.target,
+                summary: target.label.clone(),
+                body: "Countdown finished".to_string(),
+                sound: false,
+            })
+            .collect()
     }
 
     fn content(&self) -> WidgetContent {
-        WidgetContent::StyledText {
-            segments: self.styled_segments(),
-            size: FontSize::Medium,
+        let shown = self.shown_targets();
+        match shown.len() {
+            0 => WidgetContent::Empty,
+            1 => WidgetContent::StyledText {
+                segments: self.target_segments(shown[0]),
+                size: FontSize::Medium,
+            },
+            _ => WidgetContent::MultiLine {
+                lines: shown
+                    .into_iter()
+                    .map(|target| (self.target_string(target), FontSize::Medium))
+                    .collect(),
+            },
         }
     }
 
@@ -238,6 +625,14 @@ impl DynWidgetFactory for CountdownWidgetFactory {
         "countdown"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Countdown",
+            "Time left until dates and events",
+            "alarm-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> anyhow::Result<Box<dyn Widget>> {
         let label = config
             .get("label")
@@ -269,21 +664,82 @@ impl DynWidgetFactory for CountdownWidgetFactory {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        let on_complete = config
+            .get("on_complete")
+            .and_then(|v| v.as_str())
+            .and_then(Completion::from_name)
+            .unwrap_or_default();
+
+        let layout = config
+            .get("display")
+            .and_then(|v| v.as_str())
+            .and_then(CountdownLayout::from_name)
+            .unwrap_or_default();
+
+        let rotation_interval = config
+            .get("rotation_interval")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(5)
+            .max(1) as u64;
+
+        let sound = config
+            .get("sound")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let notify = config
+            .get("notify")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let inhibit_idle = config
+            .get("inhibit_idle")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        // A list of targets, or the single `label`/`target_date` pair
+        let targets = match config.get("targets").and_then(|v| v.as_array()) {
+            Some(entries) => entries
+                .iter()
+                .map(|entry| {
+                    let entry = entry
+                        .as_table()
+                        .context("'targets' entries must be tables")?;
+                    let label = entry
+                        .get("label")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("Countdown");
+                    let date = entry
+                        .get("date")
+                        .and_then(|v| v.as_str())
+                        .context("Each countdown target needs a 'date'")?;
+                    Ok(CountdownTarget::from_date_string(label, date)?
+                        .with_repeat(parse_repeat(entry)))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            None => vec![CountdownTarget::from_date_string(label, target_date)?
+                .with_repeat(parse_repeat(config))],
+        };
+
         debug!(
-            label = %label,
-            target_date = %target_date,
+            targets = targets.len(),
+            layout = ?layout,
+            on_complete = ?on_complete,
             "Creating CountdownWidget"
         );
 
-        CountdownWidget::from_date_string(
-            label,
-            target_date,
+        let widget = CountdownWidget::with_targets(
+            targets,
             show_days,
             show_hours,
             show_minutes,
             show_seconds,
         )
-        .map(|w| Box::new(w) as Box<dyn Widget>)
+        .with_completion(on_complete)
+        .with_layout(layout, Duration::from_secs(rotation_interval))
+        .with_alerts(sound, notify)
+        .with_inhibit_idle(inhibit_idle);
+        Ok(Box::new(widget))
     }
 
     fn default_config(&self) -> toml::Table {
@@ -310,8 +766,145 @@ impl DynWidgetFactory for CountdownWidgetFactory {
             // Validate date format
             CountdownWidget::parse_datetime(target_str)?;
         }
+        validate_repeat(config)?;
+
+        if let Some(targets) = config.get("targets") {
+            let targets = targets
+                .as_array()
+                .context("'targets' must be an array of tables")?;
+            for entry in targets {
+                let entry = entry
+                    .as_table()
+                    .context("'targets' entries must be tables")?;
+                let date = entry
+                    .get("date")
+                    .context("Each countdown target needs a 'date'")?
+                    .as_str()
+                    .context("Countdown target 'date' must be a string")?;
+                CountdownWidget::parse_datetime(date)?;
+                validate_repeat(entry)?;
+            }
+        }
+
+        if let Some(on_complete) = config.get("on_complete") {
+            let on_complete = on_complete
+                .as_str()
+                .context("'on_complete' must be a string")?;
+            if Completion::from_name(on_complete).is_none() {
+                bail!(
+                    "'on_complete' must be one of {}, got '{}'",
+                    Completion::NAMES.join(", "),
+                    on_complete
+                );
+            }
+        }
+
+        if let Some(display) = config.get("display") {
+            let display = display.as_str().context("'display' must be a string")?;
+            if CountdownLayout::from_name(display).is_none() {
+                bail!(
+                    "'display' must be one of {}, got '{}'",
+                    CountdownLayout::NAMES.join(", "),
+                    display
+                );
+            }
+        }
+
+        if let Some(interval) = config.get("rotation_interval") {
+            let interval = interval
+                .as_integer()
+                .context("'rotation_interval' must be an integer")?;
+            if interval < 1 {
+                bail!("'rotation_interval' must be at least 1 second");
+            }
+        }
         Ok(())
     }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new("label", "Label", FieldKind::Text))
+            .with_field(
+                ConfigField::new("target_date", "Target date", FieldKind::Text)
+                    .with_description("YYYY-MM-DD or YYYY-MM-DD HH:MM:SS"),
+            )
+            .with_field(
+                ConfigField::new("repeat", "Repeat", FieldKind::Choice(Repeat::NAMES))
+                    .with_description("Count down to the next occurrence once reached"),
+            )
+            .with_field(
+                ConfigField::new(
+                    "display",
+                    "Display",
+                    FieldKind::Choice(CountdownLayout::NAMES),
+                )
+                .with_description("How several targets share the widget"),
+            )
+            .with_field(ConfigField::new(
+                "rotation_interval",
+                "Rotation interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(
+                ConfigField::new(
+                    "on_complete",
+                    "When complete",
+                    FieldKind::Choice(Completion::NAMES),
+                )
+                .with_description("Show Passed!, show Done, or hide the countdown"),
+            )
+            .with_field(ConfigField::new("sound", "Alarm sound", FieldKind::Bool))
+            .with_field(
+                ConfigField::new("notify", "Notification", FieldKind::Bool)
+                    .with_description("Show a desktop notification when a target is reached"),
+            )
+            .with_field(
+                ConfigField::new("inhibit_idle", "Keep screen awake", FieldKind::Bool)
+                    .with_description("During the last 5 minutes before a target"),
+            )
+            .with_field(ConfigField::new("show_days", "Show days", FieldKind::Bool))
+            .with_field(ConfigField::new(
+                "show_hours",
+                "Show hours",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "show_minutes",
+                "Show minutes",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "show_seconds",
+                "Show seconds",
+                FieldKind::Bool,
+            ))
+    }
+}
+
+/// The `repeat` option of a target, defaulting to never
+fn parse_repeat(config: &toml::Table) -> Repeat {
+    config
+        .get("repeat")
+        .and_then(|v| v.as_str())
+        .and_then(Repeat::from_name)
+        .unwrap_or_default()
+}
+
+fn validate_repeat(config: &toml::Table) -> anyhow::Result<()> {
+    if let Some(repeat) = config.get("repeat") {
+        let repeat = repeat.as_str().context("'repeat' must be a string")?;
+        if Repeat::from_name(repeat).is_none() {
+            bail!(
+                "'repeat' must be one of {}, got '{}'",
+                Repeat::NAMES.join(", "),
+                repeat
+            );
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -325,6 +918,28 @@ mod tests {
         assert_eq!(widget.info().id, "countdown");
     }
 
+    #[test]
+    fn test_countdown_sound_cue() {
+        let target = Local::now() + chrono::Duration::milliseconds(50);
+        let mut widget = CountdownWidget::new("Soon", target, true, true, true, true);
+        widget.update();
+        assert_eq!(widget.take_sound_cue(), None);
+
+        std::thread::sleep(Duration::from_millis(80));
+        widget.update();
+        assert_eq!(widget.take_sound_cue(), Some(SoundCue::Alarm));
+
+        // Only once per target
+        widget.update();
+        assert_eq!(widget.take_sound_cue(), None);
+
+        // Targets already past at startup stay silent
+        let past = Local::now() - chrono::Duration::days(1);
+        let mut widget = CountdownWidget::new("Past", past, true, true, true, true);
+        widget.update();
+        assert_eq!(widget.take_sound_cue(), None);
+    }
+
     #[test]
     fn test_countdown_display() {
         let target = Local::now() + chrono::Duration::days(1) + chrono::Duration::hours(2);
@@ -354,6 +969,150 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_repeat_next_after() {
+        let at = |y, m, d, h| Local.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap();
+
+        // Weekly standup, long after the first one
+        let standup = at(2026, 1, 5, 9);
+        assert_eq!(
+            Repeat::Weekly.next_after(standup, at(2026, 3, 4, 12)),
+            at(2026, 3, 9, 9)
+        );
+        assert_eq!(
+            Repeat::Weekly.next_after(standup, at(2026, 3, 9, 8)),
+            at(2026, 3, 9, 9)
+        );
+
+        // Monthly on the 31st falls back in short months without drifting
+        let rent = at(2026, 1, 31, 0);
+        assert_eq!(
+            Repeat::Monthly.next_after(rent, at(2026, 2, 10, 0)),
+            at(2026, 2, 28, 0)
+        );
+        assert_eq!(
+            Repeat::Monthly.next_after(rent, at(2026, 3, 1, 0)),
+            at(2026, 3, 31, 0)
+        );
+
+        // Yearly anniversary, and one-offs stay put
+        let anniversary = at(2019, 6, 15, 0);
+        assert_eq!(
+            Repeat::Yearly.next_after(anniversary, at(2026, 7, 1, 0)),
+            at(2027, 6, 15, 0)
+        );
+        assert_eq!(
+            Repeat::Never.next_after(anniversary, at(2026, 7, 1, 0)),
+            anniversary
+        );
+    }
+
+    #[test]
+    fn test_multiple_targets() {
+        let soon = Local::now() + chrono::Duration::hours(2);
+        let past = Local::now() - chrono::Duration::days(1);
+        let targets = vec![
+            CountdownTarget::new("Launch", soon),
+            CountdownTarget::new("Old", past),
+        ];
+
+        let widget = CountdownWidget::with_targets(targets.clone(), true, true, true, false);
+        let display = widget.display_string();
+        assert_eq!(display.lines().count(), 2);
+        assert!(display.contains("Old: Passed!"));
+        assert!(matches!(widget.content(), WidgetContent::MultiLine { .. }));
+
+        let widget = CountdownWidget::with_targets(targets.clone(), true, true, true, false)
+            .with_completion(Completion::Hide);
+        assert!(!widget.display_string().contains("Old"));
+        assert!(matches!(widget.content(), WidgetContent::StyledText { .. }));
+
+        let widget = CountdownWidget::with_targets(targets, true, true, true, false)
+            .with_completion(Completion::Done)
+            .with_layout(CountdownLayout::Rotate, Duration::from_secs(5));
+        assert_eq!(widget.display_string().lines().count(), 1);
+        assert_eq!(widget.info().preferred_height, 40.0);
+    }
+
+    #[test]
+    fn test_recurring_target_rolls_over() {
+        let now = Local::now();
+        let mut target = CountdownTarget::new("Daily", now - chrono::Duration::hours(1))
+            .with_repeat(Repeat::Daily);
+        // Already moved on to the next occurrence, and not marked reached
+        assert!(target.target() > now);
+        assert!(!target.reached);
+
+        let next = target.target();
+        assert!(target.reach(next));
+        assert_eq!(
+            target.target().naive_local(),
+            next.naive_local() + chrono::Duration::days(1)
+        );
+        assert!(!target.reach(next));
+    }
+
+    #[test]
+    fn test_countdown_inhibit_idle() {
+        let soon = Local::now() + chrono::Duration::minutes(3);
+        let widget = CountdownWidget::new("Launch", soon, true, true, true, true);
+        assert!(!widget.inhibits_idle());
+        assert!(widget.with_inhibit_idle(true).inhibits_idle());
+
+        let later = Local::now() + chrono::Duration::hours(2);
+        let widget =
+            CountdownWidget::new("Launch", later, true, true, true, true).with_inhibit_idle(true);
+        assert!(!widget.inhibits_idle());
+
+        let past = Local::now() - chrono::Duration::minutes(1);
+        let widget =
+            CountdownWidget::new("Launch", past, true, true, true, true).with_inhibit_idle(true);
+        assert!(!widget.inhibits_idle());
+    }
+
+    #[test]
+    fn test_countdown_notification() {
+        let target = Local::now() + chrono::Duration::hours(1);
+        let widget = CountdownWidget::new("Launch", target, true, true, true, false);
+        assert!(widget.reminders().is_empty());
+
+        let widget = widget.with_alerts(false, true);
+        let reminders = widget.reminders();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].at, target);
+        assert_eq!(reminders[0].summary, "Launch");
+    }
+
+    #[test]
+    fn test_factory_targets() {
+        let factory = CountdownWidgetFactory;
+        let config: toml::Table = toml::from_str(
+            r#"
+            display = "rotate"
+            on_complete = "hide"
+
+            [[targets]]
+            label = "Standup"
+            date = "2026-01-05 09:30:00"
+            repeat = "weekly"
+
+            [[targets]]
+            label = "Anniversary"
+            date = "2019-06-15"
+            repeat = "yearly"
+            "#,
+        )
+        .unwrap();
+        assert!(factory.validate_config(&config).is_ok());
+        assert!(factory.create(&config).is_ok());
+
+        let invalid: toml::Table = toml::from_str("[[targets]]\nlabel = \"No date\"").unwrap();
+        assert!(factory.validate_config(&invalid).is_err());
+
+        let invalid: toml::Table = toml::from_str("repeat = \"hourly\"").unwrap();
+        assert!(factory.validate_config(&invalid).is_err());
+    }
+
     #[test]
     fn test_factory_creation() {
         let factory = CountdownWidgetFactory;
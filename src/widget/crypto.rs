@@ -2,6 +2,8 @@
 //!
 //! This widget shows cryptocurrency prices from CoinGecko API (free, no API key required).
 //! Supports multiple cryptocurrencies with configurable update intervals.
+//! With holdings configured, it also shows what the portfolio is worth.
+//! Price alerts notify when a coin crosses a threshold.
 
 use std::time::{Duration, Instant};
 
@@ -9,8 +11,13 @@ use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
+use super::price_alert::{self, PriceAlerts};
 use super::registry::DynWidgetFactory;
-use super::traits::{FontSize, Widget, WidgetContent, WidgetInfo};
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{FontSize, MouseButton, Widget, WidgetAction, WidgetContent, WidgetInfo};
+use crate::template::{Template, TemplateVars};
+use crate::update::tasks::{self, TaskOutput};
+use crate::update::{Reminder, Source};
 
 /// CoinGecko API response structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,6 +72,63 @@ impl CryptoPrice {
             format!("{}: {}", self.symbol, price_str)
         }
     }
+
+    /// Format price through a user template
+    pub fn render(&self, template: &Template, currency: &str) -> String {
+        let mut vars = TemplateVars::new()
+            .with("symbol", self.symbol.as_str())
+            .with("price", self.price)
+            .with("currency", currency.to_uppercase());
+        match self.change_24h {
+            Some(change) => vars.set("change", change),
+            None => vars.set("change", ""),
+        }
+        template.render(&vars)
+    }
+}
+
+/// An amount of one coin held
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holding {
+    /// CoinGecko coin ID, e.g. "bitcoin"
+    pub coin: String,
+    /// Number of coins held
+    pub amount: f64,
+}
+
+impl Holding {
+    /// Read a `{ coin, amount }` table from the `holdings` option
+    fn from_config(value: &toml::Value) -> anyhow::Result<Self> {
+        let table = value
+            .as_table()
+            .context("each of 'holdings' must be a table with 'coin' and 'amount'")?;
+        let coin = table
+            .get("coin")
+            .and_then(|v| v.as_str())
+            .context("each of 'holdings' needs a 'coin' string")?;
+        let amount = table
+            .get("amount")
+            .and_then(|v| v.as_float().or_else(|| v.as_integer().map(|i| i as f64)))
+            .context("each of 'holdings' needs a numeric 'amount'")?;
+        if amount < 0.0 {
+            anyhow::bail!("'amount' of '{}' must not be negative", coin);
+        }
+        Ok(Self {
+            coin: coin.to_string(),
+            amount,
+        })
+    }
+}
+
+/// Total worth of the holdings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PortfolioValue {
+    /// Current value
+    pub total: f64,
+    /// Change in value over 24 hours
+    pub change: f64,
+    /// Change in percent over 24 hours
+    pub change_percent: f64,
 }
 
 /// Crypto widget showing cryptocurrency prices
@@ -76,9 +140,18 @@ pub struct CryptoWidget {
     last_update: Instant,
     update_interval: Duration,
     error_message: Option<String>,
+    template: Option<Template>,
+    holdings: Vec<Holding>,
+    /// Mask the portfolio's value, leaving only percentages
+    hide_values: bool,
+    alerts: PriceAlerts,
 }
 
 impl CryptoWidget {
+    /// Placeholders available to a crypto `format` template (applied per coin)
+    pub const TEMPLATE_PLACEHOLDERS: &'static [&'static str] =
+        &["symbol", "price", "change", "currency"];
+
     /// Create a new Crypto widget
     pub fn new(
         coins: Vec<String>,
@@ -94,9 +167,104 @@ impl CryptoWidget {
             last_update: Instant::now(),
             update_interval: Duration::from_secs(update_interval),
             error_message: None,
+            template: None,
+            holdings: Vec::new(),
+            hide_values: false,
+            alerts: PriceAlerts::new(Vec::new()),
         }
     }
 
+    /// Watch prices for `alerts`
+    pub fn with_alerts(mut self, alerts: PriceAlerts) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// Show the total value of `holdings`, masked while `hide_values` is set
+    ///
+    /// Coins held are fetched along with the configured coins.
+    pub fn with_holdings(mut self, holdings: Vec<Holding>, hide_values: bool) -> Self {
+        for holding in &holdings {
+            if !self.coins.contains(&holding.coin) {
+                self.coins.push(holding.coin.clone());
+            }
+        }
+        self.holdings = holdings;
+        self.hide_values = hide_values;
+        self
+    }
+
+    /// Value of the holdings at the current prices
+    ///
+    /// None until prices arrive, or when a coin held has no price.
+    pub fn portfolio_value(&self) -> Option<PortfolioValue> {
+        if self.holdings.is_empty() {
+            return None;
+        }
+        let prices = self.data.as_ref()?;
+
+        let mut total = 0.0;
+        let mut previous = 0.0;
+        for holding in &self.holdings {
+            let symbol = holding.coin.to_uppercase();
+            let price = prices.iter().find(|p| p.symbol == symbol)?;
+            let value = holding.amount * price.price;
+            total += value;
+            // The price 24 hours ago, from the relative change since
+            previous += match price.change_24h {
+                Some(change) => value / (1.0 + change / 100.0),
+                None => value,
+            };
+        }
+
+        let change = total - previous;
+        let change_percent = if previous > 0.0 {
+            change / previous * 100.0
+        } else {
+            0.0
+        };
+        Some(PortfolioValue {
+            total,
+            change,
+            change_percent,
+        })
+    }
+
+    /// Portfolio line, e.g. "Portfolio: $1234 (+$56, +4.76%)"
+    fn portfolio_string(&self) -> Option<String> {
+        let value = self.portfolio_value()?;
+        let symbol = match self.currency.as_str() {
+            "eur" => "€",
+            _ => "$",
+        };
+        let sign = if value.change >= 0.0 { "+" } else { "-" };
+        Some(if self.hide_values {
+            format!(
+                "Portfolio: {}••••• ({}{:.2}%)",
+                symbol,
+                sign,
+                value.change_percent.abs()
+            )
+        } else {
+            format!(
+                "Portfolio: {}{:.2} ({}{}{:.2}, {}{:.2}%)",
+                symbol,
+                value.total,
+                sign,
+                symbol,
+                value.change.abs(),
+                sign,
+                value.change_percent.abs()
+            )
+        })
+    }
+
+    /// Format each coin through a custom template
+    pub fn with_template(mut self, template: Template) -> Self {
+        self.template = Some(template);
+        self
+    }
+
     /// Set crypto data from successful API fetch
     pub fn set_data(&mut self, data: Vec<CryptoPrice>) {
         debug!(
@@ -104,6 +272,10 @@ impl CryptoWidget {
             currency = %self.currency,
             "Crypto data updated"
         );
+        for price in &data {
+            self.alerts
+                .check(&price.symbol, price.price, price.change_24h);
+        }
         self.data = Some(data);
         self.last_update = Instant::now();
         self.error_message = None;
@@ -119,23 +291,17 @@ impl CryptoWidget {
     pub fn display_string(&self) -> Option<String> {
         // If there's an error and no data, show error
         if self.data.is_none() && self.error_message.is_some() {
-            return self.error_message.as_ref().map(|e| format!("Error: {}", e));
+            return self
+                .error_message
+                .as_ref()
+                .map(|e| crate::fl!("error", message = e.as_str()));
         }
 
         self.data.as_ref().map(|prices| {
-            // Check if data is stale (older than 2x update interval)
-            let stale_threshold = self.update_interval * 2;
-            let is_stale = self.last_update.elapsed() > stale_threshold;
-
-            let mut lines: Vec<String> = prices
-                .iter()
-                .map(|price| price.display(self.show_change))
-                .collect();
-
-            // Add indicators
-            if is_stale {
-                lines.push("(stale)".to_string());
-            }
+            let mut lines: Vec<String> =
+                prices.iter().map(|price| self.price_text(price)).collect();
+
+            // Old prices after a failed update
             if self.error_message.is_some() {
                 lines.push("⚠".to_string());
             }
@@ -144,26 +310,55 @@ impl CryptoWidget {
         })
     }
 
-    /// Fetch cryptocurrency prices from CoinGecko API
-    pub async fn fetch_prices(&mut self) -> anyhow::Result<()> {
-        if self.coins.is_empty() {
+    fn price_text(&self, price: &CryptoPrice) -> String {
+        match &self.template {
+            Some(template) => price.render(template, &self.currency),
+            None => price.display(self.show_change),
+        }
+    }
+
+    /// Prices with the coins whose alert fired in the highlight color
+    fn highlighted_prices(&self) -> Option<WidgetContent> {
+        if !self.alerts.any_highlighted() {
+            return None;
+        }
+        let parts = self
+            .data
+            .as_ref()?
+            .iter()
+            .map(|price| {
+                (
+                    self.price_text(price),
+                    self.alerts.highlight_color(&price.symbol),
+                )
+            })
+            .collect();
+        Some(WidgetContent::StyledText {
+            segments: price_alert::highlighted_line(parts),
+            size: FontSize::Medium,
+        })
+    }
+
+    /// Request current prices for `coins` in `currency` from CoinGecko
+    async fn request_prices(coins: &[String], currency: &str) -> anyhow::Result<Vec<CryptoPrice>> {
+        if coins.is_empty() {
             return Err(anyhow::anyhow!("No coins configured"));
         }
 
         info!(
-            coins = ?self.coins,
-            currency = %self.currency,
+            coins = ?coins,
+            currency = %currency,
             "Fetching crypto prices from CoinGecko API"
         );
 
-        let coins_param = self.coins.join(",");
+        let coins_param = coins.join(",");
         let url = format!(
             "https://api.coingecko.com/api/v3/simple/price?ids={}&vs_currencies={}&include_24hr_change=true",
-            coins_param, self.currency
+            coins_param, currency
         );
 
-        let response = reqwest::get(&url).await.map_err(|e| {
-            warn!(error = %e, coins = ?self.coins, "Failed to fetch crypto prices from API");
+        let response = crate::http::client().get(&url).send().await.map_err(|e| {
+            warn!(error = %e, coins = ?coins, "Failed to fetch crypto prices from API");
             e
         })?;
 
@@ -180,9 +375,9 @@ impl CryptoWidget {
 
         let mut prices = Vec::new();
 
-        for coin_id in &self.coins {
+        for coin_id in coins {
             if let Some(coin_data) = response_data.coins.get(coin_id) {
-                let (price, change) = match self.currency.as_str() {
+                let (price, change) = match currency {
                     "eur" => (coin_data.eur, coin_data.eur_24h_change),
                     _ => (coin_data.usd, coin_data.usd_24h_change),
                 };
@@ -205,12 +400,9 @@ impl CryptoWidget {
             return Err(anyhow::anyhow!("No valid price data received"));
         }
 
-        self.data = Some(prices.clone());
-        self.last_update = Instant::now();
-
         info!(count = prices.len(), "Crypto API fetch successful");
 
-        Ok(())
+        Ok(prices)
     }
 
     /// Get the list of configured coins
@@ -229,24 +421,49 @@ impl Widget for CryptoWidget {
         WidgetInfo {
             id: "crypto",
             name: "Crypto",
-            preferred_height: 40.0,
+            preferred_height: if self.holdings.is_empty() { 40.0 } else { 60.0 },
             min_height: 30.0,
             expand: false,
         }
     }
 
     fn update(&mut self) {
-        // Update is handled by background thread
-        // This method is a no-op for async widgets
+        // Prices arrive through apply_fetch from the data service
     }
 
     fn content(&self) -> WidgetContent {
-        match self.display_string() {
-            Some(text) => WidgetContent::Text {
+        let prices = self.display_string();
+        match (self.portfolio_string(), prices) {
+            (Some(portfolio), Some(prices)) => WidgetContent::MultiLine {
+                lines: vec![(portfolio, FontSize::Medium), (prices, FontSize::Small)],
+            },
+            (_, Some(_)) if self.data.is_none() => {
+                WidgetContent::error(self.error_message.as_deref().unwrap_or_default())
+            }
+            (_, Some(text)) => self.highlighted_prices().unwrap_or(WidgetContent::Text {
                 text,
                 size: FontSize::Medium,
-            },
-            None => WidgetContent::Empty,
+            }),
+            (_, None) => WidgetContent::Empty,
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        !self.holdings.is_empty()
+    }
+
+    fn reminders(&self) -> Vec<Reminder> {
+        self.alerts.reminders()
+    }
+
+    fn on_click(&mut self, button: MouseButton, _x: f32, _y: f32) -> Option<WidgetAction> {
+        match button {
+            // Show or mask the portfolio's value
+            MouseButton::Left if !self.holdings.is_empty() => {
+                self.hide_values = !self.hide_values;
+                Some(WidgetAction::Toggle)
+            }
+            _ => None,
         }
     }
 
@@ -261,6 +478,27 @@ impl Widget for CryptoWidget {
     fn error(&self) -> Option<&str> {
         self.error_message.as_deref()
     }
+
+    fn last_success(&self) -> Option<Instant> {
+        self.data.as_ref().map(|_| self.last_update)
+    }
+
+    fn data_source(&self) -> Option<Source> {
+        let coins = self.coins.clone();
+        let currency = self.currency.clone();
+        Some(Source::new(self.update_interval, move || {
+            let coins = coins.clone();
+            let currency = currency.clone();
+            tasks::task(async move { Self::request_prices(&coins, &currency).await })
+        }))
+    }
+
+    fn apply_fetch(&mut self, result: anyhow::Result<TaskOutput>) {
+        match tasks::take_output::<Vec<CryptoPrice>>(result) {
+            Ok(prices) => self.set_data(prices),
+            Err(e) => self.set_error(e.to_string()),
+        }
+    }
 }
 
 impl Default for CryptoWidget {
@@ -286,7 +524,17 @@ impl DynWidgetFactory for CryptoWidgetFactory {
         "crypto"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Crypto",
+            "Cryptocurrency prices from CoinGecko, with price alerts",
+            "emblem-money-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> anyhow::Result<Box<dyn Widget>> {
+        let holdings = parse_holdings(config)?;
+
         // Parse coins array
         let coins = if let Some(coins_value) = config.get("coins") {
             if let Some(coins_array) = coins_value.as_array() {
@@ -297,11 +545,14 @@ impl DynWidgetFactory for CryptoWidgetFactory {
             } else {
                 vec!["bitcoin".to_string(), "ethereum".to_string()]
             }
+        } else if !holdings.is_empty() {
+            // Prices of the coins held
+            Vec::new()
         } else {
             vec!["bitcoin".to_string(), "ethereum".to_string()]
         };
 
-        if coins.is_empty() {
+        if coins.is_empty() && holdings.is_empty() {
             anyhow::bail!("At least one cryptocurrency must be configured");
         }
 
@@ -320,20 +571,32 @@ impl DynWidgetFactory for CryptoWidgetFactory {
             .and_then(|v| v.as_integer())
             .unwrap_or(120) as u64;
 
+        let hide_values = config
+            .get("hide_values")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let template =
+            Template::from_config(config, "format", CryptoWidget::TEMPLATE_PLACEHOLDERS)?;
+        let alerts = PriceAlerts::from_config(config)?;
+
         debug!(
             coins = ?coins,
             currency = %currency,
             show_change = %show_change,
             update_interval = %update_interval,
+            holdings = holdings.len(),
             "Creating CryptoWidget"
         );
 
-        Ok(Box::new(CryptoWidget::new(
-            coins,
-            currency,
-            show_change,
-            update_interval,
-        )))
+        let mut widget = CryptoWidget::new(coins, currency, show_change, update_interval)
+            .with_holdings(holdings, hide_values)
+            .with_alerts(alerts);
+        if let Some(template) = template {
+            widget = widget.with_template(template);
+        }
+
+        Ok(Box::new(widget))
     }
 
     fn default_config(&self) -> toml::Table {
@@ -394,8 +657,73 @@ impl DynWidgetFactory for CryptoWidgetFactory {
             }
         }
 
+        if let Some(hide_values) = config.get("hide_values") {
+            hide_values
+                .as_bool()
+                .context("'hide_values' must be a boolean")?;
+        }
+
+        parse_holdings(config)?;
+        PriceAlerts::from_config(config)?;
+        Template::from_config(config, "format", CryptoWidget::TEMPLATE_PLACEHOLDERS)?;
+
         Ok(())
     }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_network()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        let schema = ConfigSchema::new()
+            .with_field(
+                ConfigField::new("coins", "Coins", FieldKind::List)
+                    .with_description("CoinGecko coin IDs, e.g. bitcoin"),
+            )
+            .with_field(ConfigField::new(
+                "currency",
+                "Currency",
+                FieldKind::Choice(&["usd", "eur"]),
+            ))
+            .with_field(ConfigField::new(
+                "show_change",
+                "Show 24h change",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "update_interval",
+                "Update interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(ConfigField::new(
+                "format",
+                "Format",
+                FieldKind::Template(CryptoWidget::TEMPLATE_PLACEHOLDERS),
+            ))
+            .with_field(
+                ConfigField::new("hide_values", "Hide portfolio value", FieldKind::Bool)
+                    .with_description(
+                        "Show only the portfolio's change in percent; click to toggle",
+                    ),
+            );
+        PriceAlerts::extend_schema(schema)
+    }
+}
+
+/// Read the optional `holdings` array of `{ coin, amount }` tables
+fn parse_holdings(config: &toml::Table) -> anyhow::Result<Vec<Holding>> {
+    let Some(value) = config.get("holdings") else {
+        return Ok(Vec::new());
+    };
+    value
+        .as_array()
+        .context("'holdings' must be an array of tables")?
+        .iter()
+        .map(Holding::from_config)
+        .collect()
 }
 
 #[cfg(test)]
@@ -474,6 +802,17 @@ mod tests {
         assert!(display.contains("$0.0725"));
     }
 
+    #[test]
+    fn test_crypto_price_render_template() {
+        let price = CryptoPrice {
+            symbol: "BTC".to_string(),
+            price: 50123.456,
+            change_24h: Some(1.234),
+        };
+        let template = Template::parse("{symbol} {price:.0} {currency} ({change:+.1}%)").unwrap();
+        assert_eq!(price.render(&template, "usd"), "BTC 50123 USD (+1.2%)");
+    }
+
     #[test]
     fn test_crypto_widget_set_data() {
         let mut widget = CryptoWidget::default();
@@ -503,6 +842,110 @@ mod tests {
         assert!(display.unwrap().contains("Error"));
     }
 
+    fn holding(coin: &str, amount: f64) -> Holding {
+        Holding {
+            coin: coin.to_string(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_portfolio_value() {
+        let mut widget = CryptoWidget::new(vec!["bitcoin".to_string()], "usd", true, 120)
+            .with_holdings(
+                vec![holding("bitcoin", 0.5), holding("ethereum", 2.0)],
+                false,
+            );
+        assert_eq!(widget.coins(), &["bitcoin", "ethereum"]);
+        assert!(widget.portfolio_value().is_none());
+
+        widget.set_data(vec![
+            CryptoPrice {
+                symbol: "BITCOIN".to_string(),
+                price: 50000.0,
+                change_24h: Some(25.0),
+            },
+            CryptoPrice {
+                symbol: "ETHEREUM".to_string(),
+                price: 2500.0,
+                change_24h: None,
+            },
+        ]);
+        let value = widget.portfolio_value().unwrap();
+        // 25000 + 5000, up from 20000 + 5000
+        assert_eq!(value.total, 30000.0);
+        assert_eq!(value.change, 5000.0);
+        assert_eq!(value.change_percent, 20.0);
+        assert_eq!(
+            widget.portfolio_string().unwrap(),
+            "Portfolio: $30000.00 (+$5000.00, +20.00%)"
+        );
+
+        // Clicking masks the amounts
+        widget.on_click(MouseButton::Left, 0.5, 0.5);
+        let masked = widget.portfolio_string().unwrap();
+        assert_eq!(masked, "Portfolio: $••••• (+20.00%)");
+        assert!(matches!(widget.content(), WidgetContent::MultiLine { .. }));
+    }
+
+    #[test]
+    fn test_price_alert_highlight() {
+        let alerts = PriceAlerts::new(vec![price_alert::PriceAlert::new(
+            "ethereum",
+            price_alert::AlertCondition::Below(3000.0),
+        )]);
+        let mut widget = CryptoWidget::default().with_alerts(alerts);
+        widget.set_data(vec![
+            CryptoPrice {
+                symbol: "BITCOIN".to_string(),
+                price: 50000.0,
+                change_24h: None,
+            },
+            CryptoPrice {
+                symbol: "ETHEREUM".to_string(),
+                price: 2900.0,
+                change_24h: None,
+            },
+        ]);
+
+        let reminders = widget.reminders();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].summary, "ETHEREUM below 3000");
+        match widget.content() {
+            WidgetContent::StyledText { segments, .. } => {
+                assert_eq!(segments.len(), 3);
+                assert!(segments[0].color.is_none());
+                assert!(segments[2].color.is_some());
+            }
+            other => panic!("Expected highlighted prices, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_factory_holdings() {
+        let factory = CryptoWidgetFactory;
+        let config: toml::Table = toml::from_str(
+            r#"
+            hide_values = true
+            holdings = [{ coin = "bitcoin", amount = 0.25 }, { coin = "solana", amount = 10 }]
+            "#,
+        )
+        .unwrap();
+        assert!(factory.validate_config(&config).is_ok());
+        assert_eq!(
+            parse_holdings(&config).unwrap(),
+            vec![holding("bitcoin", 0.25), holding("solana", 10.0)]
+        );
+        assert!(factory.create(&config).is_ok());
+
+        let config: toml::Table =
+            toml::from_str(r#"holdings = [{ coin = "bitcoin", amount = -1 }]"#).unwrap();
+        assert!(factory.validate_config(&config).is_err());
+
+        let config: toml::Table = toml::from_str(r#"holdings = [{ amount = 1 }]"#).unwrap();
+        assert!(factory.validate_config(&config).is_err());
+    }
+
     #[test]
     fn test_factory_creation() {
         let factory = CryptoWidgetFactory;
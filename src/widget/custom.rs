@@ -0,0 +1,817 @@
+//! Custom (declarative) widget driven entirely by configuration
+//!
+//! A custom widget has no Rust code of its own. Its configuration names a
+//! data source, a polling interval and a display template:
+//!
+//! ```toml
+//! [[widgets]]
+//! type = "custom"
+//! [widgets.config]
+//! source = "command"
+//! command = "uptime -p"
+//! interval = 60
+//! template = "Uptime: {value}"
+//! ```
+//!
+//! Instead of `interval`, a `schedule` runs the source at fixed times, e.g.
+//! `schedule = "0 30 2 * * *"` for a nightly backup status check. An `icon`,
+//! from the icon theme or the user icon directory, goes beside the text.
+//!
+//! Supported sources are shell commands, files, HTTP endpoints (optionally
+//! narrowed with a JSON pointer) and D-Bus properties. Sources are polled by
+//! the data service on its blocking pool so slow commands or requests never
+//! block rendering, and a failing source is polled less and less often until
+//! it recovers.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use tracing::{debug, warn};
+
+use super::registry::DynWidgetFactory;
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{FontSize, Widget, WidgetContent, WidgetInfo};
+use crate::http::TlsOptions;
+use crate::template::{Template, TemplateVars};
+use crate::update::tasks::{self, TaskOutput};
+use crate::update::{Schedule, Source};
+
+/// Timeout applied to HTTP sources
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Which D-Bus bus a property lives on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DBusBus {
+    /// The per-user session bus
+    Session,
+    /// The system-wide bus
+    System,
+}
+
+/// Where a custom widget gets its value from
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataSource {
+    /// Run a shell command and use its standard output
+    Command {
+        /// Command line passed to `sh -c`
+        command: String,
+    },
+    /// Read the contents of a file
+    File {
+        /// Path to the file
+        path: PathBuf,
+    },
+    /// Fetch a URL with an HTTP GET request
+    Http {
+        /// URL to fetch
+        url: String,
+        /// Optional JSON pointer (e.g. `/main/temp`) into the response body
+        json_pointer: Option<String>,
+        /// Extra CA or disabled certificate checks for self-hosted servers
+        tls: TlsOptions,
+    },
+    /// Read a property from a D-Bus object
+    DBus {
+        /// Bus to connect to
+        bus: DBusBus,
+        /// Well-known service name (e.g. `org.freedesktop.UPower`)
+        service: String,
+        /// Object path
+        path: String,
+        /// Interface that owns the property
+        interface: String,
+        /// Property name
+        property: String,
+    },
+}
+
+impl DataSource {
+    /// Parse a data source from widget configuration
+    pub fn from_config(config: &toml::Table) -> Result<Self> {
+        let get_str = |key: &str| -> Result<String> {
+            config
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .with_context(|| format!("'{}' is required for this source", key))
+        };
+
+        let source = config
+            .get("source")
+            .and_then(|v| v.as_str())
+            .unwrap_or("command");
+
+        match source {
+            "command" => Ok(Self::Command {
+                command: get_str("command")?,
+            }),
+            "file" => Ok(Self::File {
+                path: expand_home(&get_str("path")?),
+            }),
+            "http" => Ok(Self::Http {
+                url: get_str("url")?,
+                json_pointer: config
+                    .get("json_pointer")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                tls: TlsOptions {
+                    ca_file: config
+                        .get("ca_file")
+                        .and_then(|v| v.as_str())
+                        .map(expand_home),
+                    accept_invalid_certs: config
+                        .get("accept_invalid_certs")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false),
+                },
+            }),
+            "dbus" => {
+                let bus = match config
+                    .get("bus")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("session")
+                {
+                    "session" => DBusBus::Session,
+                    "system" => DBusBus::System,
+                    other => bail!("'bus' must be 'session' or 'system', got '{}'", other),
+                };
+                Ok(Self::DBus {
+                    bus,
+                    service: get_str("service")?,
+                    path: get_str("object_path")?,
+                    interface: get_str("interface")?,
+                    property: get_str("property")?,
+                })
+            }
+            other => bail!(
+                "'source' must be one of 'command', 'file', 'http' or 'dbus', got '{}'",
+                other
+            ),
+        }
+    }
+
+    /// Fetch the current value from this source (blocking)
+    pub fn fetch(&self) -> Result<String> {
+        match self {
+            Self::Command { command } => {
+                let output = std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .output()
+                    .with_context(|| format!("Failed to run command: {}", command))?;
+
+                if !output.status.success() {
+                    bail!("Command exited with {}", output.status);
+                }
+
+                Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+            }
+            Self::File { path } => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                Ok(content.trim().to_string())
+            }
+            Self::Http {
+                url,
+                json_pointer,
+                tls,
+            } => {
+                let client = tls
+                    .apply(crate::http::blocking_builder()?)?
+                    .timeout(HTTP_TIMEOUT)
+                    .build()
+                    .context("Failed to build HTTP client")?;
+
+                let response = client
+                    .get(url)
+                    .send()
+                    .with_context(|| format!("Request to {} failed", url))?;
+
+                if !response.status().is_success() {
+                    bail!("HTTP {}", response.status());
+                }
+
+                let body = response.text().context("Failed to read response body")?;
+
+                match json_pointer {
+                    Some(pointer) => {
+                        let json: serde_json::Value =
+                            serde_json::from_str(&body).context("Response is not valid JSON")?;
+                        let value = json
+                            .pointer(pointer)
+                            .with_context(|| format!("JSON pointer '{}' not found", pointer))?;
+                        Ok(json_to_string(value))
+                    }
+                    None => Ok(body.trim().to_string()),
+                }
+            }
+            Self::DBus {
+                bus,
+                service,
+                path,
+                interface,
+                property,
+            } => {
+                let connection = match bus {
+                    DBusBus::Session => zbus::blocking::Connection::session(),
+                    DBusBus::System => zbus::blocking::Connection::system(),
+                }
+                .context("Failed to connect to D-Bus")?;
+
+                let proxy = zbus::blocking::Proxy::new(
+                    &connection,
+                    service.as_str(),
+                    path.as_str(),
+                    interface.as_str(),
+                )
+                .context("Failed to create D-Bus proxy")?;
+
+                let value = proxy
+                    .get_property::<zbus::zvariant::OwnedValue>(property.as_str())
+                    .with_context(|| format!("Failed to read property '{}'", property))?;
+
+                Ok(dbus_value_to_string(&value))
+            }
+        }
+    }
+}
+
+/// Render a JSON value without quoting plain strings
+fn json_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Render a D-Bus value as display text
+fn dbus_value_to_string(value: &zbus::zvariant::Value<'_>) -> String {
+    use zbus::zvariant::Value;
+
+    match value {
+        Value::Str(s) => s.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::U8(n) => n.to_string(),
+        Value::I16(n) => n.to_string(),
+        Value::U16(n) => n.to_string(),
+        Value::I32(n) => n.to_string(),
+        Value::U32(n) => n.to_string(),
+        Value::I64(n) => n.to_string(),
+        Value::U64(n) => n.to_string(),
+        Value::F64(n) => format!("{:.1}", n),
+        Value::ObjectPath(p) => p.to_string(),
+        Value::Value(inner) => dbus_value_to_string(inner),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Expand a leading `~/` to the home directory
+pub(super) fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Widget whose content comes from a configured data source and template
+pub struct CustomWidget {
+    template: Template,
+    update_interval: Duration,
+    /// Cron schedule replacing the interval
+    schedule: Option<Schedule>,
+    /// Polled by the data service; None for pushed values
+    source: Option<DataSource>,
+    value: Option<String>,
+    error_message: Option<String>,
+    /// When the source last returned a value
+    last_fetch: Option<Instant>,
+    /// Icon shown beside the text
+    icon: Option<String>,
+}
+
+impl CustomWidget {
+    /// Create a custom widget showing what `source` returns
+    pub fn new(source: DataSource, template: Template, update_interval: u64) -> Self {
+        let mut widget = Self::new_idle(template, update_interval);
+        widget.source = Some(source);
+        widget
+    }
+
+    /// Create a custom widget without a source (values are pushed in with
+    /// [`CustomWidget::set_value`])
+    fn new_idle(template: Template, update_interval: u64) -> Self {
+        Self {
+            template,
+            update_interval: Duration::from_secs(update_interval.max(1)),
+            schedule: None,
+            source: None,
+            value: None,
+            error_message: None,
+            last_fetch: None,
+            icon: None,
+        }
+    }
+
+    /// Poll the source on `schedule` rather than every interval
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// Show `icon` beside the text
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into()).filter(|icon: &String| !icon.is_empty());
+        self
+    }
+
+    /// Set the current value directly
+    pub fn set_value(&mut self, value: String) {
+        self.value = Some(value);
+        self.error_message = None;
+    }
+
+    /// Record a fetch error, keeping the last good value
+    pub fn set_error(&mut self, error: String) {
+        self.error_message = Some(error);
+    }
+
+    /// Render the template with the current value
+    pub fn display_string(&self) -> Option<String> {
+        match (&self.value, &self.error_message) {
+            (Some(value), error) => {
+                let text = self
+                    .template
+                    .render(&TemplateVars::new().with("value", value.as_str()));
+                if error.is_some() {
+                    Some(format!("{} ⚠", text))
+                } else {
+                    Some(text)
+                }
+            }
+            (None, Some(error)) => Some(crate::fl!("error", message = error.as_str())),
+            (None, None) => None,
+        }
+    }
+}
+
+impl Widget for CustomWidget {
+    fn info(&self) -> WidgetInfo {
+        WidgetInfo {
+            id: "custom",
+            name: "Custom",
+            preferred_height: 40.0,
+            min_height: 30.0,
+            expand: false,
+        }
+    }
+
+    fn update(&mut self) {
+        // Values arrive through apply_fetch from the data service
+    }
+
+    fn content(&self) -> WidgetContent {
+        if let (None, Some(error)) = (&self.value, &self.error_message) {
+            return WidgetContent::error(error);
+        }
+        let text = self.display_string();
+        if let (Some(text), Some(icon)) = (&text, &self.icon) {
+            return if text.contains('\n') {
+                WidgetContent::IconLines {
+                    icon: icon.clone(),
+                    lines: text
+                        .lines()
+                        .map(|line| (line.to_string(), FontSize::Medium))
+                        .collect(),
+                }
+            } else {
+                WidgetContent::IconText {
+                    icon: icon.clone(),
+                    text: text.clone(),
+                    size: FontSize::Medium,
+                }
+            };
+        }
+        match text {
+            Some(text) if text.contains('\n') => WidgetContent::MultiLine {
+                lines: text
+                    .lines()
+                    .map(|line| (line.to_string(), FontSize::Medium))
+                    .collect(),
+            },
+            Some(text) => WidgetContent::Text {
+                text,
+                size: FontSize::Medium,
+            },
+            None => WidgetContent::Empty,
+        }
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.update_interval
+    }
+
+    fn is_ready(&self) -> bool {
+        self.value.is_some() || self.error_message.is_some()
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error_message.as_deref()
+    }
+
+    fn last_success(&self) -> Option<Instant> {
+        self.last_fetch
+    }
+
+    fn data_source(&self) -> Option<Source> {
+        let source = self.source.clone()?;
+        let needs_network = matches!(source, DataSource::Http { .. });
+        let mut data_source = Source::blocking(self.update_interval, move || source.fetch())
+            .with_network(needs_network);
+        if let Some(schedule) = &self.schedule {
+            data_source = data_source.with_schedule(schedule.clone());
+        }
+        Some(data_source)
+    }
+
+    fn apply_fetch(&mut self, result: anyhow::Result<TaskOutput>) {
+        match tasks::take_output::<String>(result) {
+            Ok(value) => {
+                self.set_value(value);
+                self.last_fetch = Some(Instant::now());
+            }
+            Err(e) => {
+                warn!(error = %e, "Custom widget source failed");
+                self.set_error(e.to_string());
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+/// Factory for CustomWidget
+pub struct CustomWidgetFactory;
+
+impl DynWidgetFactory for CustomWidgetFactory {
+    fn widget_type(&self) -> &'static str {
+        "custom"
+    }
+
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Custom",
+            "Output of a command, file, web request or D-Bus property",
+            "utilities-terminal-symbolic",
+        )
+    }
+
+    fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
+        let source = DataSource::from_config(config)?;
+
+        let template = Template::from_config(config, "template", &["value"])?
+            .unwrap_or_else(|| Template::parse("{value}").expect("default template is valid"));
+
+        let interval = config
+            .get("interval")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(30) as u64;
+        let schedule = Schedule::from_config(config, "interval", 30)?;
+
+        debug!(
+            source = ?source,
+            template = %template,
+            schedule = %schedule,
+            "Creating CustomWidget"
+        );
+
+        let mut widget = CustomWidget::new(source, template, interval);
+        if schedule.is_cron() {
+            widget = widget.with_schedule(schedule);
+        }
+        if let Some(icon) = config.get("icon").and_then(|v| v.as_str()) {
+            widget = widget.with_icon(icon);
+        }
+        Ok(Box::new(widget))
+    }
+
+    fn default_config(&self) -> toml::Table {
+        let mut config = toml::Table::new();
+        config.insert(
+            "source".to_string(),
+            toml::Value::String("command".to_string()),
+        );
+        config.insert(
+            "command".to_string(),
+            toml::Value::String("uptime -p".to_string()),
+        );
+        config.insert("interval".to_string(), toml::Value::Integer(60));
+        config.insert(
+            "template".to_string(),
+            toml::Value::String("{value}".to_string()),
+        );
+        config
+    }
+
+    fn validate_config(&self, config: &toml::Table) -> Result<()> {
+        if let DataSource::Http { tls, .. } = DataSource::from_config(config)? {
+            tls.certificates()?;
+        }
+
+        Template::from_config(config, "template", &["value"])?;
+
+        if let Some(interval) = config.get("interval") {
+            let val = interval
+                .as_integer()
+                .context("'interval' must be an integer")?;
+
+            if val < 1 {
+                bail!("'interval' must be at least 1 second");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_network().with_dbus()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new(
+                "source",
+                "Source",
+                FieldKind::Choice(&["command", "file", "http", "dbus"]),
+            ))
+            .with_field(
+                ConfigField::new("command", "Command", FieldKind::Text)
+                    .with_description("Shell command (command source)"),
+            )
+            .with_field(
+                ConfigField::new("path", "File", FieldKind::Path)
+                    .with_description("File to read (file source)"),
+            )
+            .with_field(
+                ConfigField::new("url", "URL", FieldKind::Text)
+                    .with_description("URL to fetch (http source)"),
+            )
+            .with_field(
+                ConfigField::new("json_pointer", "JSON pointer", FieldKind::Text)
+                    .with_description("e.g. /data/0/value (http source)"),
+            )
+            .with_field(
+                ConfigField::new("ca_file", "CA certificates", FieldKind::Path)
+                    .with_description("PEM file for a private CA (http source)"),
+            )
+            .with_field(
+                ConfigField::new(
+                    "accept_invalid_certs",
+                    "Skip certificate checks",
+                    FieldKind::Bool,
+                )
+                .with_description("Insecure; prefer a CA file (http source)"),
+            )
+            .with_field(ConfigField::new(
+                "bus",
+                "Bus",
+                FieldKind::Choice(&["session", "system"]),
+            ))
+            .with_field(
+                ConfigField::new("service", "Service", FieldKind::Text)
+                    .with_description("D-Bus service name (dbus source)"),
+            )
+            .with_field(ConfigField::new(
+                "object_path",
+                "Object path",
+                FieldKind::Text,
+            ))
+            .with_field(ConfigField::new("interface", "Interface", FieldKind::Text))
+            .with_field(ConfigField::new("property", "Property", FieldKind::Text))
+            .with_field(ConfigField::new(
+                "interval",
+                "Interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(
+                ConfigField::new("schedule", "Schedule", FieldKind::Text)
+                    .with_description("Cron expression, e.g. @hourly (overrides interval)"),
+            )
+            .with_field(ConfigField::new(
+                "template",
+                "Template",
+                FieldKind::Template(&["value"]),
+            ))
+            .with_field(
+                ConfigField::new("icon", "Icon", FieldKind::Text)
+                    .with_description("Icon name or file from the icons directory"),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn table(entries: &[(&str, &str)]) -> toml::Table {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), toml::Value::String(v.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_sources() {
+        let command = DataSource::from_config(&table(&[("command", "echo hi")])).unwrap();
+        assert_eq!(
+            command,
+            DataSource::Command {
+                command: "echo hi".to_string()
+            }
+        );
+
+        let http = DataSource::from_config(&table(&[
+            ("source", "http"),
+            ("url", "https://example.com"),
+            ("json_pointer", "/a/b"),
+            ("ca_file", "/etc/ssl/homelab.pem"),
+        ]))
+        .unwrap();
+        assert!(matches!(
+            &http,
+            DataSource::Http {
+                json_pointer: Some(_),
+                tls: TlsOptions {
+                    ca_file: Some(_),
+                    accept_invalid_certs: false,
+                },
+                ..
+            }
+        ));
+
+        let dbus = DataSource::from_config(&table(&[
+            ("source", "dbus"),
+            ("bus", "system"),
+            ("service", "org.freedesktop.UPower"),
+            ("object_path", "/org/freedesktop/UPower"),
+            ("interface", "org.freedesktop.UPower"),
+            ("property", "OnBattery"),
+        ]))
+        .unwrap();
+        assert!(matches!(
+            dbus,
+            DataSource::DBus {
+                bus: DBusBus::System,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_source_missing_keys() {
+        assert!(DataSource::from_config(&table(&[("source", "file")])).is_err());
+        assert!(DataSource::from_config(&table(&[("source", "http")])).is_err());
+        assert!(DataSource::from_config(&table(&[("source", "ftp")])).is_err());
+    }
+
+    #[test]
+    fn test_fetch_command() {
+        let source = DataSource::Command {
+            command: "echo hello".to_string(),
+        };
+        assert_eq!(source.fetch().unwrap(), "hello");
+    }
+
+    #[test]
+    fn test_fetch_file() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "42").unwrap();
+
+        let source = DataSource::File {
+            path: file.path().to_path_buf(),
+        };
+        assert_eq!(source.fetch().unwrap(), "42");
+    }
+
+    #[test]
+    fn test_template_rendering() {
+        let mut widget = CustomWidget::new_idle(Template::parse("Load: {value:.4}").unwrap(), 5);
+        assert!(widget.display_string().is_none());
+
+        widget.set_value("0.42".to_string());
+        assert_eq!(widget.display_string().unwrap(), "Load: 0.42");
+
+        widget.set_error("timeout".to_string());
+        assert!(widget.display_string().unwrap().ends_with('⚠'));
+    }
+
+    #[test]
+    fn test_icon() {
+        let mut widget =
+            CustomWidget::new_idle(Template::parse("{value}").unwrap(), 5).with_icon("backup");
+        widget.set_value("done".to_string());
+        assert!(matches!(
+            widget.content(),
+            WidgetContent::IconText { icon, .. } if icon == "backup"
+        ));
+
+        widget.set_value("done\n2 GB".to_string());
+        assert!(matches!(
+            widget.content(),
+            WidgetContent::IconLines { lines, .. } if lines.len() == 2
+        ));
+
+        let widget = CustomWidget::new_idle(Template::parse("{value}").unwrap(), 5).with_icon("");
+        assert!(widget.icon.is_none());
+    }
+
+    #[test]
+    fn test_data_source() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "7").unwrap();
+        let source = DataSource::File {
+            path: file.path().to_path_buf(),
+        };
+        let mut widget = CustomWidget::new(source, Template::parse("{value} up").unwrap(), 60);
+        assert!(
+            CustomWidget::new_idle(Template::parse("{value}").unwrap(), 5)
+                .data_source()
+                .is_none()
+        );
+
+        let source = widget.data_source().unwrap();
+        assert!(!source.needs_network());
+        assert_eq!(source.schedule(), &Schedule::Every(Duration::from_secs(60)));
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        widget.apply_fetch(runtime.block_on(source.fetch()));
+        assert_eq!(widget.display_string().unwrap(), "7 up");
+
+        widget.apply_fetch(Err(anyhow::anyhow!("gone")));
+        assert_eq!(widget.display_string().unwrap(), "7 up ⚠");
+    }
+
+    #[test]
+    fn test_json_to_string() {
+        let json: serde_json::Value = serde_json::json!({"s": "text", "n": 1.5});
+        assert_eq!(json_to_string(&json["s"]), "text");
+        assert_eq!(json_to_string(&json["n"]), "1.5");
+    }
+
+    #[test]
+    fn test_factory_creation() {
+        let factory = CustomWidgetFactory;
+        let config = factory.default_config();
+        assert!(factory.validate_config(&config).is_ok());
+        let widget = factory.create(&config).unwrap();
+        assert_eq!(widget.info().id, "custom");
+    }
+
+    #[test]
+    fn test_factory_validation() {
+        let factory = CustomWidgetFactory;
+
+        let mut invalid = factory.default_config();
+        invalid.insert("interval".to_string(), toml::Value::Integer(0));
+        assert!(factory.validate_config(&invalid).is_err());
+
+        let missing_command = table(&[("source", "command")]);
+        assert!(factory.validate_config(&missing_command).is_err());
+
+        let missing_ca = table(&[
+            ("source", "http"),
+            ("url", "https://ha.local"),
+            ("ca_file", "/nonexistent/ca.pem"),
+        ]);
+        assert!(factory.validate_config(&missing_ca).is_err());
+    }
+
+    #[test]
+    fn test_factory_schedule() {
+        let factory = CustomWidgetFactory;
+        let mut config = factory.default_config();
+        config.insert(
+            "schedule".to_string(),
+            toml::Value::String("0 30 2 * * *".to_string()),
+        );
+        let widget = factory.create(&config).unwrap();
+        assert!(widget.data_source().unwrap().schedule().is_cron());
+
+        config.insert(
+            "schedule".to_string(),
+            toml::Value::String("nightly".to_string()),
+        );
+        assert!(factory.create(&config).is_err());
+    }
+}
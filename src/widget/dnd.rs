@@ -0,0 +1,401 @@
+//! Do Not Disturb toggle widget
+//!
+//! Shows whether COSMIC's notification daemon is in Do Not Disturb mode and
+//! flips it on click. The state lives in the daemon's cosmic-config entry
+//! (`com.system76.CosmicNotifications/v1/do_not_disturb`), which the daemon
+//! watches, so toggling here has the same effect as the panel applet.
+//!
+//! With focus hours set, DND is switched on when the window starts and back
+//! off when it ends, unless it was changed by hand in between.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveTime};
+use tracing::{debug, info, warn};
+
+use super::registry::DynWidgetFactory;
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{FontSize, MouseButton, Widget, WidgetAction, WidgetContent, WidgetInfo};
+
+/// cosmic-config entry holding the DND flag, relative to the config dir
+const DND_ENTRY: &str = "cosmic/com.system76.CosmicNotifications/v1/do_not_disturb";
+
+/// Daily window during which DND is switched on automatically
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusHours {
+    /// When DND goes on
+    pub start: NaiveTime,
+    /// When DND goes off again; before `start` for windows past midnight
+    pub end: NaiveTime,
+}
+
+impl FocusHours {
+    /// Whether `time` falls inside the window
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Widget showing and toggling Do Not Disturb
+pub struct DndWidget {
+    /// The daemon's `do_not_disturb` config file
+    path: PathBuf,
+    show_label: bool,
+    focus_hours: Option<FocusHours>,
+    enabled: bool,
+    /// Whether the last update was inside the focus window
+    in_focus: bool,
+    /// DND was switched on by the focus window and not touched since
+    auto_enabled: bool,
+    error_message: Option<String>,
+}
+
+impl DndWidget {
+    /// Create a widget using the COSMIC notifications config in the user's
+    /// config directory
+    pub fn new() -> Self {
+        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+        Self::with_config_dir(&config_dir)
+    }
+
+    /// Create a widget using the COSMIC config under `config_dir`
+    pub fn with_config_dir(config_dir: &Path) -> Self {
+        let path = config_dir.join(DND_ENTRY);
+        let enabled = read_dnd(&path);
+        Self {
+            path,
+            show_label: true,
+            focus_hours: None,
+            enabled,
+            in_focus: false,
+            auto_enabled: false,
+            error_message: None,
+        }
+    }
+
+    /// Show "Do Not Disturb" / "Notifications on" beside the icon
+    pub fn with_label(mut self, show_label: bool) -> Self {
+        self.show_label = show_label;
+        self
+    }
+
+    /// Switch DND on automatically during these hours
+    pub fn with_focus_hours(mut self, focus_hours: Option<FocusHours>) -> Self {
+        self.focus_hours = focus_hours;
+        self
+    }
+
+    /// Whether DND is on
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turn DND on or off
+    pub fn set_enabled(&mut self, enabled: bool) {
+        match write_dnd(&self.path, enabled) {
+            Ok(()) => {
+                info!(enabled = enabled, "Set Do Not Disturb");
+                self.enabled = enabled;
+                self.error_message = None;
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to set Do Not Disturb");
+                self.error_message = Some("Can't change Do Not Disturb".to_string());
+            }
+        }
+    }
+
+    /// Pick up outside changes and apply the focus window at `now`
+    fn refresh(&mut self, now: NaiveTime) {
+        let enabled = read_dnd(&self.path);
+        if enabled != self.enabled {
+            debug!(enabled = enabled, "Do Not Disturb changed elsewhere");
+            self.enabled = enabled;
+            self.auto_enabled = false;
+        }
+
+        let Some(focus_hours) = self.focus_hours else {
+            return;
+        };
+        let in_focus = focus_hours.contains(now);
+        if in_focus && !self.in_focus && !self.enabled {
+            info!("Focus hours started");
+            self.set_enabled(true);
+            self.auto_enabled = self.enabled;
+        } else if !in_focus && self.in_focus && self.auto_enabled {
+            info!("Focus hours ended");
+            self.set_enabled(false);
+            self.auto_enabled = false;
+        }
+        self.in_focus = in_focus;
+    }
+}
+
+impl Default for DndWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for DndWidget {
+    fn info(&self) -> WidgetInfo {
+        WidgetInfo {
+            id: "dnd",
+            name: "Do Not Disturb",
+            preferred_height: 48.0,
+            min_height: 32.0,
+            expand: false,
+        }
+    }
+
+    fn update(&mut self) {
+        self.refresh(Local::now().time());
+    }
+
+    fn content(&self) -> WidgetContent {
+        let icon = if self.enabled {
+            "notifications-off"
+        } else {
+            "notifications-on"
+        };
+        let text = match (self.show_label, self.enabled) {
+            (false, _) => String::new(),
+            (true, true) => "Do Not Disturb".to_string(),
+            (true, false) => "Notifications on".to_string(),
+        };
+        WidgetContent::IconText {
+            icon: icon.to_string(),
+            text,
+            size: FontSize::Medium,
+        }
+    }
+
+    fn update_interval(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error_message.as_deref()
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn on_click(&mut self, button: MouseButton, _x: f32, _y: f32) -> Option<WidgetAction> {
+        if button != MouseButton::Left {
+            return None;
+        }
+        self.set_enabled(!self.enabled);
+        // A manual choice wins over the focus window until it next starts
+        self.auto_enabled = false;
+        Some(WidgetAction::Toggle)
+    }
+}
+
+/// Read the DND flag; a missing entry means off
+fn read_dnd(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|s| s.trim() == "true")
+        .unwrap_or(false)
+}
+
+/// Write the DND flag in cosmic-config's RON format
+fn write_dnd(path: &Path, enabled: bool) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    fs::write(path, if enabled { "true" } else { "false" })
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+/// Factory for DndWidget
+pub struct DndWidgetFactory;
+
+impl DynWidgetFactory for DndWidgetFactory {
+    fn widget_type(&self) -> &'static str {
+        "dnd"
+    }
+
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Do Not Disturb",
+            "Shows and toggles Do Not Disturb, with optional focus hours",
+            "notification-disabled-symbolic",
+        )
+    }
+
+    fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
+        let show_label = config
+            .get("show_label")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let focus_start = config
+            .get("focus_start")
+            .and_then(|v| v.as_str())
+            .and_then(parse_time);
+        let focus_end = config
+            .get("focus_end")
+            .and_then(|v| v.as_str())
+            .and_then(parse_time);
+        let focus_hours = match (focus_start, focus_end) {
+            (Some(start), Some(end)) => Some(FocusHours { start, end }),
+            _ => None,
+        };
+
+        info!(
+            show_label = show_label,
+            focus_hours = ?focus_hours,
+            "Creating DndWidget"
+        );
+
+        Ok(Box::new(
+            DndWidget::new()
+                .with_label(show_label)
+                .with_focus_hours(focus_hours),
+        ))
+    }
+
+    fn default_config(&self) -> toml::Table {
+        let mut config = toml::Table::new();
+        config.insert("show_label".to_string(), toml::Value::Boolean(true));
+        config
+    }
+
+    fn validate_config(&self, config: &toml::Table) -> Result<()> {
+        if let Some(show_label) = config.get("show_label") {
+            show_label
+                .as_bool()
+                .context("'show_label' must be a boolean")?;
+        }
+
+        for key in ["focus_start", "focus_end"] {
+            if let Some(value) = config.get(key) {
+                let value = value
+                    .as_str()
+                    .with_context(|| format!("'{}' must be a string", key))?;
+                if parse_time(value).is_none() {
+                    anyhow::bail!("'{}' must be HH:MM, got '{}'", key, value);
+                }
+            }
+        }
+
+        if config.contains_key("focus_start") != config.contains_key("focus_end") {
+            anyhow::bail!("'focus_start' and 'focus_end' must be set together");
+        }
+
+        Ok(())
+    }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_interactive()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new(
+                "show_label",
+                "Show label",
+                FieldKind::Bool,
+            ))
+            .with_field(
+                ConfigField::new("focus_start", "Focus hours start", FieldKind::Text)
+                    .with_description("Turn Do Not Disturb on at this time (HH:MM)"),
+            )
+            .with_field(
+                ConfigField::new("focus_end", "Focus hours end", FieldKind::Text)
+                    .with_description("Turn it back off at this time (HH:MM)"),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> NaiveTime {
+        parse_time(value).unwrap()
+    }
+
+    #[test]
+    fn test_toggle_writes_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut widget = DndWidget::with_config_dir(dir.path());
+        assert!(!widget.is_enabled());
+
+        widget.on_click(MouseButton::Left, 0.0, 0.0);
+        assert!(widget.is_enabled());
+        assert!(read_dnd(&dir.path().join(DND_ENTRY)));
+
+        // Changes from the panel applet are picked up
+        write_dnd(&dir.path().join(DND_ENTRY), false).unwrap();
+        widget.refresh(time("12:00"));
+        assert!(!widget.is_enabled());
+    }
+
+    #[test]
+    fn test_focus_hours() {
+        let overnight = FocusHours {
+            start: time("22:00"),
+            end: time("07:00"),
+        };
+        assert!(overnight.contains(time("23:30")));
+        assert!(overnight.contains(time("06:59")));
+        assert!(!overnight.contains(time("12:00")));
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut widget =
+            DndWidget::with_config_dir(dir.path()).with_focus_hours(Some(FocusHours {
+                start: time("09:00"),
+                end: time("12:00"),
+            }));
+        widget.refresh(time("08:59"));
+        assert!(!widget.is_enabled());
+        widget.refresh(time("09:00"));
+        assert!(widget.is_enabled());
+        widget.refresh(time("12:00"));
+        assert!(!widget.is_enabled());
+
+        // Turning DND off by hand during focus hours sticks
+        widget.refresh(time("09:00"));
+        widget.on_click(MouseButton::Left, 0.0, 0.0);
+        widget.refresh(time("10:00"));
+        assert!(!widget.is_enabled());
+    }
+
+    #[test]
+    fn test_factory_validation() {
+        let factory = DndWidgetFactory;
+        assert!(factory.validate_config(&factory.default_config()).is_ok());
+
+        let mut config = toml::Table::new();
+        config.insert(
+            "focus_start".to_string(),
+            toml::Value::String("9am".to_string()),
+        );
+        config.insert(
+            "focus_end".to_string(),
+            toml::Value::String("12:00".to_string()),
+        );
+        assert!(factory.validate_config(&config).is_err());
+
+        config.remove("focus_start");
+        assert!(factory.validate_config(&config).is_err());
+    }
+}
@@ -0,0 +1,516 @@
+//! Google Calendar backend for the calendar widget
+//!
+//! Signing in uses Google's device-code flow: `cosmic-desktop-widget
+//! google-auth` prints a code to enter at Google's verification page, then
+//! waits for the grant. The resulting tokens are kept in the XDG data
+//! directory and refreshed by the widget as they expire.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use super::calendar::CalendarEvent;
+
+const DEVICE_CODE_URL: &str = "https://oauth2.googleapis.com/device/code";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const EVENTS_URL: &str = "https://www.googleapis.com/calendar/v3/calendars";
+const SCOPE: &str = "https://www.googleapis.com/auth/calendar.readonly";
+const DEVICE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+const TOKEN_FILE: &str = "google-calendar-token.toml";
+
+/// Refresh this long before the access token actually expires
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// OAuth client registered in the Google Cloud console
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoogleCredentials {
+    /// OAuth client ID
+    pub client_id: String,
+    /// OAuth client secret
+    pub client_secret: String,
+}
+
+impl GoogleCredentials {
+    /// Read `google_client_id` and `google_client_secret` from a calendar
+    /// widget's config
+    pub fn from_config(config: &toml::Table) -> Option<Self> {
+        let field = |key: &str| {
+            config
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        Some(Self {
+            client_id: field("google_client_id")?,
+            client_secret: field("google_client_secret")?,
+        })
+    }
+}
+
+/// Tokens granted by signing in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoogleToken {
+    /// Short-lived token sent with API requests
+    pub access_token: String,
+    /// Long-lived token used to get new access tokens
+    pub refresh_token: String,
+    /// When the access token expires, in seconds since the Unix epoch
+    pub expires_at: i64,
+}
+
+impl GoogleToken {
+    /// Where tokens are stored
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("cosmic-desktop-widget").join(TOKEN_FILE))
+    }
+
+    /// Load the stored token, if signed in
+    pub fn load() -> Result<Option<Self>> {
+        match Self::path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(None),
+        }
+    }
+
+    /// Load a token from `path`; a missing file means not signed in
+    pub fn load_from(path: &Path) -> Result<Option<Self>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let token =
+            toml::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(token))
+    }
+
+    /// Store the token, readable only by the user
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().context("No data directory to store the token in")?;
+        self.save_to(&path)
+    }
+
+    /// Store the token at `path`, readable only by the user
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let text = toml::to_string(self).context("Failed to serialize token")?;
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        file.write_all(text.as_bytes())
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Whether the access token has expired (or is about to) at `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now.timestamp() + EXPIRY_MARGIN_SECS >= self.expires_at
+    }
+}
+
+/// Code the user enters to sign in
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceCode {
+    /// Identifies this sign-in when polling for the token
+    pub device_code: String,
+    /// Code shown to the user
+    pub user_code: String,
+    /// Page the user enters the code on
+    pub verification_url: String,
+    /// Seconds until the code expires
+    pub expires_in: u64,
+    /// Seconds to wait between polls
+    #[serde(default = "default_poll_interval")]
+    pub interval: u64,
+}
+
+fn default_poll_interval() -> u64 {
+    5
+}
+
+/// Token endpoint response, for both grants and refreshes
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+    error: Option<String>,
+}
+
+/// Sign in with the device-code flow, blocking until the user approves
+///
+/// `show_code` is called once with the code to present to the user.
+pub fn authorize(
+    credentials: &GoogleCredentials,
+    show_code: impl FnOnce(&DeviceCode),
+) -> Result<GoogleToken> {
+    let client = crate::http::blocking_builder()?
+        .build()
+        .context("Failed to build HTTP client")?;
+    let response = client
+        .post(DEVICE_CODE_URL)
+        .form(&[
+            ("client_id", credentials.client_id.as_str()),
+            ("scope", SCOPE),
+        ])
+        .send()
+        .context("Failed to request a device code")?;
+    if !response.status().is_success() {
+        bail!(
+            "Google returned status {} for the device code",
+            response.status()
+        );
+    }
+    let code: DeviceCode = response.json().context("Invalid device code response")?;
+    show_code(&code);
+
+    let mut interval = Duration::from_secs(code.interval);
+    let deadline = std::time::Instant::now() + Duration::from_secs(code.expires_in);
+    while std::time::Instant::now() < deadline {
+        std::thread::sleep(interval);
+        let response: TokenResponse = client
+            .post(TOKEN_URL)
+            .form(&[
+                ("client_id", credentials.client_id.as_str()),
+                ("client_secret", credentials.client_secret.as_str()),
+                ("device_code", code.device_code.as_str()),
+                ("grant_type", DEVICE_GRANT),
+            ])
+            .send()
+            .context("Failed to poll for the token")?
+            .json()
+            .context("Invalid token response")?;
+
+        match response.error.as_deref() {
+            Some("authorization_pending") => continue,
+            Some("slow_down") => interval += Duration::from_secs(5),
+            Some("access_denied") => bail!("Sign-in was declined"),
+            Some(error) => bail!("Sign-in failed: {}", error),
+            None => return token_from_response(response, None, Utc::now()),
+        }
+    }
+    bail!("The sign-in code expired before it was entered")
+}
+
+fn token_from_response(
+    response: TokenResponse,
+    previous_refresh: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<GoogleToken> {
+    let access_token = response
+        .access_token
+        .context("Token response has no access token")?;
+    // Refreshes don't hand out a new refresh token; keep the old one
+    let refresh_token = response
+        .refresh_token
+        .or_else(|| previous_refresh.map(str::to_string))
+        .context("Token response has no refresh token")?;
+    Ok(GoogleToken {
+        access_token,
+        refresh_token,
+        expires_at: now.timestamp() + response.expires_in.unwrap_or(3600),
+    })
+}
+
+/// A valid access token, refreshing and storing the stored one if needed
+async fn access_token(client: &reqwest::Client, credentials: &GoogleCredentials) -> Result<String> {
+    let token = GoogleToken::load()?
+        .context("Not signed in to Google. Run: cosmic-desktop-widget google-auth")?;
+    if !token.is_expired(Utc::now()) {
+        return Ok(token.access_token);
+    }
+
+    debug!("Refreshing Google access token");
+    let response = client
+        .post(TOKEN_URL)
+        .form(&[
+            ("client_id", credentials.client_id.as_str()),
+            ("client_secret", credentials.client_secret.as_str()),
+            ("refresh_token", token.refresh_token.as_str()),
+            ("grant_type", "refresh_token"),
+        ])
+        .send()
+        .await
+        .context("Failed to refresh the Google token")?;
+    let status = response.status();
+    let response: TokenResponse = response.json().await.context("Invalid token response")?;
+    if let Some(error) = response.error.as_deref() {
+        bail!(
+            "Google token refresh failed ({}): {}. Run: cosmic-desktop-widget google-auth",
+            status,
+            error
+        );
+    }
+
+    let token = token_from_response(response, Some(&token.refresh_token), Utc::now())?;
+    token.save()?;
+    Ok(token.access_token)
+}
+
+/// One page of an events list
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventList {
+    #[serde(default)]
+    items: Vec<GoogleEvent>,
+    next_page_token: Option<String>,
+    /// Reminders for events that use the calendar's defaults
+    #[serde(default)]
+    default_reminders: Vec<EventReminder>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventReminder {
+    minutes: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventReminders {
+    #[serde(default)]
+    use_default: bool,
+    #[serde(default)]
+    overrides: Vec<EventReminder>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleEvent {
+    summary: Option<String>,
+    status: Option<String>,
+    start: Option<EventTime>,
+    reminders: Option<EventReminders>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventTime {
+    /// Set for timed events
+    date_time: Option<String>,
+    /// Set for all-day events
+    date: Option<String>,
+}
+
+impl GoogleEvent {
+    fn into_event(self, default_reminders: &[EventReminder]) -> Option<CalendarEvent> {
+        if self.status.as_deref() == Some("cancelled") {
+            return None;
+        }
+        let start = self.start?;
+        let (start, all_day) = match (start.date_time, start.date) {
+            (Some(time), _) => (
+                DateTime::parse_from_rfc3339(&time)
+                    .ok()?
+                    .with_timezone(&Local),
+                false,
+            ),
+            (None, Some(date)) => {
+                let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()?;
+                let start = Local
+                    .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+                    .earliest()?;
+                (start, true)
+            }
+            (None, None) => return None,
+        };
+        // The earliest of the event's reminders
+        let reminders = match &self.reminders {
+            Some(reminders) if !reminders.use_default => reminders.overrides.as_slice(),
+            _ => default_reminders,
+        };
+        Some(CalendarEvent {
+            start,
+            title: self.summary.unwrap_or_else(|| "(No title)".to_string()),
+            all_day,
+            reminder_minutes: reminders.iter().map(|reminder| reminder.minutes).max(),
+        })
+    }
+}
+
+fn parse_events(list: EventList) -> Vec<CalendarEvent> {
+    let defaults = list.default_reminders;
+    list.items
+        .into_iter()
+        .filter_map(|event| event.into_event(&defaults))
+        .collect()
+}
+
+/// Fetch events between `start` and `end` from each of `calendars`
+///
+/// Calendars are given by ID; `primary` is the signed-in user's own.
+pub async fn fetch_events(
+    credentials: &GoogleCredentials,
+    calendars: &[String],
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+) -> Result<Vec<CalendarEvent>> {
+    let client = crate::http::client();
+    let token = access_token(&client, credentials).await?;
+    let time_min = start.to_rfc3339();
+    let time_max = end.to_rfc3339();
+
+    let mut events = Vec::new();
+    for calendar in calendars {
+        let mut url = reqwest::Url::parse(EVENTS_URL)?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid events URL"))?
+            .push(calendar)
+            .push("events");
+
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut request = client.get(url.clone()).bearer_auth(&token).query(&[
+                ("timeMin", time_min.as_str()),
+                ("timeMax", time_max.as_str()),
+                ("singleEvents", "true"),
+                ("orderBy", "startTime"),
+            ]);
+            if let Some(page) = &page_token {
+                request = request.query(&[("pageToken", page.as_str())]);
+            }
+
+            let response = request
+                .send()
+                .await
+                .with_context(|| format!("Failed to fetch calendar '{}'", calendar))?;
+            if !response.status().is_success() {
+                bail!(
+                    "Google returned status {} for calendar '{}'",
+                    response.status(),
+                    calendar
+                );
+            }
+            let list: EventList = response
+                .json()
+                .await
+                .with_context(|| format!("Invalid events for calendar '{}'", calendar))?;
+            page_token = list.next_page_token.clone();
+            events.extend(parse_events(list));
+            if page_token.is_none() {
+                break;
+            }
+        }
+    }
+
+    info!(
+        calendars = calendars.len(),
+        events = events.len(),
+        "Fetched Google Calendar events"
+    );
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+
+    #[test]
+    fn test_parse_events() {
+        let list: EventList = serde_json::from_str(
+            r#"{
+                "items": [
+                    {"summary": "Standup", "start": {"dateTime": "2026-03-02T09:30:00Z"},
+                     "reminders": {"useDefault": true}},
+                    {"summary": "Holiday", "start": {"date": "2026-03-03"},
+                     "reminders": {"useDefault": false, "overrides": [
+                        {"method": "popup", "minutes": 60},
+                        {"method": "email", "minutes": 1440}
+                     ]}},
+                    {"summary": "Moved", "status": "cancelled",
+                     "start": {"dateTime": "2026-03-02T11:00:00Z"}},
+                    {"start": {"dateTime": "2026-03-04T15:00:00+01:00"}}
+                ],
+                "nextPageToken": "abc",
+                "defaultReminders": [{"method": "popup", "minutes": 10}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(list.next_page_token.as_deref(), Some("abc"));
+
+        let events = parse_events(list);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].title, "Standup");
+        assert!(!events[0].all_day);
+        assert_eq!(events[0].start.with_timezone(&Utc).hour(), 9);
+        assert_eq!(events[0].reminder_minutes, Some(10));
+        assert_eq!(events[1].title, "Holiday");
+        assert!(events[1].all_day);
+        assert_eq!(
+            events[1].start.date_naive(),
+            NaiveDate::from_ymd_opt(2026, 3, 3).unwrap()
+        );
+        assert_eq!(events[1].reminder_minutes, Some(1440));
+        assert_eq!(events[2].title, "(No title)");
+    }
+
+    #[test]
+    fn test_token_expiry_and_refresh() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let response = TokenResponse {
+            access_token: Some("new".to_string()),
+            refresh_token: None,
+            expires_in: Some(3600),
+            error: None,
+        };
+        let token = token_from_response(response, Some("refresh"), now).unwrap();
+        assert_eq!(token.refresh_token, "refresh");
+        assert!(!token.is_expired(now));
+        assert!(token.is_expired(now + chrono::Duration::minutes(59)));
+
+        let response = TokenResponse {
+            access_token: Some("new".to_string()),
+            refresh_token: None,
+            expires_in: None,
+            error: None,
+        };
+        assert!(token_from_response(response, None, now).is_err());
+    }
+
+    #[test]
+    fn test_token_file_round_trip() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("data").join(TOKEN_FILE);
+        assert!(GoogleToken::load_from(&path).unwrap().is_none());
+
+        let token = GoogleToken {
+            access_token: "access".to_string(),
+            refresh_token: "refresh".to_string(),
+            expires_at: 1_800_000_000,
+        };
+        token.save_to(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let loaded = GoogleToken::load_from(&path).unwrap().unwrap();
+        assert_eq!(loaded.refresh_token, "refresh");
+        assert_eq!(loaded.expires_at, 1_800_000_000);
+    }
+
+    #[test]
+    fn test_credentials_from_config() {
+        let mut config = toml::Table::new();
+        config.insert("google_client_id".into(), "id".into());
+        assert!(GoogleCredentials::from_config(&config).is_none());
+        config.insert("google_client_secret".into(), "secret".into());
+        let credentials = GoogleCredentials::from_config(&config).unwrap();
+        assert_eq!(credentials.client_id, "id");
+        assert_eq!(credentials.client_secret, "secret");
+    }
+}
@@ -0,0 +1,346 @@
+//! Containing widget panics
+//!
+//! The registry wraps every widget it creates in a [`GuardedWidget`]. A
+//! panic in any widget method is caught there, the widget is switched off
+//! and shows that it stopped, and the rest of the widgets carry on. The
+//! panic hook (see [`crate::crash`]) still writes a crash report for it.
+//!
+//! A widget that couldn't be created at all is stood in for by a
+//! [`FailedWidget`], so its slot shows an error card rather than going
+//! missing.
+
+use std::borrow::Cow;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use super::traits::{
+    FetchReport, MouseButton, ScrollDirection, SoundCue, Widget, WidgetAction, WidgetContent,
+    WidgetInfo,
+};
+use crate::metrics::PerfStats;
+use crate::update::{Reminder, Source, TaskOutput};
+
+/// A widget that disables itself instead of taking the process down
+pub struct GuardedWidget {
+    inner: Box<dyn Widget>,
+    /// Layout info from before any panic, reused once stopped
+    info: WidgetInfo,
+    /// Why the widget stopped; set by the first panic
+    failure: OnceLock<String>,
+}
+
+impl GuardedWidget {
+    /// Guard `inner`
+    pub fn new(inner: Box<dyn Widget>) -> Self {
+        let info = inner.info();
+        Self {
+            inner,
+            info,
+            failure: OnceLock::new(),
+        }
+    }
+
+    /// Whether the widget panicked and was switched off
+    pub fn has_failed(&self) -> bool {
+        self.failure.get().is_some()
+    }
+
+    /// Call `f` on the widget unless it has failed, returning `fallback` if
+    /// it has or does now
+    fn call<T>(&mut self, method: &str, fallback: T, f: impl FnOnce(&mut dyn Widget) -> T) -> T {
+        if self.has_failed() {
+            return fallback;
+        }
+        let inner = self.inner.as_mut();
+        match panic::catch_unwind(AssertUnwindSafe(|| f(inner))) {
+            Ok(value) => value,
+            Err(payload) => {
+                self.fail(method, payload.as_ref());
+                fallback
+            }
+        }
+    }
+
+    /// [`GuardedWidget::call`] for methods that only look at the widget
+    fn peek<T>(&self, method: &str, fallback: T, f: impl FnOnce(&dyn Widget) -> T) -> T {
+        if self.has_failed() {
+            return fallback;
+        }
+        let inner = self.inner.as_ref();
+        match panic::catch_unwind(AssertUnwindSafe(|| f(inner))) {
+            Ok(value) => value,
+            Err(payload) => {
+                self.fail(method, payload.as_ref());
+                fallback
+            }
+        }
+    }
+
+    fn fail(&self, method: &str, payload: &(dyn std::any::Any + Send)) {
+        let message = crate::crash::panic_message(payload);
+        tracing::error!(
+            widget = self.info.id,
+            method,
+            panic = %message,
+            "Widget panicked, disabling it"
+        );
+        let _ = self.failure.set(message);
+    }
+}
+
+impl Widget for GuardedWidget {
+    fn info(&self) -> WidgetInfo {
+        self.peek("info", self.info.clone(), |w| w.info())
+    }
+
+    fn update(&mut self) {
+        self.call("update", (), |w| w.update())
+    }
+
+    fn content(&self) -> WidgetContent {
+        let content = self.peek("content", None, |w| Some(w.content()));
+        content.unwrap_or_else(|| WidgetContent::error(&crate::fl!("widget-crashed")))
+    }
+
+    fn content_ref(&self) -> Cow<'_, WidgetContent> {
+        let crashed = || Cow::Owned(WidgetContent::error(&crate::fl!("widget-crashed")));
+        if self.has_failed() {
+            return crashed();
+        }
+        // Not through peek: the content borrows from the inner widget
+        match panic::catch_unwind(AssertUnwindSafe(|| self.inner.content_ref())) {
+            Ok(content) => content,
+            Err(payload) => {
+                self.fail("content", payload.as_ref());
+                crashed()
+            }
+        }
+    }
+
+    fn update_interval(&self) -> Duration {
+        // Stopped widgets still get asked; don't let them set the pace
+        self.peek("update_interval", Duration::from_secs(60), |w| {
+            w.update_interval()
+        })
+    }
+
+    fn is_ready(&self) -> bool {
+        self.peek("is_ready", true, |w| w.is_ready())
+    }
+
+    fn error(&self) -> Option<&str> {
+        if let Some(failure) = self.failure.get() {
+            return Some(failure);
+        }
+        match panic::catch_unwind(AssertUnwindSafe(|| self.inner.error())) {
+            Ok(error) => error,
+            Err(payload) => {
+                self.fail("error", payload.as_ref());
+                self.failure.get().map(String::as_str)
+            }
+        }
+    }
+
+    fn take_sound_cue(&mut self) -> Option<SoundCue> {
+        self.call("take_sound_cue", None, |w| w.take_sound_cue())
+    }
+
+    fn reminders(&self) -> Vec<Reminder> {
+        self.peek("reminders", Vec::new(), |w| w.reminders())
+    }
+
+    fn inhibits_idle(&self) -> bool {
+        self.peek("inhibits_idle", false, |w| w.inhibits_idle())
+    }
+
+    fn observe_metrics(&mut self, stats: &PerfStats) {
+        self.call("observe_metrics", (), |w| w.observe_metrics(stats))
+    }
+
+    fn take_fetch_reports(&mut self) -> Vec<FetchReport> {
+        self.call("take_fetch_reports", Vec::new(), |w| w.take_fetch_reports())
+    }
+
+    fn data_source(&self) -> Option<Source> {
+        self.peek("data_source", None, |w| w.data_source())
+    }
+
+    fn apply_fetch(&mut self, result: anyhow::Result<TaskOutput>) {
+        self.call("apply_fetch", (), |w| w.apply_fetch(result))
+    }
+
+    fn last_success(&self) -> Option<Instant> {
+        self.peek("last_success", None, |w| w.last_success())
+    }
+
+    fn glyph_set(&self) -> Option<String> {
+        self.peek("glyph_set", None, |w| w.glyph_set())
+    }
+
+    fn on_start(&mut self) {
+        self.call("on_start", (), |w| w.on_start())
+    }
+
+    fn on_stop(&mut self) {
+        self.call("on_stop", (), |w| w.on_stop())
+    }
+
+    fn on_suspend(&mut self) {
+        self.call("on_suspend", (), |w| w.on_suspend())
+    }
+
+    fn on_resume(&mut self) {
+        self.call("on_resume", (), |w| w.on_resume())
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.peek("is_interactive", false, |w| w.is_interactive())
+    }
+
+    fn on_click(&mut self, button: MouseButton, x: f32, y: f32) -> Option<WidgetAction> {
+        self.call("on_click", None, |w| w.on_click(button, x, y))
+    }
+
+    fn on_scroll(&mut self, direction: ScrollDirection, x: f32, y: f32) -> Option<WidgetAction> {
+        self.call("on_scroll", None, |w| w.on_scroll(direction, x, y))
+    }
+
+    fn on_pointer_enter(&mut self) {
+        self.call("on_pointer_enter", (), |w| w.on_pointer_enter())
+    }
+
+    fn on_pointer_leave(&mut self) {
+        self.call("on_pointer_leave", (), |w| w.on_pointer_leave())
+    }
+
+    fn set_compact(&mut self, compact: bool) {
+        self.call("set_compact", (), |w| w.set_compact(compact))
+    }
+
+    fn save_state(&self) -> Option<toml::Table> {
+        self.peek("save_state", None, |w| w.save_state())
+    }
+
+    fn restore_state(&mut self, state: &toml::Table) {
+        self.call("restore_state", (), |w| w.restore_state(state))
+    }
+}
+
+/// Placeholder for a widget the registry failed to create
+///
+/// Keeps the widget's place in the layout and shows why it's empty.
+pub struct FailedWidget {
+    message: String,
+}
+
+impl FailedWidget {
+    /// Stand-in for a widget that failed with `error`
+    pub fn new(error: &anyhow::Error) -> Self {
+        Self {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl Widget for FailedWidget {
+    fn info(&self) -> WidgetInfo {
+        WidgetInfo {
+            id: "failed",
+            name: "Failed Widget",
+            preferred_height: 60.0,
+            min_height: 40.0,
+            expand: false,
+        }
+    }
+
+    fn update(&mut self) {}
+
+    fn content(&self) -> WidgetContent {
+        WidgetContent::error(&self.message)
+    }
+
+    fn update_interval(&self) -> Duration {
+        Duration::from_secs(3600)
+    }
+
+    fn error(&self) -> Option<&str> {
+        Some(&self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::FontSize;
+
+    /// Panics on its second update
+    struct Flaky {
+        updates: u32,
+    }
+
+    impl Widget for Flaky {
+        fn info(&self) -> WidgetInfo {
+            WidgetInfo {
+                id: "flaky",
+                name: "Flaky",
+                preferred_height: 40.0,
+                min_height: 30.0,
+                expand: false,
+            }
+        }
+
+        fn update(&mut self) {
+            self.updates += 1;
+            if self.updates == 2 {
+                panic!("second update");
+            }
+        }
+
+        fn content(&self) -> WidgetContent {
+            WidgetContent::Text {
+                text: format!("update {}", self.updates),
+                size: FontSize::Medium,
+            }
+        }
+    }
+
+    #[test]
+    fn test_guarded_widget_survives_panic() {
+        let mut widget = GuardedWidget::new(Box::new(Flaky { updates: 0 }));
+        widget.update();
+        assert!(!widget.has_failed());
+        assert_eq!(
+            widget.content(),
+            WidgetContent::Text {
+                text: "update 1".to_string(),
+                size: FontSize::Medium,
+            }
+        );
+
+        widget.update();
+        assert!(widget.has_failed());
+        assert_eq!(widget.error(), Some("second update"));
+        assert!(matches!(widget.content(), WidgetContent::Error { .. }));
+        assert!(matches!(
+            widget.content_ref().as_ref(),
+            WidgetContent::Error { .. }
+        ));
+        assert_eq!(widget.info().id, "flaky");
+
+        // Stopped for good
+        widget.update();
+        widget.on_stop();
+        assert!(widget.on_click(MouseButton::Left, 0.5, 0.5).is_none());
+    }
+
+    #[test]
+    fn test_failed_widget() {
+        let widget = FailedWidget::new(&anyhow::anyhow!("Unknown widget type: news"));
+        assert_eq!(widget.error(), Some("Unknown widget type: news"));
+        assert_eq!(
+            widget.content(),
+            WidgetContent::error("Unknown widget type: news")
+        );
+    }
+}
@@ -0,0 +1,424 @@
+//! Quick-launcher widget
+//!
+//! A row of app icons that start the app when clicked. Apps come from their
+//! desktop files (`firefox`, `org.gnome.Nautilus`), which supply the name,
+//! icon and command, or are given as an explicit command and icon name.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tracing::{debug, info, warn};
+
+use super::registry::DynWidgetFactory;
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{FontSize, MouseButton, Widget, WidgetAction, WidgetContent, WidgetInfo};
+
+/// One app in the launcher
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortcut {
+    /// Caption under the icon
+    pub name: String,
+    /// Icon name from the icon theme, or an image path
+    pub icon: String,
+    /// Shell command that starts the app
+    pub command: String,
+}
+
+impl Shortcut {
+    /// A shortcut running `command`
+    pub fn new(
+        name: impl Into<String>,
+        icon: impl Into<String>,
+        command: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            icon: icon.into(),
+            command: command.into(),
+        }
+    }
+
+    /// The shortcut for a desktop file ID like `firefox` or
+    /// `org.gnome.Nautilus.desktop`, searched in the XDG application dirs
+    pub fn from_desktop_id(id: &str) -> Option<Self> {
+        Self::from_desktop_id_in(id, &application_dirs())
+    }
+
+    /// Like [`Shortcut::from_desktop_id`], searching `dirs` instead
+    pub fn from_desktop_id_in(id: &str, dirs: &[PathBuf]) -> Option<Self> {
+        let file_name = if id.ends_with(".desktop") {
+            id.to_string()
+        } else {
+            format!("{}.desktop", id)
+        };
+        let path = dirs
+            .iter()
+            .map(|dir| dir.join(&file_name))
+            .find(|path| path.is_file())?;
+        let contents = std::fs::read_to_string(&path).ok()?;
+        let shortcut = parse_desktop_entry(&contents);
+        if shortcut.is_none() {
+            debug!(path = %path.display(), "Desktop file has no launchable entry");
+        }
+        shortcut
+    }
+}
+
+/// Widget with a row of app shortcuts
+pub struct LauncherWidget {
+    shortcuts: Vec<Shortcut>,
+    show_labels: bool,
+}
+
+impl LauncherWidget {
+    /// Create a launcher for `shortcuts`, left to right
+    pub fn new(shortcuts: Vec<Shortcut>) -> Self {
+        Self {
+            shortcuts,
+            show_labels: true,
+        }
+    }
+
+    /// Show app names under the icons
+    pub fn with_labels(mut self, show_labels: bool) -> Self {
+        self.show_labels = show_labels;
+        self
+    }
+
+    /// The shortcuts, left to right
+    pub fn shortcuts(&self) -> &[Shortcut] {
+        &self.shortcuts
+    }
+}
+
+impl Widget for LauncherWidget {
+    fn info(&self) -> WidgetInfo {
+        WidgetInfo {
+            id: "launcher",
+            name: "Launcher",
+            preferred_height: if self.show_labels { 80.0 } else { 64.0 },
+            min_height: 40.0,
+            expand: false,
+        }
+    }
+
+    fn update(&mut self) {}
+
+    fn content(&self) -> WidgetContent {
+        if self.shortcuts.is_empty() {
+            return WidgetContent::Text {
+                text: "No shortcuts".to_string(),
+                size: FontSize::Small,
+            };
+        }
+        WidgetContent::IconRow {
+            icons: self.shortcuts.iter().map(|s| s.icon.clone()).collect(),
+            labels: if self.show_labels {
+                self.shortcuts.iter().map(|s| s.name.clone()).collect()
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    fn update_interval(&self) -> Duration {
+        // Nothing changes on its own
+        Duration::from_secs(3600)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn on_click(&mut self, button: MouseButton, x: f32, _y: f32) -> Option<WidgetAction> {
+        if button != MouseButton::Left {
+            return None;
+        }
+        let slot = WidgetContent::icon_row_slot(x, self.shortcuts.len())?;
+        let shortcut = &self.shortcuts[slot];
+        info!(app = %shortcut.name, "Launching");
+        Some(WidgetAction::RunCommand(shortcut.command.clone()))
+    }
+}
+
+/// Name, icon and command from the `[Desktop Entry]` group of a desktop
+/// file; None for hidden entries and ones without a command
+fn parse_desktop_entry(contents: &str) -> Option<Shortcut> {
+    let mut in_entry = false;
+    let (mut name, mut icon, mut exec) = (None, None, None);
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "Name" => name = Some(value),
+            "Icon" => icon = Some(value),
+            "Exec" => exec = Some(value),
+            "Hidden" | "NoDisplay" if value == "true" => return None,
+            _ => {}
+        }
+    }
+
+    let command = strip_field_codes(&exec?);
+    (!command.is_empty()).then(|| Shortcut {
+        name: name.unwrap_or_else(|| command.clone()),
+        icon: icon.unwrap_or_default(),
+        command,
+    })
+}
+
+/// Drop the `%f`/`%U`-style placeholders from an `Exec` line, since apps
+/// are launched without files
+fn strip_field_codes(exec: &str) -> String {
+    exec.split_whitespace()
+        .filter(|arg| !(arg.len() == 2 && arg.starts_with('%') && *arg != "%%"))
+        .map(|arg| arg.replace("%%", "%"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `applications` directories from `$XDG_DATA_HOME` and `$XDG_DATA_DIRS`
+fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = dirs::data_dir()
+        .map(|d| d.join("applications"))
+        .into_iter()
+        .collect();
+
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(
+        data_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| Path::new(dir).join("applications")),
+    );
+    dirs
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+/// Factory for LauncherWidget
+pub struct LauncherWidgetFactory;
+
+impl DynWidgetFactory for LauncherWidgetFactory {
+    fn widget_type(&self) -> &'static str {
+        "launcher"
+    }
+
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Launcher",
+            "A row of app icons that launch on click",
+            "view-app-grid-symbolic",
+        )
+    }
+
+    fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
+        let show_labels = config
+            .get("show_labels")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let mut shortcuts = Vec::new();
+        let apps = config
+            .get("apps")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str());
+        for id in apps {
+            match Shortcut::from_desktop_id(id) {
+                Some(shortcut) => shortcuts.push(shortcut),
+                None => warn!(app = %id, "No desktop file found for launcher app"),
+            }
+        }
+
+        let commands = config
+            .get("commands")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_table());
+        for table in commands {
+            let Some(command) = table.get("command").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let name = table
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or(command);
+            let icon = table.get("icon").and_then(|v| v.as_str()).unwrap_or("");
+            shortcuts.push(Shortcut::new(name, icon, command));
+        }
+
+        info!(
+            shortcuts = shortcuts.len(),
+            show_labels = show_labels,
+            "Creating LauncherWidget"
+        );
+
+        Ok(Box::new(
+            LauncherWidget::new(shortcuts).with_labels(show_labels),
+        ))
+    }
+
+    fn default_config(&self) -> toml::Table {
+        let mut config = toml::Table::new();
+        config.insert(
+            "apps".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("firefox".to_string()),
+                toml::Value::String("com.system76.CosmicFiles".to_string()),
+                toml::Value::String("com.system76.CosmicTerm".to_string()),
+            ]),
+        );
+        config.insert("show_labels".to_string(), toml::Value::Boolean(true));
+        config
+    }
+
+    fn validate_config(&self, config: &toml::Table) -> Result<()> {
+        if let Some(apps) = config.get("apps") {
+            let apps = apps
+                .as_array()
+                .context("'apps' must be an array of desktop file IDs")?;
+            if apps.iter().any(|app| !app.is_str()) {
+                anyhow::bail!("'apps' must only contain strings");
+            }
+        }
+
+        if let Some(commands) = config.get("commands") {
+            let commands = commands
+                .as_array()
+                .context("'commands' must be an array of tables")?;
+            for (i, entry) in commands.iter().enumerate() {
+                let table = entry
+                    .as_table()
+                    .with_context(|| format!("'commands' entry {} must be a table", i + 1))?;
+                let has_command = table
+                    .get("command")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|command| !command.trim().is_empty());
+                if !has_command {
+                    anyhow::bail!("'commands' entry {} needs a 'command'", i + 1);
+                }
+                for key in ["name", "icon"] {
+                    if table.get(key).is_some_and(|v| !v.is_str()) {
+                        anyhow::bail!("'commands' entry {}: '{}' must be a string", i + 1, key);
+                    }
+                }
+            }
+        }
+
+        if let Some(show_labels) = config.get("show_labels") {
+            show_labels
+                .as_bool()
+                .context("'show_labels' must be a boolean")?;
+        }
+
+        Ok(())
+    }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_interactive()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(
+                ConfigField::new("apps", "Apps", FieldKind::List)
+                    .with_description("Desktop file IDs, e.g. firefox, org.gnome.Nautilus"),
+            )
+            .with_field(ConfigField::new(
+                "show_labels",
+                "Show names",
+                FieldKind::Bool,
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("org.example.Editor.desktop"),
+            "[Desktop Entry]\nType=Application\nName=Editor\nName[de]=Bearbeiter\n\
+             Icon=accessories-text-editor\nExec=example-editor --new-window %U\n\n\
+             [Desktop Action new]\nName=New\nExec=example-editor --other\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("hidden.desktop"),
+            "[Desktop Entry]\nName=Hidden\nExec=hidden\nNoDisplay=true\n",
+        )
+        .unwrap();
+        let dirs = vec![dir.path().to_path_buf()];
+
+        let shortcut = Shortcut::from_desktop_id_in("org.example.Editor", &dirs).unwrap();
+        assert_eq!(
+            shortcut,
+            Shortcut::new(
+                "Editor",
+                "accessories-text-editor",
+                "example-editor --new-window"
+            )
+        );
+        assert!(Shortcut::from_desktop_id_in("org.example.Editor.desktop", &dirs).is_some());
+        assert!(Shortcut::from_desktop_id_in("hidden", &dirs).is_none());
+        assert!(Shortcut::from_desktop_id_in("missing", &dirs).is_none());
+    }
+
+    #[test]
+    fn test_strip_field_codes() {
+        assert_eq!(strip_field_codes("app %F"), "app");
+        assert_eq!(strip_field_codes("app --rate=100%% %u"), "app --rate=100%");
+    }
+
+    #[test]
+    fn test_click_launches_slot() {
+        let mut widget = LauncherWidget::new(vec![
+            Shortcut::new("One", "", "one"),
+            Shortcut::new("Two", "", "two"),
+        ]);
+        assert_eq!(
+            widget.on_click(MouseButton::Left, 0.75, 0.5),
+            Some(WidgetAction::RunCommand("two".to_string()))
+        );
+        assert_eq!(
+            widget.on_click(MouseButton::Left, 0.1, 0.5),
+            Some(WidgetAction::RunCommand("one".to_string()))
+        );
+        assert_eq!(widget.on_click(MouseButton::Right, 0.1, 0.5), None);
+    }
+
+    #[test]
+    fn test_factory_validation() {
+        let factory = LauncherWidgetFactory;
+        assert!(factory.validate_config(&factory.default_config()).is_ok());
+
+        let config: toml::Table = toml::from_str(
+            r#"commands = [{ name = "Top", icon = "utilities-system-monitor", command = "htop" }]"#,
+        )
+        .unwrap();
+        assert!(factory.validate_config(&config).is_ok());
+
+        let config: toml::Table = toml::from_str(r#"commands = [{ name = "Nothing" }]"#).unwrap();
+        assert!(factory.validate_config(&config).is_err());
+    }
+}
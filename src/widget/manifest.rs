@@ -0,0 +1,413 @@
+//! Widget manifests discovered from the config directory
+//!
+//! Users can add widgets without touching the main config by dropping a
+//! manifest into `~/.config/cosmic-desktop-widget/widgets/`:
+//!
+//! ```toml
+//! # ~/.config/cosmic-desktop-widget/widgets/uptime.toml
+//! name = "Uptime"
+//! type = "uptime"
+//! description = "System uptime"
+//! icon = "system-run"
+//! exec = "uptime -p"
+//!
+//! [config]
+//! interval = 60
+//! template = "Up {value}"
+//! ```
+//!
+//! Each manifest becomes a new widget type in the [`WidgetRegistry`](super::WidgetRegistry).
+//! Instances are backed by the [`CustomWidget`](super::CustomWidget) command
+//! source, with the manifest's `[config]` table as defaults that the user's
+//! per-instance config can override.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use super::custom::{expand_home, CustomWidgetFactory};
+use super::registry::DynWidgetFactory;
+use super::schema::{ConfigSchema, WidgetMetadata};
+use super::traits::Widget;
+
+/// A widget definition loaded from a manifest file
+#[derive(Debug, Clone, Deserialize)]
+pub struct WidgetManifest {
+    /// Human-readable name shown in the config GUI
+    pub name: String,
+
+    /// Widget type identifier used in `[[widgets]]` entries
+    #[serde(rename = "type")]
+    pub widget_type: String,
+
+    /// Short description
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Icon name or path
+    #[serde(default)]
+    pub icon: Option<String>,
+
+    /// Command line to run for the widget's value
+    #[serde(default)]
+    pub exec: Option<String>,
+
+    /// Path to an executable plugin script (relative to the manifest)
+    #[serde(default)]
+    pub plugin: Option<PathBuf>,
+
+    /// Default widget configuration
+    #[serde(default)]
+    pub config: toml::Table,
+
+    /// File the manifest was loaded from
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl WidgetManifest {
+    /// Directory scanned for manifests
+    pub fn manifest_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("cosmic-desktop-widget").join("widgets"))
+    }
+
+    /// Load and validate a single manifest file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read manifest: {}", path.display()))?;
+
+        let mut manifest: Self = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse manifest: {}", path.display()))?;
+        manifest.path = path.to_path_buf();
+
+        if manifest.widget_type.trim().is_empty() {
+            bail!("'type' must not be empty");
+        }
+        if manifest.exec.is_none() && manifest.plugin.is_none() {
+            bail!("manifest must set either 'exec' or 'plugin'");
+        }
+
+        Ok(manifest)
+    }
+
+    /// Load every `*.toml` manifest in `dir`, skipping invalid ones
+    pub fn discover(dir: &Path) -> Vec<Self> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                debug!(dir = %dir.display(), error = %e, "No widget manifest directory");
+                return Vec::new();
+            }
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("toml"))
+            .collect();
+        paths.sort();
+
+        paths
+            .iter()
+            .filter_map(|path| match Self::load(path) {
+                Ok(manifest) => Some(manifest),
+                Err(e) => {
+                    warn!(path = %path.display(), error = %e, "Skipping invalid widget manifest");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Command line the widget runs, resolving `plugin` relative to the manifest
+    pub fn command(&self) -> String {
+        if let Some(exec) = &self.exec {
+            return exec.clone();
+        }
+
+        let plugin = self.plugin.clone().unwrap_or_default();
+        self.resolve(plugin).display().to_string()
+    }
+
+    /// Script file backing this widget, if any
+    ///
+    /// This is the resolved `plugin` path, or the program of `exec` when it
+    /// names a file rather than something looked up in `$PATH`.
+    pub fn script_path(&self) -> Option<PathBuf> {
+        if let Some(plugin) = &self.plugin {
+            return Some(self.resolve(plugin.clone()));
+        }
+
+        let program = self.exec.as_deref()?.split_whitespace().next()?;
+        if program.contains('/') {
+            Some(self.resolve(expand_home(program)))
+        } else {
+            None
+        }
+    }
+
+    /// Whether a change to `path` affects this widget
+    pub fn is_affected_by(&self, path: &Path) -> bool {
+        self.path == path || self.script_path().as_deref() == Some(path)
+    }
+
+    fn resolve(&self, path: PathBuf) -> PathBuf {
+        if path.is_relative() {
+            self.path
+                .parent()
+                .map(|dir| dir.join(&path))
+                .unwrap_or(path)
+        } else {
+            path
+        }
+    }
+
+    /// Manifest defaults merged with the command source settings
+    fn base_config(&self) -> toml::Table {
+        let mut config = self.config.clone();
+        config.insert(
+            "source".to_string(),
+            toml::Value::String("command".to_string()),
+        );
+        config.insert("command".to_string(), toml::Value::String(self.command()));
+        config
+    }
+
+    /// Merge an instance's config over the manifest defaults
+    fn merged_config(&self, overrides: &toml::Table) -> toml::Table {
+        let mut config = self.base_config();
+        for (key, value) in overrides {
+            config.insert(key.clone(), value.clone());
+        }
+        config
+    }
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+/// Factory exposing a [`WidgetManifest`] as a registry widget type
+pub struct ManifestWidgetFactory {
+    manifest: WidgetManifest,
+    widget_type: &'static str,
+}
+
+impl ManifestWidgetFactory {
+    /// Wrap a manifest in a factory
+    pub fn new(manifest: WidgetManifest) -> Self {
+        let widget_type = intern_widget_type(&manifest.widget_type);
+        Self {
+            manifest,
+            widget_type,
+        }
+    }
+
+    /// The manifest backing this factory
+    pub fn manifest(&self) -> &WidgetManifest {
+        &self.manifest
+    }
+}
+
+/// Registry keys are `&'static str`. Manifests are reloaded whenever a plugin
+/// changes, so type names are leaked once and reused rather than on every load.
+fn intern_widget_type(name: &str) -> &'static str {
+    static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    let mut names = NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(&existing) = names.get(name) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(name.to_string().into_boxed_str());
+    names.insert(leaked);
+    leaked
+}
+
+impl DynWidgetFactory for ManifestWidgetFactory {
+    fn widget_type(&self) -> &'static str {
+        self.widget_type
+    }
+
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            &self.manifest.name,
+            self.manifest.description.as_deref().unwrap_or(""),
+            self.manifest
+                .icon
+                .as_deref()
+                .unwrap_or(WidgetMetadata::DEFAULT_ICON),
+        )
+    }
+
+    fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
+        debug!(
+            widget_type = %self.widget_type,
+            manifest = %self.manifest.path.display(),
+            "Creating widget from manifest"
+        );
+        CustomWidgetFactory.create(&self.manifest.merged_config(config))
+    }
+
+    fn default_config(&self) -> toml::Table {
+        self.manifest.config.clone()
+    }
+
+    fn validate_config(&self, config: &toml::Table) -> Result<()> {
+        CustomWidgetFactory.validate_config(&self.manifest.merged_config(config))
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        // The source is fixed by the manifest; only polling and display are tunable
+        CustomWidgetFactory
+            .config_schema()
+            .retain(|field| matches!(field.key, "interval" | "template"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_manifest() {
+        let dir = TempDir::new().unwrap();
+        let path = write(
+            dir.path(),
+            "uptime.toml",
+            r#"
+name = "Uptime"
+type = "uptime"
+exec = "uptime -p"
+
+[config]
+interval = 60
+"#,
+        );
+
+        let manifest = WidgetManifest::load(&path).unwrap();
+        assert_eq!(manifest.name, "Uptime");
+        assert_eq!(manifest.widget_type, "uptime");
+        assert_eq!(manifest.command(), "uptime -p");
+        assert_eq!(
+            manifest.config.get("interval").and_then(|v| v.as_integer()),
+            Some(60)
+        );
+    }
+
+    #[test]
+    fn test_manifest_requires_exec_or_plugin() {
+        let dir = TempDir::new().unwrap();
+        let path = write(dir.path(), "bad.toml", "name = \"Bad\"\ntype = \"bad\"\n");
+        assert!(WidgetManifest::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_plugin_path_relative_to_manifest() {
+        let dir = TempDir::new().unwrap();
+        let path = write(
+            dir.path(),
+            "script.toml",
+            "name = \"Script\"\ntype = \"script\"\nplugin = \"script.sh\"\n",
+        );
+        let manifest = WidgetManifest::load(&path).unwrap();
+        assert_eq!(
+            manifest.command(),
+            dir.path().join("script.sh").display().to_string()
+        );
+    }
+
+    #[test]
+    fn test_script_path_and_affected_paths() {
+        let dir = TempDir::new().unwrap();
+        let plugin = write(
+            dir.path(),
+            "script.toml",
+            "name = \"Script\"\ntype = \"script\"\nplugin = \"script.sh\"\n",
+        );
+        let manifest = WidgetManifest::load(&plugin).unwrap();
+        let script = dir.path().join("script.sh");
+        assert_eq!(manifest.script_path(), Some(script.clone()));
+        assert!(manifest.is_affected_by(&script));
+        assert!(manifest.is_affected_by(&plugin));
+        assert!(!manifest.is_affected_by(&dir.path().join("other.sh")));
+
+        let exec = write(
+            dir.path(),
+            "exec.toml",
+            "name = \"Exec\"\ntype = \"exec\"\nexec = \"/usr/local/bin/status --short\"\n",
+        );
+        let manifest = WidgetManifest::load(&exec).unwrap();
+        assert_eq!(
+            manifest.script_path(),
+            Some(PathBuf::from("/usr/local/bin/status"))
+        );
+
+        let path_lookup = write(
+            dir.path(),
+            "uptime.toml",
+            "name = \"Uptime\"\ntype = \"uptime\"\nexec = \"uptime -p\"\n",
+        );
+        assert_eq!(
+            WidgetManifest::load(&path_lookup).unwrap().script_path(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_discover_skips_invalid() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "a.toml",
+            "name = \"A\"\ntype = \"a\"\nexec = \"echo a\"\n",
+        );
+        write(dir.path(), "broken.toml", "this is not toml = = =");
+        write(dir.path(), "notes.txt", "ignored");
+
+        let manifests = WidgetManifest::discover(dir.path());
+        assert_eq!(manifests.len(), 1);
+        assert_eq!(manifests[0].widget_type, "a");
+    }
+
+    #[test]
+    fn test_factory_merges_config() {
+        let dir = TempDir::new().unwrap();
+        let path = write(
+            dir.path(),
+            "echo.toml",
+            "name = \"Echo\"\ntype = \"echo\"\nexec = \"echo hi\"\n[config]\ninterval = 5\n",
+        );
+        let factory = ManifestWidgetFactory::new(WidgetManifest::load(&path).unwrap());
+        assert_eq!(factory.widget_type(), "echo");
+
+        let mut overrides = toml::Table::new();
+        overrides.insert("interval".to_string(), toml::Value::Integer(0));
+        assert!(factory.validate_config(&overrides).is_err());
+        assert!(factory.validate_config(&toml::Table::new()).is_ok());
+
+        let widget = factory.create(&toml::Table::new()).unwrap();
+        assert_eq!(widget.info().id, "custom");
+
+        let schema = factory.config_schema();
+        assert!(schema.field("interval").is_some());
+        assert!(schema.field("command").is_none());
+
+        // Reloading the manifest reuses the interned type name
+        let reloaded = ManifestWidgetFactory::new(WidgetManifest::load(&path).unwrap());
+        assert!(std::ptr::eq(reloaded.widget_type(), factory.widget_type()));
+    }
+}
@@ -10,12 +10,21 @@
 //! - [`SystemMonitorWidget`] - CPU, RAM, and disk usage (requires `sysinfo` feature)
 //! - [`CountdownWidget`] - Countdown to a target date/time
 //! - [`CryptoWidget`] - Cryptocurrency prices from CoinGecko API
-//! - [`CalendarWidget`] - Upcoming events from ICS calendar files
+//! - [`CalendarWidget`] - Upcoming events or a month grid from ICS calendar files
+//! - [`CarouselWidget`] - Cycles through several child widgets in one surface
 //! - [`PomodoroWidget`] - Pomodoro timer with work/break cycles
 //! - [`QuotesWidget`] - Inspirational quotes display
 //! - [`BatteryWidget`] - Battery status, percentage, and time remaining
 //! - [`StocksWidget`] - Real-time stock prices from Yahoo Finance
 //! - [`NewsWidget`] - News headlines from RSS feeds with rotation
+//! - [`NotificationsWidget`] - The latest desktop notification, shown inline
+//! - [`DndWidget`] - Do Not Disturb toggle with optional focus hours
+//! - [`LauncherWidget`] - A row of app shortcuts that launch on click
+//! - [`NightLightWidget`] - Night light status and temperature, click to toggle
+//! - [`PowerProfileWidget`] - Active power profile, click to switch
+//! - [`VpnWidget`] - A NetworkManager VPN connection with its IP, click to toggle
+//! - [`CustomWidget`] - Declarative widget fed by a command, file, URL or D-Bus property
+//! - [`PerfWidget`] - Debug overlay with render times, memory and cache hit rates
 //!
 //! # Creating Custom Widgets
 //!
@@ -26,44 +35,82 @@
 //!
 //! The [`registry`] module provides dynamic widget creation from configuration.
 //! Use [`WidgetRegistry::with_builtins()`](registry::WidgetRegistry::with_builtins)
-//! to get a registry with all built-in widgets registered.
+//! to get a registry with all built-in widgets registered. Each factory also
+//! declares its [`WidgetCapabilities`] and a [`ConfigSchema`] describing its settings.
 
 pub mod registry;
+pub mod schema;
 pub mod traits;
 
 // New widgets
 pub mod battery;
 pub mod calendar;
+pub mod carousel;
 pub mod countdown;
 pub mod crypto;
+pub mod custom;
+pub mod dnd;
+pub mod google_calendar;
+pub mod guard;
+pub mod launcher;
+pub mod manifest;
 pub mod mpris;
 pub mod news;
+pub mod night_light;
+pub mod notifications;
+pub mod perf;
 pub mod pomodoro;
+pub mod power_profile;
+pub mod price_alert;
+pub mod quote_provider;
 pub mod quotes;
+pub mod recurrence;
+pub mod replay;
 pub mod stocks;
 pub mod system_monitor;
+pub mod vpn;
 
 pub use battery::BatteryWidget;
-pub use calendar::CalendarWidget;
+pub use calendar::{CalendarView, CalendarWidget};
+pub use carousel::CarouselWidget;
 pub use countdown::CountdownWidget;
-pub use crypto::{CryptoPrice, CryptoWidget};
+pub use crypto::{CryptoPrice, CryptoWidget, Holding, PortfolioValue};
+pub use custom::{CustomWidget, DataSource};
+pub use dnd::{DndWidget, FocusHours};
+pub use google_calendar::{GoogleCredentials, GoogleToken};
+pub use guard::{FailedWidget, GuardedWidget};
+pub use launcher::{LauncherWidget, Shortcut};
+pub use manifest::WidgetManifest;
 pub use mpris::{MprisConfig, MprisWidget};
-pub use news::{Headline, NewsWidget};
+pub use news::{Feed, Headline, NewsWidget};
+pub use night_light::{NightLightDaemon, NightLightStatus, NightLightWidget};
+pub use notifications::{NotificationsWidget, ReceivedNotification};
+pub use perf::PerfWidget;
 pub use pomodoro::{PomodoroState, PomodoroWidget};
+pub use power_profile::{PowerProfileWidget, PowerProfiles};
+pub use price_alert::{PriceAlert, PriceAlerts};
+pub use quote_provider::{QuoteProvider, QuoteProviders};
 pub use quotes::{Quote, QuotesWidget};
 pub use registry::{DynWidgetFactory, WidgetInstance, WidgetRegistry};
-pub use stocks::{StockData, StocksWidget};
+pub use replay::{RecordedFrame, Recorder, Recording, ReplayWidget};
+pub use schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+pub use stocks::{ExtendedHours, Session, StockData, StocksWidget};
 pub use system_monitor::SystemMonitorWidget;
+pub use vpn::{VpnState, VpnStatus, VpnWidget};
 pub use traits::{
-    FontSize, MouseButton, ProgressBar, ProgressColor, ScrollDirection, TextSegment, Widget,
-    WidgetAction, WidgetConfig, WidgetContent, WidgetFactory, WidgetInfo,
+    ChartSeries, FetchReport, FontSize, Freshness, GridCell, MediaControl, MouseButton, ProgressBar, ProgressColor, ScrollDirection, SoundCue, TextSegment,
+    Widget, WidgetAction, WidgetConfig, WidgetContent, WidgetFactory, WidgetInfo,
 };
 
-use crate::error::{WeatherError, WeatherResult};
-use chrono::Local;
+use crate::template::{Template, TemplateVars};
+use crate::update::tasks::{self, TaskOutput};
+use crate::update::Source;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::time::Duration;
-use tracing::{debug, info, warn};
+use tracing::{debug, warn};
 
 /// Clock widget displaying current time
 ///
@@ -74,8 +121,10 @@ use tracing::{debug, info, warn};
 pub struct ClockWidget {
     /// Cached formatted time string
     current_time: String,
-    /// Cached date string (updated at midnight)
+    /// Cached date line (updated when the date changes)
     current_date: String,
+    /// Date the date line was formatted for
+    last_date: NaiveDate,
     /// Last update timestamp
     last_update: std::time::Instant,
     /// Last second value (0-59) to detect changes
@@ -86,51 +135,199 @@ pub struct ClockWidget {
     show_seconds: bool,
     /// Whether to show date
     show_date: bool,
+    /// Whether the date line includes the ISO week number
+    show_week: bool,
+    /// Whether the date line includes the day of the year
+    show_day_of_year: bool,
     /// Whether content changed on last update
     changed: bool,
+    /// Custom format template (replaces the 12h/24h presets when set)
+    template: Option<Template>,
+    /// chrono strftime format (replaces both the presets and the template)
+    custom_format: Option<String>,
+    /// Timezone to show the time in, instead of the local one
+    timezone: Option<Tz>,
+    /// Secondary line under the time, e.g. the region of the timezone
+    label: Option<String>,
+    /// Whether to chime at the top of each hour
+    hourly_chime: bool,
+    /// Hour of the last update, to detect the hour changing
+    last_hour: u32,
+    /// Chime waiting to be played
+    sound_cue: Option<SoundCue>,
+    /// What the widget shows, rebuilt when the text changes
+    content: WidgetContent,
 }
 
 impl ClockWidget {
+    /// Placeholders available to a clock `format` template
+    pub const TEMPLATE_PLACEHOLDERS: &'static [&'static str] = &[
+        "time", "hour", "hour12", "minute", "second", "ampm", "weekday", "day", "month",
+        "month_num", "year", "date",
+    ];
+
     pub fn new(format: &str, show_seconds: bool, show_date: bool) -> Self {
         use chrono::Timelike;
         let format_str = format.to_string();
-        let now = Local::now();
-        Self {
-            current_time: Self::format_time_internal(&format_str, show_seconds),
+        let now = Local::now().fixed_offset();
+        let mut clock = Self {
+            current_time: Self::format_time_internal(&format_str, &now, show_seconds),
             current_date: now.format("%A, %B %d, %Y").to_string(),
+            last_date: now.date_naive(),
             last_update: std::time::Instant::now(),
             last_second: now.second(),
             format: format_str,
             show_seconds,
             show_date,
+            show_week: false,
+            show_day_of_year: false,
             changed: true, // First frame is always "changed"
+            template: None,
+            custom_format: None,
+            timezone: None,
+            label: None,
+            hourly_chime: false,
+            last_hour: now.hour(),
+            sound_cue: None,
+            content: WidgetContent::Empty,
+        };
+        clock.refresh_content();
+        clock
+    }
+
+    /// Play the `"hour"` sound event at the top of each hour
+    pub fn with_hourly_chime(mut self, enabled: bool) -> Self {
+        self.hourly_chime = enabled;
+        self
+    }
+
+    /// Render the time through a custom format template
+    pub fn with_template(mut self, template: Template) -> Self {
+        self.template = Some(template);
+        self.current_time = self.format_now(&self.now());
+        self.refresh_content();
+        self
+    }
+
+    /// Show the time in `timezone` instead of the local time
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        use chrono::Timelike;
+        self.timezone = Some(timezone);
+        let now = self.now();
+        self.current_time = self.format_now(&now);
+        self.current_date = self.format_date(&now);
+        self.last_date = now.date_naive();
+        self.last_hour = now.hour();
+        self.refresh_content();
+        self
+    }
+
+    /// Add the ISO week number to the date line
+    pub fn with_week_number(mut self, enabled: bool) -> Self {
+        self.show_week = enabled;
+        self.current_date = self.format_date(&self.now());
+        self.refresh_content();
+        self
+    }
+
+    /// Add the day of the year to the date line
+    pub fn with_day_of_year(mut self, enabled: bool) -> Self {
+        self.show_day_of_year = enabled;
+        self.current_date = self.format_date(&self.now());
+        self.refresh_content();
+        self
+    }
+
+    /// Show `label` on a second line under the time
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self.refresh_content();
+        self
+    }
+
+    /// Look up an IANA timezone such as `"America/New_York"`
+    pub fn parse_timezone(name: &str) -> anyhow::Result<Tz> {
+        name.parse()
+            .map_err(|e| anyhow::anyhow!("Unknown timezone '{}': {}", name, e))
+    }
+
+    /// Place name of a timezone, for labelling it: `"America/New_York"`
+    /// becomes `"New York"`
+    pub fn timezone_label(timezone: Tz) -> String {
+        let name = timezone.name();
+        name.rsplit('/').next().unwrap_or(name).replace('_', " ")
+    }
+
+    /// The label shown under the time, if any
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Render the time with a chrono strftime format such as
+    /// `"%a %d %b  %H:%M"`
+    ///
+    /// The format must have passed [`Self::check_custom_format`]; chrono
+    /// panics while formatting an invalid one.
+    pub fn with_custom_format(mut self, format: &str) -> Self {
+        self.custom_format = Some(format.to_string());
+        self.current_time = self.format_now(&self.now());
+        self.refresh_content();
+        self
+    }
+
+    /// Check that `format` is a usable strftime format
+    pub fn check_custom_format(format: &str) -> anyhow::Result<()> {
+        use chrono::format::{Item, StrftimeItems};
+        if format.trim().is_empty() {
+            anyhow::bail!("Format is empty");
+        }
+        if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+            anyhow::bail!("Invalid strftime format '{}'", format);
         }
+        Ok(())
     }
 
     /// Update the clock if the second has changed
     pub fn update(&mut self) {
         use chrono::Timelike;
-        let now = Local::now();
+        let now = self.now();
         let current_second = now.second();
 
         // Only update if the second changed
         if current_second != self.last_second {
-            self.current_time = Self::format_time_internal(&self.format, self.show_seconds);
+            self.current_time = self.format_now(&now);
             self.last_second = current_second;
             self.last_update = std::time::Instant::now();
             self.changed = true;
 
-            // Update date at midnight
-            if now.hour() == 0 && now.minute() == 0 && current_second == 0 {
-                self.current_date = now.format("%A, %B %d, %Y").to_string();
+            // Compared by date, so waking from a suspend at the same hour
+            // on a later day still moves the date on
+            if now.date_naive() != self.last_date {
+                self.current_date = self.format_date(&now);
+                self.last_date = now.date_naive();
             }
 
+            self.check_hour(now.hour(), now.minute());
+            self.refresh_content();
+
             debug!(time = %self.current_time, "Clock updated");
         } else {
             self.changed = false;
         }
     }
 
+    /// Queue the chime when the hour has changed
+    fn check_hour(&mut self, hour: u32, minute: u32) {
+        if hour == self.last_hour {
+            return;
+        }
+        self.last_hour = hour;
+        // Only on the hour, not when waking from suspend at half past
+        if self.hourly_chime && minute == 0 {
+            self.sound_cue = Some(SoundCue::Event("hour"));
+        }
+    }
+
     /// Get the current time string (clones the cached value)
     #[inline]
     pub fn time_string(&self) -> String {
@@ -163,14 +360,67 @@ impl ClockWidget {
         }
     }
 
+    /// Rebuild the content from the time, date and label
+    fn refresh_content(&mut self) {
+        let mut lines = vec![(self.time_string(), FontSize::Large)];
+        if self.show_date {
+            lines.push((self.date_string(), FontSize::Small));
+        }
+        if let Some(label) = &self.label {
+            lines.push((label.clone(), FontSize::Small));
+        }
+
+        self.content = if lines.len() == 1 {
+            WidgetContent::Text {
+                text: self.time_string(),
+                size: FontSize::Large,
+            }
+        } else {
+            WidgetContent::MultiLine { lines }
+        };
+    }
+
     /// Check if the clock display changed on the last update
     #[inline]
     pub fn has_changed(&self) -> bool {
         self.changed
     }
 
-    fn format_time_internal(format: &str, show_seconds: bool) -> String {
-        let now = Local::now();
+    /// The current time in the clock's timezone
+    fn now(&self) -> DateTime<FixedOffset> {
+        match self.timezone {
+            Some(timezone) => Utc::now().with_timezone(&timezone).fixed_offset(),
+            None => Local::now().fixed_offset(),
+        }
+    }
+
+    /// The time text for `now`, in whichever format is configured
+    fn format_now(&self, now: &DateTime<FixedOffset>) -> String {
+        match (&self.custom_format, &self.template) {
+            (Some(format), _) => now.format(format).to_string(),
+            (None, Some(template)) => Self::render_template(template, now, self.show_seconds),
+            (None, None) => Self::format_time_internal(&self.format, now, self.show_seconds),
+        }
+    }
+
+    /// The date line for `now`, with the week and day numbers if enabled
+    fn format_date(&self, now: &DateTime<FixedOffset>) -> String {
+        use chrono::Datelike;
+        let mut date = now.format("%A, %B %d, %Y").to_string();
+        if self.show_week {
+            date.push_str(&format!(" · Week {}", now.iso_week().week()));
+        }
+        if self.show_day_of_year {
+            date.push_str(&format!(" · Day {}", now.ordinal()));
+        }
+        date
+    }
+
+    fn format_time_internal(
+        format: &str,
+        now: &DateTime<FixedOffset>,
+        show_seconds: bool,
+    ) -> String {
         match (format, show_seconds) {
             ("12h", true) => now.format("%I:%M:%S %p").to_string(),
             ("12h", false) => now.format("%I:%M %p").to_string(),
@@ -178,6 +428,35 @@ impl ClockWidget {
             ("24h", false) | (_, false) => now.format("%H:%M").to_string(),
         }
     }
+
+    fn render_template(
+        template: &Template,
+        now: &DateTime<FixedOffset>,
+        show_seconds: bool,
+    ) -> String {
+        use chrono::{Datelike, Timelike};
+        let time = if show_seconds {
+            now.format("%H:%M:%S")
+        } else {
+            now.format("%H:%M")
+        };
+
+        let vars = TemplateVars::new()
+            .with("time", time.to_string())
+            .with("hour", now.hour())
+            .with("hour12", now.hour12().1)
+            .with("minute", now.minute())
+            .with("second", now.second())
+            .with("ampm", now.format("%p").to_string())
+            .with("weekday", now.format("%A").to_string())
+            .with("day", now.day())
+            .with("month", now.format("%B").to_string())
+            .with("month_num", now.month())
+            .with("year", now.year() as i64)
+            .with("date", now.format("%Y-%m-%d").to_string());
+
+        template.render(&vars)
+    }
 }
 
 impl Default for ClockWidget {
@@ -204,15 +483,38 @@ impl Widget for ClockWidget {
     }
 
     fn content(&self) -> WidgetContent {
-        WidgetContent::Text {
-            text: self.time_string(),
-            size: FontSize::Large,
-        }
+        self.content.clone()
+    }
+
+    fn content_ref(&self) -> Cow<'_, WidgetContent> {
+        Cow::Borrowed(&self.content)
     }
 
     fn update_interval(&self) -> Duration {
         Duration::from_secs(1)
     }
+
+    fn take_sound_cue(&mut self) -> Option<SoundCue> {
+        self.sound_cue.take()
+    }
+
+    fn glyph_set(&self) -> Option<String> {
+        let mut glyphs = String::from("0123456789:");
+        if self.format == "12h" {
+            glyphs.push_str(" AMP");
+        }
+        // Templates add their own literal text and names; repeats are cheap hits
+        if self.template.is_some() || self.custom_format.is_some() {
+            glyphs.push_str(&self.current_time);
+        }
+        if self.show_date {
+            glyphs.push_str(&self.current_date);
+        }
+        if let Some(label) = &self.label {
+            glyphs.push_str(label);
+        }
+        Some(glyphs)
+    }
 }
 
 /// Weather widget displaying current weather conditions
@@ -224,6 +526,9 @@ pub struct WeatherWidget {
     update_interval: std::time::Duration,
     temperature_unit: String,
     error_message: Option<String>,
+    template: Option<Template>,
+    /// Show only the temperature (collapsed)
+    compact: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -244,9 +549,21 @@ impl WeatherWidget {
             update_interval: std::time::Duration::from_secs(update_interval),
             temperature_unit: temperature_unit.to_string(),
             error_message: None,
+            template: None,
+            compact: false,
         }
     }
 
+    /// Placeholders available to a weather `format` template
+    pub const TEMPLATE_PLACEHOLDERS: &'static [&'static str] =
+        &["temp", "unit", "condition", "humidity", "wind", "city"];
+
+    /// Render the weather line through a custom format template
+    pub fn with_template(mut self, template: Template) -> Self {
+        self.template = Some(template);
+        self
+    }
+
     /// Map weather condition to icon name
     pub fn condition_to_icon(condition: &str) -> &'static str {
         match condition.to_lowercase().as_str() {
@@ -287,20 +604,14 @@ impl WeatherWidget {
     pub fn display_string(&self) -> Option<String> {
         // If there's an error and no data, show error
         if self.data.is_none() && self.error_message.is_some() {
-            return self.error_message.as_ref().map(|e| format!("Error: {}", e));
+            return self
+                .error_message
+                .as_ref()
+                .map(|e| crate::fl!("error", message = e.as_str()));
         }
 
         self.data.as_ref().map(|data| {
-            let (temp, unit) = match self.temperature_unit.as_str() {
-                "fahrenheit" => ((data.temperature * 9.0 / 5.0) + 32.0, "°F"),
-                _ => (data.temperature, "°C"), // Default to celsius
-            };
-
-            // Check if data is stale (older than 2x update interval)
-            let stale_threshold = self.update_interval * 2;
-            let is_stale = self.last_update.elapsed() > stale_threshold;
-
-            let stale_indicator = if is_stale { " (stale)" } else { "" };
+            let (temp, unit) = self.temperature(data);
 
             // Show error indicator if there's an error but we have old data
             let error_indicator = if self.error_message.is_some() {
@@ -309,85 +620,35 @@ impl WeatherWidget {
                 ""
             };
 
-            format!(
-                "{}{} {} | {}% humidity{}{}",
-                temp.round(),
-                unit,
-                data.condition,
-                data.humidity,
-                stale_indicator,
-                error_indicator
-            )
+            let text = match &self.template {
+                Some(template) => template.render(
+                    &TemplateVars::new()
+                        .with("temp", temp)
+                        .with("unit", unit.trim_start_matches('°'))
+                        .with("condition", data.condition.as_str())
+                        .with("humidity", data.humidity)
+                        .with("wind", data.wind_speed)
+                        .with("city", self.city.as_str()),
+                ),
+                None => format!(
+                    "{}{} {} | {}",
+                    temp.round(),
+                    unit,
+                    data.condition,
+                    crate::fl!("weather-humidity", humidity = data.humidity)
+                ),
+            };
+
+            format!("{}{}", text, error_indicator)
         })
     }
 
-    pub async fn fetch_weather(&mut self) -> WeatherResult<()> {
-        // Validate API key is configured
-        if self.api_key.is_empty() {
-            warn!("Weather API key not configured");
-            return Err(WeatherError::NoApiKey);
-        }
-
-        info!(city = %self.city, "Fetching weather from API");
-
-        let url = format!(
-            "https://api.openweathermap.org/data/2.5/weather?q={}&appid={}&units=metric",
-            self.city, self.api_key
-        );
-
-        let response = reqwest::get(&url).await.map_err(|e| {
-            warn!(error = %e, city = %self.city, "Failed to fetch weather from API");
-            e
-        })?;
-
-        // Check if the response indicates city not found
-        if !response.status().is_success() {
-            warn!(city = %self.city, status = %response.status(), "Weather API returned error status");
-            return Err(WeatherError::CityNotFound(self.city.clone()));
+    /// Temperature in the configured unit, with the unit's symbol
+    fn temperature(&self, data: &WeatherData) -> (f32, &'static str) {
+        match self.temperature_unit.as_str() {
+            "fahrenheit" => ((data.temperature * 9.0 / 5.0) + 32.0, "°F"),
+            _ => (data.temperature, "°C"), // Default to celsius
         }
-
-        let json: serde_json::Value = response.json().await.map_err(|e| {
-            warn!(error = %e, "Failed to parse weather API response");
-            e
-        })?;
-
-        // Parse the response with better error handling
-        let temperature = json["main"]["temp"]
-            .as_f64()
-            .ok_or_else(|| WeatherError::ParseError("temperature".to_string()))?
-            as f32;
-
-        let condition = json["weather"][0]["main"]
-            .as_str()
-            .ok_or_else(|| WeatherError::ParseError("condition".to_string()))?
-            .to_string();
-
-        let humidity = json["main"]["humidity"]
-            .as_u64()
-            .ok_or_else(|| WeatherError::ParseError("humidity".to_string()))?
-            as u32;
-
-        let wind_speed = json["wind"]["speed"]
-            .as_f64()
-            .ok_or_else(|| WeatherError::ParseError("wind_speed".to_string()))?
-            as f32;
-
-        self.data = Some(WeatherData {
-            temperature,
-            condition: condition.clone(),
-            humidity,
-            wind_speed,
-        });
-
-        self.last_update = std::time::Instant::now();
-
-        info!(
-            temperature = %temperature,
-            condition = %condition,
-            "Weather API fetch successful"
-        );
-
-        Ok(())
     }
 }
 
@@ -410,6 +671,14 @@ impl Widget for WeatherWidget {
 
     fn content(&self) -> WidgetContent {
         match (&self.data, self.display_string()) {
+            (Some(data), Some(_)) if self.compact => {
+                let (temp, unit) = self.temperature(data);
+                WidgetContent::IconText {
+                    icon: Self::condition_to_icon(&data.condition).to_string(),
+                    text: format!("{}{}", temp.round(), unit),
+                    size: FontSize::Medium,
+                }
+            }
             (Some(data), Some(text)) => {
                 let icon = Self::condition_to_icon(&data.condition);
                 WidgetContent::IconText {
@@ -418,19 +687,15 @@ impl Widget for WeatherWidget {
                     size: FontSize::Medium,
                 }
             }
-            (None, Some(text)) => {
-                // Error case - no icon, just text
-                WidgetContent::Text {
-                    text,
-                    size: FontSize::Medium,
-                }
+            (None, Some(_)) => {
+                WidgetContent::error(self.error_message.as_deref().unwrap_or_default())
             }
             _ => {
                 // No data and no error - show waiting message
                 let msg = if self.api_key.is_empty() {
-                    "Weather: No API key".to_string()
+                    crate::fl!("weather-no-api-key")
                 } else {
-                    "Weather: Loading...".to_string()
+                    crate::fl!("weather-loading")
                 };
                 WidgetContent::Text {
                     text: msg,
@@ -451,11 +716,45 @@ impl Widget for WeatherWidget {
     fn error(&self) -> Option<&str> {
         self.error_message.as_deref()
     }
+
+    fn last_success(&self) -> Option<std::time::Instant> {
+        self.data.as_ref().map(|_| self.last_update)
+    }
+
+    fn data_source(&self) -> Option<Source> {
+        // Nothing to fetch without a key; content already says so
+        if self.api_key.is_empty() {
+            return None;
+        }
+        let city = self.city.clone();
+        let api_key = self.api_key.clone();
+        Some(Source::new(self.update_interval, move || {
+            let city = city.clone();
+            let api_key = api_key.clone();
+            tasks::task(async move {
+                crate::weather::fetch_weather_data(&city, &api_key)
+                    .await
+                    .map_err(anyhow::Error::from)
+            })
+        }))
+    }
+
+    fn apply_fetch(&mut self, result: anyhow::Result<TaskOutput>) {
+        match tasks::take_output::<WeatherData>(result) {
+            Ok(data) => self.set_data(data),
+            Err(e) => self.set_error(e.to_string()),
+        }
+    }
+
+    fn set_compact(&mut self, compact: bool) {
+        self.compact = compact;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::update::Schedule;
 
     #[test]
     fn test_clock_widget() {
@@ -469,6 +768,44 @@ mod tests {
         assert!(!clock.time_string().is_empty());
     }
 
+    #[test]
+    fn test_clock_hourly_chime() {
+        let mut clock = ClockWidget::new("24h", true, false).with_hourly_chime(true);
+        clock.last_hour = 9;
+
+        clock.check_hour(9, 59);
+        assert_eq!(Widget::take_sound_cue(&mut clock), None);
+        clock.check_hour(10, 0);
+        assert_eq!(
+            Widget::take_sound_cue(&mut clock),
+            Some(SoundCue::Event("hour"))
+        );
+        assert_eq!(Widget::take_sound_cue(&mut clock), None);
+
+        // Resuming mid-hour doesn't chime
+        clock.check_hour(14, 30);
+        assert_eq!(Widget::take_sound_cue(&mut clock), None);
+
+        let mut silent = ClockWidget::new("24h", true, false);
+        silent.last_hour = 9;
+        silent.check_hour(10, 0);
+        assert_eq!(Widget::take_sound_cue(&mut silent), None);
+    }
+
+    #[test]
+    fn test_clock_date_follows_day() {
+        let mut clock = ClockWidget::new("24h", true, true);
+        let today = clock.current_date.clone();
+
+        // As if resumed from a suspend that started the day before
+        clock.last_date = clock.last_date.pred_opt().unwrap();
+        clock.current_date = "yesterday".to_string();
+        clock.last_second = 60;
+        clock.update();
+        assert_eq!(clock.current_date, today);
+        assert_eq!(clock.last_date, clock.now().date_naive());
+    }
+
     #[test]
     fn test_clock_widget_12h_format() {
         let clock = ClockWidget::new("12h", true, false);
@@ -477,6 +814,15 @@ mod tests {
         assert!(time.contains("AM") || time.contains("PM"));
     }
 
+    #[test]
+    fn test_clock_glyph_set() {
+        for format in ["12h", "24h"] {
+            let clock = ClockWidget::new(format, true, false);
+            let glyphs = Widget::glyph_set(&clock).unwrap();
+            assert!(clock.time_str().chars().all(|c| glyphs.contains(c)));
+        }
+    }
+
     #[test]
     fn test_clock_widget_24h_format() {
         let clock = ClockWidget::new("24h", true, false);
@@ -513,6 +859,32 @@ mod tests {
         assert!(date.contains("202"));
     }
 
+    #[test]
+    fn test_clock_date_line() {
+        use chrono::Datelike;
+        let clock = ClockWidget::new("24h", true, false);
+        assert!(matches!(clock.content(), WidgetContent::Text { .. }));
+
+        let clock = ClockWidget::new("24h", true, true)
+            .with_week_number(true)
+            .with_day_of_year(true);
+        let today = Local::now();
+        let date = clock.date_string();
+        assert!(date.contains(&format!("Week {}", today.iso_week().week())));
+        assert!(date.contains(&format!("Day {}", today.ordinal())));
+
+        match clock.content() {
+            WidgetContent::MultiLine { lines } => {
+                assert_eq!(lines.len(), 2);
+                assert_eq!(lines[1].0, date);
+            }
+            _ => panic!("Expected MultiLine content"),
+        }
+        // Lent rather than rebuilt each frame
+        assert!(matches!(clock.content_ref(), Cow::Borrowed(_)));
+        assert_eq!(*clock.content_ref(), clock.content());
+    }
+
     #[test]
     fn test_weather_widget() {
         let weather = WeatherWidget::new("London", "test_key", "celsius", 600);
@@ -528,9 +900,11 @@ mod tests {
             humidity: 70,
             wind_speed: 10.0,
         };
+        assert!(weather.last_success().is_none());
         weather.set_data(data);
         assert!(weather.data.is_some());
         assert!(weather.error_message.is_none());
+        assert!(weather.last_success().is_some());
     }
 
     #[test]
@@ -540,6 +914,61 @@ mod tests {
         assert!(weather.error_message.is_some());
     }
 
+    #[test]
+    fn test_weather_widget_compact() {
+        let mut weather = WeatherWidget::new("London", "test_key", "celsius", 600);
+        weather.set_data(WeatherData {
+            temperature: 20.5,
+            condition: "Rain".to_string(),
+            humidity: 70,
+            wind_speed: 10.0,
+        });
+
+        weather.set_compact(true);
+        assert_eq!(
+            weather.content(),
+            WidgetContent::IconText {
+                icon: "weather-rain".to_string(),
+                text: "21°C".to_string(),
+                size: FontSize::Medium,
+            }
+        );
+
+        weather.set_compact(false);
+        let WidgetContent::IconText { text, .. } = weather.content() else {
+            panic!("expected icon text");
+        };
+        assert!(text.contains("70% humidity"));
+    }
+
+    #[test]
+    fn test_weather_widget_apply_fetch() {
+        assert!(WeatherWidget::new("London", "", "celsius", 600)
+            .data_source()
+            .is_none());
+
+        let mut weather = WeatherWidget::new("London", "test_key", "celsius", 600);
+        let source = weather.data_source().unwrap();
+        assert_eq!(
+            source.schedule(),
+            &Schedule::Every(Duration::from_secs(600))
+        );
+        assert!(source.needs_network());
+
+        weather.apply_fetch(Err(anyhow::anyhow!("City not found: London")));
+        assert_eq!(weather.error(), Some("City not found: London"));
+
+        let data = WeatherData {
+            temperature: 20.5,
+            condition: "Cloudy".to_string(),
+            humidity: 70,
+            wind_speed: 10.0,
+        };
+        weather.apply_fetch(Ok(Box::new(data)));
+        assert!(weather.data.is_some());
+        assert!(weather.error().is_none());
+    }
+
     #[test]
     fn test_weather_widget_display_string_with_error_no_data() {
         let mut weather = WeatherWidget::new("London", "test_key", "celsius", 600);
@@ -547,6 +976,70 @@ mod tests {
         let display = weather.display_string();
         assert!(display.is_some());
         assert!(display.unwrap().contains("Error"));
+        assert_eq!(weather.content(), WidgetContent::error("Connection failed"));
+    }
+
+    #[test]
+    fn test_clock_widget_template() {
+        let template =
+            Template::parse_with_names("{hour:02}h{minute:02}", ClockWidget::TEMPLATE_PLACEHOLDERS)
+                .unwrap();
+        let clock = ClockWidget::new("24h", false, false).with_template(template);
+        let time = clock.time_string();
+        assert_eq!(time.len(), 5);
+        assert_eq!(&time[2..3], "h");
+    }
+
+    #[test]
+    fn test_clock_custom_format() {
+        assert!(ClockWidget::check_custom_format("%a %d %b  %H:%M").is_ok());
+        assert!(ClockWidget::check_custom_format("%Q").is_err());
+        assert!(ClockWidget::check_custom_format("").is_err());
+
+        let clock = ClockWidget::new("12h", true, false).with_custom_format("%H|%M");
+        let time = clock.time_string();
+        assert_eq!(time.len(), 5);
+        assert_eq!(&time[2..3], "|");
+        assert!(clock.glyph_set().unwrap().contains('|'));
+    }
+
+    #[test]
+    fn test_clock_timezone() {
+        let tokyo = ClockWidget::parse_timezone("Asia/Tokyo").unwrap();
+        assert!(ClockWidget::parse_timezone("Mars/Olympus_Mons").is_err());
+        assert_eq!(
+            ClockWidget::timezone_label(ClockWidget::parse_timezone("America/New_York").unwrap()),
+            "New York"
+        );
+
+        // Tokyo has no daylight saving, so it is always UTC+9
+        let clock = ClockWidget::new("24h", false, false)
+            .with_custom_format("%z")
+            .with_timezone(tokyo)
+            .with_label("Tokyo");
+        assert_eq!(clock.time_str(), "+0900");
+        assert_eq!(clock.label(), Some("Tokyo"));
+        match clock.content() {
+            WidgetContent::MultiLine { lines } => {
+                assert_eq!(lines[0].0, "+0900");
+                assert_eq!(lines[1].0, "Tokyo");
+            }
+            _ => panic!("Expected MultiLine content"),
+        }
+    }
+
+    #[test]
+    fn test_weather_widget_template() {
+        let template = Template::parse("{temp:.1}°{unit} {condition}").unwrap();
+        let mut weather =
+            WeatherWidget::new("London", "test_key", "celsius", 600).with_template(template);
+        weather.set_data(WeatherData {
+            temperature: 20.54,
+            condition: "Clouds".to_string(),
+            humidity: 70,
+            wind_speed: 3.0,
+        });
+        assert_eq!(weather.display_string().unwrap(), "20.5°C Clouds");
     }
 
     #[test]
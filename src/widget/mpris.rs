@@ -2,6 +2,11 @@
 //!
 //! Displays currently playing media from D-Bus MPRIS interface.
 //! Shows artist, title, album, and playback status from active media players.
+//!
+//! With controls on, the widget draws previous/play-pause/next buttons and a
+//! bar showing the position in the track, polled from the player's
+//! `Position` property. Clicking a button sends the command to the player,
+//! and scrolling over the widget changes its volume.
 
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -11,7 +16,17 @@ use tracing::{debug, info, warn};
 use zbus::{fdo::DBusProxy, Connection};
 
 use super::registry::DynWidgetFactory;
-use super::traits::{FontSize, Widget, WidgetContent, WidgetInfo};
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{
+    FontSize, MediaControl, MouseButton, ScrollDirection, Widget, WidgetAction, WidgetContent,
+    WidgetInfo,
+};
+
+/// Volume change per scroll step
+const VOLUME_STEP: f64 = 0.05;
+
+/// How long the volume replaces the position after scrolling
+const VOLUME_DISPLAY: Duration = Duration::from_secs(2);
 
 /// MPRIS metadata for currently playing track
 #[derive(Debug, Clone, Default)]
@@ -20,6 +35,41 @@ struct MprisMetadata {
     title: Option<String>,
     album: Option<String>,
     playback_status: PlaybackStatus,
+    /// Bus name of the player, for sending it commands
+    player: Option<String>,
+    /// Track length, if the player reports one
+    length: Option<Duration>,
+    /// Position in the track when it was polled
+    position: Option<Duration>,
+    /// When `position` was polled, to advance it between polls
+    polled_at: Option<Instant>,
+    /// Player volume from 0.0 to 1.0
+    volume: Option<f64>,
+}
+
+impl MprisMetadata {
+    /// Position now, counting the time played since the last poll
+    fn current_position(&self) -> Option<Duration> {
+        let position = self.position?;
+        let played = match (self.playback_status, self.polled_at) {
+            (PlaybackStatus::Playing, Some(polled_at)) => polled_at.elapsed(),
+            _ => Duration::ZERO,
+        };
+        let position = position + played;
+        Some(match self.length {
+            Some(length) => position.min(length),
+            None => position,
+        })
+    }
+}
+
+/// A command for the player
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PlayerCommand {
+    Previous,
+    PlayPause,
+    Next,
+    SetVolume(f64),
 }
 
 /// Playback status from MPRIS
@@ -63,6 +113,8 @@ pub struct MprisConfig {
     pub preferred_player: Option<String>,
     pub max_length: usize,
     pub update_interval: u64,
+    /// Draw playback controls and the position bar
+    pub show_controls: bool,
 }
 
 impl Default for MprisConfig {
@@ -74,6 +126,7 @@ impl Default for MprisConfig {
             preferred_player: None,
             max_length: 50,
             update_interval: 1,
+            show_controls: true,
         }
     }
 }
@@ -85,6 +138,10 @@ pub struct MprisWidget {
     last_update: Instant,
     update_interval: Duration,
     error_message: Option<String>,
+    /// Background polling loop, running between `on_start` and `on_stop`
+    update_task: Option<tokio::task::JoinHandle<()>>,
+    /// Show the volume instead of the position until then
+    volume_shown_until: Option<Instant>,
 }
 
 impl MprisWidget {
@@ -96,29 +153,15 @@ impl MprisWidget {
     /// Create with custom configuration
     pub fn with_config(config: MprisConfig) -> Self {
         let update_interval = Duration::from_secs(config.update_interval);
-        let metadata = Arc::new(Mutex::new(MprisMetadata::default()));
-
-        // Spawn background task to fetch MPRIS data (only if tokio runtime is available)
-        let metadata_clone = Arc::clone(&metadata);
-        let preferred_player = config.preferred_player.clone();
-
-        // Check if we're running in a tokio context
-        if tokio::runtime::Handle::try_current().is_ok() {
-            tokio::spawn(async move {
-                if let Err(e) = Self::mpris_update_loop(metadata_clone, preferred_player).await {
-                    warn!(error = %e, "MPRIS update loop failed");
-                }
-            });
-        } else {
-            debug!("No tokio runtime available, MPRIS updates will be disabled");
-        }
 
         Self {
             config,
-            metadata,
+            metadata: Arc::new(Mutex::new(MprisMetadata::default())),
             last_update: Instant::now(),
             update_interval,
             error_message: None,
+            update_task: None,
+            volume_shown_until: None,
         }
     }
 
@@ -126,6 +169,7 @@ impl MprisWidget {
     async fn mpris_update_loop(
         metadata: Arc<Mutex<MprisMetadata>>,
         preferred_player: Option<String>,
+        interval: Duration,
     ) -> Result<()> {
         loop {
             match Self::fetch_mpris_data(preferred_player.as_deref()).await {
@@ -139,7 +183,7 @@ impl MprisWidget {
                 }
             }
 
-            tokio::time::sleep(Duration::from_secs(1)).await;
+            tokio::time::sleep(interval).await;
         }
     }
 
@@ -151,16 +195,7 @@ impl MprisWidget {
 
         // Find active media players
         let player_name = Self::find_active_player(&connection, preferred_player).await?;
-
-        // Get player proxy
-        let player_proxy = zbus::Proxy::new(
-            &connection,
-            player_name.as_str(),
-            "/org/mpris/MediaPlayer2",
-            "org.mpris.MediaPlayer2.Player",
-        )
-        .await
-        .context("Failed to create player proxy")?;
+        let player_proxy = Self::player_proxy(&connection, &player_name).await?;
 
         // Get playback status
         let status_str = player_proxy
@@ -169,6 +204,14 @@ impl MprisWidget {
             .unwrap_or_else(|_| "Stopped".to_string());
         let playback_status = PlaybackStatus::from_str(&status_str);
 
+        // Players without seeking or volume support leave these out
+        let position = player_proxy
+            .get_property::<i64>("Position")
+            .await
+            .ok()
+            .and_then(micros);
+        let volume = player_proxy.get_property::<f64>("Volume").await.ok();
+
         // Get metadata
         use zbus::zvariant::OwnedValue;
         let metadata_variant = player_proxy
@@ -179,6 +222,7 @@ impl MprisWidget {
         let mut artist = None;
         let mut title = None;
         let mut album = None;
+        let mut length = None;
 
         // Parse metadata dictionary using TryFrom
         if let Ok(dict) = <std::collections::HashMap<String, OwnedValue>>::try_from(metadata_variant) {
@@ -213,6 +257,19 @@ impl MprisWidget {
                     }
                 }
             }
+
+            // Get length (microseconds, signed or unsigned depending on the player)
+            if let Some(length_val) = dict.get("mpris:length") {
+                if let Ok(owned) = length_val.try_clone() {
+                    if let Ok(owned2) = owned.try_clone() {
+                        if let Ok(us) = i64::try_from(owned2) {
+                            length = micros(us);
+                        } else if let Ok(us) = u64::try_from(owned) {
+                            length = Some(Duration::from_micros(us));
+                        }
+                    }
+                }
+            }
         }
 
         Ok(MprisMetadata {
@@ -220,9 +277,134 @@ impl MprisWidget {
             title,
             album,
             playback_status,
+            player: Some(player_name),
+            length: length.filter(|length| !length.is_zero()),
+            position,
+            polled_at: Some(Instant::now()),
+            volume,
         })
     }
 
+    /// Proxy for the player interface of `player_name`
+    async fn player_proxy<'a>(
+        connection: &Connection,
+        player_name: &'a str,
+    ) -> Result<zbus::Proxy<'a>> {
+        zbus::Proxy::new(
+            connection,
+            player_name,
+            "/org/mpris/MediaPlayer2",
+            "org.mpris.MediaPlayer2.Player",
+        )
+        .await
+        .context("Failed to create player proxy")
+    }
+
+    /// Send `command` to the player at `player_name`
+    async fn send_command(player_name: &str, command: PlayerCommand) -> Result<()> {
+        let connection = Connection::session()
+            .await
+            .context("Failed to connect to D-Bus session bus")?;
+        let proxy = Self::player_proxy(&connection, player_name).await?;
+        let method = match command {
+            PlayerCommand::Previous => "Previous",
+            PlayerCommand::PlayPause => "PlayPause",
+            PlayerCommand::Next => "Next",
+            PlayerCommand::SetVolume(volume) => {
+                return proxy
+                    .set_property("Volume", volume)
+                    .await
+                    .context("Failed to set volume");
+            }
+        };
+        proxy
+            .call_method(method, &())
+            .await
+            .with_context(|| format!("Failed to send {} to player", method))?;
+        Ok(())
+    }
+
+    /// Send `command` to the current player in the background
+    ///
+    /// Skipped without a player, or outside a tokio runtime.
+    fn send(&self, command: PlayerCommand) {
+        let Some(player) = self.metadata.lock().ok().and_then(|m| m.player.clone()) else {
+            return;
+        };
+        if tokio::runtime::Handle::try_current().is_err() {
+            debug!(
+                ?command,
+                "No tokio runtime available, MPRIS command dropped"
+            );
+            return;
+        }
+        tokio::spawn(async move {
+            if let Err(e) = Self::send_command(&player, command).await {
+                warn!(error = %e, player = %player, "MPRIS command failed");
+            }
+        });
+    }
+
+    /// Run a transport control, updating the shown state right away
+    fn control(&mut self, control: MediaControl) -> WidgetAction {
+        match control {
+            MediaControl::Previous => {
+                self.send(PlayerCommand::Previous);
+                WidgetAction::PreviousItem
+            }
+            MediaControl::PlayPause => {
+                if let Ok(mut metadata) = self.metadata.lock() {
+                    if metadata.player.is_some() {
+                        // Keep the position where it is at the switch
+                        metadata.position = metadata.current_position();
+                        metadata.polled_at = Some(Instant::now());
+                        metadata.playback_status = match metadata.playback_status {
+                            PlaybackStatus::Playing => PlaybackStatus::Paused,
+                            _ => PlaybackStatus::Playing,
+                        };
+                    }
+                }
+                self.send(PlayerCommand::PlayPause);
+                WidgetAction::Toggle
+            }
+            MediaControl::Next => {
+                self.send(PlayerCommand::Next);
+                WidgetAction::NextItem
+            }
+        }
+    }
+
+    /// Change the volume by `delta`, if the player reports one
+    fn change_volume(&mut self, delta: f64) -> Option<WidgetAction> {
+        let volume = {
+            let mut metadata = self.metadata.lock().ok()?;
+            let volume = (metadata.volume? + delta).clamp(0.0, 1.0);
+            metadata.volume = Some(volume);
+            volume
+        };
+        self.volume_shown_until = Some(Instant::now() + VOLUME_DISPLAY);
+        self.send(PlayerCommand::SetVolume(volume));
+        Some(WidgetAction::Custom("volume".to_string()))
+    }
+
+    /// Position text for the bar, or the volume right after a change
+    fn position_text(&self, metadata: &MprisMetadata) -> String {
+        let volume_shown = self
+            .volume_shown_until
+            .is_some_and(|until| Instant::now() < until);
+        if let Some(volume) = metadata.volume.filter(|_| volume_shown) {
+            return format!("Vol {:.0}%", volume * 100.0);
+        }
+        match (metadata.current_position(), metadata.length) {
+            (Some(position), Some(length)) => {
+                format!("{} / {}", format_time(position), format_time(length))
+            }
+            (Some(position), None) => format_time(position),
+            (None, Some(length)) => format_time(length),
+            (None, None) => String::new(),
+        }
+    }
+
     /// Find an active media player on the bus
     async fn find_active_player(
         connection: &Connection,
@@ -265,10 +447,14 @@ impl MprisWidget {
 
     /// Format display string based on configuration
     fn format_display(&self, metadata: &MprisMetadata) -> String {
+        self.format_track(metadata, self.config.show_status)
+    }
+
+    fn format_track(&self, metadata: &MprisMetadata, show_status: bool) -> String {
         let mut parts = Vec::new();
 
         // Add status icon if configured
-        if self.config.show_status && metadata.playback_status != PlaybackStatus::Stopped {
+        if show_status && metadata.playback_status != PlaybackStatus::Stopped {
             parts.push(format!("{}", metadata.playback_status.icon()));
         }
 
@@ -326,12 +512,12 @@ impl MprisWidget {
     /// Get current display string
     pub fn display_string(&self) -> String {
         if let Some(error) = &self.error_message {
-            return format!("Error: {}", error);
+            return crate::fl!("error", message = error.as_str());
         }
 
         if let Ok(metadata) = self.metadata.lock() {
             if metadata.title.is_none() && metadata.playback_status == PlaybackStatus::Stopped {
-                return "No media playing".to_string();
+                return crate::fl!("media-nothing-playing");
             }
             self.format_display(&metadata)
         } else {
@@ -351,7 +537,11 @@ impl Widget for MprisWidget {
         WidgetInfo {
             id: "mpris",
             name: "Now Playing (MPRIS)",
-            preferred_height: 40.0,
+            preferred_height: if self.config.show_controls {
+                72.0
+            } else {
+                40.0
+            },
             min_height: 30.0,
             expand: false,
         }
@@ -364,6 +554,28 @@ impl Widget for MprisWidget {
     }
 
     fn content(&self) -> WidgetContent {
+        if let Some(error) = &self.error_message {
+            return WidgetContent::error(error);
+        }
+        if self.config.show_controls {
+            if let Ok(metadata) = self.metadata.lock() {
+                if metadata.player.is_some() {
+                    let progress = match (metadata.current_position(), metadata.length) {
+                        (Some(position), Some(length)) => {
+                            Some(position.as_secs_f32() / length.as_secs_f32())
+                        }
+                        _ => None,
+                    };
+                    return WidgetContent::Media {
+                        // The play/pause button shows the status
+                        title: self.format_track(&metadata, false),
+                        playing: metadata.playback_status == PlaybackStatus::Playing,
+                        progress,
+                        position_text: self.position_text(&metadata),
+                    };
+                }
+            }
+        }
         WidgetContent::Text {
             text: self.display_string(),
             size: FontSize::Medium,
@@ -381,6 +593,75 @@ impl Widget for MprisWidget {
     fn error(&self) -> Option<&str> {
         self.error_message.as_deref()
     }
+
+    fn on_start(&mut self) {
+        if self.update_task.is_some() {
+            return;
+        }
+        // Only runs inside a tokio context
+        if tokio::runtime::Handle::try_current().is_err() {
+            debug!("No tokio runtime available, MPRIS updates will be disabled");
+            return;
+        }
+
+        let metadata = Arc::clone(&self.metadata);
+        let preferred_player = self.config.preferred_player.clone();
+        let interval = self.update_interval;
+        self.update_task = Some(tokio::spawn(async move {
+            if let Err(e) = Self::mpris_update_loop(metadata, preferred_player, interval).await {
+                warn!(error = %e, "MPRIS update loop failed");
+            }
+        }));
+    }
+
+    fn on_stop(&mut self) {
+        if let Some(task) = self.update_task.take() {
+            task.abort();
+        }
+    }
+
+    fn on_suspend(&mut self) {
+        self.on_stop();
+    }
+
+    fn on_resume(&mut self) {
+        self.on_start();
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.config.show_controls
+    }
+
+    fn on_click(&mut self, button: MouseButton, x: f32, y: f32) -> Option<WidgetAction> {
+        if button != MouseButton::Left {
+            return None;
+        }
+        MediaControl::at(x, y).map(|control| self.control(control))
+    }
+
+    fn on_scroll(&mut self, direction: ScrollDirection, _x: f32, _y: f32) -> Option<WidgetAction> {
+        match direction {
+            ScrollDirection::Up => self.change_volume(VOLUME_STEP),
+            ScrollDirection::Down => self.change_volume(-VOLUME_STEP),
+            _ => None,
+        }
+    }
+}
+
+/// Convert an MPRIS time in microseconds, rejecting negative values
+fn micros(us: i64) -> Option<Duration> {
+    u64::try_from(us).ok().map(Duration::from_micros)
+}
+
+/// Format a track time as "m:ss", or "h:mm:ss" from an hour up
+fn format_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
 }
 
 // ============================================================================
@@ -395,6 +676,14 @@ impl DynWidgetFactory for MprisWidgetFactory {
         "mpris"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Now Playing",
+            "Track and playback controls of the active media player",
+            "multimedia-player-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
         let show_artist = config
             .get("show_artist")
@@ -426,6 +715,11 @@ impl DynWidgetFactory for MprisWidgetFactory {
             .and_then(|v| v.as_integer())
             .unwrap_or(1) as u64;
 
+        let show_controls = config
+            .get("show_controls")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
         info!(
             show_artist = %show_artist,
             show_album = %show_album,
@@ -433,6 +727,7 @@ impl DynWidgetFactory for MprisWidgetFactory {
             preferred_player = ?preferred_player,
             max_length = %max_length,
             update_interval = %update_interval,
+            show_controls = %show_controls,
             "Creating MprisWidget"
         );
 
@@ -443,6 +738,7 @@ impl DynWidgetFactory for MprisWidgetFactory {
             preferred_player,
             max_length,
             update_interval,
+            show_controls,
         };
 
         Ok(Box::new(MprisWidget::with_config(widget_config)))
@@ -455,6 +751,7 @@ impl DynWidgetFactory for MprisWidgetFactory {
         config.insert("show_status".to_string(), toml::Value::Boolean(true));
         config.insert("max_length".to_string(), toml::Value::Integer(50));
         config.insert("update_interval".to_string(), toml::Value::Integer(1));
+        config.insert("show_controls".to_string(), toml::Value::Boolean(true));
         config
     }
 
@@ -481,6 +778,56 @@ impl DynWidgetFactory for MprisWidgetFactory {
 
         Ok(())
     }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_dbus()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new(
+                "show_artist",
+                "Show artist",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "show_album",
+                "Show album",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "show_status",
+                "Show playback status",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "max_length",
+                "Maximum length",
+                FieldKind::Integer {
+                    min: Some(10),
+                    max: None,
+                },
+            ))
+            .with_field(
+                ConfigField::new("preferred_player", "Preferred player", FieldKind::Text)
+                    .with_description("Player name to prefer, e.g. spotify"),
+            )
+            .with_field(ConfigField::new(
+                "update_interval",
+                "Update interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(
+                ConfigField::new("show_controls", "Show controls", FieldKind::Bool)
+                    .with_description(
+                        "Previous, play/pause and next buttons with a position bar; \
+                         scroll to change the volume",
+                    ),
+            )
+    }
 }
 
 #[cfg(test)]
@@ -535,6 +882,7 @@ mod tests {
             title: Some("Test Song".to_string()),
             album: None,
             playback_status: PlaybackStatus::Playing,
+            ..Default::default()
         };
         let display = widget.format_display(&metadata);
         assert!(display.contains("Test Song"));
@@ -549,6 +897,7 @@ mod tests {
             title: Some("Test Song".to_string()),
             album: None,
             playback_status: PlaybackStatus::Playing,
+            ..Default::default()
         };
         let display = widget.format_display(&metadata);
         assert!(display.contains("Test Artist"));
@@ -568,12 +917,79 @@ mod tests {
             title: Some("Very Long Song Title That Should Be Truncated".to_string()),
             album: None,
             playback_status: PlaybackStatus::Playing,
+            ..Default::default()
         };
         let display = widget.format_display(&metadata);
         assert!(display.len() <= 20);
         assert!(display.ends_with("..."));
     }
 
+    fn playing_track() -> MprisMetadata {
+        MprisMetadata {
+            artist: Some("Artist".to_string()),
+            title: Some("Song".to_string()),
+            playback_status: PlaybackStatus::Paused,
+            player: Some("org.mpris.MediaPlayer2.test".to_string()),
+            length: Some(Duration::from_secs(240)),
+            position: Some(Duration::from_secs(60)),
+            polled_at: Some(Instant::now()),
+            volume: Some(0.5),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_media_control_regions() {
+        assert_eq!(MediaControl::at(0.05, 0.75), Some(MediaControl::Previous));
+        assert_eq!(MediaControl::at(0.15, 0.75), Some(MediaControl::PlayPause));
+        assert_eq!(MediaControl::at(0.25, 0.75), Some(MediaControl::Next));
+        // The track line and the position bar aren't buttons
+        assert_eq!(MediaControl::at(0.15, 0.25), None);
+        assert_eq!(MediaControl::at(0.6, 0.75), None);
+    }
+
+    #[test]
+    fn test_controls_content() {
+        let mut widget = MprisWidget::new();
+        *widget.metadata.lock().unwrap() = playing_track();
+
+        assert_eq!(
+            widget.content(),
+            WidgetContent::Media {
+                title: "Artist - Song".to_string(),
+                playing: false,
+                progress: Some(0.25),
+                position_text: "1:00 / 4:00".to_string(),
+            }
+        );
+
+        // Clicking play/pause shows the new state before the next poll
+        assert_eq!(
+            widget.on_click(MouseButton::Left, 0.15, 0.75),
+            Some(WidgetAction::Toggle)
+        );
+        assert!(matches!(
+            widget.content(),
+            WidgetContent::Media { playing: true, .. }
+        ));
+        assert_eq!(widget.on_click(MouseButton::Left, 0.6, 0.25), None);
+
+        // Scrolling steps the volume and shows it in place of the position
+        widget.on_scroll(ScrollDirection::Up, 0.5, 0.5);
+        assert_eq!(widget.metadata.lock().unwrap().volume, Some(0.55));
+        match widget.content() {
+            WidgetContent::Media { position_text, .. } => assert_eq!(position_text, "Vol 55%"),
+            other => panic!("Expected media content, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(Duration::from_secs(83)), "1:23");
+        assert_eq!(format_time(Duration::from_secs(3723)), "1:02:03");
+        assert_eq!(micros(-1), None);
+    }
+
     #[test]
     fn test_factory_creation() {
         let factory = MprisWidgetFactory;
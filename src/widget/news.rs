@@ -1,20 +1,55 @@
 //! News Headlines widget displaying rotating news from RSS feeds
 //!
-//! This widget displays rotating news headlines. Currently uses embedded sample headlines.
-//! RSS feed fetching will be implemented in a future version with proper async handling.
-
+//! Headlines come from the configured RSS feeds, or from inline or embedded
+//! sample headlines without any. Each feed can include or exclude headlines
+//! by keyword, and a story carried by several feeds is shown once. Sources
+//! are prefixed in their feed's color, and clicking opens the headline's
+//! link. Headlines read before, even in an earlier run, rotate after the
+//! new ones.
+
+use std::collections::HashSet;
 use std::time::{Duration, Instant};
 
-use tracing::debug;
+use anyhow::Context;
+use chrono::Local;
+use tracing::{debug, warn};
 
 use super::registry::DynWidgetFactory;
-use super::traits::{FontSize, Widget, WidgetContent, WidgetInfo};
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{
+    FontSize, MouseButton, ScrollDirection, TextSegment, Widget, WidgetAction, WidgetContent,
+    WidgetInfo,
+};
+use crate::state::NewsReadState;
+use crate::text::FontWeight;
+use crate::theme::Color;
+use crate::update::tasks::{self, TaskOutput};
+use crate::update::{Schedule, Source, Ticker};
+
+/// Source colors for feeds without one, picked by the feed's name
+const SOURCE_PALETTE: [[u8; 4]; 6] = [
+    [66, 165, 245, 255],
+    [239, 83, 80, 255],
+    [102, 187, 106, 255],
+    [255, 167, 38, 255],
+    [171, 71, 188, 255],
+    [38, 198, 218, 255],
+];
 
 /// A news headline with source information
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Headline {
     pub title: String,
     pub source: String,
+    /// Article link, opened on click
+    #[serde(default)]
+    pub link: Option<String>,
+    /// Identifies the story for read tracking (guid or link)
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Color of the source prefix
+    #[serde(default)]
+    pub color: Option<[u8; 4]>,
 }
 
 impl Headline {
@@ -23,9 +58,28 @@ impl Headline {
         Self {
             title: title.to_string(),
             source: source.to_string(),
+            link: None,
+            id: None,
+            color: None,
         }
     }
 
+    /// Link the headline to its article
+    pub fn with_link(mut self, link: &str) -> Self {
+        self.link = Some(link.to_string());
+        self
+    }
+
+    /// The title reduced to lowercase letters and digits, to spot the same
+    /// story from different feeds
+    fn dedup_key(&self) -> String {
+        self.title
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
     /// Format the headline for display
     pub fn display(&self, show_source: bool) -> String {
         if show_source {
@@ -36,13 +90,192 @@ impl Headline {
     }
 }
 
+/// An RSS feed and which of its headlines to show
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feed {
+    /// Feed URL
+    pub url: String,
+    /// Source name shown before its headlines; the feed's title if None
+    pub name: Option<String>,
+    /// Color of the source prefix
+    pub color: [u8; 4],
+    /// Only show headlines containing one of these words, if any
+    pub include: Vec<String>,
+    /// Never show headlines containing any of these words
+    pub exclude: Vec<String>,
+}
+
+impl Feed {
+    /// A feed showing every headline, colored by `url`
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            name: None,
+            color: palette_color(url),
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Whether a headline with `text` (title and summary) passes the
+    /// keyword filters; matching ignores case
+    pub fn accepts(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        let contains = |word: &String| text.contains(&word.to_lowercase());
+        (self.include.is_empty() || self.include.iter().any(contains))
+            && !self.exclude.iter().any(contains)
+    }
+
+    fn from_config(value: &toml::Value) -> anyhow::Result<Self> {
+        if let Some(url) = value.as_str() {
+            return Ok(Self::new(url));
+        }
+        let table = value
+            .as_table()
+            .context("Each feed must be a URL or a table with 'url'")?;
+        let url = table
+            .get("url")
+            .and_then(|v| v.as_str())
+            .context("Each feed needs a 'url'")?;
+        let mut feed = Self::new(url);
+        feed.name = table
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        if let Some(name) = &feed.name {
+            feed.color = palette_color(name);
+        }
+        if let Some(color) = table.get("color") {
+            let color = color.as_str().context("Feed 'color' must be a string")?;
+            feed.color = Color::from_hex(color)
+                .with_context(|| format!("Invalid feed color '{}'", color))?
+                .to_array();
+        }
+        feed.include = keywords(table, "include")?;
+        feed.exclude = keywords(table, "exclude")?;
+        Ok(feed)
+    }
+
+    /// Fetch the feed's newest headlines that pass its filters
+    async fn fetch(&self, max_items: usize) -> anyhow::Result<Vec<Headline>> {
+        let response = crate::http::client()
+            .get(&self.url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch feed {}", self.url))?;
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Feed {} returned error status: {}",
+                self.url,
+                response.status()
+            );
+        }
+        let bytes = response.bytes().await.context("Failed to read feed")?;
+        let channel = rss::Channel::read_from(&bytes[..])
+            .with_context(|| format!("Failed to parse feed {}", self.url))?;
+        Ok(self.headlines(&channel, max_items))
+    }
+
+    fn headlines(&self, channel: &rss::Channel, max_items: usize) -> Vec<Headline> {
+        let source = self
+            .name
+            .clone()
+            .unwrap_or_else(|| channel.title().to_string());
+        channel
+            .items()
+            .iter()
+            .filter_map(|item| {
+                let title = item.title()?.trim();
+                let text = format!("{} {}", title, item.description().unwrap_or(""));
+                if title.is_empty() || !self.accepts(&text) {
+                    return None;
+                }
+                let link = item.link().map(str::to_string);
+                Some(Headline {
+                    title: title.to_string(),
+                    source: source.clone(),
+                    id: item
+                        .guid()
+                        .map(|guid| guid.value().to_string())
+                        .or_else(|| link.clone()),
+                    link,
+                    color: Some(self.color),
+                })
+            })
+            .take(max_items)
+            .collect()
+    }
+}
+
+/// Read a keyword list of a feed
+fn keywords(table: &toml::Table, key: &str) -> anyhow::Result<Vec<String>> {
+    let Some(value) = table.get(key) else {
+        return Ok(Vec::new());
+    };
+    value
+        .as_array()
+        .with_context(|| format!("Feed '{}' must be an array of strings", key))?
+        .iter()
+        .map(|v| {
+            v.as_str()
+                .map(str::to_string)
+                .with_context(|| format!("Feed '{}' must be an array of strings", key))
+        })
+        .collect()
+}
+
+/// Palette color for a source, the same one each run
+fn palette_color(name: &str) -> [u8; 4] {
+    let hash = name.bytes().fold(0usize, |hash, b| {
+        hash.wrapping_mul(31).wrapping_add(b as usize)
+    });
+    SOURCE_PALETTE[hash % SOURCE_PALETTE.len()]
+}
+
+/// Read the `feeds` option
+fn parse_feeds(config: &toml::Table) -> anyhow::Result<Vec<Feed>> {
+    let Some(value) = config.get("feeds") else {
+        return Ok(Vec::new());
+    };
+    value
+        .as_array()
+        .context("'feeds' must be an array")?
+        .iter()
+        .map(Feed::from_config)
+        .collect()
+}
+
+/// Drop headlines already seen in an earlier feed, by link or title
+fn dedup(headlines: Vec<Headline>) -> Vec<Headline> {
+    let mut seen = HashSet::new();
+    headlines
+        .into_iter()
+        .filter(|headline| {
+            let link_new = headline
+                .link
+                .as_ref()
+                .map_or(true, |link| seen.insert(link.clone()));
+            // Both keys are recorded even when the first one repeats
+            let title_new = seen.insert(headline.dedup_key());
+            link_new && title_new
+        })
+        .collect()
+}
+
 /// News Headlines widget displaying rotating news
 pub struct NewsWidget {
     headlines: Vec<Headline>,
     current_index: usize,
-    last_rotation: Instant,
-    rotation_interval: Duration,
+    rotation: Ticker,
     show_source: bool,
+    feeds: Vec<Feed>,
+    update_interval: Duration,
+    max_per_feed: usize,
+    read_state: NewsReadState,
+    /// Ids of the headlines that were unread when they arrived
+    unread: HashSet<String>,
+    /// When headlines last arrived from the feeds
+    last_fetch: Option<Instant>,
 }
 
 impl NewsWidget {
@@ -50,13 +283,7 @@ impl NewsWidget {
     pub fn new(rotation_interval: u64, show_source: bool) -> Self {
         let headlines = Self::default_headlines();
 
-        Self {
-            headlines,
-            current_index: 0,
-            last_rotation: Instant::now(),
-            rotation_interval: Duration::from_secs(rotation_interval),
-            show_source,
-        }
+        Self::with_headlines(headlines, rotation_interval, show_source)
     }
 
     /// Create with custom headlines
@@ -64,10 +291,101 @@ impl NewsWidget {
         Self {
             headlines,
             current_index: 0,
-            last_rotation: Instant::now(),
-            rotation_interval: Duration::from_secs(rotation_interval),
+            rotation: Ticker::new(Schedule::Every(Duration::from_secs(rotation_interval))),
             show_source,
+            feeds: Vec::new(),
+            update_interval: Duration::from_secs(900),
+            max_per_feed: 10,
+            read_state: NewsReadState::default(),
+            unread: HashSet::new(),
+            last_fetch: None,
+        }
+    }
+
+    /// Show headlines from `feeds` instead, fetched every `update_interval`
+    pub fn with_feeds(
+        mut self,
+        feeds: Vec<Feed>,
+        update_interval: Duration,
+        max_per_feed: usize,
+    ) -> Self {
+        if !feeds.is_empty() {
+            self.headlines.clear();
+            self.current_index = 0;
         }
+        self.feeds = feeds;
+        self.update_interval = update_interval;
+        self.max_per_feed = max_per_feed;
+        self
+    }
+
+    /// Remember read headlines in `read_state`
+    pub fn with_read_state(mut self, read_state: NewsReadState) -> Self {
+        self.read_state = read_state;
+        self
+    }
+
+    /// Fetch every feed, skipping the ones that fail
+    async fn fetch_feeds(feeds: Vec<Feed>, max_per_feed: usize) -> anyhow::Result<Vec<Headline>> {
+        let mut headlines = Vec::new();
+        let mut failures = 0;
+        for feed in &feeds {
+            match feed.fetch(max_per_feed).await {
+                Ok(items) => headlines.extend(items),
+                Err(e) => {
+                    warn!(url = %feed.url, error = %e, "Failed to fetch feed");
+                    failures += 1;
+                }
+            }
+        }
+        if failures == feeds.len() {
+            anyhow::bail!("Failed to fetch any feed");
+        }
+        Ok(dedup(headlines))
+    }
+
+    /// Replace the headlines with freshly fetched ones, unread first
+    fn set_headlines(&mut self, headlines: Vec<Headline>) {
+        let (unread, read): (Vec<_>, Vec<_>) = headlines.into_iter().partition(|headline| {
+            headline
+                .id
+                .as_ref()
+                .map_or(true, |id| !self.read_state.is_read(id))
+        });
+        debug!(
+            unread = unread.len(),
+            read = read.len(),
+            "Headlines updated"
+        );
+        self.unread = unread.iter().filter_map(|h| h.id.clone()).collect();
+        self.headlines = unread.into_iter().chain(read).collect();
+        self.current_index = 0;
+        self.rotation.restart();
+        self.mark_current_read();
+    }
+
+    /// Whether the headline was unread when it arrived
+    fn is_new(&self, headline: &Headline) -> bool {
+        headline
+            .id
+            .as_ref()
+            .is_some_and(|id| self.unread.contains(id))
+    }
+
+    /// Remember the shown headline as read
+    fn mark_current_read(&mut self) {
+        let Some(id) = self.current_headline().and_then(|h| h.id.clone()) else {
+            return;
+        };
+        if let Err(e) = self.read_state.mark_read(&id, Local::now()) {
+            warn!(error = %e, "Failed to save news read state");
+        }
+    }
+
+    /// Rotate on `schedule` instead, e.g. a cron expression
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.rotation = Ticker::new(schedule);
+        self
     }
 
     /// Default sample headlines for demonstration
@@ -98,6 +416,40 @@ impl NewsWidget {
         }
 
         self.current_index = (self.current_index + 1) % self.headlines.len();
+        self.mark_current_read();
+    }
+
+    /// Go back to the previous headline
+    fn previous_headline(&mut self) {
+        if self.headlines.is_empty() {
+            return;
+        }
+
+        self.current_index = (self.current_index + self.headlines.len() - 1) % self.headlines.len();
+        self.mark_current_read();
+    }
+
+    /// The current headline with its source prefixed in the source's color
+    fn styled_headline(&self, headline: &Headline) -> Vec<TextSegment> {
+        let mut segments = Vec::new();
+        if self.show_source {
+            let color = headline
+                .color
+                .unwrap_or_else(|| palette_color(&headline.source));
+            segments.push(TextSegment::with_color(
+                headline.source.clone(),
+                FontWeight::Bold,
+                color,
+            ));
+            segments.push(TextSegment::regular("  "));
+        }
+        // New headlines stand out until they come round again
+        segments.push(if self.is_new(headline) {
+            TextSegment::bold(headline.title.clone())
+        } else {
+            TextSegment::regular(headline.title.clone())
+        });
+        segments
     }
 
     /// Display string for the current headline
@@ -109,8 +461,8 @@ impl NewsWidget {
     }
 
     /// Check if it's time to rotate to next headline
-    fn should_rotate(&self) -> bool {
-        self.last_rotation.elapsed() >= self.rotation_interval
+    fn should_rotate(&mut self) -> bool {
+        self.rotation.tick()
     }
 }
 
@@ -129,15 +481,20 @@ impl Widget for NewsWidget {
         // Rotate headline if needed
         if self.should_rotate() && !self.headlines.is_empty() {
             self.next_headline();
-            self.last_rotation = Instant::now();
             debug!(index = self.current_index, "Headline rotated");
         }
     }
 
     fn content(&self) -> WidgetContent {
-        WidgetContent::Text {
-            text: self.display_string(),
-            size: FontSize::Small,
+        match self.current_headline() {
+            Some(headline) => WidgetContent::StyledText {
+                segments: self.styled_headline(headline),
+                size: FontSize::Small,
+            },
+            None => WidgetContent::Text {
+                text: self.display_string(),
+                size: FontSize::Small,
+            },
         }
     }
 
@@ -149,6 +506,69 @@ impl Widget for NewsWidget {
     fn is_ready(&self) -> bool {
         !self.headlines.is_empty()
     }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn on_click(&mut self, button: MouseButton, _x: f32, _y: f32) -> Option<WidgetAction> {
+        match button {
+            MouseButton::Left => {
+                let link = self.current_headline()?.link.clone()?;
+                self.mark_current_read();
+                Some(WidgetAction::OpenUrl(link))
+            }
+            MouseButton::Right => {
+                self.next_headline();
+                self.rotation.restart();
+                Some(WidgetAction::NextItem)
+            }
+            _ => None,
+        }
+    }
+
+    fn on_scroll(&mut self, direction: ScrollDirection, _x: f32, _y: f32) -> Option<WidgetAction> {
+        match direction {
+            ScrollDirection::Down => {
+                self.next_headline();
+                self.rotation.restart();
+                Some(WidgetAction::NextItem)
+            }
+            ScrollDirection::Up => {
+                self.previous_headline();
+                self.rotation.restart();
+                Some(WidgetAction::PreviousItem)
+            }
+            _ => None,
+        }
+    }
+
+    fn data_source(&self) -> Option<Source> {
+        if self.feeds.is_empty() {
+            return None;
+        }
+        let feeds = self.feeds.clone();
+        let max_per_feed = self.max_per_feed;
+        Some(Source::new(self.update_interval, move || {
+            let feeds = feeds.clone();
+            tasks::task(async move { Self::fetch_feeds(feeds, max_per_feed).await })
+        }))
+    }
+
+    fn last_success(&self) -> Option<Instant> {
+        self.last_fetch
+    }
+
+    fn apply_fetch(&mut self, result: anyhow::Result<TaskOutput>) {
+        match tasks::take_output::<Vec<Headline>>(result) {
+            Ok(headlines) => {
+                self.set_headlines(headlines);
+                self.last_fetch = Some(Instant::now());
+            }
+            // The last headlines keep rotating
+            Err(e) => warn!(error = %e, "Failed to update headlines"),
+        }
+    }
 }
 
 impl Default for NewsWidget {
@@ -169,17 +589,46 @@ impl DynWidgetFactory for NewsWidgetFactory {
         "news"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "News",
+            "Rotating headlines from RSS feeds",
+            "application-rss+xml-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> anyhow::Result<Box<dyn Widget>> {
         let rotation_interval = config
             .get("rotation_interval")
             .and_then(|v| v.as_integer())
             .unwrap_or(30) as u64;
+        let schedule = Schedule::from_config(config, "rotation_interval", 30)?;
 
         let show_source = config
             .get("show_source")
             .and_then(|v| v.as_bool())
             .unwrap_or(true);
 
+        let feeds = parse_feeds(config)?;
+        if !feeds.is_empty() {
+            let update_interval = config
+                .get("update_interval")
+                .and_then(|v| v.as_integer())
+                .unwrap_or(900) as u64;
+            let max_per_feed = config
+                .get("max_per_feed")
+                .and_then(|v| v.as_integer())
+                .unwrap_or(10) as usize;
+
+            debug!(feeds = feeds.len(), "Creating NewsWidget with feeds");
+            return Ok(Box::new(
+                NewsWidget::with_headlines(Vec::new(), rotation_interval, show_source)
+                    .with_schedule(schedule)
+                    .with_feeds(feeds, Duration::from_secs(update_interval), max_per_feed)
+                    .with_read_state(NewsReadState::load()),
+            ));
+        }
+
         // Check for custom headlines
         if let Some(headlines_array) = config.get("headlines").and_then(|v| v.as_array()) {
             let mut headlines = Vec::new();
@@ -188,24 +637,33 @@ impl DynWidgetFactory for NewsWidgetFactory {
                     let title = table.get("title").and_then(|v| v.as_str()).unwrap_or("");
                     let source = table.get("source").and_then(|v| v.as_str()).unwrap_or("News");
                     if !title.is_empty() {
-                        headlines.push(Headline::new(title, source));
+                        let mut headline = Headline::new(title, source);
+                        if let Some(link) = table.get("link").and_then(|v| v.as_str()) {
+                            headline = headline.with_link(link);
+                        }
+                        headlines.push(headline);
                     }
                 }
             }
 
             if !headlines.is_empty() {
                 debug!(count = headlines.len(), "Using custom headlines");
-                return Ok(Box::new(NewsWidget::with_headlines(headlines, rotation_interval, show_source)));
+                return Ok(Box::new(
+                    NewsWidget::with_headlines(headlines, rotation_interval, show_source)
+                        .with_schedule(schedule),
+                ));
             }
         }
 
         debug!(
-            rotation_interval = %rotation_interval,
+            schedule = %schedule,
             show_source = %show_source,
             "Creating NewsWidget with default headlines"
         );
 
-        Ok(Box::new(NewsWidget::new(rotation_interval, show_source)))
+        Ok(Box::new(
+            NewsWidget::new(rotation_interval, show_source).with_schedule(schedule),
+        ))
     }
 
     fn default_config(&self) -> toml::Table {
@@ -226,8 +684,68 @@ impl DynWidgetFactory for NewsWidgetFactory {
             }
         }
 
+        if let Some(interval) = config.get("update_interval") {
+            let interval = interval
+                .as_integer()
+                .context("'update_interval' must be an integer")?;
+            if interval < 60 {
+                anyhow::bail!("'update_interval' must be at least 60 seconds");
+            }
+        }
+
+        if let Some(max) = config.get("max_per_feed") {
+            let max = max
+                .as_integer()
+                .context("'max_per_feed' must be an integer")?;
+            if max < 1 {
+                anyhow::bail!("'max_per_feed' must be at least 1");
+            }
+        }
+
+        parse_feeds(config)?;
         Ok(())
     }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_network().with_interactive()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new(
+                "rotation_interval",
+                "Rotation interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(
+                ConfigField::new("schedule", "Schedule", FieldKind::Text)
+                    .with_description("Cron expression, e.g. 0 0 9 * * * (overrides interval)"),
+            )
+            .with_field(ConfigField::new(
+                "show_source",
+                "Show source",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "update_interval",
+                "Feed update interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(60),
+                    max: None,
+                },
+            ))
+            .with_field(ConfigField::new(
+                "max_per_feed",
+                "Headlines per feed",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+    }
 }
 
 #[cfg(test)]
@@ -273,6 +791,101 @@ mod tests {
         assert!(headlines.len() >= 5); // Should have several sample headlines
     }
 
+    fn feed_channel() -> rss::Channel {
+        rss::Channel::read_from(
+            r#"<?xml version="1.0"?>
+            <rss version="2.0"><channel>
+              <title>Example News</title><link>https://example.com</link><description>News</description>
+              <item><title>Rust 2.0 released</title><link>https://example.com/rust</link></item>
+              <item><title>Football scores</title><link>https://example.com/football</link></item>
+              <item><title>Weather warning</title><link>https://example.com/weather</link>
+                <description>Storms on the way for Rust Belt</description></item>
+            </channel></rss>"#
+                .as_bytes(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_feed_filters() {
+        let config: toml::Table = toml::from_str(
+            r##"
+            feeds = [
+                { url = "https://example.com/rss", name = "Ex", color = "#ff0000", include = ["RUST"], exclude = ["football"] },
+            ]
+            "##,
+        )
+        .unwrap();
+        let feeds = parse_feeds(&config).unwrap();
+        assert_eq!(feeds[0].color, [255, 0, 0, 255]);
+
+        // The include keyword matches the summary too
+        let headlines = feeds[0].headlines(&feed_channel(), 10);
+        let titles: Vec<&str> = headlines.iter().map(|h| h.title.as_str()).collect();
+        assert_eq!(titles, vec!["Rust 2.0 released", "Weather warning"]);
+        assert_eq!(headlines[0].source, "Ex");
+        assert_eq!(headlines[0].id.as_deref(), Some("https://example.com/rust"));
+
+        // Without a name the feed's title is the source
+        let plain = Feed::new("https://example.com/rss").headlines(&feed_channel(), 2);
+        assert_eq!(plain.len(), 2);
+        assert_eq!(plain[1].source, "Example News");
+    }
+
+    #[test]
+    fn test_dedup_across_feeds() {
+        let headlines = dedup(vec![
+            Headline::new("Rust 2.0 released", "A").with_link("https://a.com/1"),
+            Headline::new("Rust 2.0 Released!", "B").with_link("https://b.com/9"),
+            Headline::new("Another story", "B").with_link("https://a.com/1"),
+            Headline::new("Third story", "C"),
+        ]);
+        let titles: Vec<&str> = headlines.iter().map(|h| h.title.as_str()).collect();
+        assert_eq!(titles, vec!["Rust 2.0 released", "Third story"]);
+    }
+
+    #[test]
+    fn test_read_headlines_rotate_last_and_open_on_click() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("news.toml");
+        let mut read_state = NewsReadState::load_from(&path);
+        read_state
+            .mark_read("https://example.com/rust", Local::now())
+            .unwrap();
+
+        let mut widget = NewsWidget::with_headlines(Vec::new(), 30, true)
+            .with_feeds(
+                vec![Feed::new("https://example.com/rss")],
+                Duration::from_secs(900),
+                10,
+            )
+            .with_read_state(read_state);
+        assert!(widget.data_source().is_some());
+        widget.set_headlines(Feed::new("https://example.com/rss").headlines(&feed_channel(), 10));
+
+        // The story read in an earlier run comes last
+        assert_eq!(widget.headlines[0].title, "Football scores");
+        assert_eq!(widget.headlines[2].title, "Rust 2.0 released");
+        match widget.content() {
+            WidgetContent::StyledText { segments, .. } => {
+                assert_eq!(segments[0].text, "Example News");
+                assert!(segments[0].color.is_some());
+                assert_eq!(segments[2].text, "Football scores");
+                assert_eq!(segments[2].weight, FontWeight::Bold);
+            }
+            other => panic!("Expected styled text, got {:?}", other),
+        }
+
+        assert_eq!(
+            widget.on_click(MouseButton::Left, 0.5, 0.5),
+            Some(WidgetAction::OpenUrl(
+                "https://example.com/football".to_string()
+            ))
+        );
+        // Shown headlines are saved as read
+        assert!(NewsReadState::load_from(&path).is_read("https://example.com/football"));
+    }
+
     #[test]
     fn test_factory_creation() {
         let factory = NewsWidgetFactory;
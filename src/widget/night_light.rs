@@ -0,0 +1,552 @@
+//! Night light status widget
+//!
+//! Shows whether a night light daemon (gammastep, wlsunset or redshift) is
+//! running, paused or shifting the screen's color temperature, and toggles
+//! it on click. None of these daemons has an IPC interface, so the widget
+//! finds them in `/proc`, asks gammastep and redshift for the current
+//! temperature with `-p`, and toggles them with `SIGUSR1`, which all three
+//! understand.
+
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use tracing::{debug, info, warn};
+
+use super::registry::DynWidgetFactory;
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{FontSize, MouseButton, Widget, WidgetAction, WidgetContent, WidgetInfo};
+use crate::update::tasks::{self, TaskOutput};
+use crate::update::Source;
+
+/// How long `gammastep -p` may take before it's given up on
+const PRINT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A supported night light daemon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NightLightDaemon {
+    /// gammastep
+    Gammastep,
+    /// wlsunset
+    Wlsunset,
+    /// redshift
+    Redshift,
+}
+
+impl NightLightDaemon {
+    /// All daemons, in the order they're looked for
+    pub const ALL: [NightLightDaemon; 3] = [Self::Gammastep, Self::Wlsunset, Self::Redshift];
+
+    /// Executable (and process) name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Gammastep => "gammastep",
+            Self::Wlsunset => "wlsunset",
+            Self::Redshift => "redshift",
+        }
+    }
+
+    /// The daemon called `name`, if supported
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|daemon| daemon.name() == name)
+    }
+
+    /// Whether the daemon can report its current temperature with `-p`
+    fn can_print(self) -> bool {
+        !matches!(self, Self::Wlsunset)
+    }
+}
+
+/// What the last probe found
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NightLightStatus {
+    /// The running daemon and its process ID
+    pub running: Option<(NightLightDaemon, u32)>,
+    /// Current color temperature in kelvin, when the daemon reports it
+    pub temperature: Option<u32>,
+    /// Current period, e.g. "Night" or "Daytime"
+    pub period: Option<String>,
+}
+
+/// Widget showing and toggling the night light
+pub struct NightLightWidget {
+    /// Only look for this daemon; None tries them all
+    daemon: Option<NightLightDaemon>,
+    /// Started on click when no daemon is running
+    start_command: Option<String>,
+    show_temperature: bool,
+    update_interval: Duration,
+    status: Option<NightLightStatus>,
+    /// Process paused with SIGUSR1 from here
+    paused_pid: Option<u32>,
+    error_message: Option<String>,
+}
+
+impl NightLightWidget {
+    /// Create a widget looking for `daemon`, or any supported one
+    pub fn new(daemon: Option<NightLightDaemon>, update_interval: Duration) -> Self {
+        Self {
+            daemon,
+            start_command: None,
+            show_temperature: true,
+            update_interval,
+            status: None,
+            paused_pid: None,
+            error_message: None,
+        }
+    }
+
+    /// Run `command` on click when no daemon is running
+    pub fn with_start_command(mut self, command: Option<String>) -> Self {
+        self.start_command = command.filter(|c| !c.trim().is_empty());
+        self
+    }
+
+    /// Show the color temperature next to the status
+    pub fn with_temperature(mut self, show_temperature: bool) -> Self {
+        self.show_temperature = show_temperature;
+        self
+    }
+
+    /// Record a probe result
+    pub fn set_status(&mut self, status: NightLightStatus) {
+        // Pausing is forgotten once that process is gone
+        let pid = status.running.map(|(_, pid)| pid);
+        if self.paused_pid.is_some() && self.paused_pid != pid {
+            self.paused_pid = None;
+        }
+        self.status = Some(status);
+        self.error_message = None;
+    }
+
+    /// Whether the running daemon was paused from here
+    pub fn is_paused(&self) -> bool {
+        self.paused_pid.is_some()
+    }
+
+    fn status_text(&self, status: &NightLightStatus) -> String {
+        let Some((daemon, _)) = status.running else {
+            return "Night light off".to_string();
+        };
+        if self.is_paused() {
+            return "Night light paused".to_string();
+        }
+        match (self.show_temperature, status.temperature, &status.period) {
+            (true, Some(kelvin), Some(period)) => format!("{}K · {}", kelvin, period),
+            (true, Some(kelvin), None) => format!("Night light {}K", kelvin),
+            _ => format!("Night light on ({})", daemon.name()),
+        }
+    }
+}
+
+impl Widget for NightLightWidget {
+    fn info(&self) -> WidgetInfo {
+        WidgetInfo {
+            id: "night_light",
+            name: "Night Light",
+            preferred_height: 48.0,
+            min_height: 32.0,
+            expand: false,
+        }
+    }
+
+    fn update(&mut self) {
+        // Status arrives through apply_fetch from the data service
+    }
+
+    fn content(&self) -> WidgetContent {
+        let Some(status) = &self.status else {
+            return self
+                .error_message
+                .as_deref()
+                .map_or(WidgetContent::Empty, WidgetContent::error);
+        };
+        let active = status.running.is_some() && !self.is_paused();
+        WidgetContent::IconText {
+            icon: if active {
+                "night-light-on"
+            } else {
+                "night-light-off"
+            }
+            .to_string(),
+            text: self.status_text(status),
+            size: FontSize::Medium,
+        }
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.update_interval
+    }
+
+    fn is_ready(&self) -> bool {
+        self.status.is_some() || self.error_message.is_some()
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error_message.as_deref()
+    }
+
+    fn data_source(&self) -> Option<Source> {
+        let daemon = self.daemon;
+        Some(Source::blocking(self.update_interval, move || {
+            probe(daemon)
+        }))
+    }
+
+    fn apply_fetch(&mut self, result: Result<TaskOutput>) {
+        match tasks::take_output::<NightLightStatus>(result) {
+            Ok(status) => self.set_status(status),
+            Err(e) => {
+                warn!(error = %e, "Failed to check night light");
+                self.error_message = Some("Can't check night light".to_string());
+            }
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn on_click(&mut self, button: MouseButton, _x: f32, _y: f32) -> Option<WidgetAction> {
+        if button != MouseButton::Left {
+            return None;
+        }
+        match self.status.as_ref()?.running {
+            Some((daemon, pid)) => {
+                info!(daemon = daemon.name(), pid = pid, "Toggling night light");
+                self.paused_pid = if self.is_paused() { None } else { Some(pid) };
+                Some(WidgetAction::RunCommand(format!("kill -USR1 {}", pid)))
+            }
+            None => {
+                let command = self.start_command.clone()?;
+                info!(command = %command, "Starting night light");
+                Some(WidgetAction::RunCommand(command))
+            }
+        }
+    }
+}
+
+/// Find the running daemon and, where possible, its current temperature
+fn probe(daemon: Option<NightLightDaemon>) -> Result<NightLightStatus> {
+    let Some((daemon, pid)) = find_daemon(Path::new("/proc"), daemon)? else {
+        return Ok(NightLightStatus::default());
+    };
+    let mut status = NightLightStatus {
+        running: Some((daemon, pid)),
+        ..Default::default()
+    };
+    if daemon.can_print() {
+        match print_status(daemon) {
+            Ok(output) => {
+                let (temperature, period) = parse_print_output(&output);
+                status.temperature = temperature;
+                status.period = period;
+            }
+            Err(e) => debug!(error = %e, daemon = daemon.name(), "No temperature report"),
+        }
+    }
+    Ok(status)
+}
+
+/// The first running daemon under `proc`, limited to `only` if set
+fn find_daemon(
+    proc: &Path,
+    only: Option<NightLightDaemon>,
+) -> Result<Option<(NightLightDaemon, u32)>> {
+    let entries =
+        std::fs::read_dir(proc).with_context(|| format!("Failed to list {}", proc.display()))?;
+    let mut found: Vec<(NightLightDaemon, u32)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            let comm = std::fs::read_to_string(entry.path().join("comm")).ok()?;
+            let daemon = NightLightDaemon::from_name(comm.trim())?;
+            only.map_or(true, |only| only == daemon)
+                .then_some((daemon, pid))
+        })
+        .collect();
+    // Prefer daemons in ALL's order, then the oldest process
+    found.sort_by_key(|(daemon, pid)| {
+        let rank = NightLightDaemon::ALL.iter().position(|d| d == daemon);
+        (rank, *pid)
+    });
+    Ok(found.into_iter().next())
+}
+
+/// Run `<daemon> -p`, which prints the current period and temperature
+fn print_status(daemon: NightLightDaemon) -> Result<String> {
+    let mut child = Command::new(daemon.name())
+        .arg("-p")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {} -p", daemon.name()))?;
+
+    // A location provider that never answers would otherwise block forever
+    let deadline = Instant::now() + PRINT_TIMEOUT;
+    while child.try_wait()?.is_none() {
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            anyhow::bail!("{} -p timed out", daemon.name());
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    let mut output = String::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        stdout.read_to_string(&mut output)?;
+    }
+    Ok(output)
+}
+
+/// Temperature and period from gammastep/redshift `-p` output, e.g.
+/// `Period: Night` and `Color temperature: 4500K`
+fn parse_print_output(output: &str) -> (Option<u32>, Option<String>) {
+    let mut temperature = None;
+    let mut period = None;
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Color temperature" => {
+                temperature = value.trim_end_matches('K').trim().parse().ok();
+            }
+            "Period" => {
+                // "Transition (45.20% day)" is shortened to "Transition"
+                let name = value.split(" (").next().unwrap_or(value);
+                period = Some(name.to_string()).filter(|p| !p.is_empty());
+            }
+            _ => {}
+        }
+    }
+    (temperature, period)
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+/// Factory for NightLightWidget
+pub struct NightLightWidgetFactory;
+
+impl DynWidgetFactory for NightLightWidgetFactory {
+    fn widget_type(&self) -> &'static str {
+        "night_light"
+    }
+
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Night Light",
+            "Night light status and color temperature, with click to toggle",
+            "night-light-symbolic",
+        )
+    }
+
+    fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
+        let daemon = config
+            .get("daemon")
+            .and_then(|v| v.as_str())
+            .and_then(NightLightDaemon::from_name);
+
+        let start_command = config
+            .get("start_command")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        let show_temperature = config
+            .get("show_temperature")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let update_interval = config
+            .get("update_interval")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(30)
+            .max(5) as u64;
+
+        info!(
+            daemon = ?daemon,
+            update_interval = update_interval,
+            "Creating NightLightWidget"
+        );
+
+        Ok(Box::new(
+            NightLightWidget::new(daemon, Duration::from_secs(update_interval))
+                .with_start_command(start_command)
+                .with_temperature(show_temperature),
+        ))
+    }
+
+    fn default_config(&self) -> toml::Table {
+        let mut config = toml::Table::new();
+        config.insert(
+            "daemon".to_string(),
+            toml::Value::String("auto".to_string()),
+        );
+        config.insert("show_temperature".to_string(), toml::Value::Boolean(true));
+        config.insert("update_interval".to_string(), toml::Value::Integer(30));
+        config
+    }
+
+    fn validate_config(&self, config: &toml::Table) -> Result<()> {
+        if let Some(daemon) = config.get("daemon") {
+            let daemon = daemon.as_str().context("'daemon' must be a string")?;
+            if daemon != "auto" && NightLightDaemon::from_name(daemon).is_none() {
+                anyhow::bail!(
+                    "'daemon' must be 'auto', 'gammastep', 'wlsunset' or 'redshift', got '{}'",
+                    daemon
+                );
+            }
+        }
+
+        if let Some(command) = config.get("start_command") {
+            command
+                .as_str()
+                .context("'start_command' must be a string")?;
+        }
+
+        if let Some(show) = config.get("show_temperature") {
+            show.as_bool()
+                .context("'show_temperature' must be a boolean")?;
+        }
+
+        if let Some(interval) = config.get("update_interval") {
+            let val = interval
+                .as_integer()
+                .context("'update_interval' must be an integer")?;
+            if val < 5 {
+                anyhow::bail!("'update_interval' must be at least 5 seconds");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_interactive()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new(
+                "daemon",
+                "Daemon",
+                FieldKind::Choice(&["auto", "gammastep", "wlsunset", "redshift"]),
+            ))
+            .with_field(
+                ConfigField::new("start_command", "Start command", FieldKind::Text)
+                    .with_description(
+                        "Run on click when no night light is running, e.g. gammastep",
+                    ),
+            )
+            .with_field(ConfigField::new(
+                "show_temperature",
+                "Show temperature",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "update_interval",
+                "Update interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(5),
+                    max: None,
+                },
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn running(pid: u32) -> NightLightStatus {
+        NightLightStatus {
+            running: Some((NightLightDaemon::Gammastep, pid)),
+            temperature: Some(4500),
+            period: Some("Night".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_print_output() {
+        let output = "Notice: Using provider `manual'.\n\
+                      Period: Transition (45.20% day)\n\
+                      Color temperature: 5213K\n\
+                      Brightness: 1.00\n";
+        assert_eq!(
+            parse_print_output(output),
+            (Some(5213), Some("Transition".to_string()))
+        );
+        assert_eq!(parse_print_output(""), (None, None));
+    }
+
+    #[test]
+    fn test_find_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        for (pid, comm) in [
+            ("12", "bash\n"),
+            ("40", "wlsunset\n"),
+            ("41", "gammastep\n"),
+        ] {
+            std::fs::create_dir(dir.path().join(pid)).unwrap();
+            std::fs::write(dir.path().join(pid).join("comm"), comm).unwrap();
+        }
+        std::fs::create_dir(dir.path().join("self")).unwrap();
+
+        assert_eq!(
+            find_daemon(dir.path(), None).unwrap(),
+            Some((NightLightDaemon::Gammastep, 41))
+        );
+        assert_eq!(
+            find_daemon(dir.path(), Some(NightLightDaemon::Wlsunset)).unwrap(),
+            Some((NightLightDaemon::Wlsunset, 40))
+        );
+        assert_eq!(
+            find_daemon(dir.path(), Some(NightLightDaemon::Redshift)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_click_toggles() {
+        let mut widget = NightLightWidget::new(None, Duration::from_secs(30))
+            .with_start_command(Some("gammastep".to_string()));
+        widget.set_status(running(41));
+        assert_eq!(widget.status_text(&running(41)), "4500K · Night");
+
+        assert_eq!(
+            widget.on_click(MouseButton::Left, 0.5, 0.5),
+            Some(WidgetAction::RunCommand("kill -USR1 41".to_string()))
+        );
+        assert!(widget.is_paused());
+        assert_eq!(widget.status_text(&running(41)), "Night light paused");
+
+        // A restarted daemon isn't paused
+        widget.set_status(running(99));
+        assert!(!widget.is_paused());
+
+        widget.set_status(NightLightStatus::default());
+        assert_eq!(
+            widget.on_click(MouseButton::Left, 0.5, 0.5),
+            Some(WidgetAction::RunCommand("gammastep".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_factory_validation() {
+        let factory = NightLightWidgetFactory;
+        assert!(factory.validate_config(&factory.default_config()).is_ok());
+
+        let mut config = toml::Table::new();
+        config.insert(
+            "daemon".to_string(),
+            toml::Value::String("f.lux".to_string()),
+        );
+        assert!(factory.validate_config(&config).is_err());
+    }
+}
@@ -0,0 +1,545 @@
+//! Desktop notification widget
+//!
+//! Shows the most recent desktop notification on the desktop: its icon, the
+//! sending app, the summary and the first lines of the body. Notifications
+//! are read by monitoring the session bus for `Notify` calls to the
+//! notification daemon, so popups keep working as before.
+//!
+//! A notification stays up for `timeout` seconds (0 keeps it until the next
+//! one); clicking the widget dismisses it early.
+
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use tracing::{debug, info, warn};
+use zbus::zvariant::OwnedValue;
+
+use super::registry::DynWidgetFactory;
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{FontSize, MouseButton, Widget, WidgetAction, WidgetContent, WidgetInfo};
+
+const INTERFACE: &str = "org.freedesktop.Notifications";
+
+/// Arguments of `org.freedesktop.Notifications.Notify`
+type NotifyArgs = (
+    String,
+    u32,
+    String,
+    String,
+    String,
+    Vec<String>,
+    HashMap<String, OwnedValue>,
+    i32,
+);
+
+/// A notification seen on the bus
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedNotification {
+    /// Name of the sending application
+    pub app_name: String,
+    /// Icon name or path the app asked for
+    pub icon: String,
+    /// Title line
+    pub summary: String,
+    /// Text below the title, without markup
+    pub body: String,
+}
+
+impl ReceivedNotification {
+    fn from_notify((app_name, _, icon, summary, body, ..): NotifyArgs) -> Self {
+        Self {
+            app_name,
+            icon,
+            summary,
+            body: strip_markup(&body),
+        }
+    }
+}
+
+/// Widget showing the latest desktop notification
+pub struct NotificationsWidget {
+    /// How long a notification stays up; None keeps it until the next one
+    timeout: Option<Duration>,
+    max_lines: usize,
+    line_length: usize,
+    /// Apps whose notifications are left out, lowercase
+    ignore_apps: Vec<String>,
+    current: Option<(ReceivedNotification, Instant)>,
+    /// Notifications from the monitor thread, while it runs
+    receiver: Option<mpsc::Receiver<ReceivedNotification>>,
+    error_message: Option<String>,
+}
+
+impl NotificationsWidget {
+    /// Create a widget that keeps notifications up for `timeout` (zero
+    /// keeps them until the next one), with up to `max_lines` body lines
+    pub fn new(timeout: Duration, max_lines: usize) -> Self {
+        Self {
+            timeout: (!timeout.is_zero()).then_some(timeout),
+            max_lines,
+            line_length: 48,
+            ignore_apps: Vec::new(),
+            current: None,
+            receiver: None,
+            error_message: None,
+        }
+    }
+
+    /// Wrap body text at `line_length` characters
+    pub fn with_line_length(mut self, line_length: usize) -> Self {
+        self.line_length = line_length.max(10);
+        self
+    }
+
+    /// Leave out notifications from these apps (case-insensitive)
+    pub fn with_ignored_apps(mut self, apps: Vec<String>) -> Self {
+        self.ignore_apps = apps.iter().map(|app| app.to_lowercase()).collect();
+        self
+    }
+
+    /// Show `notification` unless its app is ignored
+    pub fn show(&mut self, notification: ReceivedNotification) {
+        if self
+            .ignore_apps
+            .contains(&notification.app_name.to_lowercase())
+        {
+            return;
+        }
+        self.current = Some((notification, Instant::now()));
+    }
+
+    /// The notification on display, if it hasn't timed out
+    pub fn current(&self) -> Option<&ReceivedNotification> {
+        let (notification, shown_at) = self.current.as_ref()?;
+        match self.timeout {
+            Some(timeout) if shown_at.elapsed() >= timeout => None,
+            _ => Some(notification),
+        }
+    }
+
+    /// Lines shown below the summary
+    fn body_lines(&self, body: &str) -> Vec<String> {
+        wrap_lines(body, self.line_length, self.max_lines)
+    }
+}
+
+impl Widget for NotificationsWidget {
+    fn info(&self) -> WidgetInfo {
+        WidgetInfo {
+            id: "notifications",
+            name: "Notifications",
+            preferred_height: 48.0 + 18.0 * self.max_lines as f32,
+            min_height: 40.0,
+            expand: false,
+        }
+    }
+
+    fn update(&mut self) {
+        let received: Vec<ReceivedNotification> = match &self.receiver {
+            Some(receiver) => receiver.try_iter().collect(),
+            None => return,
+        };
+        for notification in received {
+            self.show(notification);
+        }
+        if self.current().is_none() {
+            self.current = None;
+        }
+    }
+
+    fn content(&self) -> WidgetContent {
+        let Some(notification) = self.current() else {
+            return WidgetContent::Text {
+                text: "No notifications".to_string(),
+                size: FontSize::Small,
+            };
+        };
+
+        let title = match (
+            notification.app_name.is_empty(),
+            notification.summary.is_empty(),
+        ) {
+            (false, false) => format!("{}: {}", notification.app_name, notification.summary),
+            (true, _) => notification.summary.clone(),
+            (false, true) => notification.app_name.clone(),
+        };
+        let mut lines = vec![(title, FontSize::Medium)];
+        lines.extend(
+            self.body_lines(&notification.body)
+                .into_iter()
+                .map(|line| (line, FontSize::Small)),
+        );
+        WidgetContent::IconLines {
+            icon: notification.icon.clone(),
+            lines,
+        }
+    }
+
+    fn update_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error_message.as_deref()
+    }
+
+    fn on_start(&mut self) {
+        if self.receiver.is_some() {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name("notification-monitor".to_string())
+            .spawn(move || {
+                if let Err(e) = monitor_notifications(&tx) {
+                    warn!(error = %e, "Notification monitoring stopped");
+                }
+            });
+        match spawned {
+            Ok(_) => {
+                self.receiver = Some(rx);
+                self.error_message = None;
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to spawn notification monitor");
+                self.error_message = Some("Can't watch notifications".to_string());
+            }
+        }
+    }
+
+    fn on_stop(&mut self) {
+        // The monitor thread ends when it next finds the channel closed
+        self.receiver = None;
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn on_click(&mut self, button: MouseButton, _x: f32, _y: f32) -> Option<WidgetAction> {
+        if button != MouseButton::Left || self.current().is_none() {
+            return None;
+        }
+        self.current = None;
+        Some(WidgetAction::Custom("dismiss".to_string()))
+    }
+}
+
+/// Watch the session bus for notifications, sending each one to `tx`
+///
+/// Returns once the receiving widget is gone.
+fn monitor_notifications(tx: &mpsc::Sender<ReceivedNotification>) -> Result<()> {
+    let connection =
+        zbus::blocking::Connection::session().context("Failed to connect to the session bus")?;
+    let rule = zbus::MatchRule::builder()
+        .msg_type(zbus::message::Type::MethodCall)
+        .interface(INTERFACE)?
+        .member("Notify")?
+        .build();
+    zbus::blocking::fdo::MonitoringProxy::new(&connection)?
+        .become_monitor(&[rule], 0)
+        .context("Failed to monitor the session bus")?;
+    info!("Watching desktop notifications");
+
+    for message in zbus::blocking::MessageIterator::from(&connection) {
+        let message = match message {
+            Ok(message) => message,
+            Err(e) => {
+                debug!(error = %e, "Failed to read bus message");
+                continue;
+            }
+        };
+        let header = message.header();
+        if header.member().map(|member| member.as_str()) != Some("Notify") {
+            continue;
+        }
+        match message.body().deserialize::<NotifyArgs>() {
+            Ok(args) => {
+                if tx.send(ReceivedNotification::from_notify(args)).is_err() {
+                    break;
+                }
+            }
+            Err(e) => debug!(error = %e, "Ignoring malformed Notify call"),
+        }
+    }
+    Ok(())
+}
+
+/// Notification bodies may use a little HTML-like markup; keep the text
+fn strip_markup(body: &str) -> String {
+    let mut text = String::with_capacity(body.len());
+    let mut in_tag = false;
+    for c in body.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Word-wrap `text` at `width` characters into at most `max_lines` lines,
+/// ending with "…" when some of it didn't fit
+fn wrap_lines(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut truncated = false;
+    'paragraphs: for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let fits = line.is_empty() || line.chars().count() + 1 + word.chars().count() <= width;
+            if !fits {
+                if lines.len() == max_lines {
+                    truncated = true;
+                    break 'paragraphs;
+                }
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        if !line.is_empty() {
+            if lines.len() == max_lines {
+                truncated = true;
+                break;
+            }
+            lines.push(line);
+        }
+    }
+
+    if truncated {
+        if let Some(last) = lines.last_mut() {
+            let kept: String = last.chars().take(width.saturating_sub(1)).collect();
+            *last = format!("{}…", kept.trim_end());
+        }
+    }
+    lines
+        .into_iter()
+        .map(|line| {
+            if line.chars().count() > width {
+                let kept: String = line.chars().take(width.saturating_sub(1)).collect();
+                format!("{}…", kept)
+            } else {
+                line
+            }
+        })
+        .collect()
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+/// Factory for NotificationsWidget
+pub struct NotificationsWidgetFactory;
+
+impl DynWidgetFactory for NotificationsWidgetFactory {
+    fn widget_type(&self) -> &'static str {
+        "notifications"
+    }
+
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Notifications",
+            "The latest desktop notification, right on the desktop",
+            "preferences-system-notifications-symbolic",
+        )
+    }
+
+    fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
+        let timeout = config
+            .get("timeout")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(30)
+            .max(0) as u64;
+
+        let max_lines = config
+            .get("max_lines")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(2)
+            .max(0) as usize;
+
+        let line_length = config
+            .get("line_length")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(48)
+            .max(0) as usize;
+
+        let ignore_apps: Vec<String> = config
+            .get("ignore_apps")
+            .and_then(|v| v.as_array())
+            .map(|apps| {
+                apps.iter()
+                    .filter_map(|app| app.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        info!(
+            timeout = timeout,
+            max_lines = max_lines,
+            ignored = ignore_apps.len(),
+            "Creating NotificationsWidget"
+        );
+
+        Ok(Box::new(
+            NotificationsWidget::new(Duration::from_secs(timeout), max_lines)
+                .with_line_length(line_length)
+                .with_ignored_apps(ignore_apps),
+        ))
+    }
+
+    fn default_config(&self) -> toml::Table {
+        let mut config = toml::Table::new();
+        config.insert("timeout".to_string(), toml::Value::Integer(30));
+        config.insert("max_lines".to_string(), toml::Value::Integer(2));
+        config
+    }
+
+    fn validate_config(&self, config: &toml::Table) -> Result<()> {
+        if let Some(timeout) = config.get("timeout") {
+            let val = timeout
+                .as_integer()
+                .context("'timeout' must be an integer")?;
+            if val < 0 {
+                anyhow::bail!("'timeout' can't be negative");
+            }
+        }
+
+        if let Some(max_lines) = config.get("max_lines") {
+            let val = max_lines
+                .as_integer()
+                .context("'max_lines' must be an integer")?;
+            if !(0..=10).contains(&val) {
+                anyhow::bail!("'max_lines' must be between 0 and 10");
+            }
+        }
+
+        if let Some(line_length) = config.get("line_length") {
+            let val = line_length
+                .as_integer()
+                .context("'line_length' must be an integer")?;
+            if val < 10 {
+                anyhow::bail!("'line_length' must be at least 10 characters");
+            }
+        }
+
+        if let Some(apps) = config.get("ignore_apps") {
+            let apps = apps
+                .as_array()
+                .context("'ignore_apps' must be an array of app names")?;
+            if apps.iter().any(|app| !app.is_str()) {
+                anyhow::bail!("'ignore_apps' must only contain strings");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_dbus().with_interactive()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(
+                ConfigField::new(
+                    "timeout",
+                    "Timeout (seconds)",
+                    FieldKind::Integer {
+                        min: Some(0),
+                        max: None,
+                    },
+                )
+                .with_description("How long a notification stays up; 0 keeps it until the next"),
+            )
+            .with_field(ConfigField::new(
+                "max_lines",
+                "Body lines",
+                FieldKind::Integer {
+                    min: Some(0),
+                    max: Some(10),
+                },
+            ))
+            .with_field(ConfigField::new(
+                "line_length",
+                "Line length",
+                FieldKind::Integer {
+                    min: Some(10),
+                    max: None,
+                },
+            ))
+            .with_field(
+                ConfigField::new("ignore_apps", "Ignored apps", FieldKind::List)
+                    .with_description("Apps whose notifications aren't shown, e.g. Spotify"),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(app_name: &str, summary: &str, body: &str) -> ReceivedNotification {
+        ReceivedNotification {
+            app_name: app_name.to_string(),
+            icon: "mail-unread".to_string(),
+            summary: summary.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_strip_markup() {
+        assert_eq!(
+            strip_markup("<b>Build</b> passed &amp; <a href=\"x\">deployed</a>"),
+            "Build passed & deployed"
+        );
+    }
+
+    #[test]
+    fn test_wrap_lines() {
+        let lines = wrap_lines("the quick brown fox jumps over the lazy dog", 15, 2);
+        assert_eq!(lines, vec!["the quick brown", "fox jumps over…"]);
+        assert_eq!(wrap_lines("short", 15, 2), vec!["short"]);
+        assert!(wrap_lines("anything", 15, 0).is_empty());
+    }
+
+    #[test]
+    fn test_shows_latest_and_dismisses() {
+        let mut widget = NotificationsWidget::new(Duration::from_secs(30), 2)
+            .with_ignored_apps(vec!["Spotify".to_string()]);
+        widget.show(notification("Mail", "New message", "Lunch at noon?"));
+        widget.show(notification("spotify", "Now playing", "Song"));
+
+        let WidgetContent::IconLines { icon, lines } = widget.content() else {
+            panic!("Expected IconLines");
+        };
+        assert_eq!(icon, "mail-unread");
+        assert_eq!(lines[0].0, "Mail: New message");
+        assert_eq!(lines[1].0, "Lunch at noon?");
+
+        assert!(widget.on_click(MouseButton::Left, 0.0, 0.0).is_some());
+        assert!(widget.current().is_none());
+    }
+
+    #[test]
+    fn test_factory_validation() {
+        let factory = NotificationsWidgetFactory;
+        assert!(factory.validate_config(&factory.default_config()).is_ok());
+
+        let mut config = toml::Table::new();
+        config.insert("max_lines".to_string(), toml::Value::Integer(20));
+        assert!(factory.validate_config(&config).is_err());
+    }
+}
@@ -0,0 +1,295 @@
+//! Performance overlay widget
+//!
+//! Shows the widget process's own render times, FPS-equivalent, memory use
+//! and glyph cache hit rate. Meant for diagnosing high CPU or memory reports:
+//! add it to the config as `type = "perf"`, or toggle it on the fly with
+//! `cosmic-desktop-widget perf toggle`.
+
+use std::time::Duration;
+
+use super::registry::DynWidgetFactory;
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetMetadata};
+use super::traits::{FontSize, Widget, WidgetContent, WidgetInfo};
+use crate::metrics::{PerfStats, WidgetUpdateStats, TARGET_RENDER_TIME_MS};
+
+const MB: f64 = 1024.0 * 1024.0;
+
+/// Live performance metrics display
+pub struct PerfWidget {
+    stats: Option<PerfStats>,
+    show_memory: bool,
+}
+
+impl PerfWidget {
+    /// Create a new performance widget
+    pub fn new(show_memory: bool) -> Self {
+        Self {
+            stats: None,
+            show_memory,
+        }
+    }
+
+    fn lines(&self, stats: &PerfStats) -> Vec<(String, FontSize)> {
+        let budget_flag = if stats.avg_render_ms > TARGET_RENDER_TIME_MS as f64 {
+            " ⚠"
+        } else {
+            ""
+        };
+
+        let mut lines = vec![
+            (
+                format!(
+                    "Render {:.2} ms (avg {:.2}, max {:.2}){}",
+                    stats.last_render_ms, stats.avg_render_ms, stats.max_render_ms, budget_flag
+                ),
+                FontSize::Small,
+            ),
+            (
+                format!(
+                    "≈{:.0} fps · {} frames · {:.1}% over budget",
+                    stats.fps_equivalent, stats.render_count, stats.frames_over_budget_pct
+                ),
+                FontSize::Small,
+            ),
+            (
+                format!(
+                    "p50 {:.1} · p95 {:.1} · p99 {:.1} ms",
+                    stats.p50_render_ms, stats.p95_render_ms, stats.p99_render_ms
+                ),
+                FontSize::Small,
+            ),
+            (
+                format!(
+                    "Glyph cache {:.1}% hits · {} evicted",
+                    stats.glyph_cache_hit_rate, stats.glyph_cache_evictions
+                ),
+                FontSize::Small,
+            ),
+        ];
+
+        if let Some(line) = Self::update_line(stats) {
+            lines.push((line, FontSize::Small));
+        }
+
+        if self.show_memory {
+            let memory = match stats.rss_bytes {
+                Some(bytes) if stats.peak_rss_bytes > bytes => format!(
+                    "RSS {:.1} MB (peak {:.1})",
+                    bytes as f64 / MB,
+                    stats.peak_rss_bytes as f64 / MB
+                ),
+                Some(bytes) => format!("RSS {:.1} MB", bytes as f64 / MB),
+                None => "RSS unavailable".to_string(),
+            };
+            lines.push((memory, FontSize::Small));
+        }
+
+        lines
+    }
+
+    /// Stuck workers or failing widgets if there are any, otherwise the
+    /// slowest updater
+    fn update_line(stats: &PerfStats) -> Option<String> {
+        let stuck: Vec<&str> = stats
+            .workers
+            .iter()
+            .filter(|(_, worker)| !worker.healthy)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        if !stuck.is_empty() {
+            return Some(format!("Stalled: {}", stuck.join(", ")));
+        }
+
+        let failing: Vec<&str> = stats
+            .widget_updates
+            .iter()
+            .filter(|(_, updates)| updates.is_failing())
+            .map(|(id, _)| id.as_str())
+            .collect();
+        if !failing.is_empty() {
+            return Some(format!("Failing: {}", failing.join(", ")));
+        }
+
+        let (id, slowest) = stats
+            .widget_updates
+            .iter()
+            .max_by(|a, b| a.1.avg_update_ms.total_cmp(&b.1.avg_update_ms))?;
+        Some(format!(
+            "Slowest update: {} {:.2} ms",
+            id, slowest.avg_update_ms
+        ))
+    }
+}
+
+impl Default for PerfWidget {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl Widget for PerfWidget {
+    fn info(&self) -> WidgetInfo {
+        WidgetInfo {
+            id: "perf",
+            name: "Performance",
+            preferred_height: 130.0,
+            min_height: 60.0,
+            expand: false,
+        }
+    }
+
+    fn update(&mut self) {
+        // Everything arrives through observe_metrics
+    }
+
+    fn content(&self) -> WidgetContent {
+        match &self.stats {
+            Some(stats) => WidgetContent::MultiLine {
+                lines: self.lines(stats),
+            },
+            None => WidgetContent::Text {
+                text: "Collecting metrics...".to_string(),
+                size: FontSize::Small,
+            },
+        }
+    }
+
+    fn update_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn observe_metrics(&mut self, stats: &PerfStats) {
+        self.stats = Some(stats.clone());
+    }
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+/// Factory for PerfWidget
+pub struct PerfWidgetFactory;
+
+impl DynWidgetFactory for PerfWidgetFactory {
+    fn widget_type(&self) -> &'static str {
+        "perf"
+    }
+
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Performance",
+            "Render and update timings of the widget process itself",
+            "utilities-system-monitor-symbolic",
+        )
+    }
+
+    fn create(&self, config: &toml::Table) -> anyhow::Result<Box<dyn Widget>> {
+        let show_memory = config
+            .get("show_memory")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        Ok(Box::new(PerfWidget::new(show_memory)))
+    }
+
+    fn default_config(&self) -> toml::Table {
+        let mut config = toml::Table::new();
+        config.insert("show_memory".to_string(), toml::Value::Boolean(true));
+        config
+    }
+
+    fn validate_config(&self, config: &toml::Table) -> anyhow::Result<()> {
+        if let Some(value) = config.get("show_memory") {
+            value
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'show_memory' must be a boolean"))?;
+        }
+        Ok(())
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new().with_field(ConfigField::new(
+            "show_memory",
+            "Show memory",
+            FieldKind::Bool,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perf_widget_content() {
+        let mut widget = PerfWidget::default();
+        assert!(matches!(widget.content(), WidgetContent::Text { .. }));
+
+        widget.observe_metrics(&PerfStats {
+            last_render_ms: 2.0,
+            avg_render_ms: 3.0,
+            max_render_ms: 20.0,
+            fps_equivalent: 333.0,
+            render_count: 42,
+            frames_over_budget_pct: 2.4,
+            p95_render_ms: 8.0,
+            glyph_cache_hit_rate: 97.5,
+            rss_bytes: Some(24 * 1024 * 1024),
+            ..Default::default()
+        });
+
+        let WidgetContent::MultiLine { lines } = widget.content() else {
+            panic!("expected multi-line content");
+        };
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].0.starts_with("Render 2.00 ms"));
+        assert!(lines[2].0.contains("p95 8.0"));
+        assert!(lines[3].0.contains("97.5%"));
+        assert_eq!(lines[4].0, "RSS 24.0 MB");
+    }
+
+    #[test]
+    fn test_perf_widget_update_line() {
+        let mut stats = PerfStats::default();
+        assert_eq!(PerfWidget::update_line(&stats), None);
+
+        let updates = |avg_update_ms, consecutive_failures| WidgetUpdateStats {
+            avg_update_ms,
+            consecutive_failures,
+            ..Default::default()
+        };
+        stats.widget_updates.insert("clock".into(), updates(0.1, 0));
+        stats
+            .widget_updates
+            .insert("weather".into(), updates(4.5, 0));
+        assert_eq!(
+            PerfWidget::update_line(&stats).unwrap(),
+            "Slowest update: weather 4.50 ms"
+        );
+
+        stats
+            .widget_updates
+            .insert("stocks".into(), updates(0.2, 3));
+        assert_eq!(PerfWidget::update_line(&stats).unwrap(), "Failing: stocks");
+
+        stats.workers.insert(
+            "widget-runtime".into(),
+            crate::metrics::WorkerStats::default(),
+        );
+        assert_eq!(
+            PerfWidget::update_line(&stats).unwrap(),
+            "Stalled: widget-runtime"
+        );
+    }
+
+    #[test]
+    fn test_perf_factory() {
+        let factory = PerfWidgetFactory;
+        let widget = factory.create(&factory.default_config()).unwrap();
+        assert_eq!(widget.info().id, "perf");
+
+        let mut invalid = toml::Table::new();
+        invalid.insert("show_memory".to_string(), toml::Value::Integer(1));
+        assert!(factory.validate_config(&invalid).is_err());
+    }
+}
@@ -2,14 +2,26 @@
 //!
 //! This widget implements a Pomodoro timer with work/break cycles.
 //! It supports configurable durations and auto-transitions between states.
+//! Completed work sessions and the running session are kept in a
+//! [`PomodoroLog`], so the daily and weekly counts and the timer itself
+//! survive restarts and config reloads.
 
 use std::time::{Duration, Instant};
 
 use anyhow::Context;
-use tracing::debug;
+use chrono::Local;
+use tracing::{debug, warn};
 
 use super::registry::DynWidgetFactory;
-use super::traits::{FontSize, Widget, WidgetContent, WidgetInfo};
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetMetadata};
+use super::traits::{
+    FontSize, MouseButton, SoundCue, Widget, WidgetAction, WidgetContent, WidgetInfo,
+};
+use crate::state::{PomodoroLog, PomodoroSession};
+
+/// How long before a session ends the screen is kept awake, with
+/// `inhibit_idle` set
+const INHIBIT_IDLE_WINDOW: Duration = Duration::from_secs(5 * 60);
 
 /// Pomodoro timer states
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -44,6 +56,26 @@ impl PomodoroState {
             PomodoroState::LongBreak => "Break",
         }
     }
+
+    /// Name the state is saved under
+    fn key(&self) -> &'static str {
+        match self {
+            PomodoroState::Idle => "idle",
+            PomodoroState::Working => "working",
+            PomodoroState::ShortBreak => "short_break",
+            PomodoroState::LongBreak => "long_break",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "idle" => Some(PomodoroState::Idle),
+            "working" => Some(PomodoroState::Working),
+            "short_break" => Some(PomodoroState::ShortBreak),
+            "long_break" => Some(PomodoroState::LongBreak),
+            _ => None,
+        }
+    }
 }
 
 /// Pomodoro timer widget
@@ -68,6 +100,14 @@ pub struct PomodoroWidget {
     auto_start_work: bool,
     /// Last update time
     last_update: Instant,
+    /// Sound to play for a session that just ended
+    sound_cue: Option<SoundCue>,
+    /// Completed sessions and the saved running session
+    log: PomodoroLog,
+    /// Whether to show today's and this week's completed sessions
+    show_stats: bool,
+    /// Keep the screen awake while a session is about to end
+    inhibit_idle: bool,
 }
 
 impl PomodoroWidget {
@@ -91,9 +131,66 @@ impl PomodoroWidget {
             auto_start_breaks,
             auto_start_work,
             last_update: Instant::now(),
+            sound_cue: None,
+            log: PomodoroLog::default(),
+            show_stats: false,
+            inhibit_idle: false,
         }
     }
 
+    /// Keep the screen awake during the last minutes of each session, so
+    /// its end isn't missed behind a blank screen
+    pub fn with_inhibit_idle(mut self, inhibit_idle: bool) -> Self {
+        self.inhibit_idle = inhibit_idle;
+        self
+    }
+
+    /// Keep statistics and the running session in `log`
+    ///
+    /// The session saved in the log is resumed, with the time that passed
+    /// in between counted against it.
+    pub fn with_log(mut self, log: PomodoroLog, show_stats: bool) -> Self {
+        if let Some(session) = log.session() {
+            if let Some(state) = PomodoroState::from_key(&session.phase) {
+                self.state = state;
+                self.completed_pomodoros = session.completed;
+                self.state_start = session.started_at.map(|started_at| {
+                    let elapsed = (Local::now().timestamp() - started_at).max(0) as u64;
+                    Instant::now()
+                        .checked_sub(Duration::from_secs(elapsed))
+                        .unwrap_or_else(Instant::now)
+                });
+                debug!(state = ?state, completed = session.completed, "Resumed pomodoro session");
+            }
+        }
+        self.log = log;
+        self.show_stats = show_stats;
+        self
+    }
+
+    /// Save the running session so it can be resumed
+    fn save_session(&mut self) {
+        let session =
+            (self.state != PomodoroState::Idle || self.completed_pomodoros > 0).then(|| {
+                PomodoroSession {
+                    phase: self.state.key().to_string(),
+                    started_at: self
+                        .state_start
+                        .map(|start| Local::now().timestamp() - start.elapsed().as_secs() as i64),
+                    completed: self.completed_pomodoros,
+                }
+            });
+        if let Err(e) = self.log.set_session(session) {
+            warn!(error = %e, "Failed to save pomodoro session");
+        }
+    }
+
+    /// Work sessions completed today and this week
+    pub fn stats(&self) -> (usize, usize) {
+        let now = Local::now();
+        (self.log.today(now), self.log.this_week(now))
+    }
+
     /// Get the duration for the current state in seconds
     fn current_duration(&self) -> u64 {
         match self.state {
@@ -144,6 +241,9 @@ impl PomodoroWidget {
             PomodoroState::Working => {
                 // Complete a pomodoro
                 self.completed_pomodoros += 1;
+                if let Err(e) = self.log.record_completed(Local::now()) {
+                    warn!(error = %e, "Failed to record completed pomodoro");
+                }
                 debug!(
                     completed = self.completed_pomodoros,
                     "Completed work session"
@@ -180,6 +280,7 @@ impl PomodoroWidget {
             self.state_start = None;
             debug!(state = ?next_state, "Waiting for manual start");
         }
+        self.save_session();
     }
 
     /// Start the current state (manual start)
@@ -191,6 +292,7 @@ impl PomodoroWidget {
         if self.state_start.is_none() {
             self.state_start = Some(Instant::now());
             debug!(state = ?self.state, "Manually started");
+            self.save_session();
         }
     }
 
@@ -200,6 +302,7 @@ impl PomodoroWidget {
         self.state_start = None;
         self.completed_pomodoros = 0;
         debug!("Timer reset");
+        self.save_session();
     }
 
     /// Format the display string
@@ -260,7 +363,7 @@ impl Widget for PomodoroWidget {
         WidgetInfo {
             id: "pomodoro",
             name: "Pomodoro Timer",
-            preferred_height: 40.0,
+            preferred_height: if self.show_stats { 64.0 } else { 40.0 },
             min_height: 30.0,
             expand: false,
         }
@@ -270,15 +373,58 @@ impl Widget for PomodoroWidget {
         // Check if current state is complete and transition if needed
         if self.is_state_complete() {
             self.transition_to_next_state();
+            self.sound_cue = Some(SoundCue::Alarm);
         }
 
         self.last_update = Instant::now();
     }
 
+    fn take_sound_cue(&mut self) -> Option<SoundCue> {
+        self.sound_cue.take()
+    }
+
+    fn inhibits_idle(&self) -> bool {
+        self.inhibit_idle
+            && self.state != PomodoroState::Idle
+            && self.state_start.is_some()
+            && self.remaining() <= INHIBIT_IDLE_WINDOW
+    }
+
     fn content(&self) -> WidgetContent {
-        WidgetContent::Text {
-            text: self.display_string(),
-            size: FontSize::Medium,
+        if !self.show_stats {
+            return WidgetContent::Text {
+                text: self.display_string(),
+                size: FontSize::Medium,
+            };
+        }
+        let (today, week) = self.stats();
+        WidgetContent::MultiLine {
+            lines: vec![
+                (self.display_string(), FontSize::Medium),
+                (
+                    format!("Today: {} · Week: {}", today, week),
+                    FontSize::Small,
+                ),
+            ],
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn on_click(&mut self, button: MouseButton, _x: f32, _y: f32) -> Option<WidgetAction> {
+        match button {
+            // Start the session, or a break that isn't auto-started
+            MouseButton::Left => {
+                self.start();
+                Some(WidgetAction::Toggle)
+            }
+            MouseButton::Right => {
+                self.reset();
+                Some(WidgetAction::Toggle)
+            }
+            _ => None,
         }
     }
 
@@ -300,6 +446,14 @@ impl DynWidgetFactory for PomodoroWidgetFactory {
         "pomodoro"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Pomodoro",
+            "Focus timer alternating work and break periods",
+            "appointment-soon-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> anyhow::Result<Box<dyn Widget>> {
         let work_duration = config
             .get("work_duration")
@@ -334,6 +488,16 @@ impl DynWidgetFactory for PomodoroWidgetFactory {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        let show_stats = config
+            .get("show_stats")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let inhibit_idle = config
+            .get("inhibit_idle")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
         debug!(
             work_duration = work_duration / 60,
             short_break = short_break / 60,
@@ -342,14 +506,18 @@ impl DynWidgetFactory for PomodoroWidgetFactory {
             "Creating PomodoroWidget"
         );
 
-        Ok(Box::new(PomodoroWidget::new(
-            work_duration,
-            short_break,
-            long_break,
-            pomodoros_until_long_break,
-            auto_start_breaks,
-            auto_start_work,
-        )))
+        Ok(Box::new(
+            PomodoroWidget::new(
+                work_duration,
+                short_break,
+                long_break,
+                pomodoros_until_long_break,
+                auto_start_breaks,
+                auto_start_work,
+            )
+            .with_log(PomodoroLog::load(), show_stats)
+            .with_inhibit_idle(inhibit_idle),
+        ))
     }
 
     fn default_config(&self) -> toml::Table {
@@ -363,6 +531,7 @@ impl DynWidgetFactory for PomodoroWidgetFactory {
         );
         config.insert("auto_start_breaks".to_string(), toml::Value::Boolean(true));
         config.insert("auto_start_work".to_string(), toml::Value::Boolean(false));
+        config.insert("show_stats".to_string(), toml::Value::Boolean(true));
         config
     }
 
@@ -407,8 +576,74 @@ impl DynWidgetFactory for PomodoroWidgetFactory {
             }
         }
 
+        if let Some(show_stats) = config.get("show_stats") {
+            show_stats
+                .as_bool()
+                .context("'show_stats' must be a boolean")?;
+        }
+
+        if let Some(inhibit_idle) = config.get("inhibit_idle") {
+            inhibit_idle
+                .as_bool()
+                .context("'inhibit_idle' must be a boolean")?;
+        }
+
         Ok(())
     }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new(
+                "work_duration",
+                "Work (minutes)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(ConfigField::new(
+                "short_break",
+                "Short break (minutes)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(ConfigField::new(
+                "long_break",
+                "Long break (minutes)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(ConfigField::new(
+                "pomodoros_until_long_break",
+                "Pomodoros until long break",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(ConfigField::new(
+                "auto_start_breaks",
+                "Auto-start breaks",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "auto_start_work",
+                "Auto-start work",
+                FieldKind::Bool,
+            ))
+            .with_field(
+                ConfigField::new("show_stats", "Show statistics", FieldKind::Bool)
+                    .with_description("Pomodoros completed today and this week"),
+            )
+            .with_field(
+                ConfigField::new("inhibit_idle", "Keep screen awake", FieldKind::Bool)
+                    .with_description("During the last 5 minutes of each session"),
+            )
+    }
 }
 
 #[cfg(test)]
@@ -423,6 +658,23 @@ mod tests {
         assert_eq!(widget.info().id, "pomodoro");
     }
 
+    #[test]
+    fn test_pomodoro_inhibit_idle() {
+        let mut widget =
+            PomodoroWidget::new(25 * 60, 5 * 60, 15 * 60, 4, true, false).with_inhibit_idle(true);
+        assert!(!widget.inhibits_idle());
+
+        widget.start();
+        assert!(!widget.inhibits_idle());
+
+        // Four minutes left
+        widget.state_start = Instant::now().checked_sub(Duration::from_secs(21 * 60));
+        assert!(widget.inhibits_idle());
+
+        widget.reset();
+        assert!(!widget.inhibits_idle());
+    }
+
     #[test]
     fn test_pomodoro_start() {
         let mut widget = PomodoroWidget::new(25 * 60, 5 * 60, 15 * 60, 4, true, false);
@@ -461,6 +713,10 @@ mod tests {
 
         assert_eq!(widget.state, PomodoroState::ShortBreak);
         assert_eq!(widget.completed_pomodoros, 1);
+
+        // The end of the session is announced once
+        assert_eq!(widget.take_sound_cue(), Some(SoundCue::Alarm));
+        assert_eq!(widget.take_sound_cue(), None);
     }
 
     #[test]
@@ -565,6 +821,32 @@ mod tests {
         assert!(factory.validate_config(&invalid).is_err());
     }
 
+    #[test]
+    fn test_pomodoro_resumes_from_log() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("pomodoro.toml");
+
+        let mut widget = PomodoroWidget::new(1, 60, 60, 4, true, false)
+            .with_log(PomodoroLog::load_from(&path), true);
+        widget.start();
+        std::thread::sleep(Duration::from_secs(2));
+        widget.update();
+        assert_eq!(widget.state, PomodoroState::ShortBreak);
+        assert_eq!(widget.stats(), (1, 1));
+
+        // A restart picks up the running break and the statistics
+        let widget = PomodoroWidget::new(1, 60, 60, 4, true, false)
+            .with_log(PomodoroLog::load_from(&path), true);
+        assert_eq!(widget.state, PomodoroState::ShortBreak);
+        assert_eq!(widget.completed_pomodoros, 1);
+        assert!(widget.remaining().as_secs() > 50);
+        assert_eq!(widget.stats(), (1, 1));
+        match widget.content() {
+            WidgetContent::MultiLine { lines } => assert_eq!(lines[1].0, "Today: 1 · Week: 1"),
+            other => panic!("expected multi-line content, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_remaining_time() {
         let mut widget = PomodoroWidget::new(60, 30, 90, 4, true, false);
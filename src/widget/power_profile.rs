@@ -0,0 +1,382 @@
+//! Power profile widget
+//!
+//! Shows the active power-profiles-daemon profile (performance, balanced
+//! or power-saver) with an icon for each, and switches to the next profile
+//! on click. The profile is read and set over the system bus, the same way
+//! the COSMIC battery applet does it.
+
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+use zbus::zvariant::OwnedValue;
+
+use super::registry::DynWidgetFactory;
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{FontSize, MouseButton, Widget, WidgetAction, WidgetContent, WidgetInfo};
+use crate::update::tasks::{self, TaskOutput};
+use crate::update::Source;
+
+const DESTINATION: &str = "net.hadess.PowerProfiles";
+const PATH: &str = "/net/hadess/PowerProfiles";
+const INTERFACE: &str = "net.hadess.PowerProfiles";
+
+/// Profiles in the order clicking cycles through them
+const CYCLE: [&str; 3] = ["power-saver", "balanced", "performance"];
+
+/// Active and available profiles reported by the daemon
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PowerProfiles {
+    /// Profile in use, e.g. "balanced"
+    pub active: String,
+    /// Profiles this machine supports, in cycling order
+    pub available: Vec<String>,
+}
+
+impl PowerProfiles {
+    /// The profile after (or before, going `backwards`) the active one
+    pub fn next(&self, backwards: bool) -> Option<&str> {
+        let count = self.available.len();
+        if count < 2 {
+            return None;
+        }
+        let current = self.available.iter().position(|p| *p == self.active)?;
+        let next = if backwards {
+            (current + count - 1) % count
+        } else {
+            (current + 1) % count
+        };
+        Some(&self.available[next])
+    }
+}
+
+/// Widget showing and cycling the power profile
+pub struct PowerProfileWidget {
+    show_label: bool,
+    update_interval: Duration,
+    profiles: Option<PowerProfiles>,
+    error_message: Option<String>,
+}
+
+impl PowerProfileWidget {
+    /// Create a widget checking the profile every `update_interval`
+    pub fn new(update_interval: Duration) -> Self {
+        Self {
+            show_label: true,
+            update_interval,
+            profiles: None,
+            error_message: None,
+        }
+    }
+
+    /// Show the profile's name beside its icon
+    pub fn with_label(mut self, show_label: bool) -> Self {
+        self.show_label = show_label;
+        self
+    }
+
+    /// Record what the daemon reported
+    pub fn set_profiles(&mut self, profiles: PowerProfiles) {
+        self.profiles = Some(profiles);
+        self.error_message = None;
+    }
+
+    /// Switch to the next profile, returning its name
+    fn cycle(&mut self, backwards: bool) -> Option<String> {
+        let profiles = self.profiles.as_mut()?;
+        let next = profiles.next(backwards)?.to_string();
+        // Shown right away; the next poll confirms it
+        profiles.active = next.clone();
+        Some(next)
+    }
+}
+
+impl Widget for PowerProfileWidget {
+    fn info(&self) -> WidgetInfo {
+        WidgetInfo {
+            id: "power_profile",
+            name: "Power Profile",
+            preferred_height: 48.0,
+            min_height: 32.0,
+            expand: false,
+        }
+    }
+
+    fn update(&mut self) {
+        // Profiles arrive through apply_fetch from the data service
+    }
+
+    fn content(&self) -> WidgetContent {
+        let Some(profiles) = &self.profiles else {
+            return self
+                .error_message
+                .as_deref()
+                .map_or(WidgetContent::Empty, WidgetContent::error);
+        };
+        WidgetContent::IconText {
+            icon: format!("power-profile-{}", profiles.active),
+            text: if self.show_label {
+                profile_label(&profiles.active)
+            } else {
+                String::new()
+            },
+            size: FontSize::Medium,
+        }
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.update_interval
+    }
+
+    fn is_ready(&self) -> bool {
+        self.profiles.is_some() || self.error_message.is_some()
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error_message.as_deref()
+    }
+
+    fn data_source(&self) -> Option<Source> {
+        Some(Source::blocking(self.update_interval, read_profiles))
+    }
+
+    fn apply_fetch(&mut self, result: Result<TaskOutput>) {
+        match tasks::take_output::<PowerProfiles>(result) {
+            Ok(profiles) => self.set_profiles(profiles),
+            Err(e) => {
+                warn!(error = %e, "Failed to read power profile");
+                self.error_message = Some("power-profiles-daemon not available".to_string());
+            }
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn on_click(&mut self, button: MouseButton, _x: f32, _y: f32) -> Option<WidgetAction> {
+        let backwards = match button {
+            MouseButton::Left => false,
+            MouseButton::Right => true,
+            _ => return None,
+        };
+        let profile = self.cycle(backwards)?;
+        info!(profile = %profile, "Switching power profile");
+
+        // Setting the property waits on polkit, so keep it off the event loop
+        let spawned = thread::Builder::new()
+            .name("power-profile".to_string())
+            .spawn(move || {
+                if let Err(e) = set_profile(&profile) {
+                    warn!(error = %e, profile = %profile, "Failed to switch power profile");
+                }
+            });
+        if let Err(e) = spawned {
+            warn!(error = %e, "Failed to spawn power profile switch");
+        }
+
+        Some(if backwards {
+            WidgetAction::PreviousItem
+        } else {
+            WidgetAction::NextItem
+        })
+    }
+}
+
+/// "power-saver" → "Power Saver"
+fn profile_label(profile: &str) -> String {
+    profile
+        .split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn proxy(connection: &zbus::blocking::Connection) -> Result<zbus::blocking::Proxy<'static>> {
+    zbus::blocking::Proxy::new(connection, DESTINATION, PATH, INTERFACE)
+        .context("Failed to create power profiles proxy")
+}
+
+/// Read the active and available profiles from power-profiles-daemon
+fn read_profiles() -> Result<PowerProfiles> {
+    let connection =
+        zbus::blocking::Connection::system().context("Failed to connect to the system bus")?;
+    let proxy = proxy(&connection)?;
+
+    let active: String = proxy
+        .get_property("ActiveProfile")
+        .context("Failed to read ActiveProfile")?;
+    let profiles: Vec<HashMap<String, OwnedValue>> = proxy
+        .get_property("Profiles")
+        .context("Failed to read Profiles")?;
+
+    let mut available: Vec<String> = profiles
+        .into_iter()
+        .filter_map(|mut profile| String::try_from(profile.remove("Profile")?).ok())
+        .collect();
+    available.sort_by_key(|name| CYCLE.iter().position(|p| p == name));
+    Ok(PowerProfiles { active, available })
+}
+
+/// Ask power-profiles-daemon to switch to `profile`
+fn set_profile(profile: &str) -> Result<()> {
+    let connection =
+        zbus::blocking::Connection::system().context("Failed to connect to the system bus")?;
+    proxy(&connection)?
+        .set_property("ActiveProfile", profile)
+        .context("Failed to set ActiveProfile")?;
+    Ok(())
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+/// Factory for PowerProfileWidget
+pub struct PowerProfileWidgetFactory;
+
+impl DynWidgetFactory for PowerProfileWidgetFactory {
+    fn widget_type(&self) -> &'static str {
+        "power_profile"
+    }
+
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Power Profile",
+            "The active power profile; click to switch",
+            "power-profile-balanced-symbolic",
+        )
+    }
+
+    fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
+        let show_label = config
+            .get("show_label")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let update_interval = config
+            .get("update_interval")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(5)
+            .max(1) as u64;
+
+        info!(
+            update_interval = update_interval,
+            "Creating PowerProfileWidget"
+        );
+
+        Ok(Box::new(
+            PowerProfileWidget::new(Duration::from_secs(update_interval)).with_label(show_label),
+        ))
+    }
+
+    fn default_config(&self) -> toml::Table {
+        let mut config = toml::Table::new();
+        config.insert("show_label".to_string(), toml::Value::Boolean(true));
+        config.insert("update_interval".to_string(), toml::Value::Integer(5));
+        config
+    }
+
+    fn validate_config(&self, config: &toml::Table) -> Result<()> {
+        if let Some(show_label) = config.get("show_label") {
+            show_label
+                .as_bool()
+                .context("'show_label' must be a boolean")?;
+        }
+
+        if let Some(interval) = config.get("update_interval") {
+            let val = interval
+                .as_integer()
+                .context("'update_interval' must be an integer")?;
+            if val < 1 {
+                anyhow::bail!("'update_interval' must be at least 1 second");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_dbus().with_interactive()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new(
+                "show_label",
+                "Show profile name",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "update_interval",
+                "Update interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profiles(active: &str, available: &[&str]) -> PowerProfiles {
+        PowerProfiles {
+            active: active.to_string(),
+            available: available.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_next_profile() {
+        let all = profiles("balanced", &CYCLE);
+        assert_eq!(all.next(false), Some("performance"));
+        assert_eq!(all.next(true), Some("power-saver"));
+        assert_eq!(
+            profiles("performance", &CYCLE).next(false),
+            Some("power-saver")
+        );
+
+        // Machines without a performance profile only have two
+        let two = profiles("balanced", &["power-saver", "balanced"]);
+        assert_eq!(two.next(false), Some("power-saver"));
+        assert_eq!(profiles("balanced", &["balanced"]).next(false), None);
+    }
+
+    #[test]
+    fn test_content() {
+        let mut widget = PowerProfileWidget::new(Duration::from_secs(5));
+        assert_eq!(widget.content(), WidgetContent::Empty);
+
+        widget.set_profiles(profiles("power-saver", &CYCLE));
+        assert_eq!(
+            widget.content(),
+            WidgetContent::IconText {
+                icon: "power-profile-power-saver".to_string(),
+                text: "Power Saver".to_string(),
+                size: FontSize::Medium,
+            }
+        );
+        assert_eq!(widget.cycle(false).as_deref(), Some("balanced"));
+    }
+
+    #[test]
+    fn test_factory_validation() {
+        let factory = PowerProfileWidgetFactory;
+        assert!(factory.validate_config(&factory.default_config()).is_ok());
+
+        let mut config = toml::Table::new();
+        config.insert("update_interval".to_string(), toml::Value::Integer(0));
+        assert!(factory.validate_config(&config).is_err());
+    }
+}
@@ -0,0 +1,370 @@
+//! Price alerts for the crypto and stocks widgets
+//!
+//! An alert fires when a quote crosses its threshold: a price above or below
+//! a level, or a daily move of at least some percent either way. It fires
+//! once per crossing and re-arms when the quote is back on the other side.
+//! Firing hands a reminder to the update scheduler, which shows the desktop
+//! notification and plays the sound, and highlights the symbol for a while.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local};
+use tracing::debug;
+
+use super::schema::{ConfigField, ConfigSchema, FieldKind};
+use super::traits::TextSegment;
+use crate::text::FontWeight;
+use crate::theme::Color;
+use crate::update::Reminder;
+
+/// Default color of a symbol whose alert fired (amber)
+const HIGHLIGHT_COLOR: [u8; 4] = [255, 193, 7, 255];
+
+/// How long fired alerts are handed to the scheduler, in minutes
+const FIRED_RETENTION_MINUTES: i64 = 10;
+
+/// What an alert watches for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlertCondition {
+    /// The price is above this level
+    Above(f64),
+    /// The price is below this level
+    Below(f64),
+    /// The daily change is at least this many percent, up or down
+    Move(f64),
+}
+
+impl AlertCondition {
+    fn holds(&self, price: f64, change_percent: Option<f64>) -> bool {
+        match *self {
+            Self::Above(level) => price > level,
+            Self::Below(level) => price < level,
+            Self::Move(percent) => change_percent.is_some_and(|change| change.abs() >= percent),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Above(level) => format!("above {}", level),
+            Self::Below(level) => format!("below {}", level),
+            Self::Move(percent) => format!("moved {}%", percent),
+        }
+    }
+}
+
+/// An alert on one symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceAlert {
+    /// Symbol or coin ID, matched case-insensitively
+    pub symbol: String,
+    /// When the alert fires
+    pub condition: AlertCondition,
+}
+
+impl PriceAlert {
+    /// Create an alert on `symbol`
+    pub fn new(symbol: impl Into<String>, condition: AlertCondition) -> Self {
+        Self {
+            symbol: symbol.into().to_uppercase(),
+            condition,
+        }
+    }
+
+    /// Read the alerts of one `{ symbol, above, below, move_percent }` table
+    fn from_config(value: &toml::Value) -> Result<Vec<Self>> {
+        let table = value
+            .as_table()
+            .context("each of 'alerts' must be a table with a 'symbol'")?;
+        let symbol = table
+            .get("symbol")
+            .and_then(|v| v.as_str())
+            .context("each of 'alerts' needs a 'symbol' string")?;
+
+        let number = |key: &str| -> Result<Option<f64>> {
+            table
+                .get(key)
+                .map(|v| {
+                    v.as_float()
+                        .or_else(|| v.as_integer().map(|i| i as f64))
+                        .with_context(|| {
+                            format!("'{}' of alert '{}' must be a number", key, symbol)
+                        })
+                })
+                .transpose()
+        };
+
+        let mut alerts = Vec::new();
+        if let Some(level) = number("above")? {
+            alerts.push(Self::new(symbol, AlertCondition::Above(level)));
+        }
+        if let Some(level) = number("below")? {
+            alerts.push(Self::new(symbol, AlertCondition::Below(level)));
+        }
+        if let Some(percent) = number("move_percent")? {
+            if percent <= 0.0 {
+                bail!("'move_percent' of alert '{}' must be positive", symbol);
+            }
+            alerts.push(Self::new(symbol, AlertCondition::Move(percent)));
+        }
+        if alerts.is_empty() {
+            bail!(
+                "alert '{}' needs 'above', 'below' or 'move_percent'",
+                symbol
+            );
+        }
+        Ok(alerts)
+    }
+}
+
+/// The alerts of one widget and what they fired
+#[derive(Debug, Clone)]
+pub struct PriceAlerts {
+    alerts: Vec<PriceAlert>,
+    /// Per alert, whether it fires on the next crossing
+    armed: Vec<bool>,
+    sound: bool,
+    highlight: Duration,
+    color: [u8; 4],
+    /// Symbols highlighted until the given time
+    highlighted: HashMap<String, Instant>,
+    fired: Vec<Reminder>,
+}
+
+impl PriceAlerts {
+    /// Watch `alerts`, with sound and a minute of highlight when they fire
+    pub fn new(alerts: Vec<PriceAlert>) -> Self {
+        Self {
+            armed: vec![true; alerts.len()],
+            alerts,
+            sound: true,
+            highlight: Duration::from_secs(60),
+            color: HIGHLIGHT_COLOR,
+            highlighted: HashMap::new(),
+            fired: Vec::new(),
+        }
+    }
+
+    /// Read the `alerts` array and the `alert_*` options
+    pub fn from_config(config: &toml::Table) -> Result<Self> {
+        let mut alerts = Vec::new();
+        if let Some(value) = config.get("alerts") {
+            for alert in value
+                .as_array()
+                .context("'alerts' must be an array of tables")?
+            {
+                alerts.extend(PriceAlert::from_config(alert)?);
+            }
+        }
+
+        let mut parsed = Self::new(alerts);
+        if let Some(sound) = config.get("alert_sound") {
+            parsed.sound = sound.as_bool().context("'alert_sound' must be a boolean")?;
+        }
+        if let Some(seconds) = config.get("alert_highlight") {
+            let seconds = seconds
+                .as_integer()
+                .context("'alert_highlight' must be an integer")?;
+            if seconds < 0 {
+                bail!("'alert_highlight' must not be negative");
+            }
+            parsed.highlight = Duration::from_secs(seconds as u64);
+        }
+        if let Some(color) = config.get("alert_color") {
+            let color = color.as_str().context("'alert_color' must be a string")?;
+            parsed.color = Color::from_hex(color)
+                .with_context(|| format!("'alert_color' must be a hex color, got '{}'", color))?
+                .to_array();
+        }
+        Ok(parsed)
+    }
+
+    /// Add the `alert_*` options to a widget's schema
+    pub fn extend_schema(schema: ConfigSchema) -> ConfigSchema {
+        schema
+            .with_field(
+                ConfigField::new("alert_sound", "Alert sound", FieldKind::Bool)
+                    .with_description("Play the notification sound when an alert fires"),
+            )
+            .with_field(
+                ConfigField::new(
+                    "alert_highlight",
+                    "Alert highlight (seconds)",
+                    FieldKind::Integer {
+                        min: Some(0),
+                        max: None,
+                    },
+                )
+                .with_description("How long a symbol stays highlighted after its alert fires"),
+            )
+            .with_field(
+                ConfigField::new("alert_color", "Alert color", FieldKind::Text)
+                    .with_description("Hex color of a highlighted symbol"),
+            )
+    }
+
+    /// Whether there are no alerts
+    pub fn is_empty(&self) -> bool {
+        self.alerts.is_empty()
+    }
+
+    /// Check a new quote for `symbol`, firing the alerts it crosses
+    pub fn check(&mut self, symbol: &str, price: f64, change_percent: Option<f64>) {
+        self.check_at(symbol, price, change_percent, Local::now());
+    }
+
+    fn check_at(
+        &mut self,
+        symbol: &str,
+        price: f64,
+        change_percent: Option<f64>,
+        now: DateTime<Local>,
+    ) {
+        let cutoff = now - chrono::Duration::minutes(FIRED_RETENTION_MINUTES);
+        self.fired.retain(|reminder| reminder.at > cutoff);
+
+        for (alert, armed) in self.alerts.iter().zip(self.armed.iter_mut()) {
+            if !alert.symbol.eq_ignore_ascii_case(symbol) {
+                continue;
+            }
+            let holds = alert.condition.holds(price, change_percent);
+            if holds && *armed {
+                debug!(symbol = %alert.symbol, condition = ?alert.condition, price, "Price alert fired");
+                let body = match change_percent {
+                    Some(change) => format!("Now {:.2} ({:+.2}%)", price, change),
+                    None => format!("Now {:.2}", price),
+                };
+                self.fired.push(Reminder {
+                    id: format!(
+                        "alert:{} {}@{}",
+                        alert.symbol,
+                        alert.condition.describe(),
+                        now.timestamp()
+                    ),
+                    at: now,
+                    summary: format!("{} {}", alert.symbol, alert.condition.describe()),
+                    body,
+                    sound: self.sound,
+                });
+                self.highlighted
+                    .insert(alert.symbol.clone(), Instant::now() + self.highlight);
+            }
+            *armed = !holds;
+        }
+    }
+
+    /// Color to draw `symbol` in while its alert is highlighted
+    pub fn highlight_color(&self, symbol: &str) -> Option<[u8; 4]> {
+        self.highlighted
+            .get(&symbol.to_uppercase())
+            .filter(|until| Instant::now() < **until)
+            .map(|_| self.color)
+    }
+
+    /// Whether any symbol is highlighted
+    pub fn any_highlighted(&self) -> bool {
+        let now = Instant::now();
+        self.highlighted.values().any(|until| now < *until)
+    }
+
+    /// Alerts that fired recently, for [`Widget::reminders`]
+    ///
+    /// [`Widget::reminders`]: super::traits::Widget::reminders
+    pub fn reminders(&self) -> Vec<Reminder> {
+        self.fired.clone()
+    }
+}
+
+/// One line of `parts` joined by " | ", with highlighted parts in color
+pub fn highlighted_line(parts: Vec<(String, Option<[u8; 4]>)>) -> Vec<TextSegment> {
+    let mut segments = Vec::new();
+    for (i, (text, color)) in parts.into_iter().enumerate() {
+        if i > 0 {
+            segments.push(TextSegment::regular(" | "));
+        }
+        segments.push(match color {
+            Some(color) => TextSegment::with_color(text, FontWeight::Bold, color),
+            None => TextSegment::regular(text),
+        });
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_alert_fires_once_per_crossing() {
+        let now = Local.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap();
+        let mut alerts = PriceAlerts::new(vec![
+            PriceAlert::new("btc", AlertCondition::Above(70000.0)),
+            PriceAlert::new("BTC", AlertCondition::Move(5.0)),
+        ]);
+
+        alerts.check_at("BTC", 69000.0, Some(1.0), now);
+        assert!(alerts.reminders().is_empty());
+        assert!(alerts.highlight_color("btc").is_none());
+
+        alerts.check_at("BTC", 70500.0, Some(2.0), now);
+        let fired = alerts.reminders();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].summary, "BTC above 70000");
+        assert_eq!(fired[0].body, "Now 70500.00 (+2.00%)");
+        assert_eq!(alerts.highlight_color("btc"), Some(HIGHLIGHT_COLOR));
+
+        // Staying above doesn't fire again; dropping and rising again does
+        alerts.check_at("BTC", 71000.0, Some(2.0), now);
+        assert_eq!(alerts.reminders().len(), 1);
+        alerts.check_at("BTC", 69500.0, Some(2.0), now);
+        alerts.check_at(
+            "BTC",
+            70100.0,
+            Some(-6.0),
+            now + chrono::Duration::minutes(1),
+        );
+        assert_eq!(alerts.reminders().len(), 3);
+
+        // Old alerts are no longer handed over
+        alerts.check_at("BTC", 70100.0, Some(-6.0), now + chrono::Duration::hours(1));
+        assert!(alerts.reminders().is_empty());
+    }
+
+    #[test]
+    fn test_alerts_from_config() {
+        let config: toml::Table = toml::from_str(
+            r##"
+            alert_sound = false
+            alert_color = "#f38ba8"
+            alerts = [
+                { symbol = "AAPL", above = 200, below = 150.5 },
+                { symbol = "bitcoin", move_percent = 5 },
+            ]
+            "##,
+        )
+        .unwrap();
+        let alerts = PriceAlerts::from_config(&config).unwrap();
+        assert_eq!(
+            alerts.alerts,
+            vec![
+                PriceAlert::new("AAPL", AlertCondition::Above(200.0)),
+                PriceAlert::new("AAPL", AlertCondition::Below(150.5)),
+                PriceAlert::new("BITCOIN", AlertCondition::Move(5.0)),
+            ]
+        );
+        assert!(!alerts.sound);
+        assert_eq!(alerts.color, [0xf3, 0x8b, 0xa8, 255]);
+
+        for bad in [
+            r#"alerts = [{ symbol = "AAPL" }]"#,
+            r#"alerts = [{ above = 3 }]"#,
+            r#"alerts = [{ symbol = "AAPL", move_percent = 0 }]"#,
+            r#"alert_color = "red""#,
+        ] {
+            let config: toml::Table = toml::from_str(bad).unwrap();
+            assert!(PriceAlerts::from_config(&config).is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,662 @@
+//! Stock quote providers
+//!
+//! The stocks widget fetches through the [`QuoteProvider`] trait, so quotes
+//! can come from Yahoo Finance (no API key needed), Finnhub or Alpha
+//! Vantage. [`QuoteProviders`] tries the configured providers in order. A
+//! provider that rate-limits is skipped until its limit lifts, and the next
+//! one answers in the meantime.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+
+use super::schema::{ConfigField, ConfigSchema, FieldKind};
+use super::stocks::{ExtendedHours, Session, StockData};
+use crate::error::QuoteError;
+
+/// How long to skip a rate-limited provider that didn't say how long
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Result type for quote fetches
+pub type QuoteResult<T> = Result<T, QuoteError>;
+
+/// A fetch running against one provider
+pub type QuoteFuture<'a, T> = Pin<Box<dyn Future<Output = QuoteResult<T>> + Send + 'a>>;
+
+/// A source of stock quotes
+pub trait QuoteProvider: Send + Sync {
+    /// Name used in the `providers` option and in logs
+    fn name(&self) -> &'static str;
+
+    /// Latest quote for `symbol`
+    fn quote<'a>(&'a self, symbol: &'a str) -> QuoteFuture<'a, StockData>;
+
+    /// Today's prices for `symbol`, oldest first
+    ///
+    /// Unsupported unless overridden; [`QuoteProviders`] then asks the next
+    /// provider.
+    fn intraday<'a>(&'a self, _symbol: &'a str) -> QuoteFuture<'a, Vec<f64>> {
+        let name = self.name();
+        Box::pin(async move { Err(QuoteError::Unsupported(name)) })
+    }
+}
+
+/// The built-in providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    /// Yahoo Finance
+    Yahoo,
+    /// Finnhub, needs `finnhub_api_key`
+    Finnhub,
+    /// Alpha Vantage, needs `alpha_vantage_api_key`
+    AlphaVantage,
+}
+
+impl ProviderKind {
+    /// Values accepted in the `providers` option
+    pub const NAMES: &'static [&'static str] = &["yahoo", "finnhub", "alpha_vantage"];
+
+    /// Parse a `providers` entry
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "yahoo" => Some(Self::Yahoo),
+            "finnhub" => Some(Self::Finnhub),
+            "alpha_vantage" => Some(Self::AlphaVantage),
+            _ => None,
+        }
+    }
+
+    /// Option holding the provider's API key, if it needs one
+    pub fn key_option(&self) -> Option<&'static str> {
+        match self {
+            Self::Yahoo => None,
+            Self::Finnhub => Some("finnhub_api_key"),
+            Self::AlphaVantage => Some("alpha_vantage_api_key"),
+        }
+    }
+
+    fn build(&self, api_key: String) -> Arc<dyn QuoteProvider> {
+        match self {
+            Self::Yahoo => Arc::new(YahooProvider),
+            Self::Finnhub => Arc::new(FinnhubProvider::new(api_key)),
+            Self::AlphaVantage => Arc::new(AlphaVantageProvider::new(api_key)),
+        }
+    }
+}
+
+/// Providers tried in order until one answers
+pub struct QuoteProviders {
+    providers: Vec<Arc<dyn QuoteProvider>>,
+    /// Rate-limited providers, with when to try them again
+    limited_until: Mutex<HashMap<&'static str, Instant>>,
+}
+
+impl QuoteProviders {
+    /// Try `providers` in the given order
+    pub fn new(providers: Vec<Arc<dyn QuoteProvider>>) -> Self {
+        Self {
+            providers,
+            limited_until: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Yahoo Finance alone, the default
+    pub fn yahoo() -> Self {
+        Self::new(vec![Arc::new(YahooProvider)])
+    }
+
+    /// Read the `providers` option and the API keys the providers need
+    pub fn from_config(config: &toml::Table) -> anyhow::Result<Self> {
+        let names: Vec<&str> = match config.get("providers") {
+            None => vec!["yahoo"],
+            Some(toml::Value::String(name)) => vec![name.as_str()],
+            Some(toml::Value::Array(names)) => names
+                .iter()
+                .map(|name| {
+                    name.as_str()
+                        .ok_or_else(|| anyhow::anyhow!("All providers must be strings"))
+                })
+                .collect::<anyhow::Result<_>>()?,
+            Some(_) => anyhow::bail!("'providers' must be a string or array of strings"),
+        };
+        if names.is_empty() {
+            anyhow::bail!("'providers' array cannot be empty");
+        }
+
+        let mut providers = Vec::new();
+        for name in names {
+            let kind = ProviderKind::from_name(name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "'providers' entries must be one of {}, got '{}'",
+                    ProviderKind::NAMES.join(", "),
+                    name
+                )
+            })?;
+            let api_key = match kind.key_option() {
+                Some(option) => {
+                    let key = config.get(option).and_then(|v| v.as_str()).unwrap_or("");
+                    if key.is_empty() {
+                        anyhow::bail!("'{}' is required for the {} provider", option, name);
+                    }
+                    key.to_string()
+                }
+                None => String::new(),
+            };
+            providers.push(kind.build(api_key));
+        }
+        Ok(Self::new(providers))
+    }
+
+    /// Add the provider options to a widget's schema
+    pub fn extend_schema(schema: ConfigSchema) -> ConfigSchema {
+        schema
+            .with_field(
+                ConfigField::new("providers", "Providers", FieldKind::List).with_description(
+                    "Quote providers to try in order: yahoo, finnhub, alpha_vantage",
+                ),
+            )
+            .with_field(
+                ConfigField::new("finnhub_api_key", "Finnhub API key", FieldKind::Secret)
+                    .with_description("Needed for the finnhub provider"),
+            )
+            .with_field(
+                ConfigField::new(
+                    "alpha_vantage_api_key",
+                    "Alpha Vantage API key",
+                    FieldKind::Secret,
+                )
+                .with_description("Needed for the alpha_vantage provider"),
+            )
+    }
+
+    /// Names of the providers, in order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+
+    /// Latest quote for `symbol` from the first provider that has it
+    pub async fn quote(&self, symbol: &str) -> QuoteResult<StockData> {
+        let mut last_error = QuoteError::NoProvider;
+        for provider in self.available(Instant::now()) {
+            match provider.quote(symbol).await {
+                Ok(data) => return Ok(data),
+                Err(e) => last_error = self.failed(provider.name(), symbol, e),
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Today's prices for `symbol` from the first provider that has them
+    pub async fn intraday(&self, symbol: &str) -> QuoteResult<Vec<f64>> {
+        let mut last_error = QuoteError::NoProvider;
+        for provider in self.available(Instant::now()) {
+            match provider.intraday(symbol).await {
+                Ok(prices) => return Ok(prices),
+                Err(e) => last_error = self.failed(provider.name(), symbol, e),
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Providers that aren't waiting out a rate limit at `now`
+    fn available(&self, now: Instant) -> Vec<Arc<dyn QuoteProvider>> {
+        let mut limited_until = self.limited_until.lock().unwrap();
+        limited_until.retain(|_, until| *until > now);
+        self.providers
+            .iter()
+            .filter(|provider| !limited_until.contains_key(provider.name()))
+            .cloned()
+            .collect()
+    }
+
+    /// Log a failed fetch, and skip the provider for a while if it
+    /// rate-limited
+    fn failed(&self, provider: &'static str, symbol: &str, error: QuoteError) -> QuoteError {
+        match &error {
+            QuoteError::RateLimited { retry_after, .. } => {
+                let cooldown = retry_after.unwrap_or(RATE_LIMIT_COOLDOWN);
+                warn!(
+                    provider,
+                    retry_in_secs = cooldown.as_secs(),
+                    "Quote provider rate-limited, falling back"
+                );
+                self.limited_until
+                    .lock()
+                    .unwrap()
+                    .insert(provider, Instant::now() + cooldown);
+            }
+            QuoteError::Unsupported(_) => {}
+            _ => debug!(provider, symbol, error = %error, "Quote provider failed"),
+        }
+        error
+    }
+}
+
+// ============================================================================
+// Providers
+// ============================================================================
+
+/// Yahoo Finance, free and without an API key
+pub struct YahooProvider;
+
+impl QuoteProvider for YahooProvider {
+    fn name(&self) -> &'static str {
+        "yahoo"
+    }
+
+    fn quote<'a>(&'a self, symbol: &'a str) -> QuoteFuture<'a, StockData> {
+        Box::pin(async move {
+            let url = format!(
+                "https://query1.finance.yahoo.com/v7/finance/quote?symbols={}",
+                symbol
+            );
+            let json = get_json(self.name(), &url).await?;
+            parse_yahoo_quote(symbol, &json)
+        })
+    }
+
+    fn intraday<'a>(&'a self, symbol: &'a str) -> QuoteFuture<'a, Vec<f64>> {
+        Box::pin(async move {
+            let url = format!(
+                "https://query1.finance.yahoo.com/v8/finance/chart/{}?range=1d&interval=5m",
+                symbol
+            );
+            let json = get_json(self.name(), &url).await?;
+            Ok(parse_yahoo_intraday(&json))
+        })
+    }
+}
+
+/// Finnhub, which needs a (free) API key
+pub struct FinnhubProvider {
+    api_key: String,
+}
+
+impl FinnhubProvider {
+    /// Fetch with `api_key`
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+        }
+    }
+}
+
+impl QuoteProvider for FinnhubProvider {
+    fn name(&self) -> &'static str {
+        "finnhub"
+    }
+
+    fn quote<'a>(&'a self, symbol: &'a str) -> QuoteFuture<'a, StockData> {
+        Box::pin(async move {
+            let url = format!(
+                "https://finnhub.io/api/v1/quote?symbol={}&token={}",
+                symbol, self.api_key
+            );
+            let json = get_json(self.name(), &url).await?;
+            parse_finnhub_quote(symbol, &json)
+        })
+    }
+}
+
+/// Alpha Vantage, which needs a (free) API key
+pub struct AlphaVantageProvider {
+    api_key: String,
+}
+
+impl AlphaVantageProvider {
+    /// Fetch with `api_key`
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+        }
+    }
+}
+
+impl QuoteProvider for AlphaVantageProvider {
+    fn name(&self) -> &'static str {
+        "alpha_vantage"
+    }
+
+    fn quote<'a>(&'a self, symbol: &'a str) -> QuoteFuture<'a, StockData> {
+        Box::pin(async move {
+            let url = format!(
+                "https://www.alphavantage.co/query?function=GLOBAL_QUOTE&symbol={}&apikey={}",
+                symbol, self.api_key
+            );
+            let json = get_json(self.name(), &url).await?;
+            parse_alpha_vantage_quote(symbol, &json)
+        })
+    }
+
+    fn intraday<'a>(&'a self, symbol: &'a str) -> QuoteFuture<'a, Vec<f64>> {
+        Box::pin(async move {
+            let url = format!(
+                "https://www.alphavantage.co/query?function=TIME_SERIES_INTRADAY&symbol={}&interval=5min&apikey={}",
+                symbol, self.api_key
+            );
+            let json = get_json(self.name(), &url).await?;
+            parse_alpha_vantage_intraday(&json)
+        })
+    }
+}
+
+/// GET `url` as JSON, turning HTTP 429 into [`QuoteError::RateLimited`]
+async fn get_json(provider: &'static str, url: &str) -> QuoteResult<serde_json::Value> {
+    let response = crate::http::client().get(url).send().await?;
+    let status = response.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs);
+        return Err(QuoteError::RateLimited {
+            provider,
+            retry_after,
+        });
+    }
+    if !status.is_success() {
+        return Err(QuoteError::InvalidResponse(format!(
+            "{} returned error status: {}",
+            provider, status
+        )));
+    }
+    Ok(response.json().await?)
+}
+
+// ============================================================================
+// Response parsing
+// ============================================================================
+
+fn parse_yahoo_quote(symbol: &str, json: &serde_json::Value) -> QuoteResult<StockData> {
+    let result = json["quoteResponse"]["result"]
+        .as_array()
+        .and_then(|arr| arr.first())
+        .ok_or_else(|| QuoteError::SymbolNotFound(symbol.to_string()))?;
+
+    let price = result["regularMarketPrice"]
+        .as_f64()
+        .ok_or_else(|| QuoteError::InvalidResponse(format!("Missing price data for {}", symbol)))?;
+
+    Ok(StockData {
+        symbol: symbol.to_uppercase(),
+        price,
+        change: result["regularMarketChange"].as_f64().unwrap_or(0.0),
+        percent_change: result["regularMarketChangePercent"].as_f64().unwrap_or(0.0),
+        extended: yahoo_extended_hours(result),
+        intraday: Vec::new(),
+    })
+}
+
+/// Read the pre-market or after-hours price from a Yahoo Finance quote
+fn yahoo_extended_hours(quote: &serde_json::Value) -> Option<ExtendedHours> {
+    let (session, prefix) = match quote["marketState"].as_str()? {
+        "PRE" | "PREPRE" => (Session::PreMarket, "preMarket"),
+        "POST" | "POSTPOST" | "CLOSED" => (Session::AfterHours, "postMarket"),
+        _ => return None,
+    };
+    Some(ExtendedHours {
+        session,
+        price: quote[format!("{}Price", prefix)].as_f64()?,
+        percent_change: quote[format!("{}ChangePercent", prefix)]
+            .as_f64()
+            .unwrap_or(0.0),
+    })
+}
+
+/// Read the closing prices from a Yahoo Finance chart, skipping gaps
+fn parse_yahoo_intraday(chart: &serde_json::Value) -> Vec<f64> {
+    chart["chart"]["result"][0]["indicators"]["quote"][0]["close"]
+        .as_array()
+        .map(|closes| closes.iter().filter_map(|v| v.as_f64()).collect())
+        .unwrap_or_default()
+}
+
+fn parse_finnhub_quote(symbol: &str, json: &serde_json::Value) -> QuoteResult<StockData> {
+    // Unknown symbols come back as all zeros, with no change
+    let price = json["c"].as_f64().filter(|price| *price > 0.0);
+    let (Some(price), Some(change)) = (price, json["d"].as_f64()) else {
+        return Err(QuoteError::SymbolNotFound(symbol.to_string()));
+    };
+    Ok(StockData {
+        symbol: symbol.to_uppercase(),
+        price,
+        change,
+        percent_change: json["dp"].as_f64().unwrap_or(0.0),
+        ..Default::default()
+    })
+}
+
+/// Alpha Vantage answers 200 with a note in place of data once over its
+/// limit
+fn alpha_vantage_limited(json: &serde_json::Value) -> QuoteResult<()> {
+    if json.get("Note").is_some() || json.get("Information").is_some() {
+        return Err(QuoteError::RateLimited {
+            provider: "alpha_vantage",
+            retry_after: None,
+        });
+    }
+    if let Some(message) = json["Error Message"].as_str() {
+        return Err(QuoteError::InvalidResponse(message.to_string()));
+    }
+    Ok(())
+}
+
+fn parse_alpha_vantage_quote(symbol: &str, json: &serde_json::Value) -> QuoteResult<StockData> {
+    alpha_vantage_limited(json)?;
+    let quote = &json["Global Quote"];
+    let number = |key: &str| {
+        quote[key]
+            .as_str()
+            .and_then(|v| v.trim_end_matches('%').parse::<f64>().ok())
+    };
+    let price =
+        number("05. price").ok_or_else(|| QuoteError::SymbolNotFound(symbol.to_string()))?;
+    Ok(StockData {
+        symbol: symbol.to_uppercase(),
+        price,
+        change: number("09. change").unwrap_or(0.0),
+        percent_change: number("10. change percent").unwrap_or(0.0),
+        ..Default::default()
+    })
+}
+
+/// Closing prices of the latest trading day in an intraday series
+fn parse_alpha_vantage_intraday(json: &serde_json::Value) -> QuoteResult<Vec<f64>> {
+    alpha_vantage_limited(json)?;
+    let series = json["Time Series (5min)"]
+        .as_object()
+        .ok_or_else(|| QuoteError::InvalidResponse("Missing intraday series".to_string()))?;
+
+    // Timestamps are "YYYY-MM-DD HH:MM:SS", so they sort as text
+    let mut times: Vec<&String> = series.keys().collect();
+    times.sort();
+    let Some(latest) = times.last().and_then(|time| time.get(..10)) else {
+        return Ok(Vec::new());
+    };
+    Ok(times
+        .iter()
+        .filter(|time| time.starts_with(latest))
+        .filter_map(|time| series[time.as_str()]["4. close"].as_str()?.parse().ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeProvider {
+        name: &'static str,
+        rate_limited: bool,
+    }
+
+    impl QuoteProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn quote<'a>(&'a self, symbol: &'a str) -> QuoteFuture<'a, StockData> {
+            Box::pin(async move {
+                if self.rate_limited {
+                    return Err(QuoteError::RateLimited {
+                        provider: self.name,
+                        retry_after: None,
+                    });
+                }
+                Ok(StockData {
+                    symbol: symbol.to_string(),
+                    price: 1.0,
+                    ..Default::default()
+                })
+            })
+        }
+    }
+
+    #[test]
+    fn test_fallback_when_rate_limited() {
+        let providers = QuoteProviders::new(vec![
+            Arc::new(FakeProvider {
+                name: "limited",
+                rate_limited: true,
+            }),
+            Arc::new(FakeProvider {
+                name: "spare",
+                rate_limited: false,
+            }),
+        ]);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            runtime.block_on(providers.quote("AAPL")).unwrap().price,
+            1.0
+        );
+        // The limited provider sits out until its limit lifts
+        let available = providers.available(Instant::now());
+        assert_eq!(available.len(), 1);
+        assert_eq!(available[0].name(), "spare");
+        assert_eq!(
+            providers
+                .available(Instant::now() + RATE_LIMIT_COOLDOWN)
+                .len(),
+            2
+        );
+
+        // Neither has intraday prices
+        assert!(matches!(
+            runtime.block_on(providers.intraday("AAPL")),
+            Err(QuoteError::Unsupported("spare"))
+        ));
+    }
+
+    #[test]
+    fn test_providers_from_config() {
+        let config: toml::Table = toml::from_str(
+            r#"
+            providers = ["finnhub", "yahoo"]
+            finnhub_api_key = "key"
+            "#,
+        )
+        .unwrap();
+        let providers = QuoteProviders::from_config(&config).unwrap();
+        assert_eq!(providers.names(), vec!["finnhub", "yahoo"]);
+
+        assert_eq!(
+            QuoteProviders::from_config(&toml::Table::new())
+                .unwrap()
+                .names(),
+            vec!["yahoo"]
+        );
+
+        let missing_key: toml::Table = toml::from_str(r#"providers = "alpha_vantage""#).unwrap();
+        assert!(QuoteProviders::from_config(&missing_key).is_err());
+        let unknown: toml::Table = toml::from_str(r#"providers = ["bloomberg"]"#).unwrap();
+        assert!(QuoteProviders::from_config(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_parse_yahoo() {
+        let quote = serde_json::json!({
+            "quoteResponse": { "result": [{
+                "regularMarketPrice": 150.0,
+                "regularMarketChange": 1.5,
+                "regularMarketChangePercent": 1.0,
+                "marketState": "PRE",
+                "preMarketPrice": 149.5,
+                "preMarketChangePercent": -0.5,
+                "postMarketPrice": 151.0,
+            }] }
+        });
+        let data = parse_yahoo_quote("aapl", &quote).unwrap();
+        assert_eq!(data.symbol, "AAPL");
+        assert_eq!(data.change, 1.5);
+        assert_eq!(
+            data.extended,
+            Some(ExtendedHours {
+                session: Session::PreMarket,
+                price: 149.5,
+                percent_change: -0.5,
+            })
+        );
+        assert_eq!(
+            yahoo_extended_hours(&serde_json::json!({ "marketState": "REGULAR" })),
+            None
+        );
+
+        let chart = serde_json::json!({
+            "chart": { "result": [{ "indicators": { "quote": [{ "close": [1.0, null, 2.5] }] } }] }
+        });
+        assert_eq!(parse_yahoo_intraday(&chart), vec![1.0, 2.5]);
+    }
+
+    #[test]
+    fn test_parse_finnhub() {
+        let json = serde_json::json!({ "c": 261.74, "d": -1.26, "dp": -0.4793 });
+        let data = parse_finnhub_quote("msft", &json).unwrap();
+        assert_eq!(data.symbol, "MSFT");
+        assert_eq!(data.price, 261.74);
+        assert_eq!(data.percent_change, -0.4793);
+
+        let unknown = serde_json::json!({ "c": 0, "d": null, "dp": null });
+        assert!(matches!(
+            parse_finnhub_quote("NOPE", &unknown),
+            Err(QuoteError::SymbolNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_alpha_vantage() {
+        let json = serde_json::json!({ "Global Quote": {
+            "01. symbol": "IBM",
+            "05. price": "168.5000",
+            "09. change": "-1.2500",
+            "10. change percent": "-0.7364%",
+        } });
+        let data = parse_alpha_vantage_quote("IBM", &json).unwrap();
+        assert_eq!(data.price, 168.5);
+        assert_eq!(data.change, -1.25);
+        assert_eq!(data.percent_change, -0.7364);
+
+        let note = serde_json::json!({ "Note": "Thank you for using Alpha Vantage!" });
+        assert!(matches!(
+            parse_alpha_vantage_quote("IBM", &note),
+            Err(QuoteError::RateLimited { .. })
+        ));
+
+        let series = serde_json::json!({ "Time Series (5min)": {
+            "2026-03-03 09:35:00": { "4. close": "101.0" },
+            "2026-03-02 19:55:00": { "4. close": "99.0" },
+            "2026-03-03 09:30:00": { "4. close": "100.0" },
+        } });
+        assert_eq!(
+            parse_alpha_vantage_intraday(&series).unwrap(),
+            vec![100.0, 101.0]
+        );
+    }
+}
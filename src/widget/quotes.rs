@@ -1,17 +1,32 @@
 //! Quotes widget displaying inspirational quotes
 //!
-//! This widget shows quotes from a configurable source (embedded, file, or JSON).
+//! This widget shows quotes from a configurable source: the embedded list,
+//! inline quotes, or a quotes file (plain text with one quote per line, TOML
+//! or JSON). Quotes can also be fetched online from quotable.io, and
+//! filtered by category. Right-click copies the current quote.
 
-use std::path::PathBuf;
-use std::time::{Duration, Instant};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::Context;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
 use tracing::{debug, warn};
 
+use super::custom::expand_home;
 use super::registry::DynWidgetFactory;
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
 use super::traits::{
     FontSize, MouseButton, ScrollDirection, Widget, WidgetAction, WidgetContent, WidgetInfo,
 };
+use crate::update::tasks::{self, TaskOutput};
+use crate::update::{Schedule, Source, Ticker};
+
+/// Random quotes endpoint of quotable.io
+const QUOTABLE_URL: &str = "https://api.quotable.io/quotes/random";
+
+/// Number of quotes fetched online at a time
+const ONLINE_BATCH: usize = 20;
 
 /// A quote with optional author attribution
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -19,6 +34,9 @@ pub struct Quote {
     pub text: String,
     #[serde(default)]
     pub author: Option<String>,
+    /// Categories the quote belongs to, e.g. "wisdom"
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl Quote {
@@ -27,9 +45,23 @@ impl Quote {
         Self {
             text: text.to_string(),
             author: author.map(|s| s.to_string()),
+            tags: Vec::new(),
         }
     }
 
+    /// Put the quote in the categories `tags`
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        self
+    }
+
+    /// Whether the quote is in any of `categories`
+    pub fn in_categories(&self, categories: &[String]) -> bool {
+        self.tags
+            .iter()
+            .any(|tag| categories.iter().any(|c| c.eq_ignore_ascii_case(tag)))
+    }
+
     /// Format the quote for display
     pub fn display(&self) -> String {
         match &self.author {
@@ -39,13 +71,49 @@ impl Quote {
     }
 }
 
+/// A quote as returned by quotable.io
+#[derive(Debug, Deserialize)]
+struct QuotableQuote {
+    content: String,
+    author: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl From<QuotableQuote> for Quote {
+    fn from(quote: QuotableQuote) -> Self {
+        Self {
+            text: quote.content,
+            author: quote.author,
+            tags: quote.tags,
+        }
+    }
+}
+
+/// Layout of a TOML quotes file
+#[derive(Debug, Deserialize)]
+struct QuoteFile {
+    #[serde(default)]
+    quotes: Vec<Quote>,
+}
+
 /// Quotes widget showing inspirational quotes
 pub struct QuotesWidget {
+    /// Local quotes first, then the ones fetched online
     quotes: Vec<Quote>,
+    /// Number of local quotes at the start of `quotes`
+    local_count: usize,
     current_index: usize,
-    last_update: Instant,
-    rotation_interval: Duration,
+    rotation: Ticker,
     random: bool,
+    /// Go through the quotes in a random order without repeats
+    shuffle: bool,
+    /// Indices still to show this round when shuffling, next one last
+    deck: Vec<usize>,
+    /// Only show quotes in these categories, if any
+    categories: Vec<String>,
+    /// Fetch quotes from quotable.io this often
+    online_interval: Option<Duration>,
 }
 
 impl QuotesWidget {
@@ -58,12 +126,25 @@ impl QuotesWidget {
             0
         };
 
+        Self::with_quotes_at(quotes, current_index, rotation_interval, random)
+    }
+
+    fn with_quotes_at(
+        quotes: Vec<Quote>,
+        current_index: usize,
+        rotation_interval: u64,
+        random: bool,
+    ) -> Self {
         Self {
+            local_count: quotes.len(),
             quotes,
             current_index,
-            last_update: Instant::now(),
-            rotation_interval: Duration::from_secs(rotation_interval),
+            rotation: Ticker::new(Schedule::Every(Duration::from_secs(rotation_interval))),
             random,
+            shuffle: false,
+            deck: Vec::new(),
+            categories: Vec::new(),
+            online_interval: None,
         }
     }
 
@@ -75,29 +156,110 @@ impl QuotesWidget {
             0
         };
 
-        Self {
-            quotes,
-            current_index,
-            last_update: Instant::now(),
-            rotation_interval: Duration::from_secs(rotation_interval),
-            random,
-        }
+        Self::with_quotes_at(quotes, current_index, rotation_interval, random)
     }
 
-    /// Load quotes from a JSON file
+    /// Load quotes from a file
+    ///
+    /// `.json` files hold an array of quotes and `.toml` files a `[[quotes]]`
+    /// table array, each quote with `text` and optional `author` and `tags`.
+    /// Anything else is plain text: one quote per line, with an optional
+    /// author after " — " or " -- ", and `#` starting a comment line.
     pub fn from_file(path: &PathBuf, rotation_interval: u64, random: bool) -> anyhow::Result<Self> {
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read quotes file: {}", path.display()))?;
+        let quotes = load_quotes(path)?;
+        Ok(Self::with_quotes(quotes, rotation_interval, random))
+    }
 
-        let quotes: Vec<Quote> = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse quotes file: {}", path.display()))?;
+    /// Go through the quotes in a random order, showing each once a round
+    pub fn with_shuffle(mut self, shuffle: bool) -> Self {
+        self.shuffle = shuffle;
+        self.deck.clear();
+        self
+    }
 
-        if quotes.is_empty() {
-            anyhow::bail!("Quotes file is empty");
+    /// Only show quotes tagged with one of `categories`
+    ///
+    /// An empty list shows every quote. When no quote matches, the
+    /// unfiltered quotes are kept rather than showing nothing.
+    pub fn with_categories(mut self, categories: Vec<String>) -> Self {
+        if !categories.is_empty() {
+            let matching: Vec<Quote> = self
+                .quotes
+                .iter()
+                .filter(|quote| quote.in_categories(&categories))
+                .cloned()
+                .collect();
+            if matching.is_empty() {
+                warn!(categories = ?categories, "No quotes in the configured categories");
+            } else {
+                self.local_count = matching.len();
+                self.quotes = matching;
+                self.current_index = if self.random || self.shuffle {
+                    rand::random::<usize>() % self.quotes.len()
+                } else {
+                    0
+                };
+                self.deck.clear();
+            }
         }
+        self.categories = categories;
+        self
+    }
 
-        debug!(count = quotes.len(), path = %path.display(), "Loaded quotes from file");
-        Ok(Self::with_quotes(quotes, rotation_interval, random))
+    /// Also fetch quotes from quotable.io every `interval`
+    pub fn with_online(mut self, interval: Duration) -> Self {
+        self.online_interval = Some(interval);
+        self
+    }
+
+    /// Fetch a batch of random quotes from quotable.io, in `categories` if
+    /// any are given
+    async fn fetch_online(categories: Vec<String>) -> anyhow::Result<Vec<Quote>> {
+        let mut url = format!("{}?limit={}", QUOTABLE_URL, ONLINE_BATCH);
+        if !categories.is_empty() {
+            // quotable.io reads "|" as "or"
+            url.push_str("&tags=");
+            url.push_str(&categories.join("|"));
+        }
+
+        let response = crate::http::client()
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch quotes from quotable.io")?;
+        if !response.status().is_success() {
+            anyhow::bail!("quotable.io returned error status: {}", response.status());
+        }
+        let quotes: Vec<QuotableQuote> = response
+            .json()
+            .await
+            .context("Failed to parse quotable.io response")?;
+        Ok(quotes.into_iter().map(Quote::from).collect())
+    }
+
+    /// Replace the quotes fetched online with `fetched`
+    fn set_fetched(&mut self, fetched: Vec<Quote>) {
+        let showing_fetched = self.current_index >= self.local_count;
+        self.quotes.truncate(self.local_count);
+        self.quotes.extend(
+            fetched.into_iter().filter(|quote| {
+                self.categories.is_empty() || quote.in_categories(&self.categories)
+            }),
+        );
+        debug!(
+            count = self.quotes.len() - self.local_count,
+            "Fetched quotes online"
+        );
+        self.deck.clear();
+        if showing_fetched || self.current_index >= self.quotes.len() {
+            self.next_quote();
+        }
+    }
+
+    /// Rotate on `schedule` instead, e.g. a cron expression
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.rotation = Ticker::new(schedule);
+        self
     }
 
     /// Get the current quote
@@ -111,7 +273,17 @@ impl QuotesWidget {
             return;
         }
 
-        if self.random {
+        if self.shuffle {
+            if self.deck.is_empty() {
+                self.deck = (0..self.quotes.len())
+                    .filter(|&i| i != self.current_index || self.quotes.len() == 1)
+                    .collect();
+                self.deck.shuffle(&mut rand::thread_rng());
+            }
+            if let Some(index) = self.deck.pop() {
+                self.current_index = index;
+            }
+        } else if self.random {
             // Pick a random different quote if possible
             if self.quotes.len() > 1 {
                 let mut new_index = self.current_index;
@@ -127,7 +299,7 @@ impl QuotesWidget {
 
     /// Default embedded quotes
     fn default_quotes() -> Vec<Quote> {
-        vec![
+        let inspiration = vec![
             Quote::new("The only way to do great work is to love what you do.", Some("Steve Jobs")),
             Quote::new("Innovation distinguishes between a leader and a follower.", Some("Steve Jobs")),
             Quote::new("Stay hungry, stay foolish.", Some("Steve Jobs")),
@@ -143,12 +315,23 @@ impl QuotesWidget {
             Quote::new("Everything you've ever wanted is on the other side of fear.", Some("George Addair")),
             Quote::new("The mind is everything. What you think you become.", Some("Buddha")),
             Quote::new("Simplicity is the ultimate sophistication.", Some("Leonardo da Vinci")),
+        ];
+        let programming = vec![
             Quote::new("Code is like humor. When you have to explain it, it's bad.", Some("Cory House")),
             Quote::new("First, solve the problem. Then, write the code.", Some("John Johnson")),
             Quote::new("Make it work, make it right, make it fast.", Some("Kent Beck")),
             Quote::new("Any fool can write code that a computer can understand. Good programmers write code that humans can understand.", Some("Martin Fowler")),
             Quote::new("Programs must be written for people to read, and only incidentally for machines to execute.", Some("Harold Abelson")),
-        ]
+        ];
+        inspiration
+            .into_iter()
+            .map(|quote| quote.with_tags(&["inspiration"]))
+            .chain(
+                programming
+                    .into_iter()
+                    .map(|quote| quote.with_tags(&["programming"])),
+            )
+            .collect()
     }
 
     /// Display string for the current quote
@@ -172,9 +355,8 @@ impl Widget for QuotesWidget {
     }
 
     fn update(&mut self) {
-        if self.last_update.elapsed() >= self.rotation_interval {
+        if self.rotation.tick() {
             self.next_quote();
-            self.last_update = Instant::now();
             debug!(index = self.current_index, "Quote rotated");
         }
     }
@@ -200,14 +382,13 @@ impl Widget for QuotesWidget {
             MouseButton::Left => {
                 // Advance to next quote on left click
                 self.next_quote();
-                self.last_update = Instant::now(); // Reset timer
+                self.rotation.restart(); // Reset timer
                 debug!("Quote advanced by click");
                 Some(WidgetAction::NextItem)
             }
-            MouseButton::Right => {
-                // Could add context menu or other action in future
-                None
-            }
+            MouseButton::Right => self
+                .current_quote()
+                .map(|quote| WidgetAction::CopyText(quote.display())),
             _ => None,
         }
     }
@@ -217,20 +398,115 @@ impl Widget for QuotesWidget {
             ScrollDirection::Down => {
                 // Scroll down = next quote
                 self.next_quote();
-                self.last_update = Instant::now();
+                self.rotation.restart();
                 debug!("Quote advanced by scroll");
                 Some(WidgetAction::NextItem)
             }
             ScrollDirection::Up => {
                 // Scroll up = also next quote (could be previous in future)
                 self.next_quote();
-                self.last_update = Instant::now();
+                self.rotation.restart();
                 debug!("Quote advanced by scroll");
                 Some(WidgetAction::NextItem)
             }
             _ => None,
         }
     }
+
+    fn data_source(&self) -> Option<Source> {
+        let interval = self.online_interval?;
+        let categories = self.categories.clone();
+        Some(Source::new(interval, move || {
+            let categories = categories.clone();
+            tasks::task(async move { Self::fetch_online(categories).await })
+        }))
+    }
+
+    fn apply_fetch(&mut self, result: anyhow::Result<TaskOutput>) {
+        match tasks::take_output::<Vec<Quote>>(result) {
+            Ok(quotes) => self.set_fetched(quotes),
+            // Local quotes keep showing
+            Err(e) => warn!(error = %e, "Failed to fetch quotes online"),
+        }
+    }
+
+    fn save_state(&self) -> Option<toml::Table> {
+        // Fetched quotes are gone after a restart
+        if self.current_index >= self.local_count {
+            return None;
+        }
+        let mut state = toml::Table::new();
+        state.insert(
+            "index".to_string(),
+            toml::Value::Integer(self.current_index as i64),
+        );
+        Some(state)
+    }
+
+    fn restore_state(&mut self, state: &toml::Table) {
+        let index = state.get("index").and_then(|v| v.as_integer());
+        if let Some(index) = index.filter(|&i| i >= 0 && (i as usize) < self.local_count) {
+            self.current_index = index as usize;
+        }
+    }
+}
+
+/// Read quotes from `path`; see [`QuotesWidget::from_file`] for the formats
+fn load_quotes(path: &Path) -> anyhow::Result<Vec<Quote>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read quotes file: {}", path.display()))?;
+
+    let quotes = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse quotes file: {}", path.display()))?,
+        Some("toml") => {
+            toml::from_str::<QuoteFile>(&content)
+                .with_context(|| format!("Failed to parse quotes file: {}", path.display()))?
+                .quotes
+        }
+        _ => parse_text_quotes(&content),
+    };
+
+    if quotes.is_empty() {
+        anyhow::bail!("Quotes file is empty");
+    }
+
+    debug!(count = quotes.len(), path = %path.display(), "Loaded quotes from file");
+    Ok(quotes)
+}
+
+/// One quote per line, optionally ending in " — Author" or " -- Author"
+fn parse_text_quotes(content: &str) -> Vec<Quote> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (text, author) = match line.rsplit_once(" — ").or_else(|| line.rsplit_once(" -- "))
+            {
+                Some((text, author)) => (text, Some(author.trim())),
+                None => (line, None),
+            };
+            Quote::new(text.trim().trim_matches('"'), author)
+        })
+        .collect()
+}
+
+/// Read the optional `categories` list
+fn parse_categories(config: &toml::Table) -> anyhow::Result<Vec<String>> {
+    let Some(value) = config.get("categories") else {
+        return Ok(Vec::new());
+    };
+    value
+        .as_array()
+        .context("'categories' must be an array of strings")?
+        .iter()
+        .map(|v| {
+            v.as_str()
+                .map(str::to_string)
+                .context("'categories' must be an array of strings")
+        })
+        .collect()
 }
 
 impl Default for QuotesWidget {
@@ -251,34 +527,61 @@ impl DynWidgetFactory for QuotesWidgetFactory {
         "quotes"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Quotes",
+            "Rotating quotes from the built-in list, a file or quotable.io",
+            "format-justify-left-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> anyhow::Result<Box<dyn Widget>> {
         let rotation_interval = config
             .get("rotation_interval")
             .and_then(|v| v.as_integer())
             .unwrap_or(60) as u64;
+        let schedule = Schedule::from_config(config, "rotation_interval", 60)?;
 
         let random = config
             .get("random")
             .and_then(|v| v.as_bool())
             .unwrap_or(true);
 
-        // Check for custom quotes file
-        if let Some(file_path) = config.get("quotes_file").and_then(|v| v.as_str()) {
-            let path = PathBuf::from(file_path);
+        let shuffle = config
+            .get("shuffle")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
 
-            // Expand ~ to home directory
-            let expanded_path = if file_path.starts_with("~/") {
-                if let Some(home) = dirs::home_dir() {
-                    home.join(&file_path[2..])
-                } else {
-                    path
-                }
+        let categories = parse_categories(config)?;
+
+        let online = config
+            .get("online")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let online_interval = config
+            .get("online_interval")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(3600) as u64;
+
+        let finish = |widget: QuotesWidget| -> Box<dyn Widget> {
+            let widget = widget
+                .with_schedule(schedule.clone())
+                .with_shuffle(shuffle)
+                .with_categories(categories.clone());
+            if online {
+                Box::new(widget.with_online(Duration::from_secs(online_interval)))
             } else {
-                path
-            };
+                Box::new(widget)
+            }
+        };
+
+        // Check for custom quotes file
+        if let Some(file_path) = config.get("quotes_file").and_then(|v| v.as_str()) {
+            let expanded_path = expand_home(file_path);
 
             match QuotesWidget::from_file(&expanded_path, rotation_interval, random) {
-                Ok(widget) => return Ok(Box::new(widget)),
+                Ok(widget) => return Ok(finish(widget)),
                 Err(e) => {
                     warn!(error = %e, path = %expanded_path.display(), "Failed to load custom quotes, using defaults");
                 }
@@ -302,7 +605,7 @@ impl DynWidgetFactory for QuotesWidgetFactory {
 
             if !quotes.is_empty() {
                 debug!(count = quotes.len(), "Using custom inline quotes");
-                return Ok(Box::new(QuotesWidget::with_quotes(
+                return Ok(finish(QuotesWidget::with_quotes(
                     quotes,
                     rotation_interval,
                     random,
@@ -311,18 +614,20 @@ impl DynWidgetFactory for QuotesWidgetFactory {
         }
 
         debug!(
-            rotation_interval = %rotation_interval,
+            schedule = %schedule,
             random = %random,
             "Creating QuotesWidget with default quotes"
         );
 
-        Ok(Box::new(QuotesWidget::new(rotation_interval, random)))
+        Ok(finish(QuotesWidget::new(rotation_interval, random)))
     }
 
     fn default_config(&self) -> toml::Table {
         let mut config = toml::Table::new();
         config.insert("rotation_interval".to_string(), toml::Value::Integer(60));
         config.insert("random".to_string(), toml::Value::Boolean(true));
+        config.insert("shuffle".to_string(), toml::Value::Boolean(false));
+        config.insert("online".to_string(), toml::Value::Boolean(false));
         config
     }
 
@@ -336,8 +641,64 @@ impl DynWidgetFactory for QuotesWidgetFactory {
                 anyhow::bail!("'rotation_interval' must be at least 1 second");
             }
         }
+
+        if let Some(interval) = config.get("online_interval") {
+            let interval_val = interval
+                .as_integer()
+                .context("'online_interval' must be an integer")?;
+            if interval_val < 60 {
+                anyhow::bail!("'online_interval' must be at least 60 seconds");
+            }
+        }
+
+        parse_categories(config)?;
         Ok(())
     }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_interactive().with_network()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new(
+                "rotation_interval",
+                "Rotation interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(
+                ConfigField::new("schedule", "Schedule", FieldKind::Text)
+                    .with_description("Cron expression, e.g. 0 0 9 * * * (overrides interval)"),
+            )
+            .with_field(ConfigField::new("random", "Random order", FieldKind::Bool))
+            .with_field(
+                ConfigField::new("shuffle", "Shuffle", FieldKind::Bool)
+                    .with_description("Show every quote once, in random order, before repeating"),
+            )
+            .with_field(
+                ConfigField::new("quotes_file", "Quotes file", FieldKind::Path)
+                    .with_description("Text file with one quote per line, or TOML or JSON"),
+            )
+            .with_field(
+                ConfigField::new("categories", "Categories", FieldKind::List)
+                    .with_description("Only show quotes with these tags, e.g. wisdom"),
+            )
+            .with_field(
+                ConfigField::new("online", "Fetch online", FieldKind::Bool)
+                    .with_description("Add quotes from quotable.io"),
+            )
+            .with_field(ConfigField::new(
+                "online_interval",
+                "Online fetch interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(60),
+                    max: None,
+                },
+            ))
+    }
 }
 
 #[cfg(test)]
@@ -381,12 +742,116 @@ mod tests {
         assert_eq!(third, 2);
     }
 
+    #[test]
+    fn test_quote_state_survives_restart() {
+        let quotes = || {
+            vec![
+                Quote::new("Quote 1", None),
+                Quote::new("Quote 2", None),
+                Quote::new("Quote 3", None),
+            ]
+        };
+        let mut widget = QuotesWidget::with_quotes(quotes(), 0, false);
+        widget.next_quote();
+        widget.next_quote();
+        let state = widget.save_state().unwrap();
+
+        let mut restarted = QuotesWidget::with_quotes(quotes(), 0, false);
+        restarted.restore_state(&state);
+        assert_eq!(restarted.current_index, 2);
+
+        // The quotes file got shorter since
+        let mut shorter = QuotesWidget::with_quotes(vec![Quote::new("Only", None)], 0, false);
+        shorter.restore_state(&state);
+        assert_eq!(shorter.current_index, 0);
+    }
+
     #[test]
     fn test_default_quotes_not_empty() {
         let quotes = QuotesWidget::default_quotes();
         assert!(!quotes.is_empty());
     }
 
+    #[test]
+    fn test_shuffle_shows_each_quote_once() {
+        let quotes = (0..5)
+            .map(|i| Quote::new(&format!("Quote {}", i), None))
+            .collect();
+        let mut widget = QuotesWidget::with_quotes(quotes, 60, false).with_shuffle(true);
+
+        let mut seen = vec![widget.current_index];
+        for _ in 0..4 {
+            widget.next_quote();
+            seen.push(widget.current_index);
+        }
+        seen.sort();
+        assert_eq!(seen, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_categories_and_fetched_quotes() {
+        let widget = QuotesWidget::new(60, false).with_categories(vec!["Programming".into()]);
+        assert_eq!(widget.quotes.len(), 5);
+        assert!(widget.display_string().contains("Code is like humor"));
+
+        let mut widget = widget;
+        let fetched: Vec<Quote> = serde_json::from_str::<Vec<QuotableQuote>>(
+            r#"[
+                {"content": "Talk is cheap.", "author": "Linus Torvalds", "tags": ["programming"]},
+                {"content": "Carpe diem.", "author": "Horace", "tags": ["wisdom"]}
+            ]"#,
+        )
+        .unwrap()
+        .into_iter()
+        .map(Quote::from)
+        .collect();
+        widget.set_fetched(fetched);
+        assert_eq!(widget.quotes.len(), 6);
+        assert_eq!(widget.quotes[5].text, "Talk is cheap.");
+
+        // The next batch replaces the last one
+        widget.set_fetched(Vec::new());
+        assert_eq!(widget.quotes.len(), 5);
+    }
+
+    #[test]
+    fn test_parse_quote_files() {
+        let quotes = parse_text_quotes(
+            "# favourites\n\"Less is more.\" — Mies van der Rohe\n\nKeep going -- Anon\nNo author here\n",
+        );
+        assert_eq!(quotes.len(), 3);
+        assert_eq!(quotes[0].display(), "\"Less is more.\" — Mies van der Rohe");
+        assert_eq!(quotes[1].author.as_deref(), Some("Anon"));
+        assert_eq!(quotes[2].author, None);
+
+        let dir = std::env::temp_dir().join(format!("quotes-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("quotes.toml");
+        std::fs::write(
+            &path,
+            r#"
+            [[quotes]]
+            text = "Know thyself."
+            author = "Socrates"
+            tags = ["wisdom"]
+            "#,
+        )
+        .unwrap();
+        let quotes = load_quotes(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(quotes[0].author.as_deref(), Some("Socrates"));
+        assert_eq!(quotes[0].tags, vec!["wisdom"]);
+    }
+
+    #[test]
+    fn test_right_click_copies_quote() {
+        let mut widget = QuotesWidget::with_quotes(vec![Quote::new("Hi", Some("Me"))], 60, false);
+        assert_eq!(
+            widget.on_click(MouseButton::Right, 0.5, 0.5),
+            Some(WidgetAction::CopyText("\"Hi\" — Me".to_string()))
+        );
+    }
+
     #[test]
     fn test_factory_creation() {
         let factory = QuotesWidgetFactory;
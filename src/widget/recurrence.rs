@@ -0,0 +1,589 @@
+//! Recurrence rules (RFC 5545 `RRULE`) for calendar events
+//!
+//! Covers the rules calendar apps actually write: daily, weekly, monthly and
+//! yearly frequencies with `INTERVAL`, `COUNT`, `UNTIL`, `BYDAY` (including
+//! ordinals like `2TU` or `-1FR`), `BYMONTHDAY` and `BYMONTH`. Expansion is
+//! always bounded by a window, so an endless rule costs no more than the
+//! occurrences that fall inside it.
+
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, Weekday};
+
+/// Stop after this many periods even if the window isn't reached
+const MAX_PERIODS: u32 = 50_000;
+
+/// How often a rule repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    /// Every `INTERVAL` days
+    Daily,
+    /// Every `INTERVAL` weeks
+    Weekly,
+    /// Every `INTERVAL` months
+    Monthly,
+    /// Every `INTERVAL` years
+    Yearly,
+}
+
+/// A parsed `RRULE` value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    freq: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<NaiveDateTime>,
+    /// Weekdays, with an optional ordinal within the month (`-1` is last)
+    by_day: Vec<(Option<i32>, Weekday)>,
+    /// Days of the month, negative counting from the end
+    by_month_day: Vec<i32>,
+    by_month: Vec<u32>,
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = anyhow::Error;
+
+    fn from_str(rule: &str) -> Result<Self> {
+        let rule = rule.trim().trim_start_matches("RRULE:");
+        let mut freq = None;
+        let mut parsed = Self {
+            freq: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+        };
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .with_context(|| format!("Invalid RRULE part '{}'", part))?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => bail!("Unsupported RRULE frequency '{}'", other),
+                    })
+                }
+                "INTERVAL" => {
+                    parsed.interval = value.parse().context("Invalid RRULE INTERVAL")?;
+                    if parsed.interval == 0 {
+                        bail!("RRULE INTERVAL must be at least 1");
+                    }
+                }
+                "COUNT" => parsed.count = Some(value.parse().context("Invalid RRULE COUNT")?),
+                "UNTIL" => {
+                    parsed.until = Some(parse_until(value).context("Invalid RRULE UNTIL")?);
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        parsed.by_day.push(parse_by_day(day)?);
+                    }
+                }
+                "BYMONTHDAY" => {
+                    for day in value.split(',') {
+                        let day: i32 = day.parse().context("Invalid RRULE BYMONTHDAY")?;
+                        if day == 0 || day.abs() > 31 {
+                            bail!("RRULE BYMONTHDAY out of range: {}", day);
+                        }
+                        parsed.by_month_day.push(day);
+                    }
+                }
+                "BYMONTH" => {
+                    for month in value.split(',') {
+                        let month: u32 = month.parse().context("Invalid RRULE BYMONTH")?;
+                        if !(1..=12).contains(&month) {
+                            bail!("RRULE BYMONTH out of range: {}", month);
+                        }
+                        parsed.by_month.push(month);
+                    }
+                }
+                // WKST and the finer BY* parts don't change the rules
+                // calendar apps write for everyday events
+                _ => {}
+            }
+        }
+
+        parsed.freq = freq.context("RRULE has no FREQ")?;
+        Ok(parsed)
+    }
+}
+
+impl RecurrenceRule {
+    /// How often the rule repeats
+    pub fn frequency(&self) -> Frequency {
+        self.freq
+    }
+
+    /// Occurrences of an event starting at `start`, from `window_start` up
+    /// to and including `window_end`
+    ///
+    /// `start` itself counts as the first occurrence. Times in `exdates`
+    /// are left out, but still count towards `COUNT`.
+    pub fn occurrences(
+        &self,
+        start: NaiveDateTime,
+        window_start: NaiveDateTime,
+        window_end: NaiveDateTime,
+        exdates: &[NaiveDateTime],
+    ) -> Vec<NaiveDateTime> {
+        let mut occurrences = Vec::new();
+        let mut seen = 0u32;
+
+        // Without a COUNT nothing before the window matters, so skip
+        // straight to it
+        let first_period = if self.count.is_none() {
+            self.periods_between(start.date(), window_start.date())
+                .saturating_sub(1)
+        } else {
+            0
+        };
+
+        for period in first_period..first_period.saturating_add(MAX_PERIODS) {
+            let Some(days) = self.period_days(start.date(), period) else {
+                break;
+            };
+            if days.period_start > window_end.date() {
+                break;
+            }
+
+            for day in days.days {
+                let occurrence = day.and_time(start.time());
+                if occurrence < start {
+                    continue;
+                }
+                if self.until.is_some_and(|until| occurrence > until)
+                    || self.count.is_some_and(|count| seen >= count)
+                    || occurrence > window_end
+                {
+                    return occurrences;
+                }
+                seen += 1;
+                if occurrence >= window_start && !exdates.contains(&occurrence) {
+                    occurrences.push(occurrence);
+                }
+            }
+        }
+
+        occurrences
+    }
+
+    /// Whole periods between `start` and `day`
+    fn periods_between(&self, start: NaiveDate, day: NaiveDate) -> u32 {
+        if day <= start {
+            return 0;
+        }
+        let units = match self.freq {
+            Frequency::Daily => (day - start).num_days(),
+            Frequency::Weekly => (day - start).num_weeks(),
+            Frequency::Monthly => {
+                i64::from(day.year() - start.year()) * 12 + i64::from(day.month())
+                    - i64::from(start.month())
+            }
+            Frequency::Yearly => i64::from(day.year() - start.year()),
+        };
+        u32::try_from(units / i64::from(self.interval)).unwrap_or(u32::MAX)
+    }
+
+    /// Candidate days in the `period`th period after `start`, in order
+    fn period_days(&self, start: NaiveDate, period: u32) -> Option<PeriodDays> {
+        let step = period.checked_mul(self.interval)?;
+        match self.freq {
+            Frequency::Daily => {
+                let day = start.checked_add_signed(Duration::days(i64::from(step)))?;
+                let days = Some(day)
+                    .filter(|day| self.matches_month(*day))
+                    .filter(|day| self.by_day.is_empty() || self.matches_weekday(*day))
+                    .filter(|day| self.by_month_day.is_empty() || self.matches_month_day(*day))
+                    .into_iter()
+                    .collect();
+                Some(PeriodDays {
+                    period_start: day,
+                    days,
+                })
+            }
+            Frequency::Weekly => {
+                let monday = start
+                    .checked_sub_signed(Duration::days(
+                        start.weekday().num_days_from_monday().into(),
+                    ))?
+                    .checked_add_signed(Duration::weeks(i64::from(step)))?;
+                let days = (0..7)
+                    .filter_map(|offset| monday.checked_add_signed(Duration::days(offset)))
+                    .filter(|day| {
+                        if self.by_day.is_empty() {
+                            day.weekday() == start.weekday()
+                        } else {
+                            self.matches_weekday(*day)
+                        }
+                    })
+                    .filter(|day| self.matches_month(*day))
+                    .collect();
+                Some(PeriodDays {
+                    period_start: monday,
+                    days,
+                })
+            }
+            Frequency::Monthly => {
+                let first = start.with_day(1)?.checked_add_months(Months::new(step))?;
+                let days = if self.by_month.is_empty() || self.by_month.contains(&first.month()) {
+                    self.days_in_month(first, start.day())
+                } else {
+                    Vec::new()
+                };
+                Some(PeriodDays {
+                    period_start: first,
+                    days,
+                })
+            }
+            Frequency::Yearly => {
+                let year = start.year().checked_add(i32::try_from(step).ok()?)?;
+                let months = if self.by_month.is_empty() {
+                    vec![start.month()]
+                } else {
+                    let mut months = self.by_month.clone();
+                    months.sort_unstable();
+                    months
+                };
+                let days = months
+                    .into_iter()
+                    .filter_map(|month| NaiveDate::from_ymd_opt(year, month, 1))
+                    .flat_map(|first| self.days_in_month(first, start.day()))
+                    .collect();
+                Some(PeriodDays {
+                    period_start: NaiveDate::from_ymd_opt(year, 1, 1)?,
+                    days,
+                })
+            }
+        }
+    }
+
+    /// Matching days of the month starting on `first`, falling back to
+    /// `start_day` (skipped in months too short for it)
+    fn days_in_month(&self, first: NaiveDate, start_day: u32) -> Vec<NaiveDate> {
+        let length = month_length(first);
+        let mut days: Vec<NaiveDate> = if !self.by_month_day.is_empty() {
+            self.by_month_day
+                .iter()
+                .filter_map(|&day| {
+                    let day = if day < 0 {
+                        length as i32 + day + 1
+                    } else {
+                        day
+                    };
+                    u32::try_from(day).ok().and_then(|day| first.with_day(day))
+                })
+                .filter(|day| self.by_day.is_empty() || self.matches_weekday(*day))
+                .collect()
+        } else if !self.by_day.is_empty() {
+            self.by_day
+                .iter()
+                .flat_map(|&(ordinal, weekday)| nth_weekdays(first, length, ordinal, weekday))
+                .collect()
+        } else {
+            first.with_day(start_day).into_iter().collect()
+        };
+        days.sort_unstable();
+        days.dedup();
+        days
+    }
+
+    fn matches_weekday(&self, day: NaiveDate) -> bool {
+        self.by_day
+            .iter()
+            .any(|&(_, weekday)| weekday == day.weekday())
+    }
+
+    fn matches_month(&self, day: NaiveDate) -> bool {
+        self.by_month.is_empty() || self.by_month.contains(&day.month())
+    }
+
+    fn matches_month_day(&self, day: NaiveDate) -> bool {
+        let length = month_length(day) as i32;
+        let day_of_month = day.day() as i32;
+        self.by_month_day
+            .iter()
+            .any(|&d| d == day_of_month || d == day_of_month - length - 1)
+    }
+}
+
+/// Days a period can produce, and where the period begins
+struct PeriodDays {
+    period_start: NaiveDate,
+    days: Vec<NaiveDate>,
+}
+
+fn month_length(day: NaiveDate) -> u32 {
+    let first = day.with_day(1).unwrap_or(day);
+    first
+        .checked_add_months(Months::new(1))
+        .map(|next| (next - first).num_days() as u32)
+        .unwrap_or(31)
+}
+
+/// Days in the month starting at `first` falling on `weekday`: all of them,
+/// or just the `ordinal`th (negative counting from the end)
+fn nth_weekdays(
+    first: NaiveDate,
+    length: u32,
+    ordinal: Option<i32>,
+    weekday: Weekday,
+) -> Vec<NaiveDate> {
+    let all: Vec<NaiveDate> = (1..=length)
+        .filter_map(|day| first.with_day(day))
+        .filter(|day| day.weekday() == weekday)
+        .collect();
+    match ordinal {
+        None => all,
+        Some(n) if n > 0 => all.get(n as usize - 1).copied().into_iter().collect(),
+        Some(n) => all
+            .len()
+            .checked_sub(n.unsigned_abs() as usize)
+            .and_then(|index| all.get(index).copied())
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Parse a `BYDAY` entry like `MO`, `2TU` or `-1FR`
+fn parse_by_day(day: &str) -> Result<(Option<i32>, Weekday)> {
+    let day = day.trim();
+    if day.len() < 2 || !day.is_ascii() {
+        bail!("Invalid RRULE BYDAY '{}'", day);
+    }
+    let (ordinal, name) = day.split_at(day.len() - 2);
+    let weekday = match name.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => bail!("Invalid RRULE BYDAY '{}'", day),
+    };
+    let ordinal = match ordinal.trim_start_matches('+') {
+        "" => None,
+        n => Some(
+            n.parse::<i32>()
+                .ok()
+                .filter(|n| *n != 0)
+                .with_context(|| format!("Invalid RRULE BYDAY '{}'", day))?,
+        ),
+    };
+    Ok((ordinal, weekday))
+}
+
+/// Parse an ICS date or date-time (`20250206` or `20250206T143000[Z]`)
+pub fn parse_ics_value(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim().trim_end_matches('Z');
+    if value.len() == 8 {
+        NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)
+    } else {
+        NaiveDateTime::parse_from_str(value.get(..15)?, "%Y%m%dT%H%M%S").ok()
+    }
+}
+
+fn parse_until(value: &str) -> Option<NaiveDateTime> {
+    let until = parse_ics_value(value)?;
+    // A date-only UNTIL includes that whole day
+    if value.trim().len() == 8 {
+        until.date().and_hms_opt(23, 59, 59)
+    } else {
+        Some(until)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        parse_ics_value(value).unwrap()
+    }
+
+    fn expand(rule: &str, start: &str, from: &str, to: &str) -> Vec<String> {
+        let rule: RecurrenceRule = rule.parse().unwrap();
+        rule.occurrences(at(start), at(from), at(to), &[])
+            .iter()
+            .map(|time| time.format("%Y%m%dT%H%M").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let rule: RecurrenceRule = "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;UNTIL=20260301"
+            .parse()
+            .unwrap();
+        assert_eq!(rule.frequency(), Frequency::Weekly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(
+            rule.by_day,
+            vec![(None, Weekday::Mon), (None, Weekday::Wed)]
+        );
+        assert_eq!(rule.until, Some(at("20260301T235959")));
+
+        assert!("INTERVAL=2".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=HOURLY".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=DAILY;INTERVAL=0".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=MONTHLY;BYDAY=0MO".parse::<RecurrenceRule>().is_err());
+    }
+
+    #[test]
+    fn test_daily_with_count() {
+        assert_eq!(
+            expand(
+                "FREQ=DAILY;COUNT=3",
+                "20260302T090000",
+                "20260301T000000",
+                "20260331T000000"
+            ),
+            ["20260302T0900", "20260303T0900", "20260304T0900"]
+        );
+    }
+
+    #[test]
+    fn test_weekly_meeting() {
+        // Mondays and Wednesdays, starting on a Monday
+        assert_eq!(
+            expand(
+                "FREQ=WEEKLY;BYDAY=MO,WE",
+                "20260105T100000",
+                "20260309T000000",
+                "20260315T235959"
+            ),
+            ["20260309T1000", "20260311T1000"]
+        );
+
+        // Every other Friday, years after the first one
+        assert_eq!(
+            expand(
+                "FREQ=WEEKLY;INTERVAL=2",
+                "20240105T150000",
+                "20260301T000000",
+                "20260331T000000"
+            ),
+            ["20260313T1500", "20260327T1500"]
+        );
+    }
+
+    #[test]
+    fn test_weekly_until_and_exdate() {
+        let rule: RecurrenceRule = "FREQ=WEEKLY;UNTIL=20260323T100000Z".parse().unwrap();
+        let occurrences = rule.occurrences(
+            at("20260302T100000"),
+            at("20260301T000000"),
+            at("20260430T000000"),
+            &[at("20260309T100000")],
+        );
+        assert_eq!(
+            occurrences,
+            [
+                at("20260302T100000"),
+                at("20260316T100000"),
+                at("20260323T100000")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_monthly_patterns() {
+        // Same day every month, skipping months without a 31st
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY",
+                "20260131T080000",
+                "20260101T000000",
+                "20260601T000000"
+            ),
+            ["20260131T0800", "20260331T0800", "20260531T0800"]
+        );
+
+        // Second Tuesday
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY;BYDAY=2TU",
+                "20260113T180000",
+                "20260201T000000",
+                "20260430T000000"
+            ),
+            ["20260210T1800", "20260310T1800", "20260414T1800"]
+        );
+
+        // Last Friday, and the last day of the month
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY;BYDAY=-1FR",
+                "20260130T120000",
+                "20260201T000000",
+                "20260331T235959"
+            ),
+            ["20260227T1200", "20260327T1200"]
+        );
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY;BYMONTHDAY=-1",
+                "20260131T000000",
+                "20260201T000000",
+                "20260331T235959"
+            ),
+            ["20260228T0000", "20260331T0000"]
+        );
+    }
+
+    #[test]
+    fn test_yearly_birthday() {
+        // All-day birthday from decades ago
+        assert_eq!(
+            expand(
+                "FREQ=YEARLY",
+                "19850314",
+                "20260101T000000",
+                "20271231T000000"
+            ),
+            ["20260314T0000", "20270314T0000"]
+        );
+
+        // Leap day birthdays only fall on leap years
+        assert_eq!(
+            expand(
+                "FREQ=YEARLY",
+                "20000229",
+                "20250101T000000",
+                "20290101T000000"
+            ),
+            ["20280229T0000"]
+        );
+
+        // Fourth Thursday of November
+        assert_eq!(
+            expand(
+                "FREQ=YEARLY;BYMONTH=11;BYDAY=4TH",
+                "20201126",
+                "20260101T000000",
+                "20261231T000000"
+            ),
+            ["20261126T0000"]
+        );
+    }
+
+    #[test]
+    fn test_endless_rule_is_bounded() {
+        let rule: RecurrenceRule = "FREQ=DAILY".parse().unwrap();
+        let occurrences = rule.occurrences(
+            at("19700101T000000"),
+            at("20260301T000000"),
+            at("20260307T235959"),
+            &[],
+        );
+        assert_eq!(occurrences.len(), 7);
+    }
+}
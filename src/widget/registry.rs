@@ -6,8 +6,10 @@
 //! - Type-erased widget factories
 //! - Registration of built-in and custom widgets
 //! - Creation of widgets from TOML configuration
+//! - Capability and config schema metadata for each widget type
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::{bail, Context, Result};
@@ -15,16 +17,30 @@ use tracing::{debug, info, warn};
 
 use super::battery::BatteryWidgetFactory;
 use super::calendar::CalendarWidgetFactory;
+use super::carousel::CarouselWidgetFactory;
 use super::countdown::CountdownWidgetFactory;
 use super::crypto::CryptoWidgetFactory;
+use super::custom::CustomWidgetFactory;
+use super::dnd::DndWidgetFactory;
+use super::guard::GuardedWidget;
+use super::launcher::LauncherWidgetFactory;
+use super::manifest::{ManifestWidgetFactory, WidgetManifest};
 use super::mpris::MprisWidgetFactory;
 use super::news::NewsWidgetFactory;
+use super::night_light::NightLightWidgetFactory;
+use super::notifications::NotificationsWidgetFactory;
+use super::perf::PerfWidgetFactory;
 use super::pomodoro::PomodoroWidgetFactory;
+use super::power_profile::PowerProfileWidgetFactory;
 use super::quotes::QuotesWidgetFactory;
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
 use super::stocks::StocksWidgetFactory;
 use super::system_monitor::SystemMonitorWidgetFactory;
 use super::traits::Widget;
+use super::vpn::VpnWidgetFactory;
 use super::{ClockWidget, WeatherWidget};
+use crate::template::Template;
+use crate::update::Schedule;
 
 /// Type-erased widget factory trait
 ///
@@ -42,6 +58,21 @@ pub trait DynWidgetFactory: Send + Sync {
 
     /// Validate configuration before creating widget
     fn validate_config(&self, config: &toml::Table) -> Result<()>;
+
+    /// Name, description and icon shown when picking widgets to add
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::for_type(self.widget_type())
+    }
+
+    /// Runtime facilities this widget type needs
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE
+    }
+
+    /// Settings this widget type accepts, for building config forms
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+    }
 }
 
 /// Registry for widget factories
@@ -50,6 +81,7 @@ pub trait DynWidgetFactory: Send + Sync {
 /// instances based on configuration.
 pub struct WidgetRegistry {
     factories: HashMap<&'static str, Arc<dyn DynWidgetFactory>>,
+    manifests: Vec<WidgetManifest>,
 }
 
 impl WidgetRegistry {
@@ -57,6 +89,7 @@ impl WidgetRegistry {
     pub fn new() -> Self {
         Self {
             factories: HashMap::new(),
+            manifests: Vec::new(),
         }
     }
 
@@ -67,16 +100,25 @@ impl WidgetRegistry {
         // Register built-in widgets
         registry.register(BatteryWidgetFactory);
         registry.register(CalendarWidgetFactory);
+        registry.register(CarouselWidgetFactory);
         registry.register(ClockWidgetFactory);
         registry.register(WeatherWidgetFactory);
         registry.register(SystemMonitorWidgetFactory);
         registry.register(CountdownWidgetFactory);
         registry.register(CryptoWidgetFactory);
+        registry.register(CustomWidgetFactory);
+        registry.register(DndWidgetFactory);
+        registry.register(LauncherWidgetFactory);
         registry.register(MprisWidgetFactory);
         registry.register(NewsWidgetFactory);
+        registry.register(NightLightWidgetFactory);
+        registry.register(NotificationsWidgetFactory);
+        registry.register(PerfWidgetFactory);
         registry.register(PomodoroWidgetFactory);
+        registry.register(PowerProfileWidgetFactory);
         registry.register(QuotesWidgetFactory);
         registry.register(StocksWidgetFactory);
+        registry.register(VpnWidgetFactory);
 
         info!(
             widget_types = ?registry.factories.keys().collect::<Vec<_>>(),
@@ -86,6 +128,51 @@ impl WidgetRegistry {
         registry
     }
 
+    /// Create a registry with built-in widgets plus any manifests found in
+    /// the user's widget directory
+    pub fn with_user_widgets() -> Self {
+        let mut registry = Self::with_builtins();
+        if let Some(dir) = WidgetManifest::manifest_dir() {
+            registry.load_manifests(&dir);
+        }
+        registry
+    }
+
+    /// Register every valid manifest in `dir`, returning how many were added
+    ///
+    /// Manifests whose type collides with an already registered widget are
+    /// skipped so a stray file can't shadow a built-in.
+    pub fn load_manifests(&mut self, dir: &Path) -> usize {
+        let mut added = 0;
+
+        for manifest in WidgetManifest::discover(dir) {
+            if self.has_widget(&manifest.widget_type) {
+                warn!(
+                    widget_type = %manifest.widget_type,
+                    path = %manifest.path.display(),
+                    "Widget manifest type already registered, skipping"
+                );
+                continue;
+            }
+
+            info!(
+                widget_type = %manifest.widget_type,
+                name = %manifest.name,
+                "Registered widget from manifest"
+            );
+            self.manifests.push(manifest.clone());
+            self.register(ManifestWidgetFactory::new(manifest));
+            added += 1;
+        }
+
+        added
+    }
+
+    /// Manifests that have been registered, for listing in the config GUI
+    pub fn manifests(&self) -> &[WidgetManifest] {
+        &self.manifests
+    }
+
     /// Register a widget factory
     pub fn register<F: DynWidgetFactory + 'static>(&mut self, factory: F) {
         let widget_type = factory.widget_type();
@@ -103,24 +190,56 @@ impl WidgetRegistry {
         self.factories.keys().copied().collect()
     }
 
+    fn factory(&self, widget_type: &str) -> Result<&dyn DynWidgetFactory> {
+        self.factories
+            .get(widget_type)
+            .map(|factory| factory.as_ref())
+            .with_context(|| {
+                format!(
+                    "Unknown widget type: '{}'. Available types: {:?}",
+                    widget_type,
+                    self.widget_types()
+                )
+            })
+    }
+
+    /// Check a widget's configuration without creating the widget
+    pub fn validate_config(&self, widget_type: &str, config: &toml::Table) -> Result<()> {
+        let factory = self.factory(widget_type)?;
+
+        // A cron schedule means the same thing for every widget, so it's
+        // checked here rather than by each factory
+        Schedule::from_config(config, "interval", 1)
+            .with_context(|| format!("Invalid configuration for widget type '{}'", widget_type))?;
+
+        factory
+            .validate_config(config)
+            .with_context(|| format!("Invalid configuration for widget type '{}'", widget_type))
+    }
+
     /// Create a widget from configuration
+    ///
+    /// The widget comes wrapped in a [`GuardedWidget`], so a panic in it
+    /// disables that widget rather than ending the process.
     pub fn create(&self, widget_type: &str, config: &toml::Table) -> Result<Box<dyn Widget>> {
-        let factory = self.factories.get(widget_type).with_context(|| {
-            format!(
-                "Unknown widget type: '{}'. Available types: {:?}",
+        let factory = self.factory(widget_type)?;
+
+        let missing = factory.capabilities().missing_features();
+        if !missing.is_empty() {
+            bail!(
+                "Widget type '{}' requires the {} feature(s), which this build was compiled without",
                 widget_type,
-                self.widget_types()
-            )
-        })?;
+                missing.join(", ")
+            );
+        }
 
         // Validate configuration first
-        factory
-            .validate_config(config)
-            .with_context(|| format!("Invalid configuration for widget type '{}'", widget_type))?;
+        self.validate_config(widget_type, config)?;
 
-        factory
+        let widget = factory
             .create(config)
-            .with_context(|| format!("Failed to create widget of type '{}'", widget_type))
+            .with_context(|| format!("Failed to create widget of type '{}'", widget_type))?;
+        Ok(Box::new(GuardedWidget::new(widget)))
     }
 
     /// Create a widget with default configuration
@@ -131,7 +250,28 @@ impl WidgetRegistry {
             .with_context(|| format!("Unknown widget type: '{}'", widget_type))?;
 
         let config = factory.default_config();
-        factory.create(&config)
+        self.create(widget_type, &config)
+    }
+
+    /// Get the name, description and icon of a widget type
+    pub fn metadata(&self, widget_type: &str) -> Option<WidgetMetadata> {
+        self.factories.get(widget_type).map(|f| f.metadata())
+    }
+
+    /// Get the capabilities declared by a widget type
+    pub fn capabilities(&self, widget_type: &str) -> Option<WidgetCapabilities> {
+        self.factories.get(widget_type).map(|f| f.capabilities())
+    }
+
+    /// Get the config schema for a widget type
+    pub fn config_schema(&self, widget_type: &str) -> Option<ConfigSchema> {
+        self.factories.get(widget_type).map(|f| f.config_schema())
+    }
+
+    /// Check whether a widget type is registered and usable in this build
+    pub fn is_available(&self, widget_type: &str) -> bool {
+        self.capabilities(widget_type)
+            .is_some_and(|caps| caps.is_supported())
     }
 
     /// Get default configuration for a widget type
@@ -163,6 +303,14 @@ impl DynWidgetFactory for ClockWidgetFactory {
         "clock"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Clock",
+            "Current time and date",
+            "preferences-system-time-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
         let format = config
             .get("format")
@@ -179,14 +327,82 @@ impl DynWidgetFactory for ClockWidgetFactory {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        let hourly_chime = config
+            .get("hourly_chime")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let show_week = config
+            .get("show_week")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let show_day_of_year = config
+            .get("show_day_of_year")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let custom_format = config.get("custom_format").and_then(|v| v.as_str());
+
+        let timezone = config
+            .get("timezone")
+            .and_then(|v| v.as_str())
+            .map(ClockWidget::parse_timezone)
+            .transpose()?;
+
+        // A clock for another region says which one, unless told otherwise
+        let label = config
+            .get("label")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| timezone.map(ClockWidget::timezone_label));
+
         debug!(
             format = %format,
+            custom_format = ?custom_format,
+            timezone = ?timezone,
             show_seconds = %show_seconds,
             show_date = %show_date,
+            hourly_chime = %hourly_chime,
             "Creating ClockWidget"
         );
 
-        Ok(Box::new(ClockWidget::new(format, show_seconds, show_date)))
+        // Anything other than the two presets is treated as a format template
+        let clock = if format == "12h" || format == "24h" {
+            ClockWidget::new(format, show_seconds, show_date)
+        } else {
+            let template = Template::parse_with_names(format, ClockWidget::TEMPLATE_PLACEHOLDERS)?;
+            ClockWidget::new("24h", show_seconds, show_date).with_template(template)
+        };
+
+        // A broken custom format shouldn't cost the user their clock
+        let clock = match custom_format.map(|f| (f, ClockWidget::check_custom_format(f))) {
+            Some((custom_format, Ok(()))) => clock.with_custom_format(custom_format),
+            Some((custom_format, Err(e))) => {
+                warn!(
+                    error = %e,
+                    custom_format = %custom_format,
+                    fallback = %format,
+                    "Invalid clock custom_format, using format"
+                );
+                clock
+            }
+            None => clock,
+        };
+        let clock = match timezone {
+            Some(timezone) => clock.with_timezone(timezone),
+            None => clock,
+        };
+        let clock = match label {
+            Some(label) => clock.with_label(&label),
+            None => clock,
+        };
+        Ok(Box::new(
+            clock
+                .with_week_number(show_week)
+                .with_day_of_year(show_day_of_year)
+                .with_hourly_chime(hourly_chime),
+        ))
     }
 
     fn default_config(&self) -> toml::Table {
@@ -202,11 +418,75 @@ impl DynWidgetFactory for ClockWidgetFactory {
             let format_str = format.as_str().context("'format' must be a string")?;
 
             if format_str != "12h" && format_str != "24h" {
-                bail!("'format' must be '12h' or '24h', got '{}'", format_str);
+                if !format_str.contains('{') {
+                    bail!(
+                        "'format' must be '12h', '24h' or a template, got '{}'",
+                        format_str
+                    );
+                }
+                Template::parse_with_names(format_str, ClockWidget::TEMPLATE_PLACEHOLDERS)
+                    .map_err(|e| anyhow::anyhow!("Invalid 'format' template: {}", e))?;
             }
         }
+
+        if let Some(custom_format) = config.get("custom_format") {
+            custom_format
+                .as_str()
+                .context("'custom_format' must be a string")?;
+        }
+
+        if let Some(timezone) = config.get("timezone") {
+            let timezone = timezone.as_str().context("'timezone' must be a string")?;
+            ClockWidget::parse_timezone(timezone)?;
+        }
+
+        if let Some(label) = config.get("label") {
+            label.as_str().context("'label' must be a string")?;
+        }
         Ok(())
     }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(
+                ConfigField::new(
+                    "format",
+                    "Format",
+                    FieldKind::Template(ClockWidget::TEMPLATE_PLACEHOLDERS),
+                )
+                .with_description("'12h', '24h' or a format template"),
+            )
+            .with_field(
+                ConfigField::new("custom_format", "Custom format", FieldKind::Text)
+                    .with_description("strftime format, e.g. \"%a %d %b  %H:%M\""),
+            )
+            .with_field(
+                ConfigField::new("timezone", "Timezone", FieldKind::Text)
+                    .with_description("IANA name like \"Europe/Oslo\"; local time if empty"),
+            )
+            .with_field(
+                ConfigField::new("label", "Label", FieldKind::Text)
+                    .with_description("Second line under the time; the timezone's city by default"),
+            )
+            .with_field(ConfigField::new(
+                "show_seconds",
+                "Show seconds",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new("show_date", "Show date", FieldKind::Bool))
+            .with_field(
+                ConfigField::new("show_week", "Show week number", FieldKind::Bool)
+                    .with_description("ISO week number on the date line"),
+            )
+            .with_field(
+                ConfigField::new("show_day_of_year", "Show day of year", FieldKind::Bool)
+                    .with_description("Day of the year on the date line"),
+            )
+            .with_field(
+                ConfigField::new("hourly_chime", "Hourly chime", FieldKind::Bool)
+                    .with_description("Play the \"hour\" sound event on the hour"),
+            )
+    }
 }
 
 /// Factory for WeatherWidget
@@ -217,6 +497,14 @@ impl DynWidgetFactory for WeatherWidgetFactory {
         "weather"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Weather",
+            "Current conditions from OpenWeatherMap",
+            "weather-few-clouds-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
         let city = config
             .get("city")
@@ -235,6 +523,9 @@ impl DynWidgetFactory for WeatherWidgetFactory {
             .and_then(|v| v.as_integer())
             .unwrap_or(600) as u64;
 
+        let template =
+            Template::from_config(config, "format", WeatherWidget::TEMPLATE_PLACEHOLDERS)?;
+
         debug!(
             city = %city,
             temperature_unit = %temperature_unit,
@@ -243,12 +534,12 @@ impl DynWidgetFactory for WeatherWidgetFactory {
             "Creating WeatherWidget"
         );
 
-        Ok(Box::new(WeatherWidget::new(
-            city,
-            api_key,
-            temperature_unit,
-            update_interval,
-        )))
+        let mut widget = WeatherWidget::new(city, api_key, temperature_unit, update_interval);
+        if let Some(template) = template {
+            widget = widget.with_template(template);
+        }
+
+        Ok(Box::new(widget))
     }
 
     fn default_config(&self) -> toml::Table {
@@ -290,16 +581,51 @@ impl DynWidgetFactory for WeatherWidgetFactory {
             }
         }
 
+        Template::from_config(config, "format", WeatherWidget::TEMPLATE_PLACEHOLDERS)?;
+
         Ok(())
     }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_network()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new("city", "City", FieldKind::Text))
+            .with_field(
+                ConfigField::new("api_key", "API key", FieldKind::Secret)
+                    .with_description("OpenWeatherMap API key"),
+            )
+            .with_field(ConfigField::new(
+                "temperature_unit",
+                "Temperature unit",
+                FieldKind::Choice(&["celsius", "fahrenheit"]),
+            ))
+            .with_field(ConfigField::new(
+                "update_interval",
+                "Update interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(ConfigField::new(
+                "format",
+                "Format",
+                FieldKind::Template(WeatherWidget::TEMPLATE_PLACEHOLDERS),
+            ))
+    }
 }
 
 // ============================================================================
 // Widget Instance Configuration
 // ============================================================================
 
+use crate::audio::SoundConfig;
 use crate::config::Margin;
 use crate::position::Position;
+use crate::theme::{Color, Theme};
 
 /// Configuration for a single widget instance
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -324,10 +650,27 @@ pub struct WidgetInstance {
     // All optional - fall back to panel defaults if not specified
 
     /// Per-widget position on screen (optional - falls back to panel default)
-    /// Values: "top-left", "top-right", "center", etc.
+    /// Values: "top-left", "top-right", "center", etc., or "absolute" to
+    /// place the widget at `x`/`y`
     #[serde(default)]
     pub position: Option<String>,
 
+    /// Horizontal coordinate for `position = "absolute"`, in pixels from
+    /// the left edge of the output, or from the right edge if negative
+    #[serde(default)]
+    pub x: Option<i32>,
+
+    /// Vertical coordinate for `position = "absolute"`, in pixels from
+    /// the top edge of the output, or from the bottom edge if negative
+    #[serde(default)]
+    pub y: Option<i32>,
+
+    /// Output (monitor) to show the widget on, by connector name like
+    /// "DP-1" (optional - the compositor picks one when unset or when that
+    /// output isn't connected)
+    #[serde(default)]
+    pub output: Option<String>,
+
     /// Per-widget width in pixels (optional)
     #[serde(default)]
     pub width: Option<u32>,
@@ -361,8 +704,57 @@ pub struct WidgetInstance {
     /// Overrides the panel theme for this widget only
     #[serde(default)]
     pub theme_override: Option<String>,
+
+    /// Per-widget accent color (optional - falls back to the theme accent)
+    /// Hex color like "#f38ba8" or a theme token like "@text_primary"
+    #[serde(default)]
+    pub accent: Option<String>,
+
+    /// Draw only text and icons, without background, border or corners
+    /// (the classic conky look). Text always gets a strong shadow.
+    #[serde(default)]
+    pub frameless: bool,
+
+    /// Per-widget sound (optional - falls back to the `[sounds]` effect)
+    /// Built-in name like "chime" or a path to a wav/ogg/mp3/flac file
+    #[serde(default)]
+    pub sound: Option<String>,
+
+    /// Surface group (optional). Widgets with the same group share one
+    /// surface, placed and styled by the first of them
+    #[serde(default)]
+    pub group: Option<String>,
+
+    /// Grid columns this widget covers when its group is a grid (default 1)
+    #[serde(default)]
+    pub column_span: Option<u32>,
+
+    /// Grid rows this widget covers when its group is a grid (default 1)
+    #[serde(default)]
+    pub row_span: Option<u32>,
+
+    /// Collapse to a compact form, and expand again, on click. The state
+    /// is remembered across restarts
+    #[serde(default)]
+    pub collapsible: bool,
+
+    /// Height in pixels while collapsed (optional)
+    #[serde(default)]
+    pub compact_height: Option<u32>,
+
+    /// Keep maximized windows clear of the widget, like a dock. Only works
+    /// at an edge's center: top-center, bottom-center, center-left or
+    /// center-right
+    #[serde(default)]
+    pub reserve_space: bool,
 }
 
+/// `position` value that places a widget at its `x`/`y` coordinates
+pub const ABSOLUTE_POSITION: &str = "absolute";
+
+/// Height of a collapsed widget without a `compact_height`
+pub const DEFAULT_COMPACT_HEIGHT: u32 = 48;
+
 fn default_true() -> bool {
     true
 }
@@ -376,6 +768,9 @@ impl WidgetInstance {
             id: None,
             config: toml::Table::new(),
             position: None,
+            x: None,
+            y: None,
+            output: None,
             width: None,
             height: None,
             margin_top: None,
@@ -384,6 +779,15 @@ impl WidgetInstance {
             margin_left: None,
             opacity: None,
             theme_override: None,
+            accent: None,
+            frameless: false,
+            sound: None,
+            group: None,
+            column_span: None,
+            row_span: None,
+            collapsible: false,
+            compact_height: None,
+            reserve_space: false,
         }
     }
 
@@ -395,6 +799,9 @@ impl WidgetInstance {
             id: None,
             config,
             position: None,
+            x: None,
+            y: None,
+            output: None,
             width: None,
             height: None,
             margin_top: None,
@@ -403,6 +810,15 @@ impl WidgetInstance {
             margin_left: None,
             opacity: None,
             theme_override: None,
+            accent: None,
+            frameless: false,
+            sound: None,
+            group: None,
+            column_span: None,
+            row_span: None,
+            collapsible: false,
+            compact_height: None,
+            reserve_space: false,
         }
     }
 
@@ -416,13 +832,51 @@ impl WidgetInstance {
     // ============================================================================
 
     /// Get effective position (widget-specific or panel default)
+    ///
+    /// An absolute widget reports the corner it is anchored to.
     pub fn effective_position(&self, panel_default: &Position) -> Position {
+        if let Some((corner, _)) = self.absolute_placement() {
+            return corner;
+        }
         self.position
             .as_ref()
             .and_then(|p| p.parse().ok())
             .unwrap_or(*panel_default)
     }
 
+    /// Whether the widget is placed at explicit `x`/`y` coordinates
+    pub fn is_absolute(&self) -> bool {
+        self.position.as_deref() == Some(ABSOLUTE_POSITION)
+    }
+
+    /// Corner and margins that put an absolute widget at its `x`/`y`
+    ///
+    /// Layer-shell surfaces are placed by anchoring them to edges, so the
+    /// coordinates become margins from the top-left corner. A negative
+    /// coordinate anchors to the right or bottom edge instead, which keeps
+    /// the widget in place on outputs of any size. Missing coordinates
+    /// count as 0. Returns None unless the widget is absolute.
+    pub fn absolute_placement(&self) -> Option<(Position, Margin)> {
+        if !self.is_absolute() {
+            return None;
+        }
+        let x = self.x.unwrap_or(0);
+        let y = self.y.unwrap_or(0);
+        let corner = match (x < 0, y < 0) {
+            (false, false) => Position::TopLeft,
+            (true, false) => Position::TopRight,
+            (false, true) => Position::BottomLeft,
+            (true, true) => Position::BottomRight,
+        };
+        let margin = Margin {
+            top: y.max(0),
+            right: x.saturating_neg().max(0),
+            bottom: y.saturating_neg().max(0),
+            left: x.max(0),
+        };
+        Some((corner, margin))
+    }
+
     /// Get effective width (widget-specific or panel default)
     pub fn effective_width(&self, panel_default: u32) -> u32 {
         self.width.unwrap_or(panel_default)
@@ -433,6 +887,13 @@ impl WidgetInstance {
         self.height.unwrap_or(panel_default)
     }
 
+    /// Height while collapsed, never taller than the expanded `height`
+    pub fn effective_compact_height(&self, expanded: u32) -> u32 {
+        self.compact_height
+            .unwrap_or(DEFAULT_COMPACT_HEIGHT)
+            .min(expanded)
+    }
+
     /// Get effective opacity (widget-specific or panel default)
     pub fn effective_opacity(&self, panel_default: f32) -> f32 {
         self.opacity.unwrap_or(panel_default).clamp(0.0, 1.0)
@@ -455,6 +916,27 @@ impl WidgetInstance {
         self.theme_override.as_deref().unwrap_or(panel_default)
     }
 
+    /// Get effective accent color (widget-specific or theme accent)
+    ///
+    /// Invalid colors fall back to the theme accent; [`Config::validate`]
+    /// warns about them when the config is loaded.
+    ///
+    /// [`Config::validate`]: crate::config::Config::validate
+    pub fn effective_accent(&self, theme: &Theme) -> Color {
+        self.accent
+            .as_deref()
+            .and_then(|accent| theme.resolve_color(accent))
+            .unwrap_or(theme.accent)
+    }
+
+    /// Sound settings for this widget, with its `sound` replacing the effect
+    pub fn effective_sound(&self, base: &SoundConfig) -> SoundConfig {
+        match &self.sound {
+            Some(effect) => base.with_effect(effect),
+            None => base.clone(),
+        }
+    }
+
     /// Check if this widget has per-widget positioning configured
     pub fn has_custom_position(&self) -> bool {
         self.position.is_some()
@@ -475,13 +957,17 @@ impl WidgetInstance {
 
     /// Check if this widget has any custom appearance settings
     pub fn has_custom_appearance(&self) -> bool {
-        self.opacity.is_some() || self.theme_override.is_some()
+        self.opacity.is_some()
+            || self.theme_override.is_some()
+            || self.accent.is_some()
+            || self.frameless
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::widget::WidgetContent;
 
     #[test]
     fn test_registry_with_builtins() {
@@ -539,6 +1025,124 @@ mod tests {
         assert!(instance.id.is_none());
     }
 
+    #[test]
+    fn test_widget_instance_accent() {
+        let theme = Theme::cosmic_dark();
+        let mut instance = WidgetInstance::new("pomodoro");
+        assert_eq!(
+            instance.effective_accent(&theme).to_array(),
+            theme.accent.to_array()
+        );
+
+        instance.accent = Some("#f38ba8".to_string());
+        assert_eq!(
+            instance.effective_accent(&theme).to_array(),
+            [0xf3, 0x8b, 0xa8, 255]
+        );
+        assert!(instance.has_custom_appearance());
+
+        instance.accent = Some("@text_secondary".to_string());
+        assert_eq!(
+            instance.effective_accent(&theme).to_array(),
+            theme.text_secondary.to_array()
+        );
+
+        instance.accent = Some("not a color".to_string());
+        assert_eq!(
+            instance.effective_accent(&theme).to_array(),
+            theme.accent.to_array()
+        );
+    }
+
+    #[test]
+    fn test_widget_instance_sound() {
+        let base = SoundConfig {
+            enabled: true,
+            effect: "alarm".to_string(),
+            volume: 0.5,
+            repeat: 3,
+        };
+        let mut instance = WidgetInstance::new("pomodoro");
+        assert_eq!(instance.effective_sound(&base).effect, "alarm");
+
+        instance.sound = Some("~/sounds/bell.ogg".to_string());
+        let sound = instance.effective_sound(&base);
+        assert_eq!(sound.effect, "~/sounds/bell.ogg");
+        assert_eq!((sound.volume, sound.repeat), (0.5, 3));
+    }
+
+    #[test]
+    fn test_widget_instance_reserve_space() {
+        let instance = WidgetInstance::new("clock");
+        assert!(!instance.reserve_space);
+
+        let instance: WidgetInstance = toml::from_str(
+            "type = \"launcher\"\nposition = \"bottom-center\"\nreserve_space = true",
+        )
+        .unwrap();
+        assert!(instance.reserve_space);
+    }
+
+    #[test]
+    fn test_widget_instance_frameless() {
+        let instance: WidgetInstance = toml::from_str("type = \"clock\"").unwrap();
+        assert!(!instance.frameless);
+        assert!(!instance.has_custom_appearance());
+
+        let instance: WidgetInstance =
+            toml::from_str("type = \"clock\"\nframeless = true").unwrap();
+        assert!(instance.frameless);
+        assert!(instance.has_custom_appearance());
+    }
+
+    #[test]
+    fn test_widget_instance_collapsible() {
+        let instance: WidgetInstance = toml::from_str("type = \"weather\"").unwrap();
+        assert!(!instance.collapsible);
+        assert_eq!(
+            instance.effective_compact_height(120),
+            DEFAULT_COMPACT_HEIGHT
+        );
+        assert_eq!(instance.effective_compact_height(30), 30);
+
+        let instance: WidgetInstance =
+            toml::from_str("type = \"weather\"\ncollapsible = true\ncompact_height = 60").unwrap();
+        assert!(instance.collapsible);
+        assert_eq!(instance.effective_compact_height(120), 60);
+    }
+
+    #[test]
+    fn test_widget_instance_absolute() {
+        let instance: WidgetInstance =
+            toml::from_str("type = \"clock\"\nposition = \"top-left\"\nx = 40").unwrap();
+        assert!(!instance.is_absolute());
+        assert!(instance.absolute_placement().is_none());
+
+        let instance: WidgetInstance =
+            toml::from_str("type = \"clock\"\nposition = \"absolute\"\nx = 40\ny = 120").unwrap();
+        assert!(instance.is_absolute());
+        let (corner, margin) = instance.absolute_placement().unwrap();
+        assert_eq!(corner, Position::TopLeft);
+        assert_eq!(
+            (margin.top, margin.right, margin.bottom, margin.left),
+            (120, 0, 0, 40)
+        );
+        assert_eq!(
+            instance.effective_position(&Position::Center),
+            Position::TopLeft
+        );
+
+        // Negative coordinates count from the right and bottom edges
+        let instance: WidgetInstance =
+            toml::from_str("type = \"clock\"\nposition = \"absolute\"\nx = -20\ny = -30").unwrap();
+        let (corner, margin) = instance.absolute_placement().unwrap();
+        assert_eq!(corner, Position::BottomRight);
+        assert_eq!(
+            (margin.top, margin.right, margin.bottom, margin.left),
+            (0, 20, 30, 0)
+        );
+    }
+
     #[test]
     fn test_clock_config_validation() {
         let factory = ClockWidgetFactory;
@@ -555,5 +1159,187 @@ mod tests {
             toml::Value::String("invalid".to_string()),
         );
         assert!(factory.validate_config(&invalid).is_err());
+
+        // Template format
+        let mut template = toml::Table::new();
+        template.insert(
+            "format".to_string(),
+            toml::Value::String("{hour:02}:{minute:02}".to_string()),
+        );
+        assert!(factory.validate_config(&template).is_ok());
+
+        // Template with unknown placeholder
+        let mut unknown = toml::Table::new();
+        unknown.insert(
+            "format".to_string(),
+            toml::Value::String("{temp}".to_string()),
+        );
+        assert!(factory.validate_config(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_clock_custom_format_fallback() {
+        let registry = WidgetRegistry::with_builtins();
+
+        let mut config = toml::Table::new();
+        config.insert(
+            "custom_format".to_string(),
+            toml::Value::String("%Y|%j".to_string()),
+        );
+        let clock = registry.create("clock", &config).unwrap();
+        let WidgetContent::Text { text, .. } = clock.content() else {
+            panic!("clock content should be text");
+        };
+        assert!(text.contains('|'));
+
+        // Parse errors fall back to the preset
+        config.insert(
+            "custom_format".to_string(),
+            toml::Value::String("%H %Q".to_string()),
+        );
+        let clock = registry.create("clock", &config).unwrap();
+        let WidgetContent::Text { text, .. } = clock.content() else {
+            panic!("clock content should be text");
+        };
+        assert_eq!(text.matches(':').count(), 2);
+
+        config.insert("custom_format".to_string(), toml::Value::Integer(3));
+        assert!(registry.create("clock", &config).is_err());
+    }
+
+    #[test]
+    fn test_clock_timezone_config() {
+        let factory = ClockWidgetFactory;
+
+        let mut config = toml::Table::new();
+        config.insert(
+            "timezone".to_string(),
+            toml::Value::String("Europe/Oslo".to_string()),
+        );
+        assert!(factory.validate_config(&config).is_ok());
+        let clock = factory.create(&config).unwrap();
+        let WidgetContent::MultiLine { lines } = clock.content() else {
+            panic!("clock with a timezone should show its label");
+        };
+        assert_eq!(lines[1].0, "Oslo");
+
+        config.insert(
+            "label".to_string(),
+            toml::Value::String("Office".to_string()),
+        );
+        let clock = factory.create(&config).unwrap();
+        let WidgetContent::MultiLine { lines } = clock.content() else {
+            panic!("clock with a label should show it");
+        };
+        assert_eq!(lines[1].0, "Office");
+
+        config.insert(
+            "timezone".to_string(),
+            toml::Value::String("Oslo".to_string()),
+        );
+        assert!(factory.validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_create_checks_schedule() {
+        let registry = WidgetRegistry::with_builtins();
+        let mut config = toml::Table::new();
+        config.insert(
+            "schedule".to_string(),
+            toml::Value::String("@hourly".to_string()),
+        );
+        assert!(registry.create("quotes", &config).is_ok());
+
+        config.insert(
+            "schedule".to_string(),
+            toml::Value::String("0 61 * * * *".to_string()),
+        );
+        let error = registry.create("quotes", &config).err().unwrap();
+        assert!(format!("{:#}", error).contains("minute value 61"));
+    }
+
+    #[test]
+    fn test_builtin_schemas_accept_defaults() {
+        let registry = WidgetRegistry::with_builtins();
+        for widget_type in registry.widget_types() {
+            let schema = registry.config_schema(widget_type).unwrap();
+            let defaults = registry.default_config(widget_type).unwrap();
+            assert!(!schema.is_empty(), "{} has no config schema", widget_type);
+            assert!(
+                schema.invalid_keys(&defaults).is_empty(),
+                "{} defaults don't match its schema: {:?}",
+                widget_type,
+                schema.invalid_keys(&defaults)
+            );
+        }
+    }
+
+    #[test]
+    fn test_builtin_metadata() {
+        let registry = WidgetRegistry::with_builtins();
+        for widget_type in registry.widget_types() {
+            let metadata = registry.metadata(widget_type).unwrap();
+            assert!(
+                !metadata.description.is_empty(),
+                "{} has no description",
+                widget_type
+            );
+        }
+        assert_eq!(registry.metadata("mpris").unwrap().name, "Now Playing");
+        assert!(registry.metadata("unknown").is_none());
+    }
+
+    #[test]
+    fn test_builtin_capabilities() {
+        let registry = WidgetRegistry::with_builtins();
+        assert!(registry.capabilities("weather").unwrap().network);
+        assert!(registry.capabilities("mpris").unwrap().dbus);
+        assert!(registry.capabilities("quotes").unwrap().interactive);
+        assert_eq!(
+            registry.capabilities("clock"),
+            Some(WidgetCapabilities::NONE)
+        );
+        assert!(registry.capabilities("nonexistent").is_none());
+        assert!(registry.is_available("clock"));
+        assert!(!registry.is_available("nonexistent"));
+    }
+
+    struct ChimeFactory;
+
+    impl DynWidgetFactory for ChimeFactory {
+        fn widget_type(&self) -> &'static str {
+            "chime"
+        }
+
+        fn create(&self, _config: &toml::Table) -> Result<Box<dyn Widget>> {
+            Ok(Box::new(ClockWidget::new("24h", false, false)))
+        }
+
+        fn default_config(&self) -> toml::Table {
+            toml::Table::new()
+        }
+
+        fn validate_config(&self, _config: &toml::Table) -> Result<()> {
+            Ok(())
+        }
+
+        fn capabilities(&self) -> WidgetCapabilities {
+            WidgetCapabilities::NONE.with_audio()
+        }
+    }
+
+    #[test]
+    fn test_create_refuses_missing_feature() {
+        let mut registry = WidgetRegistry::new();
+        registry.register(ChimeFactory);
+
+        let result = registry.create("chime", &toml::Table::new());
+        if cfg!(feature = "audio") {
+            assert!(result.is_ok());
+        } else {
+            let err = result.err().unwrap().to_string();
+            assert!(err.contains("audio"), "unexpected error: {}", err);
+            assert!(!registry.is_available("chime"));
+        }
     }
 }
@@ -0,0 +1,280 @@
+//! Recording and replaying widget content
+//!
+//! Started with `--record FILE`, the widget appends what each widget shows
+//! to `FILE` whenever it changes: weather as it was fetched, the track MPRIS
+//! reported, system monitor samples and so on. Started with `--replay FILE`,
+//! the widgets in the recording are replaced by [`ReplayWidget`]s that show
+//! the same content on the same timeline, without fetching anything, so a
+//! bug report or a screenshot can be reproduced on any machine.
+//!
+//! Recordings are JSON lines, one [`RecordedFrame`] each.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::traits::{Widget, WidgetContent, WidgetInfo};
+
+/// How long the last frame stays up before a replay starts over
+const LOOP_HOLD: Duration = Duration::from_secs(5);
+
+/// How often a replaying widget checks for its next frame
+const REPLAY_TICK: Duration = Duration::from_millis(250);
+
+/// What one widget showed from a point in a recording on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// Milliseconds since the recording started
+    pub at_ms: u64,
+    /// Instance id of the widget
+    pub widget: String,
+    /// What it showed
+    pub content: WidgetContent,
+}
+
+/// Appends widget content to a recording as it changes
+pub struct Recorder {
+    writer: BufWriter<File>,
+    started: Instant,
+    /// Last content written per widget, so unchanged frames are skipped
+    last: HashMap<String, WidgetContent>,
+}
+
+impl Recorder {
+    /// Start a new recording at `path`, replacing any file there
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .with_context(|| format!("Failed to create recording {}", path.display()))?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            started: Instant::now(),
+            last: HashMap::new(),
+        })
+    }
+
+    /// Record `content` for `widget` if it changed since the last call
+    pub fn record(&mut self, widget: &str, content: &WidgetContent) -> Result<()> {
+        if self.last.get(widget) == Some(content) {
+            return Ok(());
+        }
+        let frame = RecordedFrame {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            widget: widget.to_string(),
+            content: content.clone(),
+        };
+        serde_json::to_writer(&mut self.writer, &frame).context("Failed to encode frame")?;
+        self.writer.write_all(b"\n")?;
+        // Flushed per frame so a crash still leaves a usable recording
+        self.writer.flush().context("Failed to write recording")?;
+        self.last.insert(frame.widget, frame.content);
+        Ok(())
+    }
+}
+
+/// A loaded recording, with each widget's frames in order
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    frames: HashMap<String, Vec<(Duration, WidgetContent)>>,
+    /// Time from the start to when the replay starts over
+    length: Duration,
+}
+
+impl Recording {
+    /// Load a recording written by a [`Recorder`]
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open recording {}", path.display()))?;
+        let mut frames = Vec::new();
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.context("Failed to read recording")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let frame: RecordedFrame = serde_json::from_str(&line).with_context(|| {
+                format!("Invalid frame on line {} of {}", number + 1, path.display())
+            })?;
+            frames.push(frame);
+        }
+        Ok(Self::from_frames(frames))
+    }
+
+    /// Build a recording from frames in any order
+    pub fn from_frames(frames: impl IntoIterator<Item = RecordedFrame>) -> Self {
+        let mut recording = Self::default();
+        let mut last = Duration::ZERO;
+        for frame in frames {
+            let at = Duration::from_millis(frame.at_ms);
+            last = last.max(at);
+            recording
+                .frames
+                .entry(frame.widget)
+                .or_default()
+                .push((at, frame.content));
+        }
+        for frames in recording.frames.values_mut() {
+            frames.sort_by_key(|(at, _)| *at);
+        }
+        recording.length = last + LOOP_HOLD;
+        recording
+    }
+
+    /// Whether the recording has frames for `widget`
+    pub fn contains(&self, widget: &str) -> bool {
+        self.frames.contains_key(widget)
+    }
+
+    /// A widget replaying `widget`'s frames, laid out like `info`, or None
+    /// if the recording has none
+    pub fn replay(&self, widget: &str, info: WidgetInfo) -> Option<ReplayWidget> {
+        let frames = self.frames.get(widget)?.clone();
+        Some(ReplayWidget {
+            info,
+            frames,
+            length: self.length,
+            started: Instant::now(),
+            current: WidgetContent::Empty,
+        })
+    }
+}
+
+/// Stands in for a widget during a replay, showing its recorded content
+pub struct ReplayWidget {
+    info: WidgetInfo,
+    frames: Vec<(Duration, WidgetContent)>,
+    length: Duration,
+    started: Instant,
+    current: WidgetContent,
+}
+
+impl ReplayWidget {
+    /// The frame on show `elapsed` into the replay
+    fn frame_at(&self, elapsed: Duration) -> &WidgetContent {
+        let position = if self.length.is_zero() {
+            elapsed
+        } else {
+            Duration::from_nanos((elapsed.as_nanos() % self.length.as_nanos()) as u64)
+        };
+        // Until a widget's first frame, it shows that frame
+        let shown = self.frames.partition_point(|(at, _)| *at <= position);
+        self.frames
+            .get(shown.saturating_sub(1))
+            .map_or(&WidgetContent::Empty, |(_, content)| content)
+    }
+}
+
+impl Widget for ReplayWidget {
+    fn info(&self) -> WidgetInfo {
+        self.info.clone()
+    }
+
+    fn update(&mut self) {
+        self.current = self.frame_at(self.started.elapsed()).clone();
+    }
+
+    fn content(&self) -> WidgetContent {
+        self.current.clone()
+    }
+
+    fn content_ref(&self) -> Cow<'_, WidgetContent> {
+        Cow::Borrowed(&self.current)
+    }
+
+    fn update_interval(&self) -> Duration {
+        REPLAY_TICK
+    }
+
+    fn on_start(&mut self) {
+        self.started = Instant::now();
+        self.update();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::FontSize;
+
+    fn text(value: &str) -> WidgetContent {
+        WidgetContent::Text {
+            text: value.to_string(),
+            size: FontSize::Medium,
+        }
+    }
+
+    fn frame(at_ms: u64, widget: &str, value: &str) -> RecordedFrame {
+        RecordedFrame {
+            at_ms,
+            widget: widget.to_string(),
+            content: text(value),
+        }
+    }
+
+    fn info() -> WidgetInfo {
+        WidgetInfo {
+            id: "weather",
+            name: "Weather",
+            preferred_height: 80.0,
+            min_height: 60.0,
+            expand: false,
+        }
+    }
+
+    #[test]
+    fn test_record_and_load() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("session.jsonl");
+
+        let mut recorder = Recorder::create(&path).unwrap();
+        recorder.record("weather", &text("12°C")).unwrap();
+        recorder.record("weather", &text("12°C")).unwrap();
+        recorder.record("cpu", &text("CPU 4%")).unwrap();
+        recorder.record("weather", &text("13°C")).unwrap();
+        drop(recorder);
+
+        let lines = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(lines.lines().count(), 3);
+
+        let recording = Recording::load(&path).unwrap();
+        assert!(recording.contains("weather"));
+        assert!(recording.contains("cpu"));
+        assert!(!recording.contains("clock"));
+        assert!(recording.replay("clock", info()).is_none());
+    }
+
+    #[test]
+    fn test_replay_timeline() {
+        let recording = Recording::from_frames([
+            frame(2000, "weather", "13°C"),
+            frame(500, "weather", "12°C"),
+            frame(1000, "cpu", "CPU 4%"),
+        ]);
+        let widget = recording.replay("weather", info()).unwrap();
+        assert_eq!(widget.info().preferred_height, 80.0);
+
+        assert_eq!(widget.frame_at(Duration::ZERO), &text("12°C"));
+        assert_eq!(widget.frame_at(Duration::from_millis(1999)), &text("12°C"));
+        assert_eq!(widget.frame_at(Duration::from_millis(2000)), &text("13°C"));
+
+        // Loops after the last frame has been held
+        let restart = Duration::from_millis(2000) + LOOP_HOLD;
+        assert_eq!(widget.frame_at(restart), &text("12°C"));
+    }
+
+    #[test]
+    fn test_load_rejects_garbage() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("broken.jsonl");
+        std::fs::write(&path, "{\"at_ms\": 0}\n").unwrap();
+        assert!(Recording::load(&path).is_err());
+    }
+}
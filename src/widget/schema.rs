@@ -0,0 +1,447 @@
+//! Widget capability and configuration schema metadata
+//!
+//! Factories describe what a widget type is ([`WidgetMetadata`]), what it
+//! needs at runtime ([`WidgetCapabilities`]) and which settings it accepts
+//! ([`ConfigSchema`]).
+//! The registry uses capabilities to refuse widgets whose backing feature was
+//! compiled out, and the config GUI uses schemas to build settings forms
+//! without hard-coding every widget type.
+
+// ============================================================================
+// Metadata
+// ============================================================================
+
+/// How a widget type is presented when picking widgets to add
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WidgetMetadata {
+    /// Human-readable name
+    pub name: String,
+    /// One-line description of what the widget shows
+    pub description: String,
+    /// Icon name from the icon theme
+    pub icon: String,
+}
+
+impl WidgetMetadata {
+    /// Icon for widget types that don't name their own
+    pub const DEFAULT_ICON: &'static str = "applications-utilities-symbolic";
+
+    /// Describe a widget type
+    pub fn new(name: &str, description: &str, icon: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            icon: icon.to_string(),
+        }
+    }
+
+    /// Bare metadata for a widget type that has none, named after the type
+    /// ("system_monitor" becomes "System monitor")
+    pub fn for_type(widget_type: &str) -> Self {
+        let mut chars = widget_type.chars();
+        let name = chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+            .replace('_', " ");
+        Self::new(&name, "", Self::DEFAULT_ICON)
+    }
+}
+
+// ============================================================================
+// Capabilities
+// ============================================================================
+
+/// Runtime facilities a widget type relies on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WidgetCapabilities {
+    /// Fetches data over the network
+    pub network: bool,
+    /// Talks to services over D-Bus
+    pub dbus: bool,
+    /// Plays sounds (requires the `audio` feature)
+    pub audio: bool,
+    /// Responds to clicks or scrolling
+    pub interactive: bool,
+}
+
+impl WidgetCapabilities {
+    /// A widget that needs nothing beyond rendering
+    pub const NONE: Self = Self {
+        network: false,
+        dbus: false,
+        audio: false,
+        interactive: false,
+    };
+
+    /// Mark the widget as needing network access
+    pub const fn with_network(mut self) -> Self {
+        self.network = true;
+        self
+    }
+
+    /// Mark the widget as needing D-Bus
+    pub const fn with_dbus(mut self) -> Self {
+        self.dbus = true;
+        self
+    }
+
+    /// Mark the widget as needing audio playback
+    pub const fn with_audio(mut self) -> Self {
+        self.audio = true;
+        self
+    }
+
+    /// Mark the widget as handling pointer input
+    pub const fn with_interactive(mut self) -> Self {
+        self.interactive = true;
+        self
+    }
+
+    /// Cargo features this widget needs that aren't compiled into this build
+    ///
+    /// Network and D-Bus support are always built in; only audio is optional.
+    pub fn missing_features(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if self.audio && !cfg!(feature = "audio") {
+            missing.push("audio");
+        }
+        missing
+    }
+
+    /// Whether every required feature is available in this build
+    pub fn is_supported(&self) -> bool {
+        self.missing_features().is_empty()
+    }
+
+    /// Short human-readable labels, e.g. for badges in the config GUI
+    pub fn labels(&self) -> Vec<&'static str> {
+        let mut labels = Vec::new();
+        if self.network {
+            labels.push("Network");
+        }
+        if self.dbus {
+            labels.push("D-Bus");
+        }
+        if self.audio {
+            labels.push("Audio");
+        }
+        if self.interactive {
+            labels.push("Interactive");
+        }
+        labels
+    }
+}
+
+// ============================================================================
+// Config Schema
+// ============================================================================
+
+/// Kind of value a config field holds, used to pick a form control
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldKind {
+    /// Checkbox / toggle
+    Bool,
+    /// Whole number with optional bounds
+    Integer {
+        /// Smallest accepted value
+        min: Option<i64>,
+        /// Largest accepted value
+        max: Option<i64>,
+    },
+    /// Decimal number with optional bounds
+    Float {
+        /// Smallest accepted value
+        min: Option<f64>,
+        /// Largest accepted value
+        max: Option<f64>,
+    },
+    /// Free-form text
+    Text,
+    /// Text that should be masked (API keys, tokens)
+    Secret,
+    /// Filesystem path; `~/` is expanded
+    Path,
+    /// One of a fixed set of strings
+    Choice(&'static [&'static str]),
+    /// Array of strings
+    List,
+    /// Format template accepting the listed placeholders
+    Template(&'static [&'static str]),
+}
+
+impl FieldKind {
+    /// Whether `value` has the right TOML type (and range) for this kind
+    pub fn accepts(&self, value: &toml::Value) -> bool {
+        match self {
+            Self::Bool => value.is_bool(),
+            Self::Integer { min, max } => value.as_integer().is_some_and(|v| {
+                min.map_or(true, |min| v >= min) && max.map_or(true, |max| v <= max)
+            }),
+            Self::Float { min, max } => value
+                .as_float()
+                .or_else(|| value.as_integer().map(|v| v as f64))
+                .is_some_and(|v| {
+                    min.map_or(true, |min| v >= min) && max.map_or(true, |max| v <= max)
+                }),
+            Self::Text | Self::Secret | Self::Path | Self::Template(_) => value.is_str(),
+            Self::Choice(options) => value.as_str().is_some_and(|v| options.contains(&v)),
+            Self::List => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(|item| item.is_str())),
+        }
+    }
+
+    /// Parse text typed into a form field into a config value
+    ///
+    /// Lists are comma-separated. Returns None for empty or unparsable input
+    /// and for values outside the allowed range.
+    pub fn parse_input(&self, input: &str) -> Option<toml::Value> {
+        let input = input.trim();
+        if input.is_empty() {
+            return None;
+        }
+
+        let value = match self {
+            Self::Bool => toml::Value::Boolean(input.parse().ok()?),
+            Self::Integer { .. } => toml::Value::Integer(input.parse().ok()?),
+            Self::Float { .. } => toml::Value::Float(input.parse().ok()?),
+            Self::List => toml::Value::Array(
+                input
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| toml::Value::String(item.to_string()))
+                    .collect(),
+            ),
+            _ => toml::Value::String(input.to_string()),
+        };
+
+        self.accepts(&value).then_some(value)
+    }
+
+    /// Format a config value for display in a form field
+    pub fn format_value(&self, value: &toml::Value) -> String {
+        match value {
+            toml::Value::String(s) => s.clone(),
+            toml::Value::Array(items) => items
+                .iter()
+                .map(|item| {
+                    item.as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| item.to_string())
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// A single setting in a widget's config table
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigField {
+    /// Key in the widget's `[widgets.config]` table
+    pub key: &'static str,
+    /// Label shown next to the control
+    pub label: &'static str,
+    /// Value kind
+    pub kind: FieldKind,
+    /// Longer help text
+    pub description: Option<&'static str>,
+    /// Whether the widget can't be created without this field
+    pub required: bool,
+}
+
+impl ConfigField {
+    /// Create an optional field
+    pub fn new(key: &'static str, label: &'static str, kind: FieldKind) -> Self {
+        Self {
+            key,
+            label,
+            kind,
+            description: None,
+            required: false,
+        }
+    }
+
+    /// Attach help text
+    pub fn with_description(mut self, description: &'static str) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    /// Mark the field as required
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+}
+
+/// Settings accepted by a widget type
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigSchema {
+    fields: Vec<ConfigField>,
+}
+
+impl ConfigSchema {
+    /// Create an empty schema
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a field
+    pub fn with_field(mut self, field: ConfigField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// All fields, in display order
+    pub fn fields(&self) -> &[ConfigField] {
+        &self.fields
+    }
+
+    /// Look up a field by key
+    pub fn field(&self, key: &str) -> Option<&ConfigField> {
+        self.fields.iter().find(|field| field.key == key)
+    }
+
+    /// Whether the schema describes no fields
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Keep only the fields matching `keep`
+    pub fn retain(mut self, keep: impl FnMut(&ConfigField) -> bool) -> Self {
+        self.fields.retain(keep);
+        self
+    }
+
+    /// Whether the widget can't work without an API key or token
+    pub fn needs_api_key(&self) -> bool {
+        self.fields
+            .iter()
+            .any(|field| field.required && field.kind == FieldKind::Secret)
+    }
+
+    /// Keys of fields that are missing or have the wrong type in `config`
+    ///
+    /// This is a cheap structural check for forms; factories still run their
+    /// own `validate_config` before creating a widget.
+    pub fn invalid_keys(&self, config: &toml::Table) -> Vec<&'static str> {
+        self.fields
+            .iter()
+            .filter(|field| match config.get(field.key) {
+                Some(value) => !field.kind.accepts(value),
+                None => field.required,
+            })
+            .map(|field| field.key)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_for_type() {
+        let metadata = WidgetMetadata::for_type("system_monitor");
+        assert_eq!(metadata.name, "System monitor");
+        assert_eq!(metadata.icon, WidgetMetadata::DEFAULT_ICON);
+        assert_eq!(WidgetMetadata::for_type("").name, "");
+    }
+
+    #[test]
+    fn test_capabilities_builders() {
+        let caps = WidgetCapabilities::NONE.with_network().with_interactive();
+        assert!(caps.network);
+        assert!(caps.interactive);
+        assert!(!caps.dbus);
+        assert_eq!(caps.labels(), vec!["Network", "Interactive"]);
+        assert!(caps.is_supported());
+    }
+
+    #[test]
+    fn test_audio_requires_feature() {
+        let caps = WidgetCapabilities::NONE.with_audio();
+        if cfg!(feature = "audio") {
+            assert!(caps.missing_features().is_empty());
+        } else {
+            assert_eq!(caps.missing_features(), vec!["audio"]);
+            assert!(!caps.is_supported());
+        }
+    }
+
+    #[test]
+    fn test_field_kind_accepts() {
+        let interval = FieldKind::Integer {
+            min: Some(1),
+            max: None,
+        };
+        assert!(interval.accepts(&toml::Value::Integer(5)));
+        assert!(!interval.accepts(&toml::Value::Integer(0)));
+        assert!(!interval.accepts(&toml::Value::String("5".into())));
+
+        let unit = FieldKind::Choice(&["celsius", "fahrenheit"]);
+        assert!(unit.accepts(&toml::Value::String("celsius".into())));
+        assert!(!unit.accepts(&toml::Value::String("kelvin".into())));
+
+        let list = FieldKind::List;
+        assert!(list.accepts(&toml::Value::Array(vec![toml::Value::String("a".into())])));
+        assert!(!list.accepts(&toml::Value::Array(vec![toml::Value::Integer(1)])));
+
+        let float = FieldKind::Float {
+            min: Some(0.0),
+            max: Some(1.0),
+        };
+        assert!(float.accepts(&toml::Value::Float(0.5)));
+        assert!(float.accepts(&toml::Value::Integer(1)));
+        assert!(!float.accepts(&toml::Value::Float(1.5)));
+    }
+
+    #[test]
+    fn test_field_kind_parse_input() {
+        let interval = FieldKind::Integer {
+            min: Some(1),
+            max: None,
+        };
+        assert_eq!(interval.parse_input(" 30 "), Some(toml::Value::Integer(30)));
+        assert_eq!(interval.parse_input("0"), None);
+        assert_eq!(interval.parse_input("abc"), None);
+        assert_eq!(interval.parse_input(""), None);
+
+        let list = FieldKind::List;
+        let value = list.parse_input("AAPL, MSFT,,").unwrap();
+        assert_eq!(
+            value,
+            toml::Value::Array(vec![
+                toml::Value::String("AAPL".into()),
+                toml::Value::String("MSFT".into()),
+            ])
+        );
+        assert_eq!(list.format_value(&value), "AAPL, MSFT");
+
+        assert_eq!(
+            FieldKind::Text.format_value(&toml::Value::String("London".into())),
+            "London"
+        );
+    }
+
+    #[test]
+    fn test_schema_invalid_keys() {
+        let schema = ConfigSchema::new()
+            .with_field(ConfigField::new("command", "Command", FieldKind::Text).required())
+            .with_field(ConfigField::new("enabled", "Enabled", FieldKind::Bool));
+
+        let mut config = toml::Table::new();
+        assert_eq!(schema.invalid_keys(&config), vec!["command"]);
+
+        config.insert("command".into(), toml::Value::String("date".into()));
+        config.insert("enabled".into(), toml::Value::Integer(1));
+        assert_eq!(schema.invalid_keys(&config), vec!["enabled"]);
+
+        assert!(schema.field("command").unwrap().required);
+        assert!(schema.field("missing").is_none());
+    }
+}
@@ -1,48 +1,161 @@
 //! Stocks widget displaying real-time stock prices
 //!
-//! This widget shows stock prices, changes, and percentage changes using
-//! the Yahoo Finance API (free, no API key required).
-
+//! This widget shows stock prices, changes, and percentage changes from a
+//! chain of [quote providers](super::quote_provider): Yahoo Finance (free,
+//! no API key required) by default, or Finnhub and Alpha Vantage with an
+//! API key, falling back down the chain when one rate-limits. Changes are
+//! drawn in green or red, with pre-market and after-hours prices and,
+//! optionally, a sparkline of the day's prices. Price alerts notify when a
+//! symbol crosses a threshold.
+
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, warn};
 
+use super::price_alert::PriceAlerts;
+use super::quote_provider::QuoteProviders;
 use super::registry::DynWidgetFactory;
-use super::traits::{FontSize, Widget, WidgetContent, WidgetInfo};
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{
+    ChartSeries, FontSize, ProgressColor, TextSegment, Widget, WidgetContent, WidgetInfo,
+};
+use crate::template::{Template, TemplateVars};
+use crate::text::FontWeight;
+use crate::update::tasks::{self, TaskOutput};
+use crate::update::{Reminder, Source};
+
+/// Color of a price that went up
+const GAIN_COLOR: [u8; 4] = [76, 175, 80, 255];
+
+/// Color of a price that went down
+const LOSS_COLOR: [u8; 4] = [244, 67, 54, 255];
+
+/// Trading session outside regular hours
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Session {
+    /// Before the market opens
+    PreMarket,
+    /// After the market closes
+    AfterHours,
+}
+
+impl Session {
+    fn label(&self) -> &'static str {
+        match self {
+            Session::PreMarket => "Pre",
+            Session::AfterHours => "After",
+        }
+    }
+}
+
+/// Price in the pre-market or after-hours session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtendedHours {
+    /// Which session the price is from
+    pub session: Session,
+    /// Latest price in the session
+    pub price: f64,
+    /// Change in percent from the regular session's close
+    pub percent_change: f64,
+}
 
 /// Stock data from API
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StockData {
     pub symbol: String,
     pub price: f64,
     pub change: f64,
     pub percent_change: f64,
+    /// Price outside regular trading hours, when trading there
+    #[serde(default)]
+    pub extended: Option<ExtendedHours>,
+    /// Today's prices so far, oldest first; empty unless requested
+    #[serde(default)]
+    pub intraday: Vec<f64>,
 }
 
 impl StockData {
     /// Format the stock data for display
     pub fn display(&self, show_change: bool, show_percent: bool) -> String {
-        let mut parts = vec![format!("{}: ${:.2}", self.symbol, self.price)];
+        let price = format!("{}: ${:.2}", self.symbol, self.price);
+        match self.change_text(show_change, show_percent) {
+            Some(change) => format!("{} {}", price, change),
+            None => price,
+        }
+    }
 
-        if show_change || show_percent {
-            let mut change_parts = Vec::new();
+    /// The change as "+2.50 (+1.69%)", or the parts of it asked for
+    fn change_text(&self, show_change: bool, show_percent: bool) -> Option<String> {
+        if !show_change && !show_percent {
+            return None;
+        }
+        let mut change_parts = Vec::new();
 
-            if show_change {
-                let sign = if self.change >= 0.0 { "+" } else { "" };
-                change_parts.push(format!("{}{:.2}", sign, self.change));
-            }
+        if show_change {
+            let sign = if self.change >= 0.0 { "+" } else { "" };
+            change_parts.push(format!("{}{:.2}", sign, self.change));
+        }
 
-            if show_percent {
-                let sign = if self.percent_change >= 0.0 { "+" } else { "" };
-                change_parts.push(format!("({}{}%)", sign, self.percent_change));
-            }
+        if show_percent {
+            let sign = if self.percent_change >= 0.0 { "+" } else { "" };
+            change_parts.push(format!("({}{}%)", sign, self.percent_change));
+        }
 
-            parts.push(change_parts.join(" "));
+        Some(change_parts.join(" "))
+    }
+
+    /// Green for a gain, red for a loss
+    fn change_color(&self) -> [u8; 4] {
+        if self.change >= 0.0 {
+            GAIN_COLOR
+        } else {
+            LOSS_COLOR
         }
+    }
+
+    /// The extended-hours price, e.g. "After $151.00 (+0.50%)"
+    pub fn extended_display(&self) -> Option<String> {
+        self.extended.as_ref().map(|extended| {
+            format!(
+                "{} ${:.2} ({:+.2}%)",
+                extended.session.label(),
+                extended.price,
+                extended.percent_change
+            )
+        })
+    }
+
+    /// Today's prices scaled between the day's low (0.0) and high (1.0)
+    fn intraday_range(&self) -> Vec<f32> {
+        let low = self.intraday.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = self
+            .intraday
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        self.intraday
+            .iter()
+            .map(|price| {
+                if high > low {
+                    ((price - low) / (high - low)) as f32
+                } else {
+                    0.5
+                }
+            })
+            .collect()
+    }
 
-        parts.join(" ")
+    /// Format the stock data through a user template
+    pub fn render(&self, template: &Template) -> String {
+        template.render(
+            &TemplateVars::new()
+                .with("symbol", self.symbol.as_str())
+                .with("price", self.price)
+                .with("change", self.change)
+                .with("percent", self.percent_change),
+        )
     }
 }
 
@@ -55,9 +168,18 @@ pub struct StocksWidget {
     show_change: bool,
     show_percent: bool,
     error_message: Option<String>,
+    template: Option<Template>,
+    alerts: PriceAlerts,
+    show_extended: bool,
+    show_sparkline: bool,
+    providers: Arc<QuoteProviders>,
 }
 
 impl StocksWidget {
+    /// Placeholders available to a stocks `format` template (applied per symbol)
+    pub const TEMPLATE_PLACEHOLDERS: &'static [&'static str] =
+        &["symbol", "price", "change", "percent"];
+
     /// Create a new Stocks widget
     pub fn new(
         symbols: Vec<String>,
@@ -73,22 +195,72 @@ impl StocksWidget {
             show_change,
             show_percent,
             error_message: None,
+            template: None,
+            alerts: PriceAlerts::new(Vec::new()),
+            show_extended: false,
+            show_sparkline: false,
+            providers: Arc::new(QuoteProviders::yahoo()),
         }
     }
 
-    /// Fetch stock data from Yahoo Finance API
-    pub async fn fetch_stocks(&mut self) -> anyhow::Result<()> {
-        if self.symbols.is_empty() {
+    /// Fetch quotes from `providers` instead of Yahoo Finance alone
+    pub fn with_providers(mut self, providers: QuoteProviders) -> Self {
+        self.providers = Arc::new(providers);
+        self
+    }
+
+    /// Show pre-market and after-hours prices
+    pub fn with_extended_hours(mut self, show: bool) -> Self {
+        self.show_extended = show;
+        self
+    }
+
+    /// Draw a sparkline of the day's prices for each symbol
+    pub fn with_sparkline(mut self, show: bool) -> Self {
+        self.show_sparkline = show;
+        self
+    }
+
+    /// Watch prices for `alerts`
+    pub fn with_alerts(mut self, alerts: PriceAlerts) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// Format each symbol through a custom template
+    pub fn with_template(mut self, template: Template) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    /// Fetch data for every symbol, skipping the ones that fail, with
+    /// today's prices when `intraday` is set
+    async fn fetch_all_stocks(
+        providers: &QuoteProviders,
+        symbols: &[String],
+        intraday: bool,
+    ) -> anyhow::Result<Vec<StockData>> {
+        if symbols.is_empty() {
             return Err(anyhow::anyhow!("No stock symbols configured"));
         }
 
-        debug!(symbols = ?self.symbols, "Fetching stock data from Yahoo Finance API");
+        debug!(symbols = ?symbols, providers = ?providers.names(), "Fetching stock data");
 
         let mut new_stocks_data = Vec::new();
 
-        for symbol in &self.symbols {
-            match Self::fetch_single_stock(symbol).await {
-                Ok(data) => new_stocks_data.push(data),
+        for symbol in symbols {
+            match providers.quote(symbol).await {
+                Ok(mut data) => {
+                    if intraday {
+                        match providers.intraday(symbol).await {
+                            Ok(prices) => data.intraday = prices,
+                            Err(e) => {
+                                warn!(symbol = %symbol, error = %e, "Failed to fetch intraday prices")
+                            }
+                        }
+                    }
+                    new_stocks_data.push(data)
+                }
                 Err(e) => {
                     warn!(symbol = %symbol, error = %e, "Failed to fetch stock data");
                     // Continue with other symbols even if one fails
@@ -96,70 +268,20 @@ impl StocksWidget {
             }
         }
 
-        if new_stocks_data.is_empty() && !self.symbols.is_empty() {
+        if new_stocks_data.is_empty() {
             return Err(anyhow::anyhow!("Failed to fetch any stock data"));
         }
 
-        self.stocks_data = new_stocks_data;
-        self.last_update = Instant::now();
-        self.error_message = None;
-
-        debug!(
-            count = self.stocks_data.len(),
-            "Stock data updated successfully"
-        );
-        Ok(())
-    }
-
-    /// Fetch data for a single stock symbol
-    async fn fetch_single_stock(symbol: &str) -> anyhow::Result<StockData> {
-        // Use Yahoo Finance query API v7 (free, no API key)
-        let url = format!(
-            "https://query1.finance.yahoo.com/v7/finance/quote?symbols={}",
-            symbol
-        );
-
-        let response = reqwest::get(&url)
-            .await
-            .with_context(|| format!("Failed to fetch data for {}", symbol))?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "Yahoo Finance API returned error status: {}",
-                response.status()
-            );
-        }
-
-        let json: serde_json::Value = response
-            .json()
-            .await
-            .context("Failed to parse Yahoo Finance response")?;
-
-        // Parse the response
-        let result = json["quoteResponse"]["result"]
-            .as_array()
-            .and_then(|arr| arr.first())
-            .ok_or_else(|| anyhow::anyhow!("No data returned for symbol: {}", symbol))?;
-
-        let price = result["regularMarketPrice"]
-            .as_f64()
-            .ok_or_else(|| anyhow::anyhow!("Missing price data for {}", symbol))?;
-
-        let change = result["regularMarketChange"].as_f64().unwrap_or(0.0);
-
-        let percent_change = result["regularMarketChangePercent"].as_f64().unwrap_or(0.0);
-
-        Ok(StockData {
-            symbol: symbol.to_uppercase(),
-            price,
-            change,
-            percent_change,
-        })
+        Ok(new_stocks_data)
     }
 
     /// Set stock data from successful API fetch
     pub fn set_data(&mut self, data: Vec<StockData>) {
         debug!(count = data.len(), "Stock data updated");
+        for stock in &data {
+            self.alerts
+                .check(&stock.symbol, stock.price, Some(stock.percent_change));
+        }
         self.stocks_data = data;
         self.last_update = Instant::now();
         self.error_message = None;
@@ -176,31 +298,98 @@ impl StocksWidget {
     pub fn display_string(&self) -> String {
         // If there's an error and no data, show error
         if self.stocks_data.is_empty() && self.error_message.is_some() {
-            return format!("Error: {}", self.error_message.as_ref().unwrap());
+            let message = self.error_message.as_deref().unwrap_or_default();
+            return crate::fl!("error", message = message);
         }
 
         if self.stocks_data.is_empty() {
-            return "No stock data".to_string();
+            return crate::fl!("stocks-no-data");
         }
 
         let stock_strings: Vec<String> = self
             .stocks_data
             .iter()
-            .map(|stock| stock.display(self.show_change, self.show_percent))
+            .map(|stock| self.stock_text(stock))
             .collect();
 
         let result = stock_strings.join(" | ");
+        match self.status_suffix() {
+            Some(suffix) => format!("{}{}", result, suffix),
+            None => result,
+        }
+    }
 
-        // Add stale indicator if data is old
-        let stale_threshold = self.update_interval * 2;
-        if self.last_update.elapsed() > stale_threshold {
-            format!("{} (stale)", result)
-        } else if self.error_message.is_some() {
-            // Show warning if there's an error but we have old data
-            format!("{} ⚠", result)
-        } else {
-            result
+    /// Marker for a failed update with old data shown
+    fn status_suffix(&self) -> Option<String> {
+        self.error_message.as_ref().map(|_| " ⚠".to_string())
+    }
+
+    fn stock_text(&self, stock: &StockData) -> String {
+        match &self.template {
+            Some(template) => stock.render(template),
+            None => {
+                let text = stock.display(self.show_change, self.show_percent);
+                match stock.extended_display().filter(|_| self.show_extended) {
+                    Some(extended) => format!("{} · {}", text, extended),
+                    None => text,
+                }
+            }
+        }
+    }
+
+    /// One symbol with its change in green or red, and in the highlight
+    /// color while its alert is fresh
+    fn stock_segments(&self, stock: &StockData) -> Vec<TextSegment> {
+        let highlight = self.alerts.highlight_color(&stock.symbol);
+        let head = match &self.template {
+            Some(template) => stock.render(template),
+            None => format!("{}: ${:.2}", stock.symbol, stock.price),
+        };
+        let mut segments = vec![match highlight {
+            Some(color) => TextSegment::with_color(head, FontWeight::Bold, color),
+            None => TextSegment::regular(head),
+        }];
+        if self.template.is_some() {
+            return segments;
+        }
+
+        if let Some(change) = stock.change_text(self.show_change, self.show_percent) {
+            segments.push(TextSegment::regular(" "));
+            segments.push(TextSegment::with_color(
+                change,
+                FontWeight::Regular,
+                stock.change_color(),
+            ));
         }
+        if let Some(extended) = stock.extended_display().filter(|_| self.show_extended) {
+            segments.push(TextSegment::regular(format!(" · {}", extended)));
+        }
+        segments
+    }
+
+    /// A sparkline of the day's prices per symbol
+    fn sparklines(&self) -> WidgetContent {
+        let series = self
+            .stocks_data
+            .iter()
+            .map(|stock| {
+                let color = self
+                    .alerts
+                    .highlight_color(&stock.symbol)
+                    .unwrap_or(stock.change_color());
+                let value_text = match stock.extended_display().filter(|_| self.show_extended) {
+                    Some(extended) => format!("{:+.2}% · {}", stock.percent_change, extended),
+                    None => format!("{:+.2}%", stock.percent_change),
+                };
+                ChartSeries::new(
+                    format!("{} ${:.2}", stock.symbol, stock.price),
+                    stock.intraday_range(),
+                )
+                .with_value_text(value_text)
+                .with_color(ProgressColor::Custom(color))
+            })
+            .collect();
+        WidgetContent::Chart { series, columns: 1 }
     }
 }
 
@@ -209,24 +398,54 @@ impl Widget for StocksWidget {
         WidgetInfo {
             id: "stocks",
             name: "Stocks",
-            preferred_height: 40.0,
+            preferred_height: if self.show_sparkline {
+                (self.symbols.len() as f32 * 45.0).max(40.0)
+            } else {
+                40.0
+            },
             min_height: 30.0,
             expand: false,
         }
     }
 
     fn update(&mut self) {
-        // Updates happen in the background thread, not here
-        // This method is a no-op similar to WeatherWidget
+        // Quotes arrive through apply_fetch from the data service
     }
 
     fn content(&self) -> WidgetContent {
-        WidgetContent::Text {
-            text: self.display_string(),
+        if self.stocks_data.is_empty() {
+            if let Some(error) = &self.error_message {
+                return WidgetContent::error(error);
+            }
+            return WidgetContent::Text {
+                text: self.display_string(),
+                size: FontSize::Medium,
+            };
+        }
+        if self.show_sparkline {
+            return self.sparklines();
+        }
+
+        let mut segments = Vec::new();
+        for (i, stock) in self.stocks_data.iter().enumerate() {
+            if i > 0 {
+                segments.push(TextSegment::regular(" | "));
+            }
+            segments.extend(self.stock_segments(stock));
+        }
+        if let Some(suffix) = self.status_suffix() {
+            segments.push(TextSegment::regular(suffix));
+        }
+        WidgetContent::StyledText {
+            segments,
             size: FontSize::Medium,
         }
     }
 
+    fn reminders(&self) -> Vec<Reminder> {
+        self.alerts.reminders()
+    }
+
     fn update_interval(&self) -> Duration {
         self.update_interval
     }
@@ -238,6 +457,28 @@ impl Widget for StocksWidget {
     fn error(&self) -> Option<&str> {
         self.error_message.as_deref()
     }
+
+    fn last_success(&self) -> Option<Instant> {
+        (!self.stocks_data.is_empty()).then_some(self.last_update)
+    }
+
+    fn data_source(&self) -> Option<Source> {
+        let symbols = self.symbols.clone();
+        let providers = Arc::clone(&self.providers);
+        let intraday = self.show_sparkline;
+        Some(Source::new(self.update_interval, move || {
+            let symbols = symbols.clone();
+            let providers = Arc::clone(&providers);
+            tasks::task(async move { Self::fetch_all_stocks(&providers, &symbols, intraday).await })
+        }))
+    }
+
+    fn apply_fetch(&mut self, result: anyhow::Result<TaskOutput>) {
+        match tasks::take_output::<Vec<StockData>>(result) {
+            Ok(data) => self.set_data(data),
+            Err(e) => self.set_error(e.to_string()),
+        }
+    }
 }
 
 impl Default for StocksWidget {
@@ -263,6 +504,14 @@ impl DynWidgetFactory for StocksWidgetFactory {
         "stocks"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "Stocks",
+            "Stock prices with daily change and intraday sparklines",
+            "view-statistics-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> anyhow::Result<Box<dyn Widget>> {
         // Parse symbols array
         let symbols = if let Some(symbols_value) = config.get("symbols") {
@@ -299,6 +548,21 @@ impl DynWidgetFactory for StocksWidgetFactory {
             .and_then(|v| v.as_integer())
             .unwrap_or(300) as u64;
 
+        let show_extended = config
+            .get("show_extended")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let show_sparkline = config
+            .get("show_sparkline")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let template =
+            Template::from_config(config, "format", StocksWidget::TEMPLATE_PLACEHOLDERS)?;
+        let alerts = PriceAlerts::from_config(config)?;
+        let providers = QuoteProviders::from_config(config)?;
+
         debug!(
             symbols = ?symbols,
             show_change = %show_change,
@@ -307,12 +571,16 @@ impl DynWidgetFactory for StocksWidgetFactory {
             "Creating StocksWidget"
         );
 
-        Ok(Box::new(StocksWidget::new(
-            symbols,
-            show_change,
-            show_percent,
-            update_interval,
-        )))
+        let mut widget = StocksWidget::new(symbols, show_change, show_percent, update_interval)
+            .with_extended_hours(show_extended)
+            .with_sparkline(show_sparkline)
+            .with_providers(providers)
+            .with_alerts(alerts);
+        if let Some(template) = template {
+            widget = widget.with_template(template);
+        }
+
+        Ok(Box::new(widget))
     }
 
     fn default_config(&self) -> toml::Table {
@@ -324,6 +592,8 @@ impl DynWidgetFactory for StocksWidgetFactory {
         config.insert("show_change".to_string(), toml::Value::Boolean(true));
         config.insert("show_percent".to_string(), toml::Value::Boolean(true));
         config.insert("update_interval".to_string(), toml::Value::Integer(300));
+        config.insert("show_extended".to_string(), toml::Value::Boolean(true));
+        config.insert("show_sparkline".to_string(), toml::Value::Boolean(false));
         config
     }
 
@@ -362,8 +632,56 @@ impl DynWidgetFactory for StocksWidgetFactory {
             }
         }
 
+        PriceAlerts::from_config(config)?;
+        QuoteProviders::from_config(config)?;
+        Template::from_config(config, "format", StocksWidget::TEMPLATE_PLACEHOLDERS)?;
+
         Ok(())
     }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_network()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        let schema = ConfigSchema::new()
+            .with_field(
+                ConfigField::new("symbols", "Symbols", FieldKind::List)
+                    .with_description("Ticker symbols, e.g. AAPL"),
+            )
+            .with_field(ConfigField::new(
+                "show_change",
+                "Show change",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "show_percent",
+                "Show percent",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "update_interval",
+                "Update interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(ConfigField::new(
+                "format",
+                "Format",
+                FieldKind::Template(StocksWidget::TEMPLATE_PLACEHOLDERS),
+            ))
+            .with_field(
+                ConfigField::new("show_extended", "Show extended hours", FieldKind::Bool)
+                    .with_description("Pre-market and after-hours prices"),
+            )
+            .with_field(
+                ConfigField::new("show_sparkline", "Show sparkline", FieldKind::Bool)
+                    .with_description("A graph of today's prices for each symbol"),
+            );
+        PriceAlerts::extend_schema(QuoteProviders::extend_schema(schema))
+    }
 }
 
 #[cfg(test)]
@@ -377,6 +695,7 @@ mod tests {
             price: 150.25,
             change: 2.50,
             percent_change: 1.69,
+            ..Default::default()
         };
 
         // With both change and percent
@@ -396,12 +715,26 @@ mod tests {
             price: 2800.00,
             change: -15.00,
             percent_change: -0.53,
+            ..Default::default()
         };
         let display = stock_down.display(true, true);
         assert!(display.contains("-15.00"));
         assert!(display.contains("-0.53%"));
     }
 
+    #[test]
+    fn test_stock_data_render_template() {
+        let stock = StockData {
+            symbol: "AAPL".to_string(),
+            price: 150.25,
+            change: -2.5,
+            percent_change: -1.6,
+            ..Default::default()
+        };
+        let template = Template::parse("{symbol:<5}{price:>8.2} {percent:+.1}%").unwrap();
+        assert_eq!(stock.render(&template), "AAPL   150.25 -1.6%");
+    }
+
     #[test]
     fn test_stocks_widget_creation() {
         let widget = StocksWidget::new(
@@ -429,6 +762,7 @@ mod tests {
             price: 150.25,
             change: 2.50,
             percent_change: 1.69,
+            ..Default::default()
         }]);
 
         let display = widget.display_string();
@@ -479,6 +813,87 @@ mod tests {
         assert!(factory.validate_config(&config).is_err());
     }
 
+    #[test]
+    fn test_colored_change_and_extended_hours() {
+        let mut widget = StocksWidget::default().with_extended_hours(true);
+        widget.set_data(vec![StockData {
+            symbol: "AAPL".to_string(),
+            price: 150.25,
+            change: -2.5,
+            percent_change: -1.6,
+            extended: Some(ExtendedHours {
+                session: Session::AfterHours,
+                price: 151.0,
+                percent_change: 0.5,
+            }),
+            intraday: Vec::new(),
+        }]);
+
+        assert_eq!(
+            widget.display_string(),
+            "AAPL: $150.25 -2.50 (-1.6%) · After $151.00 (+0.50%)"
+        );
+        match widget.content() {
+            WidgetContent::StyledText { segments, .. } => {
+                assert_eq!(segments[0].text, "AAPL: $150.25");
+                assert_eq!(segments[2].text, "-2.50 (-1.6%)");
+                assert_eq!(segments[2].color, Some(LOSS_COLOR));
+                assert_eq!(segments[3].text, " · After $151.00 (+0.50%)");
+            }
+            other => panic!("Expected styled text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sparkline_content() {
+        let mut widget = StocksWidget::default().with_sparkline(true);
+        widget.set_data(vec![StockData {
+            symbol: "AAPL".to_string(),
+            price: 151.0,
+            change: 1.0,
+            percent_change: 0.67,
+            intraday: vec![150.0, 152.0, 151.0],
+            ..Default::default()
+        }]);
+        match widget.content() {
+            WidgetContent::Chart { series, columns } => {
+                assert_eq!(columns, 1);
+                assert_eq!(series[0].label, "AAPL $151.00");
+                assert_eq!(series[0].value_text, "+0.67%");
+                assert_eq!(series[0].values, vec![0.0, 1.0, 0.5]);
+                assert_eq!(series[0].color, ProgressColor::Custom(GAIN_COLOR));
+            }
+            other => panic!("Expected a chart, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_daily_move_alert() {
+        let factory = StocksWidgetFactory;
+        let config: toml::Table = toml::from_str(
+            r#"
+            symbols = ["AAPL", "MSFT"]
+            alerts = [{ symbol = "msft", move_percent = 3 }]
+            "#,
+        )
+        .unwrap();
+        assert!(factory.validate_config(&config).is_ok());
+
+        let mut widget = StocksWidget::new(vec!["AAPL".into(), "MSFT".into()], true, true, 300)
+            .with_alerts(PriceAlerts::from_config(&config).unwrap());
+        widget.set_data(vec![StockData {
+            symbol: "MSFT".to_string(),
+            price: 400.0,
+            change: -14.0,
+            percent_change: -3.4,
+            ..Default::default()
+        }]);
+        let reminders = widget.reminders();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].summary, "MSFT moved 3%");
+        assert!(matches!(widget.content(), WidgetContent::StyledText { .. }));
+    }
+
     #[test]
     fn test_factory_validation_invalid_interval() {
         let factory = StocksWidgetFactory;
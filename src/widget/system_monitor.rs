@@ -1,14 +1,106 @@
 //! System Monitor widget displaying CPU, RAM, and disk usage
 //!
 //! This widget shows real-time system resource usage using the sysinfo crate.
+//! CPU usage is either one aggregate bar or a small graph of recent usage
+//! per core. Optional rows add the CPU temperature and the throughput of
+//! each network interface.
 
+use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
 
-use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+use sysinfo::{Components, CpuRefreshKind, MemoryRefreshKind, Networks, RefreshKind, System};
 use tracing::debug;
 
 use super::registry::DynWidgetFactory;
-use super::traits::{FontSize, ProgressBar, ProgressColor, Widget, WidgetContent, WidgetInfo};
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetMetadata};
+use super::traits::{
+    ChartSeries, FontSize, ProgressBar, ProgressColor, Widget, WidgetContent, WidgetInfo,
+};
+use crate::template::{Template, TemplateVars};
+
+/// Graphs per row in the per-core view
+const CHART_COLUMNS: usize = 4;
+
+/// Temperature drawn as a full bar, in °C
+const FULL_SCALE_TEMPERATURE: f32 = 100.0;
+
+/// Sensor labels that usually measure the CPU package, most specific first
+const CPU_SENSORS: &[&str] = &["package id 0", "tctl", "tdie", "cpu", "coretemp", "k10temp"];
+
+/// How CPU usage is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CpuView {
+    /// One bar for all cores together
+    #[default]
+    Aggregate,
+    /// A graph of recent usage for each core
+    PerCore,
+}
+
+impl CpuView {
+    /// Values accepted by the `cpu_view` option
+    pub const NAMES: &'static [&'static str] = &["aggregate", "per_core"];
+
+    /// Parse a `cpu_view` option value
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "aggregate" => Some(Self::Aggregate),
+            "per_core" => Some(Self::PerCore),
+            _ => None,
+        }
+    }
+}
+
+/// The most recent samples of one value, oldest first
+#[derive(Debug, Clone)]
+struct History {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl History {
+    fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(2),
+        }
+    }
+
+    fn push(&mut self, value: f32) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    fn values(&self) -> Vec<f32> {
+        self.samples.iter().copied().collect()
+    }
+
+    fn peak(&self) -> f32 {
+        self.samples.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Samples scaled so the largest is 1.0
+    fn normalized(&self) -> Vec<f32> {
+        let peak = self.peak();
+        if peak > 0.0 {
+            self.samples.iter().map(|v| v / peak).collect()
+        } else {
+            self.values()
+        }
+    }
+}
+
+/// Throughput of one network interface since the previous update
+#[derive(Debug, Clone, PartialEq)]
+struct InterfaceRate {
+    name: String,
+    /// Bytes received per second
+    received: f64,
+    /// Bytes sent per second
+    transmitted: f64,
+}
 
 /// System Monitor widget showing CPU, RAM, and optionally disk usage
 pub struct SystemMonitorWidget {
@@ -27,9 +119,51 @@ pub struct SystemMonitorWidget {
     memory_total: u64,
     disk_used: u64,
     disk_total: u64,
+
+    // Custom text format (replaces the progress bars when set)
+    template: Option<Template>,
+
+    // Fixed bar color instead of green/yellow/red thresholds
+    bar_color: Option<ProgressColor>,
+
+    // Collapsed: only the first bar
+    compact: bool,
+
+    // Per-core graphs and the samples they are drawn from
+    cpu_view: CpuView,
+    cpu_history: History,
+    core_history: Vec<History>,
+    memory_history: History,
+    disk_history: History,
+    history_length: usize,
+
+    // CPU temperature; sensors are only read when shown
+    components: Option<Components>,
+    temperature_sensor: Option<String>,
+    temperature: Option<f32>,
+    temperature_history: History,
+
+    // Network throughput; interfaces are only read when shown
+    networks: Option<Networks>,
+    network_interfaces: Vec<String>,
+    network_rates: Vec<InterfaceRate>,
+    network_history: HashMap<String, History>,
+    last_network_refresh: Instant,
 }
 
 impl SystemMonitorWidget {
+    /// Placeholders available to a system monitor `format` template
+    pub const TEMPLATE_PLACEHOLDERS: &'static [&'static str] = &[
+        "cpu",
+        "mem_used",
+        "mem_total",
+        "mem_percent",
+        "disk_used",
+        "disk_total",
+        "disk_percent",
+        "temp",
+    ];
+
     /// Create a new System Monitor widget
     pub fn new(show_cpu: bool, show_memory: bool, show_disk: bool, update_interval: u64) -> Self {
         let mut system = System::new_with_specifics(
@@ -65,7 +199,281 @@ impl SystemMonitorWidget {
             memory_total,
             disk_used,
             disk_total,
+            template: None,
+            bar_color: None,
+            compact: false,
+            cpu_view: CpuView::Aggregate,
+            cpu_history: History::new(0),
+            core_history: Vec::new(),
+            memory_history: History::new(0),
+            disk_history: History::new(0),
+            history_length: 0,
+            components: None,
+            temperature_sensor: None,
+            temperature: None,
+            temperature_history: History::new(0),
+            networks: None,
+            network_interfaces: Vec::new(),
+            network_rates: Vec::new(),
+            network_history: HashMap::new(),
+            last_network_refresh: Instant::now(),
+        }
+    }
+
+    /// Show CPU usage as `view`, keeping `history_length` samples per graph
+    pub fn with_cpu_view(mut self, view: CpuView, history_length: usize) -> Self {
+        self.cpu_view = view;
+        self.cpu_history = History::new(history_length);
+        self.core_history = vec![History::new(history_length); self.system.cpus().len()];
+        self.memory_history = History::new(history_length);
+        self.disk_history = History::new(history_length);
+        self.temperature_history = History::new(history_length);
+        self.history_length = history_length;
+        self
+    }
+
+    /// Show the CPU temperature, read from the sensor whose label contains
+    /// `sensor`, or the likeliest CPU sensor when None
+    pub fn with_temperature(mut self, sensor: Option<String>) -> Self {
+        let sensor = sensor.map(|s| s.to_lowercase());
+        let components = Components::new_with_refreshed_list();
+        self.temperature = Self::cpu_temperature(&components, sensor.as_deref());
+        self.components = Some(components);
+        self.temperature_sensor = sensor;
+        self
+    }
+
+    /// Show the throughput of `interfaces`, or of every interface but
+    /// loopback when empty
+    pub fn with_network(mut self, interfaces: Vec<String>) -> Self {
+        self.networks = Some(Networks::new_with_refreshed_list());
+        self.network_interfaces = interfaces;
+        self.last_network_refresh = Instant::now();
+        self
+    }
+
+    /// Pick the CPU temperature from the sensors
+    fn cpu_temperature(components: &Components, sensor: Option<&str>) -> Option<f32> {
+        let find = |pattern: &str| {
+            components
+                .list()
+                .iter()
+                .find(|c| c.label().to_lowercase().contains(pattern))
+                .map(|c| c.temperature())
+        };
+        match sensor {
+            Some(sensor) => find(sensor),
+            None => CPU_SENSORS.iter().find_map(|pattern| find(pattern)),
+        }
+        .filter(|t| t.is_finite())
+    }
+
+    /// Read the sensors and interface counters
+    fn refresh_sensors(&mut self) {
+        if let Some(components) = &mut self.components {
+            components.refresh();
+            self.temperature =
+                Self::cpu_temperature(components, self.temperature_sensor.as_deref());
+        }
+
+        if let Some(networks) = &mut self.networks {
+            networks.refresh_list();
+            let elapsed = self.last_network_refresh.elapsed().as_secs_f64().max(0.001);
+            self.last_network_refresh = Instant::now();
+
+            let mut rates: Vec<InterfaceRate> = networks
+                .list()
+                .iter()
+                .filter(|(name, _)| {
+                    if self.network_interfaces.is_empty() {
+                        name.as_str() != "lo"
+                    } else {
+                        self.network_interfaces.iter().any(|i| i == *name)
+                    }
+                })
+                .map(|(name, data)| InterfaceRate {
+                    name: name.clone(),
+                    received: data.received() as f64 / elapsed,
+                    transmitted: data.transmitted() as f64 / elapsed,
+                })
+                .collect();
+            rates.sort_by(|a, b| a.name.cmp(&b.name));
+            self.network_rates = rates;
+        }
+    }
+
+    fn temperature_text(temperature: f32) -> String {
+        format!("{:.0}°C", temperature)
+    }
+
+    fn rate_text(rate: &InterfaceRate) -> String {
+        format!(
+            "↓{}/s ↑{}/s",
+            Self::format_bytes(rate.received as u64),
+            Self::format_bytes(rate.transmitted as u64)
+        )
+    }
+
+    /// Show a single text line rendered from a template instead of bars
+    pub fn with_template(mut self, template: Template) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    /// Draw every bar in one color (e.g. `"@accent"`) instead of by threshold
+    pub fn with_bar_color(mut self, color: ProgressColor) -> Self {
+        self.bar_color = Some(color);
+        self
+    }
+
+    fn percent(used: u64, total: u64) -> f64 {
+        if total > 0 {
+            (used as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    fn render_template(&self, template: &Template) -> String {
+        template.render(
+            &TemplateVars::new()
+                .with("cpu", self.cpu_usage)
+                .with("mem_used", Self::format_bytes(self.memory_used))
+                .with("mem_total", Self::format_bytes(self.memory_total))
+                .with(
+                    "mem_percent",
+                    Self::percent(self.memory_used, self.memory_total),
+                )
+                .with("disk_used", Self::format_bytes(self.disk_used))
+                .with("disk_total", Self::format_bytes(self.disk_total))
+                .with(
+                    "disk_percent",
+                    Self::percent(self.disk_used, self.disk_total),
+                )
+                .with("temp", self.temperature.unwrap_or(0.0)),
+        )
+    }
+
+    /// Record the current values in the graph histories
+    fn record_history(&mut self) {
+        self.cpu_history.push(self.cpu_usage / 100.0);
+        for (history, cpu) in self.core_history.iter_mut().zip(self.system.cpus()) {
+            history.push(cpu.cpu_usage() / 100.0);
+        }
+        self.memory_history
+            .push(Self::percent(self.memory_used, self.memory_total) as f32 / 100.0);
+        self.disk_history
+            .push(Self::percent(self.disk_used, self.disk_total) as f32 / 100.0);
+        if let Some(temperature) = self.temperature {
+            self.temperature_history
+                .push(temperature / FULL_SCALE_TEMPERATURE);
+        }
+
+        let length = self.history_length;
+        for rate in &self.network_rates {
+            self.network_history
+                .entry(rate.name.clone())
+                .or_insert_with(|| History::new(length))
+                .push((rate.received + rate.transmitted) as f32);
+        }
+        let rates = &self.network_rates;
+        self.network_history
+            .retain(|name, _| rates.iter().any(|r| &r.name == name));
+    }
+
+    fn cpu_color(&self) -> ProgressColor {
+        self.bar_color.unwrap_or(ProgressColor::Threshold {
+            green_below: 0.6,
+            yellow_below: 0.85,
+        })
+    }
+
+    fn usage_color(&self) -> ProgressColor {
+        self.bar_color.unwrap_or(ProgressColor::Threshold {
+            green_below: 0.7,
+            yellow_below: 0.9,
+        })
+    }
+
+    fn temperature_color(&self) -> ProgressColor {
+        self.bar_color.unwrap_or(ProgressColor::Threshold {
+            green_below: 0.7,
+            yellow_below: 0.85,
+        })
+    }
+
+    /// Graphs for the per-core view: one per core, then memory and disk
+    fn chart_content(&self) -> WidgetContent {
+        if self.compact {
+            return WidgetContent::Chart {
+                series: vec![
+                    ChartSeries::new("CPU", self.cpu_history.values()).with_color(self.cpu_color())
+                ],
+                columns: 1,
+            };
+        }
+
+        let mut series = Vec::new();
+        if self.show_cpu {
+            series.extend(self.core_history.iter().enumerate().map(|(core, history)| {
+                ChartSeries::new(format!("CPU{}", core), history.values())
+                    .with_color(self.cpu_color())
+            }));
+        }
+        if let Some(temperature) = self.temperature {
+            series.push(
+                ChartSeries::new("Temp", self.temperature_history.values())
+                    .with_value_text(Self::temperature_text(temperature))
+                    .with_color(self.temperature_color()),
+            );
+        }
+        if self.show_memory && self.memory_total > 0 {
+            series.push(
+                ChartSeries::new("RAM", self.memory_history.values())
+                    .with_color(self.usage_color()),
+            );
+        }
+        if self.show_disk && self.disk_total > 0 {
+            series.push(
+                ChartSeries::new("Disk", self.disk_history.values()).with_color(self.usage_color()),
+            );
+        }
+        for rate in &self.network_rates {
+            // Scaled to the busiest moment in view
+            let values = self
+                .network_history
+                .get(&rate.name)
+                .map(History::normalized)
+                .unwrap_or_default();
+            series.push(
+                ChartSeries::new(rate.name.clone(), values).with_value_text(Self::rate_text(rate)),
+            );
         }
+
+        if series.is_empty() {
+            return WidgetContent::Text {
+                text: "System Monitor".to_string(),
+                size: FontSize::Medium,
+            };
+        }
+        WidgetContent::Chart {
+            columns: series.len().min(CHART_COLUMNS),
+            series,
+        }
+    }
+
+    /// Number of graphs the per-core view shows
+    fn chart_len(&self) -> usize {
+        let cores = if self.show_cpu {
+            self.core_history.len()
+        } else {
+            0
+        };
+        cores
+            + usize::from(self.temperature.is_some())
+            + usize::from(self.show_memory)
+            + usize::from(self.show_disk)
+            + self.network_rates.len()
     }
 
     /// Get disk usage information for the root filesystem
@@ -113,10 +521,24 @@ impl SystemMonitorWidget {
 
     /// Generate display string
     pub fn display_string(&self) -> String {
+        if let Some(template) = &self.template {
+            return self.render_template(template);
+        }
+
         let mut parts = Vec::new();
 
         if self.show_cpu {
-            parts.push(format!("CPU: {:.0}%", self.cpu_usage));
+            parts.push(crate::fl!(
+                "sysmon-cpu",
+                usage = format!("{:.0}", self.cpu_usage)
+            ));
+        }
+
+        if let Some(temperature) = self.temperature {
+            parts.push(crate::fl!(
+                "sysmon-temperature",
+                temperature = Self::temperature_text(temperature)
+            ));
         }
 
         if self.show_memory {
@@ -125,24 +547,28 @@ impl SystemMonitorWidget {
             } else {
                 0.0
             };
-            parts.push(format!(
-                "RAM: {}/{} ({:.0}%)",
-                Self::format_bytes(self.memory_used),
-                Self::format_bytes(self.memory_total),
-                mem_percent
+            parts.push(crate::fl!(
+                "sysmon-memory",
+                used = Self::format_bytes(self.memory_used),
+                total = Self::format_bytes(self.memory_total),
+                percent = format!("{:.0}", mem_percent)
             ));
         }
 
         if self.show_disk && self.disk_total > 0 {
             let disk_percent = (self.disk_used as f64 / self.disk_total as f64) * 100.0;
-            parts.push(format!(
-                "Disk: {}/{} ({:.0}%)",
-                Self::format_bytes(self.disk_used),
-                Self::format_bytes(self.disk_total),
-                disk_percent
+            parts.push(crate::fl!(
+                "sysmon-disk",
+                used = Self::format_bytes(self.disk_used),
+                total = Self::format_bytes(self.disk_total),
+                percent = format!("{:.0}", disk_percent)
             ));
         }
 
+        for rate in &self.network_rates {
+            parts.push(format!("{}: {}", rate.name, Self::rate_text(rate)));
+        }
+
         if parts.is_empty() {
             "System Monitor".to_string()
         } else {
@@ -153,12 +579,24 @@ impl SystemMonitorWidget {
 
 impl Widget for SystemMonitorWidget {
     fn info(&self) -> WidgetInfo {
-        // Calculate preferred height based on number of enabled bars
-        let bar_count = [self.show_cpu, self.show_memory, self.show_disk]
+        let preferred_height = if self.cpu_view == CpuView::PerCore && self.template.is_none() {
+            // A row of graphs per CHART_COLUMNS
+            let rows = self.chart_len().div_ceil(CHART_COLUMNS);
+            (rows as f32 * 50.0).max(50.0)
+        } else {
+            // Calculate preferred height based on number of enabled bars
+            let bar_count = [
+                self.show_cpu,
+                self.temperature.is_some(),
+                self.show_memory,
+                self.show_disk,
+            ]
             .iter()
             .filter(|&&b| b)
-            .count();
-        let preferred_height = (bar_count as f32 * 25.0).max(40.0);
+            .count()
+                + self.network_rates.len();
+            (bar_count as f32 * 25.0).max(40.0)
+        };
 
         WidgetInfo {
             id: "system_monitor",
@@ -188,6 +626,9 @@ impl Widget for SystemMonitorWidget {
             self.disk_total = total;
         }
 
+        self.refresh_sensors();
+        self.record_history();
+
         self.last_update = Instant::now();
 
         debug!(
@@ -199,16 +640,34 @@ impl Widget for SystemMonitorWidget {
     }
 
     fn content(&self) -> WidgetContent {
+        if let Some(template) = &self.template {
+            return WidgetContent::Text {
+                text: self.render_template(template),
+                size: FontSize::Medium,
+            };
+        }
+
+        if self.cpu_view == CpuView::PerCore {
+            return self.chart_content();
+        }
+
         let mut bars = Vec::new();
 
         if self.show_cpu {
             bars.push(ProgressBar {
                 label: "CPU".to_string(),
                 value: self.cpu_usage / 100.0,
-                color: ProgressColor::Threshold {
-                    green_below: 0.6,
-                    yellow_below: 0.85,
-                },
+                color: self.cpu_color(),
+                value_text: None,
+            });
+        }
+
+        if let Some(temperature) = self.temperature {
+            bars.push(ProgressBar {
+                label: "Temp".to_string(),
+                value: (temperature / FULL_SCALE_TEMPERATURE).clamp(0.0, 1.0),
+                color: self.temperature_color(),
+                value_text: Some(Self::temperature_text(temperature)),
             });
         }
 
@@ -221,10 +680,8 @@ impl Widget for SystemMonitorWidget {
                     Self::format_bytes(self.memory_total)
                 ),
                 value: mem_percent,
-                color: ProgressColor::Threshold {
-                    green_below: 0.7,
-                    yellow_below: 0.9,
-                },
+                color: self.usage_color(),
+                value_text: None,
             });
         }
 
@@ -237,13 +694,28 @@ impl Widget for SystemMonitorWidget {
                     Self::format_bytes(self.disk_total)
                 ),
                 value: disk_percent,
-                color: ProgressColor::Threshold {
-                    green_below: 0.7,
-                    yellow_below: 0.9,
-                },
+                color: self.usage_color(),
+                value_text: None,
             });
         }
 
+        for rate in &self.network_rates {
+            // Scaled to the busiest moment in the history
+            let peak = self
+                .network_history
+                .get(&rate.name)
+                .map_or(0.0, History::peak);
+            let total = (rate.received + rate.transmitted) as f32;
+            let value = if peak > 0.0 { total / peak } else { 0.0 };
+            bars.push(
+                ProgressBar::new(rate.name.clone(), value).with_value_text(Self::rate_text(rate)),
+            );
+        }
+
+        if self.compact {
+            bars.truncate(1);
+        }
+
         if bars.is_empty() {
             WidgetContent::Text {
                 text: "System Monitor".to_string(),
@@ -257,6 +729,10 @@ impl Widget for SystemMonitorWidget {
     fn update_interval(&self) -> Duration {
         self.update_interval
     }
+
+    fn set_compact(&mut self, compact: bool) {
+        self.compact = compact;
+    }
 }
 
 impl Default for SystemMonitorWidget {
@@ -277,6 +753,14 @@ impl DynWidgetFactory for SystemMonitorWidgetFactory {
         "system_monitor"
     }
 
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "System Monitor",
+            "CPU, memory and disk usage",
+            "utilities-system-monitor-symbolic",
+        )
+    }
+
     fn create(&self, config: &toml::Table) -> anyhow::Result<Box<dyn Widget>> {
         let show_cpu = config
             .get("show_cpu")
@@ -298,20 +782,68 @@ impl DynWidgetFactory for SystemMonitorWidgetFactory {
             .and_then(|v| v.as_integer())
             .unwrap_or(2) as u64;
 
+        let history_length = config
+            .get("history_length")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(30) as usize;
+
+        let show_temperature = config
+            .get("show_temperature")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let temperature_sensor = config
+            .get("temperature_sensor")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        let show_network = config
+            .get("show_network")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let network_interfaces = config
+            .get("network_interfaces")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let template =
+            Template::from_config(config, "format", SystemMonitorWidget::TEMPLATE_PLACEHOLDERS)?;
+        let cpu_view = parse_cpu_view(config)?;
+
         debug!(
             show_cpu = %show_cpu,
             show_memory = %show_memory,
             show_disk = %show_disk,
+            show_temperature = %show_temperature,
+            show_network = %show_network,
             update_interval = %update_interval,
             "Creating SystemMonitorWidget"
         );
 
-        Ok(Box::new(SystemMonitorWidget::new(
-            show_cpu,
-            show_memory,
-            show_disk,
-            update_interval,
-        )))
+        let mut widget =
+            SystemMonitorWidget::new(show_cpu, show_memory, show_disk, update_interval)
+                .with_cpu_view(cpu_view, history_length);
+        if let Some(template) = template {
+            widget = widget.with_template(template);
+        }
+        if let Some(color) = parse_bar_color(config)? {
+            widget = widget.with_bar_color(color);
+        }
+        if show_temperature {
+            widget = widget.with_temperature(temperature_sensor);
+        }
+        if show_network {
+            widget = widget.with_network(network_interfaces);
+        }
+
+        Ok(Box::new(widget))
     }
 
     fn default_config(&self) -> toml::Table {
@@ -320,6 +852,13 @@ impl DynWidgetFactory for SystemMonitorWidgetFactory {
         config.insert("show_memory".to_string(), toml::Value::Boolean(true));
         config.insert("show_disk".to_string(), toml::Value::Boolean(false));
         config.insert("update_interval".to_string(), toml::Value::Integer(2));
+        config.insert(
+            "cpu_view".to_string(),
+            toml::Value::String("aggregate".to_string()),
+        );
+        config.insert("history_length".to_string(), toml::Value::Integer(30));
+        config.insert("show_temperature".to_string(), toml::Value::Boolean(false));
+        config.insert("show_network".to_string(), toml::Value::Boolean(false));
         config
     }
 
@@ -333,8 +872,134 @@ impl DynWidgetFactory for SystemMonitorWidgetFactory {
                 anyhow::bail!("'update_interval' must be at least 1 second");
             }
         }
+
+        if let Some(length) = config.get("history_length") {
+            let length = length
+                .as_integer()
+                .ok_or_else(|| anyhow::anyhow!("'history_length' must be an integer"))?;
+
+            if length < 2 {
+                anyhow::bail!("'history_length' must be at least 2 samples");
+            }
+        }
+
+        if let Some(sensor) = config.get("temperature_sensor") {
+            if !sensor.is_str() {
+                anyhow::bail!("'temperature_sensor' must be a string");
+            }
+        }
+
+        if let Some(interfaces) = config.get("network_interfaces") {
+            let all_strings = interfaces
+                .as_array()
+                .is_some_and(|arr| arr.iter().all(|v| v.is_str()));
+            if !all_strings {
+                anyhow::bail!("'network_interfaces' must be an array of strings");
+            }
+        }
+
+        Template::from_config(config, "format", SystemMonitorWidget::TEMPLATE_PLACEHOLDERS)?;
+        parse_bar_color(config)?;
+        parse_cpu_view(config)?;
         Ok(())
     }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(ConfigField::new("show_cpu", "Show CPU", FieldKind::Bool))
+            .with_field(ConfigField::new(
+                "show_memory",
+                "Show memory",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new("show_disk", "Show disk", FieldKind::Bool))
+            .with_field(ConfigField::new(
+                "update_interval",
+                "Update interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+            .with_field(ConfigField::new(
+                "format",
+                "Format",
+                FieldKind::Template(SystemMonitorWidget::TEMPLATE_PLACEHOLDERS),
+            ))
+            .with_field(
+                ConfigField::new("bar_color", "Bar color", FieldKind::Text).with_description(
+                    "Hex color or theme token like \"@accent\"; defaults to green/yellow/red by usage",
+                ),
+            )
+            .with_field(
+                ConfigField::new("cpu_view", "CPU view", FieldKind::Choice(CpuView::NAMES))
+                    .with_description("One bar for all cores, or a usage graph per core"),
+            )
+            .with_field(
+                ConfigField::new(
+                    "history_length",
+                    "Graph history (samples)",
+                    FieldKind::Integer {
+                        min: Some(2),
+                        max: None,
+                    },
+                )
+                .with_description("Updates shown in each per-core graph"),
+            )
+            .with_field(ConfigField::new(
+                "show_temperature",
+                "Show CPU temperature",
+                FieldKind::Bool,
+            ))
+            .with_field(
+                ConfigField::new("temperature_sensor", "Temperature sensor", FieldKind::Text)
+                    .with_description(
+                        "Part of the sensor label, e.g. \"Tctl\"; picks the CPU sensor by default",
+                    ),
+            )
+            .with_field(ConfigField::new(
+                "show_network",
+                "Show network throughput",
+                FieldKind::Bool,
+            ))
+            .with_field(
+                ConfigField::new("network_interfaces", "Network interfaces", FieldKind::List)
+                    .with_description("Interfaces to show, e.g. \"wlan0\"; all but loopback by default"),
+            )
+    }
+}
+
+/// Read the optional `cpu_view` key
+fn parse_cpu_view(config: &toml::Table) -> anyhow::Result<CpuView> {
+    let Some(value) = config.get("cpu_view") else {
+        return Ok(CpuView::default());
+    };
+    let value = value
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("'cpu_view' must be a string"))?;
+    CpuView::from_name(value).ok_or_else(|| {
+        anyhow::anyhow!(
+            "'cpu_view' must be one of {}, got '{}'",
+            CpuView::NAMES.join(", "),
+            value
+        )
+    })
+}
+
+/// Read the optional `bar_color` key
+fn parse_bar_color(config: &toml::Table) -> anyhow::Result<Option<ProgressColor>> {
+    let Some(value) = config.get("bar_color") else {
+        return Ok(None);
+    };
+    let value = value
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("'bar_color' must be a string"))?;
+    ProgressColor::parse(value).map(Some).ok_or_else(|| {
+        anyhow::anyhow!(
+            "'bar_color' must be a hex color or theme token, got '{}'",
+            value
+        )
+    })
 }
 
 #[cfg(test)]
@@ -370,6 +1035,115 @@ mod tests {
         assert!(display.contains("RAM:"));
     }
 
+    #[test]
+    fn test_template_content() {
+        let template = Template::parse("CPU {cpu:.0}% MEM {mem_percent:.0}%").unwrap();
+        let widget = SystemMonitorWidget::new(true, true, false, 2).with_template(template);
+        match widget.content() {
+            WidgetContent::Text { text, .. } => {
+                assert!(text.starts_with("CPU "));
+                assert!(text.contains("MEM "));
+            }
+            _ => panic!("Expected Text content when a template is set"),
+        }
+    }
+
+    #[test]
+    fn test_compact_content() {
+        let mut widget = SystemMonitorWidget::new(true, true, false, 2);
+        widget.set_compact(true);
+        match widget.content() {
+            WidgetContent::MultiProgress { bars } => {
+                assert_eq!(bars.len(), 1);
+                assert_eq!(bars[0].label, "CPU");
+            }
+            _ => panic!("Expected progress bars"),
+        }
+    }
+
+    #[test]
+    fn test_history_keeps_latest_samples() {
+        let mut history = History::new(3);
+        for value in [0.1, 0.2, 0.3, 0.4] {
+            history.push(value);
+        }
+        assert_eq!(history.values(), vec![0.2, 0.3, 0.4]);
+    }
+
+    #[test]
+    fn test_per_core_chart() {
+        let mut widget =
+            SystemMonitorWidget::new(true, true, false, 2).with_cpu_view(CpuView::PerCore, 10);
+        let cores = widget.system.cpus().len();
+        widget.record_history();
+        widget.record_history();
+
+        match widget.content() {
+            WidgetContent::Chart { series, columns } => {
+                assert_eq!(series.len(), cores + 1);
+                assert_eq!(columns, series.len().min(CHART_COLUMNS));
+                assert_eq!(series.last().unwrap().label, "RAM");
+                assert!(series.iter().all(|s| s.values.len() == 2));
+            }
+            other => panic!("Expected a chart, got {:?}", other),
+        }
+
+        widget.set_compact(true);
+        match widget.content() {
+            WidgetContent::Chart { series, .. } => assert_eq!(series[0].label, "CPU"),
+            other => panic!("Expected a chart, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_temperature_and_network_rows() {
+        let mut widget = SystemMonitorWidget::new(true, false, false, 2);
+        widget.temperature = Some(54.4);
+        widget.network_rates = vec![InterfaceRate {
+            name: "wlan0".to_string(),
+            received: 2.0 * 1024.0 * 1024.0,
+            transmitted: 30.0 * 1024.0,
+        }];
+        widget.record_history();
+
+        match widget.content() {
+            WidgetContent::MultiProgress { bars } => {
+                assert_eq!(bars.len(), 3);
+                assert_eq!(bars[1].label, "Temp");
+                assert_eq!(bars[1].value_text.as_deref(), Some("54°C"));
+                assert_eq!(bars[2].label, "wlan0");
+                assert_eq!(bars[2].value_text.as_deref(), Some("↓2M/s ↑30K/s"));
+                assert_eq!(bars[2].value, 1.0);
+            }
+            other => panic!("Expected progress bars, got {:?}", other),
+        }
+        assert!(widget.display_string().contains("Temp: 54°C"));
+        assert_eq!(widget.info().preferred_height, 75.0);
+    }
+
+    #[test]
+    fn test_cpu_view_validation() {
+        let factory = SystemMonitorWidgetFactory;
+        let mut config = factory.default_config();
+        config.insert("cpu_view".into(), toml::Value::String("per_core".into()));
+        assert!(factory.validate_config(&config).is_ok());
+        assert!(factory.create(&config).is_ok());
+
+        config.insert("cpu_view".into(), toml::Value::String("sideways".into()));
+        assert!(factory.validate_config(&config).is_err());
+
+        let mut config = factory.default_config();
+        config.insert("history_length".into(), toml::Value::Integer(1));
+        assert!(factory.validate_config(&config).is_err());
+
+        let mut config = factory.default_config();
+        config.insert(
+            "network_interfaces".into(),
+            toml::Value::Array(vec![toml::Value::Integer(0)]),
+        );
+        assert!(factory.validate_config(&config).is_err());
+    }
+
     #[test]
     fn test_factory_creation() {
         let factory = SystemMonitorWidgetFactory;
@@ -377,4 +1151,21 @@ mod tests {
         let widget = factory.create(&config).unwrap();
         assert_eq!(widget.info().id, "system_monitor");
     }
+
+    #[test]
+    fn test_bar_color_token() {
+        let factory = SystemMonitorWidgetFactory;
+        let mut config = factory.default_config();
+        config.insert("bar_color".into(), toml::Value::String("@accent".into()));
+        assert!(factory.validate_config(&config).is_ok());
+
+        let color = parse_bar_color(&config).unwrap();
+        assert!(matches!(
+            color,
+            Some(ProgressColor::Token(crate::theme::ThemeToken::Accent))
+        ));
+
+        config.insert("bar_color".into(), toml::Value::String("@nope".into()));
+        assert!(factory.validate_config(&config).is_err());
+    }
 }
@@ -3,8 +3,14 @@
 //! This module defines the core traits that all widgets must implement.
 //! New widgets can be added by implementing these traits.
 
+use crate::audio::SoundPriority;
+use crate::metrics::PerfStats;
 use crate::text::FontWeight;
-use std::time::Duration;
+use crate::theme::{Color, ThemeToken};
+use crate::update::{Reminder, Source, TaskOutput};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::time::{Duration, Instant};
 
 /// Mouse button identifier
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -39,6 +45,8 @@ pub enum WidgetAction {
     OpenUrl(String),
     /// Run a shell command
     RunCommand(String),
+    /// Copy text to the clipboard
+    CopyText(String),
     /// Advance to the next item (quotes, news, etc.)
     NextItem,
     /// Go to the previous item
@@ -51,6 +59,92 @@ pub enum WidgetAction {
     None,
 }
 
+/// Sounds a widget can ask the application to play
+///
+/// The cue picks which `[sounds]` entry is used; a widget instance's
+/// `sound` setting can replace the effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundCue {
+    /// A timer or alarm went off (`[sounds.alarm]`)
+    Alarm,
+    /// Something worth a short notice (`[sounds.notification]`)
+    Notification,
+    /// A named widget event such as `"hour"` (`[sounds.events.<name>]`,
+    /// or the notification sound if that isn't configured)
+    Event(&'static str),
+}
+
+impl SoundCue {
+    /// Playback priority: alarms interrupt notifications, which take
+    /// precedence over ambient events
+    pub fn priority(self) -> SoundPriority {
+        match self {
+            SoundCue::Alarm => SoundPriority::High,
+            SoundCue::Notification => SoundPriority::Normal,
+            SoundCue::Event(_) => SoundPriority::Low,
+        }
+    }
+}
+
+/// Outcome of one background fetch, reported for the metrics
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchReport {
+    /// How long the fetch took
+    pub duration: Duration,
+    /// Why it failed, or None on success
+    pub error: Option<String>,
+}
+
+/// How current a widget's fetched data is, shown as a badge on the widget
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Freshness {
+    /// Recent data, or nothing that can go stale
+    #[default]
+    Fresh,
+    /// No successful fetch for a while, though none has failed either
+    Stale,
+    /// No successful fetch for a while and the last one failed
+    Offline,
+}
+
+impl Freshness {
+    /// Freshness at `now` of data last fetched at `last_success`, expected
+    /// every `interval` and stale after `stale_after` intervals
+    ///
+    /// A `stale_after` of zero turns the badges off.
+    pub fn at(
+        last_success: Option<Instant>,
+        interval: Duration,
+        failing: bool,
+        stale_after: f32,
+        now: Instant,
+    ) -> Self {
+        let Some(last_success) = last_success else {
+            return Self::Fresh;
+        };
+        if stale_after <= 0.0 {
+            return Self::Fresh;
+        }
+        let threshold = interval.mul_f32(stale_after);
+        if now.saturating_duration_since(last_success) <= threshold {
+            Self::Fresh
+        } else if failing {
+            Self::Offline
+        } else {
+            Self::Stale
+        }
+    }
+
+    /// Icon of the badge drawn in the widget's corner, if any
+    pub fn badge_icon(self) -> Option<&'static str> {
+        match self {
+            Self::Fresh => None,
+            Self::Stale => Some("badge-stale"),
+            Self::Offline => Some("badge-offline"),
+        }
+    }
+}
+
 /// Information about a widget for layout purposes
 #[derive(Debug, Clone)]
 pub struct WidgetInfo {
@@ -67,7 +161,7 @@ pub struct WidgetInfo {
 }
 
 /// A styled text segment with optional weight and color
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextSegment {
     /// The text content
     pub text: String,
@@ -106,8 +200,41 @@ impl TextSegment {
     }
 }
 
+/// One cell of a [`WidgetContent::Grid`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GridCell {
+    /// Cell text; cells without text are left blank
+    pub text: String,
+    /// Draw the cell on an accent-colored circle (e.g. today)
+    pub highlighted: bool,
+    /// Draw a dot under the text (e.g. a day with events)
+    pub marked: bool,
+}
+
+impl GridCell {
+    /// Create a plain cell
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Highlight the cell
+    pub fn with_highlight(mut self, highlighted: bool) -> Self {
+        self.highlighted = highlighted;
+        self
+    }
+
+    /// Mark the cell with a dot
+    pub fn with_mark(mut self, marked: bool) -> Self {
+        self.marked = marked;
+        self
+    }
+}
+
 /// Color mode for progress bars
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ProgressColor {
     /// Use the theme's accent color
     Accent,
@@ -120,6 +247,24 @@ pub enum ProgressColor {
     },
     /// Custom fixed color (RGBA)
     Custom([u8; 4]),
+    /// A theme color, resolved against the active theme when drawing
+    Token(ThemeToken),
+}
+
+impl ProgressColor {
+    /// Parse a configured color: `"@token"`, a hex color, or `"threshold"`
+    pub fn parse(value: &str) -> Option<Self> {
+        if value.trim() == "threshold" {
+            return Some(Self::Threshold {
+                green_below: 0.7,
+                yellow_below: 0.9,
+            });
+        }
+        match ThemeToken::parse(value) {
+            Some(token) => Some(Self::Token(token)),
+            None => Color::from_hex(value).map(|color| Self::Custom(color.to_array())),
+        }
+    }
 }
 
 impl Default for ProgressColor {
@@ -129,7 +274,7 @@ impl Default for ProgressColor {
 }
 
 /// A progress bar definition with label and color
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProgressBar {
     /// Label displayed beside the bar
     pub label: String,
@@ -137,6 +282,8 @@ pub struct ProgressBar {
     pub value: f32,
     /// Color mode for the bar
     pub color: ProgressColor,
+    /// Text drawn right of the bar instead of the value as a percentage
+    pub value_text: Option<String>,
 }
 
 impl ProgressBar {
@@ -146,6 +293,7 @@ impl ProgressBar {
             label: label.into(),
             value: value.clamp(0.0, 1.0),
             color: ProgressColor::Accent,
+            value_text: None,
         }
     }
 
@@ -163,12 +311,91 @@ impl ProgressBar {
                 green_below,
                 yellow_below,
             },
+            value_text: None,
+        }
+    }
+
+    /// Show `text` right of the bar, e.g. a temperature
+    pub fn with_value_text(mut self, text: impl Into<String>) -> Self {
+        self.value_text = Some(text.into());
+        self
+    }
+}
+
+/// Recent values drawn as a sparkline in a [`WidgetContent::Chart`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartSeries {
+    /// Label drawn above the graph
+    pub label: String,
+    /// Samples from oldest to newest, each 0.0 to 1.0
+    pub values: Vec<f32>,
+    /// Text drawn opposite the label, usually the latest value
+    pub value_text: String,
+    /// Color of the graph; thresholds apply to the latest value
+    pub color: ProgressColor,
+}
+
+impl ChartSeries {
+    /// Create a series labelled with its latest value as a percentage
+    pub fn new(label: impl Into<String>, values: Vec<f32>) -> Self {
+        let latest = values.last().copied().unwrap_or(0.0);
+        Self {
+            label: label.into(),
+            values,
+            value_text: format!("{:.0}%", latest * 100.0),
+            color: ProgressColor::Accent,
+        }
+    }
+
+    /// Show `text` instead of the latest value as a percentage
+    pub fn with_value_text(mut self, text: impl Into<String>) -> Self {
+        self.value_text = text.into();
+        self
+    }
+
+    /// Draw the graph in `color`
+    pub fn with_color(mut self, color: ProgressColor) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+/// Transport controls drawn by [`WidgetContent::Media`], left to right
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaControl {
+    /// Skip to the previous track
+    Previous,
+    /// Play or pause
+    PlayPause,
+    /// Skip to the next track
+    Next,
+}
+
+impl MediaControl {
+    /// Controls in the order they are drawn
+    pub const ALL: [MediaControl; 3] = [Self::Previous, Self::PlayPause, Self::Next];
+
+    /// Share of the widget's width the controls take, from the left edge
+    pub const AREA_WIDTH: f32 = 0.3;
+
+    /// The control at (`x`, `y`), relative to the widget (0.0 to 1.0 each)
+    ///
+    /// Controls sit side by side in the left of the widget's lower half,
+    /// where the renderer draws them.
+    pub fn at(x: f32, y: f32) -> Option<Self> {
+        if !(0.5..=1.0).contains(&y) || !(0.0..Self::AREA_WIDTH).contains(&x) {
+            return None;
         }
+        let slot = (x / Self::AREA_WIDTH * Self::ALL.len() as f32) as usize;
+        Self::ALL.get(slot).copied()
     }
 }
 
 /// Content to be rendered by a widget
-#[derive(Debug, Clone)]
+///
+/// Compared against the last committed frame to skip redrawing widgets
+/// whose content hasn't changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum WidgetContent {
     /// Single line of text with font size
     Text { text: String, size: FontSize },
@@ -180,6 +407,13 @@ pub enum WidgetContent {
         text: String,
         size: FontSize,
     },
+    /// An icon beside left-aligned lines of text, like a notification
+    IconLines {
+        /// Icon name from the icon theme, or a path to an image
+        icon: String,
+        /// Lines right of the icon, top to bottom
+        lines: Vec<(String, FontSize)>,
+    },
     /// Progress bar (like the minute progress)
     Progress {
         value: f32, // 0.0 to 1.0
@@ -192,12 +426,92 @@ pub enum WidgetContent {
     },
     /// Multiple progress bars with labels and colors
     MultiProgress { bars: Vec<ProgressBar> },
+    /// Table of short cells under an optional title, like a month calendar
+    Grid {
+        /// Line above the table
+        title: Option<String>,
+        /// Column headings; the table has one column per heading
+        header: Vec<String>,
+        /// Cells in row-major order
+        cells: Vec<GridCell>,
+    },
+    /// Sparklines laid out in equal cells, filling rows left to right
+    Chart {
+        /// Graphs in row-major order
+        series: Vec<ChartSeries>,
+        /// Number of cells per row
+        columns: usize,
+    },
+    /// Now playing, with transport controls and a position bar below
+    Media {
+        /// Track line, e.g. "Artist - Title"
+        title: String,
+        /// Whether the middle control shows pause rather than play
+        playing: bool,
+        /// Position in the track from 0.0 to 1.0; no bar when unknown
+        progress: Option<f32>,
+        /// Text right of the bar, e.g. "1:23 / 4:56"
+        position_text: String,
+    },
+    /// A row of icons in equal slots across the widget, like app shortcuts
+    IconRow {
+        /// Icon names from the icon theme, or image paths, left to right
+        icons: Vec<String>,
+        /// Captions under the icons, or empty for none
+        labels: Vec<String>,
+    },
+    /// A compact error card, for a widget with an error and nothing else
+    /// to show: a warning icon, the message and a pointer to the logs
+    Error {
+        /// Short description, e.g. the widget's [`Widget::error`]
+        message: String,
+    },
     /// Empty/nothing to render
     Empty,
 }
 
+/// Longest error message shown on an error card; the logs have the rest
+const ERROR_CARD_MESSAGE_CHARS: usize = 48;
+
+impl WidgetContent {
+    /// Error card for `message`
+    pub fn error(message: &str) -> Self {
+        Self::Error {
+            message: message.to_string(),
+        }
+    }
+
+    /// The [`WidgetContent::Error`] card as it is drawn
+    ///
+    /// Only the first line of the message is kept, cut short if it's long.
+    pub fn error_card(message: &str) -> Self {
+        let first_line = message.lines().next().unwrap_or_default().trim();
+        let mut text: String = first_line.chars().take(ERROR_CARD_MESSAGE_CHARS).collect();
+        if first_line.chars().count() > ERROR_CARD_MESSAGE_CHARS {
+            text.push('…');
+        }
+        Self::IconLines {
+            icon: "widget-error".to_string(),
+            lines: vec![
+                (text, FontSize::Medium),
+                (crate::fl!("error-check-logs"), FontSize::Small),
+            ],
+        }
+    }
+
+    /// The slot of an [`WidgetContent::IconRow`] with `count` icons at `x`,
+    /// relative to the widget's width (0.0 to 1.0)
+    pub fn icon_row_slot(x: f32, count: usize) -> Option<usize> {
+        if !(0.0..1.0).contains(&x) {
+            return None;
+        }
+        let slot = (x * count as f32) as usize;
+        (slot < count).then_some(slot)
+    }
+}
+
 /// Font size hint for rendering
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum FontSize {
     /// Large text (primary content like clock)
     Large,
@@ -232,6 +546,17 @@ pub trait Widget: Send {
     /// Get content to render
     fn content(&self) -> WidgetContent;
 
+    /// Content to render, borrowed where the widget keeps it
+    ///
+    /// The event loop asks for content several times a frame, to check
+    /// whether a surface changed and then to draw it. Widgets that build
+    /// their content once per update and keep it can lend it here rather
+    /// than copying every string; the default builds it with
+    /// [`content`](Widget::content).
+    fn content_ref(&self) -> Cow<'_, WidgetContent> {
+        Cow::Owned(self.content())
+    }
+
     /// How often this widget needs updates
     fn update_interval(&self) -> Duration {
         Duration::from_secs(1)
@@ -247,6 +572,96 @@ pub trait Widget: Send {
         None
     }
 
+    /// Take the sound this widget wants played, if any
+    ///
+    /// Polled after each update. Return a cue once per event, then None.
+    fn take_sound_cue(&mut self) -> Option<SoundCue> {
+        None
+    }
+
+    /// Upcoming reminders to show as desktop notifications
+    ///
+    /// Handed to the update scheduler after each update, which fires them
+    /// on time whether or not the widget is being redrawn. Reminders keep
+    /// their id across calls so each one only fires once.
+    fn reminders(&self) -> Vec<Reminder> {
+        Vec::new()
+    }
+
+    /// Whether the screen should be kept awake right now
+    ///
+    /// Checked on every tick, so return true only while it matters, e.g.
+    /// during a timer's final minutes.
+    fn inhibits_idle(&self) -> bool {
+        false
+    }
+
+    /// Receive the latest performance metrics
+    ///
+    /// Called before each update. Only diagnostic widgets need them, so the
+    /// default ignores them.
+    fn observe_metrics(&mut self, _stats: &PerfStats) {}
+
+    /// Take the background fetches completed since the last call
+    ///
+    /// Polled after each update. Widgets that fetch data off the event loop
+    /// report them here so slow or failing sources show up in the metrics.
+    fn take_fetch_reports(&mut self) -> Vec<FetchReport> {
+        Vec::new()
+    }
+
+    /// Where this widget's data comes from, if it fetches any
+    ///
+    /// Asked once when the widget starts. The data service polls the source
+    /// from then on, off the event loop, and each result comes back through
+    /// [`Widget::apply_fetch`]. Widgets that don't fetch anything, or can't
+    /// (e.g. no API key), return None.
+    fn data_source(&self) -> Option<Source> {
+        None
+    }
+
+    /// Receive the result of a fetch from [`Widget::data_source`]
+    fn apply_fetch(&mut self, _result: anyhow::Result<TaskOutput>) {}
+
+    /// When the data from [`Widget::data_source`] last arrived successfully
+    ///
+    /// Widgets showing fetched data return it so the data can be marked as
+    /// stale or offline (see [`Freshness`]) once it stops arriving. None
+    /// before the first successful fetch, and for widgets that keep showing
+    /// something sensible without one.
+    fn last_success(&self) -> Option<Instant> {
+        None
+    }
+
+    /// Characters this widget's text is drawn from
+    ///
+    /// Used to pre-warm the glyph cache at startup so the first frames don't
+    /// stall on rasterizing. Widgets whose text is unpredictable return None.
+    fn glyph_set(&self) -> Option<String> {
+        None
+    }
+
+    // === Lifecycle Methods (Optional) ===
+
+    /// Called once the widget is in place, before its first update
+    ///
+    /// Widgets that hold connections or background threads (D-Bus
+    /// subscriptions, pollers, sockets) open them here rather than in their
+    /// constructor, so a widget that is created and thrown away costs nothing.
+    fn on_start(&mut self) {}
+
+    /// Called before the widget is dropped
+    ///
+    /// Happens on config reloads, plugin reloads and shutdown. Close
+    /// whatever [`Widget::on_start`] opened; nothing outlives the widget.
+    fn on_stop(&mut self) {}
+
+    /// Called when updates pause, e.g. while the session is idle or locked
+    fn on_suspend(&mut self) {}
+
+    /// Called when updates resume after [`Widget::on_suspend`]
+    fn on_resume(&mut self) {}
+
     // === Interaction Methods (Optional) ===
 
     /// Whether this widget accepts pointer interactions
@@ -294,6 +709,30 @@ pub trait Widget: Send {
     /// Called when the pointer leaves this widget's area.
     /// Useful for clearing hover effects.
     fn on_pointer_leave(&mut self) {}
+
+    /// Switch between the compact and expanded (default) form
+    ///
+    /// Called when a `collapsible` widget is collapsed or expanded, and at
+    /// startup with its saved state. Widgets with a shorter presentation
+    /// (weather showing just the temperature) switch [`Widget::content`]
+    /// to it; the default keeps the same content in the smaller surface.
+    fn set_compact(&mut self, _compact: bool) {}
+
+    /// State to keep across restarts, e.g. which quote is showing
+    ///
+    /// Asked for after every update and saved under the instance id in the
+    /// state directory whenever it changes, so keep it small. None for
+    /// widgets with nothing worth keeping.
+    fn save_state(&self) -> Option<toml::Table> {
+        None
+    }
+
+    /// Pick up what [`Widget::save_state`] returned before the restart
+    ///
+    /// Called once, right after the widget starts. The state may be from an
+    /// older version or no longer fit the config, so anything missing or out
+    /// of range should be ignored.
+    fn restore_state(&mut self, _state: &toml::Table) {}
 }
 
 /// Configuration for a widget instance
@@ -333,4 +772,42 @@ mod tests {
         assert!((FontSize::Small.to_pixels(height) - 15.0).abs() < 0.01);
         assert!((FontSize::Custom(32.0).to_pixels(height) - 32.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_error_card() {
+        let WidgetContent::IconLines { icon, lines } =
+            WidgetContent::error_card("HTTP 401: invalid API key\nresponse body")
+        else {
+            panic!("Expected an icon with lines");
+        };
+        assert_eq!(icon, "widget-error");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].0, "HTTP 401: invalid API key");
+
+        let long = "x".repeat(100);
+        let WidgetContent::IconLines { lines, .. } = WidgetContent::error_card(&long) else {
+            panic!("Expected an icon with lines");
+        };
+        assert_eq!(lines[0].0.chars().count(), ERROR_CARD_MESSAGE_CHARS + 1);
+        assert!(lines[0].0.ends_with('…'));
+    }
+
+    #[test]
+    fn test_freshness() {
+        let now = Instant::now();
+        let at = |fetched, failing, stale_after| {
+            Freshness::at(fetched, Duration::from_secs(60), failing, stale_after, now)
+        };
+        let fetched = Some(now - Duration::from_secs(150));
+
+        assert_eq!(at(None, true, 2.0), Freshness::Fresh);
+        assert_eq!(at(fetched, false, 3.0), Freshness::Fresh);
+        assert_eq!(at(fetched, false, 2.0), Freshness::Stale);
+        assert_eq!(at(fetched, true, 2.0), Freshness::Offline);
+        // Turned off
+        assert_eq!(at(fetched, true, 0.0), Freshness::Fresh);
+
+        assert_eq!(Freshness::Fresh.badge_icon(), None);
+        assert_eq!(Freshness::Offline.badge_icon(), Some("badge-offline"));
+    }
 }
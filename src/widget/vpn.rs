@@ -0,0 +1,516 @@
+//! VPN connection widget
+//!
+//! Shows the state of one NetworkManager VPN or WireGuard connection, with
+//! its IP address while it's up, and brings it up or down on click. All of
+//! it goes through NetworkManager on the system bus, so connections set up
+//! in COSMIC Settings (or nmcli) work as they are.
+
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue};
+
+use super::registry::DynWidgetFactory;
+use super::schema::{ConfigField, ConfigSchema, FieldKind, WidgetCapabilities, WidgetMetadata};
+use super::traits::{FontSize, MouseButton, Widget, WidgetAction, WidgetContent, WidgetInfo};
+use crate::update::tasks::{self, TaskOutput};
+use crate::update::Source;
+
+const NM_DESTINATION: &str = "org.freedesktop.NetworkManager";
+const NM_PATH: &str = "/org/freedesktop/NetworkManager";
+const NM_INTERFACE: &str = "org.freedesktop.NetworkManager";
+const SETTINGS_PATH: &str = "/org/freedesktop/NetworkManager/Settings";
+const SETTINGS_INTERFACE: &str = "org.freedesktop.NetworkManager.Settings";
+const CONNECTION_INTERFACE: &str = "org.freedesktop.NetworkManager.Settings.Connection";
+const ACTIVE_INTERFACE: &str = "org.freedesktop.NetworkManager.Connection.Active";
+const IP4_INTERFACE: &str = "org.freedesktop.NetworkManager.IP4Config";
+
+/// Connection types the widget accepts
+const VPN_TYPES: [&str; 2] = ["vpn", "wireguard"];
+
+/// Where a VPN connection is in its lifecycle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VpnState {
+    /// Not active
+    #[default]
+    Disconnected,
+    /// Being brought up
+    Connecting,
+    /// Up
+    Connected,
+    /// Being taken down
+    Disconnecting,
+}
+
+impl VpnState {
+    /// State from NetworkManager's `NMActiveConnectionState`
+    pub fn from_nm(state: u32) -> Self {
+        match state {
+            1 => Self::Connecting,
+            2 => Self::Connected,
+            3 => Self::Disconnecting,
+            _ => Self::Disconnected,
+        }
+    }
+}
+
+/// What NetworkManager reported for the configured connection
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VpnStatus {
+    /// Settings object of the connection, used to activate it
+    pub connection: Option<OwnedObjectPath>,
+    /// Active connection object while it's up, used to deactivate it
+    pub active: Option<OwnedObjectPath>,
+    /// Current state
+    pub state: VpnState,
+    /// First IPv4 address while connected
+    pub address: Option<String>,
+}
+
+/// Widget showing and toggling a VPN connection
+pub struct VpnWidget {
+    /// NetworkManager connection name
+    name: String,
+    /// Shown instead of `name`
+    label: Option<String>,
+    show_ip: bool,
+    update_interval: Duration,
+    status: Option<VpnStatus>,
+    error_message: Option<String>,
+}
+
+impl VpnWidget {
+    /// Create a widget for the connection called `name`
+    pub fn new(name: impl Into<String>, update_interval: Duration) -> Self {
+        Self {
+            name: name.into(),
+            label: None,
+            show_ip: true,
+            update_interval,
+            status: None,
+            error_message: None,
+        }
+    }
+
+    /// Show `label` instead of the connection's name
+    pub fn with_label(mut self, label: Option<String>) -> Self {
+        self.label = label.filter(|l| !l.trim().is_empty());
+        self
+    }
+
+    /// Show the assigned IP address while connected
+    pub fn with_ip(mut self, show_ip: bool) -> Self {
+        self.show_ip = show_ip;
+        self
+    }
+
+    /// Record what NetworkManager reported
+    pub fn set_status(&mut self, status: VpnStatus) {
+        self.error_message = status
+            .connection
+            .is_none()
+            .then(|| format!("No VPN connection named '{}'", self.name));
+        self.status = Some(status);
+    }
+
+    fn status_text(&self, status: &VpnStatus) -> String {
+        let name = self.label.as_deref().unwrap_or(&self.name);
+        match (status.state, &status.address) {
+            (VpnState::Connected, Some(address)) if self.show_ip => {
+                format!("{} · {}", name, address)
+            }
+            (VpnState::Connected, _) => format!("{} connected", name),
+            (VpnState::Connecting, _) => format!("{} connecting…", name),
+            (VpnState::Disconnecting, _) => format!("{} disconnecting…", name),
+            (VpnState::Disconnected, _) => format!("{} off", name),
+        }
+    }
+}
+
+impl Widget for VpnWidget {
+    fn info(&self) -> WidgetInfo {
+        WidgetInfo {
+            id: "vpn",
+            name: "VPN",
+            preferred_height: 48.0,
+            min_height: 32.0,
+            expand: false,
+        }
+    }
+
+    fn update(&mut self) {
+        // Status arrives through apply_fetch from the data service
+    }
+
+    fn content(&self) -> WidgetContent {
+        let Some(status) = &self.status else {
+            return WidgetContent::Empty;
+        };
+        let icon = if status.state == VpnState::Connected {
+            "vpn-connected"
+        } else {
+            "vpn-disconnected"
+        };
+        WidgetContent::IconText {
+            icon: icon.to_string(),
+            text: self.status_text(status),
+            size: FontSize::Medium,
+        }
+    }
+
+    fn update_interval(&self) -> Duration {
+        self.update_interval
+    }
+
+    fn is_ready(&self) -> bool {
+        self.status.is_some() || self.error_message.is_some()
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error_message.as_deref()
+    }
+
+    fn data_source(&self) -> Option<Source> {
+        let name = self.name.clone();
+        Some(Source::blocking(self.update_interval, move || {
+            read_status(&name)
+        }))
+    }
+
+    fn apply_fetch(&mut self, result: Result<TaskOutput>) {
+        match tasks::take_output::<VpnStatus>(result) {
+            Ok(status) => self.set_status(status),
+            Err(e) => {
+                warn!(error = %e, "Failed to read VPN state");
+                self.error_message = Some("NetworkManager not available".to_string());
+            }
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn on_click(&mut self, button: MouseButton, _x: f32, _y: f32) -> Option<WidgetAction> {
+        if button != MouseButton::Left {
+            return None;
+        }
+        let status = self.status.as_mut()?;
+        let request = match (status.state, &status.active, &status.connection) {
+            (VpnState::Connected | VpnState::Connecting, Some(active), _) => {
+                status.state = VpnState::Disconnecting;
+                Toggle::Down(active.clone())
+            }
+            (VpnState::Disconnected, _, Some(connection)) => {
+                status.state = VpnState::Connecting;
+                Toggle::Up(connection.clone())
+            }
+            _ => return None,
+        };
+        info!(connection = %self.name, request = ?request, "Toggling VPN");
+
+        // NetworkManager may ask polkit or a secret agent first
+        let spawned = thread::Builder::new()
+            .name("vpn-toggle".to_string())
+            .spawn(move || {
+                if let Err(e) = request.send() {
+                    warn!(error = %e, "Failed to toggle VPN");
+                }
+            });
+        if let Err(e) = spawned {
+            warn!(error = %e, "Failed to spawn VPN toggle");
+        }
+        Some(WidgetAction::Toggle)
+    }
+}
+
+/// A request to bring a connection up or down
+#[derive(Debug)]
+enum Toggle {
+    /// Activate this settings connection
+    Up(OwnedObjectPath),
+    /// Deactivate this active connection
+    Down(OwnedObjectPath),
+}
+
+impl Toggle {
+    fn send(self) -> Result<()> {
+        let connection =
+            zbus::blocking::Connection::system().context("Failed to connect to the system bus")?;
+        let nm = proxy(&connection, NM_PATH, NM_INTERFACE)?;
+        match self {
+            Toggle::Up(path) => {
+                let none = ObjectPath::from_static_str_unchecked("/");
+                let _active: OwnedObjectPath = nm
+                    .call("ActivateConnection", &(path.as_ref(), &none, &none))
+                    .context("ActivateConnection failed")?;
+            }
+            Toggle::Down(path) => {
+                nm.call::<_, _, ()>("DeactivateConnection", &(path.as_ref(),))
+                    .context("DeactivateConnection failed")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn proxy<'a, P>(
+    connection: &zbus::blocking::Connection,
+    path: P,
+    interface: &'static str,
+) -> Result<zbus::blocking::Proxy<'a>>
+where
+    P: TryInto<ObjectPath<'a>>,
+    P::Error: Into<zbus::Error>,
+{
+    zbus::blocking::Proxy::new(connection, NM_DESTINATION, path, interface)
+        .context("Failed to create NetworkManager proxy")
+}
+
+/// Find the VPN connection called `name` and whether it's active
+fn read_status(name: &str) -> Result<VpnStatus> {
+    let bus =
+        zbus::blocking::Connection::system().context("Failed to connect to the system bus")?;
+
+    let settings = proxy(&bus, SETTINGS_PATH, SETTINGS_INTERFACE)?;
+    let paths: Vec<OwnedObjectPath> = settings
+        .call("ListConnections", &())
+        .context("Failed to list connections")?;
+    let Some(connection) = paths.into_iter().find(|path| {
+        connection_id(&bus, path)
+            .is_some_and(|(id, kind)| id == name && VPN_TYPES.contains(&kind.as_str()))
+    }) else {
+        return Ok(VpnStatus::default());
+    };
+
+    let nm = proxy(&bus, NM_PATH, NM_INTERFACE)?;
+    let active_paths: Vec<OwnedObjectPath> = nm
+        .get_property("ActiveConnections")
+        .context("Failed to read active connections")?;
+    for active_path in active_paths {
+        let active = proxy(&bus, active_path.as_ref(), ACTIVE_INTERFACE)?;
+        let Ok(settings_path) = active.get_property::<OwnedObjectPath>("Connection") else {
+            continue;
+        };
+        if settings_path != connection {
+            continue;
+        }
+
+        let state = VpnState::from_nm(active.get_property("State").unwrap_or(0));
+        let address = if state == VpnState::Connected {
+            active
+                .get_property::<OwnedObjectPath>("Ip4Config")
+                .ok()
+                .and_then(|ip4| first_address(&bus, &ip4))
+        } else {
+            None
+        };
+        return Ok(VpnStatus {
+            connection: Some(connection),
+            active: Some(active_path),
+            state,
+            address,
+        });
+    }
+
+    Ok(VpnStatus {
+        connection: Some(connection),
+        ..Default::default()
+    })
+}
+
+/// `connection.id` and `connection.type` of a settings connection
+fn connection_id(
+    bus: &zbus::blocking::Connection,
+    path: &OwnedObjectPath,
+) -> Option<(String, String)> {
+    let proxy = proxy(bus, path.as_ref(), CONNECTION_INTERFACE).ok()?;
+    let mut settings: HashMap<String, HashMap<String, OwnedValue>> =
+        proxy.call("GetSettings", &()).ok()?;
+    let mut section = settings.remove("connection")?;
+    let id = String::try_from(section.remove("id")?).ok()?;
+    let kind = String::try_from(section.remove("type")?).ok()?;
+    Some((id, kind))
+}
+
+/// The first address of an IPv4 config object
+fn first_address(bus: &zbus::blocking::Connection, path: &OwnedObjectPath) -> Option<String> {
+    if path.as_str() == "/" {
+        return None;
+    }
+    let proxy = proxy(bus, path.as_ref(), IP4_INTERFACE).ok()?;
+    let addresses: Vec<HashMap<String, OwnedValue>> = proxy.get_property("AddressData").ok()?;
+    addresses
+        .into_iter()
+        .find_map(|mut entry| String::try_from(entry.remove("address")?).ok())
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+/// Factory for VpnWidget
+pub struct VpnWidgetFactory;
+
+impl DynWidgetFactory for VpnWidgetFactory {
+    fn widget_type(&self) -> &'static str {
+        "vpn"
+    }
+
+    fn metadata(&self) -> WidgetMetadata {
+        WidgetMetadata::new(
+            "VPN",
+            "A NetworkManager VPN or WireGuard connection; click to connect",
+            "network-vpn-symbolic",
+        )
+    }
+
+    fn create(&self, config: &toml::Table) -> Result<Box<dyn Widget>> {
+        let name = config
+            .get("connection")
+            .and_then(|v| v.as_str())
+            .context("VPN widget needs a 'connection' name")?;
+
+        let label = config
+            .get("label")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        let show_ip = config
+            .get("show_ip")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let update_interval = config
+            .get("update_interval")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(5)
+            .max(1) as u64;
+
+        info!(connection = %name, "Creating VpnWidget");
+
+        Ok(Box::new(
+            VpnWidget::new(name, Duration::from_secs(update_interval))
+                .with_label(label)
+                .with_ip(show_ip),
+        ))
+    }
+
+    fn default_config(&self) -> toml::Table {
+        let mut config = toml::Table::new();
+        config.insert(
+            "connection".to_string(),
+            toml::Value::String("wg0".to_string()),
+        );
+        config.insert("show_ip".to_string(), toml::Value::Boolean(true));
+        config.insert("update_interval".to_string(), toml::Value::Integer(5));
+        config
+    }
+
+    fn validate_config(&self, config: &toml::Table) -> Result<()> {
+        let name = config
+            .get("connection")
+            .context("'connection' is required")?
+            .as_str()
+            .context("'connection' must be a string")?;
+        if name.trim().is_empty() {
+            anyhow::bail!("'connection' can't be empty");
+        }
+
+        if let Some(label) = config.get("label") {
+            label.as_str().context("'label' must be a string")?;
+        }
+
+        if let Some(show_ip) = config.get("show_ip") {
+            show_ip.as_bool().context("'show_ip' must be a boolean")?;
+        }
+
+        if let Some(interval) = config.get("update_interval") {
+            let val = interval
+                .as_integer()
+                .context("'update_interval' must be an integer")?;
+            if val < 1 {
+                anyhow::bail!("'update_interval' must be at least 1 second");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn capabilities(&self) -> WidgetCapabilities {
+        WidgetCapabilities::NONE.with_dbus().with_interactive()
+    }
+
+    fn config_schema(&self) -> ConfigSchema {
+        ConfigSchema::new()
+            .with_field(
+                ConfigField::new("connection", "Connection", FieldKind::Text)
+                    .required()
+                    .with_description("Name of the VPN or WireGuard connection in NetworkManager"),
+            )
+            .with_field(
+                ConfigField::new("label", "Label", FieldKind::Text)
+                    .with_description("Shown instead of the connection name"),
+            )
+            .with_field(ConfigField::new(
+                "show_ip",
+                "Show IP address",
+                FieldKind::Bool,
+            ))
+            .with_field(ConfigField::new(
+                "update_interval",
+                "Update interval (seconds)",
+                FieldKind::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(value: &str) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn test_status_text() {
+        let mut widget =
+            VpnWidget::new("wg-work", Duration::from_secs(5)).with_label(Some("Work".to_string()));
+        let mut status = VpnStatus {
+            connection: Some(path("/org/freedesktop/NetworkManager/Settings/7")),
+            ..Default::default()
+        };
+        assert_eq!(widget.status_text(&status), "Work off");
+
+        status.state = VpnState::Connected;
+        status.address = Some("10.8.0.2".to_string());
+        assert_eq!(widget.status_text(&status), "Work · 10.8.0.2");
+
+        widget = widget.with_ip(false);
+        assert_eq!(widget.status_text(&status), "Work connected");
+        assert_eq!(VpnState::from_nm(1), VpnState::Connecting);
+    }
+
+    #[test]
+    fn test_missing_connection_is_an_error() {
+        let mut widget = VpnWidget::new("nope", Duration::from_secs(5));
+        widget.set_status(VpnStatus::default());
+        assert_eq!(widget.error(), Some("No VPN connection named 'nope'"));
+        // Nothing to toggle
+        assert_eq!(widget.on_click(MouseButton::Left, 0.5, 0.5), None);
+    }
+
+    #[test]
+    fn test_factory_validation() {
+        let factory = VpnWidgetFactory;
+        assert!(factory.validate_config(&factory.default_config()).is_ok());
+        assert!(factory.validate_config(&toml::Table::new()).is_err());
+    }
+}
@@ -444,3 +444,24 @@ fn test_new_widgets() {
     let display = quotes.display_string();
     assert!(!display.is_empty());
 }
+
+// Test rendering a widget to an image without Wayland
+#[test]
+fn test_render_widget_to_png() {
+    use cosmic_desktop_widget::render::Renderer;
+
+    let clock = ClockWidget::new("24h", true, false);
+    let mut renderer = Renderer::new();
+    let theme = Theme::cosmic_dark();
+    let png = renderer
+        .render_widget_to_png(&clock, (300, 120), &theme)
+        .unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+
+    let image = image::load_from_memory(&png).unwrap();
+    assert_eq!((image.width(), image.height()), (300, 120));
+
+    assert!(renderer
+        .render_widget_to_png(&clock, (0, 120), &theme)
+        .is_err());
+}